@@ -0,0 +1,239 @@
+/*!
+Derive macros for the `ReadBytes` and `WriteBytes` traits of `byteorder`.
+
+This crate should not be used directly. Instead, enable the `derive` feature
+of `byteorder`, which re-exports these macros next to the traits they
+implement. See the documentation of `byteorder::ReadBytes` for details.
+*/
+
+#![deny(missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput,
+    Fields, Index, Member,
+};
+
+/// Derives `byteorder::ReadBytes` for a struct.
+///
+/// Fields are read in declaration order, with the byte order given to
+/// `read_from`, unless the field has one of these attributes:
+///
+/// * `#[byteorder(big)]` reads the field in big-endian, always.
+/// * `#[byteorder(little)]` reads the field in little-endian, always.
+/// * `#[byteorder(skip)]` doesn't read the field at all, and sets it to its
+///   `Default` value instead.
+#[proc_macro_derive(ReadBytes, attributes(byteorder))]
+pub fn derive_read_bytes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_read(input).unwrap_or_else(|err| err.to_compile_error()).into()
+}
+
+/// Derives `byteorder::WriteBytes` for a struct.
+///
+/// Fields are written in declaration order, with the byte order given to
+/// `write_to`, unless the field has one of these attributes:
+///
+/// * `#[byteorder(big)]` writes the field in big-endian, always.
+/// * `#[byteorder(little)]` writes the field in little-endian, always.
+/// * `#[byteorder(skip)]` doesn't write the field at all.
+#[proc_macro_derive(WriteBytes, attributes(byteorder))]
+pub fn derive_write_bytes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_write(input).unwrap_or_else(|err| err.to_compile_error()).into()
+}
+
+/// How a single field is read or written.
+enum Mode {
+    /// In the byte order given by the caller.
+    Inherit,
+    /// Always in big-endian.
+    Big,
+    /// Always in little-endian.
+    Little,
+    /// Not at all.
+    Skip,
+}
+
+/// A field of the struct being derived, along with its attributes.
+struct Field<'a> {
+    member: Member,
+    ty: &'a syn::Type,
+    mode: Mode,
+}
+
+impl<'a> Field<'a> {
+    /// Returns the byte order type to read or write this field with, where
+    /// `inherit` is the byte order given by the caller.
+    fn order(&self, inherit: &syn::Ident) -> TokenStream2 {
+        match self.mode {
+            Mode::Big => quote!(::byteorder::BigEndian),
+            Mode::Little => quote!(::byteorder::LittleEndian),
+            Mode::Inherit | Mode::Skip => quote!(#inherit),
+        }
+    }
+}
+
+/// Returns the fields of `input`, or an error if it isn't a struct or a
+/// field has an invalid `byteorder` attribute.
+fn fields<'a>(
+    input: &'a DeriveInput,
+    derive: &str,
+) -> syn::Result<Vec<Field<'a>>> {
+    let data = match input.data {
+        Data::Struct(ref data) => data,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                format!("{} can only be derived for structs", derive),
+            ))
+        }
+    };
+    let mut fields = vec![];
+    for (i, field) in data.fields.iter().enumerate() {
+        let member = match field.ident {
+            Some(ref ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index {
+                index: i as u32,
+                span: field.ty.span(),
+            }),
+        };
+        let mut mode = Mode::Inherit;
+        for attr in &field.attrs {
+            if !attr.path().is_ident("byteorder") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if !matches!(mode, Mode::Inherit) {
+                    return Err(meta.error(
+                        "only one of `big`, `little` or `skip` may be given",
+                    ));
+                }
+                mode = if meta.path.is_ident("big") {
+                    Mode::Big
+                } else if meta.path.is_ident("little") {
+                    Mode::Little
+                } else if meta.path.is_ident("skip") {
+                    Mode::Skip
+                } else {
+                    return Err(
+                        meta.error("expected `big`, `little` or `skip`")
+                    );
+                };
+                Ok(())
+            })?;
+        }
+        fields.push(Field { member, ty: &field.ty, mode });
+    }
+    Ok(fields)
+}
+
+fn expand_read(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = fields(&input, "ReadBytes")?;
+    let order = format_ident!("__E");
+    let reader = format_ident!("__R");
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for field in &fields {
+        let ty = field.ty;
+        where_clause.predicates.push(match field.mode {
+            Mode::Skip => parse_quote!(#ty: ::core::default::Default),
+            _ => parse_quote!(#ty: ::byteorder::ReadBytes),
+        });
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let values = fields.iter().map(|field| {
+        let (member, ty) = (&field.member, field.ty);
+        let field_order = field.order(&order);
+        match field.mode {
+            Mode::Skip => {
+                quote!(#member: ::core::default::Default::default())
+            }
+            _ => quote! {
+                #member: <#ty as ::byteorder::ReadBytes>::read_from::<
+                    #reader,
+                    #field_order,
+                >(rdr)?
+            },
+        }
+    });
+    let value = match input.data {
+        Data::Struct(ref data) if matches!(data.fields, Fields::Unit) => {
+            quote!(Self)
+        }
+        _ => quote!(Self { #(#values,)* }),
+    };
+
+    let name = &input.ident;
+    Ok(quote! {
+        impl #impl_generics ::byteorder::ReadBytes for #name #ty_generics
+            #where_clause
+        {
+            #[allow(unused_variables)]
+            fn read_from<
+                #reader: ::std::io::Read + ?Sized,
+                #order: ::byteorder::ByteOrder,
+            >(
+                rdr: &mut #reader,
+            ) -> ::std::io::Result<Self> {
+                ::std::result::Result::Ok(#value)
+            }
+        }
+    })
+}
+
+fn expand_write(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = fields(&input, "WriteBytes")?;
+    let order = format_ident!("__E");
+    let writer = format_ident!("__W");
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for field in &fields {
+        if let Mode::Skip = field.mode {
+            continue;
+        }
+        let ty = field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::byteorder::WriteBytes));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let writes = fields.iter().filter_map(|field| {
+        if let Mode::Skip = field.mode {
+            return None;
+        }
+        let (member, ty) = (&field.member, field.ty);
+        let field_order = field.order(&order);
+        Some(quote! {
+            <#ty as ::byteorder::WriteBytes>::write_to::<
+                #writer,
+                #field_order,
+            >(&self.#member, wtr)?;
+        })
+    });
+
+    let name = &input.ident;
+    Ok(quote! {
+        impl #impl_generics ::byteorder::WriteBytes for #name #ty_generics
+            #where_clause
+        {
+            #[allow(unused_variables)]
+            fn write_to<
+                #writer: ::std::io::Write + ?Sized,
+                #order: ::byteorder::ByteOrder,
+            >(
+                &self,
+                wtr: &mut #writer,
+            ) -> ::std::io::Result<()> {
+                #(#writes)*
+                ::std::result::Result::Ok(())
+            }
+        }
+    })
+}
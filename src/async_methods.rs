@@ -0,0 +1,1044 @@
+/*!
+The methods shared by the asynchronous extension traits.
+
+Each async runtime has its own `AsyncRead` and `AsyncWrite` traits, and so its
+own module with its own extension traits and futures. The methods of those
+traits are the same everywhere though, so they are generated by the macros in
+this module, along with the futures that those methods return.
+
+This module is compiled whenever `std` is, so that its test helpers are
+available to every async module, but its macros are only used when one of the
+async features is enabled.
+*/
+
+#![cfg_attr(
+    not(any(feature = "futures-io", feature = "tokio")),
+    allow(dead_code, unused_imports, unused_macros)
+)]
+
+use std::io;
+
+use crate::Error;
+
+/// Decodes a variable length integer from the start of a buffer, and
+/// returns it along with its length, like
+/// [`decode_uleb128`](crate::varint::decode_uleb128).
+pub(crate) type DecodeVarint<N> = fn(&[u8]) -> Result<(N, usize), Error>;
+
+/// Expands to the futures returned by the methods of the `AsyncReadBytesExt`
+/// and `AsyncWriteBytesExt` traits of a module.
+///
+/// The arguments are the runtime's reader and writer traits. The module must
+/// also define `poll_read_some` and `poll_write_some` functions, which make a
+/// single call to `poll_read` or `poll_write` with a byte slice, and return
+/// the number of bytes read or written.
+macro_rules! future_types {
+    ($read:path, $write:path) => {
+        /// A future that reads a number, returned by the methods of
+        /// [`AsyncReadBytesExt`].
+        #[must_use = "futures do nothing unless you `.await` or poll them"]
+        pub struct ReadNum<'a, R: ?Sized, N> {
+            rdr: &'a mut R,
+            buf: [u8; 16],
+            filled: usize,
+            len: usize,
+            decode: fn(&[u8]) -> N,
+        }
+
+        impl<'a, R: ?Sized, N> ReadNum<'a, R, N> {
+            fn new(
+                rdr: &'a mut R,
+                len: usize,
+                decode: fn(&[u8]) -> N,
+            ) -> Self {
+                ReadNum { rdr, buf: [0; 16], filled: 0, len, decode }
+            }
+        }
+
+        impl<'a, R: ?Sized, N> ::core::fmt::Debug for ReadNum<'a, R, N> {
+            fn fmt(
+                &self,
+                f: &mut ::core::fmt::Formatter<'_>,
+            ) -> ::core::fmt::Result {
+                f.debug_struct("ReadNum")
+                    .field("filled", &self.filled)
+                    .field("len", &self.len)
+                    .finish()
+            }
+        }
+
+        impl<'a, R: $read + Unpin + ?Sized, N> ::core::future::Future
+            for ReadNum<'a, R, N>
+        {
+            type Output = ::std::io::Result<N>;
+
+            fn poll(
+                self: ::core::pin::Pin<&mut Self>,
+                cx: &mut ::core::task::Context<'_>,
+            ) -> ::core::task::Poll<Self::Output> {
+                use ::core::{pin::Pin, task::Poll};
+
+                let me = self.get_mut();
+                while me.filled < me.len {
+                    let buf = &mut me.buf[me.filled..me.len];
+                    match poll_read_some(Pin::new(&mut *me.rdr), cx, buf) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                        Poll::Ready(Ok(0)) => {
+                            return Poll::Ready(Err(
+                                $crate::async_methods::eof(),
+                            ))
+                        }
+                        Poll::Ready(Ok(n)) => me.filled += n,
+                    }
+                }
+                Poll::Ready(Ok((me.decode)(&me.buf[..me.len])))
+            }
+        }
+
+        /// A future that reads a variable length integer, returned by the
+        /// methods of [`AsyncReadBytesExt`].
+        #[must_use = "futures do nothing unless you `.await` or poll them"]
+        pub struct ReadVarint<'a, R: ?Sized, N> {
+            rdr: &'a mut R,
+            buf: [u8; $crate::varint::MAX_ULEB128_U128_LEN],
+            filled: usize,
+            max: usize,
+            decode: $crate::async_methods::DecodeVarint<N>,
+        }
+
+        impl<'a, R: ?Sized, N> ReadVarint<'a, R, N> {
+            fn new(
+                rdr: &'a mut R,
+                max: usize,
+                decode: $crate::async_methods::DecodeVarint<N>,
+            ) -> Self {
+                let buf = [0; $crate::varint::MAX_ULEB128_U128_LEN];
+                ReadVarint { rdr, buf, filled: 0, max, decode }
+            }
+
+            /// Returns true when the bytes read so far end the varint,
+            /// either because the last one has no continuation bit or
+            /// because there can't be any more.
+            fn is_done(&self) -> bool {
+                self.filled == self.max
+                    || (self.filled > 0
+                        && self.buf[self.filled - 1] & 0x80 == 0)
+            }
+        }
+
+        impl<'a, R: ?Sized, N> ::core::fmt::Debug for ReadVarint<'a, R, N> {
+            fn fmt(
+                &self,
+                f: &mut ::core::fmt::Formatter<'_>,
+            ) -> ::core::fmt::Result {
+                f.debug_struct("ReadVarint")
+                    .field("buf", &&self.buf[..self.filled])
+                    .field("max", &self.max)
+                    .finish()
+            }
+        }
+
+        impl<'a, R: $read + Unpin + ?Sized, N> ::core::future::Future
+            for ReadVarint<'a, R, N>
+        {
+            type Output = ::std::io::Result<N>;
+
+            fn poll(
+                self: ::core::pin::Pin<&mut Self>,
+                cx: &mut ::core::task::Context<'_>,
+            ) -> ::core::task::Poll<Self::Output> {
+                use ::core::{pin::Pin, task::Poll};
+
+                let me = self.get_mut();
+                while !me.is_done() {
+                    let buf = &mut me.buf[me.filled..me.filled + 1];
+                    match poll_read_some(Pin::new(&mut *me.rdr), cx, buf) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                        Poll::Ready(Ok(0)) => {
+                            return Poll::Ready(Err(
+                                $crate::async_methods::eof(),
+                            ))
+                        }
+                        Poll::Ready(Ok(_)) => me.filled += 1,
+                    }
+                }
+                let res = (me.decode)(&me.buf[..me.filled]);
+                Poll::Ready(
+                    res.map(|(n, _)| n).map_err(::std::io::Error::from),
+                )
+            }
+        }
+
+        /// A future that fills a slice with numbers, returned by the methods
+        /// of [`AsyncReadBytesExt`].
+        #[must_use = "futures do nothing unless you `.await` or poll them"]
+        pub struct ReadInto<'a, R: ?Sized, N> {
+            rdr: &'a mut R,
+            dst: &'a mut [N],
+            filled: usize,
+            convert: fn(&mut [N]),
+        }
+
+        impl<'a, R: ?Sized, N: Copy> ReadInto<'a, R, N> {
+            /// Creates a future that reads the bytes of `dst` in place, and
+            /// then calls `convert` to fix their byte order. `N` must be a
+            /// numeric type.
+            fn new(
+                rdr: &'a mut R,
+                dst: &'a mut [N],
+                convert: fn(&mut [N]),
+            ) -> Self {
+                ReadInto { rdr, dst, filled: 0, convert }
+            }
+        }
+
+        impl<'a, R: ?Sized, N> ::core::fmt::Debug for ReadInto<'a, R, N> {
+            fn fmt(
+                &self,
+                f: &mut ::core::fmt::Formatter<'_>,
+            ) -> ::core::fmt::Result {
+                f.debug_struct("ReadInto")
+                    .field("filled", &self.filled)
+                    .field("len", &::core::mem::size_of_val(self.dst))
+                    .finish()
+            }
+        }
+
+        impl<'a, R: $read + Unpin + ?Sized, N: Copy> ::core::future::Future
+            for ReadInto<'a, R, N>
+        {
+            type Output = ::std::io::Result<()>;
+
+            fn poll(
+                self: ::core::pin::Pin<&mut Self>,
+                cx: &mut ::core::task::Context<'_>,
+            ) -> ::core::task::Poll<Self::Output> {
+                use ::core::{pin::Pin, task::Poll};
+
+                let me = self.get_mut();
+                // SAFETY: the constructor is only called with numeric types,
+                // for which every bit pattern is valid.
+                let bytes = unsafe { $crate::io::slice_to_u8_mut(me.dst) };
+                while me.filled < bytes.len() {
+                    let buf = &mut bytes[me.filled..];
+                    match poll_read_some(Pin::new(&mut *me.rdr), cx, buf) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                        Poll::Ready(Ok(0)) => {
+                            return Poll::Ready(Err(
+                                $crate::async_methods::eof(),
+                            ))
+                        }
+                        Poll::Ready(Ok(n)) => me.filled += n,
+                    }
+                }
+                (me.convert)(me.dst);
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        /// A future that writes a number, returned by the methods of
+        /// [`AsyncWriteBytesExt`].
+        #[must_use = "futures do nothing unless you `.await` or poll them"]
+        pub struct WriteNum<'a, W: ?Sized> {
+            wtr: &'a mut W,
+            buf: [u8; 16],
+            written: usize,
+            len: usize,
+        }
+
+        impl<'a, W: ?Sized> WriteNum<'a, W> {
+            fn new(
+                wtr: &'a mut W,
+                len: usize,
+                encode: impl FnOnce(&mut [u8]),
+            ) -> Self {
+                let mut buf = [0; 16];
+                encode(&mut buf[..len]);
+                WriteNum { wtr, buf, written: 0, len }
+            }
+        }
+
+        impl<'a, W: ?Sized> ::core::fmt::Debug for WriteNum<'a, W> {
+            fn fmt(
+                &self,
+                f: &mut ::core::fmt::Formatter<'_>,
+            ) -> ::core::fmt::Result {
+                f.debug_struct("WriteNum")
+                    .field("buf", &&self.buf[..self.len])
+                    .field("written", &self.written)
+                    .finish()
+            }
+        }
+
+        impl<'a, W: $write + Unpin + ?Sized> ::core::future::Future
+            for WriteNum<'a, W>
+        {
+            type Output = ::std::io::Result<()>;
+
+            fn poll(
+                self: ::core::pin::Pin<&mut Self>,
+                cx: &mut ::core::task::Context<'_>,
+            ) -> ::core::task::Poll<Self::Output> {
+                use ::core::{pin::Pin, task::Poll};
+
+                let me = self.get_mut();
+                while me.written < me.len {
+                    let buf = &me.buf[me.written..me.len];
+                    match poll_write_some(Pin::new(&mut *me.wtr), cx, buf) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                        Poll::Ready(Ok(0)) => {
+                            return Poll::Ready(Err(
+                                $crate::async_methods::write_zero(),
+                            ))
+                        }
+                        Poll::Ready(Ok(n)) => me.written += n,
+                    }
+                }
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        /// A future that writes a slice of numbers, returned by the methods
+        /// of [`AsyncWriteBytesExt`].
+        #[must_use = "futures do nothing unless you `.await` or poll them"]
+        pub struct WriteFrom<'a, W: ?Sized, N> {
+            wtr: &'a mut W,
+            src: &'a [N],
+            encode: fn(&[N], &mut [u8]),
+            buf: Vec<u8>,
+            written: usize,
+        }
+
+        impl<'a, W: ?Sized, N> WriteFrom<'a, W, N> {
+            fn new(
+                wtr: &'a mut W,
+                src: &'a [N],
+                encode: fn(&[N], &mut [u8]),
+            ) -> Self {
+                let len = ::core::mem::size_of_val(src)
+                    .min($crate::io::STAGING_SIZE);
+                let buf = Vec::with_capacity(len);
+                WriteFrom { wtr, src, encode, buf, written: 0 }
+            }
+        }
+
+        impl<'a, W: ?Sized, N> ::core::fmt::Debug for WriteFrom<'a, W, N> {
+            fn fmt(
+                &self,
+                f: &mut ::core::fmt::Formatter<'_>,
+            ) -> ::core::fmt::Result {
+                f.debug_struct("WriteFrom")
+                    .field("remaining", &self.src.len())
+                    .field("staged", &(self.buf.len() - self.written))
+                    .finish()
+            }
+        }
+
+        impl<'a, W: $write + Unpin + ?Sized, N> ::core::future::Future
+            for WriteFrom<'a, W, N>
+        {
+            type Output = ::std::io::Result<()>;
+
+            fn poll(
+                self: ::core::pin::Pin<&mut Self>,
+                cx: &mut ::core::task::Context<'_>,
+            ) -> ::core::task::Poll<Self::Output> {
+                use ::core::{pin::Pin, task::Poll};
+
+                let me = self.get_mut();
+                loop {
+                    if me.written == me.buf.len() {
+                        if me.src.is_empty() {
+                            return Poll::Ready(Ok(()));
+                        }
+                        let size = ::core::mem::size_of::<N>();
+                        let count = ($crate::io::STAGING_SIZE / size)
+                            .min(me.src.len());
+                        let (chunk, rest) = me.src.split_at(count);
+                        me.buf.clear();
+                        me.buf.resize(count * size, 0);
+                        (me.encode)(chunk, &mut me.buf);
+                        me.src = rest;
+                        me.written = 0;
+                    }
+                    let buf = &me.buf[me.written..];
+                    match poll_write_some(Pin::new(&mut *me.wtr), cx, buf) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                        Poll::Ready(Ok(0)) => {
+                            return Poll::Ready(Err(
+                                $crate::async_methods::write_zero(),
+                            ))
+                        }
+                        Poll::Ready(Ok(n)) => me.written += n,
+                    }
+                }
+            }
+        }
+
+        /// A read of a single value that can be cancelled and resumed
+        /// without losing any bytes.
+        ///
+        /// The futures returned by [`AsyncReadBytesExt`] hold the bytes they
+        /// have read so far, so dropping one before it completes, for
+        /// example in a losing branch of `select!`, loses those bytes and
+        /// leaves the reader in the middle of a value. A `ResumableRead`
+        /// holds them instead. The future returned by
+        /// [`read`](ResumableRead::read) only borrows it, and can be dropped
+        /// at any point: the next call to `read` continues where the last
+        /// one stopped. Once a value is complete, the `ResumableRead` starts
+        /// over with the next one.
+        ///
+        /// The value can be of any type that implements
+        /// [`FromWire`](crate::wire::FromWire), which includes all numbers
+        /// and arrays of them. Its bytes are kept in a buffer that is
+        /// allocated once, when the `ResumableRead` is created.
+        pub struct ResumableRead<N> {
+            buf: Vec<u8>,
+            filled: usize,
+            decode: fn(&[u8]) -> Result<N, $crate::Error>,
+        }
+
+        impl<N: $crate::wire::FromWire> ResumableRead<N> {
+            /// Creates a resumable read of values of type `N`, in the byte
+            /// order `E`.
+            pub fn new<E: $crate::ByteOrder>() -> ResumableRead<N> {
+                ResumableRead {
+                    buf: vec![0; N::WIRE_SIZE],
+                    filled: 0,
+                    decode: N::from_wire::<E>,
+                }
+            }
+        }
+
+        impl<N> ResumableRead<N> {
+            /// Returns a future that reads the rest of the current value
+            /// from `rdr`.
+            ///
+            /// # Errors
+            ///
+            /// The future fails with the same errors as `read_exact`, and
+            /// with an error of kind `InvalidData` when the bytes aren't a
+            /// valid value of type `N`. When reading fails, the bytes read
+            /// so far are kept, so the read can be retried. When decoding
+            /// fails, they are discarded.
+            pub fn read<'a, R: $read + Unpin + ?Sized>(
+                &'a mut self,
+                rdr: &'a mut R,
+            ) -> ReadResumable<'a, R, N> {
+                ReadResumable { state: self, rdr }
+            }
+
+            /// Attempts to read the rest of the current value from `rdr`.
+            ///
+            /// This is what the future returned by
+            /// [`read`](ResumableRead::read) calls, for use in
+            /// hand-written futures that can't borrow the `ResumableRead`.
+            pub fn poll_read<R: $read + ?Sized>(
+                &mut self,
+                cx: &mut ::core::task::Context<'_>,
+                mut rdr: ::core::pin::Pin<&mut R>,
+            ) -> ::core::task::Poll<::std::io::Result<N>> {
+                use ::core::task::Poll;
+
+                while self.filled < self.buf.len() {
+                    let buf = &mut self.buf[self.filled..];
+                    match poll_read_some(rdr.as_mut(), cx, buf) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                        Poll::Ready(Ok(0)) => {
+                            return Poll::Ready(Err(
+                                $crate::async_methods::eof(),
+                            ))
+                        }
+                        Poll::Ready(Ok(n)) => self.filled += n,
+                    }
+                }
+                self.filled = 0;
+                Poll::Ready((self.decode)(&self.buf).map_err(From::from))
+            }
+
+            /// Returns the number of bytes of the current value that were
+            /// read so far.
+            pub fn filled(&self) -> usize {
+                self.filled
+            }
+
+            /// Discards the bytes of the current value that were read so
+            /// far, so that the next read starts a new value.
+            pub fn reset(&mut self) {
+                self.filled = 0;
+            }
+        }
+
+        impl<N> ::core::fmt::Debug for ResumableRead<N> {
+            fn fmt(
+                &self,
+                f: &mut ::core::fmt::Formatter<'_>,
+            ) -> ::core::fmt::Result {
+                f.debug_struct("ResumableRead")
+                    .field("buf", &&self.buf[..self.filled])
+                    .field("len", &self.buf.len())
+                    .finish()
+            }
+        }
+
+        /// A future that continues a [`ResumableRead`], returned by
+        /// [`ResumableRead::read`].
+        #[must_use = "futures do nothing unless you `.await` or poll them"]
+        pub struct ReadResumable<'a, R: ?Sized, N> {
+            state: &'a mut ResumableRead<N>,
+            rdr: &'a mut R,
+        }
+
+        impl<'a, R: ?Sized, N> ::core::fmt::Debug for ReadResumable<'a, R, N> {
+            fn fmt(
+                &self,
+                f: &mut ::core::fmt::Formatter<'_>,
+            ) -> ::core::fmt::Result {
+                f.debug_struct("ReadResumable")
+                    .field("state", &self.state)
+                    .finish()
+            }
+        }
+
+        impl<'a, R: $read + Unpin + ?Sized, N> ::core::future::Future
+            for ReadResumable<'a, R, N>
+        {
+            type Output = ::std::io::Result<N>;
+
+            fn poll(
+                self: ::core::pin::Pin<&mut Self>,
+                cx: &mut ::core::task::Context<'_>,
+            ) -> ::core::task::Poll<Self::Output> {
+                let me = self.get_mut();
+                me.state.poll_read(cx, ::core::pin::Pin::new(&mut *me.rdr))
+            }
+        }
+    };
+}
+
+/// Returns the error for a reader that ends before a number is complete.
+pub(crate) fn eof() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "failed to fill whole buffer")
+}
+
+/// Returns the error for a writer that stops accepting bytes.
+pub(crate) fn write_zero() -> io::Error {
+    io::Error::new(io::ErrorKind::WriteZero, "failed to write whole buffer")
+}
+
+macro_rules! read_num_methods {
+    ($($name:ident($ty:ty, $size:expr, $read:ident, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Reads ", $desc, " from the underlying reader.\n\n",
+            "# Errors\n\n",
+            "The future fails with the same errors as `read_exact`.",
+        )]
+        #[inline]
+        fn $name<T: ByteOrder>(&mut self) -> ReadNum<'_, Self, $ty> {
+            ReadNum::new(self, $size, T::$read)
+        }
+    )*};
+}
+
+macro_rules! write_num_methods {
+    ($($name:ident($ty:ty, $size:expr, $write:ident, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Writes ", $desc, " to the underlying writer.\n\n",
+            "# Errors\n\n",
+            "The future fails with the same errors as `write_all`.",
+        )]
+        #[inline]
+        fn $name<T: ByteOrder>(&mut self, n: $ty) -> WriteNum<'_, Self> {
+            WriteNum::new(self, $size, |buf| T::$write(buf, n))
+        }
+    )*};
+}
+
+macro_rules! read_into_methods {
+    ($($name:ident($ty:ty, $convert:ident, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Reads a sequence of ", $desc, " from the underlying reader.\n\n",
+            "The given buffer is either filled completely or the future ",
+            "fails. If it fails, the contents of `dst` are unspecified.\n\n",
+            "# Errors\n\n",
+            "The future fails with the same errors as `read_exact`.",
+        )]
+        #[inline]
+        fn $name<'a, T: ByteOrder>(
+            &'a mut self,
+            dst: &'a mut [$ty],
+        ) -> ReadInto<'a, Self, $ty> {
+            ReadInto::new(self, dst, T::$convert)
+        }
+    )*};
+}
+
+macro_rules! write_from_methods {
+    ($($name:ident($ty:ty, $encode:ident, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Writes a sequence of ", $desc, " to the underlying writer.\n\n",
+            "The numbers are encoded in chunks into a buffer that is ",
+            "allocated once per call, and each chunk is passed to ",
+            "`poll_write` until it is written. This is much cheaper than ",
+            "writing each number on its own. If the future fails, an ",
+            "unspecified prefix of `src` may have been written.\n\n",
+            "# Errors\n\n",
+            "The future fails with the same errors as `write_all`.",
+        )]
+        #[inline]
+        fn $name<'a, T: ByteOrder>(
+            &'a mut self,
+            src: &'a [$ty],
+        ) -> WriteFrom<'a, Self, $ty> {
+            WriteFrom::new(self, src, T::$encode)
+        }
+    )*};
+}
+
+/// Expands to the methods of an `AsyncReadBytesExt` trait.
+macro_rules! read_methods {
+    () => {
+        /// Reads an unsigned 8 bit integer from the underlying reader.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `read_exact`.
+        #[inline]
+        fn read_u8(&mut self) -> ReadNum<'_, Self, u8> {
+            ReadNum::new(self, 1, |buf| buf[0])
+        }
+
+        /// Reads a signed 8 bit integer from the underlying reader.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `read_exact`.
+        #[inline]
+        fn read_i8(&mut self) -> ReadNum<'_, Self, i8> {
+            ReadNum::new(self, 1, |buf| buf[0] as i8)
+        }
+
+        $crate::async_methods::read_num_methods! {
+            read_u16(u16, 2, read_u16, "an unsigned 16 bit integer");
+            read_i16(i16, 2, read_i16, "a signed 16 bit integer");
+            read_u24(u32, 3, read_u24, "an unsigned 24 bit integer");
+            read_i24(i32, 3, read_i24, "a signed 24 bit integer");
+            read_u32(u32, 4, read_u32, "an unsigned 32 bit integer");
+            read_i32(i32, 4, read_i32, "a signed 32 bit integer");
+            read_u48(u64, 6, read_u48, "an unsigned 48 bit integer");
+            read_i48(i64, 6, read_i48, "a signed 48 bit integer");
+            read_u64(u64, 8, read_u64, "an unsigned 64 bit integer");
+            read_i64(i64, 8, read_i64, "a signed 64 bit integer");
+            read_u128(u128, 16, read_u128, "an unsigned 128 bit integer");
+            read_i128(i128, 16, read_i128, "a signed 128 bit integer");
+            read_f32(f32, 4, read_f32, "an IEEE754 single-precision float");
+            read_f64(f64, 8, read_f64, "an IEEE754 double-precision float");
+        }
+
+        /// Reads an unsigned n-bytes integer from the underlying reader.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `read_exact`.
+        ///
+        /// # Panics
+        ///
+        /// Panics when `nbytes < 1` or `nbytes > 8`.
+        #[inline]
+        fn read_uint<T: ByteOrder>(
+            &mut self,
+            nbytes: usize,
+        ) -> ReadNum<'_, Self, u64> {
+            assert!((1..=8).contains(&nbytes), "nbytes must be in 1..=8");
+            ReadNum::new(self, nbytes, |buf| T::read_uint(buf, buf.len()))
+        }
+
+        /// Reads a signed n-bytes integer from the underlying reader.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `read_exact`.
+        ///
+        /// # Panics
+        ///
+        /// Panics when `nbytes < 1` or `nbytes > 8`.
+        #[inline]
+        fn read_int<T: ByteOrder>(
+            &mut self,
+            nbytes: usize,
+        ) -> ReadNum<'_, Self, i64> {
+            assert!((1..=8).contains(&nbytes), "nbytes must be in 1..=8");
+            ReadNum::new(self, nbytes, |buf| T::read_int(buf, buf.len()))
+        }
+
+        /// Reads an unsigned n-bytes integer from the underlying reader.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `read_exact`.
+        ///
+        /// # Panics
+        ///
+        /// Panics when `nbytes < 1` or `nbytes > 16`.
+        #[inline]
+        fn read_uint128<T: ByteOrder>(
+            &mut self,
+            nbytes: usize,
+        ) -> ReadNum<'_, Self, u128> {
+            assert!((1..=16).contains(&nbytes), "nbytes must be in 1..=16");
+            ReadNum::new(self, nbytes, |buf| T::read_uint128(buf, buf.len()))
+        }
+
+        /// Reads a signed n-bytes integer from the underlying reader.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `read_exact`.
+        ///
+        /// # Panics
+        ///
+        /// Panics when `nbytes < 1` or `nbytes > 16`.
+        #[inline]
+        fn read_int128<T: ByteOrder>(
+            &mut self,
+            nbytes: usize,
+        ) -> ReadNum<'_, Self, i128> {
+            assert!((1..=16).contains(&nbytes), "nbytes must be in 1..=16");
+            ReadNum::new(self, nbytes, |buf| T::read_int128(buf, buf.len()))
+        }
+
+        /// Reads a sequence of signed 8 bit integers from the underlying
+        /// reader.
+        ///
+        /// The given buffer is either filled completely or the future fails.
+        /// If it fails, the contents of `dst` are unspecified.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `read_exact`.
+        #[inline]
+        fn read_i8_into<'a>(
+            &'a mut self,
+            dst: &'a mut [i8],
+        ) -> ReadInto<'a, Self, i8> {
+            ReadInto::new(self, dst, |_| {})
+        }
+
+        $crate::async_methods::read_into_methods! {
+            read_u16_into(u16, from_slice_u16, "unsigned 16 bit integers");
+            read_i16_into(i16, from_slice_i16, "signed 16 bit integers");
+            read_u32_into(u32, from_slice_u32, "unsigned 32 bit integers");
+            read_i32_into(i32, from_slice_i32, "signed 32 bit integers");
+            read_u64_into(u64, from_slice_u64, "unsigned 64 bit integers");
+            read_i64_into(i64, from_slice_i64, "signed 64 bit integers");
+            read_u128_into(u128, from_slice_u128, "unsigned 128 bit integers");
+            read_i128_into(i128, from_slice_i128, "signed 128 bit integers");
+            read_f32_into(f32, from_slice_f32, "IEEE754 single-precision floats");
+            read_f64_into(f64, from_slice_f64, "IEEE754 double-precision floats");
+        }
+
+        /// Reads an unsigned LEB128 variable length integer from the
+        /// underlying reader.
+        ///
+        /// See the [`varint`](crate::varint) module for the format. Bytes
+        /// are read one at a time, so that nothing after the integer is
+        /// consumed, which makes buffering the reader worthwhile.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `read_exact`. If the
+        /// encoding is longer than
+        /// [`MAX_ULEB128_LEN`](crate::varint::MAX_ULEB128_LEN) bytes, or its
+        /// value doesn't fit in a `u64`, it fails with an error of kind
+        /// `InvalidData`, and no more than that many bytes are read.
+        #[inline]
+        fn read_uleb128(&mut self) -> ReadVarint<'_, Self, u64> {
+            ReadVarint::new(
+                self,
+                $crate::varint::MAX_ULEB128_LEN,
+                $crate::varint::decode_uleb128,
+            )
+        }
+
+        /// Reads a protocol buffers varint from the underlying reader.
+        ///
+        /// This is the same as
+        /// [`read_uleb128`](AsyncReadBytesExt::read_uleb128).
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as
+        /// [`read_uleb128`](AsyncReadBytesExt::read_uleb128).
+        #[inline]
+        fn read_varint_u64(&mut self) -> ReadVarint<'_, Self, u64> {
+            self.read_uleb128()
+        }
+
+        /// Reads a signed integer, ZigZag encoded as an unsigned LEB128
+        /// variable length integer, from the underlying reader.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as
+        /// [`read_uleb128`](AsyncReadBytesExt::read_uleb128).
+        #[inline]
+        fn read_varint_zigzag(&mut self) -> ReadVarint<'_, Self, i64> {
+            ReadVarint::new(self, $crate::varint::MAX_ULEB128_LEN, |buf| {
+                let (n, len) = $crate::varint::decode_uleb128(buf)?;
+                Ok(($crate::varint::zigzag_decode_u64(n), len))
+            })
+        }
+
+        /// Reads an unsigned LEB128 variable length integer that fits in a
+        /// `u128` from the underlying reader.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `read_exact`. If the
+        /// encoding is longer than
+        /// [`MAX_ULEB128_U128_LEN`](crate::varint::MAX_ULEB128_U128_LEN)
+        /// bytes, or its value doesn't fit in a `u128`, it fails with an
+        /// error of kind `InvalidData`, and no more than that many bytes are
+        /// read.
+        #[inline]
+        fn read_uleb128_u128(&mut self) -> ReadVarint<'_, Self, u128> {
+            ReadVarint::new(
+                self,
+                $crate::varint::MAX_ULEB128_U128_LEN,
+                $crate::varint::decode_uleb128_u128,
+            )
+        }
+
+        /// Reads a signed 128 bit integer, ZigZag encoded as an unsigned
+        /// LEB128 variable length integer, from the underlying reader.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as
+        /// [`read_uleb128_u128`](AsyncReadBytesExt::read_uleb128_u128).
+        #[inline]
+        fn read_varint_zigzag_i128(&mut self) -> ReadVarint<'_, Self, i128> {
+            ReadVarint::new(
+                self,
+                $crate::varint::MAX_ULEB128_U128_LEN,
+                |buf| {
+                    let (n, len) = $crate::varint::decode_uleb128_u128(buf)?;
+                    Ok(($crate::varint::zigzag_decode_u128(n), len))
+                },
+            )
+        }
+    };
+}
+
+/// Expands to the methods of an `AsyncWriteBytesExt` trait.
+macro_rules! write_methods {
+    () => {
+        /// Writes an unsigned 8 bit integer to the underlying writer.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `write_all`.
+        #[inline]
+        fn write_u8(&mut self, n: u8) -> WriteNum<'_, Self> {
+            WriteNum::new(self, 1, |buf| buf[0] = n)
+        }
+
+        /// Writes a signed 8 bit integer to the underlying writer.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `write_all`.
+        #[inline]
+        fn write_i8(&mut self, n: i8) -> WriteNum<'_, Self> {
+            WriteNum::new(self, 1, |buf| buf[0] = n as u8)
+        }
+
+        $crate::async_methods::write_num_methods! {
+            write_u16(u16, 2, write_u16, "an unsigned 16 bit integer");
+            write_i16(i16, 2, write_i16, "a signed 16 bit integer");
+            write_u24(u32, 3, write_u24, "an unsigned 24 bit integer");
+            write_i24(i32, 3, write_i24, "a signed 24 bit integer");
+            write_u32(u32, 4, write_u32, "an unsigned 32 bit integer");
+            write_i32(i32, 4, write_i32, "a signed 32 bit integer");
+            write_u48(u64, 6, write_u48, "an unsigned 48 bit integer");
+            write_i48(i64, 6, write_i48, "a signed 48 bit integer");
+            write_u64(u64, 8, write_u64, "an unsigned 64 bit integer");
+            write_i64(i64, 8, write_i64, "a signed 64 bit integer");
+            write_u128(u128, 16, write_u128, "an unsigned 128 bit integer");
+            write_i128(i128, 16, write_i128, "a signed 128 bit integer");
+            write_f32(f32, 4, write_f32, "an IEEE754 single-precision float");
+            write_f64(f64, 8, write_f64, "an IEEE754 double-precision float");
+        }
+
+        /// Writes an unsigned n-bytes integer to the underlying writer.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `write_all`.
+        ///
+        /// # Panics
+        ///
+        /// If the given integer is not representable in the given number of
+        /// bytes, this method panics. If `nbytes > 8`, this method panics.
+        #[inline]
+        fn write_uint<T: ByteOrder>(
+            &mut self,
+            n: u64,
+            nbytes: usize,
+        ) -> WriteNum<'_, Self> {
+            assert!(nbytes <= 8, "nbytes must be in 0..=8");
+            WriteNum::new(self, nbytes, |buf| T::write_uint(buf, n, nbytes))
+        }
+
+        /// Writes a signed n-bytes integer to the underlying writer.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `write_all`.
+        ///
+        /// # Panics
+        ///
+        /// If the given integer is not representable in the given number of
+        /// bytes, this method panics. If `nbytes > 8`, this method panics.
+        #[inline]
+        fn write_int<T: ByteOrder>(
+            &mut self,
+            n: i64,
+            nbytes: usize,
+        ) -> WriteNum<'_, Self> {
+            assert!(nbytes <= 8, "nbytes must be in 0..=8");
+            WriteNum::new(self, nbytes, |buf| T::write_int(buf, n, nbytes))
+        }
+
+        /// Writes an unsigned n-bytes integer to the underlying writer.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `write_all`.
+        ///
+        /// # Panics
+        ///
+        /// If the given integer is not representable in the given number of
+        /// bytes, this method panics. If `nbytes > 16`, this method panics.
+        #[inline]
+        fn write_uint128<T: ByteOrder>(
+            &mut self,
+            n: u128,
+            nbytes: usize,
+        ) -> WriteNum<'_, Self> {
+            assert!(nbytes <= 16, "nbytes must be in 0..=16");
+            WriteNum::new(self, nbytes, |buf| T::write_uint128(buf, n, nbytes))
+        }
+
+        /// Writes a signed n-bytes integer to the underlying writer.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `write_all`.
+        ///
+        /// # Panics
+        ///
+        /// If the given integer is not representable in the given number of
+        /// bytes, this method panics. If `nbytes > 16`, this method panics.
+        #[inline]
+        fn write_int128<T: ByteOrder>(
+            &mut self,
+            n: i128,
+            nbytes: usize,
+        ) -> WriteNum<'_, Self> {
+            assert!(nbytes <= 16, "nbytes must be in 0..=16");
+            WriteNum::new(self, nbytes, |buf| T::write_int128(buf, n, nbytes))
+        }
+
+        /// Writes a sequence of signed 8 bit integers to the underlying
+        /// writer.
+        ///
+        /// The numbers are copied in chunks into a buffer that is allocated
+        /// once per call, and each chunk is passed to `poll_write` until it
+        /// is written. If the future fails, an unspecified prefix of `src`
+        /// may have been written.
+        ///
+        /// # Errors
+        ///
+        /// The future fails with the same errors as `write_all`.
+        #[inline]
+        fn write_i8_from<'a>(
+            &'a mut self,
+            src: &'a [i8],
+        ) -> WriteFrom<'a, Self, i8> {
+            WriteFrom::new(self, src, |src, dst| {
+                for (dst, &n) in dst.iter_mut().zip(src) {
+                    *dst = n as u8;
+                }
+            })
+        }
+
+        $crate::async_methods::write_from_methods! {
+            write_u16_from(u16, write_u16_into, "unsigned 16 bit integers");
+            write_i16_from(i16, write_i16_into, "signed 16 bit integers");
+            write_u32_from(u32, write_u32_into, "unsigned 32 bit integers");
+            write_i32_from(i32, write_i32_into, "signed 32 bit integers");
+            write_u64_from(u64, write_u64_into, "unsigned 64 bit integers");
+            write_i64_from(i64, write_i64_into, "signed 64 bit integers");
+            write_u128_from(u128, write_u128_into, "unsigned 128 bit integers");
+            write_i128_from(i128, write_i128_into, "signed 128 bit integers");
+            write_f32_from(f32, write_f32_into, "IEEE754 single-precision floats");
+            write_f64_from(f64, write_f64_into, "IEEE754 double-precision floats");
+        }
+    };
+}
+
+pub(crate) use {
+    future_types, read_into_methods, read_methods, read_num_methods,
+    write_from_methods, write_methods, write_num_methods,
+};
+
+#[cfg(test)]
+struct NoopWaker;
+
+#[cfg(test)]
+impl std::task::Wake for NoopWaker {
+    fn wake(self: std::sync::Arc<Self>) {}
+}
+
+/// Polls `fut` until it completes, which only works for futures that make
+/// progress every time they are polled. This lets the tests of the async
+/// traits run without a runtime.
+#[cfg(test)]
+pub(crate) fn block_on<F: core::future::Future>(fut: F) -> F::Output {
+    use std::{
+        sync::Arc,
+        task::{Context, Poll, Waker},
+    };
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = Box::pin(fut);
+    loop {
+        if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+            return out;
+        }
+    }
+}
+
+/// Polls `fut` once, and then drops it, like a `select!` whose other branch
+/// won.
+#[cfg(test)]
+pub(crate) fn poll_once<F: core::future::Future>(
+    fut: F,
+) -> core::task::Poll<F::Output> {
+    use std::{
+        sync::Arc,
+        task::{Context, Waker},
+    };
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    Box::pin(fut).as_mut().poll(&mut cx)
+}
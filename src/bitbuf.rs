@@ -0,0 +1,254 @@
+/*!
+An output buffer that mixes bit-packed data with byte-aligned numbers.
+
+Compressed formats like DEFLATE pack codes of arbitrary bit lengths into a
+stream of bytes, but interleave them with ordinary byte-aligned fields, like
+the lengths in the header of a stored block. [`BitBuf`] accumulates bits, and
+its `align_and_put_*` methods pad the current byte with zero bits and then
+append a number in a given byte order, so that encoders never have to flush
+a bit accumulator by hand.
+
+Bits are packed starting with the least significant bit of each byte, and
+the bits of a value are written starting with its least significant bit.
+This is the order used by DEFLATE, among others.
+
+# Examples
+
+A DEFLATE stored block, which starts with a three bit header and then has a
+byte-aligned length and its complement, both in little-endian:
+
+```rust
+use byteorder::{bitbuf::BitBuf, LittleEndian};
+
+let data = b"hi";
+let mut buf = BitBuf::new();
+// BFINAL = 1, BTYPE = 00.
+buf.put_bits(0b001, 3);
+buf.align_and_put_u16::<LittleEndian>(data.len() as u16);
+buf.align_and_put_u16::<LittleEndian>(!(data.len() as u16));
+buf.align_and_put_bytes(data);
+assert_eq!(b"\x01\x02\x00\xfd\xffhi", &buf.into_bytes()[..]);
+```
+*/
+
+use alloc::vec::Vec;
+
+use crate::ByteOrder;
+
+/// A growable buffer of bits.
+///
+/// See the [module documentation](self) for the order in which bits are
+/// packed.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BitBuf {
+    bytes: Vec<u8>,
+    /// Bits that don't make up a whole byte yet, in the low bits.
+    acc: u8,
+    /// The number of bits in `acc`, which is always less than 8.
+    nacc: u32,
+}
+
+impl BitBuf {
+    /// Creates an empty buffer.
+    pub fn new() -> BitBuf {
+        BitBuf::default()
+    }
+
+    /// Creates an empty buffer with room for at least `bytes` bytes.
+    pub fn with_capacity(bytes: usize) -> BitBuf {
+        BitBuf { bytes: Vec::with_capacity(bytes), acc: 0, nacc: 0 }
+    }
+
+    /// Returns the number of bits in this buffer.
+    pub fn bit_len(&self) -> u64 {
+        self.bytes.len() as u64 * 8 + u64::from(self.nacc)
+    }
+
+    /// Returns true if the number of bits in this buffer is a multiple of 8.
+    pub fn is_aligned(&self) -> bool {
+        self.nacc == 0
+    }
+
+    /// Returns the whole bytes in this buffer, leaving out any bits of a
+    /// byte that isn't complete yet.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Appends the low `nbits` bits of `value`, starting with the least
+    /// significant one.
+    ///
+    /// Bits of `value` above the low `nbits` are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbits > 64`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::bitbuf::BitBuf;
+    ///
+    /// let mut buf = BitBuf::new();
+    /// buf.put_bits(0b101, 3);
+    /// buf.put_bits(0b11111, 5);
+    /// buf.put_bits(0xABC, 12);
+    /// assert_eq!(20, buf.bit_len());
+    /// assert_eq!(&[0b1111_1101, 0xBC], buf.as_bytes());
+    /// ```
+    pub fn put_bits(&mut self, value: u64, nbits: u32) {
+        assert!(nbits <= 64, "cannot put more than 64 bits at once");
+        let mut value =
+            if nbits == 64 { value } else { value & !(!0 << nbits) };
+        let mut nbits = nbits;
+        // Top up the partial byte first, so that the rest is byte aligned.
+        if self.nacc > 0 {
+            let take = nbits.min(8 - self.nacc);
+            self.acc |= ((value & ((1 << take) - 1)) as u8) << self.nacc;
+            self.nacc += take;
+            value >>= take;
+            nbits -= take;
+            if self.nacc < 8 {
+                return;
+            }
+            self.bytes.push(self.acc);
+            self.acc = 0;
+            self.nacc = 0;
+        }
+        while nbits >= 8 {
+            self.bytes.push(value as u8);
+            value >>= 8;
+            nbits -= 8;
+        }
+        self.acc = value as u8;
+        self.nacc = nbits;
+    }
+
+    /// Appends a single bit.
+    pub fn put_bit(&mut self, bit: bool) {
+        self.put_bits(u64::from(bit), 1);
+    }
+
+    /// Pads the partial byte at the end of this buffer, if any, with zero
+    /// bits.
+    pub fn align(&mut self) {
+        if self.nacc > 0 {
+            self.bytes.push(self.acc);
+            self.acc = 0;
+            self.nacc = 0;
+        }
+    }
+
+    /// Pads to a byte boundary, and then appends `bytes`.
+    pub fn align_and_put_bytes(&mut self, bytes: &[u8]) {
+        self.align();
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    /// Pads to a byte boundary, and then appends an unsigned 8 bit integer.
+    pub fn align_and_put_u8(&mut self, n: u8) {
+        self.align();
+        self.bytes.push(n);
+    }
+
+    /// Pads to a byte boundary, and then appends an unsigned 16 bit integer
+    /// in byte order `E`.
+    pub fn align_and_put_u16<E: ByteOrder>(&mut self, n: u16) {
+        let mut buf = [0; 2];
+        E::write_u16(&mut buf, n);
+        self.align_and_put_bytes(&buf);
+    }
+
+    /// Pads to a byte boundary, and then appends an unsigned 32 bit integer
+    /// in byte order `E`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{bitbuf::BitBuf, BigEndian};
+    ///
+    /// let mut buf = BitBuf::new();
+    /// buf.put_bit(true);
+    /// buf.align_and_put_u32::<BigEndian>(0x01020304);
+    /// buf.put_bits(0b11, 2);
+    /// assert_eq!(vec![1, 1, 2, 3, 4, 0b11], buf.into_bytes());
+    /// ```
+    pub fn align_and_put_u32<E: ByteOrder>(&mut self, n: u32) {
+        let mut buf = [0; 4];
+        E::write_u32(&mut buf, n);
+        self.align_and_put_bytes(&buf);
+    }
+
+    /// Pads to a byte boundary, and then appends an unsigned 64 bit integer
+    /// in byte order `E`.
+    pub fn align_and_put_u64<E: ByteOrder>(&mut self, n: u64) {
+        let mut buf = [0; 8];
+        E::write_u64(&mut buf, n);
+        self.align_and_put_bytes(&buf);
+    }
+
+    /// Pads to a byte boundary, and returns the bytes of this buffer.
+    pub fn into_bytes(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::BitBuf;
+    use crate::{BigEndian, ByteOrder, LittleEndian};
+
+    #[test]
+    fn bits() {
+        let mut buf = BitBuf::new();
+        for i in 0..10 {
+            buf.put_bit(i % 3 == 0);
+        }
+        assert_eq!(10, buf.bit_len());
+        assert!(!buf.is_aligned());
+        assert_eq!(&[0b0100_1001], buf.as_bytes());
+        assert_eq!(vec![0b0100_1001, 0b10], buf.into_bytes());
+
+        // Every size, at every offset into a byte.
+        for offset in 0..8 {
+            for nbits in 0..=64 {
+                let mut buf = BitBuf::new();
+                buf.put_bits(0, offset);
+                buf.put_bits(u64::MAX, nbits);
+                buf.put_bits(0, 64);
+                let bytes = buf.into_bytes();
+                assert_eq!((offset + nbits + 64 + 7) / 8, bytes.len() as u32);
+                let mut padded = [0; 17];
+                padded[..bytes.len()].copy_from_slice(&bytes);
+                let want = ((1u128 << nbits) - 1) << offset;
+                let got = LittleEndian::read_u128(&padded);
+                assert_eq!(want, got, "{} {}", offset, nbits);
+            }
+        }
+    }
+
+    #[test]
+    fn high_bits_are_ignored() {
+        let mut buf = BitBuf::new();
+        buf.put_bits(0xFF, 3);
+        buf.put_bits(0xFF, 0);
+        buf.put_bits(0, 5);
+        assert_eq!(vec![0b111], buf.into_bytes());
+    }
+
+    #[test]
+    fn aligned_numbers() {
+        let mut buf = BitBuf::with_capacity(16);
+        buf.align_and_put_u16::<BigEndian>(0x0102);
+        assert!(buf.is_aligned());
+        buf.put_bits(0b1, 1);
+        buf.align_and_put_u64::<LittleEndian>(3);
+        buf.align_and_put_u8(9);
+        buf.align();
+        assert_eq!(8 * 12, buf.bit_len());
+        assert_eq!(vec![1, 2, 1, 3, 0, 0, 0, 0, 0, 0, 0, 9], buf.into_bytes());
+    }
+}
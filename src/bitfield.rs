@@ -0,0 +1,466 @@
+/*!
+Bit-fields packed into 8, 16, 32 or 64 bit storage units.
+
+Network protocols and file formats often pack several small fields into a
+single integer, like the version and header length of an IPv4 header, which
+share a byte, or the data offset and flags of a TCP header, which share a 16
+bit word. Reading such a field takes two steps: reading the storage unit in
+the right byte order, and then extracting the bits. This module does both.
+
+There are two conventions for numbering the bits of a unit, and
+specifications use both, so the numbering is always given explicitly:
+
+* [`BitNumbering::Msb0`] numbers the most significant bit as bit 0. This is
+  the numbering of the header diagrams in IETF RFCs.
+* [`BitNumbering::Lsb0`] numbers the least significant bit as bit 0, so that
+  bit `n` has the value `1 << n`. This is the numbering of most hardware
+  register documentation.
+
+Fields are given as half-open ranges of bit numbers, so `0..4` is the first
+four bits in either numbering. [`get_bits`] and [`set_bits`] read and write a
+single field of a unit stored in a byte slice. The
+[`bitfield!`](macro@crate::bitfield) macro declares a struct holding the bytes
+of a unit, with a getter and a setter for each field.
+
+# Examples
+
+The second 16 bit word of the flags part of a TCP header, as drawn in RFC
+9293:
+
+```rust
+use byteorder::{bitfield, BigEndian};
+
+bitfield! {
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    pub struct TcpFlags(u16, BigEndian, Msb0) {
+        /// The size of the header, in 32 bit words.
+        pub data_offset, set_data_offset: 0..4;
+        pub cwr, set_cwr: 8;
+        pub ece, set_ece: 9;
+        pub urg, set_urg: 10;
+        pub ack, set_ack: 11;
+        pub psh, set_psh: 12;
+        pub rst, set_rst: 13;
+        pub syn, set_syn: 14;
+        pub fin, set_fin: 15;
+    }
+}
+
+let mut flags = TcpFlags::from_bytes([0x50, 0x12]);
+assert_eq!(5, flags.data_offset());
+assert!(flags.syn() && flags.ack() && !flags.fin());
+
+flags.set_syn(false);
+flags.set_data_offset(6);
+assert_eq!([0x60, 0x10], flags.into_bytes());
+```
+*/
+
+use core::ops::Range;
+
+use crate::{ByteOrder, Primitive};
+
+/// The numbering of the bits in a storage unit.
+///
+/// See the [module documentation](self) for details.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BitNumbering {
+    /// The most significant bit is bit 0.
+    Msb0,
+    /// The least significant bit is bit 0.
+    Lsb0,
+}
+
+impl BitNumbering {
+    /// Returns the shift and the mask of the field at `bits`, in a unit
+    /// that is `unit_bits` bits wide.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `bits` is empty or doesn't fit in the unit.
+    fn locate(self, unit_bits: u32, bits: Range<u32>) -> (u32, u64) {
+        assert!(
+            bits.start < bits.end && bits.end <= unit_bits,
+            "bits {}..{} are not a field of a {} bit unit",
+            bits.start,
+            bits.end,
+            unit_bits,
+        );
+        let width = bits.end - bits.start;
+        let mask = u64::MAX >> (64 - width);
+        let shift = match self {
+            BitNumbering::Msb0 => unit_bits - bits.end,
+            BitNumbering::Lsb0 => bits.start,
+        };
+        (shift, mask)
+    }
+}
+
+/// The unsigned integer types that bit-fields can be packed into.
+///
+/// This is implemented for `u8`, `u16`, `u32` and `u64`. It is sealed, and
+/// can't be implemented outside of this crate.
+pub trait StorageUnit: Primitive {
+    /// The number of bits in this unit.
+    const BITS: u32;
+
+    /// Converts this unit to a `u64`, without loss.
+    #[doc(hidden)]
+    fn to_u64(self) -> u64;
+
+    /// Converts a `u64` that fits in this unit to this unit.
+    #[doc(hidden)]
+    fn from_u64(n: u64) -> Self;
+}
+
+macro_rules! impl_storage_unit {
+    ($($ty:ty),*) => {$(
+        impl StorageUnit for $ty {
+            const BITS: u32 = <$ty>::BITS;
+
+            #[inline]
+            fn to_u64(self) -> u64 {
+                u64::from(self)
+            }
+
+            #[inline]
+            fn from_u64(n: u64) -> $ty {
+                n as $ty
+            }
+        }
+    )*};
+}
+
+impl_storage_unit!(u8, u16, u32, u64);
+
+/// Returns the field at `bits` of the unit of type `U` at the start of `buf`,
+/// which is stored in byte order `E`.
+///
+/// # Panics
+///
+/// Panics when `buf.len() < U::SIZE`, or when `bits` is empty or doesn't fit
+/// in a `U`.
+///
+/// # Examples
+///
+/// The version and header length of an IPv4 header:
+///
+/// ```rust
+/// use byteorder::{
+///     bitfield::{get_bits, BitNumbering},
+///     BigEndian,
+/// };
+///
+/// let header = [0x45, 0x00];
+/// let version = get_bits::<u8, BigEndian>(&header, BitNumbering::Msb0, 0..4);
+/// let ihl = get_bits::<u8, BigEndian>(&header, BitNumbering::Lsb0, 0..4);
+/// assert_eq!((4, 5), (version, ihl));
+/// ```
+#[inline]
+pub fn get_bits<U: StorageUnit, E: ByteOrder>(
+    buf: &[u8],
+    numbering: BitNumbering,
+    bits: Range<u32>,
+) -> U {
+    let (shift, mask) = numbering.locate(U::BITS, bits);
+    U::from_u64((U::read::<E>(buf).to_u64() >> shift) & mask)
+}
+
+/// Sets the field at `bits` of the unit of type `U` at the start of `buf`,
+/// which is stored in byte order `E`, to `value`.
+///
+/// The other bits of the unit are left as they are.
+///
+/// # Panics
+///
+/// Panics when `buf.len() < U::SIZE`, when `bits` is empty or doesn't fit in
+/// a `U`, or when `value` doesn't fit in the field.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{
+///     bitfield::{set_bits, BitNumbering},
+///     LittleEndian,
+/// };
+///
+/// let mut reg = [0xFF, 0x00];
+/// set_bits::<u16, LittleEndian>(&mut reg, BitNumbering::Lsb0, 4..12, 0xAB);
+/// assert_eq!([0xBF, 0x0A], reg);
+/// ```
+#[inline]
+pub fn set_bits<U: StorageUnit, E: ByteOrder>(
+    buf: &mut [u8],
+    numbering: BitNumbering,
+    bits: Range<u32>,
+    value: U,
+) {
+    let width = bits.end.wrapping_sub(bits.start);
+    let (shift, mask) = numbering.locate(U::BITS, bits);
+    let value = value.to_u64();
+    assert!(
+        value <= mask,
+        "value {} doesn't fit in a {} bit field",
+        value,
+        width
+    );
+    let unit = U::read::<E>(buf).to_u64() & !(mask << shift);
+    U::from_u64(unit | (value << shift)).write::<E>(buf);
+}
+
+/// Declares a struct holding the bytes of a storage unit, with a getter and
+/// a setter for each bit-field packed into it.
+///
+/// The struct is declared as `struct Name(Unit, Order, Numbering)`, where
+/// `Unit` is one of `u8`, `u16`, `u32` or `u64`, `Order` is the byte order
+/// the unit is stored in, like [`BigEndian`](crate::BigEndian), and
+/// `Numbering` is `Msb0` or `Lsb0`, as described by
+/// [`BitNumbering`](crate::bitfield::BitNumbering). It is a tuple struct
+/// around the unit's bytes, in the order they are stored in, so it has the
+/// same size as the unit and an alignment of 1. It gets these methods:
+///
+/// * `from_bytes`, which creates it from the bytes of the unit.
+/// * `into_bytes` and `as_bytes`, which return the bytes of the unit.
+///
+/// Each field is given as `getter, setter: start..end;`, where `start..end`
+/// is the half-open range of its bit numbers. The getter returns the field
+/// as the unit type, and the setter takes it as the unit type, and panics if
+/// it doesn't fit in the field. A field of a single bit may instead be given
+/// as `getter, setter: bit;`, in which case the getter returns and the
+/// setter takes a `bool`. Ranges that don't fit in the unit fail to compile.
+///
+/// Attributes, including doc comments and derives, may be given on the
+/// struct and on each field, where they apply to the getter. The
+/// constructors and conversions have the same visibility as the struct, and
+/// each getter and setter has the visibility given before it.
+///
+/// See the [`bitfield`](mod@crate::bitfield) module for an example.
+///
+/// # Examples
+///
+/// A hardware register with its least significant bit numbered 0:
+///
+/// ```rust
+/// use byteorder::{bitfield, LittleEndian};
+///
+/// bitfield! {
+///     struct Control(u32, LittleEndian, Lsb0) {
+///         enable, set_enable: 0;
+///         mode, set_mode: 1..3;
+///         divisor, set_divisor: 16..32;
+///     }
+/// }
+///
+/// let mut ctrl = Control::from_bytes([0; 4]);
+/// ctrl.set_enable(true);
+/// ctrl.set_mode(2);
+/// ctrl.set_divisor(0x1234);
+/// assert_eq!([0x05, 0x00, 0x34, 0x12], ctrl.into_bytes());
+/// ```
+#[macro_export]
+macro_rules! bitfield {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident($unit:ty, $order:ty, $numbering:ident) {
+            $($fields:tt)*
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name([u8; ::core::mem::size_of::<$unit>()]);
+
+        #[allow(dead_code)]
+        impl $name {
+            /// Creates this bit-field from the bytes of its storage unit.
+            #[inline]
+            $vis const fn from_bytes(
+                bytes: [u8; ::core::mem::size_of::<$unit>()],
+            ) -> $name {
+                $name(bytes)
+            }
+
+            /// Returns the bytes of the storage unit of this bit-field.
+            #[inline]
+            $vis const fn into_bytes(
+                self,
+            ) -> [u8; ::core::mem::size_of::<$unit>()] {
+                self.0
+            }
+
+            /// Returns a reference to the bytes of the storage unit of this
+            /// bit-field.
+            #[inline]
+            $vis const fn as_bytes(
+                &self,
+            ) -> &[u8; ::core::mem::size_of::<$unit>()] {
+                &self.0
+            }
+
+            $crate::bitfield!(@fields ($unit, $order, $numbering) $($fields)*);
+        }
+    };
+    (
+        @fields ($unit:ty, $order:ty, $numbering:ident)
+        $(#[$attr:meta])*
+        $field_vis:vis $get:ident, $set:ident: $start:literal..$end:literal;
+        $($rest:tt)*
+    ) => {
+        $(#[$attr])*
+        #[inline]
+        $field_vis fn $get(&self) -> $unit {
+            $crate::bitfield!(@check $unit, $start, $end);
+            $crate::bitfield::get_bits::<$unit, $order>(
+                &self.0,
+                $crate::bitfield::BitNumbering::$numbering,
+                $start..$end,
+            )
+        }
+
+        #[doc = concat!(
+            "Sets the bits returned by [`", stringify!($get), "`]",
+            "(Self::", stringify!($get), ").\n\n",
+            "# Panics\n\n",
+            "Panics when `value` doesn't fit in the field.",
+        )]
+        #[inline]
+        $field_vis fn $set(&mut self, value: $unit) {
+            $crate::bitfield::set_bits::<$unit, $order>(
+                &mut self.0,
+                $crate::bitfield::BitNumbering::$numbering,
+                $start..$end,
+                value,
+            )
+        }
+
+        $crate::bitfield!(@fields ($unit, $order, $numbering) $($rest)*);
+    };
+    (
+        @fields ($unit:ty, $order:ty, $numbering:ident)
+        $(#[$attr:meta])*
+        $field_vis:vis $get:ident, $set:ident: $bit:literal;
+        $($rest:tt)*
+    ) => {
+        $(#[$attr])*
+        #[inline]
+        $field_vis fn $get(&self) -> bool {
+            $crate::bitfield!(@check $unit, $bit);
+            $crate::bitfield::get_bits::<$unit, $order>(
+                &self.0,
+                $crate::bitfield::BitNumbering::$numbering,
+                $bit..$bit + 1,
+            ) != 0
+        }
+
+        #[doc = concat!(
+            "Sets the bit returned by [`", stringify!($get), "`]",
+            "(Self::", stringify!($get), ").",
+        )]
+        #[inline]
+        $field_vis fn $set(&mut self, value: bool) {
+            $crate::bitfield::set_bits::<$unit, $order>(
+                &mut self.0,
+                $crate::bitfield::BitNumbering::$numbering,
+                $bit..$bit + 1,
+                <$unit>::from(value),
+            )
+        }
+
+        $crate::bitfield!(@fields ($unit, $order, $numbering) $($rest)*);
+    };
+    (@fields ($unit:ty, $order:ty, $numbering:ident)) => {};
+    (@check $unit:ty, $bit:expr) => {
+        const _: () = assert!(
+            $bit < <$unit as $crate::bitfield::StorageUnit>::BITS,
+            "bit-field doesn't fit in its storage unit",
+        );
+    };
+    (@check $unit:ty, $start:expr, $end:expr) => {
+        const _: () = assert!(
+            $start < $end
+                && $end <= <$unit as $crate::bitfield::StorageUnit>::BITS,
+            "bit-field doesn't fit in its storage unit",
+        );
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_bits, set_bits, BitNumbering};
+    use crate::{BigEndian, LittleEndian};
+
+    crate::bitfield! {
+        #[derive(Clone, Copy, Debug, Default, PartialEq)]
+        struct VersionIhl(u8, BigEndian, Msb0) {
+            version, set_version: 0..4;
+            ihl, set_ihl: 4..8;
+        }
+    }
+
+    crate::bitfield! {
+        struct Wide(u64, LittleEndian, Lsb0) {
+            low, set_low: 0;
+            all, set_all: 0..64;
+            /// The most significant bit.
+            pub(crate) high, set_high: 63;
+        }
+    }
+
+    #[test]
+    fn ipv4() {
+        let mut b = VersionIhl::from_bytes([0x45]);
+        assert_eq!((4, 5), (b.version(), b.ihl()));
+        b.set_version(6);
+        b.set_ihl(15);
+        assert_eq!(&[0x6F], b.as_bytes());
+        assert_eq!(1, core::mem::size_of::<VersionIhl>());
+        assert_eq!(VersionIhl::from_bytes([0]), VersionIhl::default());
+    }
+
+    #[test]
+    fn wide() {
+        let mut w = Wide::from_bytes([0; 8]);
+        w.set_high(true);
+        assert_eq!([0, 0, 0, 0, 0, 0, 0, 0x80], w.into_bytes());
+        let mut w = Wide::from_bytes([0; 8]);
+        w.set_all(u64::MAX - 1);
+        assert!(!w.low() && w.high());
+        w.set_low(true);
+        assert_eq!(u64::MAX, w.all());
+    }
+
+    #[test]
+    fn numbering() {
+        let buf = [0b1000_0001, 0b0000_0110];
+        let msb = |r| get_bits::<u16, BigEndian>(&buf, BitNumbering::Msb0, r);
+        let lsb = |r| get_bits::<u16, BigEndian>(&buf, BitNumbering::Lsb0, r);
+        assert_eq!(1, msb(0..1));
+        assert_eq!(0b110, msb(13..16));
+        assert_eq!(0b011, lsb(1..3));
+        assert_eq!(1, lsb(15..16));
+        assert_eq!(
+            0x0681,
+            get_bits::<u16, LittleEndian>(&buf, BitNumbering::Lsb0, 0..16,)
+        );
+    }
+
+    #[test]
+    fn set_keeps_other_bits() {
+        let mut buf = [0xFF; 4];
+        set_bits::<u32, BigEndian>(&mut buf, BitNumbering::Msb0, 8..24, 0);
+        assert_eq!([0xFF, 0, 0, 0xFF], buf);
+        set_bits::<u32, LittleEndian>(&mut buf, BitNumbering::Lsb0, 0..4, 3);
+        assert_eq!([0xF3, 0, 0, 0xFF], buf);
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_too_wide() {
+        let mut b = VersionIhl::default();
+        b.set_ihl(16);
+    }
+
+    #[test]
+    #[should_panic]
+    fn range_out_of_unit() {
+        get_bits::<u8, BigEndian>(&[0], BitNumbering::Lsb0, 4..9);
+    }
+}
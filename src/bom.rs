@@ -0,0 +1,99 @@
+/*!
+Byte order marks.
+
+Text stored inside binary containers often starts with a byte order mark,
+the character `U+FEFF`, whose encoding reveals the byte order of UTF-16
+text, or merely announces UTF-8. The constants in this module are the
+encodings of the mark, and [`strip_bom`] detects and removes one from the
+start of some bytes.
+
+The `write_bom_u16` and `write_bom_u8` methods of
+[`WriteBytesExt`](https://docs.rs/byteorder/1/byteorder/trait.WriteBytesExt.html)
+write byte order marks with `std::io`.
+
+# Examples
+
+```rust
+use byteorder::{bom::strip_bom, BigEndian, ByteOrder, Endianness};
+
+let data = b"\xFE\xFF\x00h\x00i";
+let (order, text) = strip_bom(data);
+assert_eq!(Some(Endianness::Big), order);
+assert_eq!(u16::from(b'h'), BigEndian::read_u16(text));
+```
+*/
+
+use crate::Endianness;
+
+/// The byte order mark of UTF-8 text.
+pub const UTF8: [u8; 3] = [0xEF, 0xBB, 0xBF];
+
+/// The byte order mark of big-endian UTF-16 text.
+pub const UTF16_BE: [u8; 2] = [0xFE, 0xFF];
+
+/// The byte order mark of little-endian UTF-16 text.
+pub const UTF16_LE: [u8; 2] = [0xFF, 0xFE];
+
+/// Removes a byte order mark from the start of `data`, and returns the byte
+/// order it indicates along with the rest of `data`.
+///
+/// A UTF-16 byte order mark gives its byte order. A UTF-8 byte order mark is
+/// removed too, but gives no byte order, since UTF-8 doesn't have one. When
+/// `data` doesn't start with a byte order mark, it is returned unchanged
+/// with no byte order.
+///
+/// UTF-32 byte order marks are not recognized, since the little-endian one
+/// can't be told apart from a UTF-16 byte order mark followed by a NUL
+/// character.
+///
+/// To require a UTF-16 byte order mark and get an error without one, use
+/// [`Endianness::from_bom_u16`] instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{bom::strip_bom, Endianness};
+///
+/// assert_eq!((Some(Endianness::Little), &b"h\0"[..]), strip_bom(b"\xFF\xFEh\0"));
+/// assert_eq!((None, &b"hi"[..]), strip_bom(b"\xEF\xBB\xBFhi"));
+/// assert_eq!((None, &b"hi"[..]), strip_bom(b"hi"));
+/// ```
+pub fn strip_bom(data: &[u8]) -> (Option<Endianness>, &[u8]) {
+    if let Some(rest) = data.strip_prefix(&UTF16_BE) {
+        (Some(Endianness::Big), rest)
+    } else if let Some(rest) = data.strip_prefix(&UTF16_LE) {
+        (Some(Endianness::Little), rest)
+    } else if let Some(rest) = data.strip_prefix(&UTF8) {
+        (None, rest)
+    } else {
+        (None, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{strip_bom, UTF16_BE, UTF16_LE, UTF8};
+    use crate::{BigEndian, ByteOrder, Endianness, LittleEndian};
+
+    #[test]
+    fn marks_encode_feff() {
+        assert_eq!(0xFEFF, BigEndian::read_u16(&UTF16_BE));
+        assert_eq!(0xFEFF, LittleEndian::read_u16(&UTF16_LE));
+        let mut buf = [0; 4];
+        assert_eq!(&UTF8[..], '\u{FEFF}'.encode_utf8(&mut buf).as_bytes());
+    }
+
+    #[test]
+    fn strips() {
+        assert_eq!((Some(Endianness::Big), &[][..]), strip_bom(&[0xFE, 0xFF]));
+        assert_eq!(
+            (Some(Endianness::Little), &[0][..]),
+            strip_bom(&[0xFF, 0xFE, 0])
+        );
+        assert_eq!((None, &[][..]), strip_bom(&UTF8));
+        // Incomplete marks are left alone.
+        assert_eq!((None, &[0xEF, 0xBB][..]), strip_bom(&[0xEF, 0xBB]));
+        assert_eq!((None, &[0xFE][..]), strip_bom(&[0xFE]));
+        assert_eq!((None, &[][..]), strip_bom(&[]));
+    }
+}
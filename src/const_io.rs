@@ -0,0 +1,730 @@
+/*!
+`const fn` versions of the fixed width reads and writes.
+
+The methods on [`ByteOrder`](crate::ByteOrder) cannot be called in `const`
+or `static` initializers, since trait methods cannot be `const`. This module
+provides free functions for the same conversions that can. They live in two
+submodules, [`be`] for big-endian and [`le`] for little-endian.
+
+Because `const fn` cannot take mutable references or slice with ranges on
+this crate's minimum supported Rust version, the functions here differ
+slightly from their `ByteOrder` counterparts:
+
+* Reads take an explicit `offset` into `buf`.
+* Writes take a byte array by value and return the updated array.
+
+Floating point conversions are not provided, since `f32::from_bits` and
+friends are not `const` on the minimum supported Rust version.
+
+# Examples
+
+Build a pre-encoded header at compile time:
+
+```rust
+use byteorder::const_io::{be, le};
+
+const MAGIC: u32 = 0xCAFE_BABE;
+const HEADER: [u8; 6] = le::write_u16(be::write_u32([0; 6], 0, MAGIC), 4, 1);
+
+assert_eq!(HEADER, [0xCA, 0xFE, 0xBA, 0xBE, 0x01, 0x00]);
+assert_eq!(MAGIC, be::read_u32(&HEADER, 0));
+```
+
+# Packing templates
+
+Computing every offset by hand gets tedious for longer templates. A
+[`Packer`] appends fields one after the other instead, and checks that they
+fill its array exactly. Since every method is a `const fn`, a mistake in the
+size of a template is a compile error:
+
+```rust
+use byteorder::const_io::Packer;
+
+const MAGIC: [u8; 4] = *b"FRM1";
+
+/// A pre-encoded frame, stored in flash rather than built at runtime.
+static PING: [u8; 12] = Packer::new()
+    .bytes(&MAGIC)
+    .u8(1)
+    .zeros(1)
+    .u16_le(4)
+    .u32_be(0xDEAD_BEEF)
+    .finish();
+
+assert_eq!(&PING, b"FRM1\x01\x00\x04\x00\xDE\xAD\xBE\xEF");
+```
+*/
+
+/// Copies `N` bytes starting at `buf[offset]` into an array.
+///
+/// Panics if `buf` does not contain `N` bytes starting at `offset`.
+const fn get<const N: usize>(buf: &[u8], offset: usize) -> [u8; N] {
+    let mut out = [0; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = buf[offset + i];
+        i += 1;
+    }
+    out
+}
+
+/// Copies `bytes` into `buf` starting at `buf[offset]`.
+///
+/// Panics if `buf` does not have room for `bytes` starting at `offset`.
+const fn put<const N: usize>(
+    mut buf: [u8; N],
+    offset: usize,
+    bytes: &[u8],
+) -> [u8; N] {
+    let mut i = 0;
+    while i < bytes.len() {
+        buf[offset + i] = bytes[i];
+        i += 1;
+    }
+    buf
+}
+
+macro_rules! pack_methods {
+    ($($name:ident($ty:ty, $to_bytes:ident, $desc:expr);)*) => {$(
+        #[doc = concat!("Appends ", $desc, ".")]
+        ///
+        /// # Panics
+        ///
+        /// Panics when the number doesn't fit in the rest of the array.
+        #[inline]
+        pub const fn $name(self, n: $ty) -> Packer<N> {
+            self.bytes(&n.$to_bytes())
+        }
+    )*};
+}
+
+/// Assembles a byte array from a sequence of fields, in a `const` context.
+///
+/// Each method takes the packer by value and returns it with one more field
+/// appended, so that a template is built by chaining calls, and
+/// [`finish`](Packer::finish) returns the array. Every method panics when a
+/// field doesn't fit, which is a compile error in a `const` or `static`
+/// initializer. See the [module documentation](self) for an example.
+#[derive(Clone, Copy, Debug)]
+pub struct Packer<const N: usize> {
+    buf: [u8; N],
+    pos: usize,
+}
+
+impl<const N: usize> Packer<N> {
+    /// Creates a packer for an array of `N` bytes, with no fields yet.
+    #[inline]
+    pub const fn new() -> Packer<N> {
+        Packer { buf: [0; N], pos: 0 }
+    }
+
+    /// Returns the number of bytes appended so far.
+    #[inline]
+    pub const fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Appends all of `bytes`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `bytes` doesn't fit in the rest of the array.
+    #[inline]
+    pub const fn bytes(mut self, bytes: &[u8]) -> Packer<N> {
+        assert!(bytes.len() <= N - self.pos, "packed fields overflow array");
+        self.buf = put(self.buf, self.pos, bytes);
+        self.pos += bytes.len();
+        self
+    }
+
+    /// Appends `n` zero bytes, as padding or as a placeholder for a field
+    /// that is filled in at runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `n` bytes don't fit in the rest of the array.
+    #[inline]
+    pub const fn zeros(mut self, n: usize) -> Packer<N> {
+        assert!(n <= N - self.pos, "packed fields overflow array");
+        self.pos += n;
+        self
+    }
+
+    /// Appends an unsigned 8 bit integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the array is already full.
+    #[inline]
+    pub const fn u8(self, n: u8) -> Packer<N> {
+        self.bytes(&[n])
+    }
+
+    /// Appends a signed 8 bit integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the array is already full.
+    #[inline]
+    pub const fn i8(self, n: i8) -> Packer<N> {
+        self.bytes(&[n as u8])
+    }
+
+    pack_methods! {
+        u16_be(u16, to_be_bytes, "a big-endian unsigned 16 bit integer");
+        u16_le(u16, to_le_bytes, "a little-endian unsigned 16 bit integer");
+        i16_be(i16, to_be_bytes, "a big-endian signed 16 bit integer");
+        i16_le(i16, to_le_bytes, "a little-endian signed 16 bit integer");
+        u32_be(u32, to_be_bytes, "a big-endian unsigned 32 bit integer");
+        u32_le(u32, to_le_bytes, "a little-endian unsigned 32 bit integer");
+        i32_be(i32, to_be_bytes, "a big-endian signed 32 bit integer");
+        i32_le(i32, to_le_bytes, "a little-endian signed 32 bit integer");
+        u64_be(u64, to_be_bytes, "a big-endian unsigned 64 bit integer");
+        u64_le(u64, to_le_bytes, "a little-endian unsigned 64 bit integer");
+        i64_be(i64, to_be_bytes, "a big-endian signed 64 bit integer");
+        i64_le(i64, to_le_bytes, "a little-endian signed 64 bit integer");
+        u128_be(u128, to_be_bytes, "a big-endian unsigned 128 bit integer");
+        u128_le(u128, to_le_bytes, "a little-endian unsigned 128 bit integer");
+        i128_be(i128, to_be_bytes, "a big-endian signed 128 bit integer");
+        i128_le(i128, to_le_bytes, "a little-endian signed 128 bit integer");
+    }
+
+    /// Returns the packed array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the fields appended don't fill the whole array, since
+    /// that usually means a field was forgotten. Append [`zeros`] to leave
+    /// the rest of the array zeroed on purpose.
+    ///
+    /// [`zeros`]: Packer::zeros
+    #[inline]
+    pub const fn finish(self) -> [u8; N] {
+        assert!(self.pos == N, "packed fields don't fill array");
+        self.buf
+    }
+}
+
+impl<const N: usize> Default for Packer<N> {
+    fn default() -> Packer<N> {
+        Packer::new()
+    }
+}
+
+/// `const fn` reads and writes in big-endian byte order.
+pub mod be {
+    use super::{get, put};
+
+    /// Reads an unsigned 16 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 2`.
+    #[inline]
+    pub const fn read_u16(buf: &[u8], offset: usize) -> u16 {
+        u16::from_be_bytes(get::<2>(buf, offset))
+    }
+
+    /// Writes an unsigned 16 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 2`.
+    #[inline]
+    pub const fn write_u16<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: u16,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_be_bytes())
+    }
+
+    /// Reads a signed 16 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 2`.
+    #[inline]
+    pub const fn read_i16(buf: &[u8], offset: usize) -> i16 {
+        i16::from_be_bytes(get::<2>(buf, offset))
+    }
+
+    /// Writes a signed 16 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 2`.
+    #[inline]
+    pub const fn write_i16<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: i16,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_be_bytes())
+    }
+
+    /// Reads an unsigned 32 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 4`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::const_io::be;
+    ///
+    /// const N: u32 = be::read_u32(&[0xFF, 0x12, 0x34, 0x56, 0x78], 1);
+    /// assert_eq!(0x1234_5678, N);
+    /// ```
+    #[inline]
+    pub const fn read_u32(buf: &[u8], offset: usize) -> u32 {
+        u32::from_be_bytes(get::<4>(buf, offset))
+    }
+
+    /// Writes an unsigned 32 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 4`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::const_io::be;
+    ///
+    /// const BUF: [u8; 5] = be::write_u32([0xFF; 5], 1, 0x1234_5678);
+    /// assert_eq!(BUF, [0xFF, 0x12, 0x34, 0x56, 0x78]);
+    /// ```
+    #[inline]
+    pub const fn write_u32<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: u32,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_be_bytes())
+    }
+
+    /// Reads a signed 32 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 4`.
+    #[inline]
+    pub const fn read_i32(buf: &[u8], offset: usize) -> i32 {
+        i32::from_be_bytes(get::<4>(buf, offset))
+    }
+
+    /// Writes a signed 32 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 4`.
+    #[inline]
+    pub const fn write_i32<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: i32,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_be_bytes())
+    }
+
+    /// Reads an unsigned 64 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 8`.
+    #[inline]
+    pub const fn read_u64(buf: &[u8], offset: usize) -> u64 {
+        u64::from_be_bytes(get::<8>(buf, offset))
+    }
+
+    /// Writes an unsigned 64 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 8`.
+    #[inline]
+    pub const fn write_u64<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: u64,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_be_bytes())
+    }
+
+    /// Reads a signed 64 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 8`.
+    #[inline]
+    pub const fn read_i64(buf: &[u8], offset: usize) -> i64 {
+        i64::from_be_bytes(get::<8>(buf, offset))
+    }
+
+    /// Writes a signed 64 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 8`.
+    #[inline]
+    pub const fn write_i64<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: i64,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_be_bytes())
+    }
+
+    /// Reads an unsigned 128 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 16`.
+    #[inline]
+    pub const fn read_u128(buf: &[u8], offset: usize) -> u128 {
+        u128::from_be_bytes(get::<16>(buf, offset))
+    }
+
+    /// Writes an unsigned 128 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 16`.
+    #[inline]
+    pub const fn write_u128<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: u128,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_be_bytes())
+    }
+
+    /// Reads a signed 128 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 16`.
+    #[inline]
+    pub const fn read_i128(buf: &[u8], offset: usize) -> i128 {
+        i128::from_be_bytes(get::<16>(buf, offset))
+    }
+
+    /// Writes a signed 128 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 16`.
+    #[inline]
+    pub const fn write_i128<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: i128,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_be_bytes())
+    }
+}
+
+/// `const fn` reads and writes in little-endian byte order.
+pub mod le {
+    use super::{get, put};
+
+    /// Reads an unsigned 16 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 2`.
+    #[inline]
+    pub const fn read_u16(buf: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes(get::<2>(buf, offset))
+    }
+
+    /// Writes an unsigned 16 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 2`.
+    #[inline]
+    pub const fn write_u16<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: u16,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_le_bytes())
+    }
+
+    /// Reads a signed 16 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 2`.
+    #[inline]
+    pub const fn read_i16(buf: &[u8], offset: usize) -> i16 {
+        i16::from_le_bytes(get::<2>(buf, offset))
+    }
+
+    /// Writes a signed 16 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 2`.
+    #[inline]
+    pub const fn write_i16<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: i16,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_le_bytes())
+    }
+
+    /// Reads an unsigned 32 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 4`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::const_io::le;
+    ///
+    /// const N: u32 = le::read_u32(&[0xFF, 0x78, 0x56, 0x34, 0x12], 1);
+    /// assert_eq!(0x1234_5678, N);
+    /// ```
+    #[inline]
+    pub const fn read_u32(buf: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(get::<4>(buf, offset))
+    }
+
+    /// Writes an unsigned 32 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 4`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::const_io::le;
+    ///
+    /// const BUF: [u8; 5] = le::write_u32([0xFF; 5], 1, 0x1234_5678);
+    /// assert_eq!(BUF, [0xFF, 0x78, 0x56, 0x34, 0x12]);
+    /// ```
+    #[inline]
+    pub const fn write_u32<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: u32,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_le_bytes())
+    }
+
+    /// Reads a signed 32 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 4`.
+    #[inline]
+    pub const fn read_i32(buf: &[u8], offset: usize) -> i32 {
+        i32::from_le_bytes(get::<4>(buf, offset))
+    }
+
+    /// Writes a signed 32 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 4`.
+    #[inline]
+    pub const fn write_i32<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: i32,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_le_bytes())
+    }
+
+    /// Reads an unsigned 64 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 8`.
+    #[inline]
+    pub const fn read_u64(buf: &[u8], offset: usize) -> u64 {
+        u64::from_le_bytes(get::<8>(buf, offset))
+    }
+
+    /// Writes an unsigned 64 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 8`.
+    #[inline]
+    pub const fn write_u64<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: u64,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_le_bytes())
+    }
+
+    /// Reads a signed 64 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 8`.
+    #[inline]
+    pub const fn read_i64(buf: &[u8], offset: usize) -> i64 {
+        i64::from_le_bytes(get::<8>(buf, offset))
+    }
+
+    /// Writes a signed 64 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 8`.
+    #[inline]
+    pub const fn write_i64<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: i64,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_le_bytes())
+    }
+
+    /// Reads an unsigned 128 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 16`.
+    #[inline]
+    pub const fn read_u128(buf: &[u8], offset: usize) -> u128 {
+        u128::from_le_bytes(get::<16>(buf, offset))
+    }
+
+    /// Writes an unsigned 128 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 16`.
+    #[inline]
+    pub const fn write_u128<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: u128,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_le_bytes())
+    }
+
+    /// Reads a signed 128 bit integer from `buf` starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < offset + 16`.
+    #[inline]
+    pub const fn read_i128(buf: &[u8], offset: usize) -> i128 {
+        i128::from_le_bytes(get::<16>(buf, offset))
+    }
+
+    /// Writes a signed 128 bit integer `n` into `buf` starting at `offset`
+    /// and returns the updated array.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `N < offset + 16`.
+    #[inline]
+    pub const fn write_i128<const N: usize>(
+        buf: [u8; N],
+        offset: usize,
+        n: i128,
+    ) -> [u8; N] {
+        put(buf, offset, &n.to_le_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{be, le, Packer};
+    use crate::{BigEndian, ByteOrder, LittleEndian};
+
+    const BE_TABLE: [u8; 30] = be::write_u128(
+        be::write_i64(
+            be::write_u32(be::write_i16([0; 30], 0, -2), 2, 7),
+            6,
+            -9,
+        ),
+        14,
+        u128::MAX - 1,
+    );
+
+    #[test]
+    fn agrees_with_byte_order() {
+        assert_eq!(-2, BigEndian::read_i16(&BE_TABLE[0..]));
+        assert_eq!(7, BigEndian::read_u32(&BE_TABLE[2..]));
+        assert_eq!(-9, BigEndian::read_i64(&BE_TABLE[6..]));
+        assert_eq!(u128::MAX - 1, BigEndian::read_u128(&BE_TABLE[14..]));
+
+        assert_eq!(-2, be::read_i16(&BE_TABLE, 0));
+        assert_eq!(7, be::read_u32(&BE_TABLE, 2));
+        assert_eq!(-9, be::read_i64(&BE_TABLE, 6));
+        assert_eq!(u128::MAX - 1, be::read_u128(&BE_TABLE, 14));
+
+        let mut buf = [0; 8];
+        LittleEndian::write_u64(&mut buf, 0x0102_0304_0506_0708);
+        assert_eq!(buf, le::write_u64([0; 8], 0, 0x0102_0304_0506_0708));
+        assert_eq!(0x0102_0304_0506_0708, le::read_u64(&buf, 0));
+        assert_eq!(0x0304, le::read_u16(&buf, 4));
+    }
+
+    #[test]
+    fn packer() {
+        const PACKED: [u8; 30] = Packer::new()
+            .i16_be(-2)
+            .u32_be(7)
+            .i64_be(-9)
+            .u128_be(u128::MAX - 1)
+            .finish();
+        assert_eq!(BE_TABLE, PACKED);
+
+        const MIXED: [u8; 8] =
+            Packer::new().bytes(b"ab").i8(-1).zeros(1).u32_le(5).finish();
+        assert_eq!(b"ab\xFF\x00\x05\x00\x00\x00", &MIXED);
+
+        let packer = Packer::<4>::new().u16_le(1);
+        assert_eq!(2, packer.position());
+        assert_eq!([1, 0, 0, 0], packer.zeros(2).finish());
+    }
+
+    #[test]
+    #[should_panic]
+    fn packer_overflow() {
+        Packer::<3>::new().u8(1).u16_be(2).u8(3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn packer_unfilled() {
+        Packer::<3>::new().u16_be(2).finish();
+    }
+
+    #[test]
+    #[should_panic]
+    fn read_out_of_bounds() {
+        be::read_u32(&[0; 4], 1);
+    }
+}
@@ -0,0 +1,239 @@
+/*!
+Readers and writers that keep track of their position.
+
+Parsers want to report where in their input an error happened, and
+serializers want to know how much they have written so far, so that a length
+field written earlier can be filled in afterwards. [`CountingReader`] and
+[`CountingWriter`] wrap any reader or writer and count the bytes that pass
+through them. Since they implement `Read` and `Write`, the methods of
+[`ReadBytesExt`](crate::ReadBytesExt) and
+[`WriteBytesExt`](crate::WriteBytesExt) work on them as usual.
+
+# Examples
+
+Report the offset of a truncated field:
+
+```rust
+use byteorder::{counting::CountingReader, BigEndian, ReadBytesExt};
+
+let mut rdr = CountingReader::new(&b"\x00\x01\x00\x02\x00"[..]);
+let mut ids = vec![];
+let err = loop {
+    let at = rdr.position();
+    match rdr.read_u16::<BigEndian>() {
+        Ok(id) => ids.push(id),
+        Err(err) => break format!("error at byte offset {}: {}", at, err),
+    }
+};
+assert_eq!(vec![1, 2], ids);
+assert!(err.starts_with("error at byte offset 4: "));
+```
+
+Backfill the length of a record:
+
+```rust
+use byteorder::{
+    counting::CountingWriter, BigEndian, ByteOrder, WriteBytesExt,
+};
+
+let mut wtr = CountingWriter::new(vec![]);
+wtr.write_u8(7).unwrap();
+let len_at = wtr.position();
+// A placeholder for the length, which isn't known yet.
+wtr.write_u16::<BigEndian>(0).unwrap();
+let start = wtr.position();
+wtr.write_u32_from::<BigEndian>(&[1, 2, 3]).unwrap();
+let len = wtr.position() - start;
+
+let mut buf = wtr.into_inner();
+BigEndian::write_u16(&mut buf[len_at as usize..], len as u16);
+assert_eq!(&buf[..3], b"\x07\x00\x0c");
+```
+*/
+
+use std::io::{BufRead, Read, Result, Seek, SeekFrom, Write};
+
+/// A reader that counts the bytes read from it.
+///
+/// The position starts at zero, or at the value given to
+/// [`CountingReader::with_position`], and advances by every byte read. When
+/// the underlying reader implements `Seek`, seeking through this reader
+/// sets the position to the one reported by the underlying reader.
+#[derive(Clone, Debug)]
+pub struct CountingReader<R> {
+    rdr: R,
+    pos: u64,
+}
+
+impl<R> CountingReader<R> {
+    /// Creates a counting reader whose position starts at zero.
+    pub fn new(rdr: R) -> CountingReader<R> {
+        CountingReader::with_position(rdr, 0)
+    }
+
+    /// Creates a counting reader whose position starts at `pos`.
+    ///
+    /// This is useful when `rdr` has already been read from, so that
+    /// positions are reported relative to the start of the input.
+    pub fn with_position(rdr: R, pos: u64) -> CountingReader<R> {
+        CountingReader { rdr, pos }
+    }
+
+    /// Returns the current position, which is the number of bytes read plus
+    /// the starting position.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.rdr
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Bytes read from the underlying reader directly are not counted.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.rdr
+    }
+
+    /// Unwraps this reader, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.rdr
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = self.rdr.read(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for CountingReader<R> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.rdr.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.rdr.consume(amt);
+        self.pos += amt as u64;
+    }
+}
+
+impl<R: Seek> Seek for CountingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.pos = self.rdr.seek(pos)?;
+        Ok(self.pos)
+    }
+}
+
+/// A writer that counts the bytes written to it.
+///
+/// The position starts at zero, or at the value given to
+/// [`CountingWriter::with_position`], and advances by every byte written.
+/// When the underlying writer implements `Seek`, seeking through this writer
+/// sets the position to the one reported by the underlying writer.
+#[derive(Clone, Debug)]
+pub struct CountingWriter<W> {
+    wtr: W,
+    pos: u64,
+}
+
+impl<W> CountingWriter<W> {
+    /// Creates a counting writer whose position starts at zero.
+    pub fn new(wtr: W) -> CountingWriter<W> {
+        CountingWriter::with_position(wtr, 0)
+    }
+
+    /// Creates a counting writer whose position starts at `pos`.
+    pub fn with_position(wtr: W, pos: u64) -> CountingWriter<W> {
+        CountingWriter { wtr, pos }
+    }
+
+    /// Returns the current position, which is the number of bytes written
+    /// plus the starting position.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.wtr
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Bytes written to the underlying writer directly are not counted.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.wtr
+    }
+
+    /// Unwraps this writer, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.wtr
+    }
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let n = self.wtr.write(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.wtr.flush()
+    }
+}
+
+impl<W: Seek> Seek for CountingWriter<W> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.pos = self.wtr.seek(pos)?;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Cursor, Read, Seek, SeekFrom};
+
+    use super::{CountingReader, CountingWriter};
+    use crate::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+    #[test]
+    fn reader() {
+        let data = b"\x01\x02\x03\x04\x05\x06ab\ncd";
+        let mut rdr = CountingReader::with_position(&data[..], 10);
+        assert_eq!(0x0201, rdr.read_u16::<LittleEndian>().unwrap());
+        assert_eq!(12, rdr.position());
+        assert!(rdr.read_u32::<LittleEndian>().is_ok());
+        let mut line = String::new();
+        rdr.read_line(&mut line).unwrap();
+        assert_eq!((19, "ab\n"), (rdr.position(), &line[..]));
+        let mut rest = vec![];
+        rdr.read_to_end(&mut rest).unwrap();
+        assert_eq!(21, rdr.position());
+    }
+
+    #[test]
+    fn seeking() {
+        let mut rdr = CountingReader::new(Cursor::new(vec![0; 10]));
+        rdr.read_u16::<LittleEndian>().unwrap();
+        assert_eq!(8, rdr.seek(SeekFrom::End(-2)).unwrap());
+        rdr.read_u8().unwrap();
+        assert_eq!(9, rdr.position());
+
+        let mut wtr = CountingWriter::new(Cursor::new(vec![]));
+        wtr.write_u64::<LittleEndian>(1).unwrap();
+        assert_eq!(8, wtr.position());
+        wtr.seek(SeekFrom::Start(2)).unwrap();
+        wtr.write_u16::<LittleEndian>(0xFFFF).unwrap();
+        assert_eq!(4, wtr.position());
+        assert_eq!(
+            vec![1, 0, 0xFF, 0xFF, 0, 0, 0, 0],
+            wtr.into_inner().into_inner()
+        );
+    }
+}
@@ -0,0 +1,410 @@
+/*!
+Helpers for serializing CRC checksums in a protocol's byte order.
+
+CRC algorithms differ in two ways that matter once the digest has been
+computed: whether the final register value is bit-reflected, and in which
+byte order the checksum field is stored. This module does not implement any
+particular CRC. Instead, [`CrcParams`] describes those final steps, and the
+[`Digest`] trait lets any CRC implementation be plugged into the reader and
+writer wrappers.
+
+# Examples
+
+Write a CRC-16/XMODEM checksum (unreflected, stored big-endian) after a
+payload:
+
+```rust
+use byteorder::{crc::CrcParams, BigEndian};
+
+// CRC-16/XMODEM of b"123456789" is 0x31C3.
+let params = CrcParams::new(16);
+let mut frame = [0; 2];
+params.write_checksum::<BigEndian>(&mut frame, params.finalize(0x31C3));
+assert_eq!(frame, [0x31, 0xC3]);
+assert_eq!(0x31C3, params.read_checksum::<BigEndian>(&frame));
+```
+*/
+
+use crate::ByteOrder;
+
+/// A checksum implementation that can be fed bytes incrementally.
+///
+/// `finish` returns the raw CRC register, before any output reflection or
+/// final XOR described by [`CrcParams`] has been applied. Implementations
+/// that already apply those steps should be paired with the default
+/// `CrcParams` for their width.
+pub trait Digest {
+    /// Feeds `bytes` into the checksum.
+    fn update(&mut self, bytes: &[u8]);
+
+    /// Returns the checksum of all bytes fed so far.
+    fn finish(&self) -> u64;
+}
+
+impl<D: Digest + ?Sized> Digest for &mut D {
+    #[inline]
+    fn update(&mut self, bytes: &[u8]) {
+        (**self).update(bytes)
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        (**self).finish()
+    }
+}
+
+/// Describes how a CRC register becomes the checksum stored on the wire.
+///
+/// By default, the register is neither reflected nor XOR'd, so `finalize`
+/// only masks it to the CRC's width.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CrcParams {
+    width: u32,
+    reflect_out: bool,
+    xor_out: u64,
+}
+
+impl CrcParams {
+    /// Creates parameters for a CRC that is `width` bits wide.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `width < 1` or `width > 64`.
+    pub const fn new(width: u32) -> CrcParams {
+        assert!(width >= 1 && width <= 64, "CRC width must be in 1..=64");
+        CrcParams { width, reflect_out: false, xor_out: 0 }
+    }
+
+    /// Sets whether the register is bit-reflected before the final XOR.
+    pub const fn reflect_out(self, yes: bool) -> CrcParams {
+        CrcParams { reflect_out: yes, ..self }
+    }
+
+    /// Sets the value XOR'd into the register as the very last step.
+    pub const fn xor_out(self, xor_out: u64) -> CrcParams {
+        CrcParams { xor_out, ..self }
+    }
+
+    /// Returns the width of this CRC, in bits.
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the number of bytes used by the checksum field.
+    pub const fn nbytes(&self) -> usize {
+        ((self.width + 7) / 8) as usize
+    }
+
+    /// Applies output reflection and the final XOR to a raw CRC register.
+    ///
+    /// The result is always masked to the width of this CRC.
+    pub fn finalize(&self, register: u64) -> u64 {
+        let mut crc = register & self.mask();
+        if self.reflect_out {
+            crc = reflect(crc, self.width);
+        }
+        (crc ^ self.xor_out) & self.mask()
+    }
+
+    /// Writes `checksum` into the first `nbytes()` bytes of `buf` using the
+    /// byte order `E`.
+    ///
+    /// Any bits of `checksum` above the CRC's width are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < self.nbytes()`.
+    pub fn write_checksum<E: ByteOrder>(&self, buf: &mut [u8], checksum: u64) {
+        E::write_uint(buf, checksum & self.mask(), self.nbytes());
+    }
+
+    /// Reads a checksum from the first `nbytes()` bytes of `buf` using the
+    /// byte order `E`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < self.nbytes()`.
+    pub fn read_checksum<E: ByteOrder>(&self, buf: &[u8]) -> u64 {
+        E::read_uint(buf, self.nbytes()) & self.mask()
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+}
+
+/// Reverses the order of the low `width` bits of `value`.
+///
+/// Bits above `width` are discarded.
+///
+/// # Panics
+///
+/// Panics when `width < 1` or `width > 64`.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::crc::reflect;
+///
+/// assert_eq!(0b0011, reflect(0b1100, 4));
+/// assert_eq!(0x8000, reflect(0x0001, 16));
+/// ```
+pub fn reflect(value: u64, width: u32) -> u64 {
+    assert!((1..=64).contains(&width), "CRC width must be in 1..=64");
+    value.reverse_bits() >> (64 - width)
+}
+
+#[cfg(feature = "std")]
+pub use self::io::{ChecksumReader, ChecksumWriter};
+
+#[cfg(feature = "std")]
+mod io {
+    use std::io::{self, Read, Result, Write};
+
+    use super::{CrcParams, Digest};
+    use crate::ByteOrder;
+
+    /// A writer that feeds every byte written through a [`Digest`].
+    ///
+    /// Once the payload has been written, `write_checksum` appends the
+    /// checksum field in the requested byte order. The checksum field itself
+    /// is not fed to the digest.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Write;
+    /// use byteorder::crc::{ChecksumWriter, CrcParams, Digest};
+    /// use byteorder::LittleEndian;
+    ///
+    /// // A toy "CRC" that just sums bytes, for illustration.
+    /// struct Sum(u64);
+    /// impl Digest for Sum {
+    ///     fn update(&mut self, bytes: &[u8]) {
+    ///         self.0 += bytes.iter().map(|&b| b as u64).sum::<u64>();
+    ///     }
+    ///     fn finish(&self) -> u64 { self.0 }
+    /// }
+    ///
+    /// let mut wtr = ChecksumWriter::new(vec![], Sum(0), CrcParams::new(16));
+    /// wtr.write_all(&[1, 2, 3]).unwrap();
+    /// wtr.write_checksum::<LittleEndian>().unwrap();
+    /// assert_eq!(wtr.into_inner(), vec![1, 2, 3, 6, 0]);
+    /// ```
+    #[derive(Debug)]
+    pub struct ChecksumWriter<W, D> {
+        wtr: W,
+        digest: D,
+        params: CrcParams,
+    }
+
+    impl<W: Write, D: Digest> ChecksumWriter<W, D> {
+        /// Wraps `wtr` so that everything written is fed to `digest`.
+        pub fn new(
+            wtr: W,
+            digest: D,
+            params: CrcParams,
+        ) -> ChecksumWriter<W, D> {
+            ChecksumWriter { wtr, digest, params }
+        }
+
+        /// Returns the finalized checksum of everything written so far.
+        pub fn checksum(&self) -> u64 {
+            self.params.finalize(self.digest.finish())
+        }
+
+        /// Writes the finalized checksum to the underlying writer using the
+        /// byte order `E`.
+        pub fn write_checksum<E: ByteOrder>(&mut self) -> Result<()> {
+            let mut buf = [0; 8];
+            let nbytes = self.params.nbytes();
+            self.params.write_checksum::<E>(&mut buf, self.checksum());
+            self.wtr.write_all(&buf[..nbytes])
+        }
+
+        /// Returns a reference to the digest.
+        pub fn digest(&self) -> &D {
+            &self.digest
+        }
+
+        /// Returns a reference to the underlying writer.
+        pub fn get_ref(&self) -> &W {
+            &self.wtr
+        }
+
+        /// Unwraps this `ChecksumWriter`, returning the underlying writer.
+        pub fn into_inner(self) -> W {
+            self.wtr
+        }
+    }
+
+    impl<W: Write, D: Digest> Write for ChecksumWriter<W, D> {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            let n = self.wtr.write(buf)?;
+            self.digest.update(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            self.wtr.flush()
+        }
+    }
+
+    /// A reader that feeds every byte read through a [`Digest`].
+    ///
+    /// Once the payload has been read, `verify_checksum` reads the checksum
+    /// field in the requested byte order and compares it to the digest. The
+    /// checksum field itself is not fed to the digest.
+    #[derive(Debug)]
+    pub struct ChecksumReader<R, D> {
+        rdr: R,
+        digest: D,
+        params: CrcParams,
+    }
+
+    impl<R: Read, D: Digest> ChecksumReader<R, D> {
+        /// Wraps `rdr` so that everything read is fed to `digest`.
+        pub fn new(
+            rdr: R,
+            digest: D,
+            params: CrcParams,
+        ) -> ChecksumReader<R, D> {
+            ChecksumReader { rdr, digest, params }
+        }
+
+        /// Returns the finalized checksum of everything read so far.
+        pub fn checksum(&self) -> u64 {
+            self.params.finalize(self.digest.finish())
+        }
+
+        /// Reads a checksum field from the underlying reader using the byte
+        /// order `E`.
+        ///
+        /// # Errors
+        ///
+        /// Besides the errors returned by [`Read::read_exact`], this returns
+        /// an error of kind `InvalidData` if the stored checksum does not
+        /// match the checksum of the bytes read so far.
+        ///
+        /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+        pub fn verify_checksum<E: ByteOrder>(&mut self) -> Result<()> {
+            let mut buf = [0; 8];
+            let nbytes = self.params.nbytes();
+            self.rdr.read_exact(&mut buf[..nbytes])?;
+            let stored = self.params.read_checksum::<E>(&buf);
+            if stored != self.checksum() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "checksum mismatch",
+                ));
+            }
+            Ok(())
+        }
+
+        /// Returns a reference to the digest.
+        pub fn digest(&self) -> &D {
+            &self.digest
+        }
+
+        /// Returns a reference to the underlying reader.
+        pub fn get_ref(&self) -> &R {
+            &self.rdr
+        }
+
+        /// Unwraps this `ChecksumReader`, returning the underlying reader.
+        pub fn into_inner(self) -> R {
+            self.rdr
+        }
+    }
+
+    impl<R: Read, D: Digest> Read for ChecksumReader<R, D> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let n = self.rdr.read(buf)?;
+            self.digest.update(&buf[..n]);
+            Ok(n)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{reflect, CrcParams, Digest};
+    use crate::{BigEndian, LittleEndian};
+
+    /// A bit-at-a-time CRC-32 (IEEE) that reports its raw register, i.e.,
+    /// it uses the unreflected polynomial and leaves reflection and the
+    /// final XOR to `CrcParams`.
+    struct Crc32(u32);
+
+    impl Digest for Crc32 {
+        fn update(&mut self, bytes: &[u8]) {
+            for &b in bytes {
+                self.0 ^= (b.reverse_bits() as u32) << 24;
+                for _ in 0..8 {
+                    self.0 = if self.0 & 0x8000_0000 != 0 {
+                        (self.0 << 1) ^ 0x04C1_1DB7
+                    } else {
+                        self.0 << 1
+                    };
+                }
+            }
+        }
+
+        fn finish(&self) -> u64 {
+            self.0 as u64
+        }
+    }
+
+    const CRC32: CrcParams =
+        CrcParams::new(32).reflect_out(true).xor_out(0xFFFF_FFFF);
+
+    #[test]
+    fn crc32_check_value() {
+        let mut crc = Crc32(0xFFFF_FFFF);
+        crc.update(b"123456789");
+        assert_eq!(0xCBF4_3926, CRC32.finalize(crc.finish()));
+    }
+
+    #[test]
+    fn checksum_field_byte_order() {
+        let mut buf = [0; 4];
+        CRC32.write_checksum::<LittleEndian>(&mut buf, 0xCBF4_3926);
+        assert_eq!(buf, [0x26, 0x39, 0xF4, 0xCB]);
+        assert_eq!(0xCBF4_3926, CRC32.read_checksum::<LittleEndian>(&buf));
+        CRC32.write_checksum::<BigEndian>(&mut buf, 0xCBF4_3926);
+        assert_eq!(buf, [0xCB, 0xF4, 0x39, 0x26]);
+    }
+
+    #[test]
+    fn odd_widths() {
+        let crc5 = CrcParams::new(5);
+        assert_eq!(1, crc5.nbytes());
+        assert_eq!(0b10000, reflect(0b00001, 5));
+        assert_eq!(0x1F, crc5.finalize(u64::MAX));
+        assert_eq!(8, CrcParams::new(64).nbytes());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn reader_writer_roundtrip() {
+        use super::{ChecksumReader, ChecksumWriter};
+        use std::io::{Read, Write};
+
+        let mut wtr = ChecksumWriter::new(vec![], Crc32(0xFFFF_FFFF), CRC32);
+        wtr.write_all(b"123456789").unwrap();
+        wtr.write_checksum::<LittleEndian>().unwrap();
+        let frame = wtr.into_inner();
+        assert_eq!(&frame[9..], &[0x26, 0x39, 0xF4, 0xCB]);
+
+        let mut rdr =
+            ChecksumReader::new(&frame[..], Crc32(0xFFFF_FFFF), CRC32);
+        let mut payload = [0; 9];
+        rdr.read_exact(&mut payload).unwrap();
+        rdr.verify_checksum::<LittleEndian>().unwrap();
+
+        let mut rdr =
+            ChecksumReader::new(&frame[..], Crc32(0xFFFF_FFFF), CRC32);
+        rdr.read_exact(&mut payload).unwrap();
+        assert!(rdr.verify_checksum::<BigEndian>().is_err());
+    }
+}
@@ -0,0 +1,177 @@
+// Detection of the byte order of data from the markers that common formats
+// put at their start. These are inherent methods on `Endianness`; they live
+// in their own module only to keep lib.rs manageable.
+
+use crate::{bom, Endianness, Error, ErrorKind};
+
+impl Endianness {
+    /// Detects the byte order of UTF-16 text from its byte order mark.
+    ///
+    /// On success, this returns the byte order along with the number of bytes
+    /// the mark occupies, which is always `2`, so that the caller can skip
+    /// it.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+    /// is shorter than 2 bytes, and of kind [`ErrorKind::InvalidMagic`] when
+    /// it doesn't start with a byte order mark.
+    ///
+    /// To accept text that may or may not start with a byte order mark,
+    /// use [`bom::strip_bom`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::Endianness;
+    ///
+    /// let text = b"\xFF\xFEh\x00i\x00";
+    /// let (endianness, nread) = Endianness::from_bom_u16(text).unwrap();
+    /// assert_eq!(Endianness::Little, endianness);
+    /// assert_eq!(u16::from(b'h'), endianness.read_u16(&text[nread..]));
+    /// ```
+    pub fn from_bom_u16(buf: &[u8]) -> Result<(Endianness, usize), Error> {
+        Error::check_len(2, buf.len())?;
+        if buf.starts_with(&bom::UTF16_BE) {
+            Ok((Endianness::Big, 2))
+        } else if buf.starts_with(&bom::UTF16_LE) {
+            Ok((Endianness::Little, 2))
+        } else {
+            Err(Error::new(ErrorKind::InvalidMagic))
+        }
+    }
+
+    /// Detects the byte order of UTF-32 text from its byte order mark.
+    ///
+    /// On success, this returns the byte order along with the number of bytes
+    /// the mark occupies, which is always `4`.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+    /// is shorter than 4 bytes, and of kind [`ErrorKind::InvalidMagic`] when
+    /// it doesn't start with a byte order mark.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::Endianness;
+    ///
+    /// let bom = b"\x00\x00\xFE\xFF";
+    /// assert_eq!(Ok((Endianness::Big, 4)), Endianness::from_bom_u32(bom));
+    /// ```
+    pub fn from_bom_u32(buf: &[u8]) -> Result<(Endianness, usize), Error> {
+        Error::check_len(4, buf.len())?;
+        match buf[..4] {
+            [0x00, 0x00, 0xFE, 0xFF] => Ok((Endianness::Big, 4)),
+            [0xFF, 0xFE, 0x00, 0x00] => Ok((Endianness::Little, 4)),
+            _ => Err(Error::new(ErrorKind::InvalidMagic)),
+        }
+    }
+
+    /// Detects the byte order of a TIFF file from the first two bytes of its
+    /// header, `II` for little-endian or `MM` for big-endian.
+    ///
+    /// On success, this returns the byte order along with the number of bytes
+    /// read, which is always `2`. The version number that follows, `42` for
+    /// TIFF or `43` for BigTIFF, is not checked, since it must itself be read
+    /// in the detected byte order.
+    ///
+    /// The same marker is used by other formats derived from TIFF, like the
+    /// EXIF data in JPEG files and many camera raw formats.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+    /// is shorter than 2 bytes, and of kind [`ErrorKind::InvalidMagic`] when
+    /// it doesn't start with `II` or `MM`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::Endianness;
+    ///
+    /// let header = b"MM\x00\x2a\x00\x00\x00\x08";
+    /// let (endianness, nread) = Endianness::from_tiff_magic(header).unwrap();
+    /// assert_eq!(Endianness::Big, endianness);
+    /// assert_eq!(42, endianness.read_u16(&header[nread..]));
+    /// ```
+    pub fn from_tiff_magic(buf: &[u8]) -> Result<(Endianness, usize), Error> {
+        Error::check_len(2, buf.len())?;
+        match &buf[..2] {
+            b"MM" => Ok((Endianness::Big, 2)),
+            b"II" => Ok((Endianness::Little, 2)),
+            _ => Err(Error::new(ErrorKind::InvalidMagic)),
+        }
+    }
+
+    /// Returns the byte order given by the `EI_DATA` byte of an ELF file's
+    /// identification, which is at offset 5 of the file.
+    ///
+    /// The value `1` (`ELFDATA2LSB`) means little-endian and `2`
+    /// (`ELFDATA2MSB`) means big-endian.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error of kind [`ErrorKind::InvalidMagic`] for any other
+    /// value, including `0` (`ELFDATANONE`).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::Endianness;
+    ///
+    /// let ident = b"\x7fELF\x02\x01\x01\x00";
+    /// assert_eq!(Ok(Endianness::Little), Endianness::from_elf_ei_data(ident[5]));
+    /// ```
+    pub fn from_elf_ei_data(ei_data: u8) -> Result<Endianness, Error> {
+        match ei_data {
+            1 => Ok(Endianness::Little),
+            2 => Ok(Endianness::Big),
+            _ => Err(Error::new(ErrorKind::InvalidMagic)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Endianness, Error, ErrorKind};
+
+    fn invalid() -> Error {
+        Error::new(ErrorKind::InvalidMagic)
+    }
+
+    fn too_small(needed: usize, available: usize) -> Error {
+        Error::new(ErrorKind::BufferTooSmall { needed, available })
+    }
+
+    #[test]
+    fn boms() {
+        let from16 = Endianness::from_bom_u16;
+        assert_eq!(Ok((Endianness::Big, 2)), from16(b"\xFE\xFF\x00"));
+        assert_eq!(Err(invalid()), from16(b"\xFE\xFE"));
+        assert_eq!(Err(too_small(2, 1)), from16(b"\xFE"));
+
+        let from32 = Endianness::from_bom_u32;
+        assert_eq!(Ok((Endianness::Little, 4)), from32(b"\xFF\xFE\x00\x00"));
+        // A UTF-16 mark is not a UTF-32 mark.
+        assert_eq!(Err(invalid()), from32(b"\xFF\xFEa\x00"));
+        assert_eq!(Err(too_small(4, 2)), from32(b"\xFF\xFE"));
+    }
+
+    #[test]
+    fn tiff() {
+        let detect = Endianness::from_tiff_magic;
+        assert_eq!(Ok((Endianness::Little, 2)), detect(b"II*\x00"));
+        assert_eq!(Ok((Endianness::Big, 2)), detect(b"MM"));
+        assert_eq!(Err(invalid()), detect(b"IM"));
+        assert_eq!(Err(too_small(2, 0)), detect(b""));
+    }
+
+    #[test]
+    fn elf() {
+        assert_eq!(Ok(Endianness::Big), Endianness::from_elf_ei_data(2));
+        assert_eq!(Err(invalid()), Endianness::from_elf_ei_data(0));
+        assert_eq!(Err(invalid()), Endianness::from_elf_ei_data(3));
+    }
+}
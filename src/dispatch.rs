@@ -0,0 +1,156 @@
+/*!
+Tables of function pointers for a byte order chosen at runtime.
+
+When the byte order of some data is only known at runtime, the obvious
+approach is to `match` on an [`Endianness`] every time a number is decoded.
+In a tight loop, that branch can cost more than the decoding itself. A
+[`Dispatch`] table holds function pointers to the monomorphized `ByteOrder`
+methods for one byte order, so the choice is made once and every call after
+that is a single indirect call.
+
+The two tables are `static`s, so looking one up is free and they can be
+shared across threads.
+
+# Examples
+
+```rust
+use byteorder::{dispatch::Dispatch, Endianness};
+
+fn sum(data: &[u8], order: Endianness) -> u64 {
+    let read_u32 = Dispatch::of(order).read_u32;
+    data.chunks_exact(4).map(|chunk| u64::from(read_u32(chunk))).sum()
+}
+
+let data = [1, 0, 0, 0, 2, 0, 0, 0];
+assert_eq!(3, sum(&data, Endianness::Little));
+assert_eq!(0x0300_0000, sum(&data, Endianness::Big));
+```
+*/
+
+use crate::{BigEndian, ByteOrder, Endianness, LittleEndian};
+
+macro_rules! dispatch {
+    ($(
+        $ty:ident:
+            $read:ident, $write:ident, $read_into:ident, $write_into:ident;
+    )*) => {
+        /// A table of function pointers to the `ByteOrder` methods of a
+        /// single byte order.
+        ///
+        /// Each field has the same name and behavior, including panics, as
+        /// the corresponding [`ByteOrder`] method. Tables can't be built
+        /// directly; use [`Dispatch::of`] or one of the statics in this
+        /// module.
+        #[derive(Clone, Copy, Debug)]
+        #[non_exhaustive]
+        pub struct Dispatch {
+            $(
+                #[doc = concat!("See [`ByteOrder::", stringify!($read), "`].")]
+                pub $read: fn(&[u8]) -> $ty,
+                #[doc = concat!("See [`ByteOrder::", stringify!($write), "`].")]
+                pub $write: fn(&mut [u8], $ty),
+                #[doc = concat!(
+                    "See [`ByteOrder::", stringify!($read_into), "`].",
+                )]
+                pub $read_into: fn(&[u8], &mut [$ty]),
+                #[doc = concat!(
+                    "See [`ByteOrder::", stringify!($write_into), "`].",
+                )]
+                pub $write_into: fn(&[$ty], &mut [u8]),
+            )*
+        }
+
+        /// The dispatch table for big-endian byte order.
+        pub static BIG_ENDIAN: Dispatch = Dispatch {
+            $(
+                $read: BigEndian::$read,
+                $write: BigEndian::$write,
+                $read_into: BigEndian::$read_into,
+                $write_into: BigEndian::$write_into,
+            )*
+        };
+
+        /// The dispatch table for little-endian byte order.
+        pub static LITTLE_ENDIAN: Dispatch = Dispatch {
+            $(
+                $read: LittleEndian::$read,
+                $write: LittleEndian::$write,
+                $read_into: LittleEndian::$read_into,
+                $write_into: LittleEndian::$write_into,
+            )*
+        };
+    };
+}
+
+dispatch! {
+    u16: read_u16, write_u16, read_u16_into, write_u16_into;
+    i16: read_i16, write_i16, read_i16_into, write_i16_into;
+    u32: read_u32, write_u32, read_u32_into, write_u32_into;
+    i32: read_i32, write_i32, read_i32_into, write_i32_into;
+    u64: read_u64, write_u64, read_u64_into, write_u64_into;
+    i64: read_i64, write_i64, read_i64_into, write_i64_into;
+    u128: read_u128, write_u128, read_u128_into, write_u128_into;
+    i128: read_i128, write_i128, read_i128_into, write_i128_into;
+    f32: read_f32, write_f32, read_f32_into, write_f32_into;
+    f64: read_f64, write_f64, read_f64_into, write_f64_into;
+}
+
+impl Dispatch {
+    /// Returns the dispatch table for the given byte order.
+    #[inline]
+    pub fn of(endianness: Endianness) -> &'static Dispatch {
+        match endianness {
+            Endianness::Big => &BIG_ENDIAN,
+            Endianness::Little => &LITTLE_ENDIAN,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dispatch;
+    use crate::Endianness;
+
+    #[test]
+    fn scalars() {
+        let big = Dispatch::of(Endianness::Big);
+        let little = Dispatch::of(Endianness::Little);
+
+        let mut buf = [0; 16];
+        (big.write_u32)(&mut buf, 0x0102_0304);
+        assert_eq!([1, 2, 3, 4], buf[..4]);
+        assert_eq!(0x0403_0201, (little.read_u32)(&buf));
+
+        (little.write_i128)(&mut buf, -2);
+        assert_eq!(-2, (little.read_i128)(&buf));
+        assert_eq!(0xFE, buf[0]);
+
+        (big.write_f64)(&mut buf, 1.5);
+        assert_eq!(1.5, (big.read_f64)(&buf));
+    }
+
+    #[test]
+    fn slices() {
+        let table = Dispatch::of(Endianness::Big);
+        let numbers = [1u16, 0x0203, 0xFFFF];
+        let mut bytes = [0; 6];
+        (table.write_u16_into)(&numbers, &mut bytes);
+        assert_eq!([0, 1, 2, 3, 0xFF, 0xFF], bytes);
+
+        let mut got = [0i16; 3];
+        (table.read_i16_into)(&bytes, &mut got);
+        assert_eq!([1, 0x0203, -1], got);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn is_shared() {
+        let a: &'static Dispatch = Dispatch::of(Endianness::Little);
+        let b = std::thread::spawn(|| {
+            Dispatch::of(Endianness::Little) as *const Dispatch as usize
+        })
+        .join()
+        .unwrap();
+        assert_eq!(a as *const Dispatch as usize, b);
+    }
+}
@@ -0,0 +1,1222 @@
+/*!
+Reading and writing numbers with the blocking readers and writers of
+`embedded-io`.
+
+Firmware and other `no_std` code usually can't use `std::io`, and uses the
+`Read` and `Write` traits of the `embedded-io` crate instead, which are
+implemented by the serial ports, sockets and flash drivers of most HALs. The
+[`ReadBytesExt`] and [`WriteBytesExt`] traits in this module have the same
+methods as the traits of the same names at the crate root, for those readers
+and writers, except for the ones for strings. The reads that return a `Vec`,
+like [`read_frame`](ReadBytesExt::read_frame), require the `alloc` feature:
+
+```rust
+use byteorder::{embedded_io::ReadBytesExt, BigEndian, LittleEndian};
+
+let mut rdr: &[u8] = &[0x12, 0x34, 0x00, 0x00, 0x80, 0x3F];
+assert_eq!(0x1234, rdr.read_u16::<BigEndian>().unwrap());
+assert_eq!(1.0, rdr.read_f32::<LittleEndian>().unwrap());
+```
+
+Reads return `embedded-io`'s `ReadExactError`, which tells apart the end of
+the input from an error of the reader, and writes return the writer's own
+error type. Reads that check the bytes they read, like
+[`read_char`](ReadBytesExt::read_char) or the varint reads, return a
+[`ReadError`] instead, which can also hold a [`crate::Error`] for bytes that
+aren't a valid value, and [`write_wire`](WriteBytesExt::write_wire) returns a
+[`WriteError`] for the same reason.
+
+Bulk reads and writes, and values of [`FromWire`] and [`ToWire`], go through a
+256 byte buffer on the stack, since there is no allocator to fall back on.
+
+# Sharing code with `std`
+
+Parsers written against these traits also run on a host with `std`, by
+wrapping a `std::io` reader or writer, like a `TcpStream`, in the `FromStd`
+adapter of the `embedded-io-adapters` crate. That way, the same code can
+parse a protocol from a UART on a microcontroller and from a socket in its
+tests or host tools:
+
+```rust
+use byteorder::{
+    embedded_io::{ReadBytesExt, ReadError},
+    LittleEndian,
+};
+use embedded_io::Read;
+
+/// Reads a length-prefixed list of readings, from any reader.
+fn readings<R: Read>(
+    rdr: &mut R,
+    out: &mut [i16],
+) -> Result<usize, ReadError<R::Error>> {
+    let len = rdr.read_uleb128()? as usize;
+    let out = &mut out[..len];
+    rdr.read_i16_into::<LittleEndian>(out)?;
+    Ok(len)
+}
+
+let mut out = [0; 8];
+let mut rdr: &[u8] = &[2, 0xFF, 0xFF, 0x02, 0x00];
+assert_eq!(Ok(2), readings(&mut rdr, &mut out));
+assert_eq!([-1, 2], out[..2]);
+```
+
+This module requires the `embedded-io` feature, which does not require `std`.
+For the async traits of `embedded-io-async`, see the
+[`embedded_io_async`](https://docs.rs/byteorder/1/byteorder/embedded_io_async/index.html)
+module.
+*/
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{convert::Infallible, fmt};
+
+use ::embedded_io::{ErrorKind, Read, ReadExactError, Write};
+
+use crate::{
+    varint,
+    wire::{FromWire, ToWire},
+    ByteOrder,
+};
+
+/// The size of the stack buffers that bulk reads and writes go through, and
+/// that values of [`FromWire`] and [`ToWire`] are encoded in. It is small,
+/// since embedded targets often have only a few kilobytes of stack.
+const STACK_SIZE: usize = 256;
+
+/// The most memory that is allocated up front for a vector whose length was
+/// read from the input, which can't be trusted.
+#[cfg(feature = "alloc")]
+const READ_VEC_MAX_CAPACITY: usize = 4 * 1024;
+
+/// An error returned by the reads of [`ReadBytesExt`] that check the bytes
+/// they read.
+///
+/// This is `embedded-io`'s `ReadExactError`, with a variant for bytes that
+/// aren't a valid value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ReadError<E> {
+    /// The reader reached the end of its input in the middle of a value.
+    UnexpectedEof,
+    /// The reader failed.
+    Other(E),
+    /// The bytes read aren't a valid value.
+    Invalid(crate::Error),
+}
+
+impl<E> From<ReadExactError<E>> for ReadError<E> {
+    fn from(err: ReadExactError<E>) -> ReadError<E> {
+        match err {
+            ReadExactError::UnexpectedEof => ReadError::UnexpectedEof,
+            ReadExactError::Other(err) => ReadError::Other(err),
+        }
+    }
+}
+
+impl<E> From<crate::Error> for ReadError<E> {
+    fn from(err: crate::Error) -> ReadError<E> {
+        ReadError::Invalid(err)
+    }
+}
+
+/// Readers that can't fail otherwise, like `&[u8]`, only fail when their
+/// input ends, so their errors convert into this crate's [`Error`].
+///
+/// [`Error`]: crate::Error
+impl From<ReadError<Infallible>> for crate::Error {
+    fn from(err: ReadError<Infallible>) -> crate::Error {
+        match err {
+            ReadError::UnexpectedEof => {
+                crate::Error::new(crate::ErrorKind::UnexpectedEof)
+            }
+            ReadError::Other(never) => match never {},
+            ReadError::Invalid(err) => err,
+        }
+    }
+}
+
+impl From<ReadExactError<Infallible>> for crate::Error {
+    fn from(err: ReadExactError<Infallible>) -> crate::Error {
+        ReadError::from(err).into()
+    }
+}
+
+impl<E: fmt::Debug> fmt::Display for ReadError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ReadError::UnexpectedEof => write!(f, "unexpected end of input"),
+            ReadError::Other(ref err) => write!(f, "{:?}", err),
+            ReadError::Invalid(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E: ::embedded_io::Error> ::embedded_io::Error for ReadError<E> {
+    fn kind(&self) -> ErrorKind {
+        match *self {
+            ReadError::UnexpectedEof => ErrorKind::Other,
+            ReadError::Other(ref err) => err.kind(),
+            ReadError::Invalid(_) => ErrorKind::InvalidData,
+        }
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl<E: fmt::Debug> core::error::Error for ReadError<E> {}
+
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+impl<E: fmt::Debug> std::error::Error for ReadError<E> {}
+
+/// An error returned by the writes of [`WriteBytesExt`] that encode values
+/// which may not be representable.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WriteError<E> {
+    /// The writer failed.
+    Other(E),
+    /// The value can't be encoded.
+    Invalid(crate::Error),
+}
+
+impl<E> From<crate::Error> for WriteError<E> {
+    fn from(err: crate::Error) -> WriteError<E> {
+        WriteError::Invalid(err)
+    }
+}
+
+impl<E: fmt::Debug> fmt::Display for WriteError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            WriteError::Other(ref err) => write!(f, "{:?}", err),
+            WriteError::Invalid(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E: ::embedded_io::Error> ::embedded_io::Error for WriteError<E> {
+    fn kind(&self) -> ErrorKind {
+        match *self {
+            WriteError::Other(ref err) => err.kind(),
+            WriteError::Invalid(_) => ErrorKind::InvalidData,
+        }
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl<E: fmt::Debug> core::error::Error for WriteError<E> {}
+
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+impl<E: fmt::Debug> std::error::Error for WriteError<E> {}
+
+macro_rules! read_methods {
+    ($($name:ident($ty:ty, $size:expr, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Reads ", $desc, " from the underlying reader.\n\n",
+            "# Errors\n\n",
+            "This method returns the same errors as `read_exact`.",
+        )]
+        #[inline]
+        fn $name<T: ByteOrder>(
+            &mut self,
+        ) -> Result<$ty, ReadExactError<Self::Error>> {
+            let mut buf = [0; $size];
+            self.read_exact(&mut buf)?;
+            Ok(T::$name(&buf))
+        }
+    )*};
+}
+
+macro_rules! read_into_methods {
+    ($($name:ident($ty:ty, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Reads a sequence of ", $desc, " from the underlying reader.\n\n",
+            "The given buffer is either filled completely or an error is ",
+            "returned. If an error is returned, the contents of `dst` are ",
+            "unspecified.\n\n",
+            "# Errors\n\n",
+            "This method returns the same errors as `read_exact`.",
+        )]
+        #[inline]
+        fn $name<T: ByteOrder>(
+            &mut self,
+            dst: &mut [$ty],
+        ) -> Result<(), ReadExactError<Self::Error>> {
+            let size = core::mem::size_of::<$ty>();
+            let mut buf = [0; STACK_SIZE];
+            for chunk in dst.chunks_mut(STACK_SIZE / size) {
+                let buf = &mut buf[..chunk.len() * size];
+                self.read_exact(buf)?;
+                T::$name(buf, chunk);
+            }
+            Ok(())
+        }
+    )*};
+}
+
+macro_rules! write_from_methods {
+    ($($name:ident($ty:ty, $into:ident, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Writes a sequence of ", $desc, " to the underlying writer.\n\n",
+            "# Errors\n\n",
+            "This method returns the same errors as `write_all`.",
+        )]
+        #[inline]
+        fn $name<T: ByteOrder>(
+            &mut self,
+            src: &[$ty],
+        ) -> Result<(), Self::Error> {
+            let size = core::mem::size_of::<$ty>();
+            let mut buf = [0; STACK_SIZE];
+            for chunk in src.chunks(STACK_SIZE / size) {
+                let buf = &mut buf[..chunk.len() * size];
+                T::$into(chunk, buf);
+                self.write_all(buf)?;
+            }
+            Ok(())
+        }
+    )*};
+}
+
+macro_rules! write_methods {
+    ($($name:ident($ty:ty, $size:expr, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Writes ", $desc, " to the underlying writer.\n\n",
+            "# Errors\n\n",
+            "This method returns the same errors as `write_all`.",
+        )]
+        #[inline]
+        fn $name<T: ByteOrder>(&mut self, n: $ty) -> Result<(), Self::Error> {
+            let mut buf = [0; $size];
+            T::$name(&mut buf, n);
+            self.write_all(&buf)
+        }
+    )*};
+}
+
+/// Extends `embedded-io`'s `Read` with methods for reading numbers.
+///
+/// This is implemented for every type that implements `Read`. See the
+/// [module documentation](self) for details.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{embedded_io::ReadBytesExt, LittleEndian};
+/// use embedded_io::ReadExactError;
+///
+/// let mut rdr: &[u8] = &[0xFF, 0x01, 0x02, 0x03];
+/// assert_eq!(-1, rdr.read_i8().unwrap());
+/// assert_eq!(0x030201, rdr.read_u24::<LittleEndian>().unwrap());
+/// assert!(matches!(rdr.read_u8(), Err(ReadExactError::UnexpectedEof)));
+/// ```
+pub trait ReadBytesExt: Read {
+    /// Reads an unsigned 8 bit integer from the underlying reader.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`.
+    #[inline]
+    fn read_u8(&mut self) -> Result<u8, ReadExactError<Self::Error>> {
+        let mut buf = [0; 1];
+        self.read_exact(&mut buf)?;
+        Ok(buf[0])
+    }
+
+    /// Reads a signed 8 bit integer from the underlying reader.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`.
+    #[inline]
+    fn read_i8(&mut self) -> Result<i8, ReadExactError<Self::Error>> {
+        let mut buf = [0; 1];
+        self.read_exact(&mut buf)?;
+        Ok(buf[0] as i8)
+    }
+
+    read_methods! {
+        read_u16(u16, 2, "an unsigned 16 bit integer");
+        read_i16(i16, 2, "a signed 16 bit integer");
+        read_u24(u32, 3, "an unsigned 24 bit integer");
+        read_i24(i32, 3, "a signed 24 bit integer");
+        read_u32(u32, 4, "an unsigned 32 bit integer");
+        read_i32(i32, 4, "a signed 32 bit integer");
+        read_u48(u64, 6, "an unsigned 48 bit integer");
+        read_i48(i64, 6, "a signed 48 bit integer");
+        read_u64(u64, 8, "an unsigned 64 bit integer");
+        read_i64(i64, 8, "a signed 64 bit integer");
+        read_u128(u128, 16, "an unsigned 128 bit integer");
+        read_i128(i128, 16, "a signed 128 bit integer");
+        read_f32(f32, 4, "an IEEE754 single-precision float");
+        read_f64(f64, 8, "an IEEE754 double-precision float");
+    }
+
+    /// Reads an unsigned n-bytes integer from the underlying reader.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes < 1` or `nbytes > 8`.
+    #[inline]
+    fn read_uint<T: ByteOrder>(
+        &mut self,
+        nbytes: usize,
+    ) -> Result<u64, ReadExactError<Self::Error>> {
+        let mut buf = [0; 8];
+        self.read_exact(&mut buf[..nbytes])?;
+        Ok(T::read_uint(&buf[..nbytes], nbytes))
+    }
+
+    /// Reads a signed n-bytes integer from the underlying reader.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes < 1` or `nbytes > 8`.
+    #[inline]
+    fn read_int<T: ByteOrder>(
+        &mut self,
+        nbytes: usize,
+    ) -> Result<i64, ReadExactError<Self::Error>> {
+        let mut buf = [0; 8];
+        self.read_exact(&mut buf[..nbytes])?;
+        Ok(T::read_int(&buf[..nbytes], nbytes))
+    }
+
+    /// Reads an unsigned n-bytes integer from the underlying reader.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes < 1` or `nbytes > 16`.
+    #[inline]
+    fn read_uint128<T: ByteOrder>(
+        &mut self,
+        nbytes: usize,
+    ) -> Result<u128, ReadExactError<Self::Error>> {
+        let mut buf = [0; 16];
+        self.read_exact(&mut buf[..nbytes])?;
+        Ok(T::read_uint128(&buf[..nbytes], nbytes))
+    }
+
+    /// Reads a signed n-bytes integer from the underlying reader.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes < 1` or `nbytes > 16`.
+    #[inline]
+    fn read_int128<T: ByteOrder>(
+        &mut self,
+        nbytes: usize,
+    ) -> Result<i128, ReadExactError<Self::Error>> {
+        let mut buf = [0; 16];
+        self.read_exact(&mut buf[..nbytes])?;
+        Ok(T::read_int128(&buf[..nbytes], nbytes))
+    }
+
+    /// Reads a boolean that must be encoded as `0` or `1`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`, and an error of
+    /// kind [`OutOfRange`](crate::ErrorKind::OutOfRange) for any other
+    /// byte.
+    #[inline]
+    fn read_bool_strict(&mut self) -> Result<bool, ReadError<Self::Error>> {
+        match ReadBytesExt::read_u8(self)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(ReadError::Invalid(crate::ErrorKind::OutOfRange.into())),
+        }
+    }
+
+    /// Reads a boolean, treating every byte other than `0` as `true`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`.
+    #[inline]
+    fn read_bool_lenient(
+        &mut self,
+    ) -> Result<bool, ReadExactError<Self::Error>> {
+        ReadBytesExt::read_u8(self).map(|n| n != 0)
+    }
+
+    /// Reads an IEEE754 half-precision float, widened to an `f32`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`.
+    #[inline]
+    fn read_f16<T: ByteOrder>(
+        &mut self,
+    ) -> Result<f32, ReadExactError<Self::Error>> {
+        let mut buf = [0; 2];
+        self.read_exact(&mut buf)?;
+        Ok(T::read_f16(&buf))
+    }
+
+    /// Reads a Unicode scalar value encoded as a 32 bit integer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`, and an error of
+    /// kind [`OutOfRange`](crate::ErrorKind::OutOfRange) when the integer is
+    /// a surrogate or larger than `0x10FFFF`.
+    #[inline]
+    fn read_char<T: ByteOrder>(
+        &mut self,
+    ) -> Result<char, ReadError<Self::Error>> {
+        let n = ReadBytesExt::read_u32::<T>(self)?;
+        char::from_u32(n).ok_or_else(|| {
+            ReadError::Invalid(crate::ErrorKind::OutOfRange.into())
+        })
+    }
+
+    /// Reads a sequence of signed 8 bit integers from the underlying reader.
+    ///
+    /// The given buffer is either filled completely or an error is returned.
+    /// If an error is returned, the contents of `dst` are unspecified.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`.
+    #[inline]
+    fn read_i8_into(
+        &mut self,
+        dst: &mut [i8],
+    ) -> Result<(), ReadExactError<Self::Error>> {
+        let mut buf = [0; STACK_SIZE];
+        for chunk in dst.chunks_mut(STACK_SIZE) {
+            let buf = &mut buf[..chunk.len()];
+            self.read_exact(buf)?;
+            for (dst, &b) in chunk.iter_mut().zip(buf.iter()) {
+                *dst = b as i8;
+            }
+        }
+        Ok(())
+    }
+
+    read_into_methods! {
+        read_u16_into(u16, "unsigned 16 bit integers");
+        read_i16_into(i16, "signed 16 bit integers");
+        read_u32_into(u32, "unsigned 32 bit integers");
+        read_i32_into(i32, "signed 32 bit integers");
+        read_u64_into(u64, "unsigned 64 bit integers");
+        read_i64_into(i64, "signed 64 bit integers");
+        read_u128_into(u128, "unsigned 128 bit integers");
+        read_i128_into(i128, "signed 128 bit integers");
+        read_f32_into(f32, "IEEE754 single-precision floats");
+        read_f64_into(f64, "IEEE754 double-precision floats");
+    }
+
+    /// Reads `N` bytes into an array.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`.
+    #[inline]
+    fn read_array<const N: usize>(
+        &mut self,
+    ) -> Result<[u8; N], ReadExactError<Self::Error>> {
+        let mut buf = [0; N];
+        self.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Reads a value of any type implementing [`FromWire`].
+    ///
+    /// Exactly `T::WIRE_SIZE` bytes are read.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`, and any error
+    /// returned by decoding the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `T::WIRE_SIZE` is more than 256 bytes, since the value is
+    /// decoded from a buffer on the stack.
+    #[inline]
+    fn read_wire<T: FromWire, E: ByteOrder>(
+        &mut self,
+    ) -> Result<T, ReadError<Self::Error>> {
+        let mut buf = [0; STACK_SIZE];
+        let buf = &mut buf[..T::WIRE_SIZE];
+        self.read_exact(buf)?;
+        Ok(T::from_wire::<E>(buf)?)
+    }
+
+    /// Reads an unsigned LEB128 integer.
+    ///
+    /// Bytes are read one at a time, so that nothing past the end of the
+    /// integer is consumed.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`, and an error of
+    /// kind [`OutOfRange`](crate::ErrorKind::OutOfRange) when the encoding
+    /// is too long or its value doesn't fit in a `u64`.
+    #[inline]
+    fn read_uleb128(&mut self) -> Result<u64, ReadError<Self::Error>> {
+        let mut buf = [0; varint::MAX_ULEB128_LEN];
+        for i in 0..buf.len() {
+            self.read_exact(&mut buf[i..i + 1])?;
+            if buf[i] & 0x80 == 0 {
+                return Ok(varint::decode_uleb128(&buf[..i + 1])?.0);
+            }
+        }
+        // Too long, which decoding reports.
+        Ok(varint::decode_uleb128(&buf)?.0)
+    }
+
+    /// Reads an unsigned variable length integer, in the encoding used by
+    /// Protocol Buffers.
+    ///
+    /// This is the same as [`read_uleb128`](ReadBytesExt::read_uleb128).
+    #[inline]
+    fn read_varint_u64(&mut self) -> Result<u64, ReadError<Self::Error>> {
+        self.read_uleb128()
+    }
+
+    /// Reads a signed variable length integer, encoded with ZigZag and then
+    /// as unsigned LEB128.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`read_uleb128`](ReadBytesExt::read_uleb128).
+    #[inline]
+    fn read_varint_zigzag(&mut self) -> Result<i64, ReadError<Self::Error>> {
+        self.read_uleb128().map(varint::zigzag_decode_u64)
+    }
+
+    /// Reads an unsigned LEB128 integer of up to 128 bits.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`, and an error of
+    /// kind [`OutOfRange`](crate::ErrorKind::OutOfRange) when the encoding
+    /// is too long or its value doesn't fit in a `u128`.
+    #[inline]
+    fn read_uleb128_u128(&mut self) -> Result<u128, ReadError<Self::Error>> {
+        let mut buf = [0; varint::MAX_ULEB128_U128_LEN];
+        for i in 0..buf.len() {
+            self.read_exact(&mut buf[i..i + 1])?;
+            if buf[i] & 0x80 == 0 {
+                return Ok(varint::decode_uleb128_u128(&buf[..i + 1])?.0);
+            }
+        }
+        // Too long, which decoding reports.
+        Ok(varint::decode_uleb128_u128(&buf)?.0)
+    }
+
+    /// Reads a signed variable length integer of up to 128 bits, encoded
+    /// with ZigZag and then as unsigned LEB128.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`read_uleb128_u128`](ReadBytesExt::read_uleb128_u128).
+    #[inline]
+    fn read_varint_zigzag_i128(
+        &mut self,
+    ) -> Result<i128, ReadError<Self::Error>> {
+        self.read_uleb128_u128().map(varint::zigzag_decode_u128)
+    }
+
+    /// Reads exactly `n` bytes into a newly allocated vector.
+    ///
+    /// Like the method of the same name of the crate's `ReadBytesExt`, this
+    /// never allocates much more memory than the reader actually provides, so
+    /// a corrupt length read from the input fails instead of exhausting the
+    /// heap.
+    ///
+    /// This method requires the `alloc` feature.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn read_exact_vec(
+        &mut self,
+        n: usize,
+    ) -> Result<Vec<u8>, ReadExactError<Self::Error>> {
+        let first = n.min(READ_VEC_MAX_CAPACITY);
+        let mut buf = Vec::with_capacity(first);
+        while buf.len() < n {
+            // Grow geometrically, so that a short reader fails long before
+            // an absurd length is allocated.
+            let start = buf.len();
+            let len = (n - start).min(start.max(first));
+            buf.resize(start + len, 0);
+            self.read_exact(&mut buf[start..])?;
+        }
+        Ok(buf)
+    }
+
+    /// Reads a frame prefixed with its 32 bit length in the given byte
+    /// order.
+    ///
+    /// A frame longer than `max_len` bytes is an error, which is reported
+    /// before any of it is read or allocated.
+    ///
+    /// This method requires the `alloc` feature.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `read_exact`. If the length is
+    /// greater than `max_len`, an error of kind
+    /// [`OutOfRange`](crate::ErrorKind::OutOfRange) is returned, and only
+    /// the length has been consumed from the reader.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{embedded_io::ReadBytesExt, BigEndian};
+    ///
+    /// let mut rdr: &[u8] = b"\x00\x00\x00\x05hello\xff\xff\xff\xff";
+    /// assert_eq!(b"hello", &rdr.read_frame::<BigEndian>(1024).unwrap()[..]);
+    /// assert!(rdr.read_frame::<BigEndian>(1024).is_err());
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn read_frame<T: ByteOrder>(
+        &mut self,
+        max_len: usize,
+    ) -> Result<Vec<u8>, ReadError<Self::Error>> {
+        let len = ReadBytesExt::read_u32::<T>(self)?;
+        match usize::try_from(len) {
+            Ok(len) if len <= max_len => Ok(self.read_exact_vec(len)?),
+            _ => Err(ReadError::Invalid(crate::ErrorKind::OutOfRange.into())),
+        }
+    }
+
+    /// Reads a frame prefixed with its 16 bit length in the given byte
+    /// order.
+    ///
+    /// This is the same as [`read_frame`](ReadBytesExt::read_frame), but
+    /// with a 16 bit length.
+    ///
+    /// This method requires the `alloc` feature.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`read_frame`](ReadBytesExt::read_frame).
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn read_frame_u16<T: ByteOrder>(
+        &mut self,
+        max_len: usize,
+    ) -> Result<Vec<u8>, ReadError<Self::Error>> {
+        let len = usize::from(ReadBytesExt::read_u16::<T>(self)?);
+        if len > max_len {
+            return Err(ReadError::Invalid(
+                crate::ErrorKind::OutOfRange.into(),
+            ));
+        }
+        Ok(self.read_exact_vec(len)?)
+    }
+}
+
+impl<R: Read + ?Sized> ReadBytesExt for R {}
+
+/// Extends `embedded-io`'s `Write` with methods for writing numbers.
+///
+/// This is implemented for every type that implements `Write`. See the
+/// [module documentation](self) for details.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{embedded_io::WriteBytesExt, BigEndian};
+///
+/// let mut buf = [0; 6];
+/// let mut wtr = &mut buf[..];
+/// wtr.write_u16::<BigEndian>(0xCAFE).unwrap();
+/// wtr.write_i32::<BigEndian>(-2).unwrap();
+/// assert_eq!([0xCA, 0xFE, 0xFF, 0xFF, 0xFF, 0xFE], buf);
+/// ```
+pub trait WriteBytesExt: Write {
+    /// Writes an unsigned 8 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    #[inline]
+    fn write_u8(&mut self, n: u8) -> Result<(), Self::Error> {
+        self.write_all(&[n])
+    }
+
+    /// Writes a signed 8 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    #[inline]
+    fn write_i8(&mut self, n: i8) -> Result<(), Self::Error> {
+        self.write_all(&[n as u8])
+    }
+
+    write_methods! {
+        write_u16(u16, 2, "an unsigned 16 bit integer");
+        write_i16(i16, 2, "a signed 16 bit integer");
+        write_u24(u32, 3, "an unsigned 24 bit integer");
+        write_i24(i32, 3, "a signed 24 bit integer");
+        write_u32(u32, 4, "an unsigned 32 bit integer");
+        write_i32(i32, 4, "a signed 32 bit integer");
+        write_u48(u64, 6, "an unsigned 48 bit integer");
+        write_i48(i64, 6, "a signed 48 bit integer");
+        write_u64(u64, 8, "an unsigned 64 bit integer");
+        write_i64(i64, 8, "a signed 64 bit integer");
+        write_u128(u128, 16, "an unsigned 128 bit integer");
+        write_i128(i128, 16, "a signed 128 bit integer");
+        write_f32(f32, 4, "an IEEE754 single-precision float");
+        write_f64(f64, 8, "an IEEE754 double-precision float");
+    }
+
+    /// Writes an unsigned n-bytes integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    ///
+    /// # Panics
+    ///
+    /// If the given integer is not representable in the given number of
+    /// bytes, this method panics. If `nbytes > 8`, this method panics.
+    #[inline]
+    fn write_uint<T: ByteOrder>(
+        &mut self,
+        n: u64,
+        nbytes: usize,
+    ) -> Result<(), Self::Error> {
+        let mut buf = [0; 8];
+        T::write_uint(&mut buf, n, nbytes);
+        self.write_all(&buf[0..nbytes])
+    }
+
+    /// Writes a signed n-bytes integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    ///
+    /// # Panics
+    ///
+    /// If the given integer is not representable in the given number of
+    /// bytes, this method panics. If `nbytes > 8`, this method panics.
+    #[inline]
+    fn write_int<T: ByteOrder>(
+        &mut self,
+        n: i64,
+        nbytes: usize,
+    ) -> Result<(), Self::Error> {
+        let mut buf = [0; 8];
+        T::write_int(&mut buf, n, nbytes);
+        self.write_all(&buf[0..nbytes])
+    }
+
+    /// Writes an unsigned n-bytes integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    ///
+    /// # Panics
+    ///
+    /// If the given integer is not representable in the given number of
+    /// bytes, this method panics. If `nbytes > 16`, this method panics.
+    #[inline]
+    fn write_uint128<T: ByteOrder>(
+        &mut self,
+        n: u128,
+        nbytes: usize,
+    ) -> Result<(), Self::Error> {
+        let mut buf = [0; 16];
+        T::write_uint128(&mut buf, n, nbytes);
+        self.write_all(&buf[0..nbytes])
+    }
+
+    /// Writes a signed n-bytes integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    ///
+    /// # Panics
+    ///
+    /// If the given integer is not representable in the given number of
+    /// bytes, this method panics. If `nbytes > 16`, this method panics.
+    #[inline]
+    fn write_int128<T: ByteOrder>(
+        &mut self,
+        n: i128,
+        nbytes: usize,
+    ) -> Result<(), Self::Error> {
+        let mut buf = [0; 16];
+        T::write_int128(&mut buf, n, nbytes);
+        self.write_all(&buf[0..nbytes])
+    }
+
+    /// Writes a boolean as `0` or `1`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    #[inline]
+    fn write_bool(&mut self, b: bool) -> Result<(), Self::Error> {
+        self.write_all(&[u8::from(b)])
+    }
+
+    /// Writes an `f32` as an IEEE754 half-precision float, rounding it to
+    /// the nearest representable value.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    #[inline]
+    fn write_f16<T: ByteOrder>(&mut self, n: f32) -> Result<(), Self::Error> {
+        let mut buf = [0; 2];
+        T::write_f16(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes a Unicode scalar value as a 32 bit integer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    #[inline]
+    fn write_char<T: ByteOrder>(
+        &mut self,
+        c: char,
+    ) -> Result<(), Self::Error> {
+        WriteBytesExt::write_u32::<T>(self, u32::from(c))
+    }
+
+    /// Writes a sequence of signed 8 bit integers to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    #[inline]
+    fn write_i8_from(&mut self, src: &[i8]) -> Result<(), Self::Error> {
+        let mut buf = [0; STACK_SIZE];
+        for chunk in src.chunks(STACK_SIZE) {
+            let buf = &mut buf[..chunk.len()];
+            for (dst, &n) in buf.iter_mut().zip(chunk) {
+                *dst = n as u8;
+            }
+            self.write_all(buf)?;
+        }
+        Ok(())
+    }
+
+    write_from_methods! {
+        write_u16_from(u16, write_u16_into, "unsigned 16 bit integers");
+        write_i16_from(i16, write_i16_into, "signed 16 bit integers");
+        write_u32_from(u32, write_u32_into, "unsigned 32 bit integers");
+        write_i32_from(i32, write_i32_into, "signed 32 bit integers");
+        write_u64_from(u64, write_u64_into, "unsigned 64 bit integers");
+        write_i64_from(i64, write_i64_into, "signed 64 bit integers");
+        write_u128_from(u128, write_u128_into, "unsigned 128 bit integers");
+        write_i128_from(i128, write_i128_into, "signed 128 bit integers");
+        write_f32_from(f32, write_f32_into, "IEEE754 single-precision floats");
+        write_f64_from(f64, write_f64_into, "IEEE754 double-precision floats");
+    }
+
+    /// Writes a value of any type implementing [`ToWire`].
+    ///
+    /// Exactly `T::WIRE_SIZE` bytes are written, with a single call to
+    /// `write_all`, and nothing is written when the value can't be encoded.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`, and any error
+    /// returned by encoding the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `T::WIRE_SIZE` is more than 256 bytes, since the value is
+    /// encoded into a buffer on the stack.
+    #[inline]
+    fn write_wire<T: ToWire, E: ByteOrder>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), WriteError<Self::Error>> {
+        let mut buf = [0; STACK_SIZE];
+        let buf = &mut buf[..T::WIRE_SIZE];
+        value.to_wire::<E>(buf)?;
+        self.write_all(buf).map_err(WriteError::Other)
+    }
+
+    /// Writes an unsigned LEB128 integer, in its shortest encoding.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    #[inline]
+    fn write_uleb128(&mut self, n: u64) -> Result<(), Self::Error> {
+        let mut buf = [0; varint::MAX_ULEB128_LEN];
+        let len = varint::uleb128_len(n);
+        // This can't fail, since the buffer fits every encoding.
+        let _ = varint::encode_uleb128(n, &mut buf);
+        self.write_all(&buf[..len])
+    }
+
+    /// Writes an unsigned variable length integer, in the encoding used by
+    /// Protocol Buffers.
+    ///
+    /// This is the same as [`write_uleb128`](WriteBytesExt::write_uleb128).
+    #[inline]
+    fn write_varint_u64(&mut self, n: u64) -> Result<(), Self::Error> {
+        self.write_uleb128(n)
+    }
+
+    /// Writes a signed variable length integer, encoded with ZigZag and then
+    /// as unsigned LEB128.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    #[inline]
+    fn write_varint_zigzag(&mut self, n: i64) -> Result<(), Self::Error> {
+        self.write_uleb128(varint::zigzag_encode_i64(n))
+    }
+
+    /// Writes an unsigned LEB128 integer of up to 128 bits, in its shortest
+    /// encoding.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    #[inline]
+    fn write_uleb128_u128(&mut self, n: u128) -> Result<(), Self::Error> {
+        let mut buf = [0; varint::MAX_ULEB128_U128_LEN];
+        let len = varint::uleb128_u128_len(n);
+        // This can't fail, since the buffer fits every encoding.
+        let _ = varint::encode_uleb128_u128(n, &mut buf);
+        self.write_all(&buf[..len])
+    }
+
+    /// Writes a signed variable length integer of up to 128 bits, encoded
+    /// with ZigZag and then as unsigned LEB128.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`.
+    #[inline]
+    fn write_varint_zigzag_i128(
+        &mut self,
+        n: i128,
+    ) -> Result<(), Self::Error> {
+        self.write_uleb128_u128(varint::zigzag_encode_i128(n))
+    }
+
+    /// Writes `frame` prefixed with its 32 bit length in the given byte
+    /// order.
+    ///
+    /// This is the inverse of [`read_frame`](ReadBytesExt::read_frame).
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as `write_all`. If the length of
+    /// `frame` doesn't fit in a `u32`, an error of kind
+    /// [`OutOfRange`](crate::ErrorKind::OutOfRange) is returned, and
+    /// nothing is written.
+    #[inline]
+    fn write_frame<T: ByteOrder>(
+        &mut self,
+        frame: &[u8],
+    ) -> Result<(), WriteError<Self::Error>> {
+        let len = u32::try_from(frame.len()).map_err(|_| {
+            WriteError::Invalid(crate::ErrorKind::OutOfRange.into())
+        })?;
+        WriteBytesExt::write_u32::<T>(self, len).map_err(WriteError::Other)?;
+        self.write_all(frame).map_err(WriteError::Other)
+    }
+
+    /// Writes `frame` prefixed with its 16 bit length in the given byte
+    /// order.
+    ///
+    /// This is the inverse of
+    /// [`read_frame_u16`](ReadBytesExt::read_frame_u16).
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`write_frame`](WriteBytesExt::write_frame).
+    #[inline]
+    fn write_frame_u16<T: ByteOrder>(
+        &mut self,
+        frame: &[u8],
+    ) -> Result<(), WriteError<Self::Error>> {
+        let len = u16::try_from(frame.len()).map_err(|_| {
+            WriteError::Invalid(crate::ErrorKind::OutOfRange.into())
+        })?;
+        WriteBytesExt::write_u16::<T>(self, len).map_err(WriteError::Other)?;
+        self.write_all(frame).map_err(WriteError::Other)
+    }
+}
+
+impl<W: Write + ?Sized> WriteBytesExt for W {}
+
+#[cfg(test)]
+mod tests {
+    use embedded_io::{ReadExactError, SliceWriteError};
+
+    use super::{ReadBytesExt, ReadError, WriteBytesExt, WriteError};
+    use crate::{fourcc::FourCc, BigEndian, ErrorKind, LittleEndian};
+
+    #[test]
+    fn roundtrip() {
+        let mut buf = [0; 36];
+        let mut wtr = &mut buf[..];
+        wtr.write_i8(-1).unwrap();
+        wtr.write_u48::<BigEndian>(0x0102_0304_0506).unwrap();
+        wtr.write_f64::<LittleEndian>(-1.5).unwrap();
+        wtr.write_int::<BigEndian>(-2, 3).unwrap();
+        wtr.write_i128::<LittleEndian>(i128::MIN).unwrap();
+        wtr.write_uint128::<BigEndian>(7, 2).unwrap();
+        assert_eq!(Err(SliceWriteError::Full), wtr.write_u16::<BigEndian>(1));
+
+        let mut rdr = &buf[..];
+        assert_eq!(Ok(-1), rdr.read_i8());
+        assert_eq!(Ok(0x0102_0304_0506), rdr.read_u48::<BigEndian>());
+        assert_eq!(Ok(-1.5), rdr.read_f64::<LittleEndian>());
+        assert_eq!(Ok(-2), rdr.read_int::<BigEndian>(3));
+        assert_eq!(Ok(i128::MIN), rdr.read_i128::<LittleEndian>());
+        assert_eq!(Ok(7), rdr.read_uint128::<BigEndian>(2));
+        assert_eq!(
+            Err(ReadExactError::UnexpectedEof),
+            rdr.read_u32::<BigEndian>()
+        );
+    }
+
+    #[test]
+    fn checked_reads() {
+        let mut rdr: &[u8] = &[1, 2, 0, 0, 0, 0x41, 0, 0, 0xD8, 0, 0x80];
+        assert_eq!(Ok(true), rdr.read_bool_strict());
+        assert_eq!(
+            Err(ReadError::Invalid(ErrorKind::OutOfRange.into())),
+            rdr.read_bool_strict()
+        );
+        assert_eq!(Ok('A'), rdr.read_char::<BigEndian>());
+        assert_eq!(
+            Err(ReadError::Invalid(ErrorKind::OutOfRange.into())),
+            rdr.read_char::<BigEndian>()
+        );
+        assert_eq!(Err(ReadError::UnexpectedEof), rdr.read_uleb128());
+    }
+
+    #[test]
+    fn slice_errors_convert() {
+        fn header(mut rdr: &[u8]) -> Result<(u16, char), crate::Error> {
+            Ok((rdr.read_u16::<BigEndian>()?, rdr.read_char::<BigEndian>()?))
+        }
+
+        assert_eq!(Ok((1, 'A')), header(&[0, 1, 0, 0, 0, 0x41]));
+        assert_eq!(
+            Err(ErrorKind::UnexpectedEof.into()),
+            header(&[0, 1, 0, 0])
+        );
+        assert_eq!(
+            Err(ErrorKind::OutOfRange.into()),
+            header(&[0, 1, 0, 0, 0xD8, 0])
+        );
+    }
+
+    #[test]
+    fn bulk_roundtrip() {
+        let src: [u32; 300] =
+            core::array::from_fn(|i| (i as u32).wrapping_mul(0x0101_0101));
+        let mut buf = [0; 1200];
+        let mut wtr = &mut buf[..];
+        wtr.write_u32_from::<BigEndian>(&src).unwrap();
+        assert!(wtr.is_empty());
+
+        let mut dst = [0; 300];
+        let mut rdr = &buf[..];
+        rdr.read_u32_into::<BigEndian>(&mut dst).unwrap();
+        assert_eq!(src, dst);
+        assert_eq!(
+            Err(ReadExactError::UnexpectedEof),
+            rdr.read_i8_into(&mut [0])
+        );
+    }
+
+    #[test]
+    fn extra_roundtrip() {
+        let mut buf = [0; 64];
+        let mut wtr = &mut buf[..];
+        wtr.write_bool(true).unwrap();
+        wtr.write_f16::<LittleEndian>(-2.5).unwrap();
+        wtr.write_char::<LittleEndian>('\u{1F600}').unwrap();
+        wtr.write_i8_from(&[-1, 2]).unwrap();
+        wtr.write_wire::<FourCc, LittleEndian>(&FourCc::new(*b"RIFF"))
+            .unwrap();
+        wtr.write_uleb128(300).unwrap();
+        wtr.write_varint_zigzag(-3).unwrap();
+        wtr.write_varint_zigzag_i128(i128::MIN).unwrap();
+        let left = wtr.len();
+        assert_eq!(
+            Err(WriteError::Other(SliceWriteError::Full)),
+            (&mut buf[60..]).write_wire::<u64, BigEndian>(&1)
+        );
+
+        let mut rdr = &buf[..64 - left];
+        assert_eq!(Ok(true), rdr.read_bool_lenient());
+        assert_eq!(Ok(-2.5), rdr.read_f16::<LittleEndian>());
+        assert_eq!(Ok('\u{1F600}'), rdr.read_char::<LittleEndian>());
+        let mut pair = [0; 2];
+        rdr.read_i8_into(&mut pair).unwrap();
+        assert_eq!([-1, 2], pair);
+        assert_eq!(Ok(*b"RIFF"), rdr.read_array::<4>());
+        assert_eq!(Ok(300), rdr.read_varint_u64());
+        assert_eq!(Ok(-3), rdr.read_varint_zigzag());
+        assert_eq!(Ok(i128::MIN), rdr.read_varint_zigzag_i128());
+        assert!(rdr.is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn frames() {
+        let mut buf = [0; 16];
+        let mut wtr = &mut buf[..];
+        wtr.write_frame::<BigEndian>(b"hello").unwrap();
+        wtr.write_frame_u16::<LittleEndian>(b"hi").unwrap();
+        assert_eq!(
+            Err(WriteError::Other(SliceWriteError::Full)),
+            wtr.write_frame::<BigEndian>(b"")
+        );
+
+        let mut rdr = &buf[..];
+        assert_eq!(b"hello", &rdr.read_frame::<BigEndian>(5).unwrap()[..]);
+        assert_eq!(
+            Err(ReadError::Invalid(ErrorKind::OutOfRange.into())),
+            rdr.read_frame_u16::<LittleEndian>(1)
+        );
+        assert_eq!(b"hi", &rdr.read_exact_vec(2).unwrap()[..]);
+        assert_eq!(
+            Err(ReadExactError::UnexpectedEof),
+            rdr.read_exact_vec(usize::MAX)
+        );
+    }
+}
@@ -0,0 +1,311 @@
+/*!
+Reading and writing numbers with the async readers and writers of
+`embedded-io-async`.
+
+This is the async counterpart of the
+[`embedded_io`](https://docs.rs/byteorder/1/byteorder/embedded_io/index.html)
+module, for executors like Embassy. The [`AsyncReadBytesExt`] and
+[`AsyncWriteBytesExt`] traits have the same methods for numbers as the
+blocking traits, except that each one returns a future:
+
+```rust
+use byteorder::{embedded_io_async::AsyncReadBytesExt, BigEndian};
+
+# async fn example() -> Result<(), embedded_io_async::ReadExactError<core::convert::Infallible>> {
+let mut rdr: &[u8] = &[0x12, 0x34, 0x56, 0x78];
+assert_eq!(0x12345678, rdr.read_u32::<BigEndian>().await?);
+# Ok(())
+# }
+```
+
+The futures borrow their reader or writer and don't require `Send`, like the
+traits of `embedded-io-async`. Dropping a read future before it completes
+loses the bytes it has read so far, since `read_exact` itself is not
+cancel-safe.
+
+This module requires the `embedded-io-async` feature, which does not require
+`std`. `embedded-io-async` itself requires Rust 1.75 or newer.
+*/
+
+use core::future::Future;
+
+use ::embedded_io_async::{Read, ReadExactError, Write};
+
+use crate::ByteOrder;
+
+macro_rules! read_methods {
+    ($($name:ident($ty:ty, $size:expr, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Reads ", $desc, " from the underlying reader.\n\n",
+            "# Errors\n\n",
+            "The future fails with the same errors as `read_exact`.",
+        )]
+        #[inline]
+        fn $name<T: ByteOrder>(
+            &mut self,
+        ) -> impl Future<Output = Result<$ty, ReadExactError<Self::Error>>>
+        {
+            async move {
+                let mut buf = [0; $size];
+                self.read_exact(&mut buf).await?;
+                Ok(T::$name(&buf))
+            }
+        }
+    )*};
+}
+
+macro_rules! read_sized_methods {
+    ($($name:ident($ty:ty, $size:expr, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Reads ", $desc, " from the underlying reader.\n\n",
+            "# Errors\n\n",
+            "The future fails with the same errors as `read_exact`.\n\n",
+            "# Panics\n\n",
+            "Panics when `nbytes < 1` or `nbytes > ", stringify!($size), "`.",
+        )]
+        #[inline]
+        fn $name<T: ByteOrder>(
+            &mut self,
+            nbytes: usize,
+        ) -> impl Future<Output = Result<$ty, ReadExactError<Self::Error>>>
+        {
+            async move {
+                let mut buf = [0; $size];
+                self.read_exact(&mut buf[..nbytes]).await?;
+                Ok(T::$name(&buf[..nbytes], nbytes))
+            }
+        }
+    )*};
+}
+
+macro_rules! write_methods {
+    ($($name:ident($ty:ty, $size:expr, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Writes ", $desc, " to the underlying writer.\n\n",
+            "# Errors\n\n",
+            "The future fails with the same errors as `write_all`.",
+        )]
+        #[inline]
+        fn $name<T: ByteOrder>(
+            &mut self,
+            n: $ty,
+        ) -> impl Future<Output = Result<(), Self::Error>> {
+            async move {
+                let mut buf = [0; $size];
+                T::$name(&mut buf, n);
+                self.write_all(&buf).await
+            }
+        }
+    )*};
+}
+
+macro_rules! write_sized_methods {
+    ($($name:ident($ty:ty, $size:expr, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Writes ", $desc, " to the underlying writer.\n\n",
+            "# Errors\n\n",
+            "The future fails with the same errors as `write_all`.\n\n",
+            "# Panics\n\n",
+            "If the given integer is not representable in the given number ",
+            "of bytes, this method panics. If `nbytes > ", stringify!($size),
+            "`, this method panics.",
+        )]
+        #[inline]
+        fn $name<T: ByteOrder>(
+            &mut self,
+            n: $ty,
+            nbytes: usize,
+        ) -> impl Future<Output = Result<(), Self::Error>> {
+            async move {
+                let mut buf = [0; $size];
+                T::$name(&mut buf, n, nbytes);
+                self.write_all(&buf[..nbytes]).await
+            }
+        }
+    )*};
+}
+
+/// Extends `embedded-io-async`'s `Read` with methods for reading numbers.
+///
+/// This is implemented for every type that implements `Read`. See the
+/// [module documentation](self) for details.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{embedded_io_async::AsyncReadBytesExt, LittleEndian};
+///
+/// async fn header(rdr: &mut &[u8]) -> (u16, i64) {
+///     let tag = rdr.read_u16::<LittleEndian>().await.unwrap();
+///     let len = rdr.read_int::<LittleEndian>(3).await.unwrap();
+///     (tag, len)
+/// }
+/// ```
+pub trait AsyncReadBytesExt: Read {
+    /// Reads an unsigned 8 bit integer from the underlying reader.
+    ///
+    /// # Errors
+    ///
+    /// The future fails with the same errors as `read_exact`.
+    #[inline]
+    fn read_u8(
+        &mut self,
+    ) -> impl Future<Output = Result<u8, ReadExactError<Self::Error>>> {
+        async move {
+            let mut buf = [0; 1];
+            self.read_exact(&mut buf).await?;
+            Ok(buf[0])
+        }
+    }
+
+    /// Reads a signed 8 bit integer from the underlying reader.
+    ///
+    /// # Errors
+    ///
+    /// The future fails with the same errors as `read_exact`.
+    #[inline]
+    fn read_i8(
+        &mut self,
+    ) -> impl Future<Output = Result<i8, ReadExactError<Self::Error>>> {
+        async move {
+            let mut buf = [0; 1];
+            self.read_exact(&mut buf).await?;
+            Ok(buf[0] as i8)
+        }
+    }
+
+    read_methods! {
+        read_u16(u16, 2, "an unsigned 16 bit integer");
+        read_i16(i16, 2, "a signed 16 bit integer");
+        read_u24(u32, 3, "an unsigned 24 bit integer");
+        read_i24(i32, 3, "a signed 24 bit integer");
+        read_u32(u32, 4, "an unsigned 32 bit integer");
+        read_i32(i32, 4, "a signed 32 bit integer");
+        read_u48(u64, 6, "an unsigned 48 bit integer");
+        read_i48(i64, 6, "a signed 48 bit integer");
+        read_u64(u64, 8, "an unsigned 64 bit integer");
+        read_i64(i64, 8, "a signed 64 bit integer");
+        read_u128(u128, 16, "an unsigned 128 bit integer");
+        read_i128(i128, 16, "a signed 128 bit integer");
+        read_f32(f32, 4, "an IEEE754 single-precision float");
+        read_f64(f64, 8, "an IEEE754 double-precision float");
+    }
+
+    read_sized_methods! {
+        read_uint(u64, 8, "an unsigned n-bytes integer");
+        read_int(i64, 8, "a signed n-bytes integer");
+        read_uint128(u128, 16, "an unsigned n-bytes integer");
+        read_int128(i128, 16, "a signed n-bytes integer");
+    }
+}
+
+impl<R: Read + ?Sized> AsyncReadBytesExt for R {}
+
+/// Extends `embedded-io-async`'s `Write` with methods for writing numbers.
+///
+/// This is implemented for every type that implements `Write`. See the
+/// [module documentation](self) for details.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{embedded_io_async::AsyncWriteBytesExt, BigEndian};
+///
+/// async fn header(wtr: &mut &mut [u8], tag: u16, len: u32) {
+///     wtr.write_u16::<BigEndian>(tag).await.unwrap();
+///     wtr.write_u24::<BigEndian>(len).await.unwrap();
+/// }
+/// ```
+pub trait AsyncWriteBytesExt: Write {
+    /// Writes an unsigned 8 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// The future fails with the same errors as `write_all`.
+    #[inline]
+    fn write_u8(
+        &mut self,
+        n: u8,
+    ) -> impl Future<Output = Result<(), Self::Error>> {
+        async move { self.write_all(&[n]).await }
+    }
+
+    /// Writes a signed 8 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// The future fails with the same errors as `write_all`.
+    #[inline]
+    fn write_i8(
+        &mut self,
+        n: i8,
+    ) -> impl Future<Output = Result<(), Self::Error>> {
+        async move { self.write_all(&[n as u8]).await }
+    }
+
+    write_methods! {
+        write_u16(u16, 2, "an unsigned 16 bit integer");
+        write_i16(i16, 2, "a signed 16 bit integer");
+        write_u24(u32, 3, "an unsigned 24 bit integer");
+        write_i24(i32, 3, "a signed 24 bit integer");
+        write_u32(u32, 4, "an unsigned 32 bit integer");
+        write_i32(i32, 4, "a signed 32 bit integer");
+        write_u48(u64, 6, "an unsigned 48 bit integer");
+        write_i48(i64, 6, "a signed 48 bit integer");
+        write_u64(u64, 8, "an unsigned 64 bit integer");
+        write_i64(i64, 8, "a signed 64 bit integer");
+        write_u128(u128, 16, "an unsigned 128 bit integer");
+        write_i128(i128, 16, "a signed 128 bit integer");
+        write_f32(f32, 4, "an IEEE754 single-precision float");
+        write_f64(f64, 8, "an IEEE754 double-precision float");
+    }
+
+    write_sized_methods! {
+        write_uint(u64, 8, "an unsigned n-bytes integer");
+        write_int(i64, 8, "a signed n-bytes integer");
+        write_uint128(u128, 16, "an unsigned n-bytes integer");
+        write_int128(i128, 16, "a signed n-bytes integer");
+    }
+}
+
+impl<W: Write + ?Sized> AsyncWriteBytesExt for W {}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use embedded_io_async::ReadExactError;
+
+    use super::{AsyncReadBytesExt, AsyncWriteBytesExt};
+    use crate::{async_methods::block_on, BigEndian, LittleEndian};
+
+    #[test]
+    fn roundtrip() {
+        let mut buf = [0; 36];
+        block_on(async {
+            let mut wtr = &mut buf[..];
+            wtr.write_i8(-1).await.unwrap();
+            wtr.write_u48::<BigEndian>(0x0102_0304_0506).await.unwrap();
+            wtr.write_f64::<LittleEndian>(-1.5).await.unwrap();
+            wtr.write_int::<BigEndian>(-2, 3).await.unwrap();
+            wtr.write_i128::<LittleEndian>(i128::MIN).await.unwrap();
+            wtr.write_uint128::<BigEndian>(7, 2).await.unwrap();
+            assert!(wtr.write_u16::<BigEndian>(1).await.is_err());
+        });
+
+        block_on(async {
+            let mut rdr = &buf[..];
+            assert_eq!(Ok(-1), rdr.read_i8().await);
+            assert_eq!(
+                Ok(0x0102_0304_0506),
+                rdr.read_u48::<BigEndian>().await
+            );
+            assert_eq!(Ok(-1.5), rdr.read_f64::<LittleEndian>().await);
+            assert_eq!(Ok(-2), rdr.read_int::<BigEndian>(3).await);
+            assert_eq!(Ok(i128::MIN), rdr.read_i128::<LittleEndian>().await);
+            assert_eq!(Ok(7), rdr.read_uint128::<BigEndian>(2).await);
+            assert_eq!(
+                Err(ReadExactError::UnexpectedEof),
+                rdr.read_u32::<BigEndian>().await
+            );
+        });
+    }
+}
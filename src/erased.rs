@@ -0,0 +1,247 @@
+/*!
+Object safe traits for reading and writing numbers.
+
+The methods of [`ReadBytesExt`](crate::ReadBytesExt) and
+[`WriteBytesExt`](crate::WriteBytesExt) take the byte order as a type
+parameter, which is what makes them fast, but which also means a library
+that accepts "anything numbers can be read from" ends up with generic
+parameters all over its public API. The [`ByteSource`] and [`ByteSink`]
+traits in this module take the byte order as an [`Endianness`] value
+instead, so they can be used as trait objects, like `&mut dyn ByteSource` or
+`Box<dyn ByteSink>`.
+
+Every reader implements `ByteSource` and every writer implements `ByteSink`,
+including byte slices, `Cursor`, `File` and `BufReader`, and trait objects
+of either.
+
+The methods are named `get_*` and `put_*`, so that they never clash with the
+methods of the extension traits when both are in scope.
+
+# Examples
+
+A parser whose public API has no generic parameters:
+
+```rust
+use std::io::Cursor;
+
+use byteorder::{erased::ByteSource, Endianness};
+
+pub struct Header {
+    pub version: u16,
+    pub len: u32,
+}
+
+pub fn parse_header(
+    src: &mut dyn ByteSource,
+    order: Endianness,
+) -> std::io::Result<Header> {
+    Ok(Header { version: src.get_u16(order)?, len: src.get_u32(order)? })
+}
+
+let data = [0, 2, 0, 0, 1, 0];
+let header = parse_header(&mut &data[..], Endianness::Big).unwrap();
+assert_eq!((2, 256), (header.version, header.len));
+
+let mut cursor = Cursor::new(vec![2, 0, 0, 1, 0, 0]);
+let header = parse_header(&mut cursor, Endianness::Little).unwrap();
+assert_eq!((2, 256), (header.version, header.len));
+```
+*/
+
+use std::io::{self, Result};
+
+use crate::Endianness;
+
+macro_rules! get_methods {
+    ($($name:ident($ty:ty, $size:expr, $read:ident, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Reads ", $desc, " in the given byte order.\n\n",
+            "# Errors\n\n",
+            "This method returns the same errors as [`Read::read_exact`].\n\n",
+            "[`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact",
+        )]
+        #[inline]
+        fn $name(&mut self, order: Endianness) -> Result<$ty> {
+            let mut buf = [0; $size];
+            self.read_exact(&mut buf)?;
+            Ok(order.$read(&buf))
+        }
+    )*};
+}
+
+macro_rules! put_methods {
+    ($($name:ident($ty:ty, $size:expr, $write:ident, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Writes ", $desc, " in the given byte order.\n\n",
+            "# Errors\n\n",
+            "This method returns the same errors as [`Write::write_all`].\n\n",
+            "[`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all",
+        )]
+        #[inline]
+        fn $name(&mut self, order: Endianness, n: $ty) -> Result<()> {
+            let mut buf = [0; $size];
+            order.$write(&mut buf, n);
+            self.write_all(&buf)
+        }
+    )*};
+}
+
+/// A source of numbers, which can be used as a trait object.
+///
+/// This is implemented for every type that implements `io::Read`. See the
+/// [module documentation](self) for details.
+pub trait ByteSource: io::Read {
+    /// Reads an unsigned 8 bit integer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`].
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    #[inline]
+    fn get_u8(&mut self) -> Result<u8> {
+        let mut buf = [0; 1];
+        self.read_exact(&mut buf)?;
+        Ok(buf[0])
+    }
+
+    /// Reads a signed 8 bit integer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`].
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    #[inline]
+    fn get_i8(&mut self) -> Result<i8> {
+        self.get_u8().map(|n| n as i8)
+    }
+
+    get_methods! {
+        get_u16(u16, 2, read_u16, "an unsigned 16 bit integer");
+        get_i16(i16, 2, read_i16, "a signed 16 bit integer");
+        get_u24(u32, 3, read_u24, "an unsigned 24 bit integer");
+        get_i24(i32, 3, read_i24, "a signed 24 bit integer");
+        get_u32(u32, 4, read_u32, "an unsigned 32 bit integer");
+        get_i32(i32, 4, read_i32, "a signed 32 bit integer");
+        get_u48(u64, 6, read_u48, "an unsigned 48 bit integer");
+        get_i48(i64, 6, read_i48, "a signed 48 bit integer");
+        get_u64(u64, 8, read_u64, "an unsigned 64 bit integer");
+        get_i64(i64, 8, read_i64, "a signed 64 bit integer");
+        get_u128(u128, 16, read_u128, "an unsigned 128 bit integer");
+        get_i128(i128, 16, read_i128, "a signed 128 bit integer");
+        get_f32(f32, 4, read_f32, "an IEEE754 single-precision float");
+        get_f64(f64, 8, read_f64, "an IEEE754 double-precision float");
+    }
+}
+
+impl<R: io::Read + ?Sized> ByteSource for R {}
+
+/// A sink for numbers, which can be used as a trait object.
+///
+/// This is implemented for every type that implements `io::Write`. See the
+/// [module documentation](self) for details.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{erased::ByteSink, Endianness};
+///
+/// let mut sinks: Vec<Box<dyn ByteSink>> = vec![Box::new(vec![])];
+/// sinks[0].put_u32(Endianness::Little, 0x0102_0304).unwrap();
+/// sinks[0].put_i8(-1).unwrap();
+/// ```
+pub trait ByteSink: io::Write {
+    /// Writes an unsigned 8 bit integer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    #[inline]
+    fn put_u8(&mut self, n: u8) -> Result<()> {
+        self.write_all(&[n])
+    }
+
+    /// Writes a signed 8 bit integer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    #[inline]
+    fn put_i8(&mut self, n: i8) -> Result<()> {
+        self.put_u8(n as u8)
+    }
+
+    put_methods! {
+        put_u16(u16, 2, write_u16, "an unsigned 16 bit integer");
+        put_i16(i16, 2, write_i16, "a signed 16 bit integer");
+        put_u24(u32, 3, write_u24, "an unsigned 24 bit integer");
+        put_i24(i32, 3, write_i24, "a signed 24 bit integer");
+        put_u32(u32, 4, write_u32, "an unsigned 32 bit integer");
+        put_i32(i32, 4, write_i32, "a signed 32 bit integer");
+        put_u48(u64, 6, write_u48, "an unsigned 48 bit integer");
+        put_i48(i64, 6, write_i48, "a signed 48 bit integer");
+        put_u64(u64, 8, write_u64, "an unsigned 64 bit integer");
+        put_i64(i64, 8, write_i64, "a signed 64 bit integer");
+        put_u128(u128, 16, write_u128, "an unsigned 128 bit integer");
+        put_i128(i128, 16, write_i128, "a signed 128 bit integer");
+        put_f32(f32, 4, write_f32, "an IEEE754 single-precision float");
+        put_f64(f64, 8, write_f64, "an IEEE754 double-precision float");
+    }
+}
+
+impl<W: io::Write + ?Sized> ByteSink for W {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor, ErrorKind};
+
+    use super::{ByteSink, ByteSource};
+    use crate::{BigEndian, Endianness, ReadBytesExt, WriteBytesExt};
+
+    #[test]
+    fn roundtrip_through_trait_objects() {
+        for &order in &[Endianness::Big, Endianness::Little] {
+            let mut sink: Box<dyn ByteSink> = Box::new(Cursor::new(vec![]));
+            sink.put_i8(-2).unwrap();
+            sink.put_u24(order, 0x0A0B0C).unwrap();
+            sink.put_i48(order, -3).unwrap();
+            sink.put_u128(order, u128::MAX - 1).unwrap();
+            sink.put_f64(order, 2.5).unwrap();
+
+            let mut wtr = vec![];
+            {
+                let sink: &mut dyn ByteSink = &mut wtr;
+                sink.put_i8(-2).unwrap();
+                sink.put_u24(order, 0x0A0B0C).unwrap();
+                sink.put_i48(order, -3).unwrap();
+                sink.put_u128(order, u128::MAX - 1).unwrap();
+                sink.put_f64(order, 2.5).unwrap();
+            }
+
+            let mut src: Box<dyn ByteSource> =
+                Box::new(BufReader::new(&wtr[..]));
+            assert_eq!(-2, src.get_i8().unwrap());
+            assert_eq!(0x0A0B0C, src.get_u24(order).unwrap());
+            assert_eq!(-3, src.get_i48(order).unwrap());
+            assert_eq!(u128::MAX - 1, src.get_u128(order).unwrap());
+            assert_eq!(2.5, src.get_f64(order).unwrap());
+            let err = src.get_u8().unwrap_err();
+            assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        }
+    }
+
+    #[test]
+    fn no_clash_with_extension_traits() {
+        let mut wtr = vec![];
+        wtr.write_u16::<BigEndian>(1).unwrap();
+        wtr.put_u16(Endianness::Big, 2).unwrap();
+        let mut rdr = &wtr[..];
+        assert_eq!(1, rdr.get_u16(Endianness::Big).unwrap());
+        assert_eq!(2, rdr.read_u16::<BigEndian>().unwrap());
+    }
+}
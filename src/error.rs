@@ -0,0 +1,141 @@
+use core::fmt;
+
+/// An error that occurs when a fallible read or write cannot be performed.
+///
+/// This is returned by the `try_` methods on [`ByteOrder`](crate::ByteOrder),
+/// which never panic because of a short buffer, and by decoders for values
+/// that have a restricted range, like those in the [`time`](crate::time)
+/// module. The specific failure can be inspected with [`Error::kind`].
+///
+/// When the `std` feature is enabled, this error converts into a
+/// `std::io::Error`.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{BigEndian, ByteOrder, ErrorKind};
+///
+/// let err = BigEndian::try_read_u32(&[1, 2]).unwrap_err();
+/// assert_eq!(
+///     &ErrorKind::BufferTooSmall { needed: 4, available: 2 },
+///     err.kind(),
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Error {
+    kind: ErrorKind,
+}
+
+/// The specific kind of an [`Error`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The buffer given was too small for the requested read or write.
+    BufferTooSmall {
+        /// The number of bytes the operation required.
+        needed: usize,
+        /// The number of bytes that were available in the buffer.
+        available: usize,
+    },
+    /// A value could not be represented by its encoding. This occurs when
+    /// reading a value that is outside of its documented range, or when
+    /// writing a value that does not fit in its field.
+    OutOfRange,
+    /// The magic number at the start of some data was not recognized.
+    InvalidMagic,
+    /// The data is in a version of its format that is not supported.
+    UnsupportedVersion,
+    /// A field that should hold a decimal number contained a byte that is
+    /// not an ASCII digit.
+    InvalidDigit,
+    /// A field that should hold text was not valid UTF-8.
+    InvalidUtf8,
+    /// The input ended in the middle of a value. Unlike
+    /// [`BufferTooSmall`](ErrorKind::BufferTooSmall), this is reported by
+    /// readers that don't know how much input is left, like the readers of
+    /// the [`embedded_io`](https://docs.rs/byteorder/1/byteorder/embedded_io/index.html)
+    /// module.
+    UnexpectedEof,
+}
+
+impl Error {
+    /// Returns the specific kind of this error.
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub(crate) fn new(kind: ErrorKind) -> Error {
+        Error { kind }
+    }
+
+    /// Returns an error if `available` is less than `needed`.
+    #[inline]
+    pub(crate) fn check_len(
+        needed: usize,
+        available: usize,
+    ) -> Result<(), Error> {
+        if available < needed {
+            return Err(Error::new(ErrorKind::BufferTooSmall {
+                needed,
+                available,
+            }));
+        }
+        Ok(())
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Error {
+        Error::new(kind)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ErrorKind::BufferTooSmall { needed, available } => write!(
+                f,
+                "buffer too small: needed {} bytes but only {} available",
+                needed, available,
+            ),
+            ErrorKind::OutOfRange => {
+                write!(f, "value out of range for its encoding")
+            }
+            ErrorKind::InvalidMagic => write!(f, "unrecognized magic number"),
+            ErrorKind::UnsupportedVersion => {
+                write!(f, "unsupported format version")
+            }
+            ErrorKind::InvalidDigit => {
+                write!(f, "invalid digit in decimal field")
+            }
+            ErrorKind::InvalidUtf8 => write!(f, "invalid UTF-8 in text field"),
+            ErrorKind::UnexpectedEof => write!(f, "unexpected end of input"),
+        }
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl core::error::Error for Error {}
+
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    fn from(err: Error) -> std::io::Error {
+        use std::io;
+
+        let kind = match *err.kind() {
+            ErrorKind::BufferTooSmall { .. } => io::ErrorKind::UnexpectedEof,
+            ErrorKind::OutOfRange => io::ErrorKind::InvalidData,
+            ErrorKind::InvalidMagic => io::ErrorKind::InvalidData,
+            ErrorKind::UnsupportedVersion => io::ErrorKind::InvalidData,
+            ErrorKind::InvalidDigit => io::ErrorKind::InvalidData,
+            ErrorKind::InvalidUtf8 => io::ErrorKind::InvalidData,
+            ErrorKind::UnexpectedEof => io::ErrorKind::UnexpectedEof,
+        };
+        io::Error::new(kind, err)
+    }
+}
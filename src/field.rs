@@ -0,0 +1,342 @@
+/*!
+Integers in fixed-width fields, in binary or as ASCII decimal.
+
+Fixed layout records, like those used in telecom billing and financial
+messaging, give every integer a field of a fixed number of bytes, which may
+not match the size of any integer type: a 12 byte big-endian amount, say, or
+a count written as 8 zero-padded ASCII digits. The [`FieldCodec`] trait
+converts integers to and from such fields, and is implemented by [`Binary`],
+for big or little-endian binary fields of any width, and by [`Decimal`], for
+ASCII decimal fields.
+
+Both are checked in both directions. Encoding a value that doesn't fit in a
+field fails with [`ErrorKind::OutOfRange`],
+instead of silently truncating it, and decoding a field whose value doesn't
+fit in the requested type fails the same way.
+
+# Examples
+
+```rust
+use byteorder::{
+    field::{Binary, Decimal, FieldCodec},
+    BigEndian, ErrorKind,
+};
+
+let mut record = [0; 20];
+Binary::<BigEndian>::new().encode(1_000_000u64, &mut record[..12]).unwrap();
+Decimal::ZEROS.encode(42u8, &mut record[12..]).unwrap();
+assert_eq!(&record[9..], b"\x0f\x42\x4000000042");
+
+let amount: u64 = Binary::<BigEndian>::new().decode(&record[..12]).unwrap();
+let count: u32 = Decimal::ZEROS.decode(&record[12..]).unwrap();
+assert_eq!((1_000_000, 42), (amount, count));
+
+// Values that don't fit are errors, not truncated.
+let err = Decimal::ZEROS.encode(123u32, &mut [0; 2]).unwrap_err();
+assert_eq!(&ErrorKind::OutOfRange, err.kind());
+```
+*/
+
+use core::{convert::TryFrom, fmt, marker::PhantomData};
+
+use crate::{ByteOrder, Endianness, Error, ErrorKind};
+
+/// A way of storing unsigned integers in fixed-width fields.
+///
+/// The width of a field is the length of the slice it is given. Encoding
+/// always writes the whole field, and decoding always reads the whole field.
+///
+/// Implementors only need to provide [`encode_u128`](FieldCodec::encode_u128)
+/// and [`decode_u128`](FieldCodec::decode_u128). The provided
+/// [`encode`](FieldCodec::encode) and [`decode`](FieldCodec::decode) methods
+/// work with any unsigned integer type.
+pub trait FieldCodec {
+    /// Encodes `n` into the whole of `field`.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error of kind
+    /// [`ErrorKind::OutOfRange`] when `n`
+    /// doesn't fit in `field`, in which case `field` is left unchanged.
+    fn encode_u128(&self, n: u128, field: &mut [u8]) -> Result<(), Error>;
+
+    /// Decodes the integer in `field`.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error of kind
+    /// [`ErrorKind::OutOfRange`] when the
+    /// value doesn't fit in a `u128`, or any error specific to the codec
+    /// when `field` is not a valid encoding.
+    fn decode_u128(&self, field: &[u8]) -> Result<u128, Error>;
+
+    /// Encodes `n`, of any unsigned integer type, into the whole of `field`.
+    ///
+    /// This is the same as [`encode_u128`](FieldCodec::encode_u128).
+    #[inline]
+    fn encode<N: Into<u128>>(
+        &self,
+        n: N,
+        field: &mut [u8],
+    ) -> Result<(), Error> {
+        self.encode_u128(n.into(), field)
+    }
+
+    /// Decodes the integer in `field` as any unsigned integer type.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors returned by
+    /// [`decode_u128`](FieldCodec::decode_u128), this returns an error of
+    /// kind [`ErrorKind::OutOfRange`] when the
+    /// value doesn't fit in `N`.
+    #[inline]
+    fn decode<N: TryFrom<u128>>(&self, field: &[u8]) -> Result<N, Error> {
+        let n = self.decode_u128(field)?;
+        N::try_from(n).map_err(|_| Error::new(ErrorKind::OutOfRange))
+    }
+}
+
+/// Binary fields of any width in byte order `E`.
+///
+/// Fields wider than 16 bytes are allowed. Their extra bytes are the most
+/// significant ones, and must be zero when decoding.
+pub struct Binary<E> {
+    _order: PhantomData<E>,
+}
+
+impl<E> Binary<E> {
+    /// Creates a binary codec for the byte order `E`.
+    pub const fn new() -> Binary<E> {
+        Binary { _order: PhantomData }
+    }
+}
+
+// These are implemented by hand, since deriving them would require `E` to
+// implement them too.
+impl<E> Clone for Binary<E> {
+    fn clone(&self) -> Binary<E> {
+        *self
+    }
+}
+
+impl<E> Copy for Binary<E> {}
+
+impl<E> Default for Binary<E> {
+    fn default() -> Binary<E> {
+        Binary::new()
+    }
+}
+
+impl<E> fmt::Debug for Binary<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Binary")
+    }
+}
+
+impl<E: ByteOrder> Binary<E> {
+    /// Returns true if the extra bytes of fields wider than 16 bytes come
+    /// first.
+    fn high_first() -> bool {
+        Endianness::of::<E>() == Endianness::Big
+    }
+}
+
+impl<E: ByteOrder> FieldCodec for Binary<E> {
+    fn encode_u128(&self, n: u128, field: &mut [u8]) -> Result<(), Error> {
+        let width = field.len();
+        if width < 16 && n >> (8 * width) != 0 {
+            return Err(Error::new(ErrorKind::OutOfRange));
+        }
+        if width == 0 {
+            return Ok(());
+        }
+        let nlow = width.min(16);
+        let (high, low) = if Binary::<E>::high_first() {
+            field.split_at_mut(width - nlow)
+        } else {
+            let (low, high) = field.split_at_mut(nlow);
+            (high, low)
+        };
+        E::write_uint128(low, n, nlow);
+        for b in high {
+            *b = 0;
+        }
+        Ok(())
+    }
+
+    fn decode_u128(&self, field: &[u8]) -> Result<u128, Error> {
+        let width = field.len();
+        if width == 0 {
+            return Ok(0);
+        }
+        let nlow = width.min(16);
+        let (high, low) = if Binary::<E>::high_first() {
+            field.split_at(width - nlow)
+        } else {
+            let (low, high) = field.split_at(nlow);
+            (high, low)
+        };
+        if high.iter().any(|&b| b != 0) {
+            return Err(Error::new(ErrorKind::OutOfRange));
+        }
+        Ok(E::read_uint128(low, nlow))
+    }
+}
+
+/// ASCII decimal fields, right aligned and padded on the left.
+///
+/// Encoding writes the digits of a value at the end of the field, and fills
+/// the rest of it with the padding byte. Decoding skips leading padding, and
+/// then requires at least one digit, and nothing but digits.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{field::{Decimal, FieldCodec}, ErrorKind};
+///
+/// let mut field = [0; 6];
+/// Decimal::SPACES.encode(1234u16, &mut field).unwrap();
+/// assert_eq!(b"  1234", &field);
+/// assert_eq!(Ok(1234u16), Decimal::SPACES.decode(b"  1234"));
+///
+/// let err = Decimal::SPACES.decode::<u16>(b"  12 4").unwrap_err();
+/// assert_eq!(&ErrorKind::InvalidDigit, err.kind());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Decimal {
+    pad: u8,
+}
+
+impl Decimal {
+    /// Decimal fields padded with `0` digits.
+    pub const ZEROS: Decimal = Decimal { pad: b'0' };
+
+    /// Decimal fields padded with spaces.
+    pub const SPACES: Decimal = Decimal { pad: b' ' };
+}
+
+impl FieldCodec for Decimal {
+    fn encode_u128(&self, n: u128, field: &mut [u8]) -> Result<(), Error> {
+        let mut digits = 1;
+        let mut rest = n / 10;
+        while rest != 0 {
+            digits += 1;
+            rest /= 10;
+        }
+        if digits > field.len() {
+            return Err(Error::new(ErrorKind::OutOfRange));
+        }
+        let (pad, field) = field.split_at_mut(field.len() - digits);
+        for b in pad {
+            *b = self.pad;
+        }
+        let mut n = n;
+        for b in field.iter_mut().rev() {
+            *b = b'0' + (n % 10) as u8;
+            n /= 10;
+        }
+        Ok(())
+    }
+
+    fn decode_u128(&self, field: &[u8]) -> Result<u128, Error> {
+        let start = field.iter().take_while(|&&b| b == self.pad).count();
+        // A field of nothing but zero padding is zero.
+        let digits = match (&field[start..], self.pad) {
+            ([], b'0') if start > 0 => return Ok(0),
+            (digits, _) => digits,
+        };
+        if digits.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidDigit));
+        }
+        let mut n: u128 = 0;
+        for &b in digits {
+            if !b.is_ascii_digit() {
+                return Err(Error::new(ErrorKind::InvalidDigit));
+            }
+            n = n
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(u128::from(b - b'0')))
+                .ok_or_else(|| Error::new(ErrorKind::OutOfRange))?;
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Binary, Decimal, FieldCodec};
+    use crate::{BigEndian, Error, ErrorKind, LittleEndian};
+
+    fn out_of_range<T>() -> Result<T, Error> {
+        Err(Error::new(ErrorKind::OutOfRange))
+    }
+
+    #[test]
+    fn binary_widths() {
+        let be = Binary::<BigEndian>::new();
+        let le = Binary::<LittleEndian>::new();
+        for width in 0..=20 {
+            let max =
+                if width >= 16 { u128::MAX } else { !(!0 << (8 * width)) };
+            for &n in [0, 1, 0xAB, max >> 1, max].iter().filter(|&&n| n <= max)
+            {
+                let mut field = [0xFF; 20];
+                be.encode_u128(n, &mut field[..width]).unwrap();
+                assert_eq!(Ok(n), be.decode_u128(&field[..width]));
+                le.encode_u128(n, &mut field[..width]).unwrap();
+                assert_eq!(Ok(n), le.decode_u128(&field[..width]));
+            }
+            if width < 16 {
+                let mut field = [0xFF; 20];
+                assert_eq!(
+                    out_of_range(),
+                    be.encode_u128(max + 1, &mut field[..width])
+                );
+                assert_eq!([0xFF; 20], field);
+            }
+        }
+
+        let mut field = [0xFF; 18];
+        be.encode(0x0102u16, &mut field).unwrap();
+        assert_eq!(&field[15..], &[0, 1, 2]);
+        assert!(field[..15].iter().all(|&b| b == 0));
+        le.encode(0x0102u16, &mut field).unwrap();
+        assert_eq!(&field[..3], &[2, 1, 0]);
+
+        field[17] = 1;
+        assert_eq!(out_of_range::<u128>(), le.decode_u128(&field));
+        assert_eq!(out_of_range::<u8>(), be.decode(&[1, 0]));
+    }
+
+    #[test]
+    fn decimal() {
+        let mut field = [0; 5];
+        Decimal::ZEROS.encode(0u8, &mut field).unwrap();
+        assert_eq!(b"00000", &field);
+        assert_eq!(Ok(0u8), Decimal::ZEROS.decode(&field));
+        Decimal::ZEROS.encode(99999u32, &mut field).unwrap();
+        assert_eq!(b"99999", &field);
+        assert_eq!(
+            out_of_range(),
+            Decimal::ZEROS.encode(100000u32, &mut field)
+        );
+        assert_eq!(b"99999", &field);
+        assert_eq!(out_of_range(), Decimal::ZEROS.encode(0u8, &mut []));
+
+        let max = b"340282366920938463463374607431768211455";
+        assert_eq!(Ok(u128::MAX), Decimal::ZEROS.decode_u128(max));
+        assert_eq!(
+            out_of_range::<u128>(),
+            Decimal::ZEROS
+                .decode_u128(b"340282366920938463463374607431768211456")
+        );
+        assert_eq!(out_of_range::<u16>(), Decimal::SPACES.decode(b" 65536"));
+
+        let invalid = Err(Error::new(ErrorKind::InvalidDigit));
+        assert_eq!(invalid, Decimal::SPACES.decode::<u8>(b"   "));
+        assert_eq!(invalid, Decimal::SPACES.decode::<u8>(b""));
+        assert_eq!(invalid, Decimal::ZEROS.decode::<u8>(b"0 1"));
+        assert_eq!(invalid, Decimal::ZEROS.decode::<u8>(b"+1"));
+    }
+}
@@ -0,0 +1,333 @@
+/*!
+Fixed size byte strings, like hash digests and IDs.
+
+Hash digests and fixed size IDs have no byte order: they are strings of bytes
+that are stored, compared and printed in the order they were produced. Yet
+they are read from the same streams as numbers, and it is all too easy to
+read a 16 byte ID as a `u128` in some byte order, or to print a digest that
+was stored in a pair of `u64`s with its halves swapped. The types in this
+module hold such values as bytes, in their original order, so that there is
+no byte order to get wrong.
+
+* [`Digest32`] holds a 32 byte digest, like SHA-256 or BLAKE3.
+* [`Id16`] holds a 16 byte ID, like a UUID or a 128 bit content hash.
+
+Both are printed as lowercase hex, both implement the
+[`wire`](crate::wire) traits so they can be read and written alongside
+numbers, and both have a `ct_eq` method that makes a best effort to compare
+them in constant time, for when they are secrets, like authentication tags.
+
+# Examples
+
+```rust
+use std::io::Cursor;
+
+use byteorder::{fixed::Id16, BigEndian, ReadBytesExt};
+
+let mut data = vec![0, 1];
+data.extend_from_slice(b"\x00\x11\x22\x33\x44\x55\x66\x77");
+data.extend_from_slice(b"\x88\x99\xaa\xbb\xcc\xdd\xee\xff");
+let mut rdr = Cursor::new(data);
+let version = rdr.read_u16::<BigEndian>().unwrap();
+let id = Id16::read_from(&mut rdr).unwrap();
+assert_eq!(1, version);
+assert_eq!("00112233445566778899aabbccddeeff", id.to_string());
+```
+*/
+
+use core::fmt;
+
+macro_rules! fixed_bytes {
+    ($(#[$attr:meta])* $name:ident, $len:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub struct $name([u8; $len]);
+
+        impl $name {
+            /// The length of this type, in bytes.
+            pub const LEN: usize = $len;
+
+            /// Creates a value from its bytes.
+            #[inline]
+            pub const fn new(bytes: [u8; $len]) -> $name {
+                $name(bytes)
+            }
+
+            /// Returns the bytes of this value.
+            #[inline]
+            pub const fn to_bytes(self) -> [u8; $len] {
+                self.0
+            }
+
+            /// Returns the bytes of this value.
+            #[inline]
+            pub fn as_bytes(&self) -> &[u8; $len] {
+                &self.0
+            }
+
+            /// Reads a value from the start of `buf`.
+            ///
+            /// # Panics
+            ///
+            #[doc = concat!(
+                "Panics when `buf.len() < ", stringify!($len), "`."
+            )]
+            #[inline]
+            pub fn read(buf: &[u8]) -> $name {
+                let mut bytes = [0; $len];
+                bytes.copy_from_slice(&buf[..$len]);
+                $name(bytes)
+            }
+
+            /// Writes this value to the start of `buf`.
+            ///
+            /// # Panics
+            ///
+            #[doc = concat!(
+                "Panics when `buf.len() < ", stringify!($len), "`."
+            )]
+            #[inline]
+            pub fn write(self, buf: &mut [u8]) {
+                buf[..$len].copy_from_slice(&self.0);
+            }
+
+            /// Compares two values, making a best effort to do so in constant
+            /// time.
+            ///
+            /// Unlike `==`, which may stop at the first byte that differs,
+            /// this always looks at every byte, so that the time it takes
+            /// doesn't reveal where two values differ. Use this when
+            /// comparing a secret, like an authentication tag, against a
+            /// value that an attacker controls.
+            ///
+            /// This is not a guarantee: the compiler and the CPU are free to
+            /// optimize the comparison in ways that make its timing depend on
+            /// the data. If that matters for your threat model, use a
+            /// dedicated constant time comparison crate instead.
+            #[inline]
+            pub fn ct_eq(&self, other: &$name) -> bool {
+                ct_eq(&self.0, &other.0)
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl $name {
+            /// Reads a value from `rdr`.
+            ///
+            /// # Errors
+            ///
+            /// This method returns the same errors as [`Read::read_exact`].
+            ///
+            /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+            pub fn read_from<R: std::io::Read + ?Sized>(
+                rdr: &mut R,
+            ) -> std::io::Result<$name> {
+                crate::ReadBytesExt::read_array(rdr).map($name)
+            }
+
+            /// Writes this value to `wtr`.
+            ///
+            /// # Errors
+            ///
+            /// This method returns the same errors as [`Write::write_all`].
+            ///
+            /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+            pub fn write_to<W: std::io::Write + ?Sized>(
+                self,
+                wtr: &mut W,
+            ) -> std::io::Result<()> {
+                wtr.write_all(&self.0)
+            }
+        }
+
+        impl From<[u8; $len]> for $name {
+            fn from(bytes: [u8; $len]) -> $name {
+                $name(bytes)
+            }
+        }
+
+        impl From<$name> for [u8; $len] {
+            fn from(value: $name) -> [u8; $len] {
+                value.0
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::LowerHex::fmt(self, f)
+            }
+        }
+
+        impl fmt::LowerHex for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                for b in &self.0 {
+                    write!(f, "{:02x}", b)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl fmt::UpperHex for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                for b in &self.0 {
+                    write!(f, "{:02X}", b)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, concat!(stringify!($name), "({:x})"), self)
+            }
+        }
+
+        impl crate::wire::WireSize for $name {
+            const WIRE_SIZE: usize = $len;
+        }
+
+        impl crate::wire::FromWire for $name {
+            /// Decodes a value from its bytes, which don't depend on the
+            /// byte order `E`.
+            #[inline]
+            fn from_wire<E: crate::ByteOrder>(
+                buf: &[u8],
+            ) -> Result<$name, crate::Error> {
+                crate::Error::check_len($len, buf.len())?;
+                Ok($name::read(buf))
+            }
+        }
+
+        impl crate::wire::ToWire for $name {
+            /// Encodes this value as its bytes, which don't depend on the
+            /// byte order `E`.
+            #[inline]
+            fn to_wire<E: crate::ByteOrder>(
+                &self,
+                buf: &mut [u8],
+            ) -> Result<(), crate::Error> {
+                crate::Error::check_len($len, buf.len())?;
+                self.write(buf);
+                Ok(())
+            }
+        }
+    };
+}
+
+fixed_bytes!(
+    /// A 32 byte hash digest, like SHA-256 or BLAKE3.
+    ///
+    /// The bytes are kept in the order the hash function produced them,
+    /// which is also the order they are printed in.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::fixed::Digest32;
+    ///
+    /// let digest = Digest32::new([0xab; 32]);
+    /// assert!(digest.ct_eq(&Digest32::read(&[0xab; 40])));
+    /// assert_eq!("abab", &digest.to_string()[..4]);
+    /// ```
+    Digest32,
+    32
+);
+
+fixed_bytes!(
+    /// A 16 byte ID, like a UUID.
+    ///
+    /// The bytes are kept in the order they are stored in, which is also the
+    /// order they are printed in. Note that this is not the right type for
+    /// Microsoft's GUIDs, whose first three fields are stored as
+    /// little-endian integers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{fixed::Id16, wire::FromWire, LittleEndian};
+    ///
+    /// let bytes: Vec<u8> = (0..16).collect();
+    /// // The byte order makes no difference.
+    /// let id = Id16::from_wire::<LittleEndian>(&bytes).unwrap();
+    /// assert_eq!("000102030405060708090a0b0c0d0e0f", id.to_string());
+    /// ```
+    Id16,
+    16
+);
+
+/// Returns true if `a` and `b` are equal, trying to take time that depends
+/// only on their length.
+///
+/// This is best effort, since nothing stops the compiler or the CPU from
+/// introducing data dependent timing.
+fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    let diff = a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y));
+    // Discourage the compiler from turning the loop above into one that
+    // returns early, which it might otherwise do once it sees that only
+    // whether `diff` is zero matters.
+    //
+    // SAFETY: `&diff` is a reference to a live, initialized and properly
+    // aligned local `u8`, so it is valid for a read.
+    unsafe { core::ptr::read_volatile(&diff) == 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Digest32, Id16};
+    use crate::{wire::ToWire, BigEndian, Error, ErrorKind};
+
+    #[test]
+    fn bytes_and_wire() {
+        let id = Id16::new(*b"0123456789abcdef");
+        let mut buf = [0; 20];
+        id.to_wire::<BigEndian>(&mut buf).unwrap();
+        assert_eq!(b"0123456789abcdef\0\0\0\0", &buf);
+        assert_eq!(id, Id16::read(&buf));
+        assert_eq!(
+            Err(Error::new(ErrorKind::BufferTooSmall {
+                needed: 16,
+                available: 15
+            })),
+            id.to_wire::<BigEndian>(&mut buf[..15])
+        );
+        assert_eq!(*b"0123456789abcdef", <[u8; 16]>::from(id));
+    }
+
+    #[test]
+    fn ct_eq() {
+        let a = Digest32::new([7; 32]);
+        let mut bytes = [7; 32];
+        assert!(a.ct_eq(&Digest32::new(bytes)));
+        bytes[31] = 6;
+        assert!(!a.ct_eq(&Digest32::new(bytes)));
+        bytes[31] = 7;
+        bytes[0] = 0;
+        assert!(!a.ct_eq(&Digest32::new(bytes)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn formatting_and_io() {
+        let mut bytes = [0; 16];
+        bytes[0] = 0xAB;
+        bytes[15] = 0x01;
+        let id = Id16::new(bytes);
+        assert_eq!("ab000000000000000000000000000001", id.to_string());
+        assert_eq!("AB000000000000000000000000000001", format!("{:X}", id));
+        assert_eq!(
+            "Id16(ab000000000000000000000000000001)",
+            format!("{:?}", id)
+        );
+
+        let mut wtr = vec![];
+        id.write_to(&mut wtr).unwrap();
+        assert_eq!(id, Id16::read_from(&mut &wtr[..]).unwrap());
+        assert!(Digest32::read_from(&mut &wtr[..]).is_err());
+    }
+}
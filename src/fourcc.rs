@@ -0,0 +1,313 @@
+/*!
+Four character codes, as used by QuickTime, RIFF and friends.
+
+A four character code is four bytes that are compared as a byte string, like
+`avc1` or `WAVE`. On disk, they are almost always stored in reading order.
+The confusion starts when a format treats a code as a `u32`, since there are
+two conventions for packing the characters into an integer:
+
+* Apple's `OSType` (and C multi-character literals like `'avc1'`) put the
+  first character in the most significant byte. This corresponds to
+  [`BigEndian`](crate::BigEndian).
+* Microsoft's `MAKEFOURCC` puts the first character in the least significant
+  byte. This corresponds to [`LittleEndian`](crate::LittleEndian).
+
+[`FourCc`] always stores its characters in reading order, and every
+conversion to or from a `u32` names the packing convention explicitly.
+
+# Examples
+
+Reading an `OSType` that was written as a little-endian integer, which shows
+up in a hex dump as `1cva`:
+
+```rust
+use byteorder::{fourcc::FourCc, BigEndian, ByteOrder, LittleEndian};
+
+let field = [b'1', b'c', b'v', b'a'];
+let n = LittleEndian::read_u32(&field);
+let code = FourCc::from_u32::<BigEndian>(n);
+assert_eq!(code, FourCc::new(*b"avc1"));
+assert_eq!("avc1", code.to_string());
+```
+*/
+
+use core::{fmt, str::FromStr};
+
+use crate::ByteOrder;
+
+/// A four character code, stored in reading order.
+///
+/// Codes can be created in constant expressions with [`FourCc::new`], parsed
+/// from a string with `str::parse`, and printed with `Display`. Bytes that
+/// are not printable ASCII are printed as `\xNN` escapes.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{fourcc::FourCc, BigEndian, LittleEndian};
+///
+/// const WAVE: FourCc = FourCc::new(*b"WAVE");
+///
+/// assert_eq!(WAVE, "WAVE".parse().unwrap());
+/// assert_eq!(0x5741_5645, WAVE.to_u32::<BigEndian>());
+/// assert_eq!(0x4556_4157, WAVE.to_u32::<LittleEndian>());
+/// ```
+#[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FourCc([u8; 4]);
+
+impl FourCc {
+    /// Creates a code from its four bytes, in reading order.
+    #[inline]
+    pub const fn new(bytes: [u8; 4]) -> FourCc {
+        FourCc(bytes)
+    }
+
+    /// Returns the four bytes of this code, in reading order.
+    #[inline]
+    pub const fn to_bytes(self) -> [u8; 4] {
+        self.0
+    }
+
+    /// Returns the four bytes of this code, in reading order.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; 4] {
+        &self.0
+    }
+
+    /// Creates a code from an integer, where `E` determines how the
+    /// characters were packed into the integer.
+    ///
+    /// `BigEndian` means the first character is the most significant byte,
+    /// as with Apple's `OSType`. `LittleEndian` means the first character is
+    /// the least significant byte, as with Microsoft's `MAKEFOURCC`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{fourcc::FourCc, BigEndian, LittleEndian};
+    ///
+    /// assert_eq!(*b"avc1", FourCc::from_u32::<BigEndian>(0x6176_6331).to_bytes());
+    /// assert_eq!(*b"DXT1", FourCc::from_u32::<LittleEndian>(0x3154_5844).to_bytes());
+    /// ```
+    #[inline]
+    pub fn from_u32<E: ByteOrder>(n: u32) -> FourCc {
+        let mut buf = [0; 4];
+        E::write_u32(&mut buf, n);
+        FourCc(buf)
+    }
+
+    /// Packs this code into an integer, where `E` determines how the
+    /// characters are packed.
+    ///
+    /// This is the inverse of [`FourCc::from_u32`].
+    #[inline]
+    pub fn to_u32<E: ByteOrder>(self) -> u32 {
+        E::read_u32(&self.0)
+    }
+
+    /// Reads a code, in reading order, from the first four bytes of `buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < 4`.
+    #[inline]
+    pub fn read(buf: &[u8]) -> FourCc {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(&buf[..4]);
+        FourCc(bytes)
+    }
+
+    /// Writes this code, in reading order, to the first four bytes of `buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < 4`.
+    #[inline]
+    pub fn write(self, buf: &mut [u8]) {
+        buf[..4].copy_from_slice(&self.0);
+    }
+
+    /// Reads a code that was stored as an integer in byte order `S`, where
+    /// the characters were packed into the integer according to `P`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < 4`.
+    ///
+    /// # Examples
+    ///
+    /// An `OSType` written by a little-endian serializer:
+    ///
+    /// ```rust
+    /// use byteorder::{fourcc::FourCc, BigEndian, LittleEndian};
+    ///
+    /// let code = FourCc::read_packed::<LittleEndian, BigEndian>(b"1cva");
+    /// assert_eq!(code, FourCc::new(*b"avc1"));
+    /// ```
+    #[inline]
+    pub fn read_packed<S: ByteOrder, P: ByteOrder>(buf: &[u8]) -> FourCc {
+        FourCc::from_u32::<P>(S::read_u32(buf))
+    }
+
+    /// Writes this code as an integer in byte order `S`, where the
+    /// characters are packed into the integer according to `P`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < 4`.
+    #[inline]
+    pub fn write_packed<S: ByteOrder, P: ByteOrder>(self, buf: &mut [u8]) {
+        S::write_u32(buf, self.to_u32::<P>());
+    }
+}
+
+impl From<[u8; 4]> for FourCc {
+    fn from(bytes: [u8; 4]) -> FourCc {
+        FourCc(bytes)
+    }
+}
+
+impl From<FourCc> for [u8; 4] {
+    fn from(code: FourCc) -> [u8; 4] {
+        code.0
+    }
+}
+
+impl fmt::Display for FourCc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use core::fmt::Write;
+
+        for &b in &self.0 {
+            if b.is_ascii_graphic() || b == b' ' {
+                f.write_char(b as char)?;
+            } else {
+                write!(f, "\\x{:02X}", b)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for FourCc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FourCc(\"{}\")", self)
+    }
+}
+
+impl FromStr for FourCc {
+    type Err = ParseFourCcError;
+
+    /// Parses a code from a string of exactly four bytes.
+    fn from_str(s: &str) -> Result<FourCc, ParseFourCcError> {
+        let bytes = s.as_bytes();
+        if bytes.len() != 4 {
+            return Err(ParseFourCcError { len: bytes.len() });
+        }
+        Ok(FourCc::read(bytes))
+    }
+}
+
+/// The error returned when parsing a [`FourCc`] from a string that is not
+/// exactly four bytes long.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ParseFourCcError {
+    len: usize,
+}
+
+impl fmt::Display for ParseFourCcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "four character code must be 4 bytes long, but got {} bytes",
+            self.len
+        )
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl core::error::Error for ParseFourCcError {}
+
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+impl std::error::Error for ParseFourCcError {}
+
+#[cfg(feature = "std")]
+impl FourCc {
+    /// Reads a code, in reading order, from `rdr`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`].
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    pub fn read_from<R: std::io::Read + ?Sized>(
+        rdr: &mut R,
+    ) -> std::io::Result<FourCc> {
+        let mut bytes = [0; 4];
+        rdr.read_exact(&mut bytes)?;
+        Ok(FourCc(bytes))
+    }
+
+    /// Writes this code, in reading order, to `wtr`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    pub fn write_to<W: std::io::Write + ?Sized>(
+        self,
+        wtr: &mut W,
+    ) -> std::io::Result<()> {
+        wtr.write_all(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FourCc;
+    use crate::{BigEndian, LittleEndian};
+
+    #[test]
+    fn packing_conventions() {
+        let code = FourCc::new(*b"avc1");
+        assert_eq!(0x6176_6331, code.to_u32::<BigEndian>());
+        assert_eq!(0x3163_7661, code.to_u32::<LittleEndian>());
+        assert_eq!(code, FourCc::from_u32::<BigEndian>(0x6176_6331));
+        assert_eq!(code, FourCc::from_u32::<LittleEndian>(0x3163_7661));
+
+        let mut buf = [0; 4];
+        code.write_packed::<BigEndian, BigEndian>(&mut buf);
+        assert_eq!(&buf, b"avc1");
+        code.write_packed::<LittleEndian, BigEndian>(&mut buf);
+        assert_eq!(&buf, b"1cva");
+        code.write_packed::<LittleEndian, LittleEndian>(&mut buf);
+        assert_eq!(&buf, b"avc1");
+        assert_eq!(
+            code,
+            FourCc::read_packed::<LittleEndian, BigEndian>(b"1cva")
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn display_and_parse() {
+        assert_eq!("avc1", FourCc::new(*b"avc1").to_string());
+        assert_eq!("ab\\x00\\xFF", FourCc::new(*b"ab\x00\xff").to_string());
+        assert_eq!("FourCc(\"mp4a\")", format!("{:?}", FourCc::new(*b"mp4a")));
+        assert_eq!(Ok(FourCc::new(*b"ftyp")), "ftyp".parse());
+        assert!("abc".parse::<FourCc>().is_err());
+        assert!("abcde".parse::<FourCc>().is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_roundtrip() {
+        let mut wtr = vec![];
+        FourCc::new(*b"moov").write_to(&mut wtr).unwrap();
+        assert_eq!(wtr, b"moov");
+        let code = FourCc::read_from(&mut &wtr[..]).unwrap();
+        assert_eq!(FourCc::new(*b"moov"), code);
+        assert!(FourCc::read_from(&mut &b"moo"[..]).is_err());
+    }
+}
@@ -0,0 +1,660 @@
+/*!
+Reading and writing numbers with the `futures-io` asynchronous readers and
+writers.
+
+The `AsyncRead` and `AsyncWrite` traits of the `futures-io` crate are shared
+by several runtimes, like smol and async-std, so the extension traits in this
+module work with all of them. They are the same as those in the
+[`tokio`](https://docs.rs/byteorder/1/byteorder/tokio/index.html) module,
+which are for tokio's traits of the same names:
+
+```rust,no_run
+use byteorder::{futures::AsyncReadBytesExt, BigEndian};
+use futures_io::AsyncRead;
+
+async fn read_header<R>(stream: &mut R) -> std::io::Result<(u16, u32)>
+where
+    R: AsyncRead + Unpin,
+{
+    let kind = stream.read_u16::<BigEndian>().await?;
+    let len = stream.read_u32::<BigEndian>().await?;
+    Ok((kind, len))
+}
+```
+
+This module requires the `futures-io` feature.
+
+# Streams and sinks
+
+A reader that holds nothing but values of one type can be turned into a
+`Stream` of them with [`NumStream`], and a writer into a `Sink` of them with
+[`NumSink`]. This lets them be used with the combinators of `StreamExt` and
+`SinkExt` from the `futures` crate, like `forward` or `chunks`.
+
+# Runtimes
+
+No adapter is needed to use these traits with async-std or smol, since their
+I/O traits are the ones from `futures-io`: `async_std::io::Read` and
+`async_std::io::Write` are re-exports of `AsyncRead` and `AsyncWrite`, and so
+are the traits of the same names in `smol::io` and `futures_lite::io`. Their
+sockets and files implement them, so after importing the traits from this
+module, a number can be read from an `async_std::net::TcpStream` with
+`stream.read_u32::<BigEndian>().await`. Neither runtime's own extension
+traits have methods with these names, so both can be in scope at once.
+
+For tokio, whose traits are separate, use the
+[`tokio`](https://docs.rs/byteorder/1/byteorder/tokio/index.html) module
+instead. The `compat` module of the `tokio-util` crate has wrappers that
+adapt the readers and writers of one ecosystem to the traits of the other.
+
+# Cancellation
+
+Like `read_exact` and `write_all`, the futures returned by these traits are
+not cancellation safe: if one is dropped before it completes, for example in
+a losing branch of `select!`, the bytes it had already read are lost, or
+some of its bytes may have been written.
+
+When a read must survive being cancelled, use a [`ResumableRead`] instead,
+which keeps the bytes read so far outside of the future. A [`NumStream`]
+does the same, so its `next()` futures can be dropped too.
+*/
+
+use core::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io;
+
+use futures_core::Stream;
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_sink::Sink;
+
+use crate::{
+    async_methods::{eof, write_zero},
+    wire::{FromWire, ToWire},
+    ByteOrder,
+};
+
+crate::async_methods::future_types!(AsyncRead, AsyncWrite);
+
+/// Reads into `buf` with a single call to `poll_read`, and returns the number
+/// of bytes read.
+fn poll_read_some<R: AsyncRead + ?Sized>(
+    rdr: Pin<&mut R>,
+    cx: &mut Context<'_>,
+    buf: &mut [u8],
+) -> Poll<io::Result<usize>> {
+    rdr.poll_read(cx, buf)
+}
+
+/// Writes from `buf` with a single call to `poll_write`, and returns the
+/// number of bytes written.
+fn poll_write_some<W: AsyncWrite + ?Sized>(
+    wtr: Pin<&mut W>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+) -> Poll<io::Result<usize>> {
+    wtr.poll_write(cx, buf)
+}
+
+/// Extends the `AsyncRead` of `futures-io` with methods for reading numbers.
+///
+/// This is implemented for every type that implements `AsyncRead` and
+/// `Unpin`. See the [module documentation](self) for details.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use byteorder::{futures::AsyncReadBytesExt, LittleEndian};
+/// use futures_io::AsyncRead;
+///
+/// async fn read_point<R>(rdr: &mut R) -> std::io::Result<(f32, f32)>
+/// where
+///     R: AsyncRead + Unpin,
+/// {
+///     let x = rdr.read_f32::<LittleEndian>().await?;
+///     let y = rdr.read_f32::<LittleEndian>().await?;
+///     Ok((x, y))
+/// }
+/// ```
+pub trait AsyncReadBytesExt: AsyncRead + Unpin {
+    crate::async_methods::read_methods!();
+}
+
+impl<R: AsyncRead + Unpin + ?Sized> AsyncReadBytesExt for R {}
+
+/// Extends the `AsyncWrite` of `futures-io` with methods for writing
+/// numbers.
+///
+/// This is implemented for every type that implements `AsyncWrite` and
+/// `Unpin`. See the [module documentation](self) for details.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use byteorder::{futures::AsyncWriteBytesExt, BigEndian};
+/// use futures_io::AsyncWrite;
+///
+/// async fn write_header<W>(wtr: &mut W, len: u32) -> std::io::Result<()>
+/// where
+///     W: AsyncWrite + Unpin,
+/// {
+///     wtr.write_u16::<BigEndian>(0xCAFE).await?;
+///     wtr.write_u32::<BigEndian>(len).await
+/// }
+/// ```
+pub trait AsyncWriteBytesExt: AsyncWrite + Unpin {
+    crate::async_methods::write_methods!();
+}
+
+impl<W: AsyncWrite + Unpin + ?Sized> AsyncWriteBytesExt for W {}
+
+/// A `Stream` of the values of type `T` read from `R` in the byte order `E`.
+///
+/// Each item is decoded with [`FromWire`], so the stream works for the
+/// primitive numbers as well as arrays of them and types that implement the
+/// trait themselves. The stream ends when the reader reaches the end of its
+/// input between two values. Reaching it in the middle of a value yields an
+/// error of kind `UnexpectedEof` instead, and bytes that aren't a valid value
+/// yield an error of kind `InvalidData`.
+///
+/// Bytes read for a value that isn't complete yet are kept by the stream, so
+/// dropping a `next()` future, for example in a losing branch of `select!`,
+/// loses nothing.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use byteorder::{futures::NumStream, LittleEndian};
+/// use futures::{io::AsyncRead, StreamExt};
+///
+/// async fn total<R>(rdr: R) -> std::io::Result<u64>
+/// where
+///     R: AsyncRead + Unpin,
+/// {
+///     let mut samples = NumStream::<_, u32, LittleEndian>::new(rdr);
+///     let mut total = 0;
+///     while let Some(n) = samples.next().await {
+///         total += u64::from(n?);
+///     }
+///     Ok(total)
+/// }
+/// ```
+pub struct NumStream<R, T, E> {
+    rdr: R,
+    buf: Vec<u8>,
+    filled: usize,
+    _marker: PhantomData<fn() -> (T, E)>,
+}
+
+impl<R, T: FromWire, E: ByteOrder> NumStream<R, T, E> {
+    /// Creates a stream of the values read from `rdr`.
+    pub fn new(rdr: R) -> NumStream<R, T, E> {
+        NumStream {
+            rdr,
+            buf: vec![0; T::WIRE_SIZE],
+            filled: 0,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<R, T, E> NumStream<R, T, E> {
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.rdr
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly while a value is only partially read
+    /// corrupts the rest of the stream.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.rdr
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// The bytes of a value that was only partially read are lost.
+    pub fn into_inner(self) -> R {
+        self.rdr
+    }
+}
+
+impl<R, T, E> core::fmt::Debug for NumStream<R, T, E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("NumStream")
+            .field("buf", &&self.buf[..self.filled])
+            .field("len", &self.buf.len())
+            .finish()
+    }
+}
+
+impl<R, T, E> Stream for NumStream<R, T, E>
+where
+    R: AsyncRead + Unpin,
+    T: FromWire,
+    E: ByteOrder,
+{
+    type Item = io::Result<T>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<io::Result<T>>> {
+        let me = self.get_mut();
+        while me.filled < me.buf.len() {
+            let buf = &mut me.buf[me.filled..];
+            match poll_read_some(Pin::new(&mut me.rdr), cx, buf) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(err)) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(Ok(0)) if me.filled == 0 => {
+                    return Poll::Ready(None)
+                }
+                Poll::Ready(Ok(0)) => return Poll::Ready(Some(Err(eof()))),
+                Poll::Ready(Ok(n)) => me.filled += n,
+            }
+        }
+        me.filled = 0;
+        Poll::Ready(Some(T::from_wire::<E>(&me.buf).map_err(From::from)))
+    }
+}
+
+/// A `Sink` that writes values of type `T` to `W` in the byte order `E`.
+///
+/// Each value is encoded with [`ToWire`] into a buffer, which is written to
+/// `W` once it holds a few kilobytes, and whenever the sink is flushed or
+/// closed. A value that can't be encoded fails `start_send` with an error of
+/// kind `InvalidData`, without affecting the values sent before it.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use byteorder::{futures::NumSink, BigEndian};
+/// use futures::{io::AsyncWrite, SinkExt};
+///
+/// async fn send<W>(wtr: W, values: &[i16]) -> std::io::Result<()>
+/// where
+///     W: AsyncWrite + Unpin,
+/// {
+///     let mut sink = NumSink::<_, i16, BigEndian>::new(wtr);
+///     for &n in values {
+///         sink.feed(n).await?;
+///     }
+///     sink.close().await
+/// }
+/// ```
+pub struct NumSink<W, T, E> {
+    wtr: W,
+    buf: Vec<u8>,
+    pos: usize,
+    _marker: PhantomData<fn(T) -> E>,
+}
+
+impl<W, T: ToWire, E: ByteOrder> NumSink<W, T, E> {
+    /// Creates a sink that writes values to `wtr`.
+    pub fn new(wtr: W) -> NumSink<W, T, E> {
+        NumSink { wtr, buf: vec![], pos: 0, _marker: PhantomData }
+    }
+}
+
+impl<W, T, E> NumSink<W, T, E> {
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.wtr
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Values that were sent but not flushed yet are written after anything
+    /// written to it directly.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.wtr
+    }
+
+    /// Returns the underlying writer.
+    ///
+    /// Values that were sent but not flushed yet are lost.
+    pub fn into_inner(self) -> W {
+        self.wtr
+    }
+}
+
+impl<W: AsyncWrite + Unpin, T, E> NumSink<W, T, E> {
+    /// Writes all of the buffered bytes to the underlying writer.
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.pos < self.buf.len() {
+            let buf = &self.buf[self.pos..];
+            match poll_write_some(Pin::new(&mut self.wtr), cx, buf) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Ready(Ok(0)) => return Poll::Ready(Err(write_zero())),
+                Poll::Ready(Ok(n)) => self.pos += n,
+            }
+        }
+        self.buf.clear();
+        self.pos = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W, T, E> core::fmt::Debug for NumSink<W, T, E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("NumSink")
+            .field("buffered", &(self.buf.len() - self.pos))
+            .finish()
+    }
+}
+
+impl<W, T, E> Sink<T> for NumSink<W, T, E>
+where
+    W: AsyncWrite + Unpin,
+    T: ToWire,
+    E: ByteOrder,
+{
+    type Error = io::Error;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let me = self.get_mut();
+        if me.buf.len() < crate::io::STAGING_SIZE {
+            return Poll::Ready(Ok(()));
+        }
+        me.poll_drain(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> io::Result<()> {
+        let me = self.get_mut();
+        let start = me.buf.len();
+        me.buf.resize(start + T::WIRE_SIZE, 0);
+        item.to_wire::<E>(&mut me.buf[start..]).map_err(|err| {
+            me.buf.truncate(start);
+            io::Error::from(err)
+        })
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let me = self.get_mut();
+        match me.poll_drain(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut me.wtr).poll_flush(cx),
+            poll => poll,
+        }
+    }
+
+    fn poll_close(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let me = self.get_mut();
+        match me.poll_drain(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut me.wtr).poll_close(cx),
+            poll => poll,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use futures::{SinkExt, StreamExt};
+    use futures_io::{AsyncRead, AsyncWrite};
+
+    use super::{
+        AsyncReadBytesExt, AsyncWriteBytesExt, NumSink, NumStream,
+        ResumableRead,
+    };
+    use crate::{
+        async_methods::{block_on, poll_once},
+        BigEndian, LittleEndian,
+    };
+
+    /// A reader and writer that alternates between returning `Pending` and
+    /// moving a single byte, to exercise partial reads and writes. Writes
+    /// fail to make progress once `max` bytes were written, unless `max` is
+    /// zero.
+    #[derive(Default)]
+    struct Trickle {
+        data: Vec<u8>,
+        pos: usize,
+        max: usize,
+        pending: bool,
+    }
+
+    impl Trickle {
+        fn poll_step(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Poll::Ready(())
+        }
+    }
+
+    impl AsyncRead for Trickle {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            if self.poll_step(cx).is_pending() {
+                return Poll::Pending;
+            }
+            match self.data.get(self.pos) {
+                None => Poll::Ready(Ok(0)),
+                Some(&b) => {
+                    buf[0] = b;
+                    self.pos += 1;
+                    Poll::Ready(Ok(1))
+                }
+            }
+        }
+    }
+
+    impl AsyncWrite for Trickle {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            if self.poll_step(cx).is_pending() {
+                return Poll::Pending;
+            }
+            if self.max != 0 && self.data.len() == self.max {
+                return Poll::Ready(Ok(0));
+            }
+            self.data.push(buf[0]);
+            Poll::Ready(Ok(1))
+        }
+
+        fn poll_flush(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn reads_numbers() {
+        let data = [0xFF, 1, 2, 3, 4, 5, 6, 0, 0, 0x80, 0x3F, 7, 8];
+        let mut rdr = &data[..];
+        assert_eq!(-1, block_on(rdr.read_i8()).unwrap());
+        assert_eq!(0x010203, block_on(rdr.read_u24::<BigEndian>()).unwrap());
+        assert_eq!(
+            0x060504,
+            block_on(rdr.read_uint::<LittleEndian>(3)).unwrap()
+        );
+        assert_eq!(1.0, block_on(rdr.read_f32::<LittleEndian>()).unwrap());
+        assert_eq!(0x0708, block_on(rdr.read_i16::<BigEndian>()).unwrap());
+        let err = block_on(rdr.read_u8()).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn writes_numbers() {
+        let mut wtr = vec![];
+        block_on(wtr.write_i8(-1)).unwrap();
+        block_on(wtr.write_u24::<BigEndian>(0x010203)).unwrap();
+        block_on(wtr.write_int::<LittleEndian>(-2, 3)).unwrap();
+        assert_eq!(wtr, [0xFF, 1, 2, 3, 0xFE, 0xFF, 0xFF]);
+
+        let mut wtr = Trickle { max: 3, ..Trickle::default() };
+        let err = block_on(wtr.write_u32::<BigEndian>(1)).unwrap_err();
+        assert_eq!(3, wtr.data.len());
+        assert_eq!(io::ErrorKind::WriteZero, err.kind());
+    }
+
+    #[test]
+    fn partial_roundtrip() {
+        let mut stream = Trickle::default();
+        block_on(stream.write_u128::<LittleEndian>(u128::MAX - 1)).unwrap();
+        block_on(stream.write_f64::<BigEndian>(-0.5)).unwrap();
+        assert_eq!(24, stream.data.len());
+        assert_eq!(
+            u128::MAX - 1,
+            block_on(stream.read_u128::<LittleEndian>()).unwrap()
+        );
+        assert_eq!(-0.5, block_on(stream.read_f64::<BigEndian>()).unwrap());
+        let err = block_on(stream.read_u16::<BigEndian>()).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn reads_varints() {
+        let mut data = vec![0xE5, 0x8E, 0x26, 0x03];
+        data.extend_from_slice(&[0xFF; 18]);
+        data.push(0x03);
+        // Too long: 10 bytes that don't end the varint, then one more.
+        data.extend_from_slice(&[0x80; 10]);
+        data.push(0x00);
+        let mut rdr = Trickle { data, ..Trickle::default() };
+        assert_eq!(624485, block_on(rdr.read_uleb128()).unwrap());
+        assert_eq!(-2, block_on(rdr.read_varint_zigzag()).unwrap());
+        assert_eq!(u128::MAX, block_on(rdr.read_uleb128_u128()).unwrap());
+        let err = block_on(rdr.read_varint_u64()).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        // Only the maximum number of bytes were read.
+        assert_eq!(0, block_on(rdr.read_varint_zigzag_i128()).unwrap());
+        let err = block_on(rdr.read_uleb128()).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn bulk() {
+        // Bigger than the staging buffer, so it is written in chunks.
+        let src: Vec<u32> =
+            (0..3000).map(|i: u32| i.wrapping_mul(0x0101_0101)).collect();
+        let mut wtr = vec![];
+        block_on(wtr.write_u32_from::<BigEndian>(&src)).unwrap();
+        block_on(wtr.write_i8_from(&[-1, 2])).unwrap();
+        block_on(wtr.write_f64_from::<LittleEndian>(&[1.5, -0.0])).unwrap();
+        assert_eq!(12018, wtr.len());
+        assert_eq!(&[0, 0, 0, 0, 1, 1, 1, 1], &wtr[..8]);
+
+        let mut rdr = &wtr[..];
+        let mut dst = vec![0; 3000];
+        block_on(rdr.read_u32_into::<BigEndian>(&mut dst)).unwrap();
+        assert_eq!(src, dst);
+        let mut dst = [0; 2];
+        block_on(rdr.read_i8_into(&mut dst)).unwrap();
+        assert_eq!([-1, 2], dst);
+        let mut rdr = Trickle { data: rdr.to_vec(), ..Trickle::default() };
+        let mut dst = [0.0; 2];
+        block_on(rdr.read_f64_into::<LittleEndian>(&mut dst)).unwrap();
+        assert_eq!([1.5, -0.0], dst);
+        let err =
+            block_on(rdr.read_i16_into::<BigEndian>(&mut [0; 1])).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn resumable_reads() {
+        let data = [1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 0xFF];
+        let mut rdr = Trickle { data: data.to_vec(), ..Trickle::default() };
+        let mut next = ResumableRead::<u32>::new::<BigEndian>();
+        // The first poll reads nothing, and every later one reads a byte
+        // before the reader returns `Pending` again. Each future is dropped
+        // after one poll.
+        for _ in 0..4 {
+            assert!(poll_once(next.read(&mut rdr)).is_pending());
+        }
+        assert_eq!(3, next.filled());
+        assert_eq!(0x01020304, block_on(next.read(&mut rdr)).unwrap());
+        assert_eq!(0, next.filled());
+        assert_eq!(0x05060708, block_on(next.read(&mut rdr)).unwrap());
+
+        let mut pair = ResumableRead::<[u8; 2]>::new::<BigEndian>();
+        assert_eq!([0, 1], block_on(pair.read(&mut rdr)).unwrap());
+        let err = block_on(pair.read(&mut rdr)).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+        assert_eq!(1, pair.filled());
+        pair.reset();
+        assert_eq!(0, pair.filled());
+    }
+
+    #[test]
+    fn streams() {
+        let data = vec![0, 1, 0xFF, 0xFE, 0, 3];
+        let rdr = Trickle { data: data.clone(), ..Trickle::default() };
+        let mut stream = NumStream::<_, i16, BigEndian>::new(rdr);
+        let got: Vec<i16> = block_on(async {
+            let mut got = vec![];
+            while let Some(n) = stream.next().await {
+                got.push(n.unwrap());
+            }
+            got
+        });
+        assert_eq!(vec![1, -2, 3], got);
+
+        let rdr = Trickle { data: data[..5].to_vec(), ..Trickle::default() };
+        let mut stream = NumStream::<_, u16, LittleEndian>::new(rdr);
+        assert_eq!(0x0100, block_on(stream.next()).unwrap().unwrap());
+        assert_eq!(0xFEFF, block_on(stream.next()).unwrap().unwrap());
+        let err = block_on(stream.next()).unwrap().unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn sinks() {
+        let mut sink = NumSink::<_, u16, BigEndian>::new(Trickle::default());
+        block_on(async {
+            for n in 0..3000u16 {
+                sink.feed(n).await.unwrap();
+            }
+            sink.close().await.unwrap();
+        });
+        let data = sink.into_inner().data;
+        assert_eq!(6000, data.len());
+        let mut rdr = &data[..];
+        for n in 0..3000u16 {
+            assert_eq!(n, block_on(rdr.read_u16::<BigEndian>()).unwrap());
+        }
+
+        let wtr = Trickle { max: 3, ..Trickle::default() };
+        let mut sink = NumSink::<_, f32, LittleEndian>::new(wtr);
+        block_on(sink.feed(1.0)).unwrap();
+        assert_eq!(0, sink.get_ref().data.len());
+        let err = block_on(sink.flush()).unwrap_err();
+        assert_eq!(io::ErrorKind::WriteZero, err.kind());
+        assert_eq!(vec![0, 0, 0x80], sink.into_inner().data);
+    }
+}
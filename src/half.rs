@@ -0,0 +1,153 @@
+// Conversions between IEEE754 half-precision floats, represented by their
+// bits, and `f32`. Every half-precision value is exactly representable as an
+// `f32`, so widening is lossless. Narrowing rounds to the nearest value, with
+// ties going to the value with an even significand, just like the hardware
+// conversion instructions do.
+
+/// Converts the bits of a half-precision float to an `f32`.
+pub(crate) fn to_f32(h: u16) -> f32 {
+    let h = u32::from(h);
+    let sign = (h & 0x8000) << 16;
+    let exp = (h >> 10) & 0x1F;
+    let man = h & 0x3FF;
+    let bits = if exp == 0x1F {
+        // Infinity or NaN. The NaN payload is kept in the high bits.
+        sign | 0x7F80_0000 | (man << 13)
+    } else if exp != 0 {
+        sign | ((exp + 127 - 15) << 23) | (man << 13)
+    } else if man == 0 {
+        sign
+    } else {
+        // A subnormal half is a normal f32. Shift the significand up until
+        // its implicit leading bit is set, adjusting the exponent to match.
+        let mut exp = 127 - 15 + 1;
+        let mut man = man;
+        while man & 0x400 == 0 {
+            man <<= 1;
+            exp -= 1;
+        }
+        sign | (exp << 23) | ((man & 0x3FF) << 13)
+    };
+    f32::from_bits(bits)
+}
+
+/// Converts an `f32` to the bits of the nearest half-precision float.
+///
+/// Values too large for a half become infinity, and values too small become
+/// zero, keeping their sign. NaNs stay NaNs.
+pub(crate) fn from_f32(x: f32) -> u16 {
+    let x = x.to_bits();
+    let sign = ((x >> 16) & 0x8000) as u16;
+    let exp = ((x >> 23) & 0xFF) as i32;
+    let man = x & 0x7F_FFFF;
+    if exp == 0xFF {
+        if man == 0 {
+            return sign | 0x7C00;
+        }
+        // Keep the high bits of the payload, and make sure the result is
+        // still a NaN by setting the quiet bit.
+        return sign | 0x7E00 | (man >> 13) as u16;
+    }
+
+    let exp = exp - 127 + 15;
+    if exp >= 0x1F {
+        return sign | 0x7C00;
+    }
+    if exp <= 0 {
+        // The result is subnormal, or rounds to zero.
+        if exp < -10 {
+            return sign;
+        }
+        let man = man | 0x80_0000;
+        let shift = (14 - exp) as u32;
+        let half = man >> shift;
+        let rest = man & ((1 << shift) - 1);
+        return sign | round(half, rest, 1 << (shift - 1)) as u16;
+    }
+    // A carry out of the significand correctly bumps the exponent, and a
+    // carry out of the largest exponent correctly produces infinity.
+    let half = ((exp as u32) << 10) | (man >> 13);
+    sign | round(half, man & 0x1FFF, 0x1000) as u16
+}
+
+/// Rounds the truncated value `half` to nearest, ties to even, where `rest`
+/// holds the discarded bits and `halfway` is half of one unit in `half`.
+fn round(half: u32, rest: u32, halfway: u32) -> u32 {
+    if rest > halfway || (rest == halfway && half & 1 == 1) {
+        half + 1
+    } else {
+        half
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_f32, to_f32};
+
+    /// Returns 2 raised to the power of `exp`, which must be in the range
+    /// of normal `f32` exponents.
+    fn pow2(exp: i32) -> f32 {
+        f32::from_bits(((exp + 127) as u32) << 23)
+    }
+
+    #[test]
+    fn known_values() {
+        let cases = [
+            (0x0000, 0.0),
+            (0x8000, -0.0),
+            (0x3C00, 1.0),
+            (0xC000, -2.0),
+            (0x3555, 0.333_251_95),
+            (0x7BFF, 65504.0),
+            (0x0400, pow2(-14)),
+            (0x0001, pow2(-24)),
+            (0x03FF, 1023.0 * pow2(-24)),
+            (0x7C00, f32::INFINITY),
+            (0xFC00, f32::NEG_INFINITY),
+        ];
+        for &(bits, x) in cases.iter() {
+            assert_eq!(x.to_bits(), to_f32(bits).to_bits(), "{:04X}", bits);
+            assert_eq!(bits, from_f32(x), "{}", x);
+        }
+    }
+
+    #[test]
+    fn rounding() {
+        // Ties go to even.
+        assert_eq!(0x3C00, from_f32(1.0 + pow2(-11)));
+        assert_eq!(0x3C02, from_f32(1.0 + 3.0 * pow2(-11)));
+        // Just above a tie rounds up.
+        assert_eq!(0x3C01, from_f32(1.0 + pow2(-11) + pow2(-20)));
+        assert_eq!(0x2E66, from_f32(0.1));
+        // Overflow and underflow.
+        assert_eq!(0x7BFF, from_f32(65519.0));
+        assert_eq!(0x7C00, from_f32(65520.0));
+        assert_eq!(0xFC00, from_f32(-1e10));
+        assert_eq!(0x0000, from_f32(pow2(-25)));
+        assert_eq!(0x0001, from_f32(pow2(-25) + pow2(-30)));
+        assert_eq!(0x8000, from_f32(-1e-10));
+        // Rounding carries from the subnormals into the normals.
+        assert_eq!(0x0400, from_f32(pow2(-14) - pow2(-25)));
+    }
+
+    #[test]
+    fn nan() {
+        assert!(to_f32(0x7E00).is_nan());
+        assert!(to_f32(0x7C01).is_nan());
+        assert_eq!(0x7E00, from_f32(f32::NAN) & 0x7E00);
+        // A NaN whose payload is entirely in the low bits stays a NaN.
+        assert!(to_f32(from_f32(f32::from_bits(0x7F80_0001))).is_nan());
+    }
+
+    #[test]
+    fn every_half_roundtrips() {
+        for bits in 0..=u16::MAX {
+            let x = to_f32(bits);
+            if x.is_nan() {
+                assert!(to_f32(from_f32(x)).is_nan());
+            } else {
+                assert_eq!(bits, from_f32(x), "{:04X}", bits);
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+/*!
+Writing numbers to the fixed capacity vectors of `heapless`.
+
+Firmware that can't allocate often serializes messages into a
+`heapless::Vec<u8, N>`, whose capacity is fixed at compile time. The
+[`VecExt`] trait in this module appends numbers to one, in either byte order,
+and returns an error of kind [`ErrorKind::BufferTooSmall`] when a number
+doesn't fit, instead of panicking. A write that fails appends nothing, so the
+vector always holds whole values:
+
+```rust
+use byteorder::{heapless::VecExt, BigEndian, ErrorKind};
+use heapless::Vec;
+
+let mut msg: Vec<u8, 6> = Vec::new();
+msg.write_u16::<BigEndian>(0xCAFE).unwrap();
+msg.write_i24::<BigEndian>(-2).unwrap();
+let err = msg.write_u16::<BigEndian>(1).unwrap_err();
+assert_eq!(&ErrorKind::BufferTooSmall { needed: 2, available: 1 }, err.kind());
+assert_eq!(&[0xCA, 0xFE, 0xFF, 0xFF, 0xFE], &msg[..]);
+```
+
+To write to a plain byte slice instead, use
+[`SliceWriter`](crate::SliceWriter).
+
+This module requires the `heapless` feature, which does not require `std`.
+*/
+
+use ::heapless::Vec;
+
+use crate::{varint, wire::ToWire, ByteOrder, Error, ErrorKind};
+
+macro_rules! write_methods {
+    ($($name:ident($ty:ty, $size:expr, $desc:expr);)*) => {$(
+        #[doc = concat!("Appends ", $desc, ".")]
+        #[inline]
+        fn $name<E: ByteOrder>(&mut self, n: $ty) -> Result<(), Error> {
+            let mut buf = [0; $size];
+            E::$name(&mut buf, n);
+            self.write_bytes(&buf)
+        }
+    )*};
+}
+
+/// Extends `heapless::Vec<u8, N>` with methods for appending numbers.
+///
+/// Every method appends all of its bytes or none of them, and returns an
+/// error of kind [`ErrorKind::BufferTooSmall`] when they don't fit in the
+/// rest of the vector's capacity. See the [module documentation](self) for
+/// details.
+///
+/// This trait is sealed, and can't be implemented outside of this crate.
+pub trait VecExt: crate::private::Sealed {
+    /// Appends all of `bytes`.
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error>;
+
+    /// Appends an unsigned 8 bit integer.
+    #[inline]
+    fn write_u8(&mut self, n: u8) -> Result<(), Error> {
+        self.write_bytes(&[n])
+    }
+
+    /// Appends a signed 8 bit integer.
+    #[inline]
+    fn write_i8(&mut self, n: i8) -> Result<(), Error> {
+        self.write_bytes(&[n as u8])
+    }
+
+    /// Appends a boolean as `0` or `1`.
+    #[inline]
+    fn write_bool(&mut self, b: bool) -> Result<(), Error> {
+        self.write_bytes(&[u8::from(b)])
+    }
+
+    write_methods! {
+        write_u16(u16, 2, "an unsigned 16 bit integer");
+        write_i16(i16, 2, "a signed 16 bit integer");
+        write_u24(u32, 3, "an unsigned 24 bit integer");
+        write_i24(i32, 3, "a signed 24 bit integer");
+        write_u32(u32, 4, "an unsigned 32 bit integer");
+        write_i32(i32, 4, "a signed 32 bit integer");
+        write_u48(u64, 6, "an unsigned 48 bit integer");
+        write_i48(i64, 6, "a signed 48 bit integer");
+        write_u64(u64, 8, "an unsigned 64 bit integer");
+        write_i64(i64, 8, "a signed 64 bit integer");
+        write_u128(u128, 16, "an unsigned 128 bit integer");
+        write_i128(i128, 16, "a signed 128 bit integer");
+        write_f16(f32, 2, "an `f32` as an IEEE754 half-precision float");
+        write_f32(f32, 4, "a IEEE754 single-precision floating point number");
+        write_f64(f64, 8, "a IEEE754 double-precision floating point number");
+    }
+
+    /// Appends a Unicode scalar value as a 32 bit integer.
+    #[inline]
+    fn write_char<E: ByteOrder>(&mut self, c: char) -> Result<(), Error> {
+        self.write_u32::<E>(u32::from(c))
+    }
+
+    /// Appends an unsigned n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes > 8` or when `n` is not representable in
+    /// `nbytes` bytes.
+    #[inline]
+    fn write_uint<E: ByteOrder>(
+        &mut self,
+        n: u64,
+        nbytes: usize,
+    ) -> Result<(), Error> {
+        let mut buf = [0; 8];
+        E::write_uint(&mut buf, n, nbytes);
+        self.write_bytes(&buf[..nbytes])
+    }
+
+    /// Appends a signed n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes > 8` or when `n` is not representable in
+    /// `nbytes` bytes.
+    #[inline]
+    fn write_int<E: ByteOrder>(
+        &mut self,
+        n: i64,
+        nbytes: usize,
+    ) -> Result<(), Error> {
+        let mut buf = [0; 8];
+        E::write_int(&mut buf, n, nbytes);
+        self.write_bytes(&buf[..nbytes])
+    }
+
+    /// Appends an unsigned n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes > 16` or when `n` is not representable in
+    /// `nbytes` bytes.
+    #[inline]
+    fn write_uint128<E: ByteOrder>(
+        &mut self,
+        n: u128,
+        nbytes: usize,
+    ) -> Result<(), Error> {
+        let mut buf = [0; 16];
+        E::write_uint128(&mut buf, n, nbytes);
+        self.write_bytes(&buf[..nbytes])
+    }
+
+    /// Appends a signed n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes > 16` or when `n` is not representable in
+    /// `nbytes` bytes.
+    #[inline]
+    fn write_int128<E: ByteOrder>(
+        &mut self,
+        n: i128,
+        nbytes: usize,
+    ) -> Result<(), Error> {
+        let mut buf = [0; 16];
+        E::write_int128(&mut buf, n, nbytes);
+        self.write_bytes(&buf[..nbytes])
+    }
+
+    /// Appends a value of any type implementing [`ToWire`].
+    ///
+    /// Exactly `T::WIRE_SIZE` bytes are appended, and only when the value is
+    /// encoded successfully.
+    fn write_wire<T: ToWire, E: ByteOrder>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Error>;
+
+    /// Appends an unsigned LEB128 integer, in its shortest encoding.
+    #[inline]
+    fn write_uleb128(&mut self, n: u64) -> Result<(), Error> {
+        let mut buf = [0; varint::MAX_ULEB128_LEN];
+        let len = varint::encode_uleb128(n, &mut buf)?;
+        self.write_bytes(&buf[..len])
+    }
+
+    /// Appends a signed variable length integer, encoded with ZigZag and then
+    /// as unsigned LEB128.
+    #[inline]
+    fn write_varint_zigzag(&mut self, n: i64) -> Result<(), Error> {
+        self.write_uleb128(varint::zigzag_encode_i64(n))
+    }
+
+    /// Appends an unsigned LEB128 integer of up to 128 bits, in its shortest
+    /// encoding.
+    #[inline]
+    fn write_uleb128_u128(&mut self, n: u128) -> Result<(), Error> {
+        let mut buf = [0; varint::MAX_ULEB128_U128_LEN];
+        let len = varint::encode_uleb128_u128(n, &mut buf)?;
+        self.write_bytes(&buf[..len])
+    }
+
+    /// Appends a signed variable length integer of up to 128 bits, encoded
+    /// with ZigZag and then as unsigned LEB128.
+    #[inline]
+    fn write_varint_zigzag_i128(&mut self, n: i128) -> Result<(), Error> {
+        self.write_uleb128_u128(varint::zigzag_encode_i128(n))
+    }
+}
+
+impl<const N: usize> crate::private::Sealed for Vec<u8, N> {}
+
+impl<const N: usize> VecExt for Vec<u8, N> {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let available = N - self.len();
+        self.extend_from_slice(bytes).map_err(|()| {
+            Error::new(ErrorKind::BufferTooSmall {
+                needed: bytes.len(),
+                available,
+            })
+        })
+    }
+
+    #[inline]
+    fn write_wire<T: ToWire, E: ByteOrder>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Error> {
+        let start = self.len();
+        // The value is encoded in place, and the bytes are dropped again
+        // when encoding fails.
+        self.resize_default(start + T::WIRE_SIZE).map_err(|()| {
+            Error::new(ErrorKind::BufferTooSmall {
+                needed: T::WIRE_SIZE,
+                available: N - start,
+            })
+        })?;
+        let result = value.to_wire::<E>(&mut self[start..]);
+        if result.is_err() {
+            self.truncate(start);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use heapless::Vec;
+
+    use super::VecExt;
+    use crate::{BigEndian, Error, ErrorKind, LittleEndian};
+
+    #[test]
+    fn appends() {
+        let mut buf: Vec<u8, 32> = Vec::new();
+        buf.write_i8(-1).unwrap();
+        buf.write_u24::<BigEndian>(0x010203).unwrap();
+        buf.write_uint::<LittleEndian>(0x060504, 3).unwrap();
+        buf.write_f32::<LittleEndian>(1.0).unwrap();
+        buf.write_wire::<[u8; 2], BigEndian>(&[7, 8]).unwrap();
+        buf.write_bool(true).unwrap();
+        buf.write_uleb128(300).unwrap();
+        buf.write_varint_zigzag(-2).unwrap();
+        assert_eq!(
+            &[0xFF, 1, 2, 3, 4, 5, 6, 0, 0, 0x80, 0x3F, 7, 8, 1, 0xAC, 2, 3],
+            &buf[..]
+        );
+    }
+
+    #[test]
+    fn full_vec_is_unchanged() {
+        let mut buf: Vec<u8, 3> = Vec::new();
+        buf.write_u8(1).unwrap();
+        assert_eq!(
+            Err(Error::new(ErrorKind::BufferTooSmall {
+                needed: 4,
+                available: 2
+            })),
+            buf.write_u32::<BigEndian>(0)
+        );
+        assert!(buf.write_uint128::<BigEndian>(0, 3).is_err());
+        assert!(buf.write_wire::<[u16; 2], BigEndian>(&[0, 0]).is_err());
+        assert!(buf.write_uleb128(u64::MAX).is_err());
+        assert_eq!(&[1], &buf[..]);
+        buf.write_u16::<LittleEndian>(0x0302).unwrap();
+        assert!(buf.write_u8(4).is_err());
+        assert_eq!(&[1, 2, 3], &buf[..]);
+    }
+}
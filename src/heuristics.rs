@@ -0,0 +1,305 @@
+/*!
+Heuristics for guessing how unknown binary data is laid out.
+
+When probing a blob of unknown provenance, it is often useful to know whether
+it looks like a sequence of 16 or 32 bit numbers, and if so, in which byte
+order. Real world numeric data rarely uses the full range of its type, so the
+most significant bytes of each element are much more predictable than the
+least significant ones. Comparing the byte value distribution at each
+position within an element exposes both the element width and the byte
+order.
+
+These are heuristics. They work well on things like audio samples, counters
+and offsets, and they will happily produce nonsense for compressed or
+encrypted data, which has no structure to find. Always treat the result as a
+hint.
+
+# Examples
+
+```rust
+use byteorder::{heuristics, BigEndian, ByteOrder, Endianness};
+
+// Small values, like offsets into a file, stored as big-endian u16s.
+let numbers: Vec<u16> = (0..500).map(|i| (i * 37) % 1000).collect();
+let mut data = vec![0; 2 * numbers.len()];
+BigEndian::write_u16_into(&numbers, &mut data);
+
+let guesses = heuristics::guess_element_width_and_endianness(&data);
+assert_eq!(2, guesses[0].width);
+assert_eq!(Endianness::Big, guesses[0].endianness);
+```
+*/
+
+use alloc::{vec, vec::Vec};
+
+use crate::Endianness;
+
+/// The element widths, in bytes, considered when guessing.
+const WIDTHS: [usize; 2] = [2, 4];
+
+/// A single guess at the layout of some data.
+///
+/// Guesses are produced by [`guess_element_width_and_endianness`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Guess {
+    /// The size, in bytes, of each element.
+    pub width: usize,
+    /// The byte order of each element.
+    pub endianness: Endianness,
+    /// How well the data fits this guess, from `0.0` (no evidence at all)
+    /// to `1.0` (the most significant bytes are constant while the least
+    /// significant bytes are uniformly distributed).
+    pub score: f64,
+}
+
+/// Returns the number of occurrences of each byte value in `data`.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::heuristics::byte_histogram;
+///
+/// let hist = byte_histogram(b"abca");
+/// assert_eq!(2, hist[b'a' as usize]);
+/// assert_eq!(1, hist[b'b' as usize]);
+/// assert_eq!(0, hist[b'd' as usize]);
+/// ```
+pub fn byte_histogram(data: &[u8]) -> [u64; 256] {
+    let mut hist = [0; 256];
+    for &b in data {
+        hist[b as usize] += 1;
+    }
+    hist
+}
+
+/// Returns the Shannon entropy, in bits per byte, of the distribution
+/// described by `histogram`.
+///
+/// The result is between `0.0` (every byte has the same value) and `8.0`
+/// (every byte value is equally likely). An empty histogram has an entropy
+/// of `0.0`.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::heuristics::{byte_histogram, entropy};
+///
+/// assert_eq!(0.0, entropy(&byte_histogram(b"aaaa")));
+/// assert_eq!(1.0, entropy(&byte_histogram(b"abab")));
+///
+/// let all: Vec<u8> = (0..=255).collect();
+/// assert_eq!(8.0, entropy(&byte_histogram(&all)));
+/// ```
+pub fn entropy(histogram: &[u64; 256]) -> f64 {
+    let total: u64 = histogram.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    let total = total as f64;
+    let mut bits = 0.0;
+    for &count in histogram.iter().filter(|&&count| count > 0) {
+        let p = count as f64 / total;
+        bits -= p * log2(p);
+    }
+    bits
+}
+
+/// Returns one histogram for each byte position within elements of `width`
+/// bytes.
+///
+/// The histogram at index `i` counts the values of byte `i` of every
+/// complete element in `data`. Any trailing bytes that do not form a
+/// complete element are ignored.
+///
+/// # Panics
+///
+/// Panics when `width == 0`.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::heuristics::position_histograms;
+///
+/// let hists = position_histograms(&[0, 1, 0, 2, 0], 2);
+/// assert_eq!(2, hists.len());
+/// assert_eq!(2, hists[0][0]);
+/// assert_eq!(1, hists[1][1]);
+/// assert_eq!(1, hists[1][2]);
+/// ```
+pub fn position_histograms(data: &[u8], width: usize) -> Vec<[u64; 256]> {
+    assert!(width > 0, "element width must be non-zero");
+    let mut hists = vec![[0; 256]; width];
+    for element in data.chunks_exact(width) {
+        for (hist, &b) in hists.iter_mut().zip(element) {
+            hist[b as usize] += 1;
+        }
+    }
+    hists
+}
+
+/// Guesses the element width and byte order of `data`, best guess first.
+///
+/// A guess is returned for each supported combination of element width
+/// (currently 2 and 4 bytes) and byte order, as long as `data` contains at
+/// least two complete elements of that width. The score of each guess
+/// measures how much more predictable the bytes in the most significant half
+/// of each element are than the bytes in the least significant half. Data
+/// without any such structure gets a score near zero for every guess.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{heuristics, ByteOrder, Endianness, LittleEndian};
+///
+/// let numbers: Vec<u32> = (0..500).map(|i| (i * 7919) % 100_000).collect();
+/// let mut data = vec![0; 4 * numbers.len()];
+/// LittleEndian::write_u32_into(&numbers, &mut data);
+///
+/// let best = heuristics::guess_element_width_and_endianness(&data)[0];
+/// assert_eq!(4, best.width);
+/// assert_eq!(Endianness::Little, best.endianness);
+/// assert!(best.score > 0.5);
+/// ```
+pub fn guess_element_width_and_endianness(data: &[u8]) -> Vec<Guess> {
+    let mut guesses = vec![];
+    for &width in WIDTHS.iter() {
+        if data.len() / width < 2 {
+            continue;
+        }
+        let entropies: Vec<f64> =
+            position_histograms(data, width).iter().map(entropy).collect();
+        let half = width / 2;
+        let first = mean(&entropies[..half]);
+        let last = mean(&entropies[half..]);
+        // The most significant half is the predictable one, so if the first
+        // half has the lower entropy, the data looks big-endian.
+        guesses.push(Guess {
+            width,
+            endianness: Endianness::Big,
+            score: ((last - first) / 8.0).max(0.0),
+        });
+        guesses.push(Guess {
+            width,
+            endianness: Endianness::Little,
+            score: ((first - last) / 8.0).max(0.0),
+        });
+    }
+    // Scores are never NaN, so the comparison can't fail.
+    guesses.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+    guesses
+}
+
+fn mean(xs: &[f64]) -> f64 {
+    xs.iter().sum::<f64>() / xs.len() as f64
+}
+
+/// Returns the base 2 logarithm of `x`, which must be positive and normal.
+///
+/// `f64::log2` needs `std`, so this splits `x` into its exponent and a
+/// mantissa `m` in `[1, 2)`, and computes `ln(m)` as `2 atanh(z)` with
+/// `z = (m - 1) / (m + 1)`. Since `z <= 1/3`, the series for `atanh`
+/// converges to full precision within 20 terms. Powers of two are exact.
+fn log2(x: f64) -> f64 {
+    let bits = x.to_bits();
+    let exp = ((bits >> 52) & 0x7FF) as i32 - 1023;
+    let m = f64::from_bits((bits & ((1 << 52) - 1)) | (1023 << 52));
+    let z = (m - 1.0) / (m + 1.0);
+    let z2 = z * z;
+    let (mut sum, mut term) = (0.0, z);
+    for k in 0..20 {
+        sum += term / f64::from(2 * k + 1);
+        term *= z2;
+    }
+    f64::from(exp) + 2.0 * sum * core::f64::consts::LOG2_E
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::{guess_element_width_and_endianness as guess, log2};
+    use crate::{BigEndian, ByteOrder, Endianness, LittleEndian};
+
+    fn samples16<E: ByteOrder>() -> Vec<u8> {
+        let numbers: Vec<i16> =
+            (0..2000).map(|i| ((i * 31) % 700) as i16 - 350).collect();
+        let mut data = vec![0; 2 * numbers.len()];
+        E::write_i16_into(&numbers, &mut data);
+        data
+    }
+
+    fn samples32<E: ByteOrder>() -> Vec<u8> {
+        let numbers: Vec<u32> =
+            (0..2000).map(|i| (i * 104_729) % 300_000).collect();
+        let mut data = vec![0; 4 * numbers.len()];
+        E::write_u32_into(&numbers, &mut data);
+        data
+    }
+
+    /// A simple xorshift generator, so the test is deterministic.
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 56) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn signed_16_bit_samples() {
+        let best = guess(&samples16::<BigEndian>())[0];
+        assert_eq!((2, Endianness::Big), (best.width, best.endianness));
+
+        let best = guess(&samples16::<LittleEndian>())[0];
+        assert_eq!((2, Endianness::Little), (best.width, best.endianness));
+    }
+
+    #[test]
+    fn unsigned_32_bit_samples() {
+        let best = guess(&samples32::<BigEndian>())[0];
+        assert_eq!((4, Endianness::Big), (best.width, best.endianness));
+
+        let best = guess(&samples32::<LittleEndian>())[0];
+        assert_eq!((4, Endianness::Little), (best.width, best.endianness));
+    }
+
+    #[test]
+    fn noise_has_no_structure() {
+        let guesses = guess(&noise(8192));
+        assert_eq!(4, guesses.len());
+        for g in guesses {
+            assert!(g.score < 0.05, "{:?}", g);
+        }
+    }
+
+    #[test]
+    fn too_short() {
+        assert!(guess(&[]).is_empty());
+        assert!(guess(&[1, 2, 3]).is_empty());
+        let guesses = guess(&[1, 2, 3, 4]);
+        assert_eq!(2, guesses.len());
+        assert!(guesses.iter().all(|g| g.width == 2));
+    }
+
+    #[test]
+    fn log2_without_std() {
+        assert_eq!(0.0, log2(1.0));
+        assert_eq!(-1.0, log2(0.5));
+        assert_eq!(-8.0, log2(1.0 / 256.0));
+        assert_eq!(10.0, log2(1024.0));
+        let cases = [
+            (0.3, -1.7369655941662063),
+            (0.75, -0.4150374992788438),
+            (1.0 / 3.0, -1.584962500721156),
+            (1.999, 0.9992784720825405),
+        ];
+        for &(x, expected) in cases.iter() {
+            let error = log2(x) - expected;
+            assert!(-1e-12 < error && error < 1e-12, "{}", x);
+        }
+    }
+}
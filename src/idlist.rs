@@ -0,0 +1,305 @@
+/*!
+A compact encoding for lists of IDs, like the postings of an inverted index.
+
+Sorted lists of IDs are best stored as the differences between consecutive
+IDs, which are small, as variable length integers, which makes small numbers
+short. This module does both in a single pass over the list. Each difference
+is also zig-zag encoded, so that lists that aren't sorted still round trip
+correctly, at the cost of a slightly larger encoding for them.
+
+# Format
+
+An encoded list is:
+
+* A single byte holding the format version, currently [`FORMAT_VERSION`].
+* The number of IDs, as a varint.
+* For each ID, the zig-zag encoding of the wrapping difference between it and
+  the previous ID (or zero, for the first ID), as a varint.
+
+Varints are unsigned LEB128, as implemented by the [`varint`] module: seven
+bits per byte, least significant group first, with the high bit of each byte
+set when more bytes follow. A varint is at most 10 bytes long.
+
+# Examples
+
+```rust
+use byteorder::idlist::{decode_id_list, encode_id_list};
+
+let ids = [3, 7, 8, 20, 1_000_000];
+let mut buf = vec![];
+encode_id_list(&ids, &mut buf);
+assert_eq!(9, buf.len());
+
+let (decoded, nread) = decode_id_list(&buf).unwrap();
+assert_eq!(&ids[..], &decoded[..]);
+assert_eq!(buf.len(), nread);
+```
+*/
+
+use alloc::vec::Vec;
+
+use crate::{varint, Error, ErrorKind};
+
+/// The version of the format written by [`encode_id_list`].
+pub const FORMAT_VERSION: u8 = 1;
+
+/// The maximum number of bytes in the encoding of a `u64` varint.
+const MAX_VARINT_LEN: usize = varint::MAX_ULEB128_LEN;
+
+/// Appends the encoding of `ids` to `out`.
+///
+/// The encoding is smallest when `ids` is sorted in ascending order with
+/// small gaps, but any list of IDs is encoded correctly.
+pub fn encode_id_list(ids: &[u64], out: &mut Vec<u8>) {
+    // Every ID takes at least one byte.
+    out.reserve(1 + MAX_VARINT_LEN + ids.len());
+    out.push(FORMAT_VERSION);
+    write_varint(out, ids.len() as u64);
+    let mut prev = 0u64;
+    for &id in ids {
+        write_varint(out, zigzag(id.wrapping_sub(prev) as i64));
+        prev = id;
+    }
+}
+
+/// Decodes a list of IDs from the start of `data`.
+///
+/// On success, the IDs are returned along with the number of bytes of `data`
+/// that were read. Any bytes after the list are ignored.
+///
+/// # Errors
+///
+/// See [`IdListDecoder`] for the errors that may occur.
+pub fn decode_id_list(data: &[u8]) -> Result<(Vec<u64>, usize), Error> {
+    let mut dec = IdListDecoder::new(data)?;
+    // The count can't be trusted, but every ID takes at least one byte.
+    let cap = dec.remaining().min((data.len() - dec.position()) as u64);
+    let mut ids = Vec::with_capacity(cap as usize);
+    for id in &mut dec {
+        ids.push(id?);
+    }
+    Ok((ids, dec.position()))
+}
+
+/// A streaming decoder for a list of IDs.
+///
+/// This is an iterator over the IDs in an encoded list, which avoids
+/// collecting them into a vector when they are only needed one at a time.
+///
+/// # Errors
+///
+/// Creating a decoder fails with [`ErrorKind::UnsupportedVersion`] if the
+/// version byte is not one this crate understands. Both creating a decoder
+/// and iterating it fail with [`ErrorKind::BufferTooSmall`] if the data ends
+/// early, in which case `needed` is a lower bound, and with
+/// [`ErrorKind::OutOfRange`] if a varint doesn't fit in a `u64`. After an
+/// error, the iterator yields nothing more.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::idlist::{encode_id_list, IdListDecoder};
+///
+/// let mut buf = vec![];
+/// encode_id_list(&[10, 11, 12], &mut buf);
+///
+/// let mut dec = IdListDecoder::new(&buf).unwrap();
+/// assert_eq!(3, dec.remaining());
+/// assert_eq!(Some(Ok(10)), dec.next());
+/// let sum: u64 = dec.map(|id| id.unwrap()).sum();
+/// assert_eq!(23, sum);
+/// ```
+#[derive(Clone, Debug)]
+pub struct IdListDecoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+    remaining: u64,
+    prev: u64,
+}
+
+impl<'a> IdListDecoder<'a> {
+    /// Creates a decoder for the list of IDs at the start of `data`, reading
+    /// its header.
+    pub fn new(data: &'a [u8]) -> Result<IdListDecoder<'a>, Error> {
+        Error::check_len(1, data.len())?;
+        if data[0] != FORMAT_VERSION {
+            return Err(Error::new(ErrorKind::UnsupportedVersion));
+        }
+        let (count, n) = read_varint(&data[1..])?;
+        Ok(IdListDecoder { data, pos: 1 + n, remaining: count, prev: 0 })
+    }
+
+    /// Returns the number of IDs that have not been decoded yet.
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// Returns the number of bytes of the data read so far.
+    ///
+    /// Once every ID has been decoded, this is the length of the encoded
+    /// list.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<'a> Iterator for IdListDecoder<'a> {
+    type Item = Result<u64, Error>;
+
+    fn next(&mut self) -> Option<Result<u64, Error>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        match read_varint(&self.data[self.pos..]) {
+            Ok((delta, n)) => {
+                self.pos += n;
+                self.remaining -= 1;
+                self.prev = self.prev.wrapping_add(unzigzag(delta) as u64);
+                Some(Ok(self.prev))
+            }
+            Err(err) => {
+                self.remaining = 0;
+                Some(Err(err))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Fewer IDs are yielded if the data is truncated.
+        (0, usize::try_from(self.remaining).ok())
+    }
+}
+
+fn zigzag(n: i64) -> u64 {
+    varint::zigzag_encode_i64(n)
+}
+
+fn unzigzag(n: u64) -> i64 {
+    varint::zigzag_decode_u64(n)
+}
+
+fn write_varint(out: &mut Vec<u8>, n: u64) {
+    let mut buf = [0; MAX_VARINT_LEN];
+    let len = varint::encode_uleb128(n, &mut buf).unwrap();
+    out.extend_from_slice(&buf[..len]);
+}
+
+/// Reads a varint from the start of `data`, returning it along with the
+/// number of bytes it occupied.
+fn read_varint(data: &[u8]) -> Result<(u64, usize), Error> {
+    varint::decode_uleb128(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::{
+        decode_id_list, encode_id_list, read_varint, unzigzag, zigzag,
+        IdListDecoder, FORMAT_VERSION,
+    };
+    use crate::{Error, ErrorKind};
+
+    fn roundtrip(ids: &[u64]) -> Vec<u8> {
+        let mut buf = vec![];
+        encode_id_list(ids, &mut buf);
+        let (got, nread) = decode_id_list(&buf).unwrap();
+        assert_eq!(ids, &got[..]);
+        assert_eq!(buf.len(), nread);
+        buf
+    }
+
+    #[test]
+    fn roundtrips() {
+        assert_eq!(vec![FORMAT_VERSION, 0], roundtrip(&[]));
+        roundtrip(&[0]);
+        roundtrip(&[u64::MAX, 0, u64::MAX, 1]);
+        roundtrip(&[5, 4, 3, 2, 1, 0]);
+        roundtrip(&[1 << 63, (1 << 63) - 1, 1 << 63]);
+
+        let dense: Vec<u64> = (1_000_000..1_001_000).collect();
+        let buf = roundtrip(&dense);
+        // Version, two bytes of count, three bytes for the first ID and then
+        // one byte for each gap of one.
+        assert_eq!(1 + 2 + 3 + 999, buf.len());
+    }
+
+    #[test]
+    fn zigzag_values() {
+        let cases =
+            [(0, 0), (-1, 1), (1, 2), (-2, 3), (i64::MAX, u64::MAX - 1)];
+        for &(n, z) in cases.iter() {
+            assert_eq!(z, zigzag(n));
+            assert_eq!(n, unzigzag(z));
+        }
+        assert_eq!(u64::MAX, zigzag(i64::MIN));
+        assert_eq!(i64::MIN, unzigzag(u64::MAX));
+    }
+
+    #[test]
+    fn varint_limits() {
+        let max = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+        assert_eq!(Ok((u64::MAX, 10)), read_varint(&max));
+
+        let mut overflow = max;
+        overflow[9] = 0x02;
+        assert_eq!(
+            Err(Error::new(ErrorKind::OutOfRange)),
+            read_varint(&overflow)
+        );
+        // Eleven bytes is too long, even if the value would fit.
+        let mut overlong = [0x80; 11];
+        overlong[10] = 0;
+        assert_eq!(
+            Err(Error::new(ErrorKind::OutOfRange)),
+            read_varint(&overlong)
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            ErrorKind::BufferTooSmall { needed: 1, available: 0 },
+            *decode_id_list(&[]).unwrap_err().kind()
+        );
+        assert_eq!(
+            ErrorKind::UnsupportedVersion,
+            *decode_id_list(&[2, 0]).unwrap_err().kind()
+        );
+
+        let mut buf = vec![];
+        encode_id_list(&[1, 300], &mut buf);
+        let err = decode_id_list(&buf[..buf.len() - 1]).unwrap_err();
+        assert_eq!(
+            ErrorKind::BufferTooSmall { needed: 2, available: 1 },
+            *err.kind()
+        );
+
+        // A huge count on short data is an error, not a huge allocation.
+        let err = decode_id_list(&[FORMAT_VERSION, 0xFF, 0xFF, 0xFF, 0x7F, 2])
+            .unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::BufferTooSmall { .. }));
+    }
+
+    #[test]
+    fn streaming() {
+        let mut buf = vec![];
+        encode_id_list(&[2, 4, 6], &mut buf);
+        buf.extend_from_slice(b"trailing");
+
+        let mut dec = IdListDecoder::new(&buf).unwrap();
+        assert_eq!(2, dec.position());
+        assert_eq!(Some(Ok(2)), dec.next());
+        assert_eq!(Some(Ok(4)), dec.next());
+        assert_eq!(1, dec.remaining());
+        assert_eq!(Some(Ok(6)), dec.next());
+        assert_eq!(None, dec.next());
+        assert_eq!(b"trailing", &buf[dec.position()..]);
+
+        // Decoding stops after an error.
+        let mut dec = IdListDecoder::new(&buf[..3]).unwrap();
+        assert_eq!(Some(Ok(2)), dec.next());
+        assert!(dec.next().unwrap().is_err());
+        assert_eq!(None, dec.next());
+    }
+}
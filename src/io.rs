@@ -1,9 +1,22 @@
 use std::{
+    borrow::Borrow,
+    ffi::CString,
+    fmt,
     io::{self, Result},
+    iter,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8,
+        NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8,
+    },
     slice,
 };
 
-use crate::ByteOrder;
+use crate::{
+    bom, varint,
+    wire::{FromWire, ToWire},
+    BigEndian, ByteOrder, Endianness, LittleEndian, Padding, PrefixWidth,
+    Primitive, PtrWidth,
+};
 
 /// Extends [`Read`] with methods for reading numbers. (For `std::io`.)
 ///
@@ -11,6 +24,11 @@ use crate::ByteOrder;
 /// must be explicitly instantiated. Typically, it is instantiated with either
 /// the [`BigEndian`] or [`LittleEndian`] types defined in this crate.
 ///
+/// When a read fails part way through a number, the bytes of that number
+/// that were read are usually lost, just like with `Read::read_exact`. Wrap
+/// the reader in a [`Restoring`](crate::restoring::Restoring) to keep them,
+/// for example when reading from a stream that may have more data later.
+///
 /// # Examples
 ///
 /// Read unsigned 16 bit big-endian integers from a [`Read`]:
@@ -88,6 +106,65 @@ pub trait ReadBytesExt: io::Read {
         Ok(buf[0] as i8)
     }
 
+    /// Reads a boolean stored in a single byte, which must be `0` or `1`,
+    /// from the underlying reader.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// byte is neither `0` nor `1`, an error of kind
+    /// [`ErrorKind::InvalidData`] is returned.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::ReadBytesExt;
+    ///
+    /// let mut rdr = Cursor::new(vec![1, 0, 2]);
+    /// assert_eq!(true, rdr.read_bool_strict().unwrap());
+    /// assert_eq!(false, rdr.read_bool_strict().unwrap());
+    /// assert!(rdr.read_bool_strict().is_err());
+    /// ```
+    #[inline]
+    fn read_bool_strict(&mut self) -> Result<bool> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            n => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid boolean 0x{:02X}", n),
+            )),
+        }
+    }
+
+    /// Reads a boolean stored in a single byte, where any byte other than
+    /// `0` is `true`, from the underlying reader.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`].
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::ReadBytesExt;
+    ///
+    /// let mut rdr = Cursor::new(vec![0xFF, 0]);
+    /// assert_eq!(true, rdr.read_bool_lenient().unwrap());
+    /// assert_eq!(false, rdr.read_bool_lenient().unwrap());
+    /// ```
+    #[inline]
+    fn read_bool_lenient(&mut self) -> Result<bool> {
+        self.read_u8().map(|n| n != 0)
+    }
+
     /// Reads an unsigned 16 bit integer from the underlying reader.
     ///
     /// # Errors
@@ -471,6 +548,109 @@ pub trait ReadBytesExt: io::Read {
         Ok(T::read_int128(&buf[..nbytes], nbytes))
     }
 
+    /// Reads an unsigned pointer sized integer, stored in `width` bytes,
+    /// from the underlying reader.
+    ///
+    /// The on-disk width is independent of the width of `usize` on the
+    /// current platform. For example, a 64 bit offset can be read on a 32
+    /// bit platform as long as its value fits.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value does not fit in a `usize`, an error of kind
+    /// [`ErrorKind::InvalidData`] is returned.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// Read a big-endian 32 bit offset and a little-endian 64 bit length:
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, LittleEndian, PtrWidth, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![0, 0, 1, 0, 5, 0, 0, 0, 0, 0, 0, 0]);
+    /// assert_eq!(256, rdr.read_usize::<BigEndian>(PtrWidth::W32).unwrap());
+    /// assert_eq!(5, rdr.read_usize::<LittleEndian>(PtrWidth::W64).unwrap());
+    /// ```
+    #[inline]
+    fn read_usize<T: ByteOrder>(&mut self, width: PtrWidth) -> Result<usize> {
+        let n = match width {
+            PtrWidth::W16 => u64::from(self.read_u16::<T>()?),
+            PtrWidth::W32 => u64::from(self.read_u32::<T>()?),
+            PtrWidth::W64 => self.read_u64::<T>()?,
+        };
+        usize::try_from(n).map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidData, "value overflows usize")
+        })
+    }
+
+    /// Reads a signed pointer sized integer, stored in `width` bytes, from
+    /// the underlying reader.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value does not fit in an `isize`, an error of kind
+    /// [`ErrorKind::InvalidData`] is returned.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// Read a big-endian 32 bit signed displacement:
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, PtrWidth, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![0xff, 0xff, 0xff, 0xf0]);
+    /// assert_eq!(-16, rdr.read_isize::<BigEndian>(PtrWidth::W32).unwrap());
+    /// ```
+    #[inline]
+    fn read_isize<T: ByteOrder>(&mut self, width: PtrWidth) -> Result<isize> {
+        let n = match width {
+            PtrWidth::W16 => i64::from(self.read_i16::<T>()?),
+            PtrWidth::W32 => i64::from(self.read_i32::<T>()?),
+            PtrWidth::W64 => self.read_i64::<T>()?,
+        };
+        isize::try_from(n).map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidData, "value overflows isize")
+        })
+    }
+
+    /// Reads a IEEE754 half-precision (2 bytes) floating point number from
+    /// the underlying reader, and widens it to an `f32`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`].
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    ///
+    /// # Examples
+    ///
+    /// Read a big-endian half-precision floating point number from a `Read`:
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![0x3c, 0x00, 0xc1, 0x00]);
+    /// assert_eq!(1.0, rdr.read_f16::<BigEndian>().unwrap());
+    /// assert_eq!(-2.5, rdr.read_f16::<BigEndian>().unwrap());
+    /// ```
+    #[inline]
+    fn read_f16<T: ByteOrder>(&mut self) -> Result<f32> {
+        let mut buf = [0; 2];
+        self.read_exact(&mut buf)?;
+        Ok(T::read_f16(&buf))
+    }
+
     /// Reads a IEEE754 single-precision (4 bytes) floating point number from
     /// the underlying reader.
     ///
@@ -533,342 +713,315 @@ pub trait ReadBytesExt: io::Read {
         Ok(T::read_f64(&buf))
     }
 
-    /// Reads a sequence of unsigned 16 bit integers from the underlying
-    /// reader.
-    ///
-    /// The given buffer is either filled completely or an error is returned.
-    /// If an error is returned, the contents of `dst` are unspecified.
+    /// Reads a `char`, stored as a 32 bit Unicode scalar value, from the
+    /// underlying reader.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Read::read_exact`].
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value is a surrogate or greater than `0x10FFFF`, an error of kind
+    /// [`ErrorKind::InvalidData`] is returned.
     ///
     /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
     ///
     /// # Examples
     ///
-    /// Read a sequence of unsigned 16 bit big-endian integers from a `Read`:
-    ///
     /// ```rust
     /// use std::io::Cursor;
     /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut rdr = Cursor::new(vec![2, 5, 3, 0]);
-    /// let mut dst = [0; 2];
-    /// rdr.read_u16_into::<BigEndian>(&mut dst).unwrap();
-    /// assert_eq!([517, 768], dst);
+    /// let mut rdr = Cursor::new(vec![0, 1, 0xF6, 0x00, 0, 0, 0xD8, 0]);
+    /// assert_eq!('\u{1F600}', rdr.read_char::<BigEndian>().unwrap());
+    /// assert!(rdr.read_char::<BigEndian>().is_err());
     /// ```
     #[inline]
-    fn read_u16_into<T: ByteOrder>(&mut self, dst: &mut [u16]) -> Result<()> {
-        {
-            let buf = unsafe { slice_to_u8_mut(dst) };
-            self.read_exact(buf)?;
-        }
-        T::from_slice_u16(dst);
-        Ok(())
+    fn read_char<T: ByteOrder>(&mut self) -> Result<char> {
+        let n = self.read_u32::<T>()?;
+        char::from_u32(n).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid Unicode scalar value 0x{:X}", n),
+            )
+        })
     }
 
-    /// Reads a sequence of unsigned 32 bit integers from the underlying
-    /// reader.
+    /// Reads an unsigned 8 bit integer that must not be zero from the
+    /// underlying reader.
     ///
-    /// The given buffer is either filled completely or an error is returned.
-    /// If an error is returned, the contents of `dst` are unspecified.
+    /// This is for fields that a format requires to be nonzero, like counts
+    /// and IDs. Besides checking the value, the `NonZero` result lets an
+    /// `Option` of it take no more space than the integer itself.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Read::read_exact`].
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value is zero, an error of kind [`ErrorKind::InvalidData`] is
+    /// returned.
     ///
     /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
     ///
     /// # Examples
     ///
-    /// Read a sequence of unsigned 32 bit big-endian integers from a `Read`:
-    ///
     /// ```rust
     /// use std::io::Cursor;
-    /// use byteorder::{BigEndian, ReadBytesExt};
+    /// use byteorder::ReadBytesExt;
     ///
-    /// let mut rdr = Cursor::new(vec![0, 0, 2, 5, 0, 0, 3, 0]);
-    /// let mut dst = [0; 2];
-    /// rdr.read_u32_into::<BigEndian>(&mut dst).unwrap();
-    /// assert_eq!([517, 768], dst);
+    /// let mut rdr = Cursor::new(vec![7, 0]);
+    /// assert_eq!(7, rdr.read_nonzero_u8().unwrap().get());
+    /// assert!(rdr.read_nonzero_u8().is_err());
     /// ```
     #[inline]
-    fn read_u32_into<T: ByteOrder>(&mut self, dst: &mut [u32]) -> Result<()> {
-        {
-            let buf = unsafe { slice_to_u8_mut(dst) };
-            self.read_exact(buf)?;
-        }
-        T::from_slice_u32(dst);
-        Ok(())
+    fn read_nonzero_u8(&mut self) -> Result<NonZeroU8> {
+        NonZeroU8::new(self.read_u8()?).ok_or_else(zero_error)
     }
 
-    /// Reads a sequence of unsigned 64 bit integers from the underlying
-    /// reader.
-    ///
-    /// The given buffer is either filled completely or an error is returned.
-    /// If an error is returned, the contents of `dst` are unspecified.
+    /// Reads a signed 8 bit integer that must not be zero from the
+    /// underlying reader.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Read::read_exact`].
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value is zero, an error of kind [`ErrorKind::InvalidData`] is
+    /// returned.
     ///
     /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
     ///
     /// # Examples
     ///
-    /// Read a sequence of unsigned 64 bit big-endian integers from a `Read`:
-    ///
     /// ```rust
     /// use std::io::Cursor;
-    /// use byteorder::{BigEndian, ReadBytesExt};
+    /// use byteorder::ReadBytesExt;
     ///
-    /// let mut rdr = Cursor::new(vec![
-    ///     0, 0, 0, 0, 0, 0, 2, 5,
-    ///     0, 0, 0, 0, 0, 0, 3, 0,
-    /// ]);
-    /// let mut dst = [0; 2];
-    /// rdr.read_u64_into::<BigEndian>(&mut dst).unwrap();
-    /// assert_eq!([517, 768], dst);
+    /// let mut rdr = Cursor::new(vec![0xFE, 0]);
+    /// assert_eq!(-2, rdr.read_nonzero_i8().unwrap().get());
+    /// assert!(rdr.read_nonzero_i8().is_err());
     /// ```
     #[inline]
-    fn read_u64_into<T: ByteOrder>(&mut self, dst: &mut [u64]) -> Result<()> {
-        {
-            let buf = unsafe { slice_to_u8_mut(dst) };
-            self.read_exact(buf)?;
-        }
-        T::from_slice_u64(dst);
-        Ok(())
+    fn read_nonzero_i8(&mut self) -> Result<NonZeroI8> {
+        NonZeroI8::new(self.read_i8()?).ok_or_else(zero_error)
     }
 
-    /// Reads a sequence of unsigned 128 bit integers from the underlying
-    /// reader.
-    ///
-    /// The given buffer is either filled completely or an error is returned.
-    /// If an error is returned, the contents of `dst` are unspecified.
+    /// Reads an unsigned 16 bit integer that must not be zero from the
+    /// underlying reader.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Read::read_exact`].
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value is zero, an error of kind [`ErrorKind::InvalidData`] is
+    /// returned.
     ///
     /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
     ///
     /// # Examples
     ///
-    /// Read a sequence of unsigned 128 bit big-endian integers from a `Read`:
-    ///
     /// ```rust
     /// use std::io::Cursor;
     /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut rdr = Cursor::new(vec![
-    ///     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 5,
-    ///     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3, 0,
-    /// ]);
-    /// let mut dst = [0; 2];
-    /// rdr.read_u128_into::<BigEndian>(&mut dst).unwrap();
-    /// assert_eq!([517, 768], dst);
+    /// let mut rdr = Cursor::new(vec![2, 5, 0, 0]);
+    /// assert_eq!(517, rdr.read_nonzero_u16::<BigEndian>().unwrap().get());
+    /// assert!(rdr.read_nonzero_u16::<BigEndian>().is_err());
     /// ```
     #[inline]
-    fn read_u128_into<T: ByteOrder>(
-        &mut self,
-        dst: &mut [u128],
-    ) -> Result<()> {
-        {
-            let buf = unsafe { slice_to_u8_mut(dst) };
-            self.read_exact(buf)?;
-        }
-        T::from_slice_u128(dst);
-        Ok(())
+    fn read_nonzero_u16<T: ByteOrder>(&mut self) -> Result<NonZeroU16> {
+        NonZeroU16::new(self.read_u16::<T>()?).ok_or_else(zero_error)
     }
 
-    /// Reads a sequence of signed 8 bit integers from the underlying reader.
-    ///
-    /// The given buffer is either filled completely or an error is returned.
-    /// If an error is returned, the contents of `dst` are unspecified.
-    ///
-    /// Note that since each `i8` is a single byte, no byte order conversions
-    /// are used. This method is included because it provides a safe, simple
-    /// way for the caller to read into a `&mut [i8]` buffer. (Without this
-    /// method, the caller would have to either use `unsafe` code or convert
-    /// each byte to `i8` individually.)
+    /// Reads a signed 16 bit integer that must not be zero from the
+    /// underlying reader.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Read::read_exact`].
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value is zero, an error of kind [`ErrorKind::InvalidData`] is
+    /// returned.
     ///
     /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
     ///
     /// # Examples
     ///
-    /// Read a sequence of signed 8 bit integers from a `Read`:
-    ///
     /// ```rust
     /// use std::io::Cursor;
     /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut rdr = Cursor::new(vec![2, 251, 3]);
-    /// let mut dst = [0; 3];
-    /// rdr.read_i8_into(&mut dst).unwrap();
-    /// assert_eq!([2, -5, 3], dst);
+    /// let mut rdr = Cursor::new(vec![0xFF, 0xFE, 0, 0]);
+    /// assert_eq!(-2, rdr.read_nonzero_i16::<BigEndian>().unwrap().get());
+    /// assert!(rdr.read_nonzero_i16::<BigEndian>().is_err());
     /// ```
     #[inline]
-    fn read_i8_into(&mut self, dst: &mut [i8]) -> Result<()> {
-        let buf = unsafe { slice_to_u8_mut(dst) };
-        self.read_exact(buf)
+    fn read_nonzero_i16<T: ByteOrder>(&mut self) -> Result<NonZeroI16> {
+        NonZeroI16::new(self.read_i16::<T>()?).ok_or_else(zero_error)
     }
 
-    /// Reads a sequence of signed 16 bit integers from the underlying
-    /// reader.
-    ///
-    /// The given buffer is either filled completely or an error is returned.
-    /// If an error is returned, the contents of `dst` are unspecified.
+    /// Reads an unsigned 32 bit integer that must not be zero from the
+    /// underlying reader.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Read::read_exact`].
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value is zero, an error of kind [`ErrorKind::InvalidData`] is
+    /// returned.
     ///
     /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
     ///
     /// # Examples
     ///
-    /// Read a sequence of signed 16 bit big-endian integers from a `Read`:
-    ///
     /// ```rust
     /// use std::io::Cursor;
     /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut rdr = Cursor::new(vec![2, 5, 3, 0]);
-    /// let mut dst = [0; 2];
-    /// rdr.read_i16_into::<BigEndian>(&mut dst).unwrap();
-    /// assert_eq!([517, 768], dst);
+    /// let mut rdr = Cursor::new(vec![0, 0, 2, 5, 0, 0, 0, 0]);
+    /// assert_eq!(517, rdr.read_nonzero_u32::<BigEndian>().unwrap().get());
+    /// assert!(rdr.read_nonzero_u32::<BigEndian>().is_err());
     /// ```
     #[inline]
-    fn read_i16_into<T: ByteOrder>(&mut self, dst: &mut [i16]) -> Result<()> {
-        {
-            let buf = unsafe { slice_to_u8_mut(dst) };
-            self.read_exact(buf)?;
-        }
-        T::from_slice_i16(dst);
-        Ok(())
+    fn read_nonzero_u32<T: ByteOrder>(&mut self) -> Result<NonZeroU32> {
+        NonZeroU32::new(self.read_u32::<T>()?).ok_or_else(zero_error)
     }
 
-    /// Reads a sequence of signed 32 bit integers from the underlying
-    /// reader.
-    ///
-    /// The given buffer is either filled completely or an error is returned.
-    /// If an error is returned, the contents of `dst` are unspecified.
+    /// Reads a signed 32 bit integer that must not be zero from the
+    /// underlying reader.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Read::read_exact`].
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value is zero, an error of kind [`ErrorKind::InvalidData`] is
+    /// returned.
     ///
     /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
     ///
     /// # Examples
     ///
-    /// Read a sequence of signed 32 bit big-endian integers from a `Read`:
-    ///
     /// ```rust
     /// use std::io::Cursor;
     /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut rdr = Cursor::new(vec![0, 0, 2, 5, 0, 0, 3, 0]);
-    /// let mut dst = [0; 2];
-    /// rdr.read_i32_into::<BigEndian>(&mut dst).unwrap();
-    /// assert_eq!([517, 768], dst);
+    /// let mut rdr = Cursor::new(vec![0xFF, 0xFF, 0xFF, 0xFE, 0, 0, 0, 0]);
+    /// assert_eq!(-2, rdr.read_nonzero_i32::<BigEndian>().unwrap().get());
+    /// assert!(rdr.read_nonzero_i32::<BigEndian>().is_err());
     /// ```
     #[inline]
-    fn read_i32_into<T: ByteOrder>(&mut self, dst: &mut [i32]) -> Result<()> {
-        {
-            let buf = unsafe { slice_to_u8_mut(dst) };
-            self.read_exact(buf)?;
-        }
-        T::from_slice_i32(dst);
-        Ok(())
+    fn read_nonzero_i32<T: ByteOrder>(&mut self) -> Result<NonZeroI32> {
+        NonZeroI32::new(self.read_i32::<T>()?).ok_or_else(zero_error)
     }
 
-    /// Reads a sequence of signed 64 bit integers from the underlying
-    /// reader.
-    ///
-    /// The given buffer is either filled completely or an error is returned.
-    /// If an error is returned, the contents of `dst` are unspecified.
+    /// Reads an unsigned 64 bit integer that must not be zero from the
+    /// underlying reader.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Read::read_exact`].
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value is zero, an error of kind [`ErrorKind::InvalidData`] is
+    /// returned.
     ///
     /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
     ///
     /// # Examples
     ///
-    /// Read a sequence of signed 64 bit big-endian integers from a `Read`:
-    ///
     /// ```rust
     /// use std::io::Cursor;
     /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut rdr = Cursor::new(vec![
-    ///     0, 0, 0, 0, 0, 0, 2, 5,
-    ///     0, 0, 0, 0, 0, 0, 3, 0,
-    /// ]);
-    /// let mut dst = [0; 2];
-    /// rdr.read_i64_into::<BigEndian>(&mut dst).unwrap();
-    /// assert_eq!([517, 768], dst);
+    /// let mut rdr = Cursor::new([517u64.to_be_bytes(), [0; 8]].concat());
+    /// assert_eq!(517, rdr.read_nonzero_u64::<BigEndian>().unwrap().get());
+    /// assert!(rdr.read_nonzero_u64::<BigEndian>().is_err());
     /// ```
     #[inline]
-    fn read_i64_into<T: ByteOrder>(&mut self, dst: &mut [i64]) -> Result<()> {
-        {
-            let buf = unsafe { slice_to_u8_mut(dst) };
-            self.read_exact(buf)?;
-        }
-        T::from_slice_i64(dst);
-        Ok(())
+    fn read_nonzero_u64<T: ByteOrder>(&mut self) -> Result<NonZeroU64> {
+        NonZeroU64::new(self.read_u64::<T>()?).ok_or_else(zero_error)
     }
 
-    /// Reads a sequence of signed 128 bit integers from the underlying
-    /// reader.
+    /// Reads a signed 64 bit integer that must not be zero from the
+    /// underlying reader.
     ///
-    /// The given buffer is either filled completely or an error is returned.
-    /// If an error is returned, the contents of `dst` are unspecified.
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value is zero, an error of kind [`ErrorKind::InvalidData`] is
+    /// returned.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new([(-2i64).to_be_bytes(), [0; 8]].concat());
+    /// assert_eq!(-2, rdr.read_nonzero_i64::<BigEndian>().unwrap().get());
+    /// assert!(rdr.read_nonzero_i64::<BigEndian>().is_err());
+    /// ```
+    #[inline]
+    fn read_nonzero_i64<T: ByteOrder>(&mut self) -> Result<NonZeroI64> {
+        NonZeroI64::new(self.read_i64::<T>()?).ok_or_else(zero_error)
+    }
+
+    /// Reads an unsigned 128 bit integer that must not be zero from the
+    /// underlying reader.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Read::read_exact`].
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value is zero, an error of kind [`ErrorKind::InvalidData`] is
+    /// returned.
     ///
     /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
     ///
     /// # Examples
     ///
-    /// Read a sequence of signed 128 bit big-endian integers from a `Read`:
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new([517u128.to_be_bytes(), [0; 16]].concat());
+    /// assert_eq!(517, rdr.read_nonzero_u128::<BigEndian>().unwrap().get());
+    /// assert!(rdr.read_nonzero_u128::<BigEndian>().is_err());
+    /// ```
+    #[inline]
+    fn read_nonzero_u128<T: ByteOrder>(&mut self) -> Result<NonZeroU128> {
+        NonZeroU128::new(self.read_u128::<T>()?).ok_or_else(zero_error)
+    }
+
+    /// Reads a signed 128 bit integer that must not be zero from the
+    /// underlying reader.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value is zero, an error of kind [`ErrorKind::InvalidData`] is
+    /// returned.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
     ///
     /// ```rust
     /// use std::io::Cursor;
     /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut rdr = Cursor::new(vec![
-    ///     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 5,
-    ///     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3, 0,
-    /// ]);
-    /// let mut dst = [0; 2];
-    /// rdr.read_i128_into::<BigEndian>(&mut dst).unwrap();
-    /// assert_eq!([517, 768], dst);
+    /// let mut rdr = Cursor::new([(-2i128).to_be_bytes(), [0; 16]].concat());
+    /// assert_eq!(-2, rdr.read_nonzero_i128::<BigEndian>().unwrap().get());
+    /// assert!(rdr.read_nonzero_i128::<BigEndian>().is_err());
     /// ```
     #[inline]
-    fn read_i128_into<T: ByteOrder>(
-        &mut self,
-        dst: &mut [i128],
-    ) -> Result<()> {
-        {
-            let buf = unsafe { slice_to_u8_mut(dst) };
-            self.read_exact(buf)?;
-        }
-        T::from_slice_i128(dst);
-        Ok(())
+    fn read_nonzero_i128<T: ByteOrder>(&mut self) -> Result<NonZeroI128> {
+        NonZeroI128::new(self.read_i128::<T>()?).ok_or_else(zero_error)
     }
 
-    /// Reads a sequence of IEEE754 single-precision (4 bytes) floating
-    /// point numbers from the underlying reader.
+    /// Reads a sequence of unsigned 16 bit integers from the underlying
+    /// reader.
     ///
     /// The given buffer is either filled completely or an error is returned.
     /// If an error is returned, the contents of `dst` are unspecified.
@@ -881,39 +1034,29 @@ pub trait ReadBytesExt: io::Read {
     ///
     /// # Examples
     ///
-    /// Read a sequence of big-endian single-precision floating point number
-    /// from a `Read`:
+    /// Read a sequence of unsigned 16 bit big-endian integers from a `Read`:
     ///
     /// ```rust
-    /// use std::f32;
     /// use std::io::Cursor;
-    ///
     /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut rdr = Cursor::new(vec![
-    ///     0x40, 0x49, 0x0f, 0xdb,
-    ///     0x3f, 0x80, 0x00, 0x00,
-    /// ]);
-    /// let mut dst = [0.0; 2];
-    /// rdr.read_f32_into::<BigEndian>(&mut dst).unwrap();
-    /// assert_eq!([f32::consts::PI, 1.0], dst);
+    /// let mut rdr = Cursor::new(vec![2, 5, 3, 0]);
+    /// let mut dst = [0; 2];
+    /// rdr.read_u16_into::<BigEndian>(&mut dst).unwrap();
+    /// assert_eq!([517, 768], dst);
     /// ```
     #[inline]
-    fn read_f32_into<T: ByteOrder>(&mut self, dst: &mut [f32]) -> Result<()> {
+    fn read_u16_into<T: ByteOrder>(&mut self, dst: &mut [u16]) -> Result<()> {
         {
             let buf = unsafe { slice_to_u8_mut(dst) };
             self.read_exact(buf)?;
         }
-        T::from_slice_f32(dst);
+        T::from_slice_u16(dst);
         Ok(())
     }
 
-    /// **DEPRECATED**.
-    ///
-    /// This method is deprecated. Use `read_f32_into` instead.
-    ///
-    /// Reads a sequence of IEEE754 single-precision (4 bytes) floating
-    /// point numbers from the underlying reader.
+    /// Reads a sequence of unsigned 32 bit integers from the underlying
+    /// reader.
     ///
     /// The given buffer is either filled completely or an error is returned.
     /// If an error is returned, the contents of `dst` are unspecified.
@@ -926,34 +1069,29 @@ pub trait ReadBytesExt: io::Read {
     ///
     /// # Examples
     ///
-    /// Read a sequence of big-endian single-precision floating point number
-    /// from a `Read`:
+    /// Read a sequence of unsigned 32 bit big-endian integers from a `Read`:
     ///
     /// ```rust
-    /// use std::f32;
     /// use std::io::Cursor;
-    ///
     /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut rdr = Cursor::new(vec![
-    ///     0x40, 0x49, 0x0f, 0xdb,
-    ///     0x3f, 0x80, 0x00, 0x00,
-    /// ]);
-    /// let mut dst = [0.0; 2];
-    /// rdr.read_f32_into_unchecked::<BigEndian>(&mut dst).unwrap();
-    /// assert_eq!([f32::consts::PI, 1.0], dst);
+    /// let mut rdr = Cursor::new(vec![0, 0, 2, 5, 0, 0, 3, 0]);
+    /// let mut dst = [0; 2];
+    /// rdr.read_u32_into::<BigEndian>(&mut dst).unwrap();
+    /// assert_eq!([517, 768], dst);
     /// ```
     #[inline]
-    #[deprecated(since = "1.2.0", note = "please use `read_f32_into` instead")]
-    fn read_f32_into_unchecked<T: ByteOrder>(
-        &mut self,
-        dst: &mut [f32],
-    ) -> Result<()> {
-        self.read_f32_into::<T>(dst)
+    fn read_u32_into<T: ByteOrder>(&mut self, dst: &mut [u32]) -> Result<()> {
+        {
+            let buf = unsafe { slice_to_u8_mut(dst) };
+            self.read_exact(buf)?;
+        }
+        T::from_slice_u32(dst);
+        Ok(())
     }
 
-    /// Reads a sequence of IEEE754 double-precision (8 bytes) floating
-    /// point numbers from the underlying reader.
+    /// Reads a sequence of unsigned 64 bit integers from the underlying
+    /// reader.
     ///
     /// The given buffer is either filled completely or an error is returned.
     /// If an error is returned, the contents of `dst` are unspecified.
@@ -966,49 +1104,36 @@ pub trait ReadBytesExt: io::Read {
     ///
     /// # Examples
     ///
-    /// Read a sequence of big-endian single-precision floating point number
-    /// from a `Read`:
+    /// Read a sequence of unsigned 64 bit big-endian integers from a `Read`:
     ///
     /// ```rust
-    /// use std::f64;
     /// use std::io::Cursor;
-    ///
     /// use byteorder::{BigEndian, ReadBytesExt};
     ///
     /// let mut rdr = Cursor::new(vec![
-    ///     0x40, 0x09, 0x21, 0xfb, 0x54, 0x44, 0x2d, 0x18,
-    ///     0x3f, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ///     0, 0, 0, 0, 0, 0, 2, 5,
+    ///     0, 0, 0, 0, 0, 0, 3, 0,
     /// ]);
-    /// let mut dst = [0.0; 2];
-    /// rdr.read_f64_into::<BigEndian>(&mut dst).unwrap();
-    /// assert_eq!([f64::consts::PI, 1.0], dst);
+    /// let mut dst = [0; 2];
+    /// rdr.read_u64_into::<BigEndian>(&mut dst).unwrap();
+    /// assert_eq!([517, 768], dst);
     /// ```
     #[inline]
-    fn read_f64_into<T: ByteOrder>(&mut self, dst: &mut [f64]) -> Result<()> {
+    fn read_u64_into<T: ByteOrder>(&mut self, dst: &mut [u64]) -> Result<()> {
         {
             let buf = unsafe { slice_to_u8_mut(dst) };
             self.read_exact(buf)?;
         }
-        T::from_slice_f64(dst);
+        T::from_slice_u64(dst);
         Ok(())
     }
 
-    /// **DEPRECATED**.
-    ///
-    /// This method is deprecated. Use `read_f64_into` instead.
-    ///
-    /// Reads a sequence of IEEE754 double-precision (8 bytes) floating
-    /// point numbers from the underlying reader.
+    /// Reads a sequence of unsigned 128 bit integers from the underlying
+    /// reader.
     ///
     /// The given buffer is either filled completely or an error is returned.
     /// If an error is returned, the contents of `dst` are unspecified.
     ///
-    /// # Safety
-    ///
-    /// This method is unsafe because there are no guarantees made about the
-    /// floating point values. In particular, this method does not check for
-    /// signaling NaNs, which may result in undefined behavior.
-    ///
     /// # Errors
     ///
     /// This method returns the same errors as [`Read::read_exact`].
@@ -1017,560 +1142,3958 @@ pub trait ReadBytesExt: io::Read {
     ///
     /// # Examples
     ///
-    /// Read a sequence of big-endian single-precision floating point number
-    /// from a `Read`:
+    /// Read a sequence of unsigned 128 bit big-endian integers from a `Read`:
     ///
     /// ```rust
-    /// use std::f64;
     /// use std::io::Cursor;
-    ///
     /// use byteorder::{BigEndian, ReadBytesExt};
     ///
     /// let mut rdr = Cursor::new(vec![
-    ///     0x40, 0x09, 0x21, 0xfb, 0x54, 0x44, 0x2d, 0x18,
-    ///     0x3f, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ///     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 5,
+    ///     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3, 0,
     /// ]);
-    /// let mut dst = [0.0; 2];
-    /// rdr.read_f64_into_unchecked::<BigEndian>(&mut dst).unwrap();
-    /// assert_eq!([f64::consts::PI, 1.0], dst);
+    /// let mut dst = [0; 2];
+    /// rdr.read_u128_into::<BigEndian>(&mut dst).unwrap();
+    /// assert_eq!([517, 768], dst);
     /// ```
     #[inline]
-    #[deprecated(since = "1.2.0", note = "please use `read_f64_into` instead")]
-    fn read_f64_into_unchecked<T: ByteOrder>(
+    fn read_u128_into<T: ByteOrder>(
         &mut self,
-        dst: &mut [f64],
+        dst: &mut [u128],
     ) -> Result<()> {
-        self.read_f64_into::<T>(dst)
+        {
+            let buf = unsafe { slice_to_u8_mut(dst) };
+            self.read_exact(buf)?;
+        }
+        T::from_slice_u128(dst);
+        Ok(())
     }
-}
-
-/// All types that implement `Read` get methods defined in `ReadBytesExt`
-/// for free.
-impl<R: io::Read + ?Sized> ReadBytesExt for R {}
 
-/// Extends [`Write`] with methods for writing numbers. (For `std::io`.)
-///
-/// Most of the methods defined here have an unconstrained type parameter that
-/// must be explicitly instantiated. Typically, it is instantiated with either
-/// the [`BigEndian`] or [`LittleEndian`] types defined in this crate.
-///
-/// # Examples
-///
-/// Write unsigned 16 bit big-endian integers to a [`Write`]:
-///
-/// ```rust
-/// use byteorder::{BigEndian, WriteBytesExt};
-///
-/// let mut wtr = vec![];
-/// wtr.write_u16::<BigEndian>(517).unwrap();
-/// wtr.write_u16::<BigEndian>(768).unwrap();
-/// assert_eq!(wtr, vec![2, 5, 3, 0]);
-/// ```
-///
-/// [`BigEndian`]: enum.BigEndian.html
-/// [`LittleEndian`]: enum.LittleEndian.html
-/// [`Write`]: https://doc.rust-lang.org/std/io/trait.Write.html
-pub trait WriteBytesExt: io::Write {
-    /// Writes an unsigned 8 bit integer to the underlying writer.
+    /// Reads a sequence of signed 8 bit integers from the underlying reader.
     ///
-    /// Note that since this writes a single byte, no byte order conversions
-    /// are used. It is included for completeness.
+    /// The given buffer is either filled completely or an error is returned.
+    /// If an error is returned, the contents of `dst` are unspecified.
+    ///
+    /// Note that since each `i8` is a single byte, no byte order conversions
+    /// are used. This method is included because it provides a safe, simple
+    /// way for the caller to read into a `&mut [i8]` buffer. (Without this
+    /// method, the caller would have to either use `unsafe` code or convert
+    /// each byte to `i8` individually.)
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// This method returns the same errors as [`Read::read_exact`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
     ///
     /// # Examples
     ///
-    /// Write unsigned 8 bit integers to a `Write`:
+    /// Read a sequence of signed 8 bit integers from a `Read`:
     ///
     /// ```rust
-    /// use byteorder::WriteBytesExt;
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_u8(2).unwrap();
-    /// wtr.write_u8(5).unwrap();
-    /// assert_eq!(wtr, b"\x02\x05");
+    /// let mut rdr = Cursor::new(vec![2, 251, 3]);
+    /// let mut dst = [0; 3];
+    /// rdr.read_i8_into(&mut dst).unwrap();
+    /// assert_eq!([2, -5, 3], dst);
     /// ```
     #[inline]
-    fn write_u8(&mut self, n: u8) -> Result<()> {
-        self.write_all(&[n])
+    fn read_i8_into(&mut self, dst: &mut [i8]) -> Result<()> {
+        let buf = unsafe { slice_to_u8_mut(dst) };
+        self.read_exact(buf)
     }
 
-    /// Writes a signed 8 bit integer to the underlying writer.
+    /// Reads a sequence of signed 16 bit integers from the underlying
+    /// reader.
     ///
-    /// Note that since this writes a single byte, no byte order conversions
-    /// are used. It is included for completeness.
+    /// The given buffer is either filled completely or an error is returned.
+    /// If an error is returned, the contents of `dst` are unspecified.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// This method returns the same errors as [`Read::read_exact`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
     ///
     /// # Examples
     ///
-    /// Write signed 8 bit integers to a `Write`:
+    /// Read a sequence of signed 16 bit big-endian integers from a `Read`:
     ///
     /// ```rust
-    /// use byteorder::WriteBytesExt;
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_i8(2).unwrap();
-    /// wtr.write_i8(-5).unwrap();
-    /// assert_eq!(wtr, b"\x02\xfb");
+    /// let mut rdr = Cursor::new(vec![2, 5, 3, 0]);
+    /// let mut dst = [0; 2];
+    /// rdr.read_i16_into::<BigEndian>(&mut dst).unwrap();
+    /// assert_eq!([517, 768], dst);
     /// ```
     #[inline]
-    fn write_i8(&mut self, n: i8) -> Result<()> {
-        self.write_all(&[n as u8])
+    fn read_i16_into<T: ByteOrder>(&mut self, dst: &mut [i16]) -> Result<()> {
+        {
+            let buf = unsafe { slice_to_u8_mut(dst) };
+            self.read_exact(buf)?;
+        }
+        T::from_slice_i16(dst);
+        Ok(())
     }
 
-    /// Writes an unsigned 16 bit integer to the underlying writer.
+    /// Reads a sequence of signed 32 bit integers from the underlying
+    /// reader.
+    ///
+    /// The given buffer is either filled completely or an error is returned.
+    /// If an error is returned, the contents of `dst` are unspecified.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// This method returns the same errors as [`Read::read_exact`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
     ///
     /// # Examples
     ///
-    /// Write unsigned 16 bit big-endian integers to a `Write`:
+    /// Read a sequence of signed 32 bit big-endian integers from a `Read`:
     ///
     /// ```rust
-    /// use byteorder::{BigEndian, WriteBytesExt};
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_u16::<BigEndian>(517).unwrap();
-    /// wtr.write_u16::<BigEndian>(768).unwrap();
-    /// assert_eq!(wtr, b"\x02\x05\x03\x00");
+    /// let mut rdr = Cursor::new(vec![0, 0, 2, 5, 0, 0, 3, 0]);
+    /// let mut dst = [0; 2];
+    /// rdr.read_i32_into::<BigEndian>(&mut dst).unwrap();
+    /// assert_eq!([517, 768], dst);
     /// ```
     #[inline]
-    fn write_u16<T: ByteOrder>(&mut self, n: u16) -> Result<()> {
-        let mut buf = [0; 2];
-        T::write_u16(&mut buf, n);
-        self.write_all(&buf)
+    fn read_i32_into<T: ByteOrder>(&mut self, dst: &mut [i32]) -> Result<()> {
+        {
+            let buf = unsafe { slice_to_u8_mut(dst) };
+            self.read_exact(buf)?;
+        }
+        T::from_slice_i32(dst);
+        Ok(())
     }
 
-    /// Writes a signed 16 bit integer to the underlying writer.
+    /// Reads a sequence of signed 64 bit integers from the underlying
+    /// reader.
+    ///
+    /// The given buffer is either filled completely or an error is returned.
+    /// If an error is returned, the contents of `dst` are unspecified.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// This method returns the same errors as [`Read::read_exact`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
     ///
     /// # Examples
     ///
-    /// Write signed 16 bit big-endian integers to a `Write`:
+    /// Read a sequence of signed 64 bit big-endian integers from a `Read`:
     ///
     /// ```rust
-    /// use byteorder::{BigEndian, WriteBytesExt};
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_i16::<BigEndian>(193).unwrap();
-    /// wtr.write_i16::<BigEndian>(-132).unwrap();
-    /// assert_eq!(wtr, b"\x00\xc1\xff\x7c");
+    /// let mut rdr = Cursor::new(vec![
+    ///     0, 0, 0, 0, 0, 0, 2, 5,
+    ///     0, 0, 0, 0, 0, 0, 3, 0,
+    /// ]);
+    /// let mut dst = [0; 2];
+    /// rdr.read_i64_into::<BigEndian>(&mut dst).unwrap();
+    /// assert_eq!([517, 768], dst);
     /// ```
     #[inline]
-    fn write_i16<T: ByteOrder>(&mut self, n: i16) -> Result<()> {
-        let mut buf = [0; 2];
-        T::write_i16(&mut buf, n);
-        self.write_all(&buf)
+    fn read_i64_into<T: ByteOrder>(&mut self, dst: &mut [i64]) -> Result<()> {
+        {
+            let buf = unsafe { slice_to_u8_mut(dst) };
+            self.read_exact(buf)?;
+        }
+        T::from_slice_i64(dst);
+        Ok(())
     }
 
-    /// Writes an unsigned 24 bit integer to the underlying writer.
+    /// Reads a sequence of signed 128 bit integers from the underlying
+    /// reader.
+    ///
+    /// The given buffer is either filled completely or an error is returned.
+    /// If an error is returned, the contents of `dst` are unspecified.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// This method returns the same errors as [`Read::read_exact`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
     ///
     /// # Examples
     ///
-    /// Write unsigned 24 bit big-endian integers to a `Write`:
+    /// Read a sequence of signed 128 bit big-endian integers from a `Read`:
     ///
     /// ```rust
-    /// use byteorder::{BigEndian, WriteBytesExt};
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_u24::<BigEndian>(267).unwrap();
-    /// wtr.write_u24::<BigEndian>(120111).unwrap();
-    /// assert_eq!(wtr, b"\x00\x01\x0b\x01\xd5\x2f");
+    /// let mut rdr = Cursor::new(vec![
+    ///     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 5,
+    ///     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3, 0,
+    /// ]);
+    /// let mut dst = [0; 2];
+    /// rdr.read_i128_into::<BigEndian>(&mut dst).unwrap();
+    /// assert_eq!([517, 768], dst);
     /// ```
     #[inline]
-    fn write_u24<T: ByteOrder>(&mut self, n: u32) -> Result<()> {
-        let mut buf = [0; 3];
-        T::write_u24(&mut buf, n);
-        self.write_all(&buf)
+    fn read_i128_into<T: ByteOrder>(
+        &mut self,
+        dst: &mut [i128],
+    ) -> Result<()> {
+        {
+            let buf = unsafe { slice_to_u8_mut(dst) };
+            self.read_exact(buf)?;
+        }
+        T::from_slice_i128(dst);
+        Ok(())
     }
 
-    /// Writes a signed 24 bit integer to the underlying writer.
+    /// Reads a sequence of IEEE754 single-precision (4 bytes) floating
+    /// point numbers from the underlying reader.
+    ///
+    /// The given buffer is either filled completely or an error is returned.
+    /// If an error is returned, the contents of `dst` are unspecified.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// This method returns the same errors as [`Read::read_exact`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
     ///
     /// # Examples
     ///
-    /// Write signed 24 bit big-endian integers to a `Write`:
+    /// Read a sequence of big-endian single-precision floating point number
+    /// from a `Read`:
     ///
     /// ```rust
-    /// use byteorder::{BigEndian, WriteBytesExt};
+    /// use std::f32;
+    /// use std::io::Cursor;
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_i24::<BigEndian>(-34253).unwrap();
-    /// wtr.write_i24::<BigEndian>(120111).unwrap();
-    /// assert_eq!(wtr, b"\xff\x7a\x33\x01\xd5\x2f");
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![
+    ///     0x40, 0x49, 0x0f, 0xdb,
+    ///     0x3f, 0x80, 0x00, 0x00,
+    /// ]);
+    /// let mut dst = [0.0; 2];
+    /// rdr.read_f32_into::<BigEndian>(&mut dst).unwrap();
+    /// assert_eq!([f32::consts::PI, 1.0], dst);
     /// ```
     #[inline]
-    fn write_i24<T: ByteOrder>(&mut self, n: i32) -> Result<()> {
-        let mut buf = [0; 3];
-        T::write_i24(&mut buf, n);
-        self.write_all(&buf)
+    fn read_f32_into<T: ByteOrder>(&mut self, dst: &mut [f32]) -> Result<()> {
+        {
+            let buf = unsafe { slice_to_u8_mut(dst) };
+            self.read_exact(buf)?;
+        }
+        T::from_slice_f32(dst);
+        Ok(())
     }
 
-    /// Writes an unsigned 32 bit integer to the underlying writer.
+    /// **DEPRECATED**.
+    ///
+    /// This method is deprecated. Use `read_f32_into` instead.
+    ///
+    /// Reads a sequence of IEEE754 single-precision (4 bytes) floating
+    /// point numbers from the underlying reader.
+    ///
+    /// The given buffer is either filled completely or an error is returned.
+    /// If an error is returned, the contents of `dst` are unspecified.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// This method returns the same errors as [`Read::read_exact`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
     ///
     /// # Examples
     ///
-    /// Write unsigned 32 bit big-endian integers to a `Write`:
+    /// Read a sequence of big-endian single-precision floating point number
+    /// from a `Read`:
     ///
     /// ```rust
-    /// use byteorder::{BigEndian, WriteBytesExt};
+    /// use std::f32;
+    /// use std::io::Cursor;
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_u32::<BigEndian>(267).unwrap();
-    /// wtr.write_u32::<BigEndian>(1205419366).unwrap();
-    /// assert_eq!(wtr, b"\x00\x00\x01\x0b\x47\xd9\x3d\x66");
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![
+    ///     0x40, 0x49, 0x0f, 0xdb,
+    ///     0x3f, 0x80, 0x00, 0x00,
+    /// ]);
+    /// let mut dst = [0.0; 2];
+    /// rdr.read_f32_into_unchecked::<BigEndian>(&mut dst).unwrap();
+    /// assert_eq!([f32::consts::PI, 1.0], dst);
     /// ```
     #[inline]
-    fn write_u32<T: ByteOrder>(&mut self, n: u32) -> Result<()> {
-        let mut buf = [0; 4];
-        T::write_u32(&mut buf, n);
-        self.write_all(&buf)
+    #[deprecated(since = "1.2.0", note = "please use `read_f32_into` instead")]
+    fn read_f32_into_unchecked<T: ByteOrder>(
+        &mut self,
+        dst: &mut [f32],
+    ) -> Result<()> {
+        self.read_f32_into::<T>(dst)
     }
 
-    /// Writes a signed 32 bit integer to the underlying writer.
+    /// Reads a sequence of IEEE754 double-precision (8 bytes) floating
+    /// point numbers from the underlying reader.
+    ///
+    /// The given buffer is either filled completely or an error is returned.
+    /// If an error is returned, the contents of `dst` are unspecified.
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// This method returns the same errors as [`Read::read_exact`].
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
     ///
     /// # Examples
     ///
-    /// Write signed 32 bit big-endian integers to a `Write`:
+    /// Read a sequence of big-endian single-precision floating point number
+    /// from a `Read`:
     ///
     /// ```rust
-    /// use byteorder::{BigEndian, WriteBytesExt};
+    /// use std::f64;
+    /// use std::io::Cursor;
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_i32::<BigEndian>(-34253).unwrap();
-    /// wtr.write_i32::<BigEndian>(1205419366).unwrap();
-    /// assert_eq!(wtr, b"\xff\xff\x7a\x33\x47\xd9\x3d\x66");
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![
+    ///     0x40, 0x09, 0x21, 0xfb, 0x54, 0x44, 0x2d, 0x18,
+    ///     0x3f, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    /// ]);
+    /// let mut dst = [0.0; 2];
+    /// rdr.read_f64_into::<BigEndian>(&mut dst).unwrap();
+    /// assert_eq!([f64::consts::PI, 1.0], dst);
     /// ```
     #[inline]
-    fn write_i32<T: ByteOrder>(&mut self, n: i32) -> Result<()> {
-        let mut buf = [0; 4];
-        T::write_i32(&mut buf, n);
-        self.write_all(&buf)
+    fn read_f64_into<T: ByteOrder>(&mut self, dst: &mut [f64]) -> Result<()> {
+        {
+            let buf = unsafe { slice_to_u8_mut(dst) };
+            self.read_exact(buf)?;
+        }
+        T::from_slice_f64(dst);
+        Ok(())
     }
 
-    /// Writes an unsigned 48 bit integer to the underlying writer.
+    /// **DEPRECATED**.
     ///
-    /// # Errors
+    /// This method is deprecated. Use `read_f64_into` instead.
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// Reads a sequence of IEEE754 double-precision (8 bytes) floating
+    /// point numbers from the underlying reader.
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// The given buffer is either filled completely or an error is returned.
+    /// If an error is returned, the contents of `dst` are unspecified.
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because there are no guarantees made about the
+    /// floating point values. In particular, this method does not check for
+    /// signaling NaNs, which may result in undefined behavior.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`].
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
     ///
     /// # Examples
     ///
-    /// Write unsigned 48 bit big-endian integers to a `Write`:
+    /// Read a sequence of big-endian single-precision floating point number
+    /// from a `Read`:
     ///
     /// ```rust
-    /// use byteorder::{BigEndian, WriteBytesExt};
+    /// use std::f64;
+    /// use std::io::Cursor;
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_u48::<BigEndian>(52360336390828).unwrap();
-    /// wtr.write_u48::<BigEndian>(541).unwrap();
-    /// assert_eq!(wtr, b"\x2f\x9f\x17\x40\x3a\xac\x00\x00\x00\x00\x02\x1d");
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![
+    ///     0x40, 0x09, 0x21, 0xfb, 0x54, 0x44, 0x2d, 0x18,
+    ///     0x3f, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    /// ]);
+    /// let mut dst = [0.0; 2];
+    /// rdr.read_f64_into_unchecked::<BigEndian>(&mut dst).unwrap();
+    /// assert_eq!([f64::consts::PI, 1.0], dst);
     /// ```
     #[inline]
-    fn write_u48<T: ByteOrder>(&mut self, n: u64) -> Result<()> {
-        let mut buf = [0; 6];
-        T::write_u48(&mut buf, n);
-        self.write_all(&buf)
+    #[deprecated(since = "1.2.0", note = "please use `read_f64_into` instead")]
+    fn read_f64_into_unchecked<T: ByteOrder>(
+        &mut self,
+        dst: &mut [f64],
+    ) -> Result<()> {
+        self.read_f64_into::<T>(dst)
     }
 
-    /// Writes a signed 48 bit integer to the underlying writer.
+    /// Returns an iterator that reads unsigned 16 bit integers from the
+    /// underlying reader until it is exhausted.
     ///
-    /// # Errors
+    /// Unlike calling [`read_u16`](ReadBytesExt::read_u16) in a loop and
+    /// stopping at the first error, the iterator tells a clean end of the
+    /// data apart from a truncated one. It ends when the reader reaches end
+    /// of file exactly at a boundary between values, and yields an error of
+    /// kind [`ErrorKind::UnexpectedEof`] when the reader ends partway through
+    /// a value. Reads that fail with [`ErrorKind::Interrupted`] are retried.
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// After yielding an error, the iterator yields nothing more. Any bytes
+    /// of a partially read value are lost.
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::UnexpectedEof`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.UnexpectedEof
+    /// [`ErrorKind::Interrupted`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.Interrupted
     ///
     /// # Examples
     ///
-    /// Write signed 48 bit big-endian integers to a `Write`:
+    /// Read every big-endian unsigned 16 bit integer from a
+    /// [`Read`]:
     ///
     /// ```rust
-    /// use byteorder::{BigEndian, WriteBytesExt};
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_i48::<BigEndian>(-108363435763825).unwrap();
-    /// wtr.write_i48::<BigEndian>(77).unwrap();
-    /// assert_eq!(wtr, b"\x9d\x71\xab\xe7\x97\x8f\x00\x00\x00\x00\x00\x4d");
+    /// let mut rdr = Cursor::new(vec![2, 5, 3, 0]);
+    /// let xs: Vec<u16> =
+    ///     rdr.iter_u16::<BigEndian>().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(vec![517, 768], xs);
+    ///
+    /// // A trailing byte that doesn't make up a whole value is an error.
+    /// let mut rdr = Cursor::new(vec![2, 5, 3]);
+    /// let mut it = rdr.iter_u16::<BigEndian>();
+    /// assert_eq!(517, it.next().unwrap().unwrap());
+    /// assert!(it.next().unwrap().is_err());
+    /// assert!(it.next().is_none());
     /// ```
+    ///
+    /// [`Read`]: https://doc.rust-lang.org/std/io/trait.Read.html
     #[inline]
-    fn write_i48<T: ByteOrder>(&mut self, n: i64) -> Result<()> {
-        let mut buf = [0; 6];
-        T::write_i48(&mut buf, n);
-        self.write_all(&buf)
+    fn iter_u16<T: ByteOrder>(&mut self) -> ReadIter<'_, Self, u16> {
+        ReadIter::new(self, 2, T::read_u16)
     }
 
-    /// Writes an unsigned 64 bit integer to the underlying writer.
-    ///
-    /// # Errors
-    ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// Returns an iterator that reads signed 16 bit integers from the
+    /// underlying reader until it is exhausted.
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// See [`iter_u16`](ReadBytesExt::iter_u16) for how the end of the data
+    /// is handled.
     ///
     /// # Examples
     ///
-    /// Write unsigned 64 bit big-endian integers to a `Write`:
-    ///
     /// ```rust
-    /// use byteorder::{BigEndian, WriteBytesExt};
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_u64::<BigEndian>(918733457491587).unwrap();
-    /// wtr.write_u64::<BigEndian>(143).unwrap();
-    /// assert_eq!(wtr, b"\x00\x03\x43\x95\x4d\x60\x86\x83\x00\x00\x00\x00\x00\x00\x00\x8f");
+    /// let mut rdr = Cursor::new(vec![0xff, 0xfe, 0, 2]);
+    /// let xs: Vec<i16> =
+    ///     rdr.iter_i16::<BigEndian>().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(vec![-2, 2], xs);
     /// ```
     #[inline]
-    fn write_u64<T: ByteOrder>(&mut self, n: u64) -> Result<()> {
-        let mut buf = [0; 8];
-        T::write_u64(&mut buf, n);
-        self.write_all(&buf)
+    fn iter_i16<T: ByteOrder>(&mut self) -> ReadIter<'_, Self, i16> {
+        ReadIter::new(self, 2, T::read_i16)
     }
 
-    /// Writes a signed 64 bit integer to the underlying writer.
-    ///
-    /// # Errors
+    /// Returns an iterator that reads unsigned 32 bit integers from the
+    /// underlying reader until it is exhausted.
     ///
-    /// This method returns the same errors as [`Write::write_all`].
-    ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// See [`iter_u16`](ReadBytesExt::iter_u16) for how the end of the data
+    /// is handled.
     ///
     /// # Examples
     ///
-    /// Write signed 64 bit big-endian integers to a `Write`:
-    ///
     /// ```rust
-    /// use byteorder::{BigEndian, WriteBytesExt};
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_i64::<BigEndian>(i64::min_value()).unwrap();
-    /// wtr.write_i64::<BigEndian>(i64::max_value()).unwrap();
-    /// assert_eq!(wtr, b"\x80\x00\x00\x00\x00\x00\x00\x00\x7f\xff\xff\xff\xff\xff\xff\xff");
+    /// let mut rdr = Cursor::new(vec![0, 0, 1, 0, 0, 0, 0, 7]);
+    /// let xs: Vec<u32> =
+    ///     rdr.iter_u32::<BigEndian>().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(vec![256, 7], xs);
     /// ```
     #[inline]
-    fn write_i64<T: ByteOrder>(&mut self, n: i64) -> Result<()> {
-        let mut buf = [0; 8];
-        T::write_i64(&mut buf, n);
-        self.write_all(&buf)
-    }
-
-    /// Writes an unsigned 128 bit integer to the underlying writer.
-    #[inline]
-    fn write_u128<T: ByteOrder>(&mut self, n: u128) -> Result<()> {
-        let mut buf = [0; 16];
-        T::write_u128(&mut buf, n);
-        self.write_all(&buf)
+    fn iter_u32<T: ByteOrder>(&mut self) -> ReadIter<'_, Self, u32> {
+        ReadIter::new(self, 4, T::read_u32)
     }
 
-    /// Writes a signed 128 bit integer to the underlying writer.
+    /// Returns an iterator that reads signed 32 bit integers from the
+    /// underlying reader until it is exhausted.
+    ///
+    /// See [`iter_u16`](ReadBytesExt::iter_u16) for how the end of the data
+    /// is handled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![0xff, 0xff, 0xff, 0xfb, 0, 0, 0, 5]);
+    /// let xs: Vec<i32> =
+    ///     rdr.iter_i32::<BigEndian>().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(vec![-5, 5], xs);
+    /// ```
     #[inline]
-    fn write_i128<T: ByteOrder>(&mut self, n: i128) -> Result<()> {
-        let mut buf = [0; 16];
-        T::write_i128(&mut buf, n);
-        self.write_all(&buf)
+    fn iter_i32<T: ByteOrder>(&mut self) -> ReadIter<'_, Self, i32> {
+        ReadIter::new(self, 4, T::read_i32)
     }
 
-    /// Writes an unsigned n-bytes integer to the underlying writer.
+    /// Returns an iterator that reads unsigned 64 bit integers from the
+    /// underlying reader until it is exhausted.
     ///
-    /// # Errors
+    /// See [`iter_u16`](ReadBytesExt::iter_u16) for how the end of the data
+    /// is handled.
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// # Examples
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// # Panics
+    /// let mut rdr = Cursor::new(vec![0, 0, 0, 0, 0, 0, 1, 0]);
+    /// let xs: Vec<u64> =
+    ///     rdr.iter_u64::<BigEndian>().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(vec![256], xs);
+    /// ```
+    #[inline]
+    fn iter_u64<T: ByteOrder>(&mut self) -> ReadIter<'_, Self, u64> {
+        ReadIter::new(self, 8, T::read_u64)
+    }
+
+    /// Returns an iterator that reads signed 64 bit integers from the
+    /// underlying reader until it is exhausted.
     ///
-    /// If the given integer is not representable in the given number of bytes,
-    /// this method panics. If `nbytes > 8`, this method panics.
+    /// See [`iter_u16`](ReadBytesExt::iter_u16) for how the end of the data
+    /// is handled.
     ///
     /// # Examples
     ///
-    /// Write unsigned 40 bit big-endian integers to a `Write`:
-    ///
     /// ```rust
-    /// use byteorder::{BigEndian, WriteBytesExt};
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_uint::<BigEndian>(312550384361, 5).unwrap();
-    /// wtr.write_uint::<BigEndian>(43, 5).unwrap();
-    /// assert_eq!(wtr, b"\x48\xc5\x74\x62\xe9\x00\x00\x00\x00\x2b");
+    /// let mut rdr = Cursor::new(vec![0xff; 8]);
+    /// let xs: Vec<i64> =
+    ///     rdr.iter_i64::<BigEndian>().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(vec![-1], xs);
     /// ```
     #[inline]
-    fn write_uint<T: ByteOrder>(
-        &mut self,
-        n: u64,
-        nbytes: usize,
-    ) -> Result<()> {
-        let mut buf = [0; 8];
-        T::write_uint(&mut buf, n, nbytes);
-        self.write_all(&buf[0..nbytes])
+    fn iter_i64<T: ByteOrder>(&mut self) -> ReadIter<'_, Self, i64> {
+        ReadIter::new(self, 8, T::read_i64)
     }
 
-    /// Writes a signed n-bytes integer to the underlying writer.
+    /// Returns an iterator that reads unsigned 128 bit integers from the
+    /// underlying reader until it is exhausted.
     ///
-    /// # Errors
+    /// See [`iter_u16`](ReadBytesExt::iter_u16) for how the end of the data
+    /// is handled.
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// # Examples
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// # Panics
+    /// let mut rdr = Cursor::new(vec![0; 16]);
+    /// let xs: Vec<u128> =
+    ///     rdr.iter_u128::<BigEndian>().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(vec![0], xs);
+    /// ```
+    #[inline]
+    fn iter_u128<T: ByteOrder>(&mut self) -> ReadIter<'_, Self, u128> {
+        ReadIter::new(self, 16, T::read_u128)
+    }
+
+    /// Returns an iterator that reads signed 128 bit integers from the
+    /// underlying reader until it is exhausted.
     ///
-    /// If the given integer is not representable in the given number of bytes,
-    /// this method panics. If `nbytes > 8`, this method panics.
+    /// See [`iter_u16`](ReadBytesExt::iter_u16) for how the end of the data
+    /// is handled.
     ///
     /// # Examples
     ///
-    /// Write signed 56 bit big-endian integers to a `Write`:
-    ///
     /// ```rust
-    /// use byteorder::{BigEndian, WriteBytesExt};
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_int::<BigEndian>(-3548172039376767, 7).unwrap();
-    /// wtr.write_int::<BigEndian>(43, 7).unwrap();
-    /// assert_eq!(wtr, b"\xf3\x64\xf4\xd1\xfd\xb0\x81\x00\x00\x00\x00\x00\x00\x2b");
+    /// let mut rdr = Cursor::new(vec![0xff; 16]);
+    /// let xs: Vec<i128> =
+    ///     rdr.iter_i128::<BigEndian>().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(vec![-1], xs);
     /// ```
     #[inline]
-    fn write_int<T: ByteOrder>(
-        &mut self,
-        n: i64,
-        nbytes: usize,
-    ) -> Result<()> {
-        let mut buf = [0; 8];
-        T::write_int(&mut buf, n, nbytes);
-        self.write_all(&buf[0..nbytes])
+    fn iter_i128<T: ByteOrder>(&mut self) -> ReadIter<'_, Self, i128> {
+        ReadIter::new(self, 16, T::read_i128)
     }
 
-    /// Writes an unsigned n-bytes integer to the underlying writer.
+    /// Returns an iterator that reads IEEE754 single-precision (4 bytes)
+    /// floating point numbers from the underlying reader until it is
+    /// exhausted.
     ///
-    /// If the given integer is not representable in the given number of bytes,
-    /// this method panics. If `nbytes > 16`, this method panics.
+    /// See [`iter_u16`](ReadBytesExt::iter_u16) for how the end of the data
+    /// is handled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![0x3f, 0x80, 0, 0, 0x40, 0, 0, 0]);
+    /// let xs: Vec<f32> =
+    ///     rdr.iter_f32::<BigEndian>().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(vec![1.0, 2.0], xs);
+    /// ```
     #[inline]
-    fn write_uint128<T: ByteOrder>(
-        &mut self,
-        n: u128,
-        nbytes: usize,
-    ) -> Result<()> {
-        let mut buf = [0; 16];
-        T::write_uint128(&mut buf, n, nbytes);
-        self.write_all(&buf[0..nbytes])
+    fn iter_f32<T: ByteOrder>(&mut self) -> ReadIter<'_, Self, f32> {
+        ReadIter::new(self, 4, T::read_f32)
     }
 
-    /// Writes a signed n-bytes integer to the underlying writer.
+    /// Returns an iterator that reads IEEE754 double-precision (8 bytes)
+    /// floating point numbers from the underlying reader until it is
+    /// exhausted.
     ///
-    /// If the given integer is not representable in the given number of bytes,
-    /// this method panics. If `nbytes > 16`, this method panics.
+    /// See [`iter_u16`](ReadBytesExt::iter_u16) for how the end of the data
+    /// is handled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![0x3f, 0xf0, 0, 0, 0, 0, 0, 0]);
+    /// let xs: Vec<f64> =
+    ///     rdr.iter_f64::<BigEndian>().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(vec![1.0], xs);
+    /// ```
     #[inline]
-    fn write_int128<T: ByteOrder>(
-        &mut self,
-        n: i128,
-        nbytes: usize,
-    ) -> Result<()> {
-        let mut buf = [0; 16];
-        T::write_int128(&mut buf, n, nbytes);
-        self.write_all(&buf[0..nbytes])
+    fn iter_f64<T: ByteOrder>(&mut self) -> ReadIter<'_, Self, f64> {
+        ReadIter::new(self, 8, T::read_f64)
     }
 
-    /// Writes a IEEE754 single-precision (4 bytes) floating point number to
-    /// the underlying writer.
+    /// Reads a value with `read` if `cond` is true, and otherwise returns
+    /// `default` without touching the underlying reader.
     ///
-    /// # Errors
+    /// This standardizes the common pattern of fields that only exist in
+    /// some versions of a format. For optional fields at the end of a record,
+    /// where old writers may simply stop early, see
+    /// [`VersionedReader::trailing`](crate::versioned::VersionedReader::trailing).
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// # Errors
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// This method returns any error returned by `read`.
     ///
     /// # Examples
     ///
-    /// Write a big-endian single-precision floating point number to a `Write`:
+    /// A flags field that was added in version 3 of a format:
     ///
     /// ```rust
-    /// use std::f32;
-    ///
-    /// use byteorder::{BigEndian, WriteBytesExt};
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_f32::<BigEndian>(f32::consts::PI).unwrap();
-    /// assert_eq!(wtr, b"\x40\x49\x0f\xdb");
+    /// let version = 2;
+    /// let mut rdr = Cursor::new(vec![0, 0, 0, 7]);
+    /// let flags =
+    ///     rdr.read_if(version >= 3, 0, |r| r.read_u32::<BigEndian>()).unwrap();
+    /// assert_eq!(0, flags);
+    /// assert_eq!(0, rdr.position());
     /// ```
     #[inline]
-    fn write_f32<T: ByteOrder>(&mut self, n: f32) -> Result<()> {
-        let mut buf = [0; 4];
-        T::write_f32(&mut buf, n);
-        self.write_all(&buf)
+    fn read_if<V, F>(&mut self, cond: bool, default: V, read: F) -> Result<V>
+    where
+        F: FnOnce(&mut Self) -> Result<V>,
+    {
+        if cond {
+            read(self)
+        } else {
+            Ok(default)
+        }
     }
 
-    /// Writes a IEEE754 double-precision (8 bytes) floating point number to
-    /// the underlying writer.
+    /// Reads an optional value, encoded as a presence byte followed by the
+    /// value if it is present.
+    ///
+    /// The layout is a single byte, `0x00` for `None` or `0x01` for `Some`,
+    /// followed in the latter case by the value as read by `read`. This is
+    /// the inverse of [`WriteBytesExt::write_option`].
+    ///
+    /// [`WriteBytesExt::write_option`]: crate::WriteBytesExt::write_option
     ///
     /// # Errors
     ///
-    /// This method returns the same errors as [`Write::write_all`].
+    /// This method returns the same errors as [`Read::read_exact`] when
+    /// reading the presence byte, and any error returned by `read`. If the
+    /// presence byte is neither `0x00` nor `0x01`, an error of kind
+    /// [`ErrorKind::InvalidData`] is returned.
     ///
-    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
     ///
     /// # Examples
     ///
-    /// Write a big-endian double-precision floating point number to a `Write`:
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![1, 0, 5, 0, 2]);
+    /// let read = |r: &mut Cursor<Vec<u8>>| r.read_u16::<BigEndian>();
+    /// assert_eq!(Some(5), rdr.read_option(read).unwrap());
+    /// assert_eq!(None, rdr.read_option(read).unwrap());
+    /// assert!(rdr.read_option(read).is_err());
+    /// ```
+    #[inline]
+    fn read_option<V, F>(&mut self, read: F) -> Result<Option<V>>
+    where
+        F: FnOnce(&mut Self) -> Result<V>,
+    {
+        match self.read_u8()? {
+            0 => Ok(None),
+            1 => read(self).map(Some),
+            b => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid presence byte {:#04x}", b),
+            )),
+        }
+    }
+
+    /// Reads a tagged enum, encoded as a tag of type `T` in byte order `E`
+    /// followed by the payload of the variant the tag selects.
+    ///
+    /// The tag is read first and passed to `read`, which reads the payload
+    /// and returns the variant, or `None` if the tag is not a known variant.
+    /// This is the inverse of [`WriteBytesExt::write_tagged_enum`].
+    ///
+    /// [`WriteBytesExt::write_tagged_enum`]: crate::WriteBytesExt::write_tagged_enum
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`] when
+    /// reading the tag, and any error returned by `read`. If `read` returns
+    /// `None`, an error of kind [`ErrorKind::InvalidData`] naming the tag is
+    /// returned.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum Shape {
+    ///     Circle { radius: u16 },
+    ///     Rect { w: u16, h: u16 },
+    /// }
+    ///
+    /// let mut rdr = Cursor::new(vec![0, 1, 0, 3, 0, 9, 0, 2, 0, 0, 0, 7]);
+    /// let mut read_shape = || {
+    ///     rdr.read_tagged_enum::<u16, BigEndian, _, _>(|r, tag| {
+    ///         Ok(match tag {
+    ///             1 => Some(Shape::Rect {
+    ///                 w: r.read_u16::<BigEndian>()?,
+    ///                 h: r.read_u16::<BigEndian>()?,
+    ///             }),
+    ///             2 => Some(Shape::Circle { radius: r.read_u16::<BigEndian>()? }),
+    ///             _ => None,
+    ///         })
+    ///     })
+    /// };
+    /// assert_eq!(Shape::Rect { w: 3, h: 9 }, read_shape().unwrap());
+    /// assert_eq!(Shape::Circle { radius: 0 }, read_shape().unwrap());
+    /// assert_eq!("unknown enum tag 7", read_shape().unwrap_err().to_string());
+    /// ```
+    #[inline]
+    fn read_tagged_enum<T, E, V, F>(&mut self, read: F) -> Result<V>
+    where
+        T: FromWire + Copy + fmt::Display,
+        E: ByteOrder,
+        F: FnOnce(&mut Self, T) -> Result<Option<V>>,
+    {
+        let tag = self.read_wire::<T, E>()?;
+        match read(self, tag)? {
+            Some(v) => Ok(v),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown enum tag {}", tag),
+            )),
+        }
+    }
+
+    /// Reads an integer of type `Repr` in byte order `E`, and converts it to
+    /// a `T` with [`TryFrom`].
+    ///
+    /// This is for fieldless enums and other types whose valid values are a
+    /// subset of an integer type, where any other value in the input is an
+    /// error. Unlike [`read_tagged_enum`](ReadBytesExt::read_tagged_enum),
+    /// no payload follows the integer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// conversion fails, an error of kind [`ErrorKind::InvalidData`] naming
+    /// the integer is returned.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{LittleEndian, ReadBytesExt};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum Compression {
+    ///     None = 0,
+    ///     Deflate = 8,
+    /// }
+    ///
+    /// impl TryFrom<u16> for Compression {
+    ///     type Error = ();
+    ///
+    ///     fn try_from(n: u16) -> Result<Compression, ()> {
+    ///         match n {
+    ///             0 => Ok(Compression::None),
+    ///             8 => Ok(Compression::Deflate),
+    ///             _ => Err(()),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut rdr = Cursor::new(vec![8, 0, 0, 0, 99, 0]);
+    /// let mut read = || rdr.read_enum::<u16, Compression, LittleEndian>();
+    /// assert_eq!(Compression::Deflate, read().unwrap());
+    /// assert_eq!(Compression::None, read().unwrap());
+    /// assert_eq!("invalid enum value 99", read().unwrap_err().to_string());
+    /// ```
+    #[inline]
+    fn read_enum<Repr, T, E>(&mut self) -> Result<T>
+    where
+        Repr: FromWire + Copy + fmt::Display,
+        T: TryFrom<Repr>,
+        E: ByteOrder,
+    {
+        let n = self.read_wire::<Repr, E>()?;
+        T::try_from(n).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid enum value {}", n),
+            )
+        })
+    }
+
+    /// Reads exactly `N` bytes from the underlying reader into an array.
+    ///
+    /// This is convenient for magic numbers, tags and hashes, which have a
+    /// fixed size known at compile time. No allocation is performed.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`].
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    ///
+    /// # Examples
+    ///
+    /// Read a magic number followed by a digest:
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::ReadBytesExt;
+    ///
+    /// let mut rdr = Cursor::new(b"\x7fELF0123456789abcdef");
+    /// assert_eq!(*b"\x7fELF", rdr.read_array().unwrap());
+    /// let digest: [u8; 16] = rdr.read_array().unwrap();
+    /// assert_eq!(b"0123456789abcdef", &digest);
+    /// assert!(rdr.read_array::<1>().is_err());
+    /// ```
+    #[inline]
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut buf = [0; N];
+        self.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Reads a value of any type implementing [`FromWire`] in the given byte
+    /// order.
+    ///
+    /// Exactly `T::WIRE_SIZE` bytes are read. This is most useful for
+    /// newtypes declared with [`wire_newtype!`](crate::wire_newtype), which
+    /// would otherwise have to be read as a number and wrapped by hand, and
+    /// for tuples of up to 12 elements, whose elements are read one after
+    /// the other, so that a fixed header can be read and destructured in one
+    /// expression.
+    ///
+    /// [`FromWire`]: crate::wire::FromWire
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// bytes read are not a valid value of `T`, an error of kind
+    /// [`ErrorKind::InvalidData`] is returned.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// Read an array of big-endian 16 bit integers:
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![0, 1, 0, 2, 0, 3]);
+    /// let xs = rdr.read_wire::<[u16; 3], BigEndian>().unwrap();
+    /// assert_eq!([1, 2, 3], xs);
+    /// ```
+    ///
+    /// Read a header of several fields as a tuple:
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{LittleEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![1, 0, 2, 0, 0, 0, 0, 0, 0xC0, 0x3F]);
+    /// let (kind, len, scale) =
+    ///     rdr.read_wire::<(u16, u32, f32), LittleEndian>().unwrap();
+    /// assert_eq!((1, 2, 1.5), (kind, len, scale));
+    /// ```
+    #[inline]
+    fn read_wire<T: FromWire, E: ByteOrder>(&mut self) -> Result<T> {
+        let mut buf = [0; WIRE_STACK_SIZE];
+        if T::WIRE_SIZE <= buf.len() {
+            let buf = &mut buf[..T::WIRE_SIZE];
+            self.read_exact(buf)?;
+            Ok(T::from_wire::<E>(buf)?)
+        } else {
+            let mut buf = vec![0; T::WIRE_SIZE];
+            self.read_exact(&mut buf)?;
+            Ok(T::from_wire::<E>(&buf)?)
+        }
+    }
+
+    /// Reads exactly `n` bytes from the underlying reader into a newly
+    /// allocated vector.
+    ///
+    /// This is useful for length prefixed data, where `n` was just read from
+    /// the same reader. Since such lengths can't be trusted, this never
+    /// allocates much more memory than the reader actually provides: the
+    /// vector starts with a bounded capacity and only grows as bytes arrive.
+    /// A corrupt length of, say, `u32::MAX` on a short input therefore fails
+    /// with an error rather than attempting a 4 GB allocation.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_to_end`]. If the
+    /// reader is exhausted before `n` bytes are read, an error of kind
+    /// [`ErrorKind::UnexpectedEof`] is returned. In that case, the bytes that
+    /// were read are consumed from the reader and lost.
+    ///
+    /// [`Read::read_to_end`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_to_end
+    /// [`ErrorKind::UnexpectedEof`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.UnexpectedEof
+    ///
+    /// # Examples
+    ///
+    /// Read a string prefixed with its big-endian 16 bit length:
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(b"\x00\x05hello, world");
+    /// let len = rdr.read_u16::<BigEndian>().unwrap();
+    /// assert_eq!(b"hello", &rdr.read_exact_vec(len as usize).unwrap()[..]);
+    ///
+    /// let mut rdr = Cursor::new(b"\xff\xffshort");
+    /// let len = rdr.read_u16::<BigEndian>().unwrap();
+    /// assert!(rdr.read_exact_vec(len as usize).is_err());
+    /// ```
+    #[inline]
+    fn read_exact_vec(&mut self, n: usize) -> Result<Vec<u8>> {
+        let mut buf = Vec::with_capacity(n.min(READ_VEC_MAX_CAPACITY));
+        let mut rdr = io::Read::take(self, n as u64);
+        io::Read::read_to_end(&mut rdr, &mut buf)?;
+        if buf.len() < n {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "failed to fill whole buffer",
+            ));
+        }
+        Ok(buf)
+    }
+
+    /// Reads `count` numbers of type `N` in the given byte order into a newly
+    /// allocated vector.
+    ///
+    /// `N` can be any of the primitive number types, as described by
+    /// [`Primitive`]. The bytes are read in large chunks and converted in
+    /// bulk with [`Primitive::read_slice`], just like with the `read_*_into`
+    /// methods, like [`read_u32_into`](ReadBytesExt::read_u32_into).
+    ///
+    /// Like [`read_exact_vec`](ReadBytesExt::read_exact_vec), this never
+    /// allocates much more memory than the reader actually provides, so a
+    /// corrupt `count` read from the input fails with an error instead of
+    /// attempting an enormous allocation.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// size of `count` values of type `N` overflows a `usize`, an error of
+    /// kind [`ErrorKind::InvalidInput`] is returned.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// Read an array of little-endian 32 bit integers prefixed with its
+    /// length:
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{LittleEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(vec![2, 0, 1, 0, 0, 0, 0, 1, 0, 0]);
+    /// let count = rdr.read_u16::<LittleEndian>().unwrap();
+    /// let xs = rdr.read_vec::<u32, LittleEndian>(count as usize).unwrap();
+    /// assert_eq!(vec![1, 256], xs);
+    /// ```
+    #[inline]
+    fn read_vec<N: Primitive, T: ByteOrder>(
+        &mut self,
+        count: usize,
+    ) -> Result<Vec<N>> {
+        if count.checked_mul(N::SIZE).is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} values of {} bytes overflow usize",
+                    count,
+                    N::SIZE
+                ),
+            ));
+        }
+        let first = count.min(READ_VEC_MAX_CAPACITY / N::SIZE);
+        let mut out = Vec::with_capacity(first);
+        let mut buf = vec![0; first * N::SIZE];
+        while out.len() < count {
+            // Read in chunks no bigger than the first, so that a short
+            // reader fails long before an absurd count is allocated.
+            let start = out.len();
+            let n = (count - start).min(first);
+            let bytes = &mut buf[..n * N::SIZE];
+            self.read_exact(bytes)?;
+            out.resize(start + n, N::default());
+            N::read_slice::<T>(bytes, &mut out[start..]);
+        }
+        Ok(out)
+    }
+
+    /// Reads `count` numbers of type `T` in the given byte order into a newly
+    /// allocated vector.
+    ///
+    /// This is exactly [`read_vec`](ReadBytesExt::read_vec), under the name
+    /// that pairs with [`write_values`](WriteBytesExt::write_values). Since
+    /// it takes any [`Primitive`] type, code that is itself generic over the
+    /// element type can read a run of numbers, like a reader for a column
+    /// whose type is only known by the caller.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`read_vec`](ReadBytesExt::read_vec).
+    ///
+    /// # Examples
+    ///
+    /// Read a column of numbers, whose type is chosen by the caller:
+    ///
+    /// ```rust
+    /// use std::io::{self, Cursor};
+    /// use byteorder::{BigEndian, Primitive, ReadBytesExt};
+    ///
+    /// fn read_column<T: Primitive>(
+    ///     rdr: &mut impl io::Read,
+    /// ) -> io::Result<Vec<T>> {
+    ///     let count = rdr.read_u8()?;
+    ///     rdr.read_values::<T, BigEndian>(count as usize)
+    /// }
+    ///
+    /// let mut rdr = Cursor::new(vec![2, 0, 1, 0xFF, 0xFF, 1, 0x3F, 0xC0, 0, 0]);
+    /// assert_eq!(vec![1, -1], read_column::<i16>(&mut rdr).unwrap());
+    /// assert_eq!(vec![1.5], read_column::<f32>(&mut rdr).unwrap());
+    /// ```
+    #[inline]
+    fn read_values<T: Primitive, E: ByteOrder>(
+        &mut self,
+        count: usize,
+    ) -> Result<Vec<T>> {
+        self.read_vec::<T, E>(count)
+    }
+
+    /// Reads an unsigned LEB128 variable length integer from the underlying
+    /// reader.
+    ///
+    /// See the [`varint`](crate::varint) module for the format. Bytes are
+    /// read one at a time, so that nothing after the integer is consumed,
+    /// which makes buffering the reader worthwhile.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// encoding is longer than
+    /// [`MAX_ULEB128_LEN`](crate::varint::MAX_ULEB128_LEN) bytes, or its
+    /// value doesn't fit in a `u64`, an error of kind
+    /// [`ErrorKind::InvalidData`] is returned, and no more than that many
+    /// bytes are read.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::ReadBytesExt;
+    ///
+    /// let mut rdr = Cursor::new(vec![0xE5, 0x8E, 0x26, 0x7F]);
+    /// assert_eq!(624485, rdr.read_uleb128().unwrap());
+    /// assert_eq!(127, rdr.read_uleb128().unwrap());
+    /// assert!(rdr.read_uleb128().is_err());
+    /// ```
+    #[inline]
+    fn read_uleb128(&mut self) -> Result<u64> {
+        let mut buf = [0; varint::MAX_ULEB128_LEN];
+        for i in 0..buf.len() {
+            self.read_exact(&mut buf[i..i + 1])?;
+            if buf[i] & 0x80 == 0 {
+                return Ok(varint::decode_uleb128(&buf[..i + 1])?.0);
+            }
+        }
+        // Too long, which decoding reports.
+        Ok(varint::decode_uleb128(&buf)?.0)
+    }
+
+    /// Reads a protocol buffers varint from the underlying reader.
+    ///
+    /// This is the same as [`read_uleb128`](ReadBytesExt::read_uleb128),
+    /// which follows the rules of the protocol buffers wire format exactly:
+    /// at most 10 bytes are read, and an encoding that is longer or whose
+    /// value doesn't fit in a `u64` is an error. See the
+    /// [`varint`](crate::varint) module for details.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`read_uleb128`](ReadBytesExt::read_uleb128).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::ReadBytesExt;
+    ///
+    /// let mut rdr = Cursor::new(vec![0x96, 0x01]);
+    /// assert_eq!(150, rdr.read_varint_u64().unwrap());
+    /// ```
+    #[inline]
+    fn read_varint_u64(&mut self) -> Result<u64> {
+        self.read_uleb128()
+    }
+
+    /// Reads an offset in the variable length encoding used by git
+    /// packfiles from the underlying reader.
+    ///
+    /// See the [`varint`](crate::varint) module for the format. Bytes are
+    /// read one at a time, so that nothing after the offset is consumed.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// value doesn't fit in a `u64`, an error of kind
+    /// [`ErrorKind::InvalidData`] is returned, and no more than
+    /// [`MAX_GIT_OFFSET_LEN`](crate::varint::MAX_GIT_OFFSET_LEN) bytes are
+    /// read.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::ReadBytesExt;
+    ///
+    /// let mut rdr = Cursor::new(vec![0x81, 0x2C, 0x7F]);
+    /// assert_eq!(300, rdr.read_git_offset().unwrap());
+    /// assert_eq!(127, rdr.read_git_offset().unwrap());
+    /// ```
+    #[inline]
+    fn read_git_offset(&mut self) -> Result<u64> {
+        let mut buf = [0; varint::MAX_GIT_OFFSET_LEN];
+        for i in 0..buf.len() {
+            self.read_exact(&mut buf[i..i + 1])?;
+            if buf[i] & 0x80 == 0 {
+                return Ok(varint::decode_git_offset(&buf[..i + 1])?.0);
+            }
+        }
+        // Any value that needs more bytes than this overflows.
+        Err(crate::Error::new(crate::ErrorKind::OutOfRange).into())
+    }
+
+    /// Reads a prefix varint from the underlying reader.
+    ///
+    /// See the [`varint`](crate::varint) module for the format. The first
+    /// byte is read on its own, and the rest of the varint, whose length it
+    /// gives, with a single call to `read_exact`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`].
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::ReadBytesExt;
+    ///
+    /// let mut rdr = Cursor::new(vec![0x81, 0x2C, 0x7F]);
+    /// assert_eq!(300, rdr.read_prefix_varint().unwrap());
+    /// assert_eq!(127, rdr.read_prefix_varint().unwrap());
+    /// ```
+    #[inline]
+    fn read_prefix_varint(&mut self) -> Result<u64> {
+        let mut buf = [0; varint::MAX_PREFIX_VARINT_LEN];
+        self.read_exact(&mut buf[..1])?;
+        let len = (buf[0].leading_ones() + 1) as usize;
+        self.read_exact(&mut buf[1..len])?;
+        Ok(varint::decode_prefix_varint(&buf[..len])?.0)
+    }
+
+    /// Reads a signed integer, ZigZag encoded as an unsigned LEB128 variable
+    /// length integer, from the underlying reader.
+    ///
+    /// This is how protocol buffers encode `sint32` and `sint64` fields. See
+    /// the [`varint`](crate::varint) module for the format.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`read_uleb128`](ReadBytesExt::read_uleb128).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::ReadBytesExt;
+    ///
+    /// let mut rdr = Cursor::new(vec![0x03, 0xFE, 0x03]);
+    /// assert_eq!(-2, rdr.read_varint_zigzag().unwrap());
+    /// assert_eq!(255, rdr.read_varint_zigzag().unwrap());
+    /// ```
+    #[inline]
+    fn read_varint_zigzag(&mut self) -> Result<i64> {
+        self.read_uleb128().map(varint::zigzag_decode_u64)
+    }
+
+    /// Reads an unsigned LEB128 variable length integer that fits in a
+    /// `u128` from the underlying reader.
+    ///
+    /// Values that fit in a `u64` are encoded the same way as for
+    /// [`read_uleb128`](ReadBytesExt::read_uleb128).
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// encoding is longer than
+    /// [`MAX_ULEB128_U128_LEN`](crate::varint::MAX_ULEB128_U128_LEN) bytes,
+    /// or its value doesn't fit in a `u128`, an error of kind
+    /// [`ErrorKind::InvalidData`] is returned, and no more than that many
+    /// bytes are read.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::ReadBytesExt;
+    ///
+    /// let mut data = vec![0xFF; 18];
+    /// data.push(0x03);
+    /// let mut rdr = Cursor::new(data);
+    /// assert_eq!(u128::MAX, rdr.read_uleb128_u128().unwrap());
+    /// ```
+    #[inline]
+    fn read_uleb128_u128(&mut self) -> Result<u128> {
+        let mut buf = [0; varint::MAX_ULEB128_U128_LEN];
+        for i in 0..buf.len() {
+            self.read_exact(&mut buf[i..i + 1])?;
+            if buf[i] & 0x80 == 0 {
+                return Ok(varint::decode_uleb128_u128(&buf[..i + 1])?.0);
+            }
+        }
+        // Too long, which decoding reports.
+        Ok(varint::decode_uleb128_u128(&buf)?.0)
+    }
+
+    /// Reads a signed 128 bit integer, ZigZag encoded as an unsigned LEB128
+    /// variable length integer, from the underlying reader.
+    ///
+    /// Values that fit in an `i64` are encoded the same way as for
+    /// [`read_varint_zigzag`](ReadBytesExt::read_varint_zigzag).
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`read_uleb128_u128`](ReadBytesExt::read_uleb128_u128).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::ReadBytesExt;
+    ///
+    /// let mut rdr = Cursor::new(vec![0x03, 0xFE, 0x03]);
+    /// assert_eq!(-2, rdr.read_varint_zigzag_i128().unwrap());
+    /// assert_eq!(255, rdr.read_varint_zigzag_i128().unwrap());
+    /// ```
+    #[inline]
+    fn read_varint_zigzag_i128(&mut self) -> Result<i128> {
+        self.read_uleb128_u128().map(varint::zigzag_decode_u128)
+    }
+
+    /// Reads a frame prefixed with its 32 bit length in the given byte order
+    /// from the underlying reader.
+    ///
+    /// Lengths read from the network can't be trusted, so a frame longer
+    /// than `max_len` bytes is an error, which is reported before any of it
+    /// is read or allocated. Even below that limit, memory is allocated as
+    /// bytes arrive, like with
+    /// [`read_exact_vec`](ReadBytesExt::read_exact_vec).
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`read_exact_vec`](ReadBytesExt::read_exact_vec). If the length is
+    /// greater than `max_len`, an error of kind [`ErrorKind::InvalidData`]
+    /// is returned, and only the length has been consumed from the reader.
+    ///
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::{Cursor, ErrorKind};
+    /// use byteorder::{BigEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(b"\x00\x00\x00\x05hello\xff\xff\xff\xff");
+    /// assert_eq!(b"hello", &rdr.read_frame::<BigEndian>(1024).unwrap()[..]);
+    ///
+    /// let err = rdr.read_frame::<BigEndian>(1024).unwrap_err();
+    /// assert_eq!(ErrorKind::InvalidData, err.kind());
+    /// ```
+    #[inline]
+    fn read_frame<T: ByteOrder>(&mut self, max_len: usize) -> Result<Vec<u8>> {
+        let len = self.read_u32::<T>()?;
+        read_frame_payload(self, u64::from(len), max_len)
+    }
+
+    /// Reads a frame prefixed with its 16 bit length in the given byte order
+    /// from the underlying reader.
+    ///
+    /// This is the same as [`read_frame`](ReadBytesExt::read_frame), but
+    /// with a 16 bit length.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`read_frame`](ReadBytesExt::read_frame).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{LittleEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(b"\x02\x00hi");
+    /// assert_eq!(b"hi", &rdr.read_frame_u16::<LittleEndian>(16).unwrap()[..]);
+    /// ```
+    #[inline]
+    fn read_frame_u16<T: ByteOrder>(
+        &mut self,
+        max_len: usize,
+    ) -> Result<Vec<u8>> {
+        let len = self.read_u16::<T>()?;
+        read_frame_payload(self, u64::from(len), max_len)
+    }
+
+    /// Reads a NUL terminated string from the underlying reader.
+    ///
+    /// Bytes are read one at a time up to and including the terminating NUL
+    /// byte, so that nothing after the string is consumed, which makes
+    /// buffering the reader worthwhile. The returned string doesn't include
+    /// the NUL byte. Use [`CString::into_bytes`] to get a plain vector, or
+    /// [`CString::into_string`] to check that it is UTF-8.
+    ///
+    /// [`CString::into_bytes`]: https://doc.rust-lang.org/std/ffi/struct.CString.html#method.into_bytes
+    /// [`CString::into_string`]: https://doc.rust-lang.org/std/ffi/struct.CString.html#method.into_string
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If no
+    /// NUL byte is found within the first `max_len` bytes of the string,
+    /// not counting the NUL byte itself, an error of kind
+    /// [`ErrorKind::InvalidData`] is returned, and no more than `max_len + 1`
+    /// bytes are read.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{LittleEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(b"libc.so.6\0\x01\x02too long\0");
+    /// let name = rdr.read_cstring(64).unwrap();
+    /// assert_eq!(b"libc.so.6", name.as_bytes());
+    /// assert_eq!(0x0201, rdr.read_u16::<LittleEndian>().unwrap());
+    /// assert!(rdr.read_cstring(4).is_err());
+    /// ```
+    #[inline]
+    fn read_cstring(&mut self, max_len: usize) -> Result<CString> {
+        let mut buf = Vec::new();
+        loop {
+            let b = self.read_u8()?;
+            if b == 0 {
+                break;
+            }
+            if buf.len() == max_len {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("C string longer than {} bytes", max_len),
+                ));
+            }
+            buf.push(b);
+        }
+        // There is no NUL byte in the buffer, since reading stopped at the
+        // first one.
+        Ok(CString::new(buf).expect("no interior NUL byte"))
+    }
+
+    /// Reads a NUL terminated string from the underlying reader, replacing
+    /// invalid UTF-8 with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// Like [`read_cstring`](ReadBytesExt::read_cstring), bytes are read one
+    /// at a time up to and including the terminating NUL byte, which isn't
+    /// part of the returned string. Unlike it, a string that is longer than
+    /// `max_len` bytes isn't an error: reading stops after `max_len` bytes,
+    /// and those bytes are returned. This suits tools that recover what they
+    /// can from damaged or untrusted data, like forensics and log parsers.
+    ///
+    /// Reading never goes past `max_len` bytes, even to look for the NUL
+    /// byte, since that would consume a byte of whatever follows a string
+    /// that was truncated. So when a string is exactly `max_len` bytes long,
+    /// its NUL byte is left unread, and the next call returns an empty
+    /// string. If the longest valid string is known, pass one more than its
+    /// length to read such strings together with their NUL byte.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`].
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::ReadBytesExt;
+    ///
+    /// let mut rdr = Cursor::new(b"caf\xe9\0too long\0");
+    /// assert_eq!("caf\u{FFFD}", rdr.read_cstring_lossy(64).unwrap());
+    /// assert_eq!("too", rdr.read_cstring_lossy(3).unwrap());
+    /// assert_eq!(" long", rdr.read_cstring_lossy(64).unwrap());
+    /// ```
+    #[inline]
+    fn read_cstring_lossy(&mut self, max_len: usize) -> Result<String> {
+        let mut buf = Vec::new();
+        while buf.len() < max_len {
+            let b = self.read_u8()?;
+            if b == 0 {
+                break;
+            }
+            buf.push(b);
+        }
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
+
+    /// Reads a UTF-8 string prefixed with its length in bytes, which is
+    /// `width` bytes wide in the given byte order, from the underlying
+    /// reader.
+    ///
+    /// Memory is allocated as bytes arrive, like with
+    /// [`read_exact_vec`](ReadBytesExt::read_exact_vec), so a corrupt length
+    /// on a short input fails with an error rather than attempting a huge
+    /// allocation.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`read_exact_vec`](ReadBytesExt::read_exact_vec). If the string
+    /// isn't valid UTF-8, an error of kind [`ErrorKind::InvalidData`] is
+    /// returned, after the whole string has been read.
+    ///
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{BigEndian, PrefixWidth, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(b"\x00\x05hello\x02\xff\xfe");
+    /// let s = rdr.read_string_prefixed::<BigEndian>(PrefixWidth::U16);
+    /// assert_eq!("hello", s.unwrap());
+    /// assert!(rdr.read_string_prefixed::<BigEndian>(PrefixWidth::U8).is_err());
+    /// ```
+    #[inline]
+    fn read_string_prefixed<T: ByteOrder>(
+        &mut self,
+        width: PrefixWidth,
+    ) -> Result<String> {
+        let buf = read_prefixed::<T, Self>(self, width)?;
+        String::from_utf8(buf)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Reads a Pascal string, prefixed with its length in a single byte,
+    /// from the underlying reader.
+    ///
+    /// This layout is used by classic Mac OS resources, Photoshop files and
+    /// many disk structures. Since such strings are often in encodings like
+    /// Mac OS Roman rather than UTF-8, their bytes are returned as they are.
+    /// Use [`read_string_prefixed`](ReadBytesExt::read_string_prefixed)
+    /// with [`PrefixWidth::U8`] for UTF-8 strings.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`].
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::ReadBytesExt;
+    ///
+    /// let mut rdr = Cursor::new(b"\x06Geneva\x00");
+    /// assert_eq!(b"Geneva", &rdr.read_pascal_string().unwrap()[..]);
+    /// assert!(rdr.read_pascal_string().unwrap().is_empty());
+    /// ```
+    #[inline]
+    fn read_pascal_string(&mut self) -> Result<Vec<u8>> {
+        let len = self.read_u8()?;
+        self.read_exact_vec(usize::from(len))
+    }
+
+    /// Reads a string prefixed with its length in bytes, which is `width`
+    /// bytes wide in the given byte order, from the underlying reader,
+    /// replacing invalid UTF-8 with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// This is the same as
+    /// [`read_string_prefixed`](ReadBytesExt::read_string_prefixed), except
+    /// for the handling of invalid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`read_exact_vec`](ReadBytesExt::read_exact_vec).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{LittleEndian, PrefixWidth, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(b"\x03\x00\x00\x00a\xffb");
+    /// let s = rdr.read_string_prefixed_lossy::<LittleEndian>(PrefixWidth::U32);
+    /// assert_eq!("a\u{FFFD}b", s.unwrap());
+    /// ```
+    #[inline]
+    fn read_string_prefixed_lossy<T: ByteOrder>(
+        &mut self,
+        width: PrefixWidth,
+    ) -> Result<String> {
+        let buf = read_prefixed::<T, Self>(self, width)?;
+        // Avoid copying strings that are valid already.
+        Ok(match String::from_utf8(buf) {
+            Ok(s) => s,
+            Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
+        })
+    }
+
+    /// Reads a UTF-16 string of `len_units` 16 bit code units in the given
+    /// byte order from the underlying reader.
+    ///
+    /// Memory is allocated as code units arrive, like with
+    /// [`read_vec`](ReadBytesExt::read_vec), so a corrupt length on a short
+    /// input fails with an error rather than attempting a huge allocation.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// string contains an unpaired surrogate, an error of kind
+    /// [`ErrorKind::InvalidData`] is returned, after the whole string has
+    /// been read.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{LittleEndian, ReadBytesExt};
+    ///
+    /// let mut rdr = Cursor::new(b"h\x00i\x00\x3D\xD8\x00\xDE");
+    /// assert_eq!("hi\u{1F600}", rdr.read_utf16_string::<LittleEndian>(4).unwrap());
+    ///
+    /// // A lone high surrogate.
+    /// let mut rdr = Cursor::new(b"\x3D\xD8");
+    /// assert!(rdr.read_utf16_string::<LittleEndian>(1).is_err());
+    /// ```
+    #[inline]
+    fn read_utf16_string<T: ByteOrder>(
+        &mut self,
+        len_units: usize,
+    ) -> Result<String> {
+        let units = self.read_vec::<u16, T>(len_units)?;
+        decode_utf16(&units)
+    }
+
+    /// Reads a UTF-16 string of `len_units` 16 bit code units, which may
+    /// start with a byte order mark, from the underlying reader.
+    ///
+    /// If the first code unit is a byte order mark, `U+FEFF`, it determines
+    /// the byte order of the string and is not part of the result.
+    /// Otherwise, the string is read in the byte order `T`. `len_units`
+    /// includes the byte order mark, if any.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`read_utf16_string`](ReadBytesExt::read_utf16_string).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{LittleEndian, ReadBytesExt};
+    ///
+    /// // A big-endian string, despite the default.
+    /// let mut rdr = Cursor::new(b"\xFE\xFF\x00h\x00i");
+    /// assert_eq!("hi", rdr.read_utf16_string_bom::<LittleEndian>(3).unwrap());
+    ///
+    /// // No byte order mark, so the default applies.
+    /// let mut rdr = Cursor::new(b"h\x00i\x00");
+    /// assert_eq!("hi", rdr.read_utf16_string_bom::<LittleEndian>(2).unwrap());
+    /// ```
+    #[inline]
+    fn read_utf16_string_bom<T: ByteOrder>(
+        &mut self,
+        len_units: usize,
+    ) -> Result<String> {
+        if len_units == 0 {
+            return Ok(String::new());
+        }
+        let first = self.read_u16::<BigEndian>()?;
+        let (order, first) = match first {
+            0xFEFF => (Endianness::Big, None),
+            0xFFFE => (Endianness::Little, None),
+            _ if Endianness::of::<T>() == Endianness::Big => {
+                (Endianness::Big, Some(first))
+            }
+            _ => (Endianness::Little, Some(first.swap_bytes())),
+        };
+        let rest = match order {
+            Endianness::Big => {
+                self.read_vec::<u16, BigEndian>(len_units - 1)?
+            }
+            Endianness::Little => {
+                self.read_vec::<u16, LittleEndian>(len_units - 1)?
+            }
+        };
+        match first {
+            None => decode_utf16(&rest),
+            Some(first) => {
+                let mut units = Vec::with_capacity(len_units);
+                units.push(first);
+                units.extend_from_slice(&rest);
+                decode_utf16(&units)
+            }
+        }
+    }
+
+    /// Reads a text field of exactly `n` bytes from the underlying reader,
+    /// and returns its text without its padding.
+    ///
+    /// See [`Padding`] for how the padding is found.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`read_exact_vec`](ReadBytesExt::read_exact_vec). If the text isn't
+    /// valid UTF-8, an error of kind [`ErrorKind::InvalidData`] is
+    /// returned, after the whole field has been read.
+    ///
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// Read the name and magic fields of a tar header:
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{Padding, ReadBytesExt};
+    ///
+    /// let mut header = vec![0; 263];
+    /// header[..9].copy_from_slice(b"hello.txt");
+    /// header[257..].copy_from_slice(b"ustar\0");
+    /// let mut rdr = Cursor::new(header);
+    ///
+    /// assert_eq!("hello.txt", rdr.read_fixed_str(100, Padding::Nul).unwrap());
+    /// rdr.set_position(257);
+    /// assert_eq!("ustar", rdr.read_fixed_str(6, Padding::Nul).unwrap());
+    /// ```
+    #[inline]
+    fn read_fixed_str(
+        &mut self,
+        n: usize,
+        padding: Padding,
+    ) -> Result<String> {
+        let mut buf = self.read_exact_vec(n)?;
+        let len = padding.trim(&buf).len();
+        buf.truncate(len);
+        String::from_utf8(buf)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+/// All types that implement `Read` get methods defined in `ReadBytesExt`
+/// for free.
+impl<R: io::Read + ?Sized> ReadBytesExt for R {}
+
+/// An iterator over the numbers read from a reader.
+///
+/// This is created by the `iter_*` methods on [`ReadBytesExt`], like
+/// [`iter_u16`](ReadBytesExt::iter_u16). See there for how the end of the
+/// data is handled.
+pub struct ReadIter<'a, R: ?Sized, N> {
+    rdr: &'a mut R,
+    size: usize,
+    decode: fn(&[u8]) -> N,
+    done: bool,
+}
+
+impl<'a, R: ?Sized, N> ReadIter<'a, R, N> {
+    fn new(
+        rdr: &'a mut R,
+        size: usize,
+        decode: fn(&[u8]) -> N,
+    ) -> ReadIter<'a, R, N> {
+        ReadIter { rdr, size, decode, done: false }
+    }
+}
+
+impl<'a, R: ?Sized, N> fmt::Debug for ReadIter<'a, R, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadIter")
+            .field("size", &self.size)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl<'a, R: io::Read + ?Sized, N> Iterator for ReadIter<'a, R, N> {
+    type Item = Result<N>;
+
+    fn next(&mut self) -> Option<Result<N>> {
+        if self.done {
+            return None;
+        }
+        let mut buf = [0; 16];
+        let buf = &mut buf[..self.size];
+        let mut nread = 0;
+        while nread < buf.len() {
+            match self.rdr.read(&mut buf[nread..]) {
+                Ok(0) => {
+                    self.done = true;
+                    if nread == 0 {
+                        return None;
+                    }
+                    return Some(Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        format!(
+                            "trailing partial value: got {} of {} bytes",
+                            nread,
+                            buf.len(),
+                        ),
+                    )));
+                }
+                Ok(n) => nread += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        Some(Ok((self.decode)(buf)))
+    }
+}
+
+impl<'a, R: io::Read + ?Sized, N> iter::FusedIterator for ReadIter<'a, R, N> {}
+
+/// Extends [`Write`] with methods for writing numbers. (For `std::io`.)
+///
+/// Most of the methods defined here have an unconstrained type parameter that
+/// must be explicitly instantiated. Typically, it is instantiated with either
+/// the [`BigEndian`] or [`LittleEndian`] types defined in this crate.
+///
+/// # Examples
+///
+/// Write unsigned 16 bit big-endian integers to a [`Write`]:
+///
+/// ```rust
+/// use byteorder::{BigEndian, WriteBytesExt};
+///
+/// let mut wtr = vec![];
+/// wtr.write_u16::<BigEndian>(517).unwrap();
+/// wtr.write_u16::<BigEndian>(768).unwrap();
+/// assert_eq!(wtr, vec![2, 5, 3, 0]);
+/// ```
+///
+/// [`BigEndian`]: enum.BigEndian.html
+/// [`LittleEndian`]: enum.LittleEndian.html
+/// [`Write`]: https://doc.rust-lang.org/std/io/trait.Write.html
+pub trait WriteBytesExt: io::Write {
+    /// Writes an unsigned 8 bit integer to the underlying writer.
+    ///
+    /// Note that since this writes a single byte, no byte order conversions
+    /// are used. It is included for completeness.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write unsigned 8 bit integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_u8(2).unwrap();
+    /// wtr.write_u8(5).unwrap();
+    /// assert_eq!(wtr, b"\x02\x05");
+    /// ```
+    #[inline]
+    fn write_u8(&mut self, n: u8) -> Result<()> {
+        self.write_all(&[n])
+    }
+
+    /// Writes a signed 8 bit integer to the underlying writer.
+    ///
+    /// Note that since this writes a single byte, no byte order conversions
+    /// are used. It is included for completeness.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write signed 8 bit integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_i8(2).unwrap();
+    /// wtr.write_i8(-5).unwrap();
+    /// assert_eq!(wtr, b"\x02\xfb");
+    /// ```
+    #[inline]
+    fn write_i8(&mut self, n: i8) -> Result<()> {
+        self.write_all(&[n as u8])
+    }
+
+    /// Writes a boolean as a single byte, `1` for `true` and `0` for
+    /// `false`, to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_bool(true).unwrap();
+    /// wtr.write_bool(false).unwrap();
+    /// assert_eq!(wtr, [1, 0]);
+    /// ```
+    #[inline]
+    fn write_bool(&mut self, b: bool) -> Result<()> {
+        self.write_all(&[u8::from(b)])
+    }
+
+    /// Writes an unsigned 16 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write unsigned 16 bit big-endian integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_u16::<BigEndian>(517).unwrap();
+    /// wtr.write_u16::<BigEndian>(768).unwrap();
+    /// assert_eq!(wtr, b"\x02\x05\x03\x00");
+    /// ```
+    #[inline]
+    fn write_u16<T: ByteOrder>(&mut self, n: u16) -> Result<()> {
+        let mut buf = [0; 2];
+        T::write_u16(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes a signed 16 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write signed 16 bit big-endian integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_i16::<BigEndian>(193).unwrap();
+    /// wtr.write_i16::<BigEndian>(-132).unwrap();
+    /// assert_eq!(wtr, b"\x00\xc1\xff\x7c");
+    /// ```
+    #[inline]
+    fn write_i16<T: ByteOrder>(&mut self, n: i16) -> Result<()> {
+        let mut buf = [0; 2];
+        T::write_i16(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes an unsigned 24 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write unsigned 24 bit big-endian integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_u24::<BigEndian>(267).unwrap();
+    /// wtr.write_u24::<BigEndian>(120111).unwrap();
+    /// assert_eq!(wtr, b"\x00\x01\x0b\x01\xd5\x2f");
+    /// ```
+    #[inline]
+    fn write_u24<T: ByteOrder>(&mut self, n: u32) -> Result<()> {
+        let mut buf = [0; 3];
+        T::write_u24(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes a signed 24 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write signed 24 bit big-endian integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_i24::<BigEndian>(-34253).unwrap();
+    /// wtr.write_i24::<BigEndian>(120111).unwrap();
+    /// assert_eq!(wtr, b"\xff\x7a\x33\x01\xd5\x2f");
+    /// ```
+    #[inline]
+    fn write_i24<T: ByteOrder>(&mut self, n: i32) -> Result<()> {
+        let mut buf = [0; 3];
+        T::write_i24(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes an unsigned 32 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write unsigned 32 bit big-endian integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_u32::<BigEndian>(267).unwrap();
+    /// wtr.write_u32::<BigEndian>(1205419366).unwrap();
+    /// assert_eq!(wtr, b"\x00\x00\x01\x0b\x47\xd9\x3d\x66");
+    /// ```
+    #[inline]
+    fn write_u32<T: ByteOrder>(&mut self, n: u32) -> Result<()> {
+        let mut buf = [0; 4];
+        T::write_u32(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes a signed 32 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write signed 32 bit big-endian integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_i32::<BigEndian>(-34253).unwrap();
+    /// wtr.write_i32::<BigEndian>(1205419366).unwrap();
+    /// assert_eq!(wtr, b"\xff\xff\x7a\x33\x47\xd9\x3d\x66");
+    /// ```
+    #[inline]
+    fn write_i32<T: ByteOrder>(&mut self, n: i32) -> Result<()> {
+        let mut buf = [0; 4];
+        T::write_i32(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes an unsigned 48 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write unsigned 48 bit big-endian integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_u48::<BigEndian>(52360336390828).unwrap();
+    /// wtr.write_u48::<BigEndian>(541).unwrap();
+    /// assert_eq!(wtr, b"\x2f\x9f\x17\x40\x3a\xac\x00\x00\x00\x00\x02\x1d");
+    /// ```
+    #[inline]
+    fn write_u48<T: ByteOrder>(&mut self, n: u64) -> Result<()> {
+        let mut buf = [0; 6];
+        T::write_u48(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes a signed 48 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write signed 48 bit big-endian integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_i48::<BigEndian>(-108363435763825).unwrap();
+    /// wtr.write_i48::<BigEndian>(77).unwrap();
+    /// assert_eq!(wtr, b"\x9d\x71\xab\xe7\x97\x8f\x00\x00\x00\x00\x00\x4d");
+    /// ```
+    #[inline]
+    fn write_i48<T: ByteOrder>(&mut self, n: i64) -> Result<()> {
+        let mut buf = [0; 6];
+        T::write_i48(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes an unsigned 64 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write unsigned 64 bit big-endian integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_u64::<BigEndian>(918733457491587).unwrap();
+    /// wtr.write_u64::<BigEndian>(143).unwrap();
+    /// assert_eq!(wtr, b"\x00\x03\x43\x95\x4d\x60\x86\x83\x00\x00\x00\x00\x00\x00\x00\x8f");
+    /// ```
+    #[inline]
+    fn write_u64<T: ByteOrder>(&mut self, n: u64) -> Result<()> {
+        let mut buf = [0; 8];
+        T::write_u64(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes a signed 64 bit integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write signed 64 bit big-endian integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_i64::<BigEndian>(i64::min_value()).unwrap();
+    /// wtr.write_i64::<BigEndian>(i64::max_value()).unwrap();
+    /// assert_eq!(wtr, b"\x80\x00\x00\x00\x00\x00\x00\x00\x7f\xff\xff\xff\xff\xff\xff\xff");
+    /// ```
+    #[inline]
+    fn write_i64<T: ByteOrder>(&mut self, n: i64) -> Result<()> {
+        let mut buf = [0; 8];
+        T::write_i64(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes an unsigned 128 bit integer to the underlying writer.
+    #[inline]
+    fn write_u128<T: ByteOrder>(&mut self, n: u128) -> Result<()> {
+        let mut buf = [0; 16];
+        T::write_u128(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes a signed 128 bit integer to the underlying writer.
+    #[inline]
+    fn write_i128<T: ByteOrder>(&mut self, n: i128) -> Result<()> {
+        let mut buf = [0; 16];
+        T::write_i128(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes an unsigned n-bytes integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Panics
+    ///
+    /// If the given integer is not representable in the given number of bytes,
+    /// this method panics. If `nbytes > 8`, this method panics.
+    ///
+    /// # Examples
+    ///
+    /// Write unsigned 40 bit big-endian integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_uint::<BigEndian>(312550384361, 5).unwrap();
+    /// wtr.write_uint::<BigEndian>(43, 5).unwrap();
+    /// assert_eq!(wtr, b"\x48\xc5\x74\x62\xe9\x00\x00\x00\x00\x2b");
+    /// ```
+    #[inline]
+    fn write_uint<T: ByteOrder>(
+        &mut self,
+        n: u64,
+        nbytes: usize,
+    ) -> Result<()> {
+        let mut buf = [0; 8];
+        T::write_uint(&mut buf, n, nbytes);
+        self.write_all(&buf[0..nbytes])
+    }
+
+    /// Writes a signed n-bytes integer to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Panics
+    ///
+    /// If the given integer is not representable in the given number of bytes,
+    /// this method panics. If `nbytes > 8`, this method panics.
+    ///
+    /// # Examples
+    ///
+    /// Write signed 56 bit big-endian integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_int::<BigEndian>(-3548172039376767, 7).unwrap();
+    /// wtr.write_int::<BigEndian>(43, 7).unwrap();
+    /// assert_eq!(wtr, b"\xf3\x64\xf4\xd1\xfd\xb0\x81\x00\x00\x00\x00\x00\x00\x2b");
+    /// ```
+    #[inline]
+    fn write_int<T: ByteOrder>(
+        &mut self,
+        n: i64,
+        nbytes: usize,
+    ) -> Result<()> {
+        let mut buf = [0; 8];
+        T::write_int(&mut buf, n, nbytes);
+        self.write_all(&buf[0..nbytes])
+    }
+
+    /// Writes an unsigned n-bytes integer to the underlying writer, or
+    /// returns an error if it doesn't fit.
+    ///
+    /// This is like [`write_uint`](WriteBytesExt::write_uint), except it
+    /// never panics. This is useful when `n` or `nbytes` come from untrusted
+    /// input.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If `n`
+    /// is not representable in `nbytes` bytes, or if `nbytes` is not in the
+    /// range `1..=8`, an error of kind [`ErrorKind::InvalidInput`] is
+    /// returned and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::ErrorKind;
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.try_write_uint::<BigEndian>(0xFFFFFF, 3).unwrap();
+    /// let err = wtr.try_write_uint::<BigEndian>(0x1000000, 3).unwrap_err();
+    /// assert_eq!(ErrorKind::InvalidInput, err.kind());
+    /// assert_eq!(wtr, b"\xff\xff\xff");
+    /// ```
+    #[inline]
+    fn try_write_uint<T: ByteOrder>(
+        &mut self,
+        n: u64,
+        nbytes: usize,
+    ) -> Result<()> {
+        if !(1..=8).contains(&nbytes) || (nbytes < 8 && n >> (nbytes * 8) != 0)
+        {
+            return Err(overflow_error(nbytes));
+        }
+        self.write_uint::<T>(n, nbytes)
+    }
+
+    /// Writes a signed n-bytes integer to the underlying writer, or returns
+    /// an error if it doesn't fit.
+    ///
+    /// This is like [`write_int`](WriteBytesExt::write_int), except it never
+    /// panics. This is useful when `n` or `nbytes` come from untrusted input.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If `n`
+    /// is not representable in `nbytes` bytes, or if `nbytes` is not in the
+    /// range `1..=8`, an error of kind [`ErrorKind::InvalidInput`] is
+    /// returned and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::ErrorKind;
+    /// use byteorder::{LittleEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.try_write_int::<LittleEndian>(-128, 1).unwrap();
+    /// let err = wtr.try_write_int::<LittleEndian>(128, 1).unwrap_err();
+    /// assert_eq!(ErrorKind::InvalidInput, err.kind());
+    /// assert_eq!(wtr, b"\x80");
+    /// ```
+    #[inline]
+    fn try_write_int<T: ByteOrder>(
+        &mut self,
+        n: i64,
+        nbytes: usize,
+    ) -> Result<()> {
+        if !(1..=8).contains(&nbytes) {
+            return Err(overflow_error(nbytes));
+        }
+        if nbytes < 8 {
+            let bits = nbytes * 8;
+            // Sign extending the low `bits` bits must give back `n`.
+            let shift = 64 - bits;
+            if (n << shift) >> shift != n {
+                return Err(overflow_error(nbytes));
+            }
+        }
+        self.write_int::<T>(n, nbytes)
+    }
+
+    /// Writes an unsigned n-bytes integer to the underlying writer.
+    ///
+    /// If the given integer is not representable in the given number of bytes,
+    /// this method panics. If `nbytes > 16`, this method panics.
+    #[inline]
+    fn write_uint128<T: ByteOrder>(
+        &mut self,
+        n: u128,
+        nbytes: usize,
+    ) -> Result<()> {
+        let mut buf = [0; 16];
+        T::write_uint128(&mut buf, n, nbytes);
+        self.write_all(&buf[0..nbytes])
+    }
+
+    /// Writes a signed n-bytes integer to the underlying writer.
+    ///
+    /// If the given integer is not representable in the given number of bytes,
+    /// this method panics. If `nbytes > 16`, this method panics.
+    #[inline]
+    fn write_int128<T: ByteOrder>(
+        &mut self,
+        n: i128,
+        nbytes: usize,
+    ) -> Result<()> {
+        let mut buf = [0; 16];
+        T::write_int128(&mut buf, n, nbytes);
+        self.write_all(&buf[0..nbytes])
+    }
+
+    /// Writes an unsigned pointer sized integer in `width` bytes to the
+    /// underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If the
+    /// value does not fit in `width` bytes, an error of kind
+    /// [`ErrorKind::InvalidInput`] is returned and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// Write a length as a little-endian 32 bit integer:
+    ///
+    /// ```rust
+    /// use byteorder::{LittleEndian, PtrWidth, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_usize::<LittleEndian>(258, PtrWidth::W32).unwrap();
+    /// assert_eq!(wtr, b"\x02\x01\x00\x00");
+    /// ```
+    #[inline]
+    fn write_usize<T: ByteOrder>(
+        &mut self,
+        n: usize,
+        width: PtrWidth,
+    ) -> Result<()> {
+        match width {
+            PtrWidth::W16 => {
+                let n = u16::try_from(n).map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "value overflows 16 bits",
+                    )
+                })?;
+                self.write_u16::<T>(n)
+            }
+            PtrWidth::W32 => {
+                let n = u32::try_from(n).map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "value overflows 32 bits",
+                    )
+                })?;
+                self.write_u32::<T>(n)
+            }
+            PtrWidth::W64 => self.write_u64::<T>(n as u64),
+        }
+    }
+
+    /// Writes a signed pointer sized integer in `width` bytes to the
+    /// underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If the
+    /// value does not fit in `width` bytes, an error of kind
+    /// [`ErrorKind::InvalidInput`] is returned and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+    #[inline]
+    fn write_isize<T: ByteOrder>(
+        &mut self,
+        n: isize,
+        width: PtrWidth,
+    ) -> Result<()> {
+        match width {
+            PtrWidth::W16 => {
+                let n = i16::try_from(n).map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "value overflows 16 bits",
+                    )
+                })?;
+                self.write_i16::<T>(n)
+            }
+            PtrWidth::W32 => {
+                let n = i32::try_from(n).map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "value overflows 32 bits",
+                    )
+                })?;
+                self.write_i32::<T>(n)
+            }
+            PtrWidth::W64 => self.write_i64::<T>(n as i64),
+        }
+    }
+
+    /// Writes an `f32` as a IEEE754 half-precision (2 bytes) floating point
+    /// number to the underlying writer.
+    ///
+    /// The number is rounded as described in
+    /// [`ByteOrder::write_f16`](crate::ByteOrder::write_f16).
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write a big-endian half-precision floating point number to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_f16::<BigEndian>(-2.5).unwrap();
+    /// assert_eq!(wtr, b"\xc1\x00");
+    /// ```
+    #[inline]
+    fn write_f16<T: ByteOrder>(&mut self, n: f32) -> Result<()> {
+        let mut buf = [0; 2];
+        T::write_f16(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes a IEEE754 single-precision (4 bytes) floating point number to
+    /// the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write a big-endian single-precision floating point number to a `Write`:
+    ///
+    /// ```rust
+    /// use std::f32;
+    ///
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_f32::<BigEndian>(f32::consts::PI).unwrap();
+    /// assert_eq!(wtr, b"\x40\x49\x0f\xdb");
+    /// ```
+    #[inline]
+    fn write_f32<T: ByteOrder>(&mut self, n: f32) -> Result<()> {
+        let mut buf = [0; 4];
+        T::write_f32(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes a IEEE754 double-precision (8 bytes) floating point number to
+    /// the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write a big-endian double-precision floating point number to a `Write`:
+    ///
+    /// ```rust
+    /// use std::f64;
+    ///
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_f64::<BigEndian>(f64::consts::PI).unwrap();
+    /// assert_eq!(wtr, b"\x40\x09\x21\xfb\x54\x44\x2d\x18");
+    /// ```
+    #[inline]
+    fn write_f64<T: ByteOrder>(&mut self, n: f64) -> Result<()> {
+        let mut buf = [0; 8];
+        T::write_f64(&mut buf, n);
+        self.write_all(&buf)
+    }
+
+    /// Writes a `char`, as a 32 bit Unicode scalar value, to the underlying
+    /// writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{LittleEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_char::<LittleEndian>('\u{1F600}').unwrap();
+    /// assert_eq!(wtr, [0x00, 0xF6, 0x01, 0x00]);
+    /// ```
+    #[inline]
+    fn write_char<T: ByteOrder>(&mut self, c: char) -> Result<()> {
+        self.write_u32::<T>(u32::from(c))
+    }
+
+    /// Writes a sequence of unsigned 16 bit integers to the underlying writer.
+    ///
+    /// The numbers are encoded in chunks into a fixed size stack buffer,
+    /// which is then passed to a single `write_all` call per chunk. This is
+    /// much cheaper than calling [`write_u16`](WriteBytesExt::write_u16) for
+    /// each number. If an error is returned, an unspecified prefix of `src`
+    /// may have been written.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write a sequence of big-endian unsigned 16 bit integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_u16_from::<BigEndian>(&[517, 768]).unwrap();
+    /// assert_eq!(wtr, b"\x02\x05\x03\x00");
+    /// ```
+    #[inline]
+    fn write_u16_from<T: ByteOrder>(&mut self, src: &[u16]) -> Result<()> {
+        write_staged(self, src, T::write_u16_into)
+    }
+
+    /// Writes a sequence of unsigned 32 bit integers to the underlying writer.
+    ///
+    /// The numbers are encoded in chunks into a fixed size stack buffer,
+    /// which is then passed to a single `write_all` call per chunk. This is
+    /// much cheaper than calling [`write_u32`](WriteBytesExt::write_u32) for
+    /// each number. If an error is returned, an unspecified prefix of `src`
+    /// may have been written.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write a sequence of big-endian unsigned 32 bit integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_u32_from::<BigEndian>(&[517, 768]).unwrap();
+    /// assert_eq!(wtr, b"\x00\x00\x02\x05\x00\x00\x03\x00");
+    /// ```
+    #[inline]
+    fn write_u32_from<T: ByteOrder>(&mut self, src: &[u32]) -> Result<()> {
+        write_staged(self, src, T::write_u32_into)
+    }
+
+    /// Writes a sequence of unsigned 64 bit integers to the underlying writer.
+    ///
+    /// The numbers are encoded in chunks into a fixed size stack buffer,
+    /// which is then passed to a single `write_all` call per chunk. This is
+    /// much cheaper than calling [`write_u64`](WriteBytesExt::write_u64) for
+    /// each number. If an error is returned, an unspecified prefix of `src`
+    /// may have been written.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    #[inline]
+    fn write_u64_from<T: ByteOrder>(&mut self, src: &[u64]) -> Result<()> {
+        write_staged(self, src, T::write_u64_into)
+    }
+
+    /// Writes a sequence of unsigned 128 bit integers to the underlying writer.
+    ///
+    /// The numbers are encoded in chunks into a fixed size stack buffer,
+    /// which is then passed to a single `write_all` call per chunk. This is
+    /// much cheaper than calling [`write_u128`](WriteBytesExt::write_u128) for
+    /// each number. If an error is returned, an unspecified prefix of `src`
+    /// may have been written.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    #[inline]
+    fn write_u128_from<T: ByteOrder>(&mut self, src: &[u128]) -> Result<()> {
+        write_staged(self, src, T::write_u128_into)
+    }
+
+    /// Writes a sequence of signed 8 bit integers to the underlying writer.
+    ///
+    /// Note that since each `i8` is a single byte, no byte order conversions
+    /// are used. It is included for completeness.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write a sequence of signed 8 bit integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_i8_from(&[2, -5, 3]).unwrap();
+    /// assert_eq!(wtr, b"\x02\xfb\x03");
+    /// ```
+    #[inline]
+    fn write_i8_from(&mut self, src: &[i8]) -> Result<()> {
+        write_staged(self, src, |src, dst| {
+            for (dst, &n) in dst.iter_mut().zip(src) {
+                *dst = n as u8;
+            }
+        })
+    }
+
+    /// Writes a sequence of signed 16 bit integers to the underlying writer.
+    ///
+    /// The numbers are encoded in chunks into a fixed size stack buffer,
+    /// which is then passed to a single `write_all` call per chunk. This is
+    /// much cheaper than calling [`write_i16`](WriteBytesExt::write_i16) for
+    /// each number. If an error is returned, an unspecified prefix of `src`
+    /// may have been written.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write a sequence of big-endian signed 16 bit integers to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_i16_from::<BigEndian>(&[-2, 3]).unwrap();
+    /// assert_eq!(wtr, b"\xff\xfe\x00\x03");
+    /// ```
+    #[inline]
+    fn write_i16_from<T: ByteOrder>(&mut self, src: &[i16]) -> Result<()> {
+        write_staged(self, src, T::write_i16_into)
+    }
+
+    /// Writes a sequence of signed 32 bit integers to the underlying writer.
+    ///
+    /// The numbers are encoded in chunks into a fixed size stack buffer,
+    /// which is then passed to a single `write_all` call per chunk. This is
+    /// much cheaper than calling [`write_i32`](WriteBytesExt::write_i32) for
+    /// each number. If an error is returned, an unspecified prefix of `src`
+    /// may have been written.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    #[inline]
+    fn write_i32_from<T: ByteOrder>(&mut self, src: &[i32]) -> Result<()> {
+        write_staged(self, src, T::write_i32_into)
+    }
+
+    /// Writes a sequence of signed 64 bit integers to the underlying writer.
+    ///
+    /// The numbers are encoded in chunks into a fixed size stack buffer,
+    /// which is then passed to a single `write_all` call per chunk. This is
+    /// much cheaper than calling [`write_i64`](WriteBytesExt::write_i64) for
+    /// each number. If an error is returned, an unspecified prefix of `src`
+    /// may have been written.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    #[inline]
+    fn write_i64_from<T: ByteOrder>(&mut self, src: &[i64]) -> Result<()> {
+        write_staged(self, src, T::write_i64_into)
+    }
+
+    /// Writes a sequence of signed 128 bit integers to the underlying writer.
+    ///
+    /// The numbers are encoded in chunks into a fixed size stack buffer,
+    /// which is then passed to a single `write_all` call per chunk. This is
+    /// much cheaper than calling [`write_i128`](WriteBytesExt::write_i128) for
+    /// each number. If an error is returned, an unspecified prefix of `src`
+    /// may have been written.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    #[inline]
+    fn write_i128_from<T: ByteOrder>(&mut self, src: &[i128]) -> Result<()> {
+        write_staged(self, src, T::write_i128_into)
+    }
+
+    /// Writes a sequence of IEEE754 single-precision (4 bytes) floating
+    /// point numbers to the underlying writer.
+    ///
+    /// The numbers are encoded in chunks into a fixed size stack buffer,
+    /// which is then passed to a single `write_all` call per chunk. This is
+    /// much cheaper than calling [`write_f32`](WriteBytesExt::write_f32) for
+    /// each number. If an error is returned, an unspecified prefix of `src`
+    /// may have been written.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write a sequence of big-endian single-precision floats to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_f32_from::<BigEndian>(&[1.0, -2.5]).unwrap();
+    /// assert_eq!(wtr, b"\x3f\x80\x00\x00\xc0\x20\x00\x00");
+    /// ```
+    #[inline]
+    fn write_f32_from<T: ByteOrder>(&mut self, src: &[f32]) -> Result<()> {
+        write_staged(self, src, T::write_f32_into)
+    }
+
+    /// Writes a sequence of IEEE754 double-precision (8 bytes) floating
+    /// point numbers to the underlying writer.
+    ///
+    /// The numbers are encoded in chunks into a fixed size stack buffer,
+    /// which is then passed to a single `write_all` call per chunk. This is
+    /// much cheaper than calling [`write_f64`](WriteBytesExt::write_f64) for
+    /// each number. If an error is returned, an unspecified prefix of `src`
+    /// may have been written.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write a sequence of big-endian double-precision floats to a `Write`:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_f64_from::<BigEndian>(&[1.0]).unwrap();
+    /// assert_eq!(wtr, b"\x3f\xf0\x00\x00\x00\x00\x00\x00");
+    /// ```
+    #[inline]
+    fn write_f64_from<T: ByteOrder>(&mut self, src: &[f64]) -> Result<()> {
+        write_staged(self, src, T::write_f64_into)
+    }
+
+    /// Writes every unsigned 16 bit integer produced by an iterator to the
+    /// underlying writer.
+    ///
+    /// See [`write_all_u32`](WriteBytesExt::write_all_u32) for details.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_all_u16::<BigEndian, _>(&[1, 2]).unwrap();
+    /// assert_eq!(wtr, b"\x00\x01\x00\x02");
+    /// ```
+    #[inline]
+    fn write_all_u16<T, I>(&mut self, iter: I) -> Result<()>
+    where
+        T: ByteOrder,
+        I: IntoIterator,
+        I::Item: Borrow<u16>,
+    {
+        write_iter(self, iter, T::write_u16)
+    }
+
+    /// Writes every signed 16 bit integer produced by an iterator to the
+    /// underlying writer.
+    ///
+    /// See [`write_all_u32`](WriteBytesExt::write_all_u32) for details.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_all_i16::<BigEndian, _>(&[-2, 2]).unwrap();
+    /// assert_eq!(wtr, b"\xff\xfe\x00\x02");
+    /// ```
+    #[inline]
+    fn write_all_i16<T, I>(&mut self, iter: I) -> Result<()>
+    where
+        T: ByteOrder,
+        I: IntoIterator,
+        I::Item: Borrow<i16>,
+    {
+        write_iter(self, iter, T::write_i16)
+    }
+
+    /// Writes every unsigned 32 bit integer produced by an iterator to the
+    /// underlying writer.
+    ///
+    /// The iterator may yield numbers or references to numbers, so a slice or
+    /// `Vec` can be passed by reference. The numbers are encoded in chunks
+    /// into a fixed size stack buffer, which is then passed to a single
+    /// `write_all` call per chunk. If an error is returned, an unspecified
+    /// prefix of the numbers may have been written.
+    ///
+    /// For numbers that are already in a slice,
+    /// [`write_u32_from`](WriteBytesExt::write_u32_from) does the same thing.
+    /// This method is for numbers that are computed on the fly.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// Write the squares of some numbers as little-endian integers:
+    ///
+    /// ```rust
+    /// use byteorder::{LittleEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_all_u32::<LittleEndian, _>((1..=3).map(|n| n * n)).unwrap();
+    /// assert_eq!(wtr, b"\x01\0\0\0\x04\0\0\0\x09\0\0\0");
+    ///
+    /// let numbers = vec![0x01020304];
+    /// wtr.clear();
+    /// wtr.write_all_u32::<LittleEndian, _>(&numbers).unwrap();
+    /// assert_eq!(wtr, b"\x04\x03\x02\x01");
+    /// ```
+    #[inline]
+    fn write_all_u32<T, I>(&mut self, iter: I) -> Result<()>
+    where
+        T: ByteOrder,
+        I: IntoIterator,
+        I::Item: Borrow<u32>,
+    {
+        write_iter(self, iter, T::write_u32)
+    }
+
+    /// Writes every signed 32 bit integer produced by an iterator to the
+    /// underlying writer.
+    ///
+    /// See [`write_all_u32`](WriteBytesExt::write_all_u32) for details.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_all_i32::<BigEndian, _>(&[-5]).unwrap();
+    /// assert_eq!(wtr, b"\xff\xff\xff\xfb");
+    /// ```
+    #[inline]
+    fn write_all_i32<T, I>(&mut self, iter: I) -> Result<()>
+    where
+        T: ByteOrder,
+        I: IntoIterator,
+        I::Item: Borrow<i32>,
+    {
+        write_iter(self, iter, T::write_i32)
+    }
+
+    /// Writes every unsigned 64 bit integer produced by an iterator to the
+    /// underlying writer.
+    ///
+    /// See [`write_all_u32`](WriteBytesExt::write_all_u32) for details.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_all_u64::<BigEndian, _>(&[256]).unwrap();
+    /// assert_eq!(wtr, b"\x00\x00\x00\x00\x00\x00\x01\x00");
+    /// ```
+    #[inline]
+    fn write_all_u64<T, I>(&mut self, iter: I) -> Result<()>
+    where
+        T: ByteOrder,
+        I: IntoIterator,
+        I::Item: Borrow<u64>,
+    {
+        write_iter(self, iter, T::write_u64)
+    }
+
+    /// Writes every signed 64 bit integer produced by an iterator to the
+    /// underlying writer.
+    ///
+    /// See [`write_all_u32`](WriteBytesExt::write_all_u32) for details.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_all_i64::<BigEndian, _>(&[-1]).unwrap();
+    /// assert_eq!(wtr, b"\xff\xff\xff\xff\xff\xff\xff\xff");
+    /// ```
+    #[inline]
+    fn write_all_i64<T, I>(&mut self, iter: I) -> Result<()>
+    where
+        T: ByteOrder,
+        I: IntoIterator,
+        I::Item: Borrow<i64>,
+    {
+        write_iter(self, iter, T::write_i64)
+    }
+
+    /// Writes every unsigned 128 bit integer produced by an iterator to the
+    /// underlying writer.
+    ///
+    /// See [`write_all_u32`](WriteBytesExt::write_all_u32) for details.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_all_u128::<BigEndian, _>(&[1]).unwrap();
+    /// assert_eq!(wtr, [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    /// ```
+    #[inline]
+    fn write_all_u128<T, I>(&mut self, iter: I) -> Result<()>
+    where
+        T: ByteOrder,
+        I: IntoIterator,
+        I::Item: Borrow<u128>,
+    {
+        write_iter(self, iter, T::write_u128)
+    }
+
+    /// Writes every signed 128 bit integer produced by an iterator to the
+    /// underlying writer.
+    ///
+    /// See [`write_all_u32`](WriteBytesExt::write_all_u32) for details.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_all_i128::<BigEndian, _>(&[-1]).unwrap();
+    /// assert_eq!(wtr, [0xff; 16]);
+    /// ```
+    #[inline]
+    fn write_all_i128<T, I>(&mut self, iter: I) -> Result<()>
+    where
+        T: ByteOrder,
+        I: IntoIterator,
+        I::Item: Borrow<i128>,
+    {
+        write_iter(self, iter, T::write_i128)
+    }
+
+    /// Writes every IEEE754 single-precision (4 bytes) floating point number
+    /// produced by an iterator to the underlying writer.
+    ///
+    /// See [`write_all_u32`](WriteBytesExt::write_all_u32) for details.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_all_f32::<BigEndian, _>(&[1.0, 2.0]).unwrap();
+    /// assert_eq!(wtr, b"\x3f\x80\x00\x00\x40\x00\x00\x00");
+    /// ```
+    #[inline]
+    fn write_all_f32<T, I>(&mut self, iter: I) -> Result<()>
+    where
+        T: ByteOrder,
+        I: IntoIterator,
+        I::Item: Borrow<f32>,
+    {
+        write_iter(self, iter, T::write_f32)
+    }
+
+    /// Writes every IEEE754 double-precision (8 bytes) floating point number
+    /// produced by an iterator to the underlying writer.
+    ///
+    /// See [`write_all_u32`](WriteBytesExt::write_all_u32) for details.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = Vec::new();
+    /// wtr.write_all_f64::<BigEndian, _>(&[1.0]).unwrap();
+    /// assert_eq!(wtr, b"\x3f\xf0\x00\x00\x00\x00\x00\x00");
+    /// ```
+    #[inline]
+    fn write_all_f64<T, I>(&mut self, iter: I) -> Result<()>
+    where
+        T: ByteOrder,
+        I: IntoIterator,
+        I::Item: Borrow<f64>,
+    {
+        write_iter(self, iter, T::write_f64)
+    }
+
+    /// Writes a value of any type implementing [`ToWire`] in the given byte
+    /// order.
+    ///
+    /// Exactly `T::WIRE_SIZE` bytes are written, with a single call to
+    /// `write_all`. Tuples of up to 12 elements are written one element after
+    /// the other, which is the inverse of reading them with
+    /// [`ReadBytesExt::read_wire`].
+    ///
+    /// [`ReadBytesExt::read_wire`]: crate::ReadBytesExt::read_wire
+    ///
+    /// [`ToWire`]: crate::wire::ToWire
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If the
+    /// value can't be encoded, an error of kind [`ErrorKind::InvalidData`]
+    /// is returned and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// Write a four character code followed by a little-endian length:
+    ///
+    /// ```rust
+    /// use byteorder::{fourcc::FourCc, LittleEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_wire::<_, LittleEndian>(&FourCc::new(*b"data")).unwrap();
+    /// wtr.write_wire::<_, LittleEndian>(&16u32).unwrap();
+    /// assert_eq!(b"data\x10\x00\x00\x00", &wtr[..]);
+    /// ```
+    ///
+    /// Write several fields as a tuple:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_wire::<_, BigEndian>(&(1u16, *b"ab", -1i8)).unwrap();
+    /// assert_eq!(wtr, [0, 1, b'a', b'b', 0xFF]);
+    /// ```
+    #[inline]
+    fn write_wire<T: ToWire, E: ByteOrder>(
+        &mut self,
+        value: &T,
+    ) -> Result<()> {
+        let mut buf = [0; WIRE_STACK_SIZE];
+        if T::WIRE_SIZE <= buf.len() {
+            let buf = &mut buf[..T::WIRE_SIZE];
+            value.to_wire::<E>(buf)?;
+            self.write_all(buf)
+        } else {
+            let mut buf = vec![0; T::WIRE_SIZE];
+            value.to_wire::<E>(&mut buf)?;
+            self.write_all(&buf)
+        }
+    }
+
+    /// Writes a slice of numbers of type `T` in the given byte order.
+    ///
+    /// This is the inverse of [`ReadBytesExt::read_values`], and like it,
+    /// takes any [`Primitive`] type, so that code that is generic over the
+    /// element type can write a run of numbers. Like the `write_*_into`
+    /// methods, the numbers are encoded in chunks on the stack with
+    /// [`Primitive::write_slice`], and each chunk is written with a single
+    /// call to `write_all`.
+    ///
+    /// [`ReadBytesExt::read_values`]: crate::ReadBytesExt::read_values
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{LittleEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_values::<u16, LittleEndian>(&[1, 0x0203]).unwrap();
+    /// wtr.write_values::<f32, LittleEndian>(&[1.5]).unwrap();
+    /// assert_eq!(wtr, [1, 0, 3, 2, 0, 0, 0xC0, 0x3F]);
+    /// ```
+    #[inline]
+    fn write_values<T: Primitive, E: ByteOrder>(
+        &mut self,
+        values: &[T],
+    ) -> Result<()> {
+        write_staged(self, values, T::write_slice::<E>)
+    }
+
+    /// Writes an optional value, encoded as a presence byte followed by the
+    /// value if it is present.
+    ///
+    /// The layout is a single byte, `0x00` for `None` or `0x01` for `Some`,
+    /// followed in the latter case by the value as written by `write`. This
+    /// is the inverse of [`ReadBytesExt::read_option`].
+    ///
+    /// [`ReadBytesExt::read_option`]: crate::ReadBytesExt::read_option
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`] when
+    /// writing the presence byte, and any error returned by `write`.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_option(Some(5), |w, n| w.write_u16::<BigEndian>(n)).unwrap();
+    /// wtr.write_option(None, |w, n| w.write_u16::<BigEndian>(n)).unwrap();
+    /// assert_eq!(wtr, [1, 0, 5, 0]);
+    /// ```
+    #[inline]
+    fn write_option<V, F>(&mut self, value: Option<V>, write: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self, V) -> Result<()>,
+    {
+        match value {
+            None => self.write_u8(0),
+            Some(v) => {
+                self.write_u8(1)?;
+                write(self, v)
+            }
+        }
+    }
+
+    /// Writes a sequence, prefixed with its number of elements, which is
+    /// `width` bytes wide in byte order `E`.
+    ///
+    /// After writing the count, `write` is called once per element, in
+    /// order. It may call this method again to write nested sequences. This
+    /// is the inverse of [`SeqReader::read_seq_with`], which also guards
+    /// against sequences that nest too deep or are too large when reading.
+    ///
+    /// [`SeqReader::read_seq_with`]: crate::seq::SeqReader::read_seq_with
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`] when
+    /// writing the count, and any error returned by `write`. If the number
+    /// of elements doesn't fit in `width`, an error of kind
+    /// [`ErrorKind::InvalidInput`] is returned, and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, PrefixWidth, WriteBytesExt};
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_seq_with::<BigEndian, _, _>(PrefixWidth::U8, &[1, 2], |w, &n| {
+    ///     w.write_u16::<BigEndian>(n)
+    /// })
+    /// .unwrap();
+    /// assert_eq!(wtr, [2, 0, 1, 0, 2]);
+    /// ```
+    #[inline]
+    fn write_seq_with<E, V, F>(
+        &mut self,
+        width: PrefixWidth,
+        values: &[V],
+        mut write: F,
+    ) -> Result<()>
+    where
+        E: ByteOrder,
+        F: FnMut(&mut Self, &V) -> Result<()>,
+    {
+        let len = values.len();
+        let err = || prefix_len_error(len, width.size());
+        match width {
+            PrefixWidth::U8 => {
+                self.write_u8(u8::try_from(len).map_err(|_| err())?)?
+            }
+            PrefixWidth::U16 => {
+                self.write_u16::<E>(u16::try_from(len).map_err(|_| err())?)?
+            }
+            PrefixWidth::U32 => {
+                self.write_u32::<E>(u32::try_from(len).map_err(|_| err())?)?
+            }
+        }
+        for v in values {
+            write(self, v)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a tagged enum, encoded as a tag of type `T` in byte order `E`
+    /// followed by the payload of the variant, as written by `write`.
+    ///
+    /// This is the inverse of [`ReadBytesExt::read_tagged_enum`].
+    ///
+    /// [`ReadBytesExt::read_tagged_enum`]: crate::ReadBytesExt::read_tagged_enum
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as
+    /// [`WriteBytesExt::write_wire`] when writing the tag, and any error
+    /// returned by `write`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{LittleEndian, WriteBytesExt};
+    ///
+    /// enum Message {
+    ///     Ping,
+    ///     Data(u32),
+    /// }
+    ///
+    /// fn write_message(wtr: &mut Vec<u8>, msg: &Message) -> std::io::Result<()> {
+    ///     match *msg {
+    ///         Message::Ping => {
+    ///             wtr.write_tagged_enum::<u8, LittleEndian, _>(0, |_| Ok(()))
+    ///         }
+    ///         Message::Data(n) => {
+    ///             wtr.write_tagged_enum::<u8, LittleEndian, _>(1, |w| {
+    ///                 w.write_u32::<LittleEndian>(n)
+    ///             })
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut wtr = vec![];
+    /// write_message(&mut wtr, &Message::Data(2)).unwrap();
+    /// write_message(&mut wtr, &Message::Ping).unwrap();
+    /// assert_eq!(wtr, [1, 2, 0, 0, 0, 0]);
+    /// ```
+    #[inline]
+    fn write_tagged_enum<T, E, F>(&mut self, tag: T, write: F) -> Result<()>
+    where
+        T: ToWire,
+        E: ByteOrder,
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        self.write_wire::<T, E>(&tag)?;
+        write(self)
+    }
+
+    /// Writes `n` as an unsigned LEB128 variable length integer to the
+    /// underlying writer.
+    ///
+    /// See the [`varint`](crate::varint) module for the format. The shortest
+    /// encoding is written, with a single call to `write_all`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_uleb128(624485).unwrap();
+    /// wtr.write_uleb128(0).unwrap();
+    /// assert_eq!(wtr, [0xE5, 0x8E, 0x26, 0]);
+    /// ```
+    #[inline]
+    fn write_uleb128(&mut self, n: u64) -> Result<()> {
+        let mut buf = [0; varint::MAX_ULEB128_LEN];
+        let len = varint::encode_uleb128(n, &mut buf)?;
+        self.write_all(&buf[..len])
+    }
+
+    /// Writes `n` as a protocol buffers varint to the underlying writer.
+    ///
+    /// This is the same as [`write_uleb128`](WriteBytesExt::write_uleb128).
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_varint_u64(150).unwrap();
+    /// assert_eq!(wtr, [0x96, 0x01]);
+    /// ```
+    #[inline]
+    fn write_varint_u64(&mut self, n: u64) -> Result<()> {
+        self.write_uleb128(n)
+    }
+
+    /// Writes `n` as an offset in the variable length encoding used by git
+    /// packfiles to the underlying writer.
+    ///
+    /// See the [`varint`](crate::varint) module for the format.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_git_offset(300).unwrap();
+    /// assert_eq!(wtr, [0x81, 0x2C]);
+    /// ```
+    #[inline]
+    fn write_git_offset(&mut self, n: u64) -> Result<()> {
+        let mut buf = [0; varint::MAX_GIT_OFFSET_LEN];
+        let len = varint::encode_git_offset(n, &mut buf)?;
+        self.write_all(&buf[..len])
+    }
+
+    /// Writes `n` as a prefix varint to the underlying writer.
+    ///
+    /// See the [`varint`](crate::varint) module for the format.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_prefix_varint(300).unwrap();
+    /// assert_eq!(wtr, [0x81, 0x2C]);
+    /// ```
+    #[inline]
+    fn write_prefix_varint(&mut self, n: u64) -> Result<()> {
+        let mut buf = [0; varint::MAX_PREFIX_VARINT_LEN];
+        let len = varint::encode_prefix_varint(n, &mut buf)?;
+        self.write_all(&buf[..len])
+    }
+
+    /// Writes `n` ZigZag encoded, as an unsigned LEB128 variable length
+    /// integer, to the underlying writer.
+    ///
+    /// This is how protocol buffers encode `sint32` and `sint64` fields. See
+    /// the [`varint`](crate::varint) module for the format.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_varint_zigzag(-2).unwrap();
+    /// wtr.write_varint_zigzag(255).unwrap();
+    /// assert_eq!(wtr, [0x03, 0xFE, 0x03]);
+    /// ```
+    #[inline]
+    fn write_varint_zigzag(&mut self, n: i64) -> Result<()> {
+        self.write_uleb128(varint::zigzag_encode_i64(n))
+    }
+
+    /// Writes the `u128` `n` as an unsigned LEB128 variable length integer
+    /// to the underlying writer.
+    ///
+    /// The shortest encoding, of at most
+    /// [`MAX_ULEB128_U128_LEN`](crate::varint::MAX_ULEB128_U128_LEN) bytes,
+    /// is always written.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_uleb128_u128(1 << 70).unwrap();
+    /// assert_eq!(wtr, [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01]);
+    /// ```
+    #[inline]
+    fn write_uleb128_u128(&mut self, n: u128) -> Result<()> {
+        let mut buf = [0; varint::MAX_ULEB128_U128_LEN];
+        let len = varint::encode_uleb128_u128(n, &mut buf)?;
+        self.write_all(&buf[..len])
+    }
+
+    /// Writes the `i128` `n` ZigZag encoded, as an unsigned LEB128 variable
+    /// length integer, to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_varint_zigzag_i128(-2).unwrap();
+    /// wtr.write_varint_zigzag_i128(i128::MIN).unwrap();
+    /// assert_eq!(&wtr[..2], &[0x03, 0xFF]);
+    /// assert_eq!(20, wtr.len());
+    /// ```
+    #[inline]
+    fn write_varint_zigzag_i128(&mut self, n: i128) -> Result<()> {
+        self.write_uleb128_u128(varint::zigzag_encode_i128(n))
+    }
+
+    /// Writes `frame` prefixed with its 32 bit length in the given byte order
+    /// to the underlying writer.
+    ///
+    /// This is the inverse of
+    /// [`ReadBytesExt::read_frame`](crate::ReadBytesExt::read_frame).
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If the
+    /// length of `frame` doesn't fit in a `u32`, an error of kind
+    /// [`ErrorKind::InvalidInput`] is returned, and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_frame::<BigEndian>(b"hello").unwrap();
+    /// assert_eq!(wtr, b"\x00\x00\x00\x05hello");
+    /// ```
+    #[inline]
+    fn write_frame<T: ByteOrder>(&mut self, frame: &[u8]) -> Result<()> {
+        let len = u32::try_from(frame.len())
+            .map_err(|_| prefix_len_error(frame.len(), 4))?;
+        self.write_u32::<T>(len)?;
+        self.write_all(frame)
+    }
+
+    /// Writes `frame` prefixed with its 16 bit length in the given byte order
+    /// to the underlying writer.
+    ///
+    /// This is the inverse of
+    /// [`ReadBytesExt::read_frame_u16`](crate::ReadBytesExt::read_frame_u16).
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If the
+    /// length of `frame` doesn't fit in a `u16`, an error of kind
+    /// [`ErrorKind::InvalidInput`] is returned, and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{LittleEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_frame_u16::<LittleEndian>(b"hi").unwrap();
+    /// assert_eq!(wtr, b"\x02\x00hi");
+    /// assert!(wtr.write_frame_u16::<LittleEndian>(&[0; 65536]).is_err());
+    /// ```
+    #[inline]
+    fn write_frame_u16<T: ByteOrder>(&mut self, frame: &[u8]) -> Result<()> {
+        let len = u16::try_from(frame.len())
+            .map_err(|_| prefix_len_error(frame.len(), 2))?;
+        self.write_u16::<T>(len)?;
+        self.write_all(frame)
+    }
+
+    /// Writes `s` followed by a terminating NUL byte to the underlying
+    /// writer.
+    ///
+    /// `s` can be any string of bytes that doesn't contain a NUL byte, like
+    /// the bytes of a `str`, or of a `CStr` without its terminator.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If `s`
+    /// contains a NUL byte, an error of kind [`ErrorKind::InvalidInput`] is
+    /// returned, and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
     ///
     /// ```rust
-    /// use std::f64;
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_cstring(b"main").unwrap();
+    /// wtr.write_cstring("".as_bytes()).unwrap();
+    /// assert_eq!(wtr, b"main\0\0");
+    /// assert!(wtr.write_cstring(b"a\0b").is_err());
+    /// ```
+    #[inline]
+    fn write_cstring(&mut self, s: &[u8]) -> Result<()> {
+        if s.contains(&0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "C string contains a NUL byte",
+            ));
+        }
+        self.write_all(s)?;
+        self.write_all(&[0])
+    }
+
+    /// Writes `s` prefixed with its length in bytes, which is `width` bytes
+    /// wide in the given byte order, to the underlying writer.
+    ///
+    /// This is the inverse of
+    /// [`ReadBytesExt::read_string_prefixed`](crate::ReadBytesExt::read_string_prefixed).
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If the
+    /// length of `s` doesn't fit in `width`, an error of kind
+    /// [`ErrorKind::InvalidInput`] is returned, and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, PrefixWidth, WriteBytesExt};
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_string_prefixed::<BigEndian>(PrefixWidth::U16, "hello").unwrap();
+    /// assert_eq!(wtr, b"\x00\x05hello");
+    /// ```
+    #[inline]
+    fn write_string_prefixed<T: ByteOrder>(
+        &mut self,
+        width: PrefixWidth,
+        s: &str,
+    ) -> Result<()> {
+        write_prefixed::<T, Self>(self, width, s.as_bytes())
+    }
+
+    /// Writes `s` as a Pascal string, prefixed with its length in a single
+    /// byte, to the underlying writer.
+    ///
+    /// This is the inverse of
+    /// [`ReadBytesExt::read_pascal_string`](crate::ReadBytesExt::read_pascal_string).
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If `s`
+    /// is longer than 255 bytes, an error of kind
+    /// [`ErrorKind::InvalidInput`] is returned, and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_pascal_string(b"Geneva").unwrap();
+    /// assert_eq!(wtr, b"\x06Geneva");
+    /// assert!(wtr.write_pascal_string(&[b'x'; 256]).is_err());
+    /// ```
+    #[inline]
+    fn write_pascal_string(&mut self, s: &[u8]) -> Result<()> {
+        // The byte order of a single byte length doesn't matter.
+        write_prefixed::<BigEndian, Self>(self, PrefixWidth::U8, s)
+    }
+
+    /// Writes `s` as UTF-16 in the given byte order to the underlying
+    /// writer.
+    ///
+    /// No length and no byte order mark are written. The number of code
+    /// units written is `s.encode_utf16().count()`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{LittleEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_utf16_string::<LittleEndian>("hi\u{1F600}").unwrap();
+    /// assert_eq!(wtr, b"h\x00i\x00\x3D\xD8\x00\xDE");
+    /// ```
+    #[inline]
+    fn write_utf16_string<T: ByteOrder>(&mut self, s: &str) -> Result<()> {
+        self.write_all_u16::<T, _>(s.encode_utf16())
+    }
+
+    /// Writes a byte order mark followed by `s` as UTF-16, both in the given
+    /// byte order, to the underlying writer.
+    ///
+    /// This is the inverse of
+    /// [`ReadBytesExt::read_utf16_string_bom`](crate::ReadBytesExt::read_utf16_string_bom),
+    /// where the byte order mark counts as one of the code units.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
     ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
     /// use byteorder::{BigEndian, WriteBytesExt};
     ///
-    /// let mut wtr = Vec::new();
-    /// wtr.write_f64::<BigEndian>(f64::consts::PI).unwrap();
-    /// assert_eq!(wtr, b"\x40\x09\x21\xfb\x54\x44\x2d\x18");
+    /// let mut wtr = vec![];
+    /// wtr.write_utf16_string_bom::<BigEndian>("hi").unwrap();
+    /// assert_eq!(wtr, b"\xFE\xFF\x00h\x00i");
     /// ```
     #[inline]
-    fn write_f64<T: ByteOrder>(&mut self, n: f64) -> Result<()> {
-        let mut buf = [0; 8];
-        T::write_f64(&mut buf, n);
-        self.write_all(&buf)
+    fn write_utf16_string_bom<T: ByteOrder>(&mut self, s: &str) -> Result<()> {
+        self.write_bom_u16::<T>()?;
+        self.write_utf16_string::<T>(s)
+    }
+
+    /// Writes a UTF-16 byte order mark in the given byte order to the
+    /// underlying writer.
+    ///
+    /// See the [`bom`](crate::bom) module for detecting it again.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{LittleEndian, WriteBytesExt};
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_bom_u16::<LittleEndian>().unwrap();
+    /// assert_eq!(wtr, b"\xFF\xFE");
+    /// ```
+    #[inline]
+    fn write_bom_u16<T: ByteOrder>(&mut self) -> Result<()> {
+        self.write_u16::<T>(0xFEFF)
+    }
+
+    /// Writes a UTF-8 byte order mark to the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`].
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::WriteBytesExt;
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_bom_u8().unwrap();
+    /// assert_eq!(wtr, b"\xEF\xBB\xBF");
+    /// ```
+    #[inline]
+    fn write_bom_u8(&mut self) -> Result<()> {
+        self.write_all(&bom::UTF8)
+    }
+
+    /// Writes `s` to a text field of exactly `n` bytes, padding it with
+    /// `padding`, to the underlying writer.
+    ///
+    /// When `s` is exactly `n` bytes long, no padding is written, which for
+    /// [`Padding::Nul`] means that the field has no terminating NUL byte.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If `s`
+    /// is longer than `n` bytes, an error of kind
+    /// [`ErrorKind::InvalidInput`] is returned, and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{Padding, WriteBytesExt};
+    ///
+    /// let mut wtr = vec![];
+    /// wtr.write_fixed_str("CD001", 8, Padding::Space).unwrap();
+    /// wtr.write_fixed_str("ab", 4, Padding::Nul).unwrap();
+    /// assert_eq!(wtr, b"CD001   ab\0\0");
+    /// assert!(wtr.write_fixed_str("too long", 4, Padding::Nul).is_err());
+    /// ```
+    #[inline]
+    fn write_fixed_str(
+        &mut self,
+        s: &str,
+        n: usize,
+        padding: Padding,
+    ) -> Result<()> {
+        if s.len() > n {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "string of {} bytes longer than {} byte field",
+                    s.len(),
+                    n
+                ),
+            ));
+        }
+        self.write_all(s.as_bytes())?;
+        let pad = [padding.byte(); 64];
+        let mut rest = n - s.len();
+        while rest > 0 {
+            let len = rest.min(pad.len());
+            self.write_all(&pad[..len])?;
+            rest -= len;
+        }
+        Ok(())
     }
 }
 
@@ -1578,13 +5101,659 @@ pub trait WriteBytesExt: io::Write {
 /// for free.
 impl<W: io::Write + ?Sized> WriteBytesExt for W {}
 
+/// Types that can be read from a [`Read`] in a given byte order.
+///
+/// This is implemented for every type implementing
+/// [`FromWire`](crate::wire::FromWire), which includes the numbers and
+/// arrays of them, by reading it with
+/// [`read_wire`](ReadBytesExt::read_wire). With the `derive` feature
+/// enabled, it can also be derived for structs whose fields all implement
+/// it, including other structs that derive it.
+///
+/// [`Read`]: https://doc.rust-lang.org/std/io/trait.Read.html
+///
+/// # Examples
+///
+/// ```rust
+/// use std::io::Cursor;
+/// use byteorder::{BigEndian, ReadBytes};
+///
+/// let mut rdr = Cursor::new(vec![0, 1, 2, 3]);
+/// assert_eq!(0x0001, u16::read_from::<_, BigEndian>(&mut rdr).unwrap());
+/// assert_eq!([2, 3], <[u8; 2]>::read_from::<_, BigEndian>(&mut rdr).unwrap());
+/// ```
+///
+/// Deriving it, along with [`WriteBytes`], for the header of a format whose
+/// magic number is always big-endian:
+///
+/// ```rust
+/// # #[cfg(feature = "derive")]
+/// # fn main() {
+/// use std::io::Cursor;
+/// use byteorder::{LittleEndian, ReadBytes, WriteBytes};
+///
+/// #[derive(Debug, PartialEq, ReadBytes, WriteBytes)]
+/// struct Chunk {
+///     #[byteorder(big)]
+///     magic: u32,
+///     lens: [u16; 2],
+///     origin: Point,
+///     #[byteorder(skip)]
+///     offset: u64,
+/// }
+///
+/// #[derive(Debug, PartialEq, ReadBytes, WriteBytes)]
+/// struct Point(i16, i16);
+///
+/// let data = b"CHNK\x01\x00\x02\x00\xFF\xFF\x03\x00";
+/// let chunk = Chunk::read_from::<_, LittleEndian>(&mut Cursor::new(data))
+///     .unwrap();
+/// assert_eq!(0x43484E4B, chunk.magic);
+/// assert_eq!([1, 2], chunk.lens);
+/// assert_eq!(Point(-1, 3), chunk.origin);
+/// assert_eq!(0, chunk.offset);
+///
+/// let mut wtr = vec![];
+/// chunk.write_to::<_, LittleEndian>(&mut wtr).unwrap();
+/// assert_eq!(&data[..], &wtr[..]);
+/// # }
+/// # #[cfg(not(feature = "derive"))]
+/// # fn main() {}
+/// ```
+pub trait ReadBytes: Sized {
+    /// Reads a value from `rdr` in byte order `E`.
+    ///
+    /// # Errors
+    ///
+    /// This returns the errors of the reads it makes, which are usually those
+    /// of [`Read::read_exact`], and an error of kind
+    /// [`ErrorKind::InvalidData`] when the bytes read are not a valid value.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    fn read_from<R: io::Read + ?Sized, E: ByteOrder>(
+        rdr: &mut R,
+    ) -> Result<Self>;
+}
+
+impl<T: FromWire> ReadBytes for T {
+    #[inline]
+    fn read_from<R: io::Read + ?Sized, E: ByteOrder>(
+        rdr: &mut R,
+    ) -> Result<T> {
+        rdr.read_wire::<T, E>()
+    }
+}
+
+/// Types that can be written to a [`Write`] in a given byte order.
+///
+/// This is implemented for every type implementing
+/// [`ToWire`](crate::wire::ToWire), which includes the numbers and arrays of
+/// them, by writing it with [`write_wire`](WriteBytesExt::write_wire). With
+/// the `derive` feature enabled, it can also be derived for structs whose
+/// fields all implement it, including other structs that derive it.
+///
+/// [`Write`]: https://doc.rust-lang.org/std/io/trait.Write.html
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{LittleEndian, WriteBytes};
+///
+/// let mut wtr = vec![];
+/// 0x0102u16.write_to::<_, LittleEndian>(&mut wtr).unwrap();
+/// [3u8, 4].write_to::<_, LittleEndian>(&mut wtr).unwrap();
+/// assert_eq!(vec![2, 1, 3, 4], wtr);
+/// ```
+pub trait WriteBytes {
+    /// Writes this value to `wtr` in byte order `E`.
+    ///
+    /// # Errors
+    ///
+    /// This returns the errors of the writes it makes, which are usually
+    /// those of [`Write::write_all`], and an error of kind
+    /// [`ErrorKind::InvalidData`] when the value can't be encoded.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    fn write_to<W: io::Write + ?Sized, E: ByteOrder>(
+        &self,
+        wtr: &mut W,
+    ) -> Result<()>;
+}
+
+impl<T: ToWire> WriteBytes for T {
+    #[inline]
+    fn write_to<W: io::Write + ?Sized, E: ByteOrder>(
+        &self,
+        wtr: &mut W,
+    ) -> Result<()> {
+        wtr.write_wire::<T, E>(self)
+    }
+}
+
+/// Reads the fields of a struct one after the other, without a derive.
+///
+/// This is an alternative to the `ReadBytes` derive macro of the `derive`
+/// feature, for code that avoids procedural macros. It takes a reader, which
+/// must be a `&mut` reference to a [`Read`], a byte order and a list of
+/// fields, and evaluates to an `io::Result`. There are two forms:
+///
+/// * `read_struct!(rdr, E, Header { a: u32, b: u16le })` creates a `Header`
+///   from the fields, which must be all of its fields.
+/// * `read_struct!(rdr, E, header => { a: u32, b: u16le })` assigns the
+///   fields of an existing value `header`, and evaluates to `io::Result<()>`.
+///
+/// Fields are read in the order they are listed. Each field is given a kind,
+/// which is one of:
+///
+/// * A number type with a `le` or `be` suffix, like `u16le` or `f64be`, which
+///   is read in that byte order rather than in `E`.
+/// * `u24`, `i24`, `u48` or `i48`, optionally with such a suffix, which is
+///   read into a `u32`, `i32`, `u64` or `i64` respectively.
+/// * Any other type implementing [`ReadBytes`], like `u32`, `[u8; 4]` or a
+///   struct that derives it, which is read in byte order `E`. A type that is
+///   more than one token, like `Vec<u8>`, must be wrapped in parentheses.
+///
+/// If a read fails, the error is returned and the fields that were read
+/// before it are lost.
+///
+/// [`Read`]: https://doc.rust-lang.org/std/io/trait.Read.html
+///
+/// # Examples
+///
+/// ```rust
+/// use std::io::Cursor;
+///
+/// use byteorder::{read_struct, BigEndian};
+///
+/// #[derive(Debug, Default, PartialEq)]
+/// struct Header {
+///     magic: [u8; 4],
+///     version: u16,
+///     flags: u16,
+///     len: u32,
+/// }
+///
+/// # fn main() -> std::io::Result<()> {
+/// let mut rdr = Cursor::new(b"WAVE\x00\x02\x01\x00\x00\x00\x01\x00");
+/// let header = read_struct!(&mut rdr, BigEndian, Header {
+///     magic: [u8; 4],
+///     version: u16,
+///     flags: u16le,
+///     len: u32,
+/// })?;
+/// assert_eq!(
+///     Header { magic: *b"WAVE", version: 2, flags: 1, len: 256 },
+///     header,
+/// );
+///
+/// // Or fill in some fields of an existing value.
+/// let mut header = Header::default();
+/// rdr.set_position(4);
+/// read_struct!(&mut rdr, BigEndian, header => { version: u16 })?;
+/// assert_eq!(2, header.version);
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! read_struct {
+    (
+        $rdr:expr, $order:ty,
+        $($name:ident)::+ { $($field:ident : $kind:tt),* $(,)? }
+    ) => {{
+        let rdr: &mut _ = $rdr;
+        #[allow(clippy::redundant_closure_call)]
+        let result = (|| -> ::std::io::Result<$($name)::+> {
+            ::std::result::Result::Ok($($name)::+ {
+                $($field: $crate::read_struct!(@field rdr, $order, $kind)?,)*
+            })
+        })();
+        result
+    }};
+    (
+        $rdr:expr, $order:ty,
+        $place:expr => { $($field:ident : $kind:tt),* $(,)? }
+    ) => {{
+        let rdr: &mut _ = $rdr;
+        #[allow(clippy::redundant_closure_call)]
+        let result = (|| -> ::std::io::Result<()> {
+            $(
+                $place.$field =
+                    $crate::read_struct!(@field rdr, $order, $kind)?;
+            )*
+            ::std::result::Result::Ok(())
+        })();
+        result
+    }};
+    (@field $rdr:ident, $order:ty, u16le) => {
+        $crate::ReadBytesExt::read_u16::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, u16be) => {
+        $crate::ReadBytesExt::read_u16::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, u32le) => {
+        $crate::ReadBytesExt::read_u32::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, u32be) => {
+        $crate::ReadBytesExt::read_u32::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, u64le) => {
+        $crate::ReadBytesExt::read_u64::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, u64be) => {
+        $crate::ReadBytesExt::read_u64::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, u128le) => {
+        $crate::ReadBytesExt::read_u128::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, u128be) => {
+        $crate::ReadBytesExt::read_u128::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i16le) => {
+        $crate::ReadBytesExt::read_i16::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i16be) => {
+        $crate::ReadBytesExt::read_i16::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i32le) => {
+        $crate::ReadBytesExt::read_i32::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i32be) => {
+        $crate::ReadBytesExt::read_i32::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i64le) => {
+        $crate::ReadBytesExt::read_i64::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i64be) => {
+        $crate::ReadBytesExt::read_i64::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i128le) => {
+        $crate::ReadBytesExt::read_i128::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i128be) => {
+        $crate::ReadBytesExt::read_i128::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, f32le) => {
+        $crate::ReadBytesExt::read_f32::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, f32be) => {
+        $crate::ReadBytesExt::read_f32::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, f64le) => {
+        $crate::ReadBytesExt::read_f64::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, f64be) => {
+        $crate::ReadBytesExt::read_f64::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, u24) => {
+        $crate::ReadBytesExt::read_u24::<$order>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, u24le) => {
+        $crate::ReadBytesExt::read_u24::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, u24be) => {
+        $crate::ReadBytesExt::read_u24::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i24) => {
+        $crate::ReadBytesExt::read_i24::<$order>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i24le) => {
+        $crate::ReadBytesExt::read_i24::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i24be) => {
+        $crate::ReadBytesExt::read_i24::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, u48) => {
+        $crate::ReadBytesExt::read_u48::<$order>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, u48le) => {
+        $crate::ReadBytesExt::read_u48::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, u48be) => {
+        $crate::ReadBytesExt::read_u48::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i48) => {
+        $crate::ReadBytesExt::read_i48::<$order>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i48le) => {
+        $crate::ReadBytesExt::read_i48::<$crate::LittleEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, i48be) => {
+        $crate::ReadBytesExt::read_i48::<$crate::BigEndian>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, ($ty:ty)) => {
+        <$ty as $crate::ReadBytes>::read_from::<_, $order>($rdr)
+    };
+    (@field $rdr:ident, $order:ty, $ty:ty) => {
+        <$ty as $crate::ReadBytes>::read_from::<_, $order>($rdr)
+    };
+}
+
+/// Writes the fields of a value one after the other, without a derive.
+///
+/// This is the counterpart of [`read_struct!`](crate::read_struct), and an
+/// alternative to the `WriteBytes` derive macro of the `derive` feature. It
+/// takes a writer, which must be a `&mut` reference to a [`Write`], a byte
+/// order, a value and a list of its fields, and evaluates to
+/// `io::Result<()>`:
+///
+/// ```text
+/// write_struct!(wtr, E, header => { a: u32, b: u16le })
+/// ```
+///
+/// Fields are written in the order they are listed, with the same kinds as
+/// in `read_struct!`. A field kind of `u24`, `i24`, `u48` or `i48` writes a
+/// `u32`, `i32`, `u64` or `i64` respectively, with the same methods of
+/// [`WriteBytesExt`], and so panics when the value doesn't fit. Other kinds
+/// must match the type of the field.
+///
+/// If a write fails, the error is returned and the fields before it may
+/// already have been written.
+///
+/// [`Write`]: https://doc.rust-lang.org/std/io/trait.Write.html
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{write_struct, LittleEndian};
+///
+/// struct Entry {
+///     id: u32,
+///     offset: u64,
+///     tag: [u8; 2],
+/// }
+///
+/// # fn main() -> std::io::Result<()> {
+/// let entry = Entry { id: 1, offset: 0x0203, tag: *b"ok" };
+/// let mut wtr = vec![];
+/// write_struct!(&mut wtr, LittleEndian, entry => {
+///     id: u32be,
+///     offset: u48,
+///     tag: [u8; 2],
+/// })?;
+/// assert_eq!(b"\x00\x00\x00\x01\x03\x02\x00\x00\x00\x00ok", &wtr[..]);
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! write_struct {
+    (
+        $wtr:expr, $order:ty,
+        $value:expr => { $($field:ident : $kind:tt),* $(,)? }
+    ) => {{
+        let wtr: &mut _ = $wtr;
+        let value = &$value;
+        #[allow(clippy::redundant_closure_call)]
+        let result = (|| -> ::std::io::Result<()> {
+            $(
+                $crate::write_struct!(@field wtr, $order, $kind, value.$field)?;
+            )*
+            ::std::result::Result::Ok(())
+        })();
+        result
+    }};
+    (@field $wtr:ident, $order:ty, u16le, $n:expr) => {
+        $crate::WriteBytesExt::write_u16::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, u16be, $n:expr) => {
+        $crate::WriteBytesExt::write_u16::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, u32le, $n:expr) => {
+        $crate::WriteBytesExt::write_u32::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, u32be, $n:expr) => {
+        $crate::WriteBytesExt::write_u32::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, u64le, $n:expr) => {
+        $crate::WriteBytesExt::write_u64::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, u64be, $n:expr) => {
+        $crate::WriteBytesExt::write_u64::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, u128le, $n:expr) => {
+        $crate::WriteBytesExt::write_u128::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, u128be, $n:expr) => {
+        $crate::WriteBytesExt::write_u128::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i16le, $n:expr) => {
+        $crate::WriteBytesExt::write_i16::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i16be, $n:expr) => {
+        $crate::WriteBytesExt::write_i16::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i32le, $n:expr) => {
+        $crate::WriteBytesExt::write_i32::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i32be, $n:expr) => {
+        $crate::WriteBytesExt::write_i32::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i64le, $n:expr) => {
+        $crate::WriteBytesExt::write_i64::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i64be, $n:expr) => {
+        $crate::WriteBytesExt::write_i64::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i128le, $n:expr) => {
+        $crate::WriteBytesExt::write_i128::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i128be, $n:expr) => {
+        $crate::WriteBytesExt::write_i128::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, f32le, $n:expr) => {
+        $crate::WriteBytesExt::write_f32::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, f32be, $n:expr) => {
+        $crate::WriteBytesExt::write_f32::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, f64le, $n:expr) => {
+        $crate::WriteBytesExt::write_f64::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, f64be, $n:expr) => {
+        $crate::WriteBytesExt::write_f64::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, u24, $n:expr) => {
+        $crate::WriteBytesExt::write_u24::<$order>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, u24le, $n:expr) => {
+        $crate::WriteBytesExt::write_u24::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, u24be, $n:expr) => {
+        $crate::WriteBytesExt::write_u24::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i24, $n:expr) => {
+        $crate::WriteBytesExt::write_i24::<$order>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i24le, $n:expr) => {
+        $crate::WriteBytesExt::write_i24::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i24be, $n:expr) => {
+        $crate::WriteBytesExt::write_i24::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, u48, $n:expr) => {
+        $crate::WriteBytesExt::write_u48::<$order>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, u48le, $n:expr) => {
+        $crate::WriteBytesExt::write_u48::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, u48be, $n:expr) => {
+        $crate::WriteBytesExt::write_u48::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i48, $n:expr) => {
+        $crate::WriteBytesExt::write_i48::<$order>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i48le, $n:expr) => {
+        $crate::WriteBytesExt::write_i48::<$crate::LittleEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, i48be, $n:expr) => {
+        $crate::WriteBytesExt::write_i48::<$crate::BigEndian>($wtr, $n)
+    };
+    (@field $wtr:ident, $order:ty, ($ty:ty), $n:expr) => {
+        <$ty as $crate::WriteBytes>::write_to::<_, $order>(&$n, $wtr)
+    };
+    (@field $wtr:ident, $order:ty, $ty:ty, $n:expr) => {
+        <$ty as $crate::WriteBytes>::write_to::<_, $order>(&$n, $wtr)
+    };
+}
+
+/// Returns the error used when a number can't be written in `nbytes` bytes.
+fn overflow_error(nbytes: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("integer not representable in {} bytes", nbytes),
+    )
+}
+
+/// Returns the error used when a number that must be nonzero is zero.
+fn zero_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "expected a nonzero value")
+}
+
+/// Reads the `len` byte payload of a frame whose length was just read,
+/// after checking it against `max_len`.
+fn read_frame_payload<R: io::Read + ?Sized>(
+    rdr: &mut R,
+    len: u64,
+    max_len: usize,
+) -> Result<Vec<u8>> {
+    match usize::try_from(len) {
+        Ok(len) if len <= max_len => rdr.read_exact_vec(len),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame of {} bytes exceeds limit of {}", len, max_len),
+        )),
+    }
+}
+
+/// Returns the error used when a length of `len` bytes doesn't fit in a
+/// length prefix of `nbytes` bytes.
+fn prefix_len_error(len: usize, nbytes: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("length {} doesn't fit in a {} byte prefix", len, nbytes),
+    )
+}
+
+/// Decodes UTF-16 code units, failing on unpaired surrogates.
+fn decode_utf16(units: &[u16]) -> Result<String> {
+    char::decode_utf16(units.iter().copied())
+        .collect::<std::result::Result<String, _>>()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Reads data prefixed with its length, which is `width` bytes wide in the
+/// byte order `T`.
+fn read_prefixed<T, R>(rdr: &mut R, width: PrefixWidth) -> Result<Vec<u8>>
+where
+    T: ByteOrder,
+    R: io::Read + ?Sized,
+{
+    let len = match width {
+        PrefixWidth::U8 => u64::from(rdr.read_u8()?),
+        PrefixWidth::U16 => u64::from(rdr.read_u16::<T>()?),
+        PrefixWidth::U32 => u64::from(rdr.read_u32::<T>()?),
+    };
+    read_frame_payload(rdr, len, usize::MAX)
+}
+
+/// Writes `data` prefixed with its length, which is `width` bytes wide in
+/// the byte order `T`.
+fn write_prefixed<T, W>(
+    wtr: &mut W,
+    width: PrefixWidth,
+    data: &[u8],
+) -> Result<()>
+where
+    T: ByteOrder,
+    W: io::Write + ?Sized,
+{
+    let len = data.len();
+    let err = || prefix_len_error(len, width.size());
+    match width {
+        PrefixWidth::U8 => {
+            wtr.write_u8(u8::try_from(len).map_err(|_| err())?)?
+        }
+        PrefixWidth::U16 => {
+            wtr.write_u16::<T>(u16::try_from(len).map_err(|_| err())?)?
+        }
+        PrefixWidth::U32 => {
+            wtr.write_u32::<T>(u32::try_from(len).map_err(|_| err())?)?
+        }
+    }
+    wtr.write_all(data)
+}
+
+/// The maximum capacity, in bytes, that `read_exact_vec` allocates up front.
+const READ_VEC_MAX_CAPACITY: usize = 64 * 1024;
+
+/// The largest wire size, in bytes, that `read_wire` and `write_wire` encode
+/// on the stack rather than in a heap allocated buffer.
+const WIRE_STACK_SIZE: usize = 64;
+
+/// The size, in bytes, of the stack buffer used to stage bulk writes.
+pub(crate) const STAGING_SIZE: usize = 4096;
+
+/// Writes `src` to `wtr` by encoding it into a stack buffer in chunks, and
+/// writing each chunk with a single call to `write_all`.
+///
+/// `encode` must fill the entire destination buffer, which is always exactly
+/// `size_of::<N>() * src.len()` bytes long.
+fn write_staged<W, N>(
+    wtr: &mut W,
+    src: &[N],
+    encode: fn(&[N], &mut [u8]),
+) -> Result<()>
+where
+    W: io::Write + ?Sized,
+{
+    let size = std::mem::size_of::<N>();
+    let mut buf = [0; STAGING_SIZE];
+    for chunk in src.chunks(STAGING_SIZE / size) {
+        let len = std::mem::size_of_val(chunk);
+        encode(chunk, &mut buf[..len]);
+        wtr.write_all(&buf[..len])?;
+    }
+    Ok(())
+}
+
+/// Writes every number produced by `iter` to `wtr` by encoding them into a
+/// stack buffer, and writing the buffer with `write_all` whenever it fills up.
+fn write_iter<W, I, N>(
+    wtr: &mut W,
+    iter: I,
+    encode: fn(&mut [u8], N),
+) -> Result<()>
+where
+    W: io::Write + ?Sized,
+    I: IntoIterator,
+    I::Item: Borrow<N>,
+    N: Copy,
+{
+    let size = std::mem::size_of::<N>();
+    let mut buf = [0; STAGING_SIZE];
+    let mut len = 0;
+    for n in iter {
+        if len + size > buf.len() {
+            wtr.write_all(&buf[..len])?;
+            len = 0;
+        }
+        encode(&mut buf[len..len + size], *n.borrow());
+        len += size;
+    }
+    if len > 0 {
+        wtr.write_all(&buf[..len])?;
+    }
+    Ok(())
+}
+
 /// Convert a slice of T (where T is plain old data) to its mutable binary
 /// representation.
 ///
 /// This function is wildly unsafe because it permits arbitrary modification of
 /// the binary representation of any `Copy` type. Use with care. It's intended
 /// to be called only where `T` is a numeric type.
-unsafe fn slice_to_u8_mut<T: Copy>(slice: &mut [T]) -> &mut [u8] {
+pub(crate) unsafe fn slice_to_u8_mut<T: Copy>(slice: &mut [T]) -> &mut [u8] {
     use std::mem::size_of;
 
     let len = size_of::<T>() * slice.len();
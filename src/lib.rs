@@ -44,10 +44,92 @@ assert_eq!(wtr, vec![5, 2, 0, 3]);
 
 # Optional Features
 
-This crate optionally provides support for 128 bit values (`i128` and `u128`)
-when built with the `i128` feature enabled.
-
-This crate can also be used without the standard library.
+Support for 128 bit values (`i128` and `u128`) is always available and does
+not require a nightly compiler. The `i128` feature that used to enable it is
+still accepted for compatibility, but it no longer does anything.
+
+This crate can also be used without the standard library by disabling the
+default `std` feature. [`ReadBytesExt`] and [`WriteBytesExt`] aren't available
+then, but [`ByteSliceExt`] and [`SliceReader`] read numbers from a byte
+slice, and [`SliceWriter`] writes them to one. On targets that have an
+allocator but not the standard library, enable the `alloc` feature as well, to
+keep the APIs that only need to allocate, like [`varint::encode_varints`] and
+the [`bitbuf`], [`heuristics`] and [`idlist`] modules.
+
+Without `std`, this crate also works on 16 bit microcontrollers like AVR and
+MSP430, where `usize` is only 16 bits wide. Nothing in it assumes a wider
+`usize`, and [`PtrWidth::native`] is [`PtrWidth::W16`] there.
+
+Without `std`, the [`Error`] returned by the slice APIs implements
+`core::error::Error` when the `core-error` feature is enabled, which requires
+Rust 1.81 or newer. It implements `std::error::Error` either way when `std` is
+enabled, and converts into a `std::io::Error`.
+
+The `minimal-panics` feature is meant for embedded targets where code size
+matters more than good panic messages. With it enabled, every panic caused by
+a buffer of the wrong length goes through a single cold function with a fixed
+message, instead of the formatted slice indexing or assertion message at each
+call site. The `try_` methods check lengths before reading or writing, so
+their panic paths are unreachable either way. Calling a dozen or so of the
+`ByteOrder` methods from a release build with `opt-level = "z"` shrinks the
+machine code by about 500 bytes, or less than one percent of that binary, so
+measure your own build before relying on it.
+
+The `no-panic` feature is for users who need a guarantee, rather than
+documentation, that their parsing code can't panic. It makes linking fail
+unless the compiler removed every panic path from the entry points that only
+fail with an error: the `try_` methods of [`ByteOrder`] for numbers of a fixed
+width, except the writes of 24 and 48 bit integers, and the reads and writes
+of [`ByteSliceExt`], [`SliceReader`] and [`SliceWriter`] for numbers of a fixed
+width, bytes and arrays. The check only happens in builds with optimizations
+and without debug assertions, like release builds, and it relies on unwinding,
+so it does nothing when `panic = "abort"`. Since the entry points are inlined
+into their callers, the check covers each call in your own release build.
+
+The `roundtrip` feature enables the `roundtrip` module, which has helpers for
+checking that this crate's read and write methods are inverses of each other,
+for use in fuzz targets and property tests.
+
+The `tokio` feature enables the `tokio` module, with extension traits for
+reading and writing numbers with tokio's asynchronous readers and writers.
+
+The `futures-io` feature enables the `futures` module, with the same extension
+traits for the asynchronous readers and writers of the `futures-io` crate.
+Since async-std and smol use those traits for their own I/O, this module works
+with both runtimes directly.
+
+The `embedded-io` feature enables the `embedded_io` module, with extension
+traits for the blocking `Read` and `Write` traits of the `embedded-io` crate
+used by embedded HALs. The `embedded-io-async` feature enables the
+`embedded_io_async` module, with the same traits for the async readers and
+writers of `embedded-io-async`. Neither feature requires `std`.
+
+The `heapless` feature enables the `heapless` module, with an extension trait
+for appending numbers to a `heapless::Vec<u8, N>`, which returns an error
+instead of panicking when the vector is full.
+
+The `derive` feature adds derive macros for the [`ReadBytes`] and
+[`WriteBytes`] traits, which read and write a struct field by field. Fields
+can be numbers, arrays and other types implementing those traits, and can be
+given a fixed byte order with `#[byteorder(big)]` or `#[byteorder(little)]`,
+or left out with `#[byteorder(skip)]`. This feature requires Rust 1.71 or
+newer.
+
+The `bytemuck` feature implements `bytemuck`'s `Pod` and `Zeroable` traits for
+the byte order aware integers of the [`types`] module, so that structs made of
+them can be cast from byte slices without copying.
+
+The `zerocopy` feature derives `zerocopy`'s traits for the same types, so that
+they can be fields of structs that derive those traits.
+
+The `serde` feature implements `serde`'s `Serialize` and `Deserialize` traits
+for the types of the [`types`] module, which are serialized as the numbers
+they hold, and for [`Endianness`], which is serialized as `"big"` or
+`"little"`.
+
+The `defmt` feature implements `defmt::Format` for this crate's error types,
+[`Endianness`] and the byte order types, so that firmware can log them with
+`defmt` without formatting them by hand.
 
 # Alternatives
 
@@ -72,17 +154,195 @@ cases.
 // When testing under miri, we disable tests that take too long. But this
 // provokes lots of dead code warnings. So we just squash them.
 #![cfg_attr(miri, allow(dead_code, unused_macros))]
+// Newer versions of clippy flag some long-standing code in this crate. We
+// keep that code as it is rather than churn it for each new lint.
+#![allow(
+    clippy::legacy_numeric_constants,
+    clippy::manual_slice_size_calculation,
+    clippy::ptr_offset_with_cast,
+    clippy::unnecessary_cast
+)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+// The derive macros refer to this crate as `::byteorder`, which only works in
+// this crate's own tests with this alias.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as byteorder;
 
 use core::{
     convert::TryInto, fmt::Debug, hash::Hash, mem::align_of,
     ptr::copy_nonoverlapping, slice,
 };
 
+pub use crate::error::{Error, ErrorKind};
 #[cfg(feature = "std")]
-pub use crate::io::{ReadBytesExt, WriteBytesExt};
+pub use crate::io::{
+    ReadBytes, ReadBytesExt, ReadIter, WriteBytes, WriteBytesExt,
+};
+pub use crate::primitive::Primitive;
+pub use crate::slice_ext::{ByteSliceExt, SliceReader, SliceWriter};
+#[cfg(feature = "derive")]
+pub use byteorder_derive::{ReadBytes, WriteBytes};
+
+/// Evaluates `$body`, and with the `no-panic` feature in an optimized build,
+/// makes linking fail unless the compiler removed every panic path from it.
+///
+/// This wraps the entry points that promise not to panic. A guard whose
+/// destructor calls a function that doesn't exist is alive while `$body`
+/// runs, so the destructor is only kept when `$body` can unwind.
+macro_rules! no_panic {
+    ($body:block) => {{
+        #[cfg(all(feature = "no-panic", not(debug_assertions)))]
+        let guard = crate::NoPanicGuard;
+        #[allow(clippy::redundant_closure_call)]
+        let result = (|| $body)();
+        #[cfg(all(feature = "no-panic", not(debug_assertions)))]
+        core::mem::forget(guard);
+        result
+    }};
+}
+
+/// The guard used by `no_panic!`.
+#[cfg(all(feature = "no-panic", not(debug_assertions)))]
+struct NoPanicGuard;
+
+#[cfg(all(feature = "no-panic", not(debug_assertions)))]
+impl Drop for NoPanicGuard {
+    #[inline(always)]
+    fn drop(&mut self) {
+        extern "C" {
+            #[link_name = "\n\nERROR[byteorder]: a function that the \
+                           `no-panic` feature checks may panic\n\n"]
+            fn byteorder_may_panic() -> !;
+        }
+        // SAFETY: This is never called in a program that links, since
+        // the function doesn't exist.
+        unsafe { byteorder_may_panic() }
+    }
+}
 
+#[cfg(feature = "std")]
+mod async_methods;
+#[cfg(feature = "alloc")]
+pub mod bitbuf;
+pub mod bitfield;
+pub mod bom;
+pub mod const_io;
+#[cfg(feature = "std")]
+pub mod counting;
+pub mod crc;
+mod detect;
+pub mod dispatch;
+#[cfg(feature = "embedded-io")]
+pub mod embedded_io;
+#[cfg(feature = "embedded-io-async")]
+pub mod embedded_io_async;
+#[cfg(feature = "std")]
+pub mod erased;
+mod error;
+pub mod field;
+pub mod fixed;
+pub mod fourcc;
+#[cfg(feature = "futures-io")]
+pub mod futures;
+mod half;
+#[cfg(feature = "heapless")]
+pub mod heapless;
+#[cfg(feature = "alloc")]
+pub mod heuristics;
+#[cfg(feature = "alloc")]
+pub mod idlist;
 #[cfg(feature = "std")]
 mod io;
+#[cfg(feature = "std")]
+pub mod ordered;
+#[cfg(feature = "std")]
+pub mod owned;
+#[cfg(feature = "std")]
+pub mod patch;
+#[cfg(feature = "std")]
+pub mod pcap;
+mod primitive;
+#[cfg(feature = "alloc")]
+pub mod record;
+#[cfg(feature = "std")]
+pub mod restoring;
+#[cfg(feature = "roundtrip")]
+pub mod roundtrip;
+pub mod search;
+#[cfg(feature = "std")]
+pub mod section;
+#[cfg(feature = "std")]
+pub mod seq;
+mod slice_ext;
+#[cfg(feature = "std")]
+pub mod sparse;
+#[cfg(feature = "std")]
+pub mod tee;
+#[cfg(feature = "std")]
+pub mod time;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+pub mod types;
+pub mod varint;
+#[cfg(feature = "std")]
+pub mod versioned;
+pub mod wire;
+
+/// Panics because a buffer has the wrong length or an argument is out of
+/// range.
+///
+/// This is only used with the `minimal-panics` feature, where every such
+/// failure goes through this one function instead of formatting a message
+/// specific to each call site.
+#[cfg(feature = "minimal-panics")]
+#[cold]
+#[inline(never)]
+fn invalid_length() -> ! {
+    panic!("byteorder: invalid buffer length")
+}
+
+/// Asserts that `$cond` holds, like `assert!`, but through `invalid_length`
+/// when the `minimal-panics` feature is enabled.
+macro_rules! check {
+    ($cond:expr) => {
+        #[cfg(not(feature = "minimal-panics"))]
+        assert!($cond);
+        #[cfg(feature = "minimal-panics")]
+        if !$cond {
+            invalid_length()
+        }
+    };
+}
+
+/// Asserts that `$left == $right`, like `assert_eq!`, but through
+/// `invalid_length` when the `minimal-panics` feature is enabled.
+macro_rules! check_eq {
+    ($left:expr, $right:expr) => {
+        #[cfg(not(feature = "minimal-panics"))]
+        assert_eq!($left, $right);
+        #[cfg(feature = "minimal-panics")]
+        if $left != $right {
+            invalid_length()
+        }
+    };
+}
+
+/// Checks that `$buf` has at least `$len` bytes when the `minimal-panics`
+/// feature is enabled.
+///
+/// Without the feature, this does nothing, and the slice indexing that
+/// follows it panics with its usual message. With it, the indexing that
+/// follows can no longer fail, so the compiler removes its panic path.
+macro_rules! check_len {
+    ($buf:expr, $len:expr) => {
+        #[cfg(feature = "minimal-panics")]
+        if $buf.len() < $len {
+            invalid_length()
+        }
+    };
+}
 
 #[inline]
 fn extend_sign(val: u64, nbytes: usize) -> i64 {
@@ -108,16 +368,31 @@ fn unextend_sign128(val: i128, nbytes: usize) -> u128 {
     (val << shift) as u128 >> shift
 }
 
+/// Returns the number of bytes needed to represent `n`, which is at least 1.
+///
+/// This and `pack_size128` compute in `u32`, and `read_uint` and friends
+/// compute in `u64` and `u128`, so none of them depend on the width of
+/// `usize`. Their results are never bigger than 16, which fits in a `usize`
+/// on every platform.
 #[inline]
-fn pack_size(n: u64) -> usize {
+const fn pack_size(n: u64) -> usize {
     (8 - ((n | 1).leading_zeros() >> 3)) as usize
 }
 
 #[inline]
-fn pack_size128(n: u128) -> usize {
+const fn pack_size128(n: u128) -> usize {
     (16 - ((n | 1).leading_zeros() >> 3)) as usize
 }
 
+// This crate supports platforms where `usize` is only 16 bits wide, like AVR
+// and MSP430. These assertions are checked whenever the crate is compiled, so
+// they hold on every platform it builds for, whether or not CI tests it.
+const _: () = assert!(core::mem::size_of::<usize>() >= 2);
+const _: () =
+    assert!(PtrWidth::native().size() == core::mem::size_of::<usize>());
+const _: () = assert!(pack_size(0) == 1 && pack_size(u64::MAX) == 8);
+const _: () = assert!(pack_size128(0) == 1 && pack_size128(u128::MAX) == 16);
+
 mod private {
     /// Sealed stops crates other than byteorder from implementing any traits
     /// that use it.
@@ -925,220 +1200,826 @@ pub trait ByteOrder:
         Self::write_u64(buf, n.to_bits())
     }
 
-    /// Reads unsigned 16 bit integers from `src` into `dst`.
+    /// Reads a IEEE754 half-precision (2 bytes) floating point number and
+    /// widens it to an `f32`.
+    ///
+    /// Every half-precision number, including infinities and subnormals, is
+    /// exactly representable as an `f32`.
     ///
     /// # Panics
     ///
-    /// Panics when `src.len() != 2*dst.len()`.
+    /// Panics when `buf.len() < 2`.
     ///
     /// # Examples
     ///
-    /// Write and read `u16` numbers in little endian order:
+    /// Write and read half-precision numbers in little endian order:
     ///
     /// ```rust
     /// use byteorder::{ByteOrder, LittleEndian};
     ///
-    /// let mut bytes = [0; 8];
-    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
-    /// LittleEndian::write_u16_into(&numbers_given, &mut bytes);
-    ///
-    /// let mut numbers_got = [0; 4];
-    /// LittleEndian::read_u16_into(&bytes, &mut numbers_got);
-    /// assert_eq!(numbers_given, numbers_got);
+    /// let mut buf = [0; 2];
+    /// LittleEndian::write_f16(&mut buf, -1.5);
+    /// assert_eq!([0x00, 0xBE], buf);
+    /// assert_eq!(-1.5, LittleEndian::read_f16(&buf));
     /// ```
-    fn read_u16_into(src: &[u8], dst: &mut [u16]);
+    #[inline]
+    fn read_f16(buf: &[u8]) -> f32 {
+        half::to_f32(Self::read_u16(buf))
+    }
 
-    /// Reads unsigned 32 bit integers from `src` into `dst`.
+    /// Writes an `f32` as a IEEE754 half-precision (2 bytes) floating point
+    /// number.
+    ///
+    /// The number is rounded to the nearest half-precision number, with ties
+    /// going to even. Numbers too large for half-precision are written as
+    /// infinity, and numbers too small are written as zero, with their sign
+    /// preserved in both cases.
     ///
     /// # Panics
     ///
-    /// Panics when `src.len() != 4*dst.len()`.
+    /// Panics when `buf.len() < 2`.
     ///
     /// # Examples
     ///
-    /// Write and read `u32` numbers in little endian order:
+    /// Write half-precision numbers in big endian order:
     ///
     /// ```rust
-    /// use byteorder::{ByteOrder, LittleEndian};
+    /// use byteorder::{BigEndian, ByteOrder};
     ///
-    /// let mut bytes = [0; 16];
-    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
-    /// LittleEndian::write_u32_into(&numbers_given, &mut bytes);
+    /// let mut buf = [0; 2];
+    /// BigEndian::write_f16(&mut buf, 0.1);
+    /// assert_eq!([0x2E, 0x66], buf);
+    /// // 0.1 is not exactly representable.
+    /// assert_eq!(0.099975586, BigEndian::read_f16(&buf));
     ///
-    /// let mut numbers_got = [0; 4];
-    /// LittleEndian::read_u32_into(&bytes, &mut numbers_got);
-    /// assert_eq!(numbers_given, numbers_got);
+    /// BigEndian::write_f16(&mut buf, 1e6);
+    /// assert_eq!(f32::INFINITY, BigEndian::read_f16(&buf));
     /// ```
-    fn read_u32_into(src: &[u8], dst: &mut [u32]);
+    #[inline]
+    fn write_f16(buf: &mut [u8], n: f32) {
+        Self::write_u16(buf, half::from_f32(n))
+    }
 
-    /// Reads unsigned 64 bit integers from `src` into `dst`.
+    /// Reads an unsigned 16 bit integer from `buf`, or returns an error if
+    /// `buf.len() < 2`.
     ///
-    /// # Panics
+    /// This is the non-panicking version of
+    /// [`read_u16`](ByteOrder::read_u16).
+    #[inline]
+    fn try_read_u16(buf: &[u8]) -> Result<u16, Error> {
+        no_panic!({
+            Error::check_len(2, buf.len())?;
+            Ok(Self::read_u16(buf))
+        })
+    }
+
+    /// Reads an unsigned 24 bit integer from `buf`, or returns an error if
+    /// `buf.len() < 3`.
     ///
-    /// Panics when `src.len() != 8*dst.len()`.
+    /// This is the non-panicking version of
+    /// [`read_u24`](ByteOrder::read_u24).
+    #[inline]
+    fn try_read_u24(buf: &[u8]) -> Result<u32, Error> {
+        no_panic!({
+            Error::check_len(3, buf.len())?;
+            Ok(Self::read_u24(buf))
+        })
+    }
+
+    /// Reads an unsigned 32 bit integer from `buf`, or returns an error if
+    /// `buf.len() < 4`.
+    ///
+    /// This is the non-panicking version of
+    /// [`read_u32`](ByteOrder::read_u32).
     ///
     /// # Examples
     ///
-    /// Write and read `u64` numbers in little endian order:
+    /// Reading from a buffer that may be too short:
     ///
     /// ```rust
     /// use byteorder::{ByteOrder, LittleEndian};
     ///
-    /// let mut bytes = [0; 32];
-    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
-    /// LittleEndian::write_u64_into(&numbers_given, &mut bytes);
-    ///
-    /// let mut numbers_got = [0; 4];
-    /// LittleEndian::read_u64_into(&bytes, &mut numbers_got);
-    /// assert_eq!(numbers_given, numbers_got);
+    /// assert_eq!(Ok(0x0403_0201), LittleEndian::try_read_u32(&[1, 2, 3, 4]));
+    /// assert!(LittleEndian::try_read_u32(&[1, 2, 3]).is_err());
     /// ```
-    fn read_u64_into(src: &[u8], dst: &mut [u64]);
+    #[inline]
+    fn try_read_u32(buf: &[u8]) -> Result<u32, Error> {
+        no_panic!({
+            Error::check_len(4, buf.len())?;
+            Ok(Self::read_u32(buf))
+        })
+    }
 
-    /// Reads unsigned 128 bit integers from `src` into `dst`.
+    /// Reads an unsigned 48 bit integer from `buf`, or returns an error if
+    /// `buf.len() < 6`.
     ///
-    /// # Panics
+    /// This is the non-panicking version of
+    /// [`read_u48`](ByteOrder::read_u48).
+    #[inline]
+    fn try_read_u48(buf: &[u8]) -> Result<u64, Error> {
+        no_panic!({
+            Error::check_len(6, buf.len())?;
+            Ok(Self::read_u48(buf))
+        })
+    }
+
+    /// Reads an unsigned 64 bit integer from `buf`, or returns an error if
+    /// `buf.len() < 8`.
     ///
-    /// Panics when `src.len() != 16*dst.len()`.
+    /// This is the non-panicking version of
+    /// [`read_u64`](ByteOrder::read_u64).
+    #[inline]
+    fn try_read_u64(buf: &[u8]) -> Result<u64, Error> {
+        no_panic!({
+            Error::check_len(8, buf.len())?;
+            Ok(Self::read_u64(buf))
+        })
+    }
+
+    /// Reads an unsigned 128 bit integer from `buf`, or returns an error if
+    /// `buf.len() < 16`.
     ///
-    /// # Examples
+    /// This is the non-panicking version of
+    /// [`read_u128`](ByteOrder::read_u128).
+    #[inline]
+    fn try_read_u128(buf: &[u8]) -> Result<u128, Error> {
+        no_panic!({
+            Error::check_len(16, buf.len())?;
+            Ok(Self::read_u128(buf))
+        })
+    }
+
+    /// Reads a signed 16 bit integer from `buf`, or returns an error if
+    /// `buf.len() < 2`.
     ///
-    /// Write and read `u128` numbers in little endian order:
+    /// This is the non-panicking version of
+    /// [`read_i16`](ByteOrder::read_i16).
+    #[inline]
+    fn try_read_i16(buf: &[u8]) -> Result<i16, Error> {
+        no_panic!({
+            Error::check_len(2, buf.len())?;
+            Ok(Self::read_i16(buf))
+        })
+    }
+
+    /// Reads a signed 24 bit integer from `buf`, or returns an error if
+    /// `buf.len() < 3`.
     ///
-    /// ```rust
-    /// use byteorder::{ByteOrder, LittleEndian};
+    /// This is the non-panicking version of
+    /// [`read_i24`](ByteOrder::read_i24).
+    #[inline]
+    fn try_read_i24(buf: &[u8]) -> Result<i32, Error> {
+        no_panic!({
+            Error::check_len(3, buf.len())?;
+            Ok(Self::read_i24(buf))
+        })
+    }
+
+    /// Reads a signed 32 bit integer from `buf`, or returns an error if
+    /// `buf.len() < 4`.
     ///
-    /// let mut bytes = [0; 64];
-    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
-    /// LittleEndian::write_u128_into(&numbers_given, &mut bytes);
+    /// This is the non-panicking version of
+    /// [`read_i32`](ByteOrder::read_i32).
+    #[inline]
+    fn try_read_i32(buf: &[u8]) -> Result<i32, Error> {
+        no_panic!({
+            Error::check_len(4, buf.len())?;
+            Ok(Self::read_i32(buf))
+        })
+    }
+
+    /// Reads a signed 48 bit integer from `buf`, or returns an error if
+    /// `buf.len() < 6`.
     ///
-    /// let mut numbers_got = [0; 4];
-    /// LittleEndian::read_u128_into(&bytes, &mut numbers_got);
-    /// assert_eq!(numbers_given, numbers_got);
-    /// ```
-    fn read_u128_into(src: &[u8], dst: &mut [u128]);
+    /// This is the non-panicking version of
+    /// [`read_i48`](ByteOrder::read_i48).
+    #[inline]
+    fn try_read_i48(buf: &[u8]) -> Result<i64, Error> {
+        no_panic!({
+            Error::check_len(6, buf.len())?;
+            Ok(Self::read_i48(buf))
+        })
+    }
 
-    /// Reads signed 16 bit integers from `src` to `dst`.
+    /// Reads a signed 64 bit integer from `buf`, or returns an error if
+    /// `buf.len() < 8`.
     ///
-    /// # Panics
+    /// This is the non-panicking version of
+    /// [`read_i64`](ByteOrder::read_i64).
+    #[inline]
+    fn try_read_i64(buf: &[u8]) -> Result<i64, Error> {
+        no_panic!({
+            Error::check_len(8, buf.len())?;
+            Ok(Self::read_i64(buf))
+        })
+    }
+
+    /// Reads a signed 128 bit integer from `buf`, or returns an error if
+    /// `buf.len() < 16`.
     ///
-    /// Panics when `buf.len() != 2*dst.len()`.
+    /// This is the non-panicking version of
+    /// [`read_i128`](ByteOrder::read_i128).
+    #[inline]
+    fn try_read_i128(buf: &[u8]) -> Result<i128, Error> {
+        no_panic!({
+            Error::check_len(16, buf.len())?;
+            Ok(Self::read_i128(buf))
+        })
+    }
+
+    /// Reads a IEEE754 single-precision (4 bytes) floating point number from `buf`, or returns an error if
+    /// `buf.len() < 4`.
     ///
-    /// # Examples
+    /// This is the non-panicking version of
+    /// [`read_f32`](ByteOrder::read_f32).
+    #[inline]
+    fn try_read_f32(buf: &[u8]) -> Result<f32, Error> {
+        no_panic!({
+            Error::check_len(4, buf.len())?;
+            Ok(Self::read_f32(buf))
+        })
+    }
+
+    /// Reads a IEEE754 double-precision (8 bytes) floating point number from `buf`, or returns an error if
+    /// `buf.len() < 8`.
     ///
-    /// Write and read `i16` numbers in little endian order:
+    /// This is the non-panicking version of
+    /// [`read_f64`](ByteOrder::read_f64).
+    #[inline]
+    fn try_read_f64(buf: &[u8]) -> Result<f64, Error> {
+        no_panic!({
+            Error::check_len(8, buf.len())?;
+            Ok(Self::read_f64(buf))
+        })
+    }
+
+    /// Reads an unsigned n-bytes integer from `buf`, or returns an error if
+    /// `buf.len() < nbytes`.
     ///
-    /// ```rust
-    /// use byteorder::{ByteOrder, LittleEndian};
+    /// This is the non-panicking version of
+    /// [`read_uint`](ByteOrder::read_uint) with respect to the length of
+    /// `buf`.
     ///
-    /// let mut bytes = [0; 8];
-    /// let numbers_given = [1, 2, 0x0f, 0xee];
-    /// LittleEndian::write_i16_into(&numbers_given, &mut bytes);
+    /// # Panics
     ///
-    /// let mut numbers_got = [0; 4];
-    /// LittleEndian::read_i16_into(&bytes, &mut numbers_got);
-    /// assert_eq!(numbers_given, numbers_got);
-    /// ```
+    /// Panics when `nbytes < 1` or `nbytes > 8`.
     #[inline]
-    fn read_i16_into(src: &[u8], dst: &mut [i16]) {
-        let dst = unsafe {
-            slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u16, dst.len())
-        };
-        Self::read_u16_into(src, dst)
+    fn try_read_uint(buf: &[u8], nbytes: usize) -> Result<u64, Error> {
+        Error::check_len(nbytes, buf.len())?;
+        Ok(Self::read_uint(buf, nbytes))
     }
 
-    /// Reads signed 32 bit integers from `src` into `dst`.
+    /// Reads a signed n-bytes integer from `buf`, or returns an error if
+    /// `buf.len() < nbytes`.
+    ///
+    /// This is the non-panicking version of
+    /// [`read_int`](ByteOrder::read_int) with respect to the length of
+    /// `buf`.
     ///
     /// # Panics
     ///
-    /// Panics when `src.len() != 4*dst.len()`.
+    /// Panics when `nbytes < 1` or `nbytes > 8`.
+    #[inline]
+    fn try_read_int(buf: &[u8], nbytes: usize) -> Result<i64, Error> {
+        Error::check_len(nbytes, buf.len())?;
+        Ok(Self::read_int(buf, nbytes))
+    }
+
+    /// Reads an unsigned n-bytes integer from `buf`, or returns an error if
+    /// `buf.len() < nbytes`.
     ///
-    /// # Examples
+    /// This is the non-panicking version of
+    /// [`read_uint128`](ByteOrder::read_uint128) with respect to the length of
+    /// `buf`.
     ///
-    /// Write and read `i32` numbers in little endian order:
+    /// # Panics
     ///
-    /// ```rust
-    /// use byteorder::{ByteOrder, LittleEndian};
+    /// Panics when `nbytes < 1` or `nbytes > 16`.
+    #[inline]
+    fn try_read_uint128(buf: &[u8], nbytes: usize) -> Result<u128, Error> {
+        Error::check_len(nbytes, buf.len())?;
+        Ok(Self::read_uint128(buf, nbytes))
+    }
+
+    /// Reads a signed n-bytes integer from `buf`, or returns an error if
+    /// `buf.len() < nbytes`.
     ///
-    /// let mut bytes = [0; 16];
-    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
-    /// LittleEndian::write_i32_into(&numbers_given, &mut bytes);
+    /// This is the non-panicking version of
+    /// [`read_int128`](ByteOrder::read_int128) with respect to the length of
+    /// `buf`.
     ///
-    /// let mut numbers_got = [0; 4];
-    /// LittleEndian::read_i32_into(&bytes, &mut numbers_got);
-    /// assert_eq!(numbers_given, numbers_got);
-    /// ```
+    /// # Panics
+    ///
+    /// Panics when `nbytes < 1` or `nbytes > 16`.
     #[inline]
-    fn read_i32_into(src: &[u8], dst: &mut [i32]) {
-        let dst = unsafe {
-            slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u32, dst.len())
-        };
-        Self::read_u32_into(src, dst);
+    fn try_read_int128(buf: &[u8], nbytes: usize) -> Result<i128, Error> {
+        Error::check_len(nbytes, buf.len())?;
+        Ok(Self::read_int128(buf, nbytes))
     }
 
-    /// Reads signed 64 bit integers from `src` into `dst`.
+    /// Writes an unsigned 16 bit integer `n` to `buf`, or returns an error if
+    /// `buf.len() < 2`.
     ///
-    /// # Panics
+    /// This is the non-panicking version of
+    /// [`write_u16`](ByteOrder::write_u16).
+    #[inline]
+    fn try_write_u16(buf: &mut [u8], n: u16) -> Result<(), Error> {
+        no_panic!({
+            Error::check_len(2, buf.len())?;
+            Self::write_u16(buf, n);
+            Ok(())
+        })
+    }
+
+    /// Writes an unsigned 24 bit integer `n` to `buf`, or returns an error if
+    /// `buf.len() < 3`.
     ///
-    /// Panics when `src.len() != 8*dst.len()`.
+    /// This is the non-panicking version of
+    /// [`write_u24`](ByteOrder::write_u24).
+    #[inline]
+    fn try_write_u24(buf: &mut [u8], n: u32) -> Result<(), Error> {
+        Error::check_len(3, buf.len())?;
+        Self::write_u24(buf, n);
+        Ok(())
+    }
+
+    /// Writes an unsigned 32 bit integer `n` to `buf`, or returns an error if
+    /// `buf.len() < 4`.
+    ///
+    /// This is the non-panicking version of
+    /// [`write_u32`](ByteOrder::write_u32).
     ///
     /// # Examples
     ///
-    /// Write and read `i64` numbers in little endian order:
+    /// Writing to a buffer that may be too short:
     ///
     /// ```rust
     /// use byteorder::{ByteOrder, LittleEndian};
     ///
-    /// let mut bytes = [0; 32];
-    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
-    /// LittleEndian::write_i64_into(&numbers_given, &mut bytes);
-    ///
-    /// let mut numbers_got = [0; 4];
-    /// LittleEndian::read_i64_into(&bytes, &mut numbers_got);
-    /// assert_eq!(numbers_given, numbers_got);
+    /// let mut buf = [0; 4];
+    /// LittleEndian::try_write_u32(&mut buf, 0x0403_0201).unwrap();
+    /// assert_eq!([1, 2, 3, 4], buf);
+    /// assert!(LittleEndian::try_write_u32(&mut buf[1..], 5).is_err());
     /// ```
     #[inline]
-    fn read_i64_into(src: &[u8], dst: &mut [i64]) {
-        let dst = unsafe {
-            slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u64, dst.len())
-        };
-        Self::read_u64_into(src, dst);
+    fn try_write_u32(buf: &mut [u8], n: u32) -> Result<(), Error> {
+        no_panic!({
+            Error::check_len(4, buf.len())?;
+            Self::write_u32(buf, n);
+            Ok(())
+        })
     }
 
-    /// Reads signed 128 bit integers from `src` into `dst`.
+    /// Writes an unsigned 48 bit integer `n` to `buf`, or returns an error if
+    /// `buf.len() < 6`.
     ///
-    /// # Panics
-    ///
-    /// Panics when `src.len() != 16*dst.len()`.
+    /// This is the non-panicking version of
+    /// [`write_u48`](ByteOrder::write_u48).
+    #[inline]
+    fn try_write_u48(buf: &mut [u8], n: u64) -> Result<(), Error> {
+        Error::check_len(6, buf.len())?;
+        Self::write_u48(buf, n);
+        Ok(())
+    }
+
+    /// Writes an unsigned 64 bit integer `n` to `buf`, or returns an error if
+    /// `buf.len() < 8`.
     ///
-    /// # Examples
+    /// This is the non-panicking version of
+    /// [`write_u64`](ByteOrder::write_u64).
+    #[inline]
+    fn try_write_u64(buf: &mut [u8], n: u64) -> Result<(), Error> {
+        no_panic!({
+            Error::check_len(8, buf.len())?;
+            Self::write_u64(buf, n);
+            Ok(())
+        })
+    }
+
+    /// Writes an unsigned 128 bit integer `n` to `buf`, or returns an error if
+    /// `buf.len() < 16`.
     ///
-    /// Write and read `i128` numbers in little endian order:
+    /// This is the non-panicking version of
+    /// [`write_u128`](ByteOrder::write_u128).
+    #[inline]
+    fn try_write_u128(buf: &mut [u8], n: u128) -> Result<(), Error> {
+        no_panic!({
+            Error::check_len(16, buf.len())?;
+            Self::write_u128(buf, n);
+            Ok(())
+        })
+    }
+
+    /// Writes a signed 16 bit integer `n` to `buf`, or returns an error if
+    /// `buf.len() < 2`.
     ///
-    /// ```rust
-    /// use byteorder::{ByteOrder, LittleEndian};
+    /// This is the non-panicking version of
+    /// [`write_i16`](ByteOrder::write_i16).
+    #[inline]
+    fn try_write_i16(buf: &mut [u8], n: i16) -> Result<(), Error> {
+        no_panic!({
+            Error::check_len(2, buf.len())?;
+            Self::write_i16(buf, n);
+            Ok(())
+        })
+    }
+
+    /// Writes a signed 24 bit integer `n` to `buf`, or returns an error if
+    /// `buf.len() < 3`.
     ///
-    /// let mut bytes = [0; 64];
-    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
-    /// LittleEndian::write_i128_into(&numbers_given, &mut bytes);
+    /// This is the non-panicking version of
+    /// [`write_i24`](ByteOrder::write_i24).
+    #[inline]
+    fn try_write_i24(buf: &mut [u8], n: i32) -> Result<(), Error> {
+        Error::check_len(3, buf.len())?;
+        Self::write_i24(buf, n);
+        Ok(())
+    }
+
+    /// Writes a signed 32 bit integer `n` to `buf`, or returns an error if
+    /// `buf.len() < 4`.
     ///
-    /// let mut numbers_got = [0; 4];
-    /// LittleEndian::read_i128_into(&bytes, &mut numbers_got);
-    /// assert_eq!(numbers_given, numbers_got);
-    /// ```
+    /// This is the non-panicking version of
+    /// [`write_i32`](ByteOrder::write_i32).
     #[inline]
-    fn read_i128_into(src: &[u8], dst: &mut [i128]) {
-        let dst = unsafe {
-            slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u128, dst.len())
-        };
-        Self::read_u128_into(src, dst);
+    fn try_write_i32(buf: &mut [u8], n: i32) -> Result<(), Error> {
+        no_panic!({
+            Error::check_len(4, buf.len())?;
+            Self::write_i32(buf, n);
+            Ok(())
+        })
     }
 
-    /// Reads IEEE754 single-precision (4 bytes) floating point numbers from
-    /// `src` into `dst`.
+    /// Writes a signed 48 bit integer `n` to `buf`, or returns an error if
+    /// `buf.len() < 6`.
     ///
-    /// # Panics
+    /// This is the non-panicking version of
+    /// [`write_i48`](ByteOrder::write_i48).
+    #[inline]
+    fn try_write_i48(buf: &mut [u8], n: i64) -> Result<(), Error> {
+        Error::check_len(6, buf.len())?;
+        Self::write_i48(buf, n);
+        Ok(())
+    }
+
+    /// Writes a signed 64 bit integer `n` to `buf`, or returns an error if
+    /// `buf.len() < 8`.
     ///
-    /// Panics when `src.len() != 4*dst.len()`.
+    /// This is the non-panicking version of
+    /// [`write_i64`](ByteOrder::write_i64).
+    #[inline]
+    fn try_write_i64(buf: &mut [u8], n: i64) -> Result<(), Error> {
+        no_panic!({
+            Error::check_len(8, buf.len())?;
+            Self::write_i64(buf, n);
+            Ok(())
+        })
+    }
+
+    /// Writes a signed 128 bit integer `n` to `buf`, or returns an error if
+    /// `buf.len() < 16`.
+    ///
+    /// This is the non-panicking version of
+    /// [`write_i128`](ByteOrder::write_i128).
+    #[inline]
+    fn try_write_i128(buf: &mut [u8], n: i128) -> Result<(), Error> {
+        no_panic!({
+            Error::check_len(16, buf.len())?;
+            Self::write_i128(buf, n);
+            Ok(())
+        })
+    }
+
+    /// Writes a IEEE754 single-precision (4 bytes) floating point number `n` to `buf`, or returns an error if
+    /// `buf.len() < 4`.
+    ///
+    /// This is the non-panicking version of
+    /// [`write_f32`](ByteOrder::write_f32).
+    #[inline]
+    fn try_write_f32(buf: &mut [u8], n: f32) -> Result<(), Error> {
+        no_panic!({
+            Error::check_len(4, buf.len())?;
+            Self::write_f32(buf, n);
+            Ok(())
+        })
+    }
+
+    /// Writes a IEEE754 double-precision (8 bytes) floating point number `n` to `buf`, or returns an error if
+    /// `buf.len() < 8`.
+    ///
+    /// This is the non-panicking version of
+    /// [`write_f64`](ByteOrder::write_f64).
+    #[inline]
+    fn try_write_f64(buf: &mut [u8], n: f64) -> Result<(), Error> {
+        no_panic!({
+            Error::check_len(8, buf.len())?;
+            Self::write_f64(buf, n);
+            Ok(())
+        })
+    }
+
+    /// Writes an unsigned n-bytes integer `n` to `buf`, or returns an error if
+    /// `buf.len() < nbytes`.
+    ///
+    /// This is the non-panicking version of
+    /// [`write_uint`](ByteOrder::write_uint) with respect to the length
+    /// of `buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes > 8` or when `n` is not representable in
+    /// `nbytes` bytes.
+    #[inline]
+    fn try_write_uint(
+        buf: &mut [u8],
+        n: u64,
+        nbytes: usize,
+    ) -> Result<(), Error> {
+        Error::check_len(nbytes, buf.len())?;
+        Self::write_uint(buf, n, nbytes);
+        Ok(())
+    }
+
+    /// Writes a signed n-bytes integer `n` to `buf`, or returns an error if
+    /// `buf.len() < nbytes`.
+    ///
+    /// This is the non-panicking version of
+    /// [`write_int`](ByteOrder::write_int) with respect to the length
+    /// of `buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes > 8` or when `n` is not representable in
+    /// `nbytes` bytes.
+    #[inline]
+    fn try_write_int(
+        buf: &mut [u8],
+        n: i64,
+        nbytes: usize,
+    ) -> Result<(), Error> {
+        Error::check_len(nbytes, buf.len())?;
+        Self::write_int(buf, n, nbytes);
+        Ok(())
+    }
+
+    /// Writes an unsigned n-bytes integer `n` to `buf`, or returns an error if
+    /// `buf.len() < nbytes`.
+    ///
+    /// This is the non-panicking version of
+    /// [`write_uint128`](ByteOrder::write_uint128) with respect to the length
+    /// of `buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes > 16` or when `n` is not representable in
+    /// `nbytes` bytes.
+    #[inline]
+    fn try_write_uint128(
+        buf: &mut [u8],
+        n: u128,
+        nbytes: usize,
+    ) -> Result<(), Error> {
+        Error::check_len(nbytes, buf.len())?;
+        Self::write_uint128(buf, n, nbytes);
+        Ok(())
+    }
+
+    /// Writes a signed n-bytes integer `n` to `buf`, or returns an error if
+    /// `buf.len() < nbytes`.
+    ///
+    /// This is the non-panicking version of
+    /// [`write_int128`](ByteOrder::write_int128) with respect to the length
+    /// of `buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes > 16` or when `n` is not representable in
+    /// `nbytes` bytes.
+    #[inline]
+    fn try_write_int128(
+        buf: &mut [u8],
+        n: i128,
+        nbytes: usize,
+    ) -> Result<(), Error> {
+        Error::check_len(nbytes, buf.len())?;
+        Self::write_int128(buf, n, nbytes);
+        Ok(())
+    }
+
+    /// Reads unsigned 16 bit integers from `src` into `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `src.len() != 2*dst.len()`.
+    ///
+    /// # Examples
+    ///
+    /// Write and read `u16` numbers in little endian order:
+    ///
+    /// ```rust
+    /// use byteorder::{ByteOrder, LittleEndian};
+    ///
+    /// let mut bytes = [0; 8];
+    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
+    /// LittleEndian::write_u16_into(&numbers_given, &mut bytes);
+    ///
+    /// let mut numbers_got = [0; 4];
+    /// LittleEndian::read_u16_into(&bytes, &mut numbers_got);
+    /// assert_eq!(numbers_given, numbers_got);
+    /// ```
+    fn read_u16_into(src: &[u8], dst: &mut [u16]);
+
+    /// Reads unsigned 32 bit integers from `src` into `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `src.len() != 4*dst.len()`.
+    ///
+    /// # Examples
+    ///
+    /// Write and read `u32` numbers in little endian order:
+    ///
+    /// ```rust
+    /// use byteorder::{ByteOrder, LittleEndian};
+    ///
+    /// let mut bytes = [0; 16];
+    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
+    /// LittleEndian::write_u32_into(&numbers_given, &mut bytes);
+    ///
+    /// let mut numbers_got = [0; 4];
+    /// LittleEndian::read_u32_into(&bytes, &mut numbers_got);
+    /// assert_eq!(numbers_given, numbers_got);
+    /// ```
+    fn read_u32_into(src: &[u8], dst: &mut [u32]);
+
+    /// Reads unsigned 64 bit integers from `src` into `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `src.len() != 8*dst.len()`.
+    ///
+    /// # Examples
+    ///
+    /// Write and read `u64` numbers in little endian order:
+    ///
+    /// ```rust
+    /// use byteorder::{ByteOrder, LittleEndian};
+    ///
+    /// let mut bytes = [0; 32];
+    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
+    /// LittleEndian::write_u64_into(&numbers_given, &mut bytes);
+    ///
+    /// let mut numbers_got = [0; 4];
+    /// LittleEndian::read_u64_into(&bytes, &mut numbers_got);
+    /// assert_eq!(numbers_given, numbers_got);
+    /// ```
+    fn read_u64_into(src: &[u8], dst: &mut [u64]);
+
+    /// Reads unsigned 128 bit integers from `src` into `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `src.len() != 16*dst.len()`.
+    ///
+    /// # Examples
+    ///
+    /// Write and read `u128` numbers in little endian order:
+    ///
+    /// ```rust
+    /// use byteorder::{ByteOrder, LittleEndian};
+    ///
+    /// let mut bytes = [0; 64];
+    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
+    /// LittleEndian::write_u128_into(&numbers_given, &mut bytes);
+    ///
+    /// let mut numbers_got = [0; 4];
+    /// LittleEndian::read_u128_into(&bytes, &mut numbers_got);
+    /// assert_eq!(numbers_given, numbers_got);
+    /// ```
+    fn read_u128_into(src: &[u8], dst: &mut [u128]);
+
+    /// Reads signed 16 bit integers from `src` to `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() != 2*dst.len()`.
+    ///
+    /// # Examples
+    ///
+    /// Write and read `i16` numbers in little endian order:
+    ///
+    /// ```rust
+    /// use byteorder::{ByteOrder, LittleEndian};
+    ///
+    /// let mut bytes = [0; 8];
+    /// let numbers_given = [1, 2, 0x0f, 0xee];
+    /// LittleEndian::write_i16_into(&numbers_given, &mut bytes);
+    ///
+    /// let mut numbers_got = [0; 4];
+    /// LittleEndian::read_i16_into(&bytes, &mut numbers_got);
+    /// assert_eq!(numbers_given, numbers_got);
+    /// ```
+    #[inline]
+    fn read_i16_into(src: &[u8], dst: &mut [i16]) {
+        let dst = unsafe {
+            slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u16, dst.len())
+        };
+        Self::read_u16_into(src, dst)
+    }
+
+    /// Reads signed 32 bit integers from `src` into `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `src.len() != 4*dst.len()`.
+    ///
+    /// # Examples
+    ///
+    /// Write and read `i32` numbers in little endian order:
+    ///
+    /// ```rust
+    /// use byteorder::{ByteOrder, LittleEndian};
+    ///
+    /// let mut bytes = [0; 16];
+    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
+    /// LittleEndian::write_i32_into(&numbers_given, &mut bytes);
+    ///
+    /// let mut numbers_got = [0; 4];
+    /// LittleEndian::read_i32_into(&bytes, &mut numbers_got);
+    /// assert_eq!(numbers_given, numbers_got);
+    /// ```
+    #[inline]
+    fn read_i32_into(src: &[u8], dst: &mut [i32]) {
+        let dst = unsafe {
+            slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u32, dst.len())
+        };
+        Self::read_u32_into(src, dst);
+    }
+
+    /// Reads signed 64 bit integers from `src` into `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `src.len() != 8*dst.len()`.
+    ///
+    /// # Examples
+    ///
+    /// Write and read `i64` numbers in little endian order:
+    ///
+    /// ```rust
+    /// use byteorder::{ByteOrder, LittleEndian};
+    ///
+    /// let mut bytes = [0; 32];
+    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
+    /// LittleEndian::write_i64_into(&numbers_given, &mut bytes);
+    ///
+    /// let mut numbers_got = [0; 4];
+    /// LittleEndian::read_i64_into(&bytes, &mut numbers_got);
+    /// assert_eq!(numbers_given, numbers_got);
+    /// ```
+    #[inline]
+    fn read_i64_into(src: &[u8], dst: &mut [i64]) {
+        let dst = unsafe {
+            slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u64, dst.len())
+        };
+        Self::read_u64_into(src, dst);
+    }
+
+    /// Reads signed 128 bit integers from `src` into `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `src.len() != 16*dst.len()`.
+    ///
+    /// # Examples
+    ///
+    /// Write and read `i128` numbers in little endian order:
+    ///
+    /// ```rust
+    /// use byteorder::{ByteOrder, LittleEndian};
+    ///
+    /// let mut bytes = [0; 64];
+    /// let numbers_given = [1, 2, 0xf00f, 0xffee];
+    /// LittleEndian::write_i128_into(&numbers_given, &mut bytes);
+    ///
+    /// let mut numbers_got = [0; 4];
+    /// LittleEndian::read_i128_into(&bytes, &mut numbers_got);
+    /// assert_eq!(numbers_given, numbers_got);
+    /// ```
+    #[inline]
+    fn read_i128_into(src: &[u8], dst: &mut [i128]) {
+        let dst = unsafe {
+            slice::from_raw_parts_mut(dst.as_mut_ptr() as *mut u128, dst.len())
+        };
+        Self::read_u128_into(src, dst);
+    }
+
+    /// Reads IEEE754 single-precision (4 bytes) floating point numbers from
+    /// `src` into `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `src.len() != 4*dst.len()`.
     ///
     /// # Examples
     ///
@@ -1258,6 +2139,74 @@ pub trait ByteOrder:
         Self::read_f64_into(src, dst);
     }
 
+    /// Reads the raw bits of IEEE754 single-precision (4 bytes) floating
+    /// point numbers from `src` into `dst`.
+    ///
+    /// This is like [`read_f32_into`](ByteOrder::read_f32_into), except
+    /// the numbers are left as `u32` bit patterns instead of being
+    /// reinterpreted as `f32`. No floating point operations are involved,
+    /// so the result is bit-exact: every NaN, including signaling NaNs and
+    /// their payloads, comes out exactly as it was encoded. Pair this with
+    /// [`write_f32_from_bits`](ByteOrder::write_f32_from_bits) to move
+    /// floats between buffers without ever touching an `f32`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `src.len() != 4*dst.len()`.
+    ///
+    /// # Examples
+    ///
+    /// Read the bits of big-endian `f32` numbers:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, ByteOrder};
+    ///
+    /// let mut bytes = [0; 8];
+    /// BigEndian::write_f32_into(&[1.0, -2.5], &mut bytes);
+    ///
+    /// let mut bits = [0; 2];
+    /// BigEndian::read_f32_bits_into(&bytes, &mut bits);
+    /// assert_eq!([1.0f32.to_bits(), (-2.5f32).to_bits()], bits);
+    /// ```
+    #[inline]
+    fn read_f32_bits_into(src: &[u8], dst: &mut [u32]) {
+        Self::read_u32_into(src, dst);
+    }
+
+    /// Reads the raw bits of IEEE754 double-precision (8 bytes) floating
+    /// point numbers from `src` into `dst`.
+    ///
+    /// This is like [`read_f64_into`](ByteOrder::read_f64_into), except
+    /// the numbers are left as `u64` bit patterns instead of being
+    /// reinterpreted as `f64`. No floating point operations are involved,
+    /// so the result is bit-exact: every NaN, including signaling NaNs and
+    /// their payloads, comes out exactly as it was encoded. Pair this with
+    /// [`write_f64_from_bits`](ByteOrder::write_f64_from_bits) to move
+    /// floats between buffers without ever touching an `f64`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `src.len() != 8*dst.len()`.
+    ///
+    /// # Examples
+    ///
+    /// Read the bits of big-endian `f64` numbers:
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, ByteOrder};
+    ///
+    /// let mut bytes = [0; 16];
+    /// BigEndian::write_f64_into(&[1.0, -2.5], &mut bytes);
+    ///
+    /// let mut bits = [0; 2];
+    /// BigEndian::read_f64_bits_into(&bytes, &mut bits);
+    /// assert_eq!([1.0f64.to_bits(), (-2.5f64).to_bits()], bits);
+    /// ```
+    #[inline]
+    fn read_f64_bits_into(src: &[u8], dst: &mut [u64]) {
+        Self::read_u64_into(src, dst);
+    }
+
     /// Writes unsigned 16 bit integers from `src` into `dst`.
     ///
     /// # Panics
@@ -1381,6 +2330,10 @@ pub trait ByteOrder:
         let src = unsafe {
             slice::from_raw_parts(src.as_ptr() as *const u8, src.len())
         };
+        #[cfg(feature = "minimal-panics")]
+        if src.len() != dst.len() {
+            invalid_length()
+        }
         dst.copy_from_slice(src);
     }
 
@@ -1554,6 +2507,74 @@ pub trait ByteOrder:
         Self::write_u64_into(src, dst);
     }
 
+    /// Writes the raw bits of IEEE754 single-precision (4 bytes) floating
+    /// point numbers from `src` into `dst`.
+    ///
+    /// This is the inverse of
+    /// [`read_f32_bits_into`](ByteOrder::read_f32_bits_into). The bytes
+    /// written are exactly those that
+    /// [`write_f32_into`](ByteOrder::write_f32_into) would write for the
+    /// `f32` values with these bits, NaN payloads included.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `dst.len() != 4*src.len()`.
+    ///
+    /// # Examples
+    ///
+    /// Write the bits of `f32` numbers in little endian order:
+    ///
+    /// ```rust
+    /// use byteorder::{ByteOrder, LittleEndian};
+    ///
+    /// let bits = [1.0f32.to_bits(), f32::NAN.to_bits() | 1];
+    /// let mut bytes = [0; 8];
+    /// LittleEndian::write_f32_from_bits(&bits, &mut bytes);
+    ///
+    /// let mut numbers = [0.0; 2];
+    /// LittleEndian::read_f32_into(&bytes, &mut numbers);
+    /// assert_eq!(1.0, numbers[0]);
+    /// assert_eq!(bits[1], numbers[1].to_bits());
+    /// ```
+    #[inline]
+    fn write_f32_from_bits(src: &[u32], dst: &mut [u8]) {
+        Self::write_u32_into(src, dst);
+    }
+
+    /// Writes the raw bits of IEEE754 double-precision (8 bytes) floating
+    /// point numbers from `src` into `dst`.
+    ///
+    /// This is the inverse of
+    /// [`read_f64_bits_into`](ByteOrder::read_f64_bits_into). The bytes
+    /// written are exactly those that
+    /// [`write_f64_into`](ByteOrder::write_f64_into) would write for the
+    /// `f64` values with these bits, NaN payloads included.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `dst.len() != 8*src.len()`.
+    ///
+    /// # Examples
+    ///
+    /// Write the bits of `f64` numbers in little endian order:
+    ///
+    /// ```rust
+    /// use byteorder::{ByteOrder, LittleEndian};
+    ///
+    /// let bits = [1.0f64.to_bits(), f64::NAN.to_bits() | 1];
+    /// let mut bytes = [0; 16];
+    /// LittleEndian::write_f64_from_bits(&bits, &mut bytes);
+    ///
+    /// let mut numbers = [0.0; 2];
+    /// LittleEndian::read_f64_into(&bytes, &mut numbers);
+    /// assert_eq!(1.0, numbers[0]);
+    /// assert_eq!(bits[1], numbers[1].to_bits());
+    /// ```
+    #[inline]
+    fn write_f64_from_bits(src: &[u64], dst: &mut [u8]) {
+        Self::write_u64_into(src, dst);
+    }
+
     /// Converts the given slice of unsigned 16 bit integers to a particular
     /// endianness.
     ///
@@ -1762,6 +2783,7 @@ pub trait ByteOrder:
 /// assert_eq!(1_000_000, BigEndian::read_u32(&buf));
 /// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum BigEndian {}
 
 impl Default for BigEndian {
@@ -1792,6 +2814,7 @@ pub type BE = BigEndian;
 /// assert_eq!(1_000_000, LittleEndian::read_u32(&buf));
 /// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum LittleEndian {}
 
 impl Default for LittleEndian {
@@ -1853,6 +2876,351 @@ pub type NativeEndian = LittleEndian;
 #[cfg(target_endian = "big")]
 pub type NativeEndian = BigEndian;
 
+/// A byte order that is only known at runtime.
+///
+/// Unlike [`BigEndian`] and [`LittleEndian`], which select a byte order at
+/// the type level, this is a plain value. It is typically the result of
+/// inspecting some data, e.g., a file header.
+///
+/// `Endianness` has a method for every method of [`ByteOrder`], with the same
+/// name and behavior, that uses the byte order of `self`. This lets a parser
+/// for a format that declares its own byte order be written once, without a
+/// generic parameter or a `match` at every read.
+///
+/// # Examples
+///
+/// Reading the first entry count of a TIFF file, whatever its byte order:
+///
+/// ```rust
+/// use byteorder::Endianness;
+///
+/// fn first_ifd_offset(tiff: &[u8]) -> Option<u32> {
+///     let endianness = match &tiff[..2] {
+///         b"MM" => Endianness::Big,
+///         b"II" => Endianness::Little,
+///         _ => return None,
+///     };
+///     if endianness.read_u16(&tiff[2..]) != 42 {
+///         return None;
+///     }
+///     Some(endianness.read_u32(&tiff[4..]))
+/// }
+///
+/// assert_eq!(Some(8), first_ifd_offset(b"II\x2a\x00\x08\x00\x00\x00"));
+/// assert_eq!(Some(8), first_ifd_offset(b"MM\x00\x2a\x00\x00\x00\x08"));
+/// ```
+///
+/// [`ByteOrder`]: trait.ByteOrder.html
+/// [`BigEndian`]: enum.BigEndian.html
+/// [`LittleEndian`]: enum.LittleEndian.html
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Endianness {
+    /// Big-endian byte order, i.e., most significant byte first.
+    Big,
+    /// Little-endian byte order, i.e., least significant byte first.
+    Little,
+}
+
+/// Defines methods on `Endianness` that forward to the `ByteOrder` method of
+/// the same name, for whichever byte order `self` is.
+macro_rules! endianness_methods {
+    ($($name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)?;)*) => {
+        $(
+            #[doc = concat!("See [`ByteOrder::", stringify!($name), "`].")]
+            #[inline]
+            pub fn $name(self, $($arg: $ty),*) $(-> $ret)? {
+                match self {
+                    Endianness::Big => BigEndian::$name($($arg),*),
+                    Endianness::Little => LittleEndian::$name($($arg),*),
+                }
+            }
+        )*
+    };
+}
+
+impl Endianness {
+    /// Returns the byte order of the [`ByteOrder`] type `B`.
+    ///
+    /// This converts a byte order chosen at compile time into a runtime
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{BigEndian, Endianness, NetworkEndian};
+    ///
+    /// assert_eq!(Endianness::Big, Endianness::of::<BigEndian>());
+    /// assert_eq!(Endianness::Big, Endianness::of::<NetworkEndian>());
+    /// ```
+    ///
+    /// [`ByteOrder`]: trait.ByteOrder.html
+    #[inline]
+    pub fn of<B: ByteOrder>() -> Endianness {
+        if B::read_u16(&[0, 1]) == 1 {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        }
+    }
+
+    /// Returns the native byte order of the current platform.
+    ///
+    /// This is the runtime value of [`NativeEndian`].
+    ///
+    /// [`NativeEndian`]: type.NativeEndian.html
+    #[inline]
+    pub const fn native() -> Endianness {
+        if cfg!(target_endian = "big") {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        }
+    }
+
+    endianness_methods! {
+        read_u16(buf: &[u8]) -> u16;
+        read_u24(buf: &[u8]) -> u32;
+        read_u32(buf: &[u8]) -> u32;
+        read_u48(buf: &[u8]) -> u64;
+        read_u64(buf: &[u8]) -> u64;
+        read_u128(buf: &[u8]) -> u128;
+        read_i16(buf: &[u8]) -> i16;
+        read_i24(buf: &[u8]) -> i32;
+        read_i32(buf: &[u8]) -> i32;
+        read_i48(buf: &[u8]) -> i64;
+        read_i64(buf: &[u8]) -> i64;
+        read_i128(buf: &[u8]) -> i128;
+        read_f32(buf: &[u8]) -> f32;
+        read_f64(buf: &[u8]) -> f64;
+        read_uint(buf: &[u8], nbytes: usize) -> u64;
+        read_uint128(buf: &[u8], nbytes: usize) -> u128;
+        read_int(buf: &[u8], nbytes: usize) -> i64;
+        read_int128(buf: &[u8], nbytes: usize) -> i128;
+        read_f16(buf: &[u8]) -> f32;
+        write_u16(buf: &mut [u8], n: u16);
+        write_u24(buf: &mut [u8], n: u32);
+        write_u32(buf: &mut [u8], n: u32);
+        write_u48(buf: &mut [u8], n: u64);
+        write_u64(buf: &mut [u8], n: u64);
+        write_u128(buf: &mut [u8], n: u128);
+        write_i16(buf: &mut [u8], n: i16);
+        write_i24(buf: &mut [u8], n: i32);
+        write_i32(buf: &mut [u8], n: i32);
+        write_i48(buf: &mut [u8], n: i64);
+        write_i64(buf: &mut [u8], n: i64);
+        write_i128(buf: &mut [u8], n: i128);
+        write_f32(buf: &mut [u8], n: f32);
+        write_f64(buf: &mut [u8], n: f64);
+        write_uint(buf: &mut [u8], n: u64, nbytes: usize);
+        write_uint128(buf: &mut [u8], n: u128, nbytes: usize);
+        write_int(buf: &mut [u8], n: i64, nbytes: usize);
+        write_int128(buf: &mut [u8], n: i128, nbytes: usize);
+        write_f16(buf: &mut [u8], n: f32);
+        try_read_u16(buf: &[u8]) -> Result<u16, Error>;
+        try_read_u24(buf: &[u8]) -> Result<u32, Error>;
+        try_read_u32(buf: &[u8]) -> Result<u32, Error>;
+        try_read_u48(buf: &[u8]) -> Result<u64, Error>;
+        try_read_u64(buf: &[u8]) -> Result<u64, Error>;
+        try_read_u128(buf: &[u8]) -> Result<u128, Error>;
+        try_read_i16(buf: &[u8]) -> Result<i16, Error>;
+        try_read_i24(buf: &[u8]) -> Result<i32, Error>;
+        try_read_i32(buf: &[u8]) -> Result<i32, Error>;
+        try_read_i48(buf: &[u8]) -> Result<i64, Error>;
+        try_read_i64(buf: &[u8]) -> Result<i64, Error>;
+        try_read_i128(buf: &[u8]) -> Result<i128, Error>;
+        try_read_f32(buf: &[u8]) -> Result<f32, Error>;
+        try_read_f64(buf: &[u8]) -> Result<f64, Error>;
+        try_read_uint(buf: &[u8], nbytes: usize) -> Result<u64, Error>;
+        try_read_uint128(buf: &[u8], nbytes: usize) -> Result<u128, Error>;
+        try_read_int(buf: &[u8], nbytes: usize) -> Result<i64, Error>;
+        try_read_int128(buf: &[u8], nbytes: usize) -> Result<i128, Error>;
+        try_write_u16(buf: &mut [u8], n: u16) -> Result<(), Error>;
+        try_write_u24(buf: &mut [u8], n: u32) -> Result<(), Error>;
+        try_write_u32(buf: &mut [u8], n: u32) -> Result<(), Error>;
+        try_write_u48(buf: &mut [u8], n: u64) -> Result<(), Error>;
+        try_write_u64(buf: &mut [u8], n: u64) -> Result<(), Error>;
+        try_write_u128(buf: &mut [u8], n: u128) -> Result<(), Error>;
+        try_write_i16(buf: &mut [u8], n: i16) -> Result<(), Error>;
+        try_write_i24(buf: &mut [u8], n: i32) -> Result<(), Error>;
+        try_write_i32(buf: &mut [u8], n: i32) -> Result<(), Error>;
+        try_write_i48(buf: &mut [u8], n: i64) -> Result<(), Error>;
+        try_write_i64(buf: &mut [u8], n: i64) -> Result<(), Error>;
+        try_write_i128(buf: &mut [u8], n: i128) -> Result<(), Error>;
+        try_write_f32(buf: &mut [u8], n: f32) -> Result<(), Error>;
+        try_write_f64(buf: &mut [u8], n: f64) -> Result<(), Error>;
+        try_write_uint(buf: &mut [u8], n: u64, nbytes: usize) -> Result<(), Error>;
+        try_write_uint128(buf: &mut [u8], n: u128, nbytes: usize) -> Result<(), Error>;
+        try_write_int(buf: &mut [u8], n: i64, nbytes: usize) -> Result<(), Error>;
+        try_write_int128(buf: &mut [u8], n: i128, nbytes: usize) -> Result<(), Error>;
+        read_u16_into(src: &[u8], dst: &mut [u16]);
+        read_u32_into(src: &[u8], dst: &mut [u32]);
+        read_u64_into(src: &[u8], dst: &mut [u64]);
+        read_u128_into(src: &[u8], dst: &mut [u128]);
+        read_i16_into(src: &[u8], dst: &mut [i16]);
+        read_i32_into(src: &[u8], dst: &mut [i32]);
+        read_i64_into(src: &[u8], dst: &mut [i64]);
+        read_i128_into(src: &[u8], dst: &mut [i128]);
+        read_f32_into(src: &[u8], dst: &mut [f32]);
+        read_f64_into(src: &[u8], dst: &mut [f64]);
+        read_f32_bits_into(src: &[u8], dst: &mut [u32]);
+        read_f64_bits_into(src: &[u8], dst: &mut [u64]);
+        write_u16_into(src: &[u16], dst: &mut [u8]);
+        write_u32_into(src: &[u32], dst: &mut [u8]);
+        write_u64_into(src: &[u64], dst: &mut [u8]);
+        write_u128_into(src: &[u128], dst: &mut [u8]);
+        write_i16_into(src: &[i16], dst: &mut [u8]);
+        write_i32_into(src: &[i32], dst: &mut [u8]);
+        write_i64_into(src: &[i64], dst: &mut [u8]);
+        write_i128_into(src: &[i128], dst: &mut [u8]);
+        write_f32_into(src: &[f32], dst: &mut [u8]);
+        write_f64_into(src: &[f64], dst: &mut [u8]);
+        write_i8_into(src: &[i8], dst: &mut [u8]);
+        write_f32_from_bits(src: &[u32], dst: &mut [u8]);
+        write_f64_from_bits(src: &[u64], dst: &mut [u8]);
+        from_slice_u16(numbers: &mut [u16]);
+        from_slice_u32(numbers: &mut [u32]);
+        from_slice_u64(numbers: &mut [u64]);
+        from_slice_u128(numbers: &mut [u128]);
+        from_slice_i16(numbers: &mut [i16]);
+        from_slice_i32(numbers: &mut [i32]);
+        from_slice_i64(numbers: &mut [i64]);
+        from_slice_i128(numbers: &mut [i128]);
+        from_slice_f32(numbers: &mut [f32]);
+        from_slice_f64(numbers: &mut [f64]);
+    }
+}
+
+/// The width of a pointer sized integer in some encoded data.
+///
+/// This is used by methods like [`ReadBytesExt::read_usize`] to read `usize`
+/// and `isize` values whose encoded width is fixed by a file format, rather
+/// than by the platform that reads them.
+///
+/// [`ReadBytesExt::read_usize`]: trait.ReadBytesExt.html#method.read_usize
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PtrWidth {
+    /// A 16 bit (2 bytes) integer, as on AVR and MSP430.
+    W16,
+    /// A 32 bit (4 bytes) integer.
+    W32,
+    /// A 64 bit (8 bytes) integer.
+    W64,
+}
+
+impl PtrWidth {
+    /// Returns the width of `usize` on the current platform.
+    ///
+    /// This is `W16` on 16 bit platforms, `W32` on 32 bit platforms and
+    /// `W64` otherwise.
+    #[inline]
+    pub const fn native() -> PtrWidth {
+        match core::mem::size_of::<usize>() {
+            2 => PtrWidth::W16,
+            4 => PtrWidth::W32,
+            _ => PtrWidth::W64,
+        }
+    }
+
+    /// Returns the number of bytes in this width.
+    #[inline]
+    pub const fn size(self) -> usize {
+        match self {
+            PtrWidth::W16 => 2,
+            PtrWidth::W32 => 4,
+            PtrWidth::W64 => 8,
+        }
+    }
+}
+
+/// The width of the length prefix of some encoded data.
+///
+/// This is used by methods like [`ReadBytesExt::read_string_prefixed`] to
+/// read data preceded by its length, where the width of the length is fixed
+/// by a file format or protocol.
+///
+/// [`ReadBytesExt::read_string_prefixed`]: trait.ReadBytesExt.html#method.read_string_prefixed
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PrefixWidth {
+    /// An 8 bit (1 byte) length.
+    U8,
+    /// A 16 bit (2 bytes) length.
+    U16,
+    /// A 32 bit (4 bytes) length.
+    U32,
+}
+
+impl PrefixWidth {
+    /// Returns the number of bytes in this width.
+    #[inline]
+    pub const fn size(self) -> usize {
+        match self {
+            PrefixWidth::U8 => 1,
+            PrefixWidth::U16 => 2,
+            PrefixWidth::U32 => 4,
+        }
+    }
+}
+
+/// The padding of a fixed-width text field.
+///
+/// Many formats, like tar, ISO 9660 and SEG-Y, store text in fields of a
+/// fixed number of bytes, with the unused bytes at the end filled with NUL
+/// bytes or spaces. This is used by methods like
+/// [`ReadBytesExt::read_fixed_str`] to remove that padding when reading,
+/// and add it when writing.
+///
+/// [`ReadBytesExt::read_fixed_str`]: trait.ReadBytesExt.html#method.read_fixed_str
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Padding {
+    /// Text is terminated by a NUL byte, unless it fills the whole field.
+    /// Everything from the first NUL byte on is padding, even if it isn't
+    /// all NUL bytes.
+    Nul,
+    /// Text is followed by spaces. Trailing spaces are padding.
+    Space,
+}
+
+impl Padding {
+    /// Returns the byte this padding is written with.
+    #[inline]
+    pub const fn byte(self) -> u8 {
+        match self {
+            Padding::Nul => 0,
+            Padding::Space => b' ',
+        }
+    }
+
+    /// Returns `field` without its padding.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::Padding;
+    ///
+    /// assert_eq!(b"ustar", Padding::Nul.trim(b"ustar\0\0\0"));
+    /// assert_eq!(b"a", Padding::Nul.trim(b"a\0garbage"));
+    /// assert_eq!(b"CD001", Padding::Space.trim(b"CD001   "));
+    /// ```
+    #[inline]
+    pub fn trim(self, field: &[u8]) -> &[u8] {
+        let end = match self {
+            Padding::Nul => {
+                field.iter().position(|&b| b == 0).unwrap_or(field.len())
+            }
+            Padding::Space => {
+                field.iter().rposition(|&b| b != b' ').map_or(0, |i| i + 1)
+            }
+        };
+        &field[..end]
+    }
+}
+
 /// Copies a &[u8] $src into a &mut [$ty] $dst for the endianness given by
 /// $from_bytes (must be either from_be_bytes or from_le_bytes).
 ///
@@ -1863,7 +3231,7 @@ macro_rules! read_slice {
         // Check types:
         let src: &[u8] = $src;
         let dst: &mut [$ty] = $dst;
-        assert_eq!(src.len(), dst.len() * SIZE);
+        check_eq!(src.len(), dst.len() * SIZE);
         for (src, dst) in src.chunks_exact(SIZE).zip(dst.iter_mut()) {
             *dst = <$ty>::$from_bytes(src.try_into().unwrap());
         }
@@ -1880,7 +3248,7 @@ macro_rules! write_slice {
         // Check types:
         let src: &[$ty] = $src;
         let dst: &mut [u8] = $dst;
-        assert_eq!(src.len() * SIZE, dst.len());
+        check_eq!(src.len() * SIZE, dst.len());
         for (src, dst) in src.iter().zip(dst.chunks_exact_mut(SIZE)) {
             dst.copy_from_slice(&src.$to_bytes());
         }
@@ -1890,28 +3258,32 @@ macro_rules! write_slice {
 impl ByteOrder for BigEndian {
     #[inline]
     fn read_u16(buf: &[u8]) -> u16 {
+        check_len!(buf, 2);
         u16::from_be_bytes(buf[..2].try_into().unwrap())
     }
 
     #[inline]
     fn read_u32(buf: &[u8]) -> u32 {
+        check_len!(buf, 4);
         u32::from_be_bytes(buf[..4].try_into().unwrap())
     }
 
     #[inline]
     fn read_u64(buf: &[u8]) -> u64 {
+        check_len!(buf, 8);
         u64::from_be_bytes(buf[..8].try_into().unwrap())
     }
 
     #[inline]
     fn read_u128(buf: &[u8]) -> u128 {
+        check_len!(buf, 16);
         u128::from_be_bytes(buf[..16].try_into().unwrap())
     }
 
     #[inline]
     fn read_uint(buf: &[u8], nbytes: usize) -> u64 {
         let mut out = [0; 8];
-        assert!(1 <= nbytes && nbytes <= out.len() && nbytes <= buf.len());
+        check!(1 <= nbytes && nbytes <= out.len() && nbytes <= buf.len());
         let start = out.len() - nbytes;
         out[start..].copy_from_slice(&buf[..nbytes]);
         u64::from_be_bytes(out)
@@ -1920,7 +3292,7 @@ impl ByteOrder for BigEndian {
     #[inline]
     fn read_uint128(buf: &[u8], nbytes: usize) -> u128 {
         let mut out = [0; 16];
-        assert!(1 <= nbytes && nbytes <= out.len() && nbytes <= buf.len());
+        check!(1 <= nbytes && nbytes <= out.len() && nbytes <= buf.len());
         let start = out.len() - nbytes;
         out[start..].copy_from_slice(&buf[..nbytes]);
         u128::from_be_bytes(out)
@@ -1928,28 +3300,32 @@ impl ByteOrder for BigEndian {
 
     #[inline]
     fn write_u16(buf: &mut [u8], n: u16) {
+        check_len!(buf, 2);
         buf[..2].copy_from_slice(&n.to_be_bytes());
     }
 
     #[inline]
     fn write_u32(buf: &mut [u8], n: u32) {
+        check_len!(buf, 4);
         buf[..4].copy_from_slice(&n.to_be_bytes());
     }
 
     #[inline]
     fn write_u64(buf: &mut [u8], n: u64) {
+        check_len!(buf, 8);
         buf[..8].copy_from_slice(&n.to_be_bytes());
     }
 
     #[inline]
     fn write_u128(buf: &mut [u8], n: u128) {
+        check_len!(buf, 16);
         buf[..16].copy_from_slice(&n.to_be_bytes());
     }
 
     #[inline]
     fn write_uint(buf: &mut [u8], n: u64, nbytes: usize) {
-        assert!(pack_size(n) <= nbytes && nbytes <= 8);
-        assert!(nbytes <= buf.len());
+        check!(pack_size(n) <= nbytes && nbytes <= 8);
+        check!(nbytes <= buf.len());
         unsafe {
             let bytes = *(&n.to_be() as *const u64 as *const [u8; 8]);
             copy_nonoverlapping(
@@ -1962,8 +3338,8 @@ impl ByteOrder for BigEndian {
 
     #[inline]
     fn write_uint128(buf: &mut [u8], n: u128, nbytes: usize) {
-        assert!(pack_size128(n) <= nbytes && nbytes <= 16);
-        assert!(nbytes <= buf.len());
+        check!(pack_size128(n) <= nbytes && nbytes <= 16);
+        check!(nbytes <= buf.len());
         unsafe {
             let bytes = *(&n.to_be() as *const u128 as *const [u8; 16]);
             copy_nonoverlapping(
@@ -2072,28 +3448,32 @@ impl ByteOrder for BigEndian {
 impl ByteOrder for LittleEndian {
     #[inline]
     fn read_u16(buf: &[u8]) -> u16 {
+        check_len!(buf, 2);
         u16::from_le_bytes(buf[..2].try_into().unwrap())
     }
 
     #[inline]
     fn read_u32(buf: &[u8]) -> u32 {
+        check_len!(buf, 4);
         u32::from_le_bytes(buf[..4].try_into().unwrap())
     }
 
     #[inline]
     fn read_u64(buf: &[u8]) -> u64 {
+        check_len!(buf, 8);
         u64::from_le_bytes(buf[..8].try_into().unwrap())
     }
 
     #[inline]
     fn read_u128(buf: &[u8]) -> u128 {
+        check_len!(buf, 16);
         u128::from_le_bytes(buf[..16].try_into().unwrap())
     }
 
     #[inline]
     fn read_uint(buf: &[u8], nbytes: usize) -> u64 {
         let mut out = [0; 8];
-        assert!(1 <= nbytes && nbytes <= out.len() && nbytes <= buf.len());
+        check!(1 <= nbytes && nbytes <= out.len() && nbytes <= buf.len());
         out[..nbytes].copy_from_slice(&buf[..nbytes]);
         u64::from_le_bytes(out)
     }
@@ -2101,35 +3481,39 @@ impl ByteOrder for LittleEndian {
     #[inline]
     fn read_uint128(buf: &[u8], nbytes: usize) -> u128 {
         let mut out = [0; 16];
-        assert!(1 <= nbytes && nbytes <= out.len() && nbytes <= buf.len());
+        check!(1 <= nbytes && nbytes <= out.len() && nbytes <= buf.len());
         out[..nbytes].copy_from_slice(&buf[..nbytes]);
         u128::from_le_bytes(out)
     }
 
     #[inline]
     fn write_u16(buf: &mut [u8], n: u16) {
+        check_len!(buf, 2);
         buf[..2].copy_from_slice(&n.to_le_bytes());
     }
 
     #[inline]
     fn write_u32(buf: &mut [u8], n: u32) {
+        check_len!(buf, 4);
         buf[..4].copy_from_slice(&n.to_le_bytes());
     }
 
     #[inline]
     fn write_u64(buf: &mut [u8], n: u64) {
+        check_len!(buf, 8);
         buf[..8].copy_from_slice(&n.to_le_bytes());
     }
 
     #[inline]
     fn write_u128(buf: &mut [u8], n: u128) {
+        check_len!(buf, 16);
         buf[..16].copy_from_slice(&n.to_le_bytes());
     }
 
     #[inline]
     fn write_uint(buf: &mut [u8], n: u64, nbytes: usize) {
-        assert!(pack_size(n) <= nbytes && nbytes <= 8);
-        assert!(nbytes <= buf.len());
+        check!(pack_size(n) <= nbytes && nbytes <= 8);
+        check!(nbytes <= buf.len());
         unsafe {
             let bytes = *(&n.to_le() as *const u64 as *const [u8; 8]);
             copy_nonoverlapping(bytes.as_ptr(), buf.as_mut_ptr(), nbytes);
@@ -2138,8 +3522,8 @@ impl ByteOrder for LittleEndian {
 
     #[inline]
     fn write_uint128(buf: &mut [u8], n: u128, nbytes: usize) {
-        assert!(pack_size128(n) <= nbytes && nbytes <= 16);
-        assert!(nbytes <= buf.len());
+        check!(pack_size128(n) <= nbytes && nbytes <= 16);
+        check!(nbytes <= buf.len());
         unsafe {
             let bytes = *(&n.to_le() as *const u128 as *const [u8; 16]);
             copy_nonoverlapping(bytes.as_ptr(), buf.as_mut_ptr(), nbytes);
@@ -3237,6 +4621,160 @@ mod test {
         let x = LittleEndian::read_i128(&xs);
         assert_eq!(x, 0);
     }
+
+    /// With the `no-panic` feature, a release build of this test only links
+    /// when none of the entry points it calls can panic. The buffers go
+    /// through `black_box`, so that their lengths aren't known at compile
+    /// time.
+    #[test]
+    fn no_panic_entry_points() {
+        use core::hint::black_box;
+
+        use crate::{
+            BigEndian, ByteOrder, ByteSliceExt, LittleEndian, SliceReader,
+            SliceWriter,
+        };
+
+        macro_rules! check {
+            ($len:expr, $size:expr, $result:expr) => {
+                assert_eq!($len >= $size, $result.is_ok());
+            };
+        }
+
+        let data = [0x80; 16];
+        for len in 0..=16 {
+            let buf = black_box(&data[..len]);
+            check!(len, 2, BigEndian::try_read_u16(buf));
+            check!(len, 3, LittleEndian::try_read_i24(buf));
+            check!(len, 4, BigEndian::try_read_f32(buf));
+            check!(len, 6, LittleEndian::try_read_u48(buf));
+            check!(len, 8, BigEndian::try_read_i64(buf));
+            check!(len, 16, LittleEndian::try_read_u128(buf));
+
+            let mut out = [0; 16];
+            let out = black_box(&mut out[..len]);
+            check!(len, 2, BigEndian::try_write_i16(out, -1));
+            check!(len, 4, LittleEndian::try_write_u32(out, 1));
+            check!(len, 8, BigEndian::try_write_f64(out, 1.0));
+            check!(len, 16, LittleEndian::try_write_i128(out, -1));
+
+            let mut rest = buf;
+            check!(len, 1, rest.read_u8());
+            check!(len, 3, rest.read_u16::<BigEndian>());
+            check!(len, 7, rest.read_array::<4>());
+
+            let mut rdr = SliceReader::new(buf);
+            check!(len, 4, rdr.read_i32::<LittleEndian>());
+            check!(len, 12, rdr.read_f64::<BigEndian>());
+
+            let mut wtr = SliceWriter::new(out);
+            check!(len, 1, wtr.write_i8(-1));
+            check!(len, 9, wtr.write_u64::<LittleEndian>(1));
+        }
+    }
+
+    #[test]
+    fn try_short_buffers() {
+        use crate::{BigEndian, ByteOrder, Error, ErrorKind, LittleEndian};
+
+        fn too_small(needed: usize, available: usize) -> Error {
+            Error::new(ErrorKind::BufferTooSmall { needed, available })
+        }
+
+        let buf = [0xFF; 16];
+        let mut out = [0; 16];
+        macro_rules! check {
+            ($read:ident, $write:ident, $n:expr, $val:expr) => {
+                assert!(BigEndian::$read(&buf[..$n]).is_ok());
+                assert_eq!(
+                    Err(too_small($n, $n - 1)),
+                    LittleEndian::$read(&buf[..$n - 1])
+                );
+                assert_eq!(Ok(()), BigEndian::$write(&mut out[..$n], $val));
+                assert_eq!(
+                    Err(too_small($n, 0)),
+                    LittleEndian::$write(&mut out[..0], $val)
+                );
+            };
+        }
+        check!(try_read_u16, try_write_u16, 2, 1);
+        check!(try_read_i16, try_write_i16, 2, -1);
+        check!(try_read_u24, try_write_u24, 3, 1);
+        check!(try_read_i24, try_write_i24, 3, -1);
+        check!(try_read_u32, try_write_u32, 4, 1);
+        check!(try_read_i32, try_write_i32, 4, -1);
+        check!(try_read_u48, try_write_u48, 6, 1);
+        check!(try_read_i48, try_write_i48, 6, -1);
+        check!(try_read_u64, try_write_u64, 8, 1);
+        check!(try_read_i64, try_write_i64, 8, -1);
+        check!(try_read_u128, try_write_u128, 16, 1);
+        check!(try_read_i128, try_write_i128, 16, -1);
+        check!(try_read_f32, try_write_f32, 4, 1.5);
+        check!(try_read_f64, try_write_f64, 8, 1.5);
+
+        assert_eq!(Ok(0xFFFFFF), BigEndian::try_read_uint(&buf, 3));
+        assert_eq!(
+            Err(too_small(5, 4)),
+            BigEndian::try_read_uint(&buf[..4], 5)
+        );
+        assert_eq!(Ok(-1), LittleEndian::try_read_int128(&buf, 11));
+        assert_eq!(Ok(()), BigEndian::try_write_int(&mut out, -2, 7));
+        assert_eq!(
+            Err(too_small(9, 8)),
+            LittleEndian::try_write_uint128(&mut out[..8], 1, 9)
+        );
+    }
+
+    #[test]
+    fn endianness_methods() {
+        use crate::{BigEndian, ByteOrder, Endianness, LittleEndian};
+
+        assert_eq!(Endianness::Little, Endianness::of::<LittleEndian>());
+        assert_eq!(
+            Endianness::native(),
+            Endianness::of::<crate::NativeEndian>()
+        );
+
+        let mut buf = [0; 16];
+        Endianness::Big.write_u48(&mut buf, 0x0102_0304_0506);
+        assert_eq!([1, 2, 3, 4, 5, 6], buf[..6]);
+        assert_eq!(0x0605_0403_0201, Endianness::Little.read_u48(&buf));
+        assert_eq!(
+            BigEndian::read_int128(&buf, 5),
+            Endianness::Big.read_int128(&buf, 5)
+        );
+        assert!(Endianness::Little.try_read_u128(&buf[1..]).is_err());
+
+        let mut numbers = [0x0102u16, 0x0304];
+        Endianness::Little.from_slice_u16(&mut numbers);
+        LittleEndian::from_slice_u16(&mut numbers);
+        assert_eq!([0x0102, 0x0304], numbers);
+        Endianness::Big.write_u16_into(&numbers, &mut buf[..4]);
+        assert_eq!([1, 2, 3, 4], buf[..4]);
+    }
+
+    #[test]
+    fn float_bits_are_exact() {
+        use crate::{BigEndian, ByteOrder, LittleEndian};
+
+        // A signaling NaN with a payload, a negative quiet NaN and a
+        // subnormal.
+        let bits32 = [0x7F80_0001, 0xFFC0_1234, 0x0000_0001];
+        let mut bytes = [0; 12];
+        BigEndian::write_f32_from_bits(&bits32, &mut bytes);
+        assert_eq!([0x7F, 0x80, 0x00, 0x01], bytes[..4]);
+        let mut got = [0; 3];
+        BigEndian::read_f32_bits_into(&bytes, &mut got);
+        assert_eq!(bits32, got);
+
+        let bits64 = [0x7FF0_0000_0000_0001, 0xFFF8_0000_DEAD_BEEF];
+        let mut bytes = [0; 16];
+        LittleEndian::write_f64_from_bits(&bits64, &mut bytes);
+        assert_eq!([0x01, 0, 0, 0, 0, 0, 0xF0, 0x7F], bytes[..8]);
+        let mut got = [0; 2];
+        LittleEndian::read_f64_bits_into(&bytes, &mut got);
+        assert_eq!(bits64, got);
+    }
 }
 
 #[cfg(test)]
@@ -3412,11 +4950,39 @@ mod stdtests {
         write_f32
     );
     qc_bytes_ext!(
-        prop_ext_f64,
-        f64,
-        ::std::i64::MAX as u64,
-        read_f64,
-        write_f64
+        prop_ext_f64,
+        f64,
+        ::std::i64::MAX as u64,
+        read_f64,
+        write_f64
+    );
+    qc_bytes_ext!(
+        prop_ext_u24,
+        u32,
+        crate::test::U24_MAX as u64,
+        read_u24,
+        write_u24
+    );
+    qc_bytes_ext!(
+        prop_ext_i24,
+        i32,
+        crate::test::I24_MAX as u64,
+        read_i24,
+        write_i24
+    );
+    qc_bytes_ext!(
+        prop_ext_u48,
+        u64,
+        crate::test::U48_MAX,
+        read_u48,
+        write_u48
+    );
+    qc_bytes_ext!(
+        prop_ext_i48,
+        i64,
+        crate::test::I48_MAX as u64,
+        read_i48,
+        write_i48
     );
 
     qc_bytes_ext!(prop_ext_u128, Wi128<u128>, 16 + 1, read_u128, write_u128);
@@ -3912,4 +5478,1169 @@ mod stdtests {
 
     qc_slice!(prop_slice_f32, f32, read_f32_into, write_f32_into, 0.0);
     qc_slice!(prop_slice_f64, f64, read_f64_into, write_f64_into, 0.0);
+
+    // Test reading slices from a `Read` with `ReadBytesExt`.
+    macro_rules! qc_slice_ext {
+        ($name:ident, $ty_int:ty, $read:ident, $write:ident, $zero:expr) => {
+            #[cfg(not(miri))]
+            mod $name {
+                use super::qc_unsized;
+                #[allow(unused_imports)]
+                use crate::test::Wi128;
+                use crate::{
+                    BigEndian, ByteOrder, LittleEndian, NativeEndian,
+                    ReadBytesExt,
+                };
+                use core::mem::size_of;
+                use std::io::Cursor;
+
+                fn roundtrip<E: ByteOrder>(numbers: Vec<$ty_int>) -> bool {
+                    let numbers: Vec<_> =
+                        numbers.into_iter().map(|x| x.clone()).collect();
+                    let num_bytes = size_of::<$ty_int>() * numbers.len();
+                    let mut bytes = vec![0; num_bytes];
+                    E::$write(&numbers, &mut bytes);
+
+                    let mut got = vec![$zero; numbers.len()];
+                    let mut rdr = Cursor::new(bytes);
+                    rdr.$read::<E>(&mut got).unwrap();
+                    numbers == got
+                }
+
+                #[test]
+                fn big_endian() {
+                    qc_unsized(roundtrip::<BigEndian> as fn(_) -> bool);
+                }
+
+                #[test]
+                fn little_endian() {
+                    qc_unsized(roundtrip::<LittleEndian> as fn(_) -> bool);
+                }
+
+                #[test]
+                fn native_endian() {
+                    qc_unsized(roundtrip::<NativeEndian> as fn(_) -> bool);
+                }
+
+                #[test]
+                fn short_input() {
+                    let bytes = vec![0; size_of::<$ty_int>() * 2 - 1];
+                    let mut got = vec![$zero; 2];
+                    let mut rdr = Cursor::new(bytes);
+                    let err = rdr.$read::<BigEndian>(&mut got).unwrap_err();
+                    assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+                }
+            }
+        };
+    }
+
+    qc_slice_ext!(prop_ext_slice_u16, u16, read_u16_into, write_u16_into, 0);
+    qc_slice_ext!(prop_ext_slice_i16, i16, read_i16_into, write_i16_into, 0);
+    qc_slice_ext!(prop_ext_slice_u32, u32, read_u32_into, write_u32_into, 0);
+    qc_slice_ext!(prop_ext_slice_i32, i32, read_i32_into, write_i32_into, 0);
+    qc_slice_ext!(prop_ext_slice_u64, u64, read_u64_into, write_u64_into, 0);
+    qc_slice_ext!(prop_ext_slice_i64, i64, read_i64_into, write_i64_into, 0);
+    qc_slice_ext!(
+        prop_ext_slice_u128,
+        Wi128<u128>,
+        read_u128_into,
+        write_u128_into,
+        0
+    );
+    qc_slice_ext!(
+        prop_ext_slice_i128,
+        Wi128<i128>,
+        read_i128_into,
+        write_i128_into,
+        0
+    );
+
+    macro_rules! qc_write_from {
+        ($name:ident, $ty_int:ty, $write_from:ident, $write:ident) => {
+            #[cfg(not(miri))]
+            mod $name {
+                use super::qc_unsized;
+                #[allow(unused_imports)]
+                use crate::test::Wi128;
+                use crate::{
+                    BigEndian, ByteOrder, LittleEndian, NativeEndian,
+                    WriteBytesExt,
+                };
+                use core::mem::size_of;
+
+                fn same_as_into<E: ByteOrder>(numbers: Vec<$ty_int>) -> bool {
+                    let numbers: Vec<_> =
+                        numbers.into_iter().map(|x| x.clone()).collect();
+                    let num_bytes = size_of::<$ty_int>() * numbers.len();
+                    let mut expected = vec![0; num_bytes];
+                    E::$write(&numbers, &mut expected);
+
+                    let mut got = vec![];
+                    got.$write_from::<E>(&numbers).unwrap();
+                    expected == got
+                }
+
+                #[test]
+                fn big_endian() {
+                    qc_unsized(same_as_into::<BigEndian> as fn(_) -> bool);
+                }
+
+                #[test]
+                fn little_endian() {
+                    qc_unsized(same_as_into::<LittleEndian> as fn(_) -> bool);
+                }
+
+                #[test]
+                fn native_endian() {
+                    qc_unsized(same_as_into::<NativeEndian> as fn(_) -> bool);
+                }
+            }
+        };
+    }
+
+    qc_write_from!(prop_write_from_u16, u16, write_u16_from, write_u16_into);
+    qc_write_from!(prop_write_from_i16, i16, write_i16_from, write_i16_into);
+    qc_write_from!(prop_write_from_u32, u32, write_u32_from, write_u32_into);
+    qc_write_from!(prop_write_from_i32, i32, write_i32_from, write_i32_into);
+    qc_write_from!(prop_write_from_u64, u64, write_u64_from, write_u64_into);
+    qc_write_from!(prop_write_from_i64, i64, write_i64_from, write_i64_into);
+    qc_write_from!(
+        prop_write_from_u128,
+        Wi128<u128>,
+        write_u128_from,
+        write_u128_into
+    );
+    qc_write_from!(
+        prop_write_from_i128,
+        Wi128<i128>,
+        write_i128_from,
+        write_i128_into
+    );
+    qc_write_from!(prop_write_from_f32, f32, write_f32_from, write_f32_into);
+    qc_write_from!(prop_write_from_f64, f64, write_f64_from, write_f64_into);
+
+    #[test]
+    fn write_from_spans_chunks() {
+        use crate::{BigEndian, ReadBytesExt, WriteBytesExt};
+        use std::io::Cursor;
+
+        let numbers: Vec<u64> = (0..10_000).map(|i| i * 0x0101).collect();
+        let mut wtr = vec![];
+        wtr.write_u64_from::<BigEndian>(&numbers).unwrap();
+        assert_eq!(8 * numbers.len(), wtr.len());
+
+        let mut got = vec![0; numbers.len()];
+        Cursor::new(wtr).read_u64_into::<BigEndian>(&mut got).unwrap();
+        assert_eq!(numbers, got);
+    }
+
+    #[test]
+    fn write_all_from_iterators() {
+        use crate::{BigEndian, LittleEndian, WriteBytesExt};
+
+        // Enough numbers to fill the staging buffer more than once.
+        let numbers: Vec<u64> = (0..10_000).map(|i| i * 0x0101).collect();
+        let mut expected = vec![];
+        expected.write_u64_from::<BigEndian>(&numbers).unwrap();
+        let mut got = vec![];
+        got.write_all_u64::<BigEndian, _>(numbers.iter().copied()).unwrap();
+        assert_eq!(expected, got);
+        got.clear();
+        got.write_all_u64::<BigEndian, _>(&numbers).unwrap();
+        assert_eq!(expected, got);
+
+        let mut got = vec![];
+        got.write_all_i16::<LittleEndian, _>(vec![-1, 2]).unwrap();
+        got.write_all_f32::<LittleEndian, _>(&[1.5]).unwrap();
+        got.write_all_u128::<LittleEndian, _>(None::<u128>).unwrap();
+        assert_eq!(b"\xff\xff\x02\x00\x00\x00\xc0\x3f", &got[..]);
+    }
+
+    #[test]
+    fn write_i8_from() {
+        use crate::WriteBytesExt;
+
+        let numbers: Vec<i8> = (-128..=127).collect();
+        let mut wtr = vec![];
+        wtr.write_i8_from(&numbers).unwrap();
+        let got: Vec<i8> = wtr.into_iter().map(|b| b as i8).collect();
+        assert_eq!(numbers, got);
+    }
+
+    #[test]
+    fn read_24_bit_sign_extension() {
+        use crate::{BigEndian, LittleEndian, ReadBytesExt};
+        use std::io::Cursor;
+
+        let mut rdr = Cursor::new(vec![0xFF, 0xFF, 0xFE, 0x80, 0x00, 0x00]);
+        assert_eq!(-2, rdr.read_i24::<BigEndian>().unwrap());
+        assert_eq!(128, rdr.read_i24::<LittleEndian>().unwrap());
+
+        let mut rdr = Cursor::new(vec![0x80, 0x00, 0x00, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(-8_388_608, rdr.read_i24::<BigEndian>().unwrap());
+        assert_eq!(0xFF_FFFF, rdr.read_u24::<LittleEndian>().unwrap());
+
+        let mut rdr = Cursor::new(vec![0x01, 0x02]);
+        let err = rdr.read_u24::<BigEndian>().unwrap_err();
+        assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn ptr_width_roundtrip() {
+        use crate::{
+            BigEndian, LittleEndian, PtrWidth, ReadBytesExt, WriteBytesExt,
+        };
+        use std::io::Cursor;
+
+        let mut wtr = vec![];
+        wtr.write_usize::<BigEndian>(0xDEAD_BEEF, PtrWidth::W32).unwrap();
+        wtr.write_isize::<LittleEndian>(-3, PtrWidth::W32).unwrap();
+        wtr.write_usize::<LittleEndian>(7, PtrWidth::W64).unwrap();
+        wtr.write_isize::<BigEndian>(-9, PtrWidth::W64).unwrap();
+        assert_eq!(24, wtr.len());
+
+        let mut rdr = Cursor::new(wtr);
+        assert_eq!(
+            0xDEAD_BEEF,
+            rdr.read_usize::<BigEndian>(PtrWidth::W32).unwrap()
+        );
+        assert_eq!(-3, rdr.read_isize::<LittleEndian>(PtrWidth::W32).unwrap());
+        assert_eq!(7, rdr.read_usize::<LittleEndian>(PtrWidth::W64).unwrap());
+        assert_eq!(-9, rdr.read_isize::<BigEndian>(PtrWidth::W64).unwrap());
+        assert_eq!(4, PtrWidth::W32.size());
+        assert_eq!(
+            core::mem::size_of::<usize>() == 4,
+            PtrWidth::native() == PtrWidth::W32
+        );
+
+        let mut wtr = vec![];
+        wtr.write_usize::<BigEndian>(0xBEEF, PtrWidth::W16).unwrap();
+        wtr.write_isize::<LittleEndian>(-2, PtrWidth::W16).unwrap();
+        assert_eq!(wtr, [0xBE, 0xEF, 0xFE, 0xFF]);
+        let mut rdr = Cursor::new(wtr);
+        assert_eq!(
+            0xBEEF,
+            rdr.read_usize::<BigEndian>(PtrWidth::W16).unwrap()
+        );
+        assert_eq!(-2, rdr.read_isize::<LittleEndian>(PtrWidth::W16).unwrap());
+        assert_eq!(2, PtrWidth::W16.size());
+        assert_eq!(core::mem::size_of::<usize>(), PtrWidth::native().size());
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn ptr_width_overflow() {
+        use crate::{BigEndian, PtrWidth, ReadBytesExt, WriteBytesExt};
+        use std::io::{Cursor, ErrorKind};
+
+        let mut wtr = vec![];
+        let err =
+            wtr.write_usize::<BigEndian>(1 << 32, PtrWidth::W32).unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        let err = wtr
+            .write_isize::<BigEndian>(i32::MIN as isize - 1, PtrWidth::W32)
+            .unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        let err =
+            wtr.write_usize::<BigEndian>(1 << 16, PtrWidth::W16).unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        let err =
+            wtr.write_isize::<BigEndian>(-32769, PtrWidth::W16).unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        assert!(wtr.is_empty());
+
+        // A 64 bit value always fits on a 64 bit platform.
+        let mut rdr = Cursor::new(vec![0xFF; 8]);
+        assert_eq!(
+            usize::MAX,
+            rdr.read_usize::<BigEndian>(PtrWidth::W64).unwrap()
+        );
+    }
+
+    #[cfg(target_pointer_width = "32")]
+    #[test]
+    fn ptr_width_overflow() {
+        use crate::{BigEndian, PtrWidth, ReadBytesExt};
+        use std::io::{Cursor, ErrorKind};
+
+        let mut rdr = Cursor::new(vec![0, 0, 0, 1, 0, 0, 0, 0]);
+        let err = rdr.read_usize::<BigEndian>(PtrWidth::W64).unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn try_write_uint_int() {
+        use crate::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+        use std::io::{Cursor, ErrorKind};
+
+        let mut wtr = vec![];
+        for nbytes in 1..=8 {
+            let max = u64::MAX >> (64 - 8 * nbytes);
+            wtr.try_write_uint::<BigEndian>(max, nbytes).unwrap();
+            let min = -1i64 << (8 * nbytes - 1);
+            wtr.try_write_int::<LittleEndian>(min, nbytes).unwrap();
+            if nbytes < 8 {
+                let err = wtr
+                    .try_write_uint::<BigEndian>(max + 1, nbytes)
+                    .unwrap_err();
+                assert_eq!(ErrorKind::InvalidInput, err.kind());
+                let err = wtr
+                    .try_write_int::<LittleEndian>(min - 1, nbytes)
+                    .unwrap_err();
+                assert_eq!(ErrorKind::InvalidInput, err.kind());
+                let err = wtr
+                    .try_write_int::<LittleEndian>(-min, nbytes)
+                    .unwrap_err();
+                assert_eq!(ErrorKind::InvalidInput, err.kind());
+            }
+        }
+
+        let mut rdr = Cursor::new(wtr);
+        for nbytes in 1..=8 {
+            let max = u64::MAX >> (64 - 8 * nbytes);
+            assert_eq!(max, rdr.read_uint::<BigEndian>(nbytes).unwrap());
+            let min = -1i64 << (8 * nbytes - 1);
+            assert_eq!(min, rdr.read_int::<LittleEndian>(nbytes).unwrap());
+        }
+
+        let mut wtr = vec![];
+        assert!(wtr.try_write_uint::<BigEndian>(0, 0).is_err());
+        assert!(wtr.try_write_uint::<BigEndian>(0, 9).is_err());
+        assert!(wtr.try_write_int::<BigEndian>(0, 0).is_err());
+        assert!(wtr.try_write_int::<BigEndian>(0, 9).is_err());
+        assert!(wtr.is_empty());
+    }
+
+    #[test]
+    fn read_array() {
+        use std::io::{Cursor, ErrorKind};
+
+        use crate::ReadBytesExt;
+
+        let mut rdr = Cursor::new(vec![1, 2, 3, 4, 5]);
+        assert_eq!([0u8; 0], rdr.read_array().unwrap());
+        assert_eq!([1, 2, 3], rdr.read_array().unwrap());
+        let err = rdr.read_array::<3>().unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn iter_values() {
+        use std::io::{self, Cursor, ErrorKind, Read};
+
+        use crate::{BigEndian, LittleEndian, ReadBytesExt};
+
+        let mut rdr = Cursor::new(vec![1, 0, 2, 0, 3, 0]);
+        let xs: Vec<u16> =
+            rdr.iter_u16::<LittleEndian>().collect::<io::Result<_>>().unwrap();
+        assert_eq!(vec![1, 2, 3], xs);
+        assert!(rdr.iter_u64::<BigEndian>().next().is_none());
+
+        let mut rdr = Cursor::new(vec![0, 0, 0, 1, 0, 0]);
+        let mut it = rdr.iter_i32::<BigEndian>();
+        assert_eq!(1, it.next().unwrap().unwrap());
+        let err = it.next().unwrap().unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        assert!(it.next().is_none());
+
+        // A reader that hands out one byte at a time, and is interrupted
+        // before each one.
+        struct Trickle(Vec<u8>, bool);
+        impl Read for Trickle {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.1 = !self.1;
+                if self.1 {
+                    return Err(ErrorKind::Interrupted.into());
+                }
+                if self.0.is_empty() || buf.is_empty() {
+                    return Ok(0);
+                }
+                buf[0] = self.0.remove(0);
+                Ok(1)
+            }
+        }
+        let mut rdr = Trickle(vec![0x3f, 0x80, 0, 0, 0xc0, 0, 0, 0], false);
+        let xs: Vec<f32> =
+            rdr.iter_f32::<BigEndian>().collect::<io::Result<_>>().unwrap();
+        assert_eq!(vec![1.0, -2.0], xs);
+    }
+
+    #[test]
+    fn read_write_wire() {
+        use std::io::{Cursor, ErrorKind};
+
+        use crate::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+        // Large enough to not fit in the stack buffer.
+        let big = [0x0102_0304_0506_0708u64; 20];
+        let mut wtr = vec![];
+        wtr.write_wire::<_, BigEndian>(&big).unwrap();
+        wtr.write_wire::<_, BigEndian>(&-1i16).unwrap();
+        assert_eq!(162, wtr.len());
+        assert_eq!([1, 2, 3, 4, 5, 6, 7, 8], wtr[..8]);
+
+        let mut rdr = Cursor::new(wtr);
+        assert_eq!(big, rdr.read_wire::<[u64; 20], BigEndian>().unwrap());
+        assert_eq!(-1, rdr.read_wire::<i16, BigEndian>().unwrap());
+        let err = rdr.read_wire::<u8, BigEndian>().unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn option_and_tagged_enum() {
+        use std::io::{Cursor, ErrorKind};
+
+        use crate::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+
+        let mut wtr = vec![];
+        for &x in [Some(-1i32), None].iter() {
+            wtr.write_option(x, |w, x| w.write_i32::<LittleEndian>(x))
+                .unwrap();
+        }
+        wtr.write_tagged_enum::<u32, BigEndian, _>(0xABCD, |w| w.write_u8(9))
+            .unwrap();
+        assert_eq!(wtr, [1, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0xAB, 0xCD, 9]);
+
+        let mut rdr = Cursor::new(wtr);
+        let read = |r: &mut Cursor<Vec<u8>>| r.read_i32::<LittleEndian>();
+        assert_eq!(Some(-1), rdr.read_option(read).unwrap());
+        assert_eq!(None, rdr.read_option(read).unwrap());
+        let got = rdr
+            .read_tagged_enum::<u32, BigEndian, _, _>(|r, tag| {
+                Ok(Some((tag, r.read_u8()?)))
+            })
+            .unwrap();
+        assert_eq!((0xABCD, 9), got);
+
+        let mut rdr = Cursor::new(vec![2, 1]);
+        let err = rdr.read_option(|r| r.read_u8()).unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        let err = rdr
+            .read_tagged_enum::<i8, BigEndian, (), _>(|_, _| Ok(None))
+            .unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!("unknown enum tag 1", err.to_string());
+    }
+
+    #[test]
+    fn read_exact_vec() {
+        use crate::ReadBytesExt;
+        use std::io::Cursor;
+
+        let mut rdr = Cursor::new(vec![1, 2, 3, 4, 5]);
+        assert_eq!(Vec::<u8>::new(), rdr.read_exact_vec(0).unwrap());
+        assert_eq!(vec![1, 2], rdr.read_exact_vec(2).unwrap());
+        assert_eq!(vec![3, 4, 5], rdr.read_exact_vec(3).unwrap());
+
+        let err = rdr.read_exact_vec(1).unwrap_err();
+        assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn read_exact_vec_absurd_length() {
+        use crate::ReadBytesExt;
+        use std::io::Cursor;
+
+        let mut rdr = Cursor::new(vec![0; 10]);
+        let err = rdr.read_exact_vec(usize::MAX).unwrap_err();
+        assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+
+        let data = vec![7; 200_000];
+        let mut rdr = Cursor::new(data.clone());
+        assert_eq!(data, rdr.read_exact_vec(data.len()).unwrap());
+    }
+
+    #[test]
+    fn read_vec() {
+        use crate::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+        use std::io::{Cursor, ErrorKind};
+
+        let mut rdr = Cursor::new(vec![0xFF, 1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(vec![-1], rdr.read_vec::<i8, BigEndian>(1).unwrap());
+        assert_eq!(
+            vec![0x0102, 0x0304],
+            rdr.read_vec::<u16, BigEndian>(2).unwrap()
+        );
+        assert_eq!(
+            vec![0x0807_0605],
+            rdr.read_vec::<u32, LittleEndian>(1).unwrap()
+        );
+        assert!(rdr.read_vec::<u64, BigEndian>(0).unwrap().is_empty());
+        let err = rdr.read_vec::<u8, BigEndian>(1).unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+
+        // Large enough to be read in several chunks.
+        let xs: Vec<f64> = (0..100_000).map(|i| i as f64 / 3.0).collect();
+        let mut wtr = vec![];
+        wtr.write_f64_from::<BigEndian>(&xs).unwrap();
+        let mut rdr = Cursor::new(wtr);
+        assert_eq!(xs, rdr.read_vec::<f64, BigEndian>(xs.len()).unwrap());
+    }
+
+    #[test]
+    fn uleb128_io() {
+        use crate::{ReadBytesExt, WriteBytesExt};
+        use std::io::{Cursor, ErrorKind};
+
+        let values = [0, 1, 127, 128, 300, 1 << 35, u64::MAX];
+        let mut wtr = vec![];
+        for &n in &values {
+            wtr.write_uleb128(n).unwrap();
+        }
+        let mut rdr = Cursor::new(wtr);
+        for &n in &values {
+            assert_eq!(n, rdr.read_uleb128().unwrap());
+        }
+        let err = rdr.read_uleb128().unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+
+        // Protocol buffers varints are the same encoding.
+        let mut wtr = vec![];
+        wtr.write_varint_u64(u64::MAX).unwrap();
+        wtr.write_uleb128(300).unwrap();
+        let mut rdr = Cursor::new(wtr);
+        assert_eq!(u64::MAX, rdr.read_uleb128().unwrap());
+        assert_eq!(300, rdr.read_varint_u64().unwrap());
+
+        let mut wtr = vec![];
+        for &n in &values {
+            wtr.write_git_offset(n).unwrap();
+        }
+        let mut rdr = Cursor::new(wtr);
+        for &n in &values {
+            assert_eq!(n, rdr.read_git_offset().unwrap());
+        }
+        let mut rdr = Cursor::new(vec![0x80; 20]);
+        let err = rdr.read_git_offset().unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(10, rdr.position());
+
+        let mut wtr = vec![];
+        for &n in &values {
+            wtr.write_prefix_varint(n).unwrap();
+        }
+        let mut rdr = Cursor::new(wtr);
+        for &n in &values {
+            assert_eq!(n, rdr.read_prefix_varint().unwrap());
+        }
+        let mut rdr = Cursor::new(vec![0xFF, 0, 0]);
+        let err = rdr.read_prefix_varint().unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+
+        // The guard stops reading after ten bytes.
+        let mut rdr = Cursor::new(vec![0x80; 20]);
+        let err = rdr.read_uleb128().unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(10, rdr.position());
+
+        let values = [0, 300, i128::MAX, i128::MIN, -1, 1 << 100];
+        let mut wtr = vec![];
+        for &n in &values {
+            wtr.write_uleb128_u128(n as u128).unwrap();
+            wtr.write_varint_zigzag_i128(n).unwrap();
+        }
+        let mut rdr = Cursor::new(wtr);
+        for &n in &values {
+            assert_eq!(n as u128, rdr.read_uleb128_u128().unwrap());
+            assert_eq!(n, rdr.read_varint_zigzag_i128().unwrap());
+        }
+        let mut rdr = Cursor::new(vec![0x80; 40]);
+        let err = rdr.read_uleb128_u128().unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(19, rdr.position());
+    }
+
+    #[test]
+    fn frames() {
+        use crate::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+        use std::io::{Cursor, ErrorKind};
+
+        let mut wtr = vec![];
+        wtr.write_frame::<BigEndian>(b"").unwrap();
+        wtr.write_frame::<LittleEndian>(b"abc").unwrap();
+        wtr.write_frame_u16::<BigEndian>(&[7; 300]).unwrap();
+        let mut rdr = Cursor::new(wtr);
+        assert!(rdr.read_frame::<BigEndian>(0).unwrap().is_empty());
+        assert_eq!(b"abc", &rdr.read_frame::<LittleEndian>(3).unwrap()[..]);
+        assert_eq!(
+            vec![7; 300],
+            rdr.read_frame_u16::<BigEndian>(300).unwrap()
+        );
+
+        // The limit is checked before the payload is read.
+        let mut rdr = Cursor::new(vec![0, 4, 1, 2, 3, 4]);
+        let err = rdr.read_frame_u16::<BigEndian>(3).unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(2, rdr.position());
+
+        // A huge length on a short input is an error, not an allocation.
+        let mut rdr = Cursor::new(vec![0xFF, 0xFF, 0xFF, 0xFF, 1]);
+        let err = rdr.read_frame::<BigEndian>(usize::MAX).unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+
+        let mut wtr = vec![];
+        let err = wtr.write_frame_u16::<BigEndian>(&[0; 65536]).unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        assert!(wtr.is_empty());
+    }
+
+    #[test]
+    fn cstrings() {
+        use crate::{ReadBytesExt, WriteBytesExt};
+        use std::io::{Cursor, ErrorKind};
+
+        let mut wtr = vec![];
+        wtr.write_cstring(b"").unwrap();
+        wtr.write_cstring(b"four").unwrap();
+        wtr.write_cstring(b"longer").unwrap();
+        let err = wtr.write_cstring(b"\0").unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+
+        let mut rdr = Cursor::new(wtr);
+        assert_eq!(b"", rdr.read_cstring(0).unwrap().as_bytes());
+        assert_eq!(b"four", rdr.read_cstring(4).unwrap().as_bytes());
+        let err = rdr.read_cstring(4).unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(11, rdr.position());
+
+        let mut rdr = Cursor::new(b"unterminated");
+        let err = rdr.read_cstring(64).unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn read_nonzero() {
+        use std::io::{Cursor, ErrorKind};
+
+        use crate::{BigEndian, LittleEndian, ReadBytesExt};
+
+        let mut rdr = Cursor::new(vec![0xFF, 1, 0, 0, 0, 0, 0, 0, 0, 0x80]);
+        assert_eq!(-1, rdr.read_nonzero_i8().unwrap().get());
+        assert_eq!(1, rdr.read_nonzero_u32::<LittleEndian>().unwrap().get());
+        let err = rdr.read_nonzero_u32::<BigEndian>().unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!("expected a nonzero value", err.to_string());
+        // The zero was consumed, like any other value that fails to parse.
+        assert_eq!(9, rdr.position());
+        assert_eq!(0x80, rdr.read_nonzero_u8().unwrap().get());
+
+        let err = rdr.read_nonzero_u8().unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        assert_eq!(
+            std::mem::size_of::<u64>(),
+            std::mem::size_of::<Option<std::num::NonZeroU64>>()
+        );
+    }
+
+    #[test]
+    fn read_cstring_lossy() {
+        use std::io::{Cursor, ErrorKind};
+
+        use crate::ReadBytesExt;
+
+        let mut rdr = Cursor::new(b"ok\0\xff\xfeab\0\0rest".to_vec());
+        assert_eq!("ok", rdr.read_cstring_lossy(16).unwrap());
+        assert_eq!("\u{FFFD}\u{FFFD}ab", rdr.read_cstring_lossy(16).unwrap());
+        assert_eq!("", rdr.read_cstring_lossy(16).unwrap());
+        assert_eq!("", rdr.read_cstring_lossy(0).unwrap());
+        assert_eq!("re", rdr.read_cstring_lossy(2).unwrap());
+        assert_eq!(11, rdr.position());
+
+        let err = rdr.read_cstring_lossy(16).unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+
+        // A string of exactly max_len bytes leaves its NUL byte unread.
+        let mut rdr = Cursor::new(b"abc\0def\0".to_vec());
+        assert_eq!("abc", rdr.read_cstring_lossy(3).unwrap());
+        assert_eq!(3, rdr.position());
+        assert_eq!("", rdr.read_cstring_lossy(3).unwrap());
+        assert_eq!("def", rdr.read_cstring_lossy(4).unwrap());
+        assert_eq!(8, rdr.position());
+    }
+
+    #[test]
+    fn prefixed_strings() {
+        use crate::{
+            BigEndian, LittleEndian, PrefixWidth, ReadBytesExt, WriteBytesExt,
+        };
+        use std::io::{Cursor, ErrorKind};
+
+        let mut wtr = vec![];
+        wtr.write_string_prefixed::<BigEndian>(PrefixWidth::U8, "").unwrap();
+        wtr.write_string_prefixed::<BigEndian>(PrefixWidth::U16, "héllo")
+            .unwrap();
+        wtr.write_string_prefixed::<LittleEndian>(PrefixWidth::U32, "ok")
+            .unwrap();
+        assert_eq!(&wtr[..4], &[0, 0, 6, b'h']);
+        assert_eq!(&wtr[wtr.len() - 6..], b"\x02\0\0\0ok");
+
+        let mut rdr = Cursor::new(wtr);
+        let s = rdr.read_string_prefixed::<BigEndian>(PrefixWidth::U8);
+        assert_eq!("", s.unwrap());
+        let s = rdr.read_string_prefixed::<BigEndian>(PrefixWidth::U16);
+        assert_eq!("héllo", s.unwrap());
+        let s =
+            rdr.read_string_prefixed_lossy::<LittleEndian>(PrefixWidth::U32);
+        assert_eq!("ok", s.unwrap());
+
+        let bad = b"\x03a\xffb";
+        let err = Cursor::new(bad)
+            .read_string_prefixed::<BigEndian>(PrefixWidth::U8)
+            .unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        let s = Cursor::new(bad)
+            .read_string_prefixed_lossy::<BigEndian>(PrefixWidth::U8);
+        assert_eq!("a\u{FFFD}b", s.unwrap());
+
+        let mut wtr = vec![];
+        let long = "x".repeat(256);
+        let err = wtr
+            .write_string_prefixed::<BigEndian>(PrefixWidth::U8, &long)
+            .unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        assert!(wtr.is_empty());
+    }
+
+    #[test]
+    fn utf16_strings() {
+        use crate::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+        use std::io::{Cursor, ErrorKind};
+
+        let s = "a\u{E9}\u{1F600}z";
+        let units = s.encode_utf16().count();
+        let mut wtr = vec![];
+        wtr.write_utf16_string::<BigEndian>(s).unwrap();
+        wtr.write_utf16_string::<LittleEndian>(s).unwrap();
+        wtr.write_utf16_string_bom::<BigEndian>(s).unwrap();
+        wtr.write_utf16_string_bom::<LittleEndian>(s).unwrap();
+        let mut rdr = Cursor::new(wtr);
+        assert_eq!(s, rdr.read_utf16_string::<BigEndian>(units).unwrap());
+        assert_eq!(s, rdr.read_utf16_string::<LittleEndian>(units).unwrap());
+        // The byte order marks win over the default.
+        let bom = units + 1;
+        assert_eq!(s, rdr.read_utf16_string_bom::<LittleEndian>(bom).unwrap());
+        assert_eq!(s, rdr.read_utf16_string_bom::<BigEndian>(bom).unwrap());
+        assert_eq!("", rdr.read_utf16_string_bom::<BigEndian>(0).unwrap());
+
+        let mut rdr = Cursor::new(vec![0, b'a', 0xD8, 0x3D]);
+        let err = rdr.read_utf16_string_bom::<BigEndian>(2).unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        // A lone low surrogate.
+        let mut rdr = Cursor::new(vec![0x00, 0xDE, b'a', 0]);
+        let err = rdr.read_utf16_string::<LittleEndian>(2).unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+
+        let mut rdr = Cursor::new(vec![0, b'a']);
+        let err = rdr.read_utf16_string::<BigEndian>(usize::MAX).unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+    }
+
+    #[test]
+    fn fixed_strs() {
+        use crate::{Padding, ReadBytesExt, WriteBytesExt};
+        use std::io::{Cursor, ErrorKind};
+
+        let mut wtr = vec![];
+        wtr.write_fixed_str("name", 100, Padding::Nul).unwrap();
+        wtr.write_fixed_str("exact", 5, Padding::Nul).unwrap();
+        wtr.write_fixed_str("h\u{E9}", 4, Padding::Space).unwrap();
+        let err = wtr.write_fixed_str("h\u{E9}", 2, Padding::Nul).unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        assert_eq!(109, wtr.len());
+
+        let mut rdr = Cursor::new(wtr);
+        assert_eq!("name", rdr.read_fixed_str(100, Padding::Nul).unwrap());
+        assert_eq!("exact", rdr.read_fixed_str(5, Padding::Nul).unwrap());
+        assert_eq!("h\u{E9}", rdr.read_fixed_str(4, Padding::Space).unwrap());
+        let err = rdr.read_fixed_str(1, Padding::Space).unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+
+        let mut rdr = Cursor::new(b"\xFF\xFE  ");
+        let err = rdr.read_fixed_str(4, Padding::Space).unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn pascal_strings() {
+        use crate::{ReadBytesExt, WriteBytesExt};
+        use std::io::{Cursor, ErrorKind};
+
+        let mut wtr = vec![];
+        wtr.write_pascal_string(b"").unwrap();
+        wtr.write_pascal_string(&[0xA5; 255]).unwrap();
+        let err = wtr.write_pascal_string(&[0; 256]).unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        assert_eq!(257, wtr.len());
+
+        let mut rdr = Cursor::new(wtr);
+        assert!(rdr.read_pascal_string().unwrap().is_empty());
+        assert_eq!(vec![0xA5; 255], rdr.read_pascal_string().unwrap());
+        let err = rdr.read_pascal_string().unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+
+        let mut rdr = Cursor::new(b"\x05abc");
+        let err = rdr.read_pascal_string().unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn boms() {
+        use crate::{
+            bom::strip_bom, BigEndian, Endianness, LittleEndian, ReadBytesExt,
+            WriteBytesExt,
+        };
+
+        let mut wtr = vec![];
+        wtr.write_bom_u16::<LittleEndian>().unwrap();
+        wtr.write_utf16_string::<LittleEndian>("hi").unwrap();
+        assert_eq!(
+            (Some(Endianness::Little), &b"h\0i\0"[..]),
+            strip_bom(&wtr)
+        );
+        let mut rdr = &wtr[..];
+        assert_eq!("hi", rdr.read_utf16_string_bom::<BigEndian>(3).unwrap());
+
+        let mut wtr = vec![];
+        wtr.write_bom_u8().unwrap();
+        wtr.extend_from_slice(b"hi");
+        assert_eq!((None, &b"hi"[..]), strip_bom(&wtr));
+    }
+
+    #[test]
+    fn chars() {
+        use crate::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+        use std::io::{Cursor, ErrorKind};
+
+        let chars = ['\0', 'a', '\u{D7FF}', '\u{E000}', char::MAX];
+        let mut wtr = vec![];
+        for &c in &chars {
+            wtr.write_char::<BigEndian>(c).unwrap();
+            wtr.write_char::<LittleEndian>(c).unwrap();
+        }
+        let mut rdr = Cursor::new(wtr);
+        for &c in &chars {
+            assert_eq!(c, rdr.read_char::<BigEndian>().unwrap());
+            assert_eq!(c, rdr.read_char::<LittleEndian>().unwrap());
+        }
+
+        for &n in &[0xD800u32, 0xDFFF, 0x11_0000, u32::MAX] {
+            let mut rdr = Cursor::new(n.to_be_bytes());
+            let err = rdr.read_char::<BigEndian>().unwrap_err();
+            assert_eq!(ErrorKind::InvalidData, err.kind());
+        }
+    }
+
+    #[test]
+    fn bools() {
+        use crate::{ReadBytesExt, WriteBytesExt};
+        use std::io::{Cursor, ErrorKind};
+
+        let mut wtr = vec![];
+        wtr.write_bool(true).unwrap();
+        wtr.write_bool(false).unwrap();
+        assert_eq!(wtr, [1, 0]);
+        let mut rdr = Cursor::new(wtr);
+        assert!(rdr.read_bool_strict().unwrap());
+        assert!(!rdr.read_bool_strict().unwrap());
+
+        let mut rdr = Cursor::new(vec![2, 2, 0x80]);
+        let err = rdr.read_bool_strict().unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert!(rdr.read_bool_lenient().unwrap());
+        assert!(rdr.read_bool_lenient().unwrap());
+        let err = rdr.read_bool_lenient().unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn read_vec_absurd_count() {
+        use crate::{BigEndian, ReadBytesExt};
+        use std::io::{Cursor, ErrorKind};
+
+        let mut rdr = Cursor::new(vec![0; 10]);
+        let err = rdr.read_vec::<u32, BigEndian>(usize::MAX).unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        let err = rdr.read_vec::<u32, BigEndian>(usize::MAX / 4).unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn error_into_io_error() {
+        use std::io;
+
+        let err = crate::Error::from(crate::ErrorKind::UnexpectedEof);
+        assert_eq!("unexpected end of input", err.to_string());
+        assert_eq!(io::ErrorKind::UnexpectedEof, io::Error::from(err).kind());
+        let err = crate::Error::from(crate::ErrorKind::BufferTooSmall {
+            needed: 4,
+            available: 2,
+        });
+        assert_eq!(io::ErrorKind::UnexpectedEof, io::Error::from(err).kind());
+        let err = crate::Error::from(crate::ErrorKind::OutOfRange);
+        assert_eq!(io::ErrorKind::InvalidData, io::Error::from(err).kind());
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derive_read_write() {
+        use std::io::Cursor;
+
+        use crate::{
+            BigEndian, LittleEndian, ReadBytes, WriteBytes, WriteBytesExt,
+        };
+
+        #[derive(Debug, Default, PartialEq, ReadBytes, WriteBytes)]
+        struct Inner {
+            #[byteorder(little)]
+            a: u16,
+            b: i8,
+        }
+
+        #[derive(Debug, PartialEq, ReadBytes, WriteBytes)]
+        struct Outer<T> {
+            head: u32,
+            inner: Inner,
+            #[byteorder(skip)]
+            cache: Vec<u8>,
+            tail: [T; 2],
+        }
+
+        #[derive(Debug, PartialEq, ReadBytes, WriteBytes)]
+        struct Empty;
+
+        let mut wtr = vec![];
+        wtr.write_u32::<BigEndian>(7).unwrap();
+        wtr.write_u16::<LittleEndian>(0x0102).unwrap();
+        wtr.write_i8(-1).unwrap();
+        wtr.write_f32::<BigEndian>(1.5).unwrap();
+        wtr.write_f32::<BigEndian>(-2.0).unwrap();
+
+        let mut rdr = Cursor::new(&wtr);
+        let outer = Outer::<f32>::read_from::<_, BigEndian>(&mut rdr).unwrap();
+        assert_eq!(
+            Outer {
+                head: 7,
+                inner: Inner { a: 0x0102, b: -1 },
+                cache: vec![],
+                tail: [1.5, -2.0],
+            },
+            outer,
+        );
+        assert_eq!(wtr.len() as u64, rdr.position());
+        assert_eq!(Empty, Empty::read_from::<_, BigEndian>(&mut rdr).unwrap());
+
+        let mut out = vec![];
+        Outer { cache: vec![1, 2, 3], ..outer }
+            .write_to::<_, BigEndian>(&mut out)
+            .unwrap();
+        Empty.write_to::<_, BigEndian>(&mut out).unwrap();
+        assert_eq!(wtr, out);
+
+        let err =
+            Inner::read_from::<_, BigEndian>(&mut &[1, 2][..]).unwrap_err();
+        assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn read_write_tuple() {
+        use std::io::Cursor;
+
+        use crate::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+        let mut wtr = vec![];
+        wtr.write_wire::<_, BigEndian>(&(7u8, -2i32, [1u16, 2], 0.25f64))
+            .unwrap();
+        assert_eq!(17, wtr.len());
+
+        let mut rdr = Cursor::new(&wtr);
+        let (kind, delta, pair, scale) =
+            rdr.read_wire::<(u8, i32, [u16; 2], f64), BigEndian>().unwrap();
+        assert_eq!((7, -2, [1, 2], 0.25), (kind, delta, pair, scale));
+
+        let mut rdr = Cursor::new(&wtr[..16]);
+        let err = rdr
+            .read_wire::<(u8, i32, [u16; 2], f64), BigEndian>()
+            .unwrap_err();
+        assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn read_enum() {
+        use std::{io::Cursor, num::NonZeroU8};
+
+        use crate::{BigEndian, LittleEndian, ReadBytesExt};
+
+        #[derive(Debug, PartialEq)]
+        enum Kind {
+            A,
+            B,
+        }
+
+        impl TryFrom<i32> for Kind {
+            type Error = i32;
+
+            fn try_from(n: i32) -> Result<Kind, i32> {
+                match n {
+                    -1 => Ok(Kind::A),
+                    0x0102 => Ok(Kind::B),
+                    n => Err(n),
+                }
+            }
+        }
+
+        let mut rdr = Cursor::new(vec![0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 1, 2]);
+        assert_eq!(Kind::A, rdr.read_enum::<i32, Kind, BigEndian>().unwrap());
+        assert_eq!(Kind::B, rdr.read_enum::<i32, Kind, BigEndian>().unwrap());
+        let err = rdr.read_enum::<i32, Kind, BigEndian>().unwrap_err();
+        assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+
+        let mut rdr = Cursor::new(vec![0, 0, 1, 2]);
+        let err = rdr.read_enum::<i32, Kind, LittleEndian>().unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+        assert_eq!("invalid enum value 33619968", err.to_string());
+
+        // Any TryFrom conversion works, not just enums.
+        let mut rdr = Cursor::new(vec![5, 0]);
+        let n = rdr.read_enum::<u8, NonZeroU8, BigEndian>().unwrap();
+        assert_eq!(5, n.get());
+        assert!(rdr.read_enum::<u8, NonZeroU8, BigEndian>().is_err());
+    }
+
+    #[test]
+    fn read_write_values() {
+        use std::io::Cursor;
+
+        use crate::{LittleEndian, Primitive, ReadBytesExt, WriteBytesExt};
+
+        fn roundtrip<T: Primitive>(values: &[T]) {
+            let mut wtr = vec![];
+            wtr.write_values::<T, LittleEndian>(values).unwrap();
+            assert_eq!(values.len() * T::SIZE, wtr.len());
+            let mut rdr = Cursor::new(&wtr);
+            let got =
+                rdr.read_values::<T, LittleEndian>(values.len()).unwrap();
+            assert_eq!(values, &got[..]);
+        }
+
+        roundtrip::<u8>(&(0..=255).collect::<Vec<u8>>());
+        roundtrip::<i8>(&[-128, 0, 127]);
+        roundtrip::<u32>(&(0..100_000).collect::<Vec<u32>>());
+        roundtrip::<i128>(&[i128::MIN, -1, 0, i128::MAX]);
+        roundtrip::<f64>(&[0.5, -1.25, f64::INFINITY]);
+        roundtrip::<u16>(&[]);
+
+        let mut rdr = Cursor::new(vec![1, 0, 2]);
+        let err = rdr.read_values::<u16, LittleEndian>(2).unwrap_err();
+        assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+
+        let mut rdr = Cursor::new(vec![0; 8]);
+        let err =
+            rdr.read_values::<u64, LittleEndian>(usize::MAX).unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidInput, err.kind());
+
+        let mut rdr = Cursor::new(vec![0; 8]);
+        let err =
+            rdr.read_values::<u64, LittleEndian>(usize::MAX / 8).unwrap_err();
+        assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn read_write_struct() {
+        use std::io::{self, Cursor};
+
+        use crate::{read_struct, write_struct, BigEndian, LittleEndian};
+
+        #[derive(Debug, Default, PartialEq)]
+        struct Record {
+            kind: u8,
+            len: u32,
+            offset: i64,
+            scale: f32,
+            pair: [u16; 2],
+        }
+
+        let value = Record {
+            kind: 3,
+            len: 0x0A0B0C,
+            offset: -2,
+            scale: 0.5,
+            pair: [1, 2],
+        };
+        let mut wtr = vec![];
+        write_struct!(&mut wtr, BigEndian, value => {
+            kind: u8,
+            len: u24le,
+            offset: i48,
+            scale: f32le,
+            pair: ([u16; 2]),
+        })
+        .unwrap();
+        assert_eq!(
+            wtr,
+            [
+                3, 0x0C, 0x0B, 0x0A, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE, 0, 0,
+                0, 0x3F, 0, 1, 0, 2,
+            ],
+        );
+
+        let mut rdr = Cursor::new(&wtr);
+        let got = read_struct!(
+            &mut rdr,
+            BigEndian,
+            Record {
+                kind: u8,
+                len: u24le,
+                offset: i48,
+                scale: f32le,
+                pair: ([u16; 2]),
+            }
+        )
+        .unwrap();
+        assert_eq!(value, got);
+
+        let mut got = Record::default();
+        let rdr = &mut Cursor::new(&wtr[4..]);
+        read_struct!(rdr, LittleEndian, got => { offset: i48be }).unwrap();
+        read_struct!(rdr, LittleEndian, got => { scale: f32 }).unwrap();
+        assert_eq!(-2, got.offset);
+        assert_eq!(0.5, got.scale);
+
+        let err =
+            read_struct!(rdr, BigEndian, got => { offset: i64 }).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn endianness_serde() {
+        use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+        use crate::Endianness;
+
+        assert_tokens(
+            &Endianness::Big,
+            &[Token::UnitVariant { name: "Endianness", variant: "big" }],
+        );
+        assert_tokens(
+            &Endianness::Little,
+            &[Token::UnitVariant { name: "Endianness", variant: "little" }],
+        );
+        assert_de_tokens_error::<Endianness>(
+            &[Token::UnitVariant { name: "Endianness", variant: "Big" }],
+            "unknown variant `Big`, expected `big` or `little`",
+        );
+    }
+
+    #[cfg(feature = "defmt")]
+    #[test]
+    fn defmt_format() {
+        fn assert_format<T: defmt::Format>() {}
+
+        assert_format::<crate::Error>();
+        assert_format::<crate::Endianness>();
+        assert_format::<crate::BigEndian>();
+        assert_format::<crate::LittleEndian>();
+        assert_format::<crate::ordered::StaticEndian<crate::BigEndian>>();
+        assert_format::<crate::fourcc::ParseFourCcError>();
+    }
 }
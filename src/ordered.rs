@@ -0,0 +1,707 @@
+/*!
+Readers and writers that carry their byte order with them.
+
+When all of the numbers in some data share one byte order, naming it on every
+call (as in `rdr.read_u32::<BigEndian>()`) is noisy, and it is easy to get one
+call wrong. [`ByteOrdered`] wraps a reader or a writer and fixes its byte order
+once, so that its methods need no type parameter.
+
+The byte order may be fixed statically, with [`ByteOrdered::big_endian`] and
+friends, or chosen at runtime by passing an [`Endianness`] to
+[`ByteOrdered::new`]. Both are handled by the same code, and the static case
+compiles down to the same code as calling the generic methods directly.
+
+# Examples
+
+Reading a header whose first byte declares the byte order of the rest:
+
+```rust
+use std::io::Cursor;
+
+use byteorder::{ordered::ByteOrdered, Endianness, ReadBytesExt};
+
+let mut rdr = Cursor::new(vec![b'B', 0, 1, 0, 0, 0, 2]);
+let endianness = match rdr.read_u8().unwrap() {
+    b'B' => Endianness::Big,
+    _ => Endianness::Little,
+};
+let mut rdr = ByteOrdered::new(rdr, endianness);
+assert_eq!(1, rdr.read_u16().unwrap());
+assert_eq!(2, rdr.read_u32().unwrap());
+```
+
+Writing the same data back out, in the same byte order:
+
+```rust
+use byteorder::{ordered::ByteOrdered, Endianness};
+
+fn write_header<W: std::io::Write>(
+    wtr: W,
+    endianness: Endianness,
+) -> std::io::Result<W> {
+    let mut wtr = ByteOrdered::new(wtr, endianness);
+    wtr.write_u8(match endianness {
+        Endianness::Big => b'B',
+        Endianness::Little => b'L',
+    })?;
+    wtr.write_u16(1)?;
+    wtr.write_u32(2)?;
+    Ok(wtr.into_inner())
+}
+
+let buf = write_header(vec![], Endianness::Big).unwrap();
+assert_eq!(buf, [b'B', 0, 1, 0, 0, 0, 2]);
+let buf = write_header(vec![], Endianness::Little).unwrap();
+assert_eq!(buf, [b'L', 1, 0, 2, 0, 0, 0]);
+```
+*/
+
+use std::{
+    io::{self, Result},
+    marker::PhantomData,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8,
+        NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8,
+    },
+};
+
+use crate::{
+    wire::{FromWire, ToWire},
+    BigEndian, Endianness, LittleEndian, PtrWidth, ReadBytesExt,
+    WriteBytesExt,
+};
+
+/// A byte order that is either fixed by a type or chosen at runtime.
+///
+/// This trait is implemented by [`StaticEndian`], for byte orders known at
+/// compile time, and by [`Endianness`], for byte orders only known at
+/// runtime. It is sealed, and can't be implemented outside of this crate.
+pub trait Endian: Copy + crate::private::Sealed {
+    /// Returns the byte order as a runtime value.
+    fn endianness(self) -> Endianness;
+}
+
+/// A byte order fixed at compile time by one of the [`ByteOrder`] types.
+///
+/// This is a zero sized value standing in for [`BigEndian`] or
+/// [`LittleEndian`], which have no values themselves.
+///
+/// [`ByteOrder`]: crate::ByteOrder
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct StaticEndian<B>(PhantomData<B>);
+
+impl<B> StaticEndian<B> {
+    /// Creates a new value for the byte order `B`.
+    pub const fn new() -> StaticEndian<B> {
+        StaticEndian(PhantomData)
+    }
+}
+
+impl crate::private::Sealed for StaticEndian<BigEndian> {}
+impl crate::private::Sealed for StaticEndian<LittleEndian> {}
+impl crate::private::Sealed for Endianness {}
+
+impl Endian for StaticEndian<BigEndian> {
+    #[inline]
+    fn endianness(self) -> Endianness {
+        Endianness::Big
+    }
+}
+
+impl Endian for StaticEndian<LittleEndian> {
+    #[inline]
+    fn endianness(self) -> Endianness {
+        Endianness::Little
+    }
+}
+
+impl Endian for Endianness {
+    #[inline]
+    fn endianness(self) -> Endianness {
+        self
+    }
+}
+
+/// Calls a generic method on `$inner` with the byte order type matching the
+/// runtime endianness `$endian`.
+macro_rules! by_endian {
+    ($endian:expr, $inner:expr, $method:ident($($arg:expr),*)) => {
+        match $endian.endianness() {
+            Endianness::Big => $inner.$method::<BigEndian>($($arg),*),
+            Endianness::Little => $inner.$method::<LittleEndian>($($arg),*),
+        }
+    };
+}
+
+/// A reader or writer with a fixed byte order.
+///
+/// `ByteOrdered` has a method for each method of [`ReadBytesExt`] and
+/// [`WriteBytesExt`] that reads or writes a single number or a slice of
+/// numbers in a byte order: the fixed size numbers and their `nonzero`
+/// variants, `uint`/`int` and their 128 bit and `try_` variants,
+/// `usize`/`isize`, `char`, the `_into` and `_from` slice methods, and
+/// `read_wire`/`write_wire`. These have the same
+/// name and behavior, except that the byte order is the one given when the
+/// `ByteOrdered` was created. Other methods that take a byte order, like
+/// `read_vec`, `iter_*`, frames and strings, are not forwarded; call them on
+/// [`get_mut`](ByteOrdered::get_mut) with an explicit byte order instead.
+///
+/// `ByteOrdered` also implements `io::Read`, `io::BufRead`,
+/// `io::Write` and `io::Seek` when the underlying reader or writer does, so
+/// it can be used wherever the underlying reader or writer could.
+///
+/// See the [module documentation](self) for an example.
+#[derive(Clone, Debug)]
+pub struct ByteOrdered<T, E> {
+    inner: T,
+    endian: E,
+}
+
+impl<T, E: Endian> ByteOrdered<T, E> {
+    /// Wraps `inner` with the byte order `endian`.
+    ///
+    /// `endian` is usually an [`Endianness`], for a byte order only known at
+    /// runtime.
+    pub fn new(inner: T, endian: E) -> ByteOrdered<T, E> {
+        ByteOrdered { inner, endian }
+    }
+
+    /// Returns the byte order of this wrapper.
+    pub fn endian(&self) -> E {
+        self.endian
+    }
+
+    /// Returns the byte order of this wrapper as a runtime value.
+    pub fn endianness(&self) -> Endianness {
+        self.endian.endianness()
+    }
+
+    /// Changes the byte order of this wrapper, keeping the underlying reader
+    /// or writer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use byteorder::{ordered::ByteOrdered, Endianness};
+    ///
+    /// let mut rdr = ByteOrdered::little_endian(Cursor::new(vec![1, 0, 0, 1]));
+    /// assert_eq!(1, rdr.read_u16().unwrap());
+    /// let mut rdr = rdr.into_endian(Endianness::Big);
+    /// assert_eq!(1, rdr.read_u16().unwrap());
+    /// ```
+    pub fn into_endian<E2: Endian>(self, endian: E2) -> ByteOrdered<T, E2> {
+        ByteOrdered { inner: self.inner, endian }
+    }
+
+    /// Returns a reference to the underlying reader or writer.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader or writer.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps this `ByteOrdered`, returning the underlying reader or writer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> ByteOrdered<T, StaticEndian<BigEndian>> {
+    /// Wraps `inner` with big-endian byte order.
+    pub fn big_endian(inner: T) -> ByteOrdered<T, StaticEndian<BigEndian>> {
+        ByteOrdered { inner, endian: StaticEndian::new() }
+    }
+}
+
+impl<T> ByteOrdered<T, StaticEndian<LittleEndian>> {
+    /// Wraps `inner` with little-endian byte order.
+    pub fn little_endian(
+        inner: T,
+    ) -> ByteOrdered<T, StaticEndian<LittleEndian>> {
+        ByteOrdered { inner, endian: StaticEndian::new() }
+    }
+}
+
+impl<T> ByteOrdered<T, StaticEndian<crate::NativeEndian>> {
+    /// Wraps `inner` with the native byte order of the current platform.
+    pub fn native_endian(
+        inner: T,
+    ) -> ByteOrdered<T, StaticEndian<crate::NativeEndian>> {
+        ByteOrdered { inner, endian: StaticEndian::new() }
+    }
+}
+
+macro_rules! read_methods {
+    ($($method:ident -> $ty:ty;)*) => {
+        $(
+            #[doc = concat!(
+                "See [`ReadBytesExt::", stringify!($method), "`].",
+            )]
+            #[inline]
+            pub fn $method(&mut self) -> Result<$ty> {
+                by_endian!(self.endian, self.inner, $method())
+            }
+        )*
+    };
+}
+
+macro_rules! read_into_methods {
+    ($($method:ident -> $ty:ty;)*) => {
+        $(
+            #[doc = concat!(
+                "See [`ReadBytesExt::", stringify!($method), "`].",
+            )]
+            #[inline]
+            pub fn $method(&mut self, dst: &mut [$ty]) -> Result<()> {
+                by_endian!(self.endian, self.inner, $method(dst))
+            }
+        )*
+    };
+}
+
+impl<R: io::Read, E: Endian> ByteOrdered<R, E> {
+    /// See [`ReadBytesExt::read_u8`].
+    #[inline]
+    pub fn read_u8(&mut self) -> Result<u8> {
+        self.inner.read_u8()
+    }
+
+    /// See [`ReadBytesExt::read_i8`].
+    #[inline]
+    pub fn read_i8(&mut self) -> Result<i8> {
+        self.inner.read_i8()
+    }
+
+    read_methods! {
+        read_u16 -> u16;
+        read_i16 -> i16;
+        read_u24 -> u32;
+        read_i24 -> i32;
+        read_u32 -> u32;
+        read_i32 -> i32;
+        read_u48 -> u64;
+        read_i48 -> i64;
+        read_u64 -> u64;
+        read_i64 -> i64;
+        read_u128 -> u128;
+        read_i128 -> i128;
+        read_f16 -> f32;
+        read_f32 -> f32;
+        read_f64 -> f64;
+        read_nonzero_u16 -> NonZeroU16;
+        read_nonzero_i16 -> NonZeroI16;
+        read_nonzero_u32 -> NonZeroU32;
+        read_nonzero_i32 -> NonZeroI32;
+        read_nonzero_u64 -> NonZeroU64;
+        read_nonzero_i64 -> NonZeroI64;
+        read_nonzero_u128 -> NonZeroU128;
+        read_nonzero_i128 -> NonZeroI128;
+    }
+
+    /// See [`ReadBytesExt::read_nonzero_u8`].
+    #[inline]
+    pub fn read_nonzero_u8(&mut self) -> Result<NonZeroU8> {
+        self.inner.read_nonzero_u8()
+    }
+
+    /// See [`ReadBytesExt::read_nonzero_i8`].
+    #[inline]
+    pub fn read_nonzero_i8(&mut self) -> Result<NonZeroI8> {
+        self.inner.read_nonzero_i8()
+    }
+
+    /// See [`ReadBytesExt::read_uint`].
+    #[inline]
+    pub fn read_uint(&mut self, nbytes: usize) -> Result<u64> {
+        by_endian!(self.endian, self.inner, read_uint(nbytes))
+    }
+
+    /// See [`ReadBytesExt::read_int`].
+    #[inline]
+    pub fn read_int(&mut self, nbytes: usize) -> Result<i64> {
+        by_endian!(self.endian, self.inner, read_int(nbytes))
+    }
+
+    /// See [`ReadBytesExt::read_uint128`].
+    #[inline]
+    pub fn read_uint128(&mut self, nbytes: usize) -> Result<u128> {
+        by_endian!(self.endian, self.inner, read_uint128(nbytes))
+    }
+
+    /// See [`ReadBytesExt::read_int128`].
+    #[inline]
+    pub fn read_int128(&mut self, nbytes: usize) -> Result<i128> {
+        by_endian!(self.endian, self.inner, read_int128(nbytes))
+    }
+
+    /// See [`ReadBytesExt::read_usize`].
+    #[inline]
+    pub fn read_usize(&mut self, width: PtrWidth) -> Result<usize> {
+        by_endian!(self.endian, self.inner, read_usize(width))
+    }
+
+    /// See [`ReadBytesExt::read_isize`].
+    #[inline]
+    pub fn read_isize(&mut self, width: PtrWidth) -> Result<isize> {
+        by_endian!(self.endian, self.inner, read_isize(width))
+    }
+
+    /// See [`ReadBytesExt::read_char`].
+    #[inline]
+    pub fn read_char(&mut self) -> Result<char> {
+        by_endian!(self.endian, self.inner, read_char())
+    }
+
+    /// See [`ReadBytesExt::read_wire`].
+    #[inline]
+    pub fn read_wire<T: FromWire>(&mut self) -> Result<T> {
+        match self.endian.endianness() {
+            Endianness::Big => self.inner.read_wire::<T, BigEndian>(),
+            Endianness::Little => self.inner.read_wire::<T, LittleEndian>(),
+        }
+    }
+
+    read_into_methods! {
+        read_u16_into -> u16;
+        read_i16_into -> i16;
+        read_u32_into -> u32;
+        read_i32_into -> i32;
+        read_u64_into -> u64;
+        read_i64_into -> i64;
+        read_u128_into -> u128;
+        read_i128_into -> i128;
+        read_f32_into -> f32;
+        read_f64_into -> f64;
+    }
+}
+
+impl<R: io::Read, E> io::Read for ByteOrdered<R, E> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.inner.read_exact(buf)
+    }
+}
+
+impl<R: io::BufRead, E> io::BufRead for ByteOrdered<R, E> {
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+macro_rules! write_methods {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            #[doc = concat!(
+                "See [`WriteBytesExt::", stringify!($method), "`].",
+            )]
+            #[inline]
+            pub fn $method(&mut self, n: $ty) -> Result<()> {
+                by_endian!(self.endian, self.inner, $method(n))
+            }
+        )*
+    };
+}
+
+macro_rules! write_from_methods {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            #[doc = concat!(
+                "See [`WriteBytesExt::", stringify!($method), "`].",
+            )]
+            #[inline]
+            pub fn $method(&mut self, src: &[$ty]) -> Result<()> {
+                by_endian!(self.endian, self.inner, $method(src))
+            }
+        )*
+    };
+}
+
+impl<W: io::Write, E: Endian> ByteOrdered<W, E> {
+    /// See [`WriteBytesExt::write_u8`].
+    #[inline]
+    pub fn write_u8(&mut self, n: u8) -> Result<()> {
+        self.inner.write_u8(n)
+    }
+
+    /// See [`WriteBytesExt::write_i8`].
+    #[inline]
+    pub fn write_i8(&mut self, n: i8) -> Result<()> {
+        self.inner.write_i8(n)
+    }
+
+    write_methods! {
+        write_u16(u16);
+        write_i16(i16);
+        write_u24(u32);
+        write_i24(i32);
+        write_u32(u32);
+        write_i32(i32);
+        write_u48(u64);
+        write_i48(i64);
+        write_u64(u64);
+        write_i64(i64);
+        write_u128(u128);
+        write_i128(i128);
+        write_f16(f32);
+        write_f32(f32);
+        write_f64(f64);
+    }
+
+    /// See [`WriteBytesExt::write_uint`].
+    #[inline]
+    pub fn write_uint(&mut self, n: u64, nbytes: usize) -> Result<()> {
+        by_endian!(self.endian, self.inner, write_uint(n, nbytes))
+    }
+
+    /// See [`WriteBytesExt::write_int`].
+    #[inline]
+    pub fn write_int(&mut self, n: i64, nbytes: usize) -> Result<()> {
+        by_endian!(self.endian, self.inner, write_int(n, nbytes))
+    }
+
+    /// See [`WriteBytesExt::write_uint128`].
+    #[inline]
+    pub fn write_uint128(&mut self, n: u128, nbytes: usize) -> Result<()> {
+        by_endian!(self.endian, self.inner, write_uint128(n, nbytes))
+    }
+
+    /// See [`WriteBytesExt::write_int128`].
+    #[inline]
+    pub fn write_int128(&mut self, n: i128, nbytes: usize) -> Result<()> {
+        by_endian!(self.endian, self.inner, write_int128(n, nbytes))
+    }
+
+    write_from_methods! {
+        write_u16_from(u16);
+        write_i16_from(i16);
+        write_u32_from(u32);
+        write_i32_from(i32);
+        write_u64_from(u64);
+        write_i64_from(i64);
+        write_u128_from(u128);
+        write_i128_from(i128);
+        write_f32_from(f32);
+        write_f64_from(f64);
+    }
+
+    /// See [`WriteBytesExt::try_write_uint`].
+    #[inline]
+    pub fn try_write_uint(&mut self, n: u64, nbytes: usize) -> Result<()> {
+        by_endian!(self.endian, self.inner, try_write_uint(n, nbytes))
+    }
+
+    /// See [`WriteBytesExt::try_write_int`].
+    #[inline]
+    pub fn try_write_int(&mut self, n: i64, nbytes: usize) -> Result<()> {
+        by_endian!(self.endian, self.inner, try_write_int(n, nbytes))
+    }
+
+    /// See [`WriteBytesExt::write_usize`].
+    #[inline]
+    pub fn write_usize(&mut self, n: usize, width: PtrWidth) -> Result<()> {
+        by_endian!(self.endian, self.inner, write_usize(n, width))
+    }
+
+    /// See [`WriteBytesExt::write_isize`].
+    #[inline]
+    pub fn write_isize(&mut self, n: isize, width: PtrWidth) -> Result<()> {
+        by_endian!(self.endian, self.inner, write_isize(n, width))
+    }
+
+    /// See [`WriteBytesExt::write_char`].
+    #[inline]
+    pub fn write_char(&mut self, c: char) -> Result<()> {
+        by_endian!(self.endian, self.inner, write_char(c))
+    }
+
+    /// See [`WriteBytesExt::write_wire`].
+    #[inline]
+    pub fn write_wire<T: ToWire>(&mut self, value: &T) -> Result<()> {
+        match self.endian.endianness() {
+            Endianness::Big => self.inner.write_wire::<T, BigEndian>(value),
+            Endianness::Little => {
+                self.inner.write_wire::<T, LittleEndian>(value)
+            }
+        }
+    }
+}
+
+impl<W: io::Write, E> io::Write for ByteOrdered<W, E> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.inner.write(buf)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        self.inner.write_all(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<S: io::Seek, E> io::Seek for ByteOrdered<S, E> {
+    #[inline]
+    fn seek(&mut self, pos: io::SeekFrom) -> Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Cursor, Read, Seek, SeekFrom, Write};
+
+    use super::{ByteOrdered, Endian, StaticEndian};
+    use crate::{BigEndian, Endianness, LittleEndian, NativeEndian, PtrWidth};
+
+    #[test]
+    fn static_and_runtime_agree() {
+        let data = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        let mut be = ByteOrdered::big_endian(Cursor::new(data.clone()));
+        let mut rt =
+            ByteOrdered::new(Cursor::new(data.clone()), Endianness::Big);
+        assert_eq!(be.read_u16().unwrap(), rt.read_u16().unwrap());
+        assert_eq!(be.read_i24().unwrap(), rt.read_i24().unwrap());
+        assert_eq!(0x060708, be.read_uint(3).unwrap());
+        assert_eq!(0x060708, rt.read_uint(3).unwrap());
+
+        let mut le = ByteOrdered::little_endian(Cursor::new(data.clone()));
+        let mut rt = ByteOrdered::new(Cursor::new(data), Endianness::Little);
+        assert_eq!(0x0807060504030201, le.read_u64().unwrap());
+        assert_eq!(0x0807060504030201, rt.read_u64().unwrap());
+        assert!(le.read_u8().is_err());
+    }
+
+    #[test]
+    fn slices() {
+        let mut rdr = ByteOrdered::new(
+            Cursor::new(vec![0, 1, 0xFF, 0xFE, 0x3F, 0x80, 0, 0]),
+            Endianness::Big,
+        );
+        let mut ints = [0i16; 2];
+        rdr.read_i16_into(&mut ints).unwrap();
+        assert_eq!([1, -2], ints);
+        let mut floats = [0.0f32; 1];
+        rdr.read_f32_into(&mut floats).unwrap();
+        assert_eq!([1.0], floats);
+
+        let mut rdr =
+            ByteOrdered::little_endian(Cursor::new(vec![1, 0, 2, 0]));
+        assert_eq!([1, 2], rdr.read_wire::<[u16; 2]>().unwrap());
+    }
+
+    #[test]
+    fn forwards_io_traits() {
+        let mut rdr = ByteOrdered::native_endian(Cursor::new(vec![1, 2, 3]));
+        assert_eq!(&[1, 2, 3], rdr.fill_buf().unwrap());
+        rdr.consume(1);
+        let mut buf = [0; 1];
+        rdr.read_exact(&mut buf).unwrap();
+        assert_eq!([2], buf);
+        rdr.seek(SeekFrom::Start(0)).unwrap();
+        assert_eq!(1, rdr.read_i8().unwrap());
+        assert_eq!(1, rdr.into_inner().position());
+    }
+
+    #[test]
+    fn endianness() {
+        assert_eq!(
+            Endianness::Big,
+            StaticEndian::<BigEndian>::new().endianness()
+        );
+        assert_eq!(
+            Endianness::Little,
+            StaticEndian::<LittleEndian>::new().endianness()
+        );
+        let native = if cfg!(target_endian = "big") {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        };
+        assert_eq!(native, StaticEndian::<NativeEndian>::new().endianness());
+
+        let rdr = ByteOrdered::new(Cursor::new(vec![0]), Endianness::Little);
+        assert_eq!(Endianness::Little, rdr.endian());
+        let rdr = rdr.into_endian(StaticEndian::<BigEndian>::new());
+        assert_eq!(Endianness::Big, rdr.endianness());
+    }
+
+    #[test]
+    fn writes() {
+        let mut be = ByteOrdered::big_endian(vec![]);
+        let mut rt = ByteOrdered::new(vec![], Endianness::Big);
+        be.write_u24(0x010203).unwrap();
+        rt.write_u24(0x010203).unwrap();
+        be.write_int(-2, 3).unwrap();
+        rt.write_int(-2, 3).unwrap();
+        be.write_i16_from(&[1, -1]).unwrap();
+        rt.write_i16_from(&[1, -1]).unwrap();
+        let be = be.into_inner();
+        assert_eq!(be, rt.into_inner());
+        assert_eq!([1, 2, 3, 0xFF, 0xFF, 0xFE, 0, 1, 0xFF, 0xFF], be[..]);
+
+        let mut le = ByteOrdered::little_endian(vec![]);
+        le.write_f32(1.0).unwrap();
+        le.write_wire(&[1u16, 2]).unwrap();
+        le.write_all(b"!").unwrap();
+        le.flush().unwrap();
+        assert_eq!([0, 0, 0x80, 0x3F, 1, 0, 2, 0, b'!'], le.get_ref()[..]);
+    }
+
+    #[test]
+    fn read_back_writes() {
+        let mut rw = ByteOrdered::new(Cursor::new(vec![]), Endianness::Little);
+        rw.write_u64(0xDEAD_BEEF).unwrap();
+        rw.write_f64_from(&[0.5, -0.5]).unwrap();
+        rw.seek(SeekFrom::Start(0)).unwrap();
+        assert_eq!(0xDEAD_BEEF, rw.read_u64().unwrap());
+        assert_eq!([0.5, -0.5], rw.read_wire::<[f64; 2]>().unwrap());
+    }
+
+    #[test]
+    fn widths_and_chars() {
+        let mut rw = ByteOrdered::new(Cursor::new(vec![]), Endianness::Big);
+        rw.write_usize(0x0102, PtrWidth::W32).unwrap();
+        rw.write_isize(-1, PtrWidth::W16).unwrap();
+        rw.try_write_uint(0x0304, 2).unwrap();
+        assert!(rw.try_write_int(128, 1).is_err());
+        rw.write_char('é').unwrap();
+        assert_eq!(
+            [0, 0, 1, 2, 0xFF, 0xFF, 3, 4, 0, 0, 0, 0xE9],
+            rw.get_ref().get_ref()[..]
+        );
+
+        rw.seek(SeekFrom::Start(0)).unwrap();
+        assert_eq!(0x0102, rw.read_usize(PtrWidth::W32).unwrap());
+        assert_eq!(-1, rw.read_isize(PtrWidth::W16).unwrap());
+        assert_eq!(0x0304, rw.read_u16().unwrap());
+        assert_eq!('é', rw.read_char().unwrap());
+
+        let mut rdr = ByteOrdered::new(
+            Cursor::new(vec![0, 0, 1, 0]),
+            Endianness::Little,
+        );
+        assert!(rdr.read_nonzero_u16().is_err());
+        assert_eq!(1, rdr.read_nonzero_i16().unwrap().get());
+    }
+}
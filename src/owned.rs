@@ -0,0 +1,567 @@
+/*!
+Reading and writing numbers with completion-based asynchronous I/O.
+
+Completion-based runtimes, like those built on `io_uring`, hand a buffer to
+the kernel and get it back when the operation completes. The buffer must be
+owned by the operation while it is in flight, so these runtimes can't read
+into a borrowed `&mut [u8]` like tokio's or `futures-io`'s `AsyncRead` does.
+Instead, their reads take a buffer by value and return it along with the
+result.
+
+The [`OwnedRead`] and [`OwnedWrite`] traits describe such readers and
+writers in terms of a `Vec<u8>`, and are small enough to implement for the
+types of any runtime. [`OwnedReadBytesExt`] and [`OwnedWriteBytesExt`] then
+add methods for numbers that follow the same convention: each takes a buffer
+and returns it with the result, so that one allocation can be reused for
+every number read or written.
+
+# Examples
+
+This implements `OwnedRead` for an in-memory reader. An adapter for a
+runtime would look the same, except that its `read_owned` would pass the
+buffer to the runtime's own read method, for example as
+`stream.read(buf.slice(start..start + len))` with `tokio-uring`.
+
+```rust
+use std::{cell::Cell, future::ready, io};
+
+use byteorder::{
+    owned::{OwnedFuture, OwnedRead, OwnedReadBytesExt},
+    BigEndian,
+};
+
+struct Memory {
+    data: Vec<u8>,
+    pos: Cell<usize>,
+}
+
+impl OwnedRead for Memory {
+    fn read_owned(
+        &self,
+        mut buf: Vec<u8>,
+        len: usize,
+    ) -> OwnedFuture<'_, (io::Result<usize>, Vec<u8>)> {
+        let rest = &self.data[self.pos.get()..];
+        let n = len.min(rest.len());
+        buf.extend_from_slice(&rest[..n]);
+        self.pos.set(self.pos.get() + n);
+        Box::pin(ready((Ok(n), buf)))
+    }
+}
+
+async fn read_header(rdr: &Memory) -> io::Result<(u16, u32)> {
+    let buf = Vec::with_capacity(8);
+    let (kind, buf) = rdr.read_u16::<BigEndian>(buf).await;
+    let (len, _) = rdr.read_u32::<BigEndian>(buf).await;
+    Ok((kind?, len?))
+}
+```
+
+# Cancellation
+
+Dropping one of these futures before it completes drops the buffer with it,
+and the bytes already read into the buffer or not yet written from it are
+lost, just like with the futures of the other async modules.
+*/
+
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io;
+
+use crate::ByteOrder;
+
+/// A boxed future returned by the methods of [`OwnedRead`] and
+/// [`OwnedWrite`].
+///
+/// The future doesn't need to be `Send`, since completion-based runtimes
+/// usually run every task on one thread.
+pub type OwnedFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// A reader that reads into buffers that it owns while a read is in flight.
+///
+/// This takes `&self` rather than `&mut self`, like the readers of
+/// completion-based runtimes, so that a number can be read in several
+/// operations by one future that borrows the reader.
+pub trait OwnedRead {
+    /// Reads at most `len` bytes, appends them to `buf`, and returns the
+    /// number of bytes read along with the buffer.
+    ///
+    /// Implementations must not read more than `len` bytes, and must return
+    /// the buffer whether or not the read succeeds. Reading `0` bytes when
+    /// `len` isn't zero indicates the end of the input.
+    fn read_owned(
+        &self,
+        buf: Vec<u8>,
+        len: usize,
+    ) -> OwnedFuture<'_, (io::Result<usize>, Vec<u8>)>;
+}
+
+/// A writer that writes from buffers that it owns while a write is in
+/// flight.
+///
+/// This takes `&self` rather than `&mut self`, for the same reason as
+/// [`OwnedRead`].
+pub trait OwnedWrite {
+    /// Writes some of the bytes of `buf` from `buf[pos..]`, and returns the
+    /// number of bytes written along with the buffer.
+    ///
+    /// Implementations must return the buffer whether or not the write
+    /// succeeds. Writing `0` bytes indicates that the writer can't accept
+    /// any more bytes.
+    fn write_owned(
+        &self,
+        buf: Vec<u8>,
+        pos: usize,
+    ) -> OwnedFuture<'_, (io::Result<usize>, Vec<u8>)>;
+}
+
+/// A future that reads a number into an owned buffer, returned by the
+/// methods of [`OwnedReadBytesExt`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadOwnedNum<'a, R: ?Sized, N> {
+    rdr: &'a R,
+    buf: Option<Vec<u8>>,
+    fut: Option<OwnedFuture<'a, (io::Result<usize>, Vec<u8>)>>,
+    len: usize,
+    decode: fn(&[u8]) -> N,
+}
+
+impl<'a, R: ?Sized, N> ReadOwnedNum<'a, R, N> {
+    fn new(
+        rdr: &'a R,
+        mut buf: Vec<u8>,
+        len: usize,
+        decode: fn(&[u8]) -> N,
+    ) -> Self {
+        buf.clear();
+        buf.reserve(len);
+        ReadOwnedNum { rdr, buf: Some(buf), fut: None, len, decode }
+    }
+}
+
+impl<'a, R: ?Sized, N> fmt::Debug for ReadOwnedNum<'a, R, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadOwnedNum")
+            .field("buf", &self.buf)
+            .field("in_flight", &self.fut.is_some())
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl<'a, R: OwnedRead + ?Sized, N> Future for ReadOwnedNum<'a, R, N> {
+    type Output = (io::Result<N>, Vec<u8>);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let me = self.get_mut();
+        loop {
+            if let Some(fut) = me.fut.as_mut() {
+                let (res, buf) = match fut.as_mut().poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(out) => out,
+                };
+                me.fut = None;
+                match res {
+                    Err(err) => return Poll::Ready((Err(err), buf)),
+                    Ok(0) => {
+                        let err = io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "failed to fill whole buffer",
+                        );
+                        return Poll::Ready((Err(err), buf));
+                    }
+                    Ok(_) => me.buf = Some(buf),
+                }
+            }
+            let buf = me.buf.take().expect("polled after completion");
+            if buf.len() >= me.len {
+                let n = (me.decode)(&buf[..me.len]);
+                return Poll::Ready((Ok(n), buf));
+            }
+            let remaining = me.len - buf.len();
+            me.fut = Some(me.rdr.read_owned(buf, remaining));
+        }
+    }
+}
+
+macro_rules! read_owned_methods {
+    ($($name:ident($ty:ty, $size:expr, $read:ident, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Reads ", $desc, " into `buf`, and returns it along with ",
+            "the buffer.\n\n",
+            "# Errors\n\n",
+            "The result is an error when the underlying reader fails, or ",
+            "when the input ends before the number is complete, in which ",
+            "case the error has kind `UnexpectedEof`.",
+        )]
+        #[inline]
+        fn $name<T: ByteOrder>(
+            &self,
+            buf: Vec<u8>,
+        ) -> ReadOwnedNum<'_, Self, $ty> {
+            ReadOwnedNum::new(self, buf, $size, T::$read)
+        }
+    )*};
+}
+
+/// Extends [`OwnedRead`] with methods for reading numbers.
+///
+/// Every method takes a buffer, which is cleared before the read, and
+/// returns it once the read completes, holding the bytes of the number.
+/// The buffer only allocates when its capacity is less than the size of the
+/// number.
+///
+/// This is implemented for every type that implements `OwnedRead`. See the
+/// [module documentation](self) for an example.
+pub trait OwnedReadBytesExt: OwnedRead {
+    /// Reads an unsigned 8 bit integer into `buf`, and returns it along with
+    /// the buffer.
+    ///
+    /// # Errors
+    ///
+    /// The result is an error when the underlying reader fails, or when the
+    /// input is empty, in which case the error has kind `UnexpectedEof`.
+    #[inline]
+    fn read_u8(&self, buf: Vec<u8>) -> ReadOwnedNum<'_, Self, u8> {
+        ReadOwnedNum::new(self, buf, 1, |buf| buf[0])
+    }
+
+    /// Reads a signed 8 bit integer into `buf`, and returns it along with
+    /// the buffer.
+    ///
+    /// # Errors
+    ///
+    /// The result is an error when the underlying reader fails, or when the
+    /// input is empty, in which case the error has kind `UnexpectedEof`.
+    #[inline]
+    fn read_i8(&self, buf: Vec<u8>) -> ReadOwnedNum<'_, Self, i8> {
+        ReadOwnedNum::new(self, buf, 1, |buf| buf[0] as i8)
+    }
+
+    read_owned_methods! {
+        read_u16(u16, 2, read_u16, "an unsigned 16 bit integer");
+        read_i16(i16, 2, read_i16, "a signed 16 bit integer");
+        read_u24(u32, 3, read_u24, "an unsigned 24 bit integer");
+        read_i24(i32, 3, read_i24, "a signed 24 bit integer");
+        read_u32(u32, 4, read_u32, "an unsigned 32 bit integer");
+        read_i32(i32, 4, read_i32, "a signed 32 bit integer");
+        read_u48(u64, 6, read_u48, "an unsigned 48 bit integer");
+        read_i48(i64, 6, read_i48, "a signed 48 bit integer");
+        read_u64(u64, 8, read_u64, "an unsigned 64 bit integer");
+        read_i64(i64, 8, read_i64, "a signed 64 bit integer");
+        read_u128(u128, 16, read_u128, "an unsigned 128 bit integer");
+        read_i128(i128, 16, read_i128, "a signed 128 bit integer");
+        read_f32(f32, 4, read_f32, "an IEEE754 single-precision float");
+        read_f64(f64, 8, read_f64, "an IEEE754 double-precision float");
+    }
+}
+
+impl<R: OwnedRead + ?Sized> OwnedReadBytesExt for R {}
+
+/// A future that writes a number from an owned buffer, returned by the
+/// methods of [`OwnedWriteBytesExt`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WriteOwnedNum<'a, W: ?Sized> {
+    wtr: &'a W,
+    buf: Option<Vec<u8>>,
+    fut: Option<OwnedFuture<'a, (io::Result<usize>, Vec<u8>)>>,
+    written: usize,
+}
+
+impl<'a, W: ?Sized> WriteOwnedNum<'a, W> {
+    fn new(
+        wtr: &'a W,
+        mut buf: Vec<u8>,
+        len: usize,
+        encode: impl FnOnce(&mut [u8]),
+    ) -> Self {
+        buf.clear();
+        buf.resize(len, 0);
+        encode(&mut buf);
+        WriteOwnedNum { wtr, buf: Some(buf), fut: None, written: 0 }
+    }
+}
+
+impl<'a, W: ?Sized> fmt::Debug for WriteOwnedNum<'a, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WriteOwnedNum")
+            .field("buf", &self.buf)
+            .field("in_flight", &self.fut.is_some())
+            .field("written", &self.written)
+            .finish()
+    }
+}
+
+impl<'a, W: OwnedWrite + ?Sized> Future for WriteOwnedNum<'a, W> {
+    type Output = (io::Result<()>, Vec<u8>);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let me = self.get_mut();
+        loop {
+            if let Some(fut) = me.fut.as_mut() {
+                let (res, buf) = match fut.as_mut().poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(out) => out,
+                };
+                me.fut = None;
+                match res {
+                    Err(err) => return Poll::Ready((Err(err), buf)),
+                    Ok(0) => {
+                        let err = io::Error::new(
+                            io::ErrorKind::WriteZero,
+                            "failed to write whole buffer",
+                        );
+                        return Poll::Ready((Err(err), buf));
+                    }
+                    Ok(n) => {
+                        me.written += n;
+                        me.buf = Some(buf);
+                    }
+                }
+            }
+            let buf = me.buf.take().expect("polled after completion");
+            if me.written >= buf.len() {
+                return Poll::Ready((Ok(()), buf));
+            }
+            me.fut = Some(me.wtr.write_owned(buf, me.written));
+        }
+    }
+}
+
+macro_rules! write_owned_methods {
+    ($($name:ident($ty:ty, $size:expr, $write:ident, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Writes ", $desc, " from `buf`, and returns the buffer once ",
+            "it is written.\n\n",
+            "# Errors\n\n",
+            "The result is an error when the underlying writer fails, or ",
+            "when it stops accepting bytes before the number is written, ",
+            "in which case the error has kind `WriteZero`.",
+        )]
+        #[inline]
+        fn $name<T: ByteOrder>(
+            &self,
+            buf: Vec<u8>,
+            n: $ty,
+        ) -> WriteOwnedNum<'_, Self> {
+            WriteOwnedNum::new(self, buf, $size, |buf| T::$write(buf, n))
+        }
+    )*};
+}
+
+/// Extends [`OwnedWrite`] with methods for writing numbers.
+///
+/// Every method takes a buffer, which is cleared and filled with the bytes
+/// of the number, and returns it once the write completes. The buffer only
+/// allocates when its capacity is less than the size of the number.
+///
+/// This is implemented for every type that implements `OwnedWrite`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::io;
+///
+/// use byteorder::{owned::{OwnedWrite, OwnedWriteBytesExt}, LittleEndian};
+///
+/// async fn write_point<W: OwnedWrite>(
+///     wtr: &W,
+///     buf: Vec<u8>,
+///     x: f32,
+///     y: f32,
+/// ) -> (io::Result<()>, Vec<u8>) {
+///     let (res, buf) = wtr.write_f32::<LittleEndian>(buf, x).await;
+///     if res.is_err() {
+///         return (res, buf);
+///     }
+///     wtr.write_f32::<LittleEndian>(buf, y).await
+/// }
+/// ```
+pub trait OwnedWriteBytesExt: OwnedWrite {
+    /// Writes an unsigned 8 bit integer from `buf`, and returns the buffer
+    /// once it is written.
+    ///
+    /// # Errors
+    ///
+    /// The result is an error when the underlying writer fails, or when it
+    /// doesn't accept the byte, in which case the error has kind
+    /// `WriteZero`.
+    #[inline]
+    fn write_u8(&self, buf: Vec<u8>, n: u8) -> WriteOwnedNum<'_, Self> {
+        WriteOwnedNum::new(self, buf, 1, |buf| buf[0] = n)
+    }
+
+    /// Writes a signed 8 bit integer from `buf`, and returns the buffer
+    /// once it is written.
+    ///
+    /// # Errors
+    ///
+    /// The result is an error when the underlying writer fails, or when it
+    /// doesn't accept the byte, in which case the error has kind
+    /// `WriteZero`.
+    #[inline]
+    fn write_i8(&self, buf: Vec<u8>, n: i8) -> WriteOwnedNum<'_, Self> {
+        WriteOwnedNum::new(self, buf, 1, |buf| buf[0] = n as u8)
+    }
+
+    write_owned_methods! {
+        write_u16(u16, 2, write_u16, "an unsigned 16 bit integer");
+        write_i16(i16, 2, write_i16, "a signed 16 bit integer");
+        write_u24(u32, 3, write_u24, "an unsigned 24 bit integer");
+        write_i24(i32, 3, write_i24, "a signed 24 bit integer");
+        write_u32(u32, 4, write_u32, "an unsigned 32 bit integer");
+        write_i32(i32, 4, write_i32, "a signed 32 bit integer");
+        write_u48(u64, 6, write_u48, "an unsigned 48 bit integer");
+        write_i48(i64, 6, write_i48, "a signed 48 bit integer");
+        write_u64(u64, 8, write_u64, "an unsigned 64 bit integer");
+        write_i64(i64, 8, write_i64, "a signed 64 bit integer");
+        write_u128(u128, 16, write_u128, "an unsigned 128 bit integer");
+        write_i128(i128, 16, write_i128, "a signed 128 bit integer");
+        write_f32(f32, 4, write_f32, "an IEEE754 single-precision float");
+        write_f64(f64, 8, write_f64, "an IEEE754 double-precision float");
+    }
+}
+
+impl<W: OwnedWrite + ?Sized> OwnedWriteBytesExt for W {}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        future::{ready, Future},
+        io,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use super::{
+        OwnedFuture, OwnedRead, OwnedReadBytesExt, OwnedWrite,
+        OwnedWriteBytesExt,
+    };
+    use crate::{async_methods::block_on, BigEndian, LittleEndian};
+
+    /// A future that returns `Pending` once before completing, like an I/O
+    /// operation that is submitted and completes later.
+    struct Later<T>(Option<T>, bool);
+
+    impl<T: Unpin> Future for Later<T> {
+        type Output = T;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+            if !self.1 {
+                self.1 = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Poll::Ready(self.0.take().unwrap())
+        }
+    }
+
+    /// An in-memory stream that moves at most `chunk` bytes per operation,
+    /// and accepts at most `max` bytes in total.
+    struct Stream {
+        data: RefCell<Vec<u8>>,
+        pos: Cell<usize>,
+        chunk: usize,
+        max: usize,
+    }
+
+    impl Stream {
+        fn new(chunk: usize) -> Stream {
+            Stream {
+                data: RefCell::new(vec![]),
+                pos: Cell::new(0),
+                chunk,
+                max: usize::MAX,
+            }
+        }
+    }
+
+    impl OwnedRead for Stream {
+        fn read_owned(
+            &self,
+            mut buf: Vec<u8>,
+            len: usize,
+        ) -> OwnedFuture<'_, (io::Result<usize>, Vec<u8>)> {
+            let data = self.data.borrow();
+            let rest = &data[self.pos.get()..];
+            let n = len.min(rest.len()).min(self.chunk);
+            buf.extend_from_slice(&rest[..n]);
+            self.pos.set(self.pos.get() + n);
+            Box::pin(Later(Some((Ok(n), buf)), false))
+        }
+    }
+
+    impl OwnedWrite for Stream {
+        fn write_owned(
+            &self,
+            buf: Vec<u8>,
+            pos: usize,
+        ) -> OwnedFuture<'_, (io::Result<usize>, Vec<u8>)> {
+            let mut data = self.data.borrow_mut();
+            let room = self.max - data.len();
+            let n = (buf.len() - pos).min(self.chunk).min(room);
+            data.extend_from_slice(&buf[pos..pos + n]);
+            Box::pin(Later(Some((Ok(n), buf)), false))
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let stream = Stream::new(3);
+        let buf = Vec::with_capacity(16);
+        let ptr = buf.as_ptr();
+        let (res, buf) = block_on(stream.write_i8(buf, -2));
+        res.unwrap();
+        let (res, buf) =
+            block_on(stream.write_u128::<BigEndian>(buf, u128::MAX - 1));
+        res.unwrap();
+        let (res, buf) = block_on(stream.write_f32::<LittleEndian>(buf, 1.5));
+        res.unwrap();
+        assert_eq!(21, stream.data.borrow().len());
+
+        let (n, buf) = block_on(stream.read_i8(buf));
+        assert_eq!(-2, n.unwrap());
+        let (n, buf) = block_on(stream.read_u128::<BigEndian>(buf));
+        assert_eq!(u128::MAX - 1, n.unwrap());
+        let (n, buf) = block_on(stream.read_f32::<LittleEndian>(buf));
+        assert_eq!(1.5, n.unwrap());
+        assert_eq!(&1.5f32.to_le_bytes(), &buf[..]);
+        // The buffer was big enough for every number, so it was reused.
+        assert_eq!(ptr, buf.as_ptr());
+
+        let (n, buf) = block_on(stream.read_u16::<BigEndian>(buf));
+        assert_eq!(io::ErrorKind::UnexpectedEof, n.unwrap_err().kind());
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let mut stream = Stream::new(1);
+        stream.max = 3;
+        let (res, buf) = block_on(stream.write_u32::<BigEndian>(vec![], 1));
+        assert_eq!(io::ErrorKind::WriteZero, res.unwrap_err().kind());
+        assert_eq!(&[0, 0, 0, 1], &buf[..]);
+
+        struct Broken;
+
+        impl OwnedRead for Broken {
+            fn read_owned(
+                &self,
+                buf: Vec<u8>,
+                _: usize,
+            ) -> OwnedFuture<'_, (io::Result<usize>, Vec<u8>)> {
+                let err = io::Error::new(io::ErrorKind::Other, "broken");
+                Box::pin(ready((Err(err), buf)))
+            }
+        }
+
+        let (n, buf) = block_on(Broken.read_u64::<BigEndian>(vec![9; 4]));
+        assert_eq!(io::ErrorKind::Other, n.unwrap_err().kind());
+        assert!(buf.is_empty());
+    }
+}
@@ -0,0 +1,547 @@
+/*!
+Checked patching of numbers in binary files.
+
+Patching tools for firmware and other binary images are driven by lists of
+edits, each saying "the value at this offset should be this, and is to be
+replaced by that". Writing the new values blindly corrupts files that don't
+match what the edit list was made for, like a different firmware version, or
+an image that was already patched. An [`EditList`] records such edits along
+with the type and byte order of each value, and only writes anything once
+every old value has been checked against the file.
+
+When some old values don't match, nothing is written, and the error describes
+every edit that failed, with the value that was found instead.
+
+# Examples
+
+```rust
+use std::io::Cursor;
+
+use byteorder::{patch::EditList, BigEndian, LittleEndian};
+
+let mut image = Cursor::new(vec![0x12, 0x34, 0, 0, 1, 0, 0, 0]);
+
+let mut edits = EditList::new();
+edits.edit_u16::<BigEndian>(0, 0x1234, 0x1235);
+edits.edit_u32::<LittleEndian>(4, 1, 2);
+edits.apply(&mut image).unwrap();
+assert_eq!(image.get_ref(), &[0x12, 0x35, 0, 0, 2, 0, 0, 0]);
+
+// Applying the same edits again fails, since the old values are gone.
+let err = edits.apply(&mut image).unwrap_err();
+assert_eq!(
+    "2 edits don't match: \
+     edit 0 at offset 0x0: expected u16 0x1234 (big-endian), \
+     found 0x1235, which is already the new value; \
+     edit 1 at offset 0x4: expected u32 0x00000001 (little-endian), \
+     found 0x00000002, which is already the new value",
+    err.to_string(),
+);
+```
+*/
+
+use std::{
+    fmt,
+    io::{self, Read, Seek, SeekFrom, Write},
+};
+
+use crate::{sparse::WriteBatch, ByteOrder, Endianness};
+
+/// A number of any of the primitive types, as read or written by an edit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    /// An unsigned 8 bit integer.
+    U8(u8),
+    /// A signed 8 bit integer.
+    I8(i8),
+    /// An unsigned 16 bit integer.
+    U16(u16),
+    /// A signed 16 bit integer.
+    I16(i16),
+    /// An unsigned 32 bit integer.
+    U32(u32),
+    /// A signed 32 bit integer.
+    I32(i32),
+    /// An unsigned 64 bit integer.
+    U64(u64),
+    /// A signed 64 bit integer.
+    I64(i64),
+    /// An unsigned 128 bit integer.
+    U128(u128),
+    /// A signed 128 bit integer.
+    I128(i128),
+    /// An IEEE754 single-precision floating point number.
+    F32(f32),
+    /// An IEEE754 double-precision floating point number.
+    F64(f64),
+}
+
+impl Value {
+    /// Returns the name of the type of this value, like `"u32"`.
+    pub fn type_name(&self) -> &'static str {
+        match *self {
+            Value::U8(_) => "u8",
+            Value::I8(_) => "i8",
+            Value::U16(_) => "u16",
+            Value::I16(_) => "i16",
+            Value::U32(_) => "u32",
+            Value::I32(_) => "i32",
+            Value::U64(_) => "u64",
+            Value::I64(_) => "i64",
+            Value::U128(_) => "u128",
+            Value::I128(_) => "i128",
+            Value::F32(_) => "f32",
+            Value::F64(_) => "f64",
+        }
+    }
+
+    /// Returns the size of this value, in bytes.
+    pub fn size(&self) -> usize {
+        match *self {
+            Value::U8(_) | Value::I8(_) => 1,
+            Value::U16(_) | Value::I16(_) => 2,
+            Value::U32(_) | Value::I32(_) | Value::F32(_) => 4,
+            Value::U64(_) | Value::I64(_) | Value::F64(_) => 8,
+            Value::U128(_) | Value::I128(_) => 16,
+        }
+    }
+
+    /// Encodes this value at the start of `buf` in the given byte order.
+    fn encode(&self, order: Endianness, buf: &mut [u8]) {
+        match *self {
+            Value::U8(n) => buf[0] = n,
+            Value::I8(n) => buf[0] = n as u8,
+            Value::U16(n) => order.write_u16(buf, n),
+            Value::I16(n) => order.write_i16(buf, n),
+            Value::U32(n) => order.write_u32(buf, n),
+            Value::I32(n) => order.write_i32(buf, n),
+            Value::U64(n) => order.write_u64(buf, n),
+            Value::I64(n) => order.write_i64(buf, n),
+            Value::U128(n) => order.write_u128(buf, n),
+            Value::I128(n) => order.write_i128(buf, n),
+            Value::F32(n) => order.write_f32(buf, n),
+            Value::F64(n) => order.write_f64(buf, n),
+        }
+    }
+
+    /// Decodes a value of the same type as this one from the start of `buf`.
+    fn decode_like(&self, order: Endianness, buf: &[u8]) -> Value {
+        match *self {
+            Value::U8(_) => Value::U8(buf[0]),
+            Value::I8(_) => Value::I8(buf[0] as i8),
+            Value::U16(_) => Value::U16(order.read_u16(buf)),
+            Value::I16(_) => Value::I16(order.read_i16(buf)),
+            Value::U32(_) => Value::U32(order.read_u32(buf)),
+            Value::I32(_) => Value::I32(order.read_i32(buf)),
+            Value::U64(_) => Value::U64(order.read_u64(buf)),
+            Value::I64(_) => Value::I64(order.read_i64(buf)),
+            Value::U128(_) => Value::U128(order.read_u128(buf)),
+            Value::I128(_) => Value::I128(order.read_i128(buf)),
+            Value::F32(_) => Value::F32(order.read_f32(buf)),
+            Value::F64(_) => Value::F64(order.read_f64(buf)),
+        }
+    }
+}
+
+/// Unsigned integers are shown in zero padded hex, since they are usually
+/// addresses, flags or magic numbers in a binary file, and the rest in
+/// decimal.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = 2 + 2 * self.size();
+        match *self {
+            Value::U8(n) => write!(f, "{:#0w$x}", n, w = width),
+            Value::U16(n) => write!(f, "{:#0w$x}", n, w = width),
+            Value::U32(n) => write!(f, "{:#0w$x}", n, w = width),
+            Value::U64(n) => write!(f, "{:#0w$x}", n, w = width),
+            Value::U128(n) => write!(f, "{:#0w$x}", n, w = width),
+            Value::I8(n) => write!(f, "{}", n),
+            Value::I16(n) => write!(f, "{}", n),
+            Value::I32(n) => write!(f, "{}", n),
+            Value::I64(n) => write!(f, "{}", n),
+            Value::I128(n) => write!(f, "{}", n),
+            Value::F32(n) => write!(f, "{:?}", n),
+            Value::F64(n) => write!(f, "{:?}", n),
+        }
+    }
+}
+
+/// A single edit in an [`EditList`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Edit {
+    /// The offset of the value in the file.
+    pub offset: u64,
+    /// The byte order of the value.
+    pub order: Endianness,
+    /// The value that must be in the file before the edit is applied.
+    pub old: Value,
+    /// The value to write.
+    pub new: Value,
+}
+
+/// A list of edits, each replacing a value of a known type at an offset.
+///
+/// Edits are recorded with [`EditList::push`] or the typed `edit_*` methods,
+/// and applied with [`EditList::apply`]. See the
+/// [module documentation](self) for an example.
+#[derive(Clone, Debug, Default)]
+pub struct EditList {
+    edits: Vec<Edit>,
+}
+
+macro_rules! edit_methods {
+    ($($name:ident($ty:ty, $variant:ident);)*) => {$(
+        #[doc = concat!(
+            "Records an edit replacing the `", stringify!($ty),
+            "` `old` at `offset` with `new`, both in byte order `E`.",
+        )]
+        pub fn $name<E: ByteOrder>(&mut self, offset: u64, old: $ty, new: $ty) {
+            self.push(Edit {
+                offset,
+                order: Endianness::of::<E>(),
+                old: Value::$variant(old),
+                new: Value::$variant(new),
+            });
+        }
+    )*};
+}
+
+impl EditList {
+    /// Creates an empty edit list.
+    pub fn new() -> EditList {
+        EditList::default()
+    }
+
+    /// Returns the number of edits in this list.
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    /// Returns true if this list has no edits.
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Returns the edits in this list, in the order they were recorded.
+    pub fn edits(&self) -> &[Edit] {
+        &self.edits
+    }
+
+    /// Records an edit.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the old and new values of the edit have different types,
+    /// or when the end of the edit overflows a `u64`.
+    pub fn push(&mut self, edit: Edit) {
+        assert_eq!(
+            edit.old.type_name(),
+            edit.new.type_name(),
+            "old and new values of an edit must have the same type",
+        );
+        edit.offset
+            .checked_add(edit.old.size() as u64)
+            .expect("end of edit overflows u64");
+        self.edits.push(edit);
+    }
+
+    /// Records an edit replacing the `u8` `old` at `offset` with `new`.
+    pub fn edit_u8(&mut self, offset: u64, old: u8, new: u8) {
+        self.edit_i8_or_u8(offset, Value::U8(old), Value::U8(new));
+    }
+
+    /// Records an edit replacing the `i8` `old` at `offset` with `new`.
+    pub fn edit_i8(&mut self, offset: u64, old: i8, new: i8) {
+        self.edit_i8_or_u8(offset, Value::I8(old), Value::I8(new));
+    }
+
+    fn edit_i8_or_u8(&mut self, offset: u64, old: Value, new: Value) {
+        // The byte order of a single byte doesn't matter.
+        self.push(Edit { offset, order: Endianness::Big, old, new });
+    }
+
+    edit_methods! {
+        edit_u16(u16, U16);
+        edit_i16(i16, I16);
+        edit_u32(u32, U32);
+        edit_i32(i32, I32);
+        edit_u64(u64, U64);
+        edit_i64(i64, I64);
+        edit_u128(u128, U128);
+        edit_i128(i128, I128);
+        edit_f32(f32, F32);
+        edit_f64(f64, F64);
+    }
+
+    /// Reads the value at the offset of every edit, and returns the edits
+    /// whose old value doesn't match.
+    ///
+    /// Values are compared by their encoded bytes, so a floating point old
+    /// value matches only a bit-identical one, and a NaN can be matched.
+    /// Nothing is written.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Seek::seek`] and
+    /// [`Read::read_exact`]. In particular, an edit past the end of `rdr`
+    /// fails with an error of kind [`ErrorKind::UnexpectedEof`].
+    ///
+    /// [`Seek::seek`]: https://doc.rust-lang.org/std/io/trait.Seek.html#tymethod.seek
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    /// [`ErrorKind::UnexpectedEof`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.UnexpectedEof
+    pub fn check<R: Read + Seek + ?Sized>(
+        &self,
+        rdr: &mut R,
+    ) -> io::Result<Vec<Mismatch>> {
+        let mut mismatches = vec![];
+        let (mut want, mut got) = ([0; 16], [0; 16]);
+        for (index, edit) in self.edits.iter().enumerate() {
+            let size = edit.old.size();
+            edit.old.encode(edit.order, &mut want);
+            rdr.seek(SeekFrom::Start(edit.offset))?;
+            rdr.read_exact(&mut got[..size])?;
+            if want[..size] != got[..size] {
+                mismatches.push(Mismatch {
+                    index,
+                    edit: *edit,
+                    found: edit.old.decode_like(edit.order, &got),
+                });
+            }
+        }
+        Ok(mismatches)
+    }
+
+    /// Checks every edit, and then writes the new values if all of them
+    /// match.
+    ///
+    /// The new values are written with a [`WriteBatch`], so adjacent edits
+    /// are merged into a single write. When edits overlap, the one recorded
+    /// last wins, but every old value is checked against the file as it
+    /// was before any edit.
+    ///
+    /// # Errors
+    ///
+    /// When the old value of any edit doesn't match, nothing is written, and
+    /// an error of kind [`ErrorKind::InvalidData`] is returned. Its inner
+    /// error is a [`MismatchError`] that lists every mismatch.
+    ///
+    /// Otherwise, this method returns the same errors as
+    /// [`EditList::check`] and [`WriteBatch::apply`].
+    ///
+    /// [`ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    ///
+    /// # Examples
+    ///
+    /// Getting at the mismatches:
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use byteorder::{patch::{EditList, MismatchError}, BigEndian};
+    ///
+    /// let mut image = Cursor::new(vec![0, 0, 0, 7]);
+    /// let mut edits = EditList::new();
+    /// edits.edit_i32::<BigEndian>(0, 5, 6);
+    /// let err = edits.apply(&mut image).unwrap_err();
+    /// let err = err.get_ref().unwrap().downcast_ref::<MismatchError>();
+    /// let mismatch = &err.unwrap().mismatches()[0];
+    /// assert_eq!(0, mismatch.offset());
+    /// assert_eq!("7", mismatch.found().to_string());
+    /// ```
+    pub fn apply<F: Read + Write + Seek + ?Sized>(
+        &self,
+        file: &mut F,
+    ) -> io::Result<()> {
+        let mismatches = self.check(file)?;
+        if !mismatches.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                MismatchError { mismatches },
+            ));
+        }
+        let mut batch = WriteBatch::new();
+        let mut buf = [0; 16];
+        for edit in &self.edits {
+            let size = edit.new.size();
+            edit.new.encode(edit.order, &mut buf);
+            batch.write_bytes_at(edit.offset, &buf[..size]);
+        }
+        batch.apply(file)?;
+        Ok(())
+    }
+}
+
+/// An edit whose old value doesn't match the file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mismatch {
+    index: usize,
+    edit: Edit,
+    found: Value,
+}
+
+impl Mismatch {
+    /// Returns the index of the edit in its [`EditList`].
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the offset of the edit.
+    pub fn offset(&self) -> u64 {
+        self.edit.offset
+    }
+
+    /// Returns the edit.
+    pub fn edit(&self) -> &Edit {
+        &self.edit
+    }
+
+    /// Returns the value that was found in the file instead of the edit's
+    /// old value.
+    pub fn found(&self) -> Value {
+        self.found
+    }
+
+    /// Returns true if the value found is the edit's new value, which
+    /// usually means that the file has already been patched.
+    pub fn is_already_applied(&self) -> bool {
+        let (mut a, mut b) = ([0; 16], [0; 16]);
+        self.found.encode(self.edit.order, &mut a);
+        self.edit.new.encode(self.edit.order, &mut b);
+        a == b
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "edit {} at offset {:#x}: expected {} {}",
+            self.index,
+            self.edit.offset,
+            self.edit.old.type_name(),
+            self.edit.old,
+        )?;
+        if self.edit.old.size() > 1 {
+            let order = match self.edit.order {
+                Endianness::Big => "big-endian",
+                Endianness::Little => "little-endian",
+            };
+            write!(f, " ({})", order)?;
+        }
+        write!(f, ", found {}", self.found)?;
+        if self.is_already_applied() {
+            write!(f, ", which is already the new value")?;
+        }
+        Ok(())
+    }
+}
+
+/// The error returned by [`EditList::apply`] when the old values of some
+/// edits don't match.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MismatchError {
+    mismatches: Vec<Mismatch>,
+}
+
+impl MismatchError {
+    /// Returns every edit that didn't match, in the order they were
+    /// recorded.
+    pub fn mismatches(&self) -> &[Mismatch] {
+        &self.mismatches
+    }
+}
+
+impl fmt::Display for MismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mismatches.len() {
+            1 => write!(f, "1 edit doesn't match: ")?,
+            n => write!(f, "{} edits don't match: ", n)?,
+        }
+        for (i, mismatch) in self.mismatches.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", mismatch)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MismatchError {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use super::{Edit, EditList, MismatchError, Value};
+    use crate::{BigEndian, Endianness, LittleEndian};
+
+    #[test]
+    fn nothing_written_on_mismatch() {
+        let original = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        let mut file = Cursor::new(original.clone());
+        let mut edits = EditList::new();
+        edits.edit_u8(0, 1, 0xFF);
+        edits.edit_i16::<LittleEndian>(2, 0x0403, -1);
+        edits.edit_u16::<BigEndian>(4, 0x0605, 0);
+        edits.edit_f32::<BigEndian>(4, 1.0, 2.0);
+        assert_eq!(4, edits.len());
+
+        let err = edits.apply(&mut file).unwrap_err();
+        assert_eq!(ErrorKind::InvalidData, err.kind());
+        assert_eq!(original, *file.get_ref());
+
+        let err = err.get_ref().unwrap();
+        let mismatches = err.downcast_ref::<MismatchError>().unwrap();
+        let mismatches = mismatches.mismatches();
+        assert_eq!(2, mismatches.len());
+        assert_eq!(2, mismatches[0].index());
+        assert_eq!(Value::U16(0x0506), mismatches[0].found());
+        assert!(!mismatches[0].is_already_applied());
+        assert_eq!(
+            "edit 3 at offset 0x4: expected f32 1.0 (big-endian), \
+             found 6.301941e-36",
+            mismatches[1].to_string(),
+        );
+    }
+
+    #[test]
+    fn apply_and_overlap() {
+        let mut file = Cursor::new(vec![0; 8]);
+        let mut edits = EditList::new();
+        edits.edit_u64::<BigEndian>(0, 0, u64::MAX);
+        edits.edit_i8(7, 0, -2);
+        edits.push(Edit {
+            offset: 0,
+            order: Endianness::Little,
+            old: Value::U16(0),
+            new: Value::U16(0x0102),
+        });
+        edits.apply(&mut file).unwrap();
+        assert_eq!(
+            vec![2, 1, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE],
+            file.into_inner()
+        );
+    }
+
+    #[test]
+    fn past_end() {
+        let mut file = Cursor::new(vec![0; 3]);
+        let mut edits = EditList::new();
+        edits.edit_u32::<BigEndian>(0, 0, 1);
+        let err = edits.apply(&mut file).unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        assert_eq!(vec![0; 3], file.into_inner());
+    }
+
+    #[test]
+    #[should_panic]
+    fn mixed_types() {
+        EditList::new().push(Edit {
+            offset: 0,
+            order: Endianness::Big,
+            old: Value::U16(0),
+            new: Value::I16(0),
+        });
+    }
+}
@@ -0,0 +1,537 @@
+/*!
+Decoding of pcap and pcapng headers.
+
+Packet captures are written in the byte order of the machine that captured
+them, and readers are expected to detect that order from a magic number at
+the start of the file. This module takes care of that detection and decodes
+the fixed size headers into plain structs. Everything after the detection is
+read in the detected [`Endianness`].
+
+For the classic pcap format, [`FileHeader`] decodes the global header,
+including whether timestamps are in microseconds or nanoseconds, and then
+decodes each [`RecordHeader`]. For pcapng, [`SectionHeader`] decodes the
+section header block that determines the byte order of a section, and
+[`BlockHeader`] decodes the type and length that start every block.
+
+# Examples
+
+Iterate over the records of a pcap file:
+
+```rust
+use std::io::{Cursor, Read};
+
+use byteorder::pcap::FileHeader;
+
+# fn example() -> std::io::Result<()> {
+let file = [
+    // Global header, little-endian with microsecond timestamps.
+    0xD4, 0xC3, 0xB2, 0xA1, 2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0xFF, 0xFF, 0, 0, 1, 0, 0, 0,
+    // A single record with 2 bytes of packet data.
+    10, 0, 0, 0, 0x20, 0xA1, 0x07, 0, 2, 0, 0, 0, 60, 0, 0, 0,
+    0xAB, 0xCD,
+];
+let mut rdr = Cursor::new(&file[..]);
+let header = FileHeader::read_from(&mut rdr)?;
+assert_eq!(1, header.linktype);
+
+let mut packets = vec![];
+while let Some(record) = header.read_record_header(&mut rdr)? {
+    let mut data = vec![0; record.incl_len as usize];
+    rdr.read_exact(&mut data)?;
+    packets.push(data);
+}
+assert_eq!(vec![vec![0xAB, 0xCD]], packets);
+# Ok(())
+# }
+# example().unwrap();
+```
+*/
+
+use std::{
+    io,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    time::SubsecUnit, BigEndian, ByteOrder, Endianness, Error, ErrorKind,
+};
+
+/// The magic number of a pcap file with microsecond timestamps.
+const MAGIC_MICROS: u32 = 0xA1B2_C3D4;
+/// The magic number of a pcap file with nanosecond timestamps.
+const MAGIC_NANOS: u32 = 0xA1B2_3C4D;
+/// The block type of a pcapng section header block. It reads the same in
+/// either byte order.
+const SECTION_HEADER_BLOCK: u32 = 0x0A0D_0D0A;
+/// The byte order magic of a pcapng section header block.
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+/// The global header at the start of a pcap file.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FileHeader {
+    /// The byte order of the file, as detected from the magic number.
+    pub endianness: Endianness,
+    /// The unit of the subsecond part of record timestamps, as detected
+    /// from the magic number.
+    pub precision: SubsecUnit,
+    /// The major version of the file format. This is usually `2`.
+    pub version_major: u16,
+    /// The minor version of the file format. This is usually `4`.
+    pub version_minor: u16,
+    /// The offset of local time from UTC, in seconds. This is usually `0`.
+    pub thiszone: i32,
+    /// The accuracy of timestamps. This is usually `0`.
+    pub sigfigs: u32,
+    /// The maximum number of bytes captured from each packet.
+    pub snaplen: u32,
+    /// The link-layer header type of the packets in this file.
+    pub linktype: u32,
+}
+
+impl FileHeader {
+    /// The size of the global header in bytes.
+    pub const SIZE: usize = 24;
+
+    /// Decodes a global header from the start of `buf`.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error when `buf` is shorter than
+    /// [`FileHeader::SIZE`], or when it does not start with one of the pcap
+    /// magic numbers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{pcap::FileHeader, time::SubsecUnit, Endianness};
+    ///
+    /// let buf = [
+    ///     0xA1, 0xB2, 0x3C, 0x4D, 0, 2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0,
+    ///     0, 0, 0xFF, 0xFF, 0, 0, 0, 1,
+    /// ];
+    /// let header = FileHeader::parse(&buf).unwrap();
+    /// assert_eq!(Endianness::Big, header.endianness);
+    /// assert_eq!(SubsecUnit::Nanos, header.precision);
+    /// assert_eq!(65535, header.snaplen);
+    /// ```
+    pub fn parse(buf: &[u8]) -> Result<FileHeader, Error> {
+        Error::check_len(FileHeader::SIZE, buf.len())?;
+        let (endianness, precision) = match BigEndian::read_u32(buf) {
+            MAGIC_MICROS => (Endianness::Big, SubsecUnit::Micros),
+            MAGIC_NANOS => (Endianness::Big, SubsecUnit::Nanos),
+            m if m.swap_bytes() == MAGIC_MICROS => {
+                (Endianness::Little, SubsecUnit::Micros)
+            }
+            m if m.swap_bytes() == MAGIC_NANOS => {
+                (Endianness::Little, SubsecUnit::Nanos)
+            }
+            _ => return Err(Error::new(ErrorKind::InvalidMagic)),
+        };
+        let e = endianness;
+        Ok(FileHeader {
+            endianness,
+            precision,
+            version_major: e.read_u16(&buf[4..]),
+            version_minor: e.read_u16(&buf[6..]),
+            thiszone: e.read_u32(&buf[8..]) as i32,
+            sigfigs: e.read_u32(&buf[12..]),
+            snaplen: e.read_u32(&buf[16..]),
+            linktype: e.read_u32(&buf[20..]),
+        })
+    }
+
+    /// Reads a global header from `rdr`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// header does not start with one of the pcap magic numbers, an error of
+    /// kind `InvalidData` is returned.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    pub fn read_from<R: io::Read + ?Sized>(
+        rdr: &mut R,
+    ) -> io::Result<FileHeader> {
+        let mut buf = [0; FileHeader::SIZE];
+        rdr.read_exact(&mut buf)?;
+        Ok(FileHeader::parse(&buf)?)
+    }
+
+    /// Decodes a record header from the start of `buf`, using the byte
+    /// order and timestamp precision of this file.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error when `buf` is shorter than
+    /// [`RecordHeader::SIZE`].
+    pub fn parse_record_header(
+        &self,
+        buf: &[u8],
+    ) -> Result<RecordHeader, Error> {
+        Error::check_len(RecordHeader::SIZE, buf.len())?;
+        let e = self.endianness;
+        Ok(RecordHeader {
+            ts_sec: e.read_u32(buf),
+            ts_frac: e.read_u32(&buf[4..]),
+            incl_len: e.read_u32(&buf[8..]),
+            orig_len: e.read_u32(&buf[12..]),
+            precision: self.precision,
+        })
+    }
+
+    /// Reads a record header from `rdr`, using the byte order and timestamp
+    /// precision of this file.
+    ///
+    /// If `rdr` is already at the end of its input, then `None` is returned.
+    /// This is how the end of a pcap file is detected.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read`]. If the input
+    /// ends part way through a record header, an error of kind
+    /// `UnexpectedEof` is returned.
+    ///
+    /// [`Read::read`]: https://doc.rust-lang.org/std/io/trait.Read.html#tymethod.read
+    pub fn read_record_header<R: io::Read + ?Sized>(
+        &self,
+        rdr: &mut R,
+    ) -> io::Result<Option<RecordHeader>> {
+        let mut buf = [0; RecordHeader::SIZE];
+        let mut nread = 0;
+        while nread < buf.len() {
+            match rdr.read(&mut buf[nread..]) {
+                Ok(0) if nread == 0 => return Ok(None),
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "incomplete pcap record header",
+                    ))
+                }
+                Ok(n) => nread += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(Some(self.parse_record_header(&buf)?))
+    }
+}
+
+/// The header that precedes each packet in a pcap file.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RecordHeader {
+    /// The seconds part of the capture time, since the Unix epoch.
+    pub ts_sec: u32,
+    /// The subsecond part of the capture time, in units of `precision`.
+    pub ts_frac: u32,
+    /// The number of bytes of packet data that follow this header.
+    pub incl_len: u32,
+    /// The length of the packet on the wire, which may be more than
+    /// `incl_len` if the packet was truncated.
+    pub orig_len: u32,
+    /// The unit of `ts_frac`, taken from the file header.
+    pub precision: SubsecUnit,
+}
+
+impl RecordHeader {
+    /// The size of a record header in bytes.
+    pub const SIZE: usize = 16;
+
+    /// Returns the capture time of this record.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// use byteorder::{pcap::RecordHeader, time::SubsecUnit};
+    ///
+    /// let record = RecordHeader {
+    ///     ts_sec: 10,
+    ///     ts_frac: 500,
+    ///     incl_len: 0,
+    ///     orig_len: 0,
+    ///     precision: SubsecUnit::Micros,
+    /// };
+    /// assert_eq!(UNIX_EPOCH + Duration::new(10, 500_000), record.timestamp());
+    /// ```
+    pub fn timestamp(&self) -> SystemTime {
+        let frac = u64::from(self.ts_frac);
+        let nanos = match self.precision {
+            SubsecUnit::Micros => frac * 1_000,
+            SubsecUnit::Nanos => frac,
+        };
+        UNIX_EPOCH
+            + Duration::from_secs(u64::from(self.ts_sec))
+            + Duration::from_nanos(nanos)
+    }
+}
+
+/// The fixed part of a pcapng section header block.
+///
+/// Every section of a pcapng file starts with this block, and the byte
+/// order magic inside it determines the byte order of the rest of the
+/// section.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SectionHeader {
+    /// The byte order of this section, as detected from the byte order
+    /// magic.
+    pub endianness: Endianness,
+    /// The total length of this block, including options and the trailing
+    /// length field.
+    pub block_length: u32,
+    /// The major version of the file format. This is usually `1`.
+    pub version_major: u16,
+    /// The minor version of the file format. This is usually `0`.
+    pub version_minor: u16,
+    /// The length of this section in bytes, excluding this block, or `None`
+    /// if it was not specified.
+    pub section_length: Option<u64>,
+}
+
+impl SectionHeader {
+    /// The size of the fixed part of a section header block in bytes.
+    pub const SIZE: usize = 24;
+
+    /// Decodes the fixed part of a section header block from the start of
+    /// `buf`.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error when `buf` is shorter than
+    /// [`SectionHeader::SIZE`], when it does not start with the section
+    /// header block type or when the byte order magic is not recognized.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{pcap::SectionHeader, Endianness};
+    ///
+    /// let buf = [
+    ///     0x0A, 0x0D, 0x0D, 0x0A, 28, 0, 0, 0, 0x4D, 0x3C, 0x2B, 0x1A,
+    ///     1, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    /// ];
+    /// let header = SectionHeader::parse(&buf).unwrap();
+    /// assert_eq!(Endianness::Little, header.endianness);
+    /// assert_eq!(28, header.block_length);
+    /// assert_eq!(None, header.section_length);
+    /// ```
+    pub fn parse(buf: &[u8]) -> Result<SectionHeader, Error> {
+        Error::check_len(SectionHeader::SIZE, buf.len())?;
+        if BigEndian::read_u32(buf) != SECTION_HEADER_BLOCK {
+            return Err(Error::new(ErrorKind::InvalidMagic));
+        }
+        let endianness = match BigEndian::read_u32(&buf[8..]) {
+            BYTE_ORDER_MAGIC => Endianness::Big,
+            m if m.swap_bytes() == BYTE_ORDER_MAGIC => Endianness::Little,
+            _ => return Err(Error::new(ErrorKind::InvalidMagic)),
+        };
+        let e = endianness;
+        let section_length = e.read_u64(&buf[16..]);
+        Ok(SectionHeader {
+            endianness,
+            block_length: e.read_u32(&buf[4..]),
+            version_major: e.read_u16(&buf[12..]),
+            version_minor: e.read_u16(&buf[14..]),
+            section_length: if section_length == u64::MAX {
+                None
+            } else {
+                Some(section_length)
+            },
+        })
+    }
+
+    /// Reads the fixed part of a section header block from `rdr`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// block is not a section header block, an error of kind `InvalidData`
+    /// is returned.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    pub fn read_from<R: io::Read + ?Sized>(
+        rdr: &mut R,
+    ) -> io::Result<SectionHeader> {
+        let mut buf = [0; SectionHeader::SIZE];
+        rdr.read_exact(&mut buf)?;
+        Ok(SectionHeader::parse(&buf)?)
+    }
+}
+
+/// The type and length that start every pcapng block.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BlockHeader {
+    /// The type of this block.
+    pub block_type: u32,
+    /// The total length of this block, including this header and the
+    /// trailing length field.
+    pub total_length: u32,
+}
+
+impl BlockHeader {
+    /// The size of a block header in bytes.
+    pub const SIZE: usize = 8;
+
+    /// Decodes a block header from the start of `buf`, in the byte order of
+    /// the enclosing section.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error when `buf` is shorter than
+    /// [`BlockHeader::SIZE`].
+    pub fn parse(
+        endianness: Endianness,
+        buf: &[u8],
+    ) -> Result<BlockHeader, Error> {
+        Error::check_len(BlockHeader::SIZE, buf.len())?;
+        Ok(BlockHeader {
+            block_type: endianness.read_u32(buf),
+            total_length: endianness.read_u32(&buf[4..]),
+        })
+    }
+
+    /// Reads a block header from `rdr`, in the byte order of the enclosing
+    /// section.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`].
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    pub fn read_from<R: io::Read + ?Sized>(
+        endianness: Endianness,
+        rdr: &mut R,
+    ) -> io::Result<BlockHeader> {
+        let mut buf = [0; BlockHeader::SIZE];
+        rdr.read_exact(&mut buf)?;
+        Ok(BlockHeader::parse(endianness, &buf)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Cursor,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use super::{BlockHeader, FileHeader, SectionHeader};
+    use crate::{
+        time::SubsecUnit, BigEndian, ByteOrder, Endianness, ErrorKind,
+        LittleEndian, WriteBytesExt,
+    };
+
+    fn file_header<E: ByteOrder>(magic: u32) -> Vec<u8> {
+        let mut buf = vec![];
+        buf.write_u32::<E>(magic).unwrap();
+        buf.write_u16::<E>(2).unwrap();
+        buf.write_u16::<E>(4).unwrap();
+        buf.write_i32::<E>(-3600).unwrap();
+        buf.write_u32::<E>(0).unwrap();
+        buf.write_u32::<E>(262_144).unwrap();
+        buf.write_u32::<E>(105).unwrap();
+        buf
+    }
+
+    #[test]
+    fn detects_order_and_precision() {
+        let cases = [
+            (
+                file_header::<BigEndian>(0xA1B2_C3D4),
+                Endianness::Big,
+                SubsecUnit::Micros,
+            ),
+            (
+                file_header::<BigEndian>(0xA1B2_3C4D),
+                Endianness::Big,
+                SubsecUnit::Nanos,
+            ),
+            (
+                file_header::<LittleEndian>(0xA1B2_C3D4),
+                Endianness::Little,
+                SubsecUnit::Micros,
+            ),
+            (
+                file_header::<LittleEndian>(0xA1B2_3C4D),
+                Endianness::Little,
+                SubsecUnit::Nanos,
+            ),
+        ];
+        for (buf, endianness, precision) in cases.iter() {
+            let header = FileHeader::parse(buf).unwrap();
+            assert_eq!(*endianness, header.endianness);
+            assert_eq!(*precision, header.precision);
+            assert_eq!((2, 4), (header.version_major, header.version_minor));
+            assert_eq!(-3600, header.thiszone);
+            assert_eq!(262_144, header.snaplen);
+            assert_eq!(105, header.linktype);
+        }
+    }
+
+    #[test]
+    fn bad_magic() {
+        let buf = file_header::<BigEndian>(0x1234_5678);
+        let err = FileHeader::parse(&buf).unwrap_err();
+        assert_eq!(&ErrorKind::InvalidMagic, err.kind());
+
+        let err = FileHeader::read_from(&mut &buf[..]).unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn records() {
+        let mut buf = file_header::<BigEndian>(0xA1B2_3C4D);
+        buf.write_u32::<BigEndian>(1_000).unwrap();
+        buf.write_u32::<BigEndian>(999_999_999).unwrap();
+        buf.write_u32::<BigEndian>(0).unwrap();
+        buf.write_u32::<BigEndian>(1514).unwrap();
+
+        let mut rdr = Cursor::new(&buf[..]);
+        let header = FileHeader::read_from(&mut rdr).unwrap();
+        let record = header.read_record_header(&mut rdr).unwrap().unwrap();
+        assert_eq!(0, record.incl_len);
+        assert_eq!(1514, record.orig_len);
+        assert_eq!(
+            UNIX_EPOCH + Duration::new(1_000, 999_999_999),
+            record.timestamp()
+        );
+        assert_eq!(None, header.read_record_header(&mut rdr).unwrap());
+
+        // A truncated record header is an error, not the end of the file.
+        let mut rdr = Cursor::new(&buf[FileHeader::SIZE..buf.len() - 1]);
+        let err = header.read_record_header(&mut rdr).unwrap_err();
+        assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn pcapng_section() {
+        let mut buf = vec![];
+        buf.write_u32::<BigEndian>(0x0A0D_0D0A).unwrap();
+        buf.write_u32::<BigEndian>(28).unwrap();
+        buf.write_u32::<BigEndian>(0x1A2B_3C4D).unwrap();
+        buf.write_u16::<BigEndian>(1).unwrap();
+        buf.write_u16::<BigEndian>(0).unwrap();
+        buf.write_u64::<BigEndian>(4096).unwrap();
+        buf.write_u32::<BigEndian>(28).unwrap();
+        buf.write_u32::<BigEndian>(1).unwrap();
+        buf.write_u32::<BigEndian>(20).unwrap();
+
+        let mut rdr = Cursor::new(&buf[..]);
+        let section = SectionHeader::read_from(&mut rdr).unwrap();
+        assert_eq!(Endianness::Big, section.endianness);
+        assert_eq!(28, section.block_length);
+        assert_eq!((1, 0), (section.version_major, section.version_minor));
+        assert_eq!(Some(4096), section.section_length);
+
+        rdr.set_position(u64::from(section.block_length));
+        let block =
+            BlockHeader::read_from(section.endianness, &mut rdr).unwrap();
+        assert_eq!(1, block.block_type);
+        assert_eq!(20, block.total_length);
+
+        buf[8] = 0;
+        let err = SectionHeader::parse(&buf).unwrap_err();
+        assert_eq!(&ErrorKind::InvalidMagic, err.kind());
+    }
+}
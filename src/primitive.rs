@@ -0,0 +1,216 @@
+use core::fmt::Debug;
+
+use crate::ByteOrder;
+
+/// The primitive number types, which every byte order can read and write.
+///
+/// This trait lets code be generic over the type of the numbers it reads or
+/// writes, like a reader for a column of a table whose element type is only
+/// chosen by the caller. It is implemented for `u8`, `i8`, the other
+/// integer types up to 128 bits, `f32` and `f64`.
+///
+/// This trait is sealed, and can't be implemented outside of this crate.
+///
+/// # Examples
+///
+/// Summing a column of numbers of any type:
+///
+/// ```rust
+/// use byteorder::{BigEndian, ByteOrder, LittleEndian, Primitive};
+///
+/// fn sum<T: Primitive + Into<f64>, E: ByteOrder>(column: &[u8]) -> f64 {
+///     column.chunks_exact(T::SIZE).map(|c| T::read::<E>(c).into()).sum()
+/// }
+///
+/// assert_eq!(3.0, sum::<u16, BigEndian>(&[0, 1, 0, 2]));
+/// assert_eq!(-1.0, sum::<i8, LittleEndian>(&[0xFF]));
+/// assert_eq!(2.5, sum::<f32, LittleEndian>(&[0, 0, 0x20, 0x40]));
+/// ```
+pub trait Primitive:
+    Copy + Debug + Default + PartialEq + PartialOrd + crate::private::Sealed
+{
+    /// The number of bytes that a number of this type occupies.
+    const SIZE: usize;
+
+    /// Reads a number of this type from the start of `buf` in byte order
+    /// `E`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < Self::SIZE`.
+    fn read<E: ByteOrder>(buf: &[u8]) -> Self;
+
+    /// Writes this number to the start of `buf` in byte order `E`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < Self::SIZE`.
+    fn write<E: ByteOrder>(self, buf: &mut [u8]);
+
+    /// Reads numbers of this type from `src` into `dst` in byte order `E`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `src.len() != Self::SIZE * dst.len()`.
+    fn read_slice<E: ByteOrder>(src: &[u8], dst: &mut [Self]);
+
+    /// Writes the numbers in `src` to `dst` in byte order `E`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `dst.len() != Self::SIZE * src.len()`.
+    fn write_slice<E: ByteOrder>(src: &[Self], dst: &mut [u8]);
+}
+
+macro_rules! impl_primitive {
+    ($($ty:ty: $read:ident, $write:ident, $read_into:ident, $write_into:ident;)*) => {$(
+        impl crate::private::Sealed for $ty {}
+
+        impl Primitive for $ty {
+            const SIZE: usize = core::mem::size_of::<$ty>();
+
+            #[inline]
+            fn read<E: ByteOrder>(buf: &[u8]) -> $ty {
+                E::$read(buf)
+            }
+
+            #[inline]
+            fn write<E: ByteOrder>(self, buf: &mut [u8]) {
+                E::$write(buf, self)
+            }
+
+            #[inline]
+            fn read_slice<E: ByteOrder>(src: &[u8], dst: &mut [$ty]) {
+                E::$read_into(src, dst)
+            }
+
+            #[inline]
+            fn write_slice<E: ByteOrder>(src: &[$ty], dst: &mut [u8]) {
+                E::$write_into(src, dst)
+            }
+        }
+    )*};
+}
+
+impl_primitive! {
+    u16: read_u16, write_u16, read_u16_into, write_u16_into;
+    i16: read_i16, write_i16, read_i16_into, write_i16_into;
+    u32: read_u32, write_u32, read_u32_into, write_u32_into;
+    i32: read_i32, write_i32, read_i32_into, write_i32_into;
+    u64: read_u64, write_u64, read_u64_into, write_u64_into;
+    i64: read_i64, write_i64, read_i64_into, write_i64_into;
+    u128: read_u128, write_u128, read_u128_into, write_u128_into;
+    i128: read_i128, write_i128, read_i128_into, write_i128_into;
+    f32: read_f32, write_f32, read_f32_into, write_f32_into;
+    f64: read_f64, write_f64, read_f64_into, write_f64_into;
+}
+
+impl crate::private::Sealed for u8 {}
+
+impl Primitive for u8 {
+    const SIZE: usize = 1;
+
+    #[inline]
+    fn read<E: ByteOrder>(buf: &[u8]) -> u8 {
+        buf[0]
+    }
+
+    #[inline]
+    fn write<E: ByteOrder>(self, buf: &mut [u8]) {
+        buf[0] = self;
+    }
+
+    #[inline]
+    fn read_slice<E: ByteOrder>(src: &[u8], dst: &mut [u8]) {
+        dst.copy_from_slice(src);
+    }
+
+    #[inline]
+    fn write_slice<E: ByteOrder>(src: &[u8], dst: &mut [u8]) {
+        dst.copy_from_slice(src);
+    }
+}
+
+impl crate::private::Sealed for i8 {}
+
+impl Primitive for i8 {
+    const SIZE: usize = 1;
+
+    #[inline]
+    fn read<E: ByteOrder>(buf: &[u8]) -> i8 {
+        buf[0] as i8
+    }
+
+    #[inline]
+    fn write<E: ByteOrder>(self, buf: &mut [u8]) {
+        buf[0] = self as u8;
+    }
+
+    #[inline]
+    fn read_slice<E: ByteOrder>(src: &[u8], dst: &mut [i8]) {
+        assert_eq!(src.len(), dst.len());
+        for (d, &s) in dst.iter_mut().zip(src) {
+            *d = s as i8;
+        }
+    }
+
+    #[inline]
+    fn write_slice<E: ByteOrder>(src: &[i8], dst: &mut [u8]) {
+        E::write_i8_into(src, dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Primitive;
+    use crate::{BigEndian, LittleEndian};
+
+    fn roundtrip<T: Primitive>(values: &[T; 3]) {
+        let mut buf = [0; 48];
+        let buf = &mut buf[..3 * T::SIZE];
+        T::write_slice::<BigEndian>(values, buf);
+        let mut out = [T::default(); 3];
+        T::read_slice::<BigEndian>(buf, &mut out);
+        assert_eq!(values, &out);
+        for (i, &v) in values.iter().enumerate() {
+            assert_eq!(v, T::read::<BigEndian>(&buf[i * T::SIZE..]));
+        }
+
+        values[1].write::<LittleEndian>(buf);
+        assert_eq!(values[1], T::read::<LittleEndian>(buf));
+    }
+
+    #[test]
+    fn all_types() {
+        roundtrip::<u8>(&[0, 1, 255]);
+        roundtrip::<i8>(&[0, -1, 127]);
+        roundtrip::<u16>(&[1, 0x1234, u16::MAX]);
+        roundtrip::<i16>(&[-1, 0x1234, i16::MIN]);
+        roundtrip::<u32>(&[1, 0x1234_5678, u32::MAX]);
+        roundtrip::<i32>(&[-1, 0x1234_5678, i32::MIN]);
+        roundtrip::<u64>(&[1, 1 << 40, u64::MAX]);
+        roundtrip::<i64>(&[-1, 1 << 40, i64::MIN]);
+        roundtrip::<u128>(&[1, 1 << 100, u128::MAX]);
+        roundtrip::<i128>(&[-1, 1 << 100, i128::MIN]);
+        roundtrip::<f32>(&[1.5, -0.0, f32::MAX]);
+        roundtrip::<f64>(&[1.5, -0.0, f64::MIN_POSITIVE]);
+
+        assert_eq!(
+            [1, 2, 4, 8, 16, 1, 2, 4, 8, 16, 4, 8],
+            [
+                u8::SIZE,
+                u16::SIZE,
+                u32::SIZE,
+                u64::SIZE,
+                u128::SIZE,
+                i8::SIZE,
+                i16::SIZE,
+                i32::SIZE,
+                i64::SIZE,
+                i128::SIZE,
+                f32::SIZE,
+                f64::SIZE,
+            ]
+        );
+    }
+}
@@ -0,0 +1,390 @@
+/*!
+Records whose layout is only known at runtime.
+
+The rest of this crate reads values whose types, and usually byte orders, are
+fixed when the program is compiled. Tools like hex inspectors and generic
+protocol dissectors instead get the layout of a record as data, e.g., from a
+configuration file or from the user. A [`Layout`] is a list of
+[`FieldType`]s, each with its own byte order, which decodes a byte slice, or
+a reader with the `std` feature, into a list of [`Value`]s.
+
+# Examples
+
+```rust
+use byteorder::{
+    record::{FieldType, Layout, Value},
+    Endianness,
+};
+
+let layout = Layout::new(vec![
+    FieldType::Bytes(4),
+    FieldType::U16(Endianness::Big),
+    FieldType::I32(Endianness::Little),
+]);
+assert_eq!(10, layout.size());
+
+let values = layout.parse(b"RIFF\x00\x02\xFE\xFF\xFF\xFF").unwrap();
+assert_eq!(
+    vec![Value::Bytes(b"RIFF".to_vec()), Value::U16(2), Value::I32(-2)],
+    values,
+);
+```
+*/
+
+use alloc::vec::Vec;
+
+use crate::{Endianness, Error};
+
+/// The type of a single field of a [`Layout`].
+///
+/// Every number type carries the byte order it is stored in. The 24 and 48
+/// bit integers are decoded into the next larger integer type, like
+/// [`ByteOrder::read_u24`](crate::ByteOrder::read_u24) does.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FieldType {
+    /// An unsigned 8 bit integer.
+    U8,
+    /// A signed 8 bit integer.
+    I8,
+    /// An unsigned 16 bit integer.
+    U16(Endianness),
+    /// A signed 16 bit integer.
+    I16(Endianness),
+    /// An unsigned 24 bit integer, decoded into a [`Value::U32`].
+    U24(Endianness),
+    /// A signed 24 bit integer, decoded into a [`Value::I32`].
+    I24(Endianness),
+    /// An unsigned 32 bit integer.
+    U32(Endianness),
+    /// A signed 32 bit integer.
+    I32(Endianness),
+    /// An unsigned 48 bit integer, decoded into a [`Value::U64`].
+    U48(Endianness),
+    /// A signed 48 bit integer, decoded into a [`Value::I64`].
+    I48(Endianness),
+    /// An unsigned 64 bit integer.
+    U64(Endianness),
+    /// A signed 64 bit integer.
+    I64(Endianness),
+    /// An unsigned 128 bit integer.
+    U128(Endianness),
+    /// A signed 128 bit integer.
+    I128(Endianness),
+    /// An IEEE754 single-precision floating point number.
+    F32(Endianness),
+    /// An IEEE754 double-precision floating point number.
+    F64(Endianness),
+    /// The given number of bytes, copied as they are.
+    Bytes(usize),
+}
+
+impl FieldType {
+    /// Returns the number of bytes that a field of this type occupies.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{record::FieldType, Endianness};
+    ///
+    /// assert_eq!(3, FieldType::U24(Endianness::Big).size());
+    /// assert_eq!(7, FieldType::Bytes(7).size());
+    /// ```
+    pub fn size(self) -> usize {
+        match self {
+            FieldType::U8 | FieldType::I8 => 1,
+            FieldType::U16(_) | FieldType::I16(_) => 2,
+            FieldType::U24(_) | FieldType::I24(_) => 3,
+            FieldType::U32(_) | FieldType::I32(_) | FieldType::F32(_) => 4,
+            FieldType::U48(_) | FieldType::I48(_) => 6,
+            FieldType::U64(_) | FieldType::I64(_) | FieldType::F64(_) => 8,
+            FieldType::U128(_) | FieldType::I128(_) => 16,
+            FieldType::Bytes(len) => len,
+        }
+    }
+
+    /// Decodes a field of this type from `buf`, which must be exactly
+    /// `self.size()` bytes long.
+    fn decode(self, buf: &[u8]) -> Value {
+        debug_assert_eq!(self.size(), buf.len());
+        match self {
+            FieldType::U8 => Value::U8(buf[0]),
+            FieldType::I8 => Value::I8(buf[0] as i8),
+            FieldType::U16(e) => Value::U16(e.read_u16(buf)),
+            FieldType::I16(e) => Value::I16(e.read_i16(buf)),
+            FieldType::U24(e) => Value::U32(e.read_u24(buf)),
+            FieldType::I24(e) => Value::I32(e.read_i24(buf)),
+            FieldType::U32(e) => Value::U32(e.read_u32(buf)),
+            FieldType::I32(e) => Value::I32(e.read_i32(buf)),
+            FieldType::U48(e) => Value::U64(e.read_u48(buf)),
+            FieldType::I48(e) => Value::I64(e.read_i48(buf)),
+            FieldType::U64(e) => Value::U64(e.read_u64(buf)),
+            FieldType::I64(e) => Value::I64(e.read_i64(buf)),
+            FieldType::U128(e) => Value::U128(e.read_u128(buf)),
+            FieldType::I128(e) => Value::I128(e.read_i128(buf)),
+            FieldType::F32(e) => Value::F32(e.read_f32(buf)),
+            FieldType::F64(e) => Value::F64(e.read_f64(buf)),
+            FieldType::Bytes(_) => Value::Bytes(buf.to_vec()),
+        }
+    }
+}
+
+/// A decoded field of a [`Layout`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// An unsigned 8 bit integer.
+    U8(u8),
+    /// A signed 8 bit integer.
+    I8(i8),
+    /// An unsigned 16 bit integer.
+    U16(u16),
+    /// A signed 16 bit integer.
+    I16(i16),
+    /// An unsigned 32 bit integer, or a 24 bit one.
+    U32(u32),
+    /// A signed 32 bit integer, or a 24 bit one.
+    I32(i32),
+    /// An unsigned 64 bit integer, or a 48 bit one.
+    U64(u64),
+    /// A signed 64 bit integer, or a 48 bit one.
+    I64(i64),
+    /// An unsigned 128 bit integer.
+    U128(u128),
+    /// A signed 128 bit integer.
+    I128(i128),
+    /// An IEEE754 single-precision floating point number.
+    F32(f32),
+    /// An IEEE754 double-precision floating point number.
+    F64(f64),
+    /// Bytes that were copied as they are.
+    Bytes(Vec<u8>),
+}
+
+/// The layout of a record, as a list of fields stored back to back.
+///
+/// See the [module documentation](self) for an example.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Layout {
+    fields: Vec<FieldType>,
+}
+
+impl Layout {
+    /// Creates a layout with the given fields, in order.
+    pub fn new(fields: Vec<FieldType>) -> Layout {
+        Layout { fields }
+    }
+
+    /// Returns the fields of this layout.
+    pub fn fields(&self) -> &[FieldType] {
+        &self.fields
+    }
+
+    /// Returns the number of bytes that a record with this layout occupies.
+    ///
+    /// This saturates at `usize::MAX`, which no buffer can hold, rather than
+    /// overflowing.
+    pub fn size(&self) -> usize {
+        self.fields.iter().fold(0, |size, f| size.saturating_add(f.size()))
+    }
+
+    /// Decodes a record with this layout from the start of `buf`.
+    ///
+    /// Only the first [`size`](Layout::size) bytes of `buf` are read.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error of kind
+    /// [`ErrorKind::BufferTooSmall`](crate::ErrorKind::BufferTooSmall) when
+    /// `buf` is shorter than the record, in which case no field is decoded.
+    pub fn parse(&self, buf: &[u8]) -> Result<Vec<Value>, Error> {
+        Error::check_len(self.size(), buf.len())?;
+        let mut values = Vec::with_capacity(self.fields.len());
+        let mut pos = 0;
+        for field in &self.fields {
+            let end = pos + field.size();
+            values.push(field.decode(&buf[pos..end]));
+            pos = end;
+        }
+        Ok(values)
+    }
+
+    /// Reads and decodes a record with this layout from `rdr`.
+    ///
+    /// Exactly [`size`](Layout::size) bytes are read, one field at a time.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`].
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    ///
+    /// use byteorder::{
+    ///     record::{FieldType, Layout, Value},
+    ///     Endianness,
+    /// };
+    ///
+    /// let layout = Layout::new(vec![
+    ///     FieldType::U8,
+    ///     FieldType::F32(Endianness::Big),
+    /// ]);
+    /// let mut rdr = Cursor::new(vec![1, 0x3F, 0xC0, 0, 0, 2, 0x40, 0, 0, 0]);
+    /// let first = layout.read_from(&mut rdr).unwrap();
+    /// let second = layout.read_from(&mut rdr).unwrap();
+    /// assert_eq!(vec![Value::U8(1), Value::F32(1.5)], first);
+    /// assert_eq!(vec![Value::U8(2), Value::F32(2.0)], second);
+    /// assert!(layout.read_from(&mut rdr).is_err());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn read_from<R: std::io::Read + ?Sized>(
+        &self,
+        rdr: &mut R,
+    ) -> std::io::Result<Vec<Value>> {
+        use std::io::{self, Read};
+
+        let mut values = Vec::with_capacity(self.fields.len());
+        for &field in &self.fields {
+            if let FieldType::Bytes(len) = field {
+                // This doesn't allocate `len` bytes up front, since the
+                // layout may come from untrusted data.
+                let mut bytes = Vec::new();
+                rdr.take(len as u64).read_to_end(&mut bytes)?;
+                if bytes.len() < len {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "failed to fill whole buffer",
+                    ));
+                }
+                values.push(Value::Bytes(bytes));
+                continue;
+            }
+            let mut buf = [0; 16];
+            let buf = &mut buf[..field.size()];
+            rdr.read_exact(buf)?;
+            values.push(field.decode(buf));
+        }
+        Ok(values)
+    }
+}
+
+impl From<Vec<FieldType>> for Layout {
+    fn from(fields: Vec<FieldType>) -> Layout {
+        Layout::new(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::{FieldType, Layout, Value};
+    use crate::{
+        Endianness::{Big, Little},
+        ErrorKind,
+    };
+
+    #[test]
+    fn parse_all_types() {
+        let layout = Layout::new(vec![
+            FieldType::U8,
+            FieldType::I8,
+            FieldType::U16(Little),
+            FieldType::I16(Big),
+            FieldType::U24(Big),
+            FieldType::I24(Little),
+            FieldType::U32(Big),
+            FieldType::I32(Little),
+            FieldType::U48(Little),
+            FieldType::I48(Big),
+            FieldType::U64(Big),
+            FieldType::I64(Little),
+            FieldType::U128(Little),
+            FieldType::I128(Big),
+            FieldType::F32(Little),
+            FieldType::F64(Big),
+            FieldType::Bytes(2),
+        ]);
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&[7, 0xFF]);
+        buf.extend_from_slice(&[1, 2]);
+        buf.extend_from_slice(&[0xFF, 0xFE]);
+        buf.extend_from_slice(&[1, 2, 3]);
+        buf.extend_from_slice(&[0xFF, 0xFF, 0xFF]);
+        buf.extend_from_slice(&[0, 0, 1, 0]);
+        buf.extend_from_slice(&[0xFD, 0xFF, 0xFF, 0xFF]);
+        buf.extend_from_slice(&[1, 0, 0, 0, 0, 0]);
+        buf.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFC]);
+        buf.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 9]);
+        buf.extend_from_slice(&[
+            0xFB, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        ]);
+        buf.extend_from_slice(&[5; 1]);
+        buf.extend_from_slice(&[0; 15]);
+        buf.extend_from_slice(&[0xFF; 16]);
+        buf.extend_from_slice(&1.5f32.to_le_bytes());
+        buf.extend_from_slice(&(-0.25f64).to_be_bytes());
+        buf.extend_from_slice(b"ok");
+        assert_eq!(layout.size(), buf.len());
+
+        let values = layout.parse(&buf).unwrap();
+        assert_eq!(
+            vec![
+                Value::U8(7),
+                Value::I8(-1),
+                Value::U16(0x0201),
+                Value::I16(-2),
+                Value::U32(0x010203),
+                Value::I32(-1),
+                Value::U32(256),
+                Value::I32(-3),
+                Value::U64(1),
+                Value::I64(-4),
+                Value::U64(9),
+                Value::I64(-5),
+                Value::U128(5),
+                Value::I128(-1),
+                Value::F32(1.5),
+                Value::F64(-0.25),
+                Value::Bytes(b"ok".to_vec()),
+            ],
+            values,
+        );
+    }
+
+    #[test]
+    fn parse_too_small() {
+        let layout: Layout =
+            vec![FieldType::U32(Big), FieldType::Bytes(3)].into();
+        let err = layout.parse(&[0; 6]).unwrap_err();
+        assert_eq!(
+            &ErrorKind::BufferTooSmall { needed: 7, available: 6 },
+            err.kind(),
+        );
+        assert_eq!(Ok(vec![]), Layout::default().parse(&[]));
+
+        let huge = Layout::new(vec![FieldType::Bytes(usize::MAX); 2]);
+        assert_eq!(usize::MAX, huge.size());
+        assert!(huge.parse(&[0; 8]).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn read_from() {
+        let layout =
+            Layout::new(vec![FieldType::U16(Little), FieldType::Bytes(3)]);
+        let mut rdr = std::io::Cursor::new(b"\x01\x00abc\x02\x00de");
+        assert_eq!(
+            vec![Value::U16(1), Value::Bytes(b"abc".to_vec())],
+            layout.read_from(&mut rdr).unwrap(),
+        );
+        let err = layout.read_from(&mut rdr).unwrap_err();
+        assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+
+        let huge = Layout::new(vec![FieldType::Bytes(usize::MAX)]);
+        let err = huge.read_from(&mut &b"abc"[..]).unwrap_err();
+        assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+    }
+}
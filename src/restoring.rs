@@ -0,0 +1,243 @@
+/*!
+A reader that never consumes any bytes of a read that fails.
+
+The methods of [`ReadBytesExt`](crate::ReadBytesExt) are built on
+`Read::read_exact`, which leaves it unspecified how many bytes were consumed
+when it fails. So when a reader ends in the middle of a number, the bytes of
+that number are usually gone. That is fine when such an error aborts parsing,
+but not when the reader is a stream that will have more data later, like a
+socket, where the right thing to do is to wait and try again.
+
+[`Restoring`] guarantees that a failed `read_exact`, and therefore a failed
+read of any number, consumes nothing. Bytes that were taken from the
+underlying reader before the failure are kept, and returned first by the next
+read.
+
+# Examples
+
+```rust
+use std::io::{BufRead, ErrorKind, Read};
+
+use byteorder::{restoring::Restoring, BigEndian, ReadBytesExt};
+
+// A stream that has only received half of a number so far.
+let mut rdr = Restoring::new(&b"\x00\x01\x02"[..]);
+let err = rdr.read_u32::<BigEndian>().unwrap_err();
+assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+// Nothing was lost.
+assert_eq!(b"\x00\x01\x02", rdr.fill_buf().unwrap());
+```
+*/
+
+use std::io::{self, BufRead, Read, Result};
+
+/// A buffered reader whose `read_exact` consumes nothing when it fails.
+///
+/// See the [module documentation](self) for details.
+///
+/// Any [`BufRead`] can be wrapped, like a [`std::io::BufReader`] or a byte
+/// slice. When the bytes for a read are already in the underlying reader's
+/// buffer, they are copied straight from there. Otherwise, they are gathered
+/// in a small buffer of this reader's own, which is kept if the read fails.
+#[derive(Debug)]
+pub struct Restoring<R> {
+    rdr: R,
+    /// Bytes taken from `rdr` by a failed `read_exact`, which must be
+    /// returned before anything else.
+    pending: Vec<u8>,
+}
+
+impl<R: BufRead> Restoring<R> {
+    /// Creates a restoring reader that reads from `rdr`.
+    pub fn new(rdr: R) -> Restoring<R> {
+        Restoring { rdr, pending: vec![] }
+    }
+
+    /// Returns the bytes kept from failed reads, which haven't been returned
+    /// by a read yet.
+    ///
+    /// These bytes have already been taken from the underlying reader.
+    pub fn pending(&self) -> &[u8] {
+        &self.pending
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.rdr
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading from the underlying reader directly skips the
+    /// [`pending`](Restoring::pending) bytes.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.rdr
+    }
+
+    /// Unwraps this reader, returning the underlying reader along with the
+    /// [`pending`](Restoring::pending) bytes, which would otherwise be lost.
+    pub fn into_parts(self) -> (R, Vec<u8>) {
+        (self.rdr, self.pending)
+    }
+}
+
+impl<R: BufRead> Read for Restoring<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.pending.is_empty() {
+            return self.rdr.read(buf);
+        }
+        let n = self.pending.len().min(buf.len());
+        buf[..n].copy_from_slice(&self.pending[..n]);
+        self.pending.drain(..n);
+        Ok(n)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        if self.pending.is_empty() {
+            match self.rdr.fill_buf() {
+                Ok(avail) if avail.len() >= buf.len() => {
+                    buf.copy_from_slice(&avail[..buf.len()]);
+                    self.rdr.consume(buf.len());
+                    return Ok(());
+                }
+                // The loop below retries interrupted reads.
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        while self.pending.len() < buf.len() {
+            let avail = match self.rdr.fill_buf() {
+                Ok(avail) => avail,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {
+                    continue
+                }
+                Err(e) => return Err(e),
+            };
+            if avail.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "failed to fill whole buffer",
+                ));
+            }
+            let n = avail.len().min(buf.len() - self.pending.len());
+            self.pending.extend_from_slice(&avail[..n]);
+            self.rdr.consume(n);
+        }
+        buf.copy_from_slice(&self.pending[..buf.len()]);
+        self.pending.drain(..buf.len());
+        Ok(())
+    }
+}
+
+impl<R: BufRead> BufRead for Restoring<R> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.pending.is_empty() {
+            self.rdr.fill_buf()
+        } else {
+            Ok(&self.pending)
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if self.pending.is_empty() {
+            self.rdr.consume(amt);
+        } else {
+            self.pending.drain(..amt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, BufRead, BufReader, ErrorKind, Read};
+
+    use super::Restoring;
+    use crate::{LittleEndian, ReadBytesExt};
+
+    /// A reader that returns its chunks one read at a time, and then an
+    /// error of the given kind, as if more data may arrive later.
+    struct Chunks(Vec<&'static [u8]>, ErrorKind);
+
+    impl Read for Chunks {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(self.1.into());
+            }
+            let chunk = &mut self.0[0];
+            let n = chunk.len().min(buf.len());
+            buf[..n].copy_from_slice(&chunk[..n]);
+            *chunk = &chunk[n..];
+            if chunk.is_empty() {
+                self.0.remove(0);
+            }
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn eof_keeps_bytes() {
+        let mut rdr = Restoring::new(&[1, 0, 2][..]);
+        assert_eq!(1, rdr.read_u16::<LittleEndian>().unwrap());
+        let err = rdr.read_u16::<LittleEndian>().unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        assert_eq!(&[2], rdr.pending());
+        assert_eq!(2, rdr.read_u8().unwrap());
+        assert!(rdr.pending().is_empty());
+    }
+
+    #[test]
+    fn retry_after_more_data() {
+        let chunks = Chunks(vec![b"\x01\x02", b"\x03"], ErrorKind::WouldBlock);
+        let mut rdr = Restoring::new(BufReader::with_capacity(2, chunks));
+        let err = rdr.read_u32::<LittleEndian>().unwrap_err();
+        assert_eq!(ErrorKind::WouldBlock, err.kind());
+        assert_eq!(&[1, 2, 3], rdr.pending());
+
+        // More data arrives.
+        rdr.get_mut().get_mut().0.push(b"\x04\x05");
+        assert_eq!(0x0403_0201, rdr.read_u32::<LittleEndian>().unwrap());
+        assert_eq!(5, rdr.read_u8().unwrap());
+    }
+
+    #[test]
+    fn retry_interrupted() {
+        /// A reader that is interrupted once before every read.
+        struct Interrupting(bool, &'static [u8]);
+
+        impl Read for Interrupting {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.0 = !self.0;
+                if self.0 {
+                    return Err(ErrorKind::Interrupted.into());
+                }
+                self.1.read(buf)
+            }
+        }
+
+        let mut rdr = Restoring::new(BufReader::with_capacity(
+            2,
+            Interrupting(false, b"\x01\x02\x03\x04\x05\x06"),
+        ));
+        assert_eq!(0x0201, rdr.read_u16::<LittleEndian>().unwrap());
+        assert_eq!(0x0605_0403, rdr.read_u32::<LittleEndian>().unwrap());
+        assert!(rdr.pending().is_empty());
+    }
+
+    #[test]
+    fn buf_read() {
+        let mut rdr = Restoring::new(&b"abc\ndef"[..]);
+        assert!(rdr.read_u64::<LittleEndian>().is_err());
+        let mut line = String::new();
+        rdr.read_line(&mut line).unwrap();
+        assert_eq!("abc\n", line);
+        assert_eq!(b"def", rdr.fill_buf().unwrap());
+        rdr.consume(1);
+        let mut rest = vec![];
+        rdr.read_to_end(&mut rest).unwrap();
+        assert_eq!(b"ef", &rest[..]);
+
+        let (_, pending) = rdr.into_parts();
+        assert!(pending.is_empty());
+    }
+}
@@ -0,0 +1,374 @@
+/*!
+Checks that reading and writing numbers are exact inverses of each other.
+
+The functions in this module decode numbers from arbitrary bytes, encode them
+again and check that the original bytes come back, using every pair of read
+and write methods on [`ByteOrder`]. They also check that the fallible `try_`
+methods agree with the panicking ones, and fail with the right error when the
+input is too short. Each function returns `true` when every check passes.
+
+They are meant for fuzz targets and property tests in downstream crates,
+which can assert these invariants without copying this crate's own test
+macros. Every function accepts input of any length, so fuzzer output can be
+passed in directly.
+
+This module is only available when the `roundtrip` feature is enabled.
+
+# Examples
+
+A fuzz target that checks every method in both byte orders:
+
+```rust
+use byteorder::roundtrip;
+
+fn fuzz_target(data: &[u8]) {
+    assert!(roundtrip::roundtrip_all(data));
+}
+
+fuzz_target(b"\x00\x01\x02\x03\x04\x05\x06\x07\x7f\x80\xff");
+fuzz_target(b"");
+```
+*/
+
+use crate::{
+    BigEndian, ByteOrder, Error, ErrorKind, LittleEndian, NativeEndian,
+};
+
+macro_rules! fixed {
+    ($(
+        $name:ident($ty:ty, $size:expr, $desc:expr):
+        $read:ident, $write:ident, $try_read:ident, $try_write:ident;
+    )*) => {$(
+        #[doc = concat!(
+            "Checks that decoding ", $desc, " from the start of `bytes` ",
+            "and encoding it again gives back the same bytes.\n\n",
+            "When `bytes` is shorter than ", stringify!($size), " bytes, ",
+            "this instead checks that reading and writing fail with an ",
+            "error of kind [`ErrorKind::BufferTooSmall`].",
+        )]
+        pub fn $name<E: ByteOrder>(bytes: &[u8]) -> bool {
+            let mut out = [0; $size];
+            if bytes.len() < $size {
+                let err = Some(too_small($size, bytes.len()));
+                let out = &mut out[..bytes.len()];
+                return E::$try_read(bytes).err() == err
+                    && E::$try_write(out, <$ty>::default()).err() == err;
+            }
+            let n = E::$read(bytes);
+            E::$write(&mut out, n);
+            let mut tried = [0; $size];
+            match E::$try_read(bytes) {
+                Ok(m) if E::$try_write(&mut tried, m).is_ok() => {}
+                _ => return false,
+            }
+            out[..] == bytes[..$size] && tried == out
+        }
+    )*};
+}
+
+fixed! {
+    roundtrip_u16(u16, 2, "an unsigned 16 bit integer"):
+        read_u16, write_u16, try_read_u16, try_write_u16;
+    roundtrip_i16(i16, 2, "a signed 16 bit integer"):
+        read_i16, write_i16, try_read_i16, try_write_i16;
+    roundtrip_u24(u32, 3, "an unsigned 24 bit integer"):
+        read_u24, write_u24, try_read_u24, try_write_u24;
+    roundtrip_i24(i32, 3, "a signed 24 bit integer"):
+        read_i24, write_i24, try_read_i24, try_write_i24;
+    roundtrip_u32(u32, 4, "an unsigned 32 bit integer"):
+        read_u32, write_u32, try_read_u32, try_write_u32;
+    roundtrip_i32(i32, 4, "a signed 32 bit integer"):
+        read_i32, write_i32, try_read_i32, try_write_i32;
+    roundtrip_u48(u64, 6, "an unsigned 48 bit integer"):
+        read_u48, write_u48, try_read_u48, try_write_u48;
+    roundtrip_i48(i64, 6, "a signed 48 bit integer"):
+        read_i48, write_i48, try_read_i48, try_write_i48;
+    roundtrip_u64(u64, 8, "an unsigned 64 bit integer"):
+        read_u64, write_u64, try_read_u64, try_write_u64;
+    roundtrip_i64(i64, 8, "a signed 64 bit integer"):
+        read_i64, write_i64, try_read_i64, try_write_i64;
+    roundtrip_u128(u128, 16, "an unsigned 128 bit integer"):
+        read_u128, write_u128, try_read_u128, try_write_u128;
+    roundtrip_i128(i128, 16, "a signed 128 bit integer"):
+        read_i128, write_i128, try_read_i128, try_write_i128;
+    roundtrip_f32(f32, 4, "a single-precision float"):
+        read_f32, write_f32, try_read_f32, try_write_f32;
+    roundtrip_f64(f64, 8, "a double-precision float"):
+        read_f64, write_f64, try_read_f64, try_write_f64;
+}
+
+/// Checks that decoding an unsigned and a signed integer of every size from
+/// 1 to 8 bytes from the start of `bytes`, and encoding them again, gives back
+/// the same bytes.
+///
+/// Sizes larger than `bytes` are checked to fail with an error of kind
+/// [`ErrorKind::BufferTooSmall`] instead.
+pub fn roundtrip_uint<E: ByteOrder>(bytes: &[u8]) -> bool {
+    let mut out = [0; 8];
+    (1..=8).all(|nbytes| {
+        if bytes.len() < nbytes {
+            let err = Some(too_small(nbytes, bytes.len()));
+            let out = &mut out[..bytes.len()];
+            return E::try_read_uint(bytes, nbytes).err() == err
+                && E::try_read_int(bytes, nbytes).err() == err
+                && E::try_write_uint(out, 0, nbytes).err() == err
+                && E::try_write_int(out, 0, nbytes).err() == err;
+        }
+        let out = &mut out[..nbytes];
+        E::write_uint(out, E::read_uint(bytes, nbytes), nbytes);
+        let unsigned = out[..] == bytes[..nbytes];
+        E::write_int(out, E::read_int(bytes, nbytes), nbytes);
+        unsigned && out[..] == bytes[..nbytes]
+    })
+}
+
+/// Checks that decoding an unsigned and a signed integer of every size from
+/// 1 to 16 bytes from the start of `bytes`, and encoding them again, gives
+/// back the same bytes.
+///
+/// Sizes larger than `bytes` are checked to fail with an error of kind
+/// [`ErrorKind::BufferTooSmall`] instead.
+pub fn roundtrip_uint128<E: ByteOrder>(bytes: &[u8]) -> bool {
+    let mut out = [0; 16];
+    (1..=16).all(|nbytes| {
+        if bytes.len() < nbytes {
+            let err = Some(too_small(nbytes, bytes.len()));
+            let out = &mut out[..bytes.len()];
+            return E::try_read_uint128(bytes, nbytes).err() == err
+                && E::try_read_int128(bytes, nbytes).err() == err
+                && E::try_write_uint128(out, 0, nbytes).err() == err
+                && E::try_write_int128(out, 0, nbytes).err() == err;
+        }
+        let out = &mut out[..nbytes];
+        E::write_uint128(out, E::read_uint128(bytes, nbytes), nbytes);
+        let unsigned = out[..] == bytes[..nbytes];
+        E::write_int128(out, E::read_int128(bytes, nbytes), nbytes);
+        unsigned && out[..] == bytes[..nbytes]
+    })
+}
+
+/// Checks that decoding a half-precision float from the start of `bytes`
+/// and encoding it again gives back the same bytes.
+///
+/// Half-precision NaNs are not always encoded with the same bits they were
+/// decoded from, since converting them to `f32` and back sets their quiet
+/// bit. For NaNs, this only checks that the result is still a NaN with the
+/// same sign. Nothing is checked when `bytes` is shorter than 2 bytes.
+pub fn roundtrip_f16<E: ByteOrder>(bytes: &[u8]) -> bool {
+    if bytes.len() < 2 {
+        return true;
+    }
+    let mut out = [0; 2];
+    let x = E::read_f16(bytes);
+    E::write_f16(&mut out, x);
+    if x.is_nan() {
+        let bits = E::read_u16(&out);
+        return bits & 0x7C00 == 0x7C00
+            && bits & 0x3FF != 0
+            && bits & 0x8000 == E::read_u16(bytes) & 0x8000;
+    }
+    out[..] == bytes[..2]
+}
+
+/// Checks the slice methods, like [`ByteOrder::read_u16_into`] and
+/// [`ByteOrder::write_u16_into`], on as much of `bytes` as makes up whole
+/// numbers of each size.
+///
+/// Besides checking that the bytes come back unchanged, this checks that
+/// converting numbers decoded in the native byte order with the
+/// `from_slice_*` methods gives the same numbers as decoding them in the byte
+/// order `E`.
+pub fn roundtrip_slices<E: ByteOrder>(bytes: &[u8]) -> bool {
+    slices::<E, u16>(bytes)
+        && slices::<E, i16>(bytes)
+        && slices::<E, u32>(bytes)
+        && slices::<E, i32>(bytes)
+        && slices::<E, u64>(bytes)
+        && slices::<E, i64>(bytes)
+        && slices::<E, u128>(bytes)
+        && slices::<E, i128>(bytes)
+        && slices::<E, f32>(bytes)
+        && slices::<E, f64>(bytes)
+}
+
+/// Runs every check in this module on `bytes`, in both big-endian and
+/// little-endian byte order.
+///
+/// This also checks that decoding the same bytes in the two byte orders
+/// gives numbers that are each other with their bytes reversed.
+pub fn roundtrip_all(bytes: &[u8]) -> bool {
+    all::<BigEndian>(bytes) && all::<LittleEndian>(bytes) && swapped(bytes)
+}
+
+fn all<E: ByteOrder>(bytes: &[u8]) -> bool {
+    roundtrip_u16::<E>(bytes)
+        && roundtrip_i16::<E>(bytes)
+        && roundtrip_u24::<E>(bytes)
+        && roundtrip_i24::<E>(bytes)
+        && roundtrip_u32::<E>(bytes)
+        && roundtrip_i32::<E>(bytes)
+        && roundtrip_u48::<E>(bytes)
+        && roundtrip_i48::<E>(bytes)
+        && roundtrip_u64::<E>(bytes)
+        && roundtrip_i64::<E>(bytes)
+        && roundtrip_u128::<E>(bytes)
+        && roundtrip_i128::<E>(bytes)
+        && roundtrip_f32::<E>(bytes)
+        && roundtrip_f64::<E>(bytes)
+        && roundtrip_f16::<E>(bytes)
+        && roundtrip_uint::<E>(bytes)
+        && roundtrip_uint128::<E>(bytes)
+        && roundtrip_slices::<E>(bytes)
+}
+
+fn swapped(bytes: &[u8]) -> bool {
+    (bytes.len() < 2
+        || BigEndian::read_u16(bytes)
+            == LittleEndian::read_u16(bytes).swap_bytes())
+        && (bytes.len() < 4
+            || BigEndian::read_u32(bytes)
+                == LittleEndian::read_u32(bytes).swap_bytes())
+        && (bytes.len() < 8
+            || BigEndian::read_u64(bytes)
+                == LittleEndian::read_u64(bytes).swap_bytes())
+        && (bytes.len() < 16
+            || BigEndian::read_u128(bytes)
+                == LittleEndian::read_u128(bytes).swap_bytes())
+}
+
+/// The number of values decoded at a time by `slices`.
+const CHUNK: usize = 8;
+
+/// Checks the slice methods for one type of number on every whole chunk of
+/// `bytes`, and then on whatever whole numbers are left over.
+fn slices<E: ByteOrder, N: Number>(bytes: &[u8]) -> bool {
+    let size = core::mem::size_of::<N>();
+    let whole = &bytes[..bytes.len() - bytes.len() % size];
+    whole.chunks(CHUNK * size).all(|src| {
+        let len = src.len() / size;
+        let mut numbers = [N::default(); CHUNK];
+        let mut native = [N::default(); CHUNK];
+        let mut out = [0; CHUNK * 16];
+        let (numbers, native) = (&mut numbers[..len], &mut native[..len]);
+        let out = &mut out[..src.len()];
+
+        N::read_into::<E>(src, numbers);
+        N::write_into::<E>(numbers, out);
+        for (bytes, n) in src.chunks(size).zip(native.iter_mut()) {
+            *n = N::read::<NativeEndian>(bytes);
+        }
+        N::from_slice::<E>(native);
+        out[..] == src[..]
+            && numbers.iter().zip(native).all(|(a, b)| a.same(b))
+    })
+}
+
+/// The numbers that have slice methods on [`ByteOrder`], so that `slices`
+/// can be generic over them.
+trait Number: Copy + Default {
+    fn read<E: ByteOrder>(buf: &[u8]) -> Self;
+    fn read_into<E: ByteOrder>(src: &[u8], dst: &mut [Self]);
+    fn write_into<E: ByteOrder>(src: &[Self], dst: &mut [u8]);
+    fn from_slice<E: ByteOrder>(numbers: &mut [Self]);
+
+    /// Compares two numbers by their bits, so that NaNs compare equal to
+    /// themselves.
+    fn same(&self, other: &Self) -> bool;
+}
+
+macro_rules! impl_number {
+    ($(
+        $ty:ty: $read:ident, $read_into:ident, $write_into:ident,
+        $from_slice:ident;
+    )*) => {$(
+        impl Number for $ty {
+            fn read<E: ByteOrder>(buf: &[u8]) -> $ty {
+                E::$read(buf)
+            }
+
+            fn read_into<E: ByteOrder>(src: &[u8], dst: &mut [$ty]) {
+                E::$read_into(src, dst)
+            }
+
+            fn write_into<E: ByteOrder>(src: &[$ty], dst: &mut [u8]) {
+                E::$write_into(src, dst)
+            }
+
+            fn from_slice<E: ByteOrder>(numbers: &mut [$ty]) {
+                E::$from_slice(numbers)
+            }
+
+            fn same(&self, other: &$ty) -> bool {
+                self.to_ne_bytes() == other.to_ne_bytes()
+            }
+        }
+    )*};
+}
+
+impl_number! {
+    u16: read_u16, read_u16_into, write_u16_into, from_slice_u16;
+    i16: read_i16, read_i16_into, write_i16_into, from_slice_i16;
+    u32: read_u32, read_u32_into, write_u32_into, from_slice_u32;
+    i32: read_i32, read_i32_into, write_i32_into, from_slice_i32;
+    u64: read_u64, read_u64_into, write_u64_into, from_slice_u64;
+    i64: read_i64, read_i64_into, write_i64_into, from_slice_i64;
+    u128: read_u128, read_u128_into, write_u128_into, from_slice_u128;
+    i128: read_i128, read_i128_into, write_i128_into, from_slice_i128;
+    f32: read_f32, read_f32_into, write_f32_into, from_slice_f32;
+    f64: read_f64, read_f64_into, write_f64_into, from_slice_f64;
+}
+
+/// Returns the error that the `try_` methods return for a short buffer.
+fn too_small(needed: usize, available: usize) -> Error {
+    Error::new(ErrorKind::BufferTooSmall { needed, available })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{roundtrip_all, roundtrip_f16, roundtrip_uint};
+    use crate::{BigEndian, LittleEndian};
+
+    #[test]
+    fn every_length() {
+        let bytes: [u8; 40] = [
+            0x00, 0x01, 0x7f, 0x80, 0xff, 0xfe, 0x7f, 0xc0, 0x00, 0x01, 0x7f,
+            0xf8, 0x00, 0x00, 0x00, 0x01, 0x80, 0x00, 0x00, 0x00, 0xff, 0xff,
+            0xff, 0xff, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x7f,
+            0x80, 0x00, 0x01, 0xff, 0xf0, 0x00, 0x00,
+        ];
+        for len in 0..=bytes.len() {
+            for start in 0..len {
+                assert!(
+                    roundtrip_all(&bytes[start..len]),
+                    "{}..{}",
+                    start,
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn nans() {
+        // Quiet and signaling NaNs, with both signs.
+        for &bits in &[[0x7e, 0x00], [0x7c, 0x01], [0xfd, 0x55]] {
+            assert!(roundtrip_f16::<BigEndian>(&bits));
+        }
+        let snan32 = [0x7f, 0x80, 0x00, 0x01];
+        assert!(roundtrip_all(&snan32));
+    }
+
+    #[test]
+    fn short_input() {
+        assert!(roundtrip_uint::<LittleEndian>(&[]));
+        assert!(roundtrip_uint::<LittleEndian>(&[1, 2, 3]));
+        assert!(roundtrip_all(&[0x80]));
+    }
+
+    #[cfg(feature = "std")]
+    quickcheck::quickcheck! {
+        fn prop_roundtrip_all(bytes: Vec<u8>) -> bool {
+            roundtrip_all(&bytes)
+        }
+    }
+}
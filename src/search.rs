@@ -0,0 +1,260 @@
+/*!
+Searching byte slices for encoded numbers.
+
+Forensics and patching tools often need to find every place a particular
+number is stored in a large buffer, in a particular byte order. The functions
+in this module encode the number once and then search for the encoded bytes,
+which is much faster than decoding a number at every offset.
+
+By default, every matching offset is reported, including overlapping ones.
+Calling [`FindIter::aligned`] restricts the search to offsets that are a
+multiple of the size of the number, which is what you want when the buffer is
+known to be an array of such numbers.
+
+# Examples
+
+```rust
+use byteorder::{search, BigEndian, LittleEndian};
+
+let haystack = b"\x00\x01\x02\x03\x04\x03\x02\x01\x00";
+let found: Vec<usize> = search::find_u32::<BigEndian>(haystack, 0x01020304)
+    .collect();
+assert_eq!(vec![1], found);
+
+let found: Vec<usize> = search::find_u32::<LittleEndian>(haystack, 0x01020304)
+    .collect();
+assert_eq!(vec![4], found);
+```
+*/
+
+use crate::ByteOrder;
+
+/// An iterator over the offsets at which an encoded number occurs in a
+/// haystack.
+///
+/// `N` is the size of the encoded number in bytes. This iterator is created
+/// by the `find_*` functions in this module.
+#[derive(Clone, Debug)]
+pub struct FindIter<'h, const N: usize> {
+    haystack: &'h [u8],
+    needle: [u8; N],
+    pos: usize,
+    aligned: bool,
+}
+
+impl<'h, const N: usize> FindIter<'h, N> {
+    /// Creates an iterator over the occurrences of `needle` in `haystack`.
+    ///
+    /// This is useful for searching for numbers of a size that doesn't have
+    /// a dedicated function, like 24 bit integers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{search::FindIter, BigEndian, ByteOrder};
+    ///
+    /// let mut needle = [0; 3];
+    /// BigEndian::write_u24(&mut needle, 0xABCDEF);
+    /// let haystack = b"\xAB\xCD\xEF\xAB\xCD\xEF";
+    /// let found: Vec<usize> = FindIter::new(haystack, needle).collect();
+    /// assert_eq!(vec![0, 3], found);
+    /// ```
+    pub fn new(haystack: &'h [u8], needle: [u8; N]) -> FindIter<'h, N> {
+        FindIter { haystack, needle, pos: 0, aligned: false }
+    }
+
+    /// Only report matches at offsets that are a multiple of `N`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{search, LittleEndian};
+    ///
+    /// let haystack = [0, 0, 0, 0, 0, 0];
+    /// let all: Vec<usize> =
+    ///     search::find_u16::<LittleEndian>(&haystack, 0).collect();
+    /// assert_eq!(vec![0, 1, 2, 3, 4], all);
+    ///
+    /// let aligned: Vec<usize> =
+    ///     search::find_u16::<LittleEndian>(&haystack, 0).aligned().collect();
+    /// assert_eq!(vec![0, 2, 4], aligned);
+    /// ```
+    pub fn aligned(mut self) -> FindIter<'h, N> {
+        self.aligned = true;
+        // Round up, in case this is called part way through a search.
+        if N > 0 && self.pos % N != 0 {
+            self.pos += N - self.pos % N;
+        }
+        self
+    }
+}
+
+impl<'h, const N: usize> Iterator for FindIter<'h, N> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let h = self.haystack;
+        if N == 0 || h.len() < N {
+            return None;
+        }
+        // The last offset at which a match can start.
+        let last = h.len() - N;
+        if self.aligned {
+            while self.pos <= last {
+                let at = self.pos;
+                self.pos += N;
+                if h[at..at + N] == self.needle {
+                    return Some(at);
+                }
+            }
+            return None;
+        }
+        while self.pos <= last {
+            // Skip ahead to the next occurrence of the first byte, which is
+            // cheap, before comparing the whole needle.
+            let first = self.needle[0];
+            let at = match find_byte(first, &h[self.pos..=last]) {
+                None => break,
+                Some(i) => self.pos + i,
+            };
+            self.pos = at + 1;
+            if h[at..at + N] == self.needle {
+                return Some(at);
+            }
+        }
+        self.pos = h.len();
+        None
+    }
+}
+
+/// Returns the index of the first occurrence of `byte` in `haystack`.
+///
+/// Like `memchr`, this looks at a whole word at a time. XORing a word with
+/// `byte` repeated in every lane turns matching bytes into zero bytes, and
+/// whether a word has a zero byte can be checked with a few arithmetic
+/// operations. Only the word containing a match is scanned byte by byte.
+fn find_byte(byte: u8, haystack: &[u8]) -> Option<usize> {
+    const WORD: usize = core::mem::size_of::<usize>();
+    const LO: usize = usize::MAX / 255;
+    const HI: usize = LO << 7;
+
+    let repeated = LO * usize::from(byte);
+    let mut offset = 0;
+    for chunk in haystack.chunks_exact(WORD) {
+        let word = usize::from_ne_bytes(chunk.try_into().unwrap()) ^ repeated;
+        if word.wrapping_sub(LO) & !word & HI != 0 {
+            break;
+        }
+        offset += WORD;
+    }
+    haystack[offset..].iter().position(|&b| b == byte).map(|i| offset + i)
+}
+
+/// Returns an iterator over the offsets at which `value`, encoded as an
+/// unsigned 16 bit integer in byte order `E`, occurs in `haystack`.
+pub fn find_u16<E: ByteOrder>(haystack: &[u8], value: u16) -> FindIter<'_, 2> {
+    let mut needle = [0; 2];
+    E::write_u16(&mut needle, value);
+    FindIter::new(haystack, needle)
+}
+
+/// Returns an iterator over the offsets at which `value`, encoded as an
+/// unsigned 32 bit integer in byte order `E`, occurs in `haystack`.
+pub fn find_u32<E: ByteOrder>(haystack: &[u8], value: u32) -> FindIter<'_, 4> {
+    let mut needle = [0; 4];
+    E::write_u32(&mut needle, value);
+    FindIter::new(haystack, needle)
+}
+
+/// Returns an iterator over the offsets at which `value`, encoded as an
+/// unsigned 64 bit integer in byte order `E`, occurs in `haystack`.
+pub fn find_u64<E: ByteOrder>(haystack: &[u8], value: u64) -> FindIter<'_, 8> {
+    let mut needle = [0; 8];
+    E::write_u64(&mut needle, value);
+    FindIter::new(haystack, needle)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::{find_byte, find_u16, find_u32, find_u64, FindIter};
+    use crate::{BigEndian, ByteOrder, LittleEndian};
+
+    /// Returns every offset at which `needle` occurs, the slow way.
+    fn naive<const N: usize>(haystack: &[u8], needle: [u8; N]) -> Vec<usize> {
+        haystack
+            .windows(N)
+            .enumerate()
+            .filter(|&(_, w)| w == needle)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    #[test]
+    fn matches_naive_search() {
+        // A haystack with lots of near misses.
+        let haystack: Vec<u8> = (0..4096u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 29) as u8)
+            .collect();
+        for value in 0..64u32 {
+            let mut needle = [0; 4];
+            LittleEndian::write_u32(&mut needle, value);
+            let got: Vec<usize> =
+                find_u32::<LittleEndian>(&haystack, value).collect();
+            assert_eq!(naive(&haystack, needle), got, "{}", value);
+        }
+    }
+
+    #[test]
+    fn find_byte_matches_position() {
+        let haystack: Vec<u8> =
+            (0..67u8).map(|i| i.wrapping_mul(37)).collect();
+        for start in 0..9 {
+            let h = &haystack[start..];
+            for byte in 0..=255u8 {
+                let expected = h.iter().position(|&b| b == byte);
+                assert_eq!(expected, find_byte(byte, h), "{} {}", start, byte);
+            }
+        }
+        assert_eq!(None, find_byte(0, &[]));
+        assert_eq!(
+            Some(15),
+            find_byte(0x80, &[[0x7F; 15], [0x80; 15]].concat())
+        );
+    }
+
+    #[test]
+    fn overlapping_and_aligned() {
+        let haystack = [0xAB; 9];
+        let all: Vec<usize> =
+            find_u64::<BigEndian>(&haystack, 0xABAB_ABAB_ABAB_ABAB).collect();
+        assert_eq!(vec![0, 1], all);
+
+        let haystack = [9, 1, 0, 1, 0, 1, 0];
+        let all: Vec<usize> =
+            find_u16::<BigEndian>(&haystack, 0x0100).collect();
+        assert_eq!(vec![1, 3, 5], all);
+        let aligned: Vec<usize> =
+            find_u16::<BigEndian>(&haystack, 0x0100).aligned().collect();
+        assert!(aligned.is_empty());
+        let aligned: Vec<usize> =
+            find_u16::<BigEndian>(&haystack, 0x0001).aligned().collect();
+        assert_eq!(vec![2, 4], aligned);
+    }
+
+    #[test]
+    fn aligned_mid_search() {
+        let haystack = [1, 1, 1, 1, 1, 1];
+        let mut it = FindIter::new(&haystack, [1, 1]);
+        assert_eq!(Some(0), it.next());
+        let rest: Vec<usize> = it.aligned().collect();
+        assert_eq!(vec![2, 4], rest);
+    }
+
+    #[test]
+    fn short_haystacks() {
+        assert_eq!(None, find_u32::<BigEndian>(&[], 0).next());
+        assert_eq!(None, find_u32::<BigEndian>(&[0, 0, 0], 0).next());
+        assert_eq!(Some(0), find_u32::<BigEndian>(&[0, 0, 0, 0], 0).next());
+        assert_eq!(None, FindIter::new(&[1, 2, 3], []).next());
+    }
+}
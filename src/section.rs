@@ -0,0 +1,282 @@
+/*!
+Reading the sections of a container format independently of each other.
+
+Many container formats start with a table of `(offset, length)` pairs, each
+describing a section of the file that is parsed on its own. A [`Splitter`]
+wraps the underlying reader and hands out a [`Section`] for each entry of
+such a table. A section is a reader of its own: it starts at offset zero, it
+ends at the section's length no matter what follows it in the file, and it
+can seek within its bounds. So a parser for one section can't read past its
+end into the next one by accident, and doesn't need to know where its
+section is in the file.
+
+Any number of sections can be alive at the same time, and reading from one
+doesn't disturb the position of the others.
+
+# Examples
+
+```rust
+use std::io::{Cursor, Read};
+
+use byteorder::{section::Splitter, BigEndian, ReadBytesExt};
+
+// A table with two entries, followed by the two sections.
+let data = b"\x00\x08\x00\x02\x00\x0a\x00\x03hiabc".to_vec();
+let mut rdr = Cursor::new(&data[..8]);
+let mut table = vec![];
+for _ in 0..2 {
+    let offset = rdr.read_u16::<BigEndian>().unwrap();
+    let len = rdr.read_u16::<BigEndian>().unwrap();
+    table.push((u64::from(offset), u64::from(len)));
+}
+
+let splitter = Splitter::new(Cursor::new(data));
+let mut first = splitter.section(table[0].0, table[0].1).unwrap();
+let mut second = splitter.section(table[1].0, table[1].1).unwrap();
+
+let mut buf = String::new();
+second.read_to_string(&mut buf).unwrap();
+assert_eq!("abc", buf);
+buf.clear();
+first.read_to_string(&mut buf).unwrap();
+assert_eq!("hi", buf);
+```
+*/
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    io::{self, Read, Result, Seek, SeekFrom},
+};
+
+/// A reader that is split into independent sections.
+///
+/// A splitter owns its underlying reader and shares it between the sections
+/// created by [`Splitter::section`]. Each section seeks the reader to its own
+/// position before reading, but only when some other section has moved it
+/// since, so reading a single section from start to end costs one seek.
+#[derive(Debug)]
+pub struct Splitter<R> {
+    rdr: RefCell<R>,
+    /// The position of `rdr`, if known.
+    pos: Cell<Option<u64>>,
+}
+
+impl<R: Read + Seek> Splitter<R> {
+    /// Creates a splitter over the given reader.
+    ///
+    /// The current position of the reader doesn't matter, since every
+    /// section starts by seeking to its own offset.
+    pub fn new(rdr: R) -> Splitter<R> {
+        Splitter { rdr: RefCell::new(rdr), pos: Cell::new(None) }
+    }
+
+    /// Returns a reader for the `len` bytes starting at `offset` in the
+    /// underlying reader.
+    ///
+    /// This doesn't read or seek anything. If the underlying reader ends
+    /// before the end of the section, reading the section ends there too.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error of kind [`io::ErrorKind::InvalidInput`] if the
+    /// end of the section, `offset + len`, overflows a `u64`.
+    pub fn section(&self, offset: u64, len: u64) -> Result<Section<'_, R>> {
+        if offset.checked_add(len).is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "section end overflows u64",
+            ));
+        }
+        Ok(Section { splitter: self, offset, len, pos: 0 })
+    }
+
+    /// Unwraps this splitter, returning the underlying reader.
+    ///
+    /// The position of the returned reader is unspecified.
+    pub fn into_inner(self) -> R {
+        self.rdr.into_inner()
+    }
+}
+
+/// A reader for one section of the data of a [`Splitter`].
+///
+/// Positions are relative to the start of the section, and reads stop at its
+/// end. Seeking past the end is allowed, just like for files, but reads from
+/// there return no bytes.
+///
+/// Cloning a section is cheap. The clone starts at the same position, and
+/// moves independently afterwards.
+pub struct Section<'a, R> {
+    splitter: &'a Splitter<R>,
+    offset: u64,
+    len: u64,
+    pos: u64,
+}
+
+impl<'a, R> Section<'a, R> {
+    /// Returns the offset of the start of this section in the underlying
+    /// reader.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the length of this section, in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns true if this section is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the current position in this section, relative to its start.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Returns the number of bytes between the current position and the end
+    /// of this section.
+    pub fn remaining(&self) -> u64 {
+        self.len.saturating_sub(self.pos)
+    }
+}
+
+impl<'a, R: Read + Seek> Read for Section<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let max = self.remaining().min(buf.len() as u64) as usize;
+        if max == 0 {
+            return Ok(0);
+        }
+        let at = self.offset + self.pos;
+        let mut rdr = self.splitter.rdr.borrow_mut();
+        if self.splitter.pos.get() != Some(at) {
+            // Forget the position first, in case seeking fails part way.
+            self.splitter.pos.set(None);
+            rdr.seek(SeekFrom::Start(at))?;
+        }
+        let n = rdr.read(&mut buf[..max])?;
+        self.pos += n as u64;
+        self.splitter.pos.set(Some(at + n as u64));
+        Ok(n)
+    }
+}
+
+impl<'a, R> Seek for Section<'a, R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let (base, delta) = match pos {
+            SeekFrom::Start(n) => (0, i128::from(n)),
+            SeekFrom::End(n) => (self.len, i128::from(n)),
+            SeekFrom::Current(n) => (self.pos, i128::from(n)),
+        };
+        let new = i128::from(base) + delta;
+        // The absolute position must fit in a u64 too.
+        if new < 0 || new > i128::from(u64::MAX - self.offset) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            ));
+        }
+        self.pos = new as u64;
+        Ok(self.pos)
+    }
+}
+
+impl<'a, R> Clone for Section<'a, R> {
+    fn clone(&self) -> Section<'a, R> {
+        Section {
+            splitter: self.splitter,
+            offset: self.offset,
+            len: self.len,
+            pos: self.pos,
+        }
+    }
+}
+
+impl<'a, R> fmt::Debug for Section<'a, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Section")
+            .field("offset", &self.offset)
+            .field("len", &self.len)
+            .field("pos", &self.pos)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind, Read, Seek, SeekFrom};
+
+    use super::Splitter;
+    use crate::{BigEndian, ReadBytesExt};
+
+    fn data() -> Cursor<Vec<u8>> {
+        Cursor::new((0..100).collect())
+    }
+
+    #[test]
+    fn interleaved() {
+        let splitter = Splitter::new(data());
+        let mut a = splitter.section(10, 4).unwrap();
+        let mut b = splitter.section(50, 20).unwrap();
+        assert_eq!(10, a.read_u8().unwrap());
+        assert_eq!(0x3233, b.read_u16::<BigEndian>().unwrap());
+        assert_eq!(0x0B0C0D, a.read_u24::<BigEndian>().unwrap());
+        assert_eq!(52, b.read_u8().unwrap());
+
+        // The first section ends where it should.
+        let err = a.read_u8().unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        assert_eq!((4, 0), (a.position(), a.remaining()));
+
+        let mut rest = vec![];
+        b.read_to_end(&mut rest).unwrap();
+        assert_eq!((53..70).collect::<Vec<u8>>(), rest);
+    }
+
+    #[test]
+    fn seeking() {
+        let splitter = Splitter::new(data());
+        let mut s = splitter.section(90, 5).unwrap();
+        assert_eq!(3, s.seek(SeekFrom::End(-2)).unwrap());
+        assert_eq!(93, s.read_u8().unwrap());
+        assert_eq!(1, s.seek(SeekFrom::Current(-3)).unwrap());
+        let mut clone = s.clone();
+        assert_eq!(91, s.read_u8().unwrap());
+        assert_eq!(92, s.read_u8().unwrap());
+        assert_eq!(91, clone.read_u8().unwrap());
+
+        // Past the end of the section, and then past the end of the data.
+        assert_eq!(10, s.seek(SeekFrom::Start(10)).unwrap());
+        assert_eq!(0, s.read(&mut [0; 4]).unwrap());
+        let mut long = splitter.section(98, 10).unwrap();
+        let mut buf = vec![];
+        long.read_to_end(&mut buf).unwrap();
+        assert_eq!(vec![98, 99], buf);
+
+        let err = s.seek(SeekFrom::Current(-11)).unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        assert_eq!(10, s.position());
+    }
+
+    #[test]
+    fn nested() {
+        let outer = Splitter::new(data());
+        let inner = Splitter::new(outer.section(20, 10).unwrap());
+        let mut s = inner.section(8, 5).unwrap();
+        let mut buf = vec![];
+        s.read_to_end(&mut buf).unwrap();
+        assert_eq!(vec![28, 29], buf);
+    }
+
+    #[test]
+    fn overflow() {
+        let splitter = Splitter::new(data());
+        let err = splitter.section(u64::MAX, 1).unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, err.kind());
+        let mut s = splitter.section(u64::MAX - 1, 1).unwrap();
+        assert!(s.seek(SeekFrom::Start(2)).is_err());
+        assert_eq!(1, s.seek(SeekFrom::End(0)).unwrap());
+    }
+}
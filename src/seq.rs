@@ -0,0 +1,360 @@
+/*!
+Reading nested sequences, each prefixed with its number of elements.
+
+Many formats encode collections as a count followed by that many elements,
+where an element may itself contain such collections: a list of lists, a
+tree of nodes with children, or a message with repeated sub-messages. A
+[`SeqReader`] wraps the underlying reader and reads these with
+[`SeqReader::read_seq_with`], which reads the count and then calls a closure
+once per element. The closure gets the `SeqReader` back, so it can read the
+element's own sequences the same way.
+
+Since the counts come from the input, they can't be trusted. A corrupt or
+malicious input could nest sequences deep enough to overflow the stack of a
+recursive parser, or claim a huge number of elements. So a `SeqReader`
+enforces two limits:
+
+* The nesting depth, which is the number of calls to `read_seq_with` that
+  are in progress at once.
+* The total number of bytes read through the `SeqReader`.
+
+Exceeding either of them fails with an error of kind
+[`ErrorKind::InvalidData`](std::io::ErrorKind::InvalidData). Vectors are
+also never allocated much ahead of the elements actually read, so a huge
+count on a short input fails when the input ends rather than allocating.
+
+The inverse is [`WriteBytesExt::write_seq_with`]. Since the data written is
+trusted, writing needs no limits, and works with any writer.
+
+[`WriteBytesExt::write_seq_with`]: crate::WriteBytesExt::write_seq_with
+
+# Examples
+
+Read a tree, where each node is a value followed by its children:
+
+```rust
+use std::io::{self, Cursor, Read};
+
+use byteorder::{
+    seq::SeqReader, BigEndian, PrefixWidth, ReadBytesExt, WriteBytesExt,
+};
+
+#[derive(Debug, PartialEq)]
+struct Node {
+    value: u32,
+    children: Vec<Node>,
+}
+
+fn read_node<R: Read>(rdr: &mut SeqReader<R>) -> io::Result<Node> {
+    let value = rdr.read_u32::<BigEndian>()?;
+    let children =
+        rdr.read_seq_with::<BigEndian, _, _>(PrefixWidth::U8, read_node)?;
+    Ok(Node { value, children })
+}
+
+fn write_node<W: io::Write>(wtr: &mut W, node: &Node) -> io::Result<()> {
+    wtr.write_u32::<BigEndian>(node.value)?;
+    let children = &node.children;
+    wtr.write_seq_with::<BigEndian, _, _>(PrefixWidth::U8, children, write_node)
+}
+
+let tree = Node {
+    value: 1,
+    children: vec![
+        Node { value: 2, children: vec![] },
+        Node {
+            value: 3,
+            children: vec![Node { value: 4, children: vec![] }],
+        },
+    ],
+};
+let mut buf = vec![];
+write_node(&mut buf, &tree).unwrap();
+
+let mut rdr = SeqReader::new(Cursor::new(&buf));
+assert_eq!(tree, read_node(&mut rdr).unwrap());
+
+// The same input, with fewer levels of nesting allowed.
+let mut rdr = SeqReader::with_limits(Cursor::new(&buf), 1, u64::MAX);
+let err = read_node(&mut rdr).unwrap_err();
+assert_eq!(io::ErrorKind::InvalidData, err.kind());
+```
+*/
+
+use std::io::{self, BufRead, Read, Result};
+
+use crate::{ByteOrder, PrefixWidth, ReadBytesExt};
+
+/// The nesting depth that [`SeqReader::new`] allows.
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+/// The largest number of elements that space is reserved for up front.
+const MAX_PREALLOCATED: usize = 1024;
+
+/// A reader of nested sequences, which limits how deep they nest and how
+/// many bytes they take in total.
+///
+/// See the [module documentation](self) for details. Every byte read through
+/// this reader counts towards the limit on the total size, whether it is
+/// read by [`SeqReader::read_seq_with`] or by one of the methods of
+/// [`ReadBytesExt`].
+#[derive(Clone, Debug)]
+pub struct SeqReader<R> {
+    rdr: R,
+    depth: usize,
+    max_depth: usize,
+    /// The number of bytes that may still be read.
+    remaining: u64,
+}
+
+impl<R> SeqReader<R> {
+    /// Creates a reader of nested sequences over the given reader.
+    ///
+    /// Sequences may nest up to [`DEFAULT_MAX_DEPTH`] levels deep, and the
+    /// total number of bytes read isn't limited. Use
+    /// [`SeqReader::with_limits`] when the input may be very large.
+    pub fn new(rdr: R) -> SeqReader<R> {
+        SeqReader::with_limits(rdr, DEFAULT_MAX_DEPTH, u64::MAX)
+    }
+
+    /// Creates a reader of nested sequences over the given reader, which
+    /// allows sequences to nest up to `max_depth` levels deep, and reads at
+    /// most `max_bytes` bytes in total.
+    ///
+    /// A `max_depth` of zero forbids reading sequences at all.
+    pub fn with_limits(
+        rdr: R,
+        max_depth: usize,
+        max_bytes: u64,
+    ) -> SeqReader<R> {
+        SeqReader { rdr, depth: 0, max_depth, remaining: max_bytes }
+    }
+
+    /// Returns the number of calls to [`SeqReader::read_seq_with`] that are
+    /// currently in progress.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the number of bytes that may still be read before the limit
+    /// on the total size is reached.
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.rdr
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Bytes read from the underlying reader directly don't count towards
+    /// the limit on the total size.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.rdr
+    }
+
+    /// Unwraps this reader, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.rdr
+    }
+}
+
+impl<R: Read> SeqReader<R> {
+    /// Reads a sequence, prefixed with its number of elements, which is
+    /// `width` bytes wide in byte order `E`.
+    ///
+    /// After reading the count, `read` is called once per element, with this
+    /// reader, and the elements it returns are collected into a vector.
+    /// `read` may call this method again to read nested sequences.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`] when
+    /// reading the count, and any error returned by `read`. If this call
+    /// would nest deeper than the limit, or the total size limit is reached
+    /// while reading, an error of kind [`io::ErrorKind::InvalidData`] is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use byteorder::{seq::SeqReader, LittleEndian, PrefixWidth, ReadBytesExt};
+    ///
+    /// let mut rdr = SeqReader::new(Cursor::new(vec![2, 0, 1, 0, 2, 0]));
+    /// let xs = rdr
+    ///     .read_seq_with::<LittleEndian, _, _>(PrefixWidth::U16, |r| {
+    ///         r.read_u16::<LittleEndian>()
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(vec![1, 2], xs);
+    /// ```
+    pub fn read_seq_with<E, V, F>(
+        &mut self,
+        width: PrefixWidth,
+        mut read: F,
+    ) -> Result<Vec<V>>
+    where
+        E: ByteOrder,
+        F: FnMut(&mut SeqReader<R>) -> Result<V>,
+    {
+        if self.depth >= self.max_depth {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "sequences nest deeper than the limit of {}",
+                    self.max_depth
+                ),
+            ));
+        }
+        let count = match width {
+            PrefixWidth::U8 => usize::from(self.read_u8()?),
+            PrefixWidth::U16 => usize::from(self.read_u16::<E>()?),
+            PrefixWidth::U32 => {
+                // A count that doesn't fit in a usize can't be read anyway,
+                // so let reading fail at the end of the input.
+                usize::try_from(self.read_u32::<E>()?).unwrap_or(usize::MAX)
+            }
+        };
+        self.depth += 1;
+        // Grow the vector as elements are read, so that a corrupt count
+        // fails when the input ends rather than allocating up front.
+        let mut out = Vec::with_capacity(count.min(MAX_PREALLOCATED));
+        let mut result = Ok(());
+        for _ in 0..count {
+            match read(self) {
+                Ok(v) => out.push(v),
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+        self.depth -= 1;
+        result.map(|()| out)
+    }
+}
+
+/// Returns the error for reading past the total size limit.
+fn limit_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "sequences exceed the limit on their total size",
+    )
+}
+
+impl<R: Read> Read for SeqReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.remaining == 0 {
+            return Err(limit_error());
+        }
+        let len = buf
+            .len()
+            .min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+        let n = self.rdr.read(&mut buf[..len])?;
+        self.remaining -= n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for SeqReader<R> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.remaining == 0 {
+            return Err(limit_error());
+        }
+        let buf = self.rdr.fill_buf()?;
+        let len = buf
+            .len()
+            .min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+        Ok(&buf[..len])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.rdr.consume(amt);
+        self.remaining -= amt as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor};
+
+    use super::SeqReader;
+    use crate::{BigEndian, PrefixWidth, ReadBytesExt, WriteBytesExt};
+
+    /// Reads a list of lists of bytes.
+    fn read_nested<R: io::Read>(
+        rdr: &mut SeqReader<R>,
+    ) -> io::Result<Vec<Vec<u8>>> {
+        rdr.read_seq_with::<BigEndian, _, _>(PrefixWidth::U16, |r| {
+            r.read_seq_with::<BigEndian, _, _>(PrefixWidth::U8, |r| {
+                r.read_u8()
+            })
+        })
+    }
+
+    #[test]
+    fn roundtrip() {
+        let lists = vec![vec![1, 2, 3], vec![], vec![4]];
+        let mut wtr = vec![];
+        wtr.write_seq_with::<BigEndian, _, _>(
+            PrefixWidth::U16,
+            &lists,
+            |w, list| {
+                w.write_seq_with::<BigEndian, _, _>(
+                    PrefixWidth::U8,
+                    list,
+                    |w, &b| w.write_u8(b),
+                )
+            },
+        )
+        .unwrap();
+        assert_eq!(wtr, [0, 3, 3, 1, 2, 3, 0, 1, 4]);
+
+        let mut rdr = SeqReader::new(Cursor::new(&wtr));
+        assert_eq!(lists, read_nested(&mut rdr).unwrap());
+        assert_eq!(0, rdr.depth());
+        assert_eq!(u64::MAX - 9, rdr.remaining());
+    }
+
+    #[test]
+    fn depth_limit() {
+        let data = [0, 1, 1, 7];
+        let mut rdr = SeqReader::with_limits(Cursor::new(&data), 2, 100);
+        assert_eq!(vec![vec![7]], read_nested(&mut rdr).unwrap());
+
+        let mut rdr = SeqReader::with_limits(Cursor::new(&data), 1, 100);
+        let err = read_nested(&mut rdr).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        // The depth is restored after an error.
+        assert_eq!(0, rdr.depth());
+
+        let mut rdr = SeqReader::with_limits(Cursor::new(&data), 0, 100);
+        let err = read_nested(&mut rdr).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn size_limit() {
+        let data = [0, 2, 1, 7, 1, 8];
+        let mut rdr = SeqReader::with_limits(Cursor::new(&data), 2, 6);
+        assert_eq!(vec![vec![7], vec![8]], read_nested(&mut rdr).unwrap());
+
+        let mut rdr = SeqReader::with_limits(Cursor::new(&data), 2, 5);
+        let err = read_nested(&mut rdr).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn huge_count() {
+        // A count of 65535 elements, on an input that ends right after it.
+        let mut rdr = SeqReader::new(Cursor::new(vec![0xFF, 0xFF]));
+        let err = read_nested(&mut rdr).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    }
+}
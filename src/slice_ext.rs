@@ -0,0 +1,920 @@
+use core::mem::MaybeUninit;
+
+use crate::{
+    wire::{FromWire, ToWire},
+    ByteOrder, Error, ErrorKind, Padding,
+};
+
+/// Extends `&[u8]` with methods that read numbers from the front of the
+/// slice and advance it past them.
+///
+/// This is for parsing data that is already in memory without wrapping it
+/// in a `Cursor` or computing offsets by hand. Every method returns an error
+/// of kind [`ErrorKind::BufferTooSmall`](crate::ErrorKind::BufferTooSmall)
+/// instead of panicking when the slice is too short, and leaves the slice
+/// unchanged when it fails. Since this works without `std`, it is available
+/// in `no_std` crates too.
+///
+/// The methods are named like those of [`ReadBytesExt`], which is also
+/// implemented for `&[u8]`. Bringing both traits into the same scope makes
+/// calls on a `&[u8]` ambiguous, so parse slices and readers in separate
+/// modules, or call the methods as `ByteSliceExt::read_u16::<E>(&mut buf)`.
+///
+/// This trait is sealed, and can't be implemented outside of this crate.
+///
+/// [`ReadBytesExt`]: https://docs.rs/byteorder/*/byteorder/trait.ReadBytesExt.html
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{BigEndian, ByteSliceExt, Error};
+///
+/// fn parse(mut buf: &[u8]) -> Result<(u16, &[u8], i32), Error> {
+///     let len = buf.read_u16::<BigEndian>()?;
+///     let name = buf.read_bytes(len as usize)?;
+///     let value = buf.read_i32::<BigEndian>()?;
+///     Ok((len, name, value))
+/// }
+///
+/// let data = b"\x00\x03abc\xff\xff\xff\xfe";
+/// assert_eq!(Ok((3, &b"abc"[..], -2)), parse(data));
+/// assert!(parse(&data[..8]).is_err());
+/// ```
+pub trait ByteSliceExt<'a>: crate::private::Sealed {
+    /// Returns the first `n` bytes of this slice, and advances it past them.
+    fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], Error>;
+
+    /// Advances this slice past its first `n` bytes.
+    fn skip(&mut self, n: usize) -> Result<(), Error>;
+
+    /// Returns the first `N` bytes of this slice as an array, and advances
+    /// it past them.
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], Error>;
+
+    /// Reads an unsigned 8 bit integer.
+    fn read_u8(&mut self) -> Result<u8, Error>;
+
+    /// Reads a signed 8 bit integer.
+    fn read_i8(&mut self) -> Result<i8, Error>;
+
+    /// Reads an unsigned 16 bit integer.
+    fn read_u16<E: ByteOrder>(&mut self) -> Result<u16, Error>;
+
+    /// Reads a signed 16 bit integer.
+    fn read_i16<E: ByteOrder>(&mut self) -> Result<i16, Error>;
+
+    /// Reads an unsigned 24 bit integer.
+    fn read_u24<E: ByteOrder>(&mut self) -> Result<u32, Error>;
+
+    /// Reads a signed 24 bit integer.
+    fn read_i24<E: ByteOrder>(&mut self) -> Result<i32, Error>;
+
+    /// Reads an unsigned 32 bit integer.
+    fn read_u32<E: ByteOrder>(&mut self) -> Result<u32, Error>;
+
+    /// Reads a signed 32 bit integer.
+    fn read_i32<E: ByteOrder>(&mut self) -> Result<i32, Error>;
+
+    /// Reads an unsigned 48 bit integer.
+    fn read_u48<E: ByteOrder>(&mut self) -> Result<u64, Error>;
+
+    /// Reads a signed 48 bit integer.
+    fn read_i48<E: ByteOrder>(&mut self) -> Result<i64, Error>;
+
+    /// Reads an unsigned 64 bit integer.
+    fn read_u64<E: ByteOrder>(&mut self) -> Result<u64, Error>;
+
+    /// Reads a signed 64 bit integer.
+    fn read_i64<E: ByteOrder>(&mut self) -> Result<i64, Error>;
+
+    /// Reads an unsigned 128 bit integer.
+    fn read_u128<E: ByteOrder>(&mut self) -> Result<u128, Error>;
+
+    /// Reads a signed 128 bit integer.
+    fn read_i128<E: ByteOrder>(&mut self) -> Result<i128, Error>;
+
+    /// Reads an unsigned n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes < 1` or `nbytes > 8`.
+    fn read_uint<E: ByteOrder>(&mut self, nbytes: usize)
+        -> Result<u64, Error>;
+
+    /// Reads a signed n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes < 1` or `nbytes > 8`.
+    fn read_int<E: ByteOrder>(&mut self, nbytes: usize) -> Result<i64, Error>;
+
+    /// Reads an unsigned n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes < 1` or `nbytes > 16`.
+    fn read_uint128<E: ByteOrder>(
+        &mut self,
+        nbytes: usize,
+    ) -> Result<u128, Error>;
+
+    /// Reads a signed n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes < 1` or `nbytes > 16`.
+    fn read_int128<E: ByteOrder>(
+        &mut self,
+        nbytes: usize,
+    ) -> Result<i128, Error>;
+
+    /// Reads a IEEE754 single-precision floating point number.
+    fn read_f32<E: ByteOrder>(&mut self) -> Result<f32, Error>;
+
+    /// Reads a IEEE754 double-precision floating point number.
+    fn read_f64<E: ByteOrder>(&mut self) -> Result<f64, Error>;
+
+    /// Reads a value of any type implementing [`FromWire`].
+    ///
+    /// Exactly `T::WIRE_SIZE` bytes are consumed, and only when the value is
+    /// decoded successfully.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{fourcc::FourCc, ByteSliceExt, LittleEndian};
+    ///
+    /// let mut buf = &b"RIFF\x04\x00\x00\x00"[..];
+    /// let riff = buf.read_wire::<FourCc, LittleEndian>().unwrap();
+    /// assert_eq!(FourCc::new(*b"RIFF"), riff);
+    /// assert_eq!(4, buf.read_wire::<u32, LittleEndian>().unwrap());
+    /// assert!(buf.is_empty());
+    /// ```
+    fn read_wire<T: FromWire, E: ByteOrder>(&mut self) -> Result<T, Error>;
+
+    /// Reads a text field of exactly `n` bytes, and returns its text without
+    /// its padding.
+    ///
+    /// The whole field is consumed, but only when its text is valid UTF-8.
+    /// Otherwise, an error of kind
+    /// [`ErrorKind::InvalidUtf8`](crate::ErrorKind::InvalidUtf8) is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use byteorder::{ByteSliceExt, Padding};
+    ///
+    /// let mut buf = &b"ustar\x0000hello   "[..];
+    /// assert_eq!(Ok("ustar"), buf.read_fixed_str(6, Padding::Nul));
+    /// assert_eq!(Ok("00hello"), buf.read_fixed_str(10, Padding::Space));
+    /// assert!(buf.is_empty());
+    /// ```
+    fn read_fixed_str(
+        &mut self,
+        n: usize,
+        padding: Padding,
+    ) -> Result<&'a str, Error>;
+}
+
+impl crate::private::Sealed for &[u8] {}
+
+macro_rules! read_fixed {
+    ($($name:ident -> $ty:ty = $read:ident($size:expr);)*) => {$(
+        #[inline]
+        fn $name<E: ByteOrder>(&mut self) -> Result<$ty, Error> {
+            no_panic!({ self.read_bytes($size).map(E::$read) })
+        }
+    )*};
+}
+
+impl<'a> ByteSliceExt<'a> for &'a [u8] {
+    #[inline]
+    fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        no_panic!({
+            Error::check_len(n, self.len())?;
+            let (head, tail) = self.split_at(n);
+            *self = tail;
+            Ok(head)
+        })
+    }
+
+    #[inline]
+    fn skip(&mut self, n: usize) -> Result<(), Error> {
+        self.read_bytes(n).map(|_| ())
+    }
+
+    #[inline]
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        no_panic!({
+            let mut out = [0; N];
+            out.copy_from_slice(self.read_bytes(N)?);
+            Ok(out)
+        })
+    }
+
+    #[inline]
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        no_panic!({ self.read_bytes(1).map(|b| b[0]) })
+    }
+
+    #[inline]
+    fn read_i8(&mut self) -> Result<i8, Error> {
+        self.read_u8().map(|n| n as i8)
+    }
+
+    read_fixed! {
+        read_u16 -> u16 = read_u16(2);
+        read_i16 -> i16 = read_i16(2);
+        read_u24 -> u32 = read_u24(3);
+        read_i24 -> i32 = read_i24(3);
+        read_u32 -> u32 = read_u32(4);
+        read_i32 -> i32 = read_i32(4);
+        read_u48 -> u64 = read_u48(6);
+        read_i48 -> i64 = read_i48(6);
+        read_u64 -> u64 = read_u64(8);
+        read_i64 -> i64 = read_i64(8);
+        read_u128 -> u128 = read_u128(16);
+        read_i128 -> i128 = read_i128(16);
+        read_f32 -> f32 = read_f32(4);
+        read_f64 -> f64 = read_f64(8);
+    }
+
+    #[inline]
+    fn read_uint<E: ByteOrder>(
+        &mut self,
+        nbytes: usize,
+    ) -> Result<u64, Error> {
+        let n = E::try_read_uint(self, nbytes)?;
+        *self = &self[nbytes..];
+        Ok(n)
+    }
+
+    #[inline]
+    fn read_int<E: ByteOrder>(&mut self, nbytes: usize) -> Result<i64, Error> {
+        let n = E::try_read_int(self, nbytes)?;
+        *self = &self[nbytes..];
+        Ok(n)
+    }
+
+    #[inline]
+    fn read_uint128<E: ByteOrder>(
+        &mut self,
+        nbytes: usize,
+    ) -> Result<u128, Error> {
+        let n = E::try_read_uint128(self, nbytes)?;
+        *self = &self[nbytes..];
+        Ok(n)
+    }
+
+    #[inline]
+    fn read_int128<E: ByteOrder>(
+        &mut self,
+        nbytes: usize,
+    ) -> Result<i128, Error> {
+        let n = E::try_read_int128(self, nbytes)?;
+        *self = &self[nbytes..];
+        Ok(n)
+    }
+
+    #[inline]
+    fn read_wire<T: FromWire, E: ByteOrder>(&mut self) -> Result<T, Error> {
+        let value = T::from_wire::<E>(self)?;
+        *self = &self[T::WIRE_SIZE..];
+        Ok(value)
+    }
+
+    #[inline]
+    fn read_fixed_str(
+        &mut self,
+        n: usize,
+        padding: Padding,
+    ) -> Result<&'a str, Error> {
+        Error::check_len(n, self.len())?;
+        let text = padding.trim(&self[..n]);
+        let text = core::str::from_utf8(text)
+            .map_err(|_| Error::new(ErrorKind::InvalidUtf8))?;
+        *self = &self[n..];
+        Ok(text)
+    }
+}
+
+/// A cursor that reads numbers from a byte slice and tracks its position.
+///
+/// This has the same methods as [`ByteSliceExt`], with the same errors, but
+/// also remembers where it started. That's useful for `no_std` parsers that
+/// report the offset of a malformed field, or that need to compute offsets
+/// relative to the start of a message. Like `ByteSliceExt`, a method that
+/// fails leaves the reader where it was.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{BigEndian, SliceReader};
+///
+/// let mut rdr = SliceReader::new(b"\x00\x02\xCA\xFE\x01");
+/// let len = rdr.read_u16::<BigEndian>().unwrap();
+/// assert_eq!(&[0xCA, 0xFE], rdr.read_bytes(len as usize).unwrap());
+/// assert_eq!(4, rdr.position());
+/// assert!(rdr.read_u32::<BigEndian>().is_err());
+/// assert_eq!(4, rdr.position());
+/// assert_eq!(&[1], rdr.rest());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct SliceReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+macro_rules! read_cursor {
+    ($($name:ident -> $ty:ty, $desc:expr;)*) => {$(
+        #[doc = concat!("Reads ", $desc, ".")]
+        #[inline]
+        pub fn $name<E: ByteOrder>(&mut self) -> Result<$ty, Error> {
+            no_panic!({ self.advance(|rest| rest.$name::<E>()) })
+        }
+    )*};
+}
+
+impl<'a> SliceReader<'a> {
+    /// Creates a reader that starts at the beginning of `buf`.
+    #[inline]
+    pub fn new(buf: &'a [u8]) -> SliceReader<'a> {
+        SliceReader { buf, pos: 0 }
+    }
+
+    /// Returns the number of bytes read so far.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Moves this reader to `pos` bytes from the start of its buffer.
+    ///
+    /// This returns an error of kind
+    /// [`ErrorKind::BufferTooSmall`](crate::ErrorKind::BufferTooSmall) when
+    /// `pos` is past the end of the buffer.
+    #[inline]
+    pub fn set_position(&mut self, pos: usize) -> Result<(), Error> {
+        Error::check_len(pos, self.buf.len())?;
+        self.pos = pos;
+        Ok(())
+    }
+
+    /// Returns the number of bytes that are left to read.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// Returns the bytes that are left to read.
+    #[inline]
+    pub fn rest(&self) -> &'a [u8] {
+        self.buf.get(self.pos..).unwrap_or_default()
+    }
+
+    /// Returns the whole buffer, including the bytes already read.
+    #[inline]
+    pub fn get_ref(&self) -> &'a [u8] {
+        self.buf
+    }
+
+    /// Runs `read` on the rest of the buffer, and moves past the bytes it
+    /// consumed.
+    #[inline]
+    fn advance<T>(
+        &mut self,
+        read: impl FnOnce(&mut &'a [u8]) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let mut rest = self.rest();
+        let value = read(&mut rest)?;
+        self.pos = self.buf.len() - rest.len();
+        Ok(value)
+    }
+
+    /// Returns the next `n` bytes, and moves past them.
+    #[inline]
+    pub fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        self.advance(|rest| rest.read_bytes(n))
+    }
+
+    /// Moves past the next `n` bytes.
+    #[inline]
+    pub fn skip(&mut self, n: usize) -> Result<(), Error> {
+        no_panic!({ self.advance(|rest| rest.skip(n)) })
+    }
+
+    /// Returns the next `N` bytes as an array, and moves past them.
+    #[inline]
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        no_panic!({ self.advance(|rest| rest.read_array::<N>()) })
+    }
+
+    /// Reads an unsigned 8 bit integer.
+    #[inline]
+    pub fn read_u8(&mut self) -> Result<u8, Error> {
+        no_panic!({ self.advance(|rest| rest.read_u8()) })
+    }
+
+    /// Reads a signed 8 bit integer.
+    #[inline]
+    pub fn read_i8(&mut self) -> Result<i8, Error> {
+        no_panic!({ self.advance(|rest| rest.read_i8()) })
+    }
+
+    read_cursor! {
+        read_u16 -> u16, "an unsigned 16 bit integer";
+        read_i16 -> i16, "a signed 16 bit integer";
+        read_u24 -> u32, "an unsigned 24 bit integer";
+        read_i24 -> i32, "a signed 24 bit integer";
+        read_u32 -> u32, "an unsigned 32 bit integer";
+        read_i32 -> i32, "a signed 32 bit integer";
+        read_u48 -> u64, "an unsigned 48 bit integer";
+        read_i48 -> i64, "a signed 48 bit integer";
+        read_u64 -> u64, "an unsigned 64 bit integer";
+        read_i64 -> i64, "a signed 64 bit integer";
+        read_u128 -> u128, "an unsigned 128 bit integer";
+        read_i128 -> i128, "a signed 128 bit integer";
+        read_f32 -> f32, "a IEEE754 single-precision floating point number";
+        read_f64 -> f64, "a IEEE754 double-precision floating point number";
+    }
+
+    /// Reads an unsigned n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes < 1` or `nbytes > 8`.
+    #[inline]
+    pub fn read_uint<E: ByteOrder>(
+        &mut self,
+        nbytes: usize,
+    ) -> Result<u64, Error> {
+        self.advance(|rest| rest.read_uint::<E>(nbytes))
+    }
+
+    /// Reads a signed n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes < 1` or `nbytes > 8`.
+    #[inline]
+    pub fn read_int<E: ByteOrder>(
+        &mut self,
+        nbytes: usize,
+    ) -> Result<i64, Error> {
+        self.advance(|rest| rest.read_int::<E>(nbytes))
+    }
+
+    /// Reads an unsigned n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes < 1` or `nbytes > 16`.
+    #[inline]
+    pub fn read_uint128<E: ByteOrder>(
+        &mut self,
+        nbytes: usize,
+    ) -> Result<u128, Error> {
+        self.advance(|rest| rest.read_uint128::<E>(nbytes))
+    }
+
+    /// Reads a signed n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes < 1` or `nbytes > 16`.
+    #[inline]
+    pub fn read_int128<E: ByteOrder>(
+        &mut self,
+        nbytes: usize,
+    ) -> Result<i128, Error> {
+        self.advance(|rest| rest.read_int128::<E>(nbytes))
+    }
+
+    /// Reads a value of any type implementing [`FromWire`].
+    ///
+    /// Exactly `T::WIRE_SIZE` bytes are consumed, and only when the value is
+    /// decoded successfully.
+    #[inline]
+    pub fn read_wire<T: FromWire, E: ByteOrder>(
+        &mut self,
+    ) -> Result<T, Error> {
+        self.advance(|rest| rest.read_wire::<T, E>())
+    }
+
+    /// Reads a text field of exactly `n` bytes, and returns its text without
+    /// its padding.
+    ///
+    /// See [`ByteSliceExt::read_fixed_str`] for details.
+    #[inline]
+    pub fn read_fixed_str(
+        &mut self,
+        n: usize,
+        padding: Padding,
+    ) -> Result<&'a str, Error> {
+        self.advance(|rest| rest.read_fixed_str(n, padding))
+    }
+}
+
+/// A cursor that writes numbers to the front of a byte slice.
+///
+/// This is the writing counterpart of [`SliceReader`], for building
+/// messages in a fixed buffer without `std`, where [`WriteBytesExt`] isn't
+/// available. Every method returns an error of kind
+/// [`ErrorKind::BufferTooSmall`](crate::ErrorKind::BufferTooSmall) instead
+/// of panicking when the rest of the buffer is too short, and writes nothing
+/// when it fails, so a message that doesn't fit can be detected after
+/// writing all of it.
+///
+/// The buffer may also be uninitialized, in which case only the bytes that
+/// were written are ever read, by [`written`](SliceWriter::written).
+///
+/// [`WriteBytesExt`]: https://docs.rs/byteorder/*/byteorder/trait.WriteBytesExt.html
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{BigEndian, Error, SliceWriter};
+///
+/// fn encode(buf: &mut [u8], name: &[u8], value: i32) -> Result<usize, Error> {
+///     let mut wtr = SliceWriter::new(buf);
+///     wtr.write_u16::<BigEndian>(name.len() as u16)?;
+///     wtr.write_bytes(name)?;
+///     wtr.write_i32::<BigEndian>(value)?;
+///     Ok(wtr.position())
+/// }
+///
+/// let mut buf = [0; 9];
+/// assert_eq!(Ok(9), encode(&mut buf, b"abc", -2));
+/// assert_eq!(b"\x00\x03abc\xff\xff\xff\xfe", &buf);
+/// assert!(encode(&mut buf, b"abcd", -2).is_err());
+/// ```
+pub struct SliceWriter<'a> {
+    buf: &'a mut [MaybeUninit<u8>],
+    pos: usize,
+}
+
+macro_rules! write_fixed {
+    ($($name:ident($ty:ty, $size:expr, $desc:expr);)*) => {$(
+        #[doc = concat!("Writes ", $desc, ".")]
+        #[inline]
+        pub fn $name<E: ByteOrder>(&mut self, n: $ty) -> Result<(), Error> {
+            no_panic!({
+                let mut buf = [0; $size];
+                E::$name(&mut buf, n);
+                self.write_bytes(&buf)
+            })
+        }
+    )*};
+}
+
+macro_rules! write_ranged {
+    ($($name:ident($ty:ty, $size:expr, $desc:expr);)*) => {$(
+        #[doc = concat!(
+            "Writes ", $desc, ".\n\n",
+            "# Panics\n\n",
+            "Panics when `n` is not representable in ", stringify!($size),
+            " bytes.",
+        )]
+        #[inline]
+        pub fn $name<E: ByteOrder>(&mut self, n: $ty) -> Result<(), Error> {
+            let mut buf = [0; $size];
+            E::$name(&mut buf, n);
+            self.write_bytes(&buf)
+        }
+    )*};
+}
+
+impl<'a> SliceWriter<'a> {
+    /// Creates a writer that starts at the beginning of `buf`.
+    #[inline]
+    pub fn new(buf: &'a mut [u8]) -> SliceWriter<'a> {
+        // SAFETY: `MaybeUninit<u8>` has the same layout as `u8`, and this
+        // writer only ever stores initialized bytes in `buf`.
+        let buf =
+            unsafe { &mut *(buf as *mut [u8] as *mut [MaybeUninit<u8>]) };
+        SliceWriter { buf, pos: 0 }
+    }
+
+    /// Creates a writer that starts at the beginning of the possibly
+    /// uninitialized `buf`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::mem::MaybeUninit;
+    ///
+    /// use byteorder::{LittleEndian, SliceWriter};
+    ///
+    /// let mut buf = [MaybeUninit::uninit(); 64];
+    /// let mut wtr = SliceWriter::new_uninit(&mut buf);
+    /// wtr.write_u24::<LittleEndian>(0x030201).unwrap();
+    /// assert_eq!(&[1, 2, 3], wtr.written());
+    /// ```
+    #[inline]
+    pub fn new_uninit(buf: &'a mut [MaybeUninit<u8>]) -> SliceWriter<'a> {
+        SliceWriter { buf, pos: 0 }
+    }
+
+    /// Returns the number of bytes written so far.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the number of bytes that can still be written.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// Returns the bytes written so far.
+    #[inline]
+    pub fn written(&self) -> &[u8] {
+        // SAFETY: the first `pos` bytes of `buf` were all initialized by
+        // `write_bytes`.
+        unsafe { &*(&self.buf[..self.pos] as *const _ as *const [u8]) }
+    }
+
+    /// Consumes this writer and returns the bytes written, with the lifetime
+    /// of the underlying buffer.
+    #[inline]
+    pub fn into_written(self) -> &'a mut [u8] {
+        let written = &mut self.buf[..self.pos];
+        // SAFETY: the first `pos` bytes of `buf` were all initialized by
+        // `write_bytes`.
+        unsafe { &mut *(written as *mut _ as *mut [u8]) }
+    }
+
+    /// Writes all of `bytes`.
+    #[inline]
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        no_panic!({
+            let rest = self.buf.get_mut(self.pos..).unwrap_or_default();
+            Error::check_len(bytes.len(), rest.len())?;
+            for (dst, &b) in rest.iter_mut().zip(bytes) {
+                *dst = MaybeUninit::new(b);
+            }
+            self.pos += bytes.len();
+            Ok(())
+        })
+    }
+
+    /// Writes an unsigned 8 bit integer.
+    #[inline]
+    pub fn write_u8(&mut self, n: u8) -> Result<(), Error> {
+        no_panic!({ self.write_bytes(&[n]) })
+    }
+
+    /// Writes a signed 8 bit integer.
+    #[inline]
+    pub fn write_i8(&mut self, n: i8) -> Result<(), Error> {
+        no_panic!({ self.write_bytes(&[n as u8]) })
+    }
+
+    write_fixed! {
+        write_u16(u16, 2, "an unsigned 16 bit integer");
+        write_i16(i16, 2, "a signed 16 bit integer");
+        write_u32(u32, 4, "an unsigned 32 bit integer");
+        write_i32(i32, 4, "a signed 32 bit integer");
+        write_u64(u64, 8, "an unsigned 64 bit integer");
+        write_i64(i64, 8, "a signed 64 bit integer");
+        write_u128(u128, 16, "an unsigned 128 bit integer");
+        write_i128(i128, 16, "a signed 128 bit integer");
+        write_f32(f32, 4, "a IEEE754 single-precision floating point number");
+        write_f64(f64, 8, "a IEEE754 double-precision floating point number");
+    }
+
+    write_ranged! {
+        write_u24(u32, 3, "an unsigned 24 bit integer");
+        write_i24(i32, 3, "a signed 24 bit integer");
+        write_u48(u64, 6, "an unsigned 48 bit integer");
+        write_i48(i64, 6, "a signed 48 bit integer");
+    }
+
+    /// Writes an unsigned n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes > 8` or when `n` is not representable in
+    /// `nbytes` bytes.
+    #[inline]
+    pub fn write_uint<E: ByteOrder>(
+        &mut self,
+        n: u64,
+        nbytes: usize,
+    ) -> Result<(), Error> {
+        let mut buf = [0; 8];
+        E::write_uint(&mut buf, n, nbytes);
+        self.write_bytes(&buf[..nbytes])
+    }
+
+    /// Writes a signed n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes > 8` or when `n` is not representable in
+    /// `nbytes` bytes.
+    #[inline]
+    pub fn write_int<E: ByteOrder>(
+        &mut self,
+        n: i64,
+        nbytes: usize,
+    ) -> Result<(), Error> {
+        let mut buf = [0; 8];
+        E::write_int(&mut buf, n, nbytes);
+        self.write_bytes(&buf[..nbytes])
+    }
+
+    /// Writes an unsigned n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes > 16` or when `n` is not representable in
+    /// `nbytes` bytes.
+    #[inline]
+    pub fn write_uint128<E: ByteOrder>(
+        &mut self,
+        n: u128,
+        nbytes: usize,
+    ) -> Result<(), Error> {
+        let mut buf = [0; 16];
+        E::write_uint128(&mut buf, n, nbytes);
+        self.write_bytes(&buf[..nbytes])
+    }
+
+    /// Writes a signed n-bytes integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `nbytes > 16` or when `n` is not representable in
+    /// `nbytes` bytes.
+    #[inline]
+    pub fn write_int128<E: ByteOrder>(
+        &mut self,
+        n: i128,
+        nbytes: usize,
+    ) -> Result<(), Error> {
+        let mut buf = [0; 16];
+        E::write_int128(&mut buf, n, nbytes);
+        self.write_bytes(&buf[..nbytes])
+    }
+
+    /// Writes a value of any type implementing [`ToWire`].
+    ///
+    /// Exactly `T::WIRE_SIZE` bytes are written, and only when the value is
+    /// encoded successfully.
+    #[inline]
+    pub fn write_wire<T: ToWire, E: ByteOrder>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Error> {
+        Error::check_len(T::WIRE_SIZE, self.remaining())?;
+        // The value's bytes are zeroed before encoding them in place, so
+        // that the encoder never sees uninitialized memory.
+        let start = self.pos;
+        for b in &mut self.buf[start..start + T::WIRE_SIZE] {
+            *b = MaybeUninit::new(0);
+        }
+        self.pos += T::WIRE_SIZE;
+        let written = &mut self.buf[start..self.pos];
+        // SAFETY: every byte of `written` was just initialized.
+        let written = unsafe { &mut *(written as *mut _ as *mut [u8]) };
+        let result = value.to_wire::<E>(written);
+        if result.is_err() {
+            self.pos = start;
+        }
+        result
+    }
+}
+
+impl<'a> core::fmt::Debug for SliceWriter<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SliceWriter")
+            .field("written", &self.written())
+            .field("remaining", &self.remaining())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::MaybeUninit;
+
+    use super::{ByteSliceExt, SliceReader, SliceWriter};
+    use crate::{BigEndian, Error, ErrorKind, LittleEndian, Padding};
+
+    #[test]
+    fn advances() {
+        let data =
+            [0xFF, 1, 2, 3, 4, 5, 6, 0, 0, 0x80, 0x3F, 7, 8, 9, 10, 11, 12];
+        let mut buf = &data[..];
+        assert_eq!(Ok(-1), buf.read_i8());
+        assert_eq!(Ok(0x010203), buf.read_u24::<BigEndian>());
+        assert_eq!(Ok(0x060504), buf.read_uint::<LittleEndian>(3));
+        assert_eq!(Ok(1.0), buf.read_f32::<LittleEndian>());
+        assert_eq!(Ok([7, 8]), buf.read_array::<2>());
+        assert_eq!(Ok(()), buf.skip(1));
+        assert_eq!(Ok(0x0A0B0C), buf.read_int128::<BigEndian>(3));
+        assert!(buf.is_empty());
+        assert_eq!(Ok(&[][..]), buf.read_bytes(0));
+    }
+
+    #[test]
+    fn fixed_str() {
+        let mut buf = &b"a\0\xFFb  c   \0\0"[..];
+        assert_eq!(Ok("a"), buf.read_fixed_str(3, Padding::Nul));
+        assert_eq!(Ok("b  c"), buf.read_fixed_str(7, Padding::Space));
+        assert_eq!(Ok(""), buf.read_fixed_str(2, Padding::Nul));
+        assert_eq!(Ok(""), buf.read_fixed_str(0, Padding::Space));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn errors_leave_slice_unchanged() {
+        let mut buf = &[1, 0xFF, 3][..];
+        assert_eq!(
+            Err(Error::new(ErrorKind::BufferTooSmall {
+                needed: 4,
+                available: 3
+            })),
+            buf.read_u32::<BigEndian>()
+        );
+        assert!(buf.read_uint::<BigEndian>(4).is_err());
+        assert!(buf.read_wire::<[u16; 2], BigEndian>().is_err());
+        assert!(buf.skip(4).is_err());
+        assert_eq!(
+            Err(Error::new(ErrorKind::InvalidUtf8)),
+            buf.read_fixed_str(2, Padding::Nul)
+        );
+        assert!(buf.read_fixed_str(4, Padding::Nul).is_err());
+        assert_eq!(3, buf.len());
+        assert_eq!(Ok(0xFF01), buf.read_u16::<LittleEndian>());
+        assert_eq!(Ok(3), buf.read_u8());
+        assert!(buf.read_u8().is_err());
+    }
+
+    #[test]
+    fn writes() {
+        let mut buf = [0; 17];
+        let mut wtr = SliceWriter::new(&mut buf);
+        wtr.write_i8(-1).unwrap();
+        wtr.write_u24::<BigEndian>(0x010203).unwrap();
+        wtr.write_uint::<LittleEndian>(0x060504, 3).unwrap();
+        wtr.write_f32::<LittleEndian>(1.0).unwrap();
+        wtr.write_wire::<[u8; 2], BigEndian>(&[7, 8]).unwrap();
+        wtr.write_bytes(&[9]).unwrap();
+        wtr.write_int128::<BigEndian>(0x0A0B0C, 3).unwrap();
+        assert_eq!(17, wtr.position());
+        assert_eq!(0, wtr.remaining());
+        assert_eq!(Ok(()), wtr.write_bytes(&[]));
+        assert_eq!(
+            &[0xFF, 1, 2, 3, 4, 5, 6, 0, 0, 0x80, 0x3F, 7, 8, 9, 10, 11, 12],
+            wtr.into_written()
+        );
+    }
+
+    #[test]
+    fn write_errors_leave_writer_unchanged() {
+        let mut buf = [MaybeUninit::uninit(); 3];
+        let mut wtr = SliceWriter::new_uninit(&mut buf);
+        wtr.write_u8(1).unwrap();
+        assert_eq!(
+            Err(Error::new(ErrorKind::BufferTooSmall {
+                needed: 4,
+                available: 2
+            })),
+            wtr.write_u32::<BigEndian>(0)
+        );
+        assert!(wtr.write_uint::<BigEndian>(0, 3).is_err());
+        assert!(wtr.write_wire::<[u16; 2], BigEndian>(&[0, 0]).is_err());
+        assert!(wtr.write_bytes(&[0; 3]).is_err());
+        assert_eq!(&[1], wtr.written());
+        wtr.write_u16::<LittleEndian>(0x0302).unwrap();
+        assert!(wtr.write_u8(4).is_err());
+        assert_eq!(&[1, 2, 3], wtr.written());
+    }
+
+    #[test]
+    fn reader_tracks_position() {
+        let data = [0xFF, 1, 2, 3, 4, 5, 6, 0, 0, 0x80, 0x3F, b'a', b' '];
+        let mut rdr = SliceReader::new(&data);
+        assert_eq!(Ok(-1), rdr.read_i8());
+        assert_eq!(Ok(0x010203), rdr.read_u24::<BigEndian>());
+        assert_eq!(4, rdr.position());
+        assert_eq!(Ok(0x060504), rdr.read_uint::<LittleEndian>(3));
+        assert_eq!(Ok(1.0), rdr.read_f32::<LittleEndian>());
+        assert!(rdr.read_u32::<BigEndian>().is_err());
+        assert!(rdr.read_fixed_str(3, Padding::Space).is_err());
+        assert_eq!(11, rdr.position());
+        assert_eq!(Ok("a"), rdr.read_fixed_str(2, Padding::Space));
+        assert_eq!(0, rdr.remaining());
+
+        assert_eq!(Ok(()), rdr.set_position(1));
+        assert_eq!(Ok([1, 2]), rdr.read_array::<2>());
+        assert_eq!(Ok(0x0304), rdr.read_wire::<u16, BigEndian>());
+        assert!(rdr.set_position(14).is_err());
+        assert_eq!(5, rdr.position());
+        assert_eq!(&data[..], rdr.get_ref());
+    }
+}
@@ -0,0 +1,344 @@
+/*!
+Batched writes of numbers at scattered offsets.
+
+Patching many small values into a large file one `seek` and `write` at a time
+is slow, especially when the values are close together. A [`WriteBatch`]
+collects typed writes at arbitrary offsets and then applies them to any
+`Write + Seek` in offset order, merging writes that touch or overlap into a
+single `write_all` call and skipping seeks that would not move the cursor.
+
+# Examples
+
+```rust
+use std::io::Cursor;
+
+use byteorder::{sparse::WriteBatch, BigEndian, LittleEndian};
+
+let mut file = Cursor::new(vec![0; 16]);
+
+let mut batch = WriteBatch::new();
+batch.write_u32_at::<BigEndian>(12, 0xDEADBEEF);
+batch.write_u16_at::<LittleEndian>(0, 0x0102);
+batch.write_u16_at::<LittleEndian>(2, 0x0304);
+// The two adjacent writes at the start are merged into one.
+assert_eq!(2, batch.apply(&mut file).unwrap());
+
+assert_eq!(
+    file.into_inner(),
+    [2, 1, 4, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0xDE, 0xAD, 0xBE, 0xEF],
+);
+```
+*/
+
+use std::io::{self, Seek, SeekFrom, Write};
+
+use crate::ByteOrder;
+
+/// A batch of writes at arbitrary offsets, to be applied all at once.
+///
+/// Writes are recorded with the `write_*_at` methods, which never fail, and
+/// are applied with [`WriteBatch::apply`]. If two writes overlap, the one
+/// that was recorded last wins, regardless of their offsets.
+#[derive(Clone, Debug, Default)]
+pub struct WriteBatch {
+    /// The encoded bytes of every write, back to back.
+    data: Vec<u8>,
+    /// The offset of each write, along with its range in `data`, in the
+    /// order the writes were recorded.
+    writes: Vec<Pending>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Pending {
+    offset: u64,
+    start: usize,
+    len: usize,
+}
+
+impl Pending {
+    fn end(&self) -> u64 {
+        self.offset + self.len as u64
+    }
+}
+
+impl WriteBatch {
+    /// Creates an empty batch.
+    pub fn new() -> WriteBatch {
+        WriteBatch::default()
+    }
+
+    /// Returns the number of writes recorded in this batch.
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+
+    /// Returns true if no writes have been recorded in this batch.
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// Removes all writes from this batch, keeping its allocations.
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.writes.clear();
+    }
+
+    /// Records a write of `bytes` at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the end of the write, `offset + bytes.len()`, overflows a
+    /// `u64`.
+    pub fn write_bytes_at(&mut self, offset: u64, bytes: &[u8]) {
+        offset
+            .checked_add(bytes.len() as u64)
+            .expect("end of write overflows u64");
+        let start = self.data.len();
+        self.data.extend_from_slice(bytes);
+        self.writes.push(Pending { offset, start, len: bytes.len() });
+    }
+
+    /// Records a write of an unsigned 8 bit integer at `offset`.
+    pub fn write_u8_at(&mut self, offset: u64, n: u8) {
+        self.write_bytes_at(offset, &[n]);
+    }
+
+    /// Records a write of a signed 8 bit integer at `offset`.
+    pub fn write_i8_at(&mut self, offset: u64, n: i8) {
+        self.write_bytes_at(offset, &[n as u8]);
+    }
+
+    /// Records a write of an unsigned 16 bit integer at `offset`.
+    pub fn write_u16_at<E: ByteOrder>(&mut self, offset: u64, n: u16) {
+        let mut buf = [0; 2];
+        E::write_u16(&mut buf, n);
+        self.write_bytes_at(offset, &buf);
+    }
+
+    /// Records a write of a signed 16 bit integer at `offset`.
+    pub fn write_i16_at<E: ByteOrder>(&mut self, offset: u64, n: i16) {
+        let mut buf = [0; 2];
+        E::write_i16(&mut buf, n);
+        self.write_bytes_at(offset, &buf);
+    }
+
+    /// Records a write of an unsigned 32 bit integer at `offset`.
+    pub fn write_u32_at<E: ByteOrder>(&mut self, offset: u64, n: u32) {
+        let mut buf = [0; 4];
+        E::write_u32(&mut buf, n);
+        self.write_bytes_at(offset, &buf);
+    }
+
+    /// Records a write of a signed 32 bit integer at `offset`.
+    pub fn write_i32_at<E: ByteOrder>(&mut self, offset: u64, n: i32) {
+        let mut buf = [0; 4];
+        E::write_i32(&mut buf, n);
+        self.write_bytes_at(offset, &buf);
+    }
+
+    /// Records a write of an unsigned 64 bit integer at `offset`.
+    pub fn write_u64_at<E: ByteOrder>(&mut self, offset: u64, n: u64) {
+        let mut buf = [0; 8];
+        E::write_u64(&mut buf, n);
+        self.write_bytes_at(offset, &buf);
+    }
+
+    /// Records a write of a signed 64 bit integer at `offset`.
+    pub fn write_i64_at<E: ByteOrder>(&mut self, offset: u64, n: i64) {
+        let mut buf = [0; 8];
+        E::write_i64(&mut buf, n);
+        self.write_bytes_at(offset, &buf);
+    }
+
+    /// Records a write of an unsigned 128 bit integer at `offset`.
+    pub fn write_u128_at<E: ByteOrder>(&mut self, offset: u64, n: u128) {
+        let mut buf = [0; 16];
+        E::write_u128(&mut buf, n);
+        self.write_bytes_at(offset, &buf);
+    }
+
+    /// Records a write of a signed 128 bit integer at `offset`.
+    pub fn write_i128_at<E: ByteOrder>(&mut self, offset: u64, n: i128) {
+        let mut buf = [0; 16];
+        E::write_i128(&mut buf, n);
+        self.write_bytes_at(offset, &buf);
+    }
+
+    /// Records a write of a IEEE754 single-precision floating point number
+    /// at `offset`.
+    pub fn write_f32_at<E: ByteOrder>(&mut self, offset: u64, n: f32) {
+        let mut buf = [0; 4];
+        E::write_f32(&mut buf, n);
+        self.write_bytes_at(offset, &buf);
+    }
+
+    /// Records a write of a IEEE754 double-precision floating point number
+    /// at `offset`.
+    pub fn write_f64_at<E: ByteOrder>(&mut self, offset: u64, n: f64) {
+        let mut buf = [0; 8];
+        E::write_f64(&mut buf, n);
+        self.write_bytes_at(offset, &buf);
+    }
+
+    /// Applies every write in this batch to `wtr`, and returns the number
+    /// of contiguous runs that were written.
+    ///
+    /// Writes are applied in increasing order of offset. Writes that overlap
+    /// or are directly adjacent are merged into a single run, and each run
+    /// is written with one call to `write_all`. A seek is only issued when
+    /// the next run does not start where the previous one ended.
+    ///
+    /// The batch is left unchanged, so it can be applied again to another
+    /// writer.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Seek::seek`] and
+    /// [`Write::write_all`]. If an error occurs, an unspecified subset of the
+    /// runs may have been written.
+    ///
+    /// [`Seek::seek`]: https://doc.rust-lang.org/std/io/trait.Seek.html#tymethod.seek
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    pub fn apply<W: Write + Seek + ?Sized>(
+        &self,
+        wtr: &mut W,
+    ) -> io::Result<usize> {
+        // Sorting indices (rather than the writes themselves) preserves the
+        // recording order, which decides who wins when writes overlap.
+        let mut order: Vec<usize> = (0..self.writes.len()).collect();
+        order.sort_by_key(|&i| self.writes[i].offset);
+
+        let mut runs = 0;
+        let mut pos = None;
+        let mut run = vec![];
+        let mut i = 0;
+        while i < order.len() {
+            let start = self.writes[order[i]].offset;
+            let mut end = self.writes[order[i]].end();
+            let mut j = i + 1;
+            while j < order.len() && self.writes[order[j]].offset <= end {
+                end = end.max(self.writes[order[j]].end());
+                j += 1;
+            }
+
+            let group = &mut order[i..j];
+            group.sort_unstable();
+            run.clear();
+            run.resize((end - start) as usize, 0);
+            for &k in group.iter() {
+                let w = self.writes[k];
+                let at = (w.offset - start) as usize;
+                run[at..at + w.len]
+                    .copy_from_slice(&self.data[w.start..w.start + w.len]);
+            }
+
+            if pos != Some(start) {
+                wtr.seek(SeekFrom::Start(start))?;
+            }
+            wtr.write_all(&run)?;
+            pos = Some(end);
+            runs += 1;
+            i = j;
+        }
+        Ok(runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor, Seek, SeekFrom, Write};
+
+    use super::WriteBatch;
+    use crate::{BigEndian, LittleEndian};
+
+    /// A writer that records the operations performed on it.
+    struct Recorder {
+        inner: Cursor<Vec<u8>>,
+        ops: Vec<String>,
+    }
+
+    impl Write for Recorder {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.ops.push(format!("write {}", buf.len()));
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Seek for Recorder {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.ops.push(format!("seek {:?}", pos));
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    fn coalesces_and_skips_seeks() {
+        let mut batch = WriteBatch::new();
+        batch.write_u32_at::<BigEndian>(8, 0x0506_0708);
+        batch.write_u32_at::<BigEndian>(4, 0x0102_0304);
+        batch.write_u8_at(20, 0xFF);
+        batch.write_u16_at::<LittleEndian>(12, 0x0A09);
+        assert_eq!(4, batch.len());
+
+        let mut wtr =
+            Recorder { inner: Cursor::new(vec![0; 24]), ops: vec![] };
+        assert_eq!(2, batch.apply(&mut wtr).unwrap());
+        assert_eq!(
+            wtr.ops,
+            vec!["seek Start(4)", "write 10", "seek Start(20)", "write 1"],
+        );
+        assert_eq!(
+            &wtr.inner.get_ref()[..],
+            &[
+                0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0, 0, 0, 0, 0, 0,
+                0xFF, 0, 0, 0
+            ][..],
+        );
+    }
+
+    #[test]
+    fn later_writes_win() {
+        let mut batch = WriteBatch::new();
+        batch.write_u8_at(3, 0xAA);
+        batch.write_u32_at::<BigEndian>(0, 0x0102_0304);
+        batch.write_u16_at::<BigEndian>(2, 0xBBCC);
+        batch.write_u8_at(1, 0xDD);
+
+        let mut wtr = Cursor::new(vec![]);
+        assert_eq!(1, batch.apply(&mut wtr).unwrap());
+        assert_eq!(wtr.into_inner(), [0x01, 0xDD, 0xBB, 0xCC]);
+    }
+
+    #[test]
+    fn empty_and_clear() {
+        let mut wtr = Cursor::new(vec![1, 2, 3]);
+        let mut batch = WriteBatch::new();
+        assert!(batch.is_empty());
+        assert_eq!(0, batch.apply(&mut wtr).unwrap());
+
+        batch.write_i64_at::<LittleEndian>(100, -1);
+        batch.clear();
+        assert!(batch.is_empty());
+        assert_eq!(0, batch.apply(&mut wtr).unwrap());
+        assert_eq!(wtr.into_inner(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn seek_past_end() {
+        let mut batch = WriteBatch::new();
+        batch.write_f32_at::<BigEndian>(4, 1.0);
+        let mut wtr = Cursor::new(vec![]);
+        batch.apply(&mut wtr).unwrap();
+        assert_eq!(wtr.into_inner(), [0, 0, 0, 0, 0x3F, 0x80, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn offset_overflow() {
+        WriteBatch::new().write_u16_at::<BigEndian>(u64::MAX, 0);
+    }
+}
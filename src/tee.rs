@@ -0,0 +1,440 @@
+/*!
+A writer that mirrors typed writes into a human readable log.
+
+[`TeeWriter`] forwards every byte to an underlying writer, and for every
+number written through its typed methods, also writes a line to a secondary
+log writer describing the offset, type, byte order, value and encoded bytes.
+This is useful for diagnosing binary serializers.
+*/
+
+use std::{
+    fmt,
+    io::{self, Result, Write},
+};
+
+use crate::ByteOrder;
+
+/// A writer that forwards bytes to a writer and annotates them in a log.
+///
+/// The typed methods on `TeeWriter` (like [`TeeWriter::write_u32`]) shadow
+/// the methods of the same name on [`WriteBytesExt`](crate::WriteBytesExt),
+/// so only calls that resolve to these inherent methods are annotated with
+/// their type and byte order. Code that is generic over `W: io::Write` goes
+/// through `WriteBytesExt` instead, and its bytes, like everything else
+/// written through the plain `io::Write` implementation, are logged as raw
+/// `bytes`.
+///
+/// A failure to write to the log never fails a write to the underlying
+/// writer, since the data has already been written by then. Instead, the
+/// first such error is kept and returned by the next call to
+/// [`flush`](Write::flush) or [`TeeWriter::take_log_error`].
+///
+/// If the underlying writer fails part way through a typed value, the bytes
+/// of it that were written are still logged and counted in the offset, so
+/// that its log line has fewer bytes than the type needs.
+///
+/// Each log line has the form:
+///
+/// ```text
+/// <offset in hex>  <type> <order> <value> [<encoded bytes in hex>]
+/// ```
+///
+/// The order is `BE` or `LE`, or `--` for single bytes and raw writes.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{tee::TeeWriter, BigEndian, LittleEndian};
+///
+/// let mut tee = TeeWriter::new(vec![], vec![]);
+/// tee.write_u16::<BigEndian>(517).unwrap();
+/// tee.write_i32::<LittleEndian>(-2).unwrap();
+///
+/// let (data, log) = tee.into_inner();
+/// assert_eq!(data, vec![2, 5, 0xFE, 0xFF, 0xFF, 0xFF]);
+/// assert_eq!(
+///     String::from_utf8(log).unwrap(),
+///     "00000000  u16 BE 517 [02 05]\n\
+///      00000002  i32 LE -2 [fe ff ff ff]\n",
+/// );
+/// ```
+#[derive(Debug)]
+pub struct TeeWriter<W, L> {
+    wtr: W,
+    log: L,
+    offset: u64,
+    log_error: Option<io::Error>,
+}
+
+impl<W: Write, L: Write> TeeWriter<W, L> {
+    /// Creates a new writer that writes data to `wtr` and annotations to
+    /// `log`.
+    pub fn new(wtr: W, log: L) -> TeeWriter<W, L> {
+        TeeWriter { wtr, log, offset: 0, log_error: None }
+    }
+
+    /// Returns the number of bytes written to the underlying writer so far.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns and clears the first error that occurred while writing to
+    /// the log, if any.
+    pub fn take_log_error(&mut self) -> Option<io::Error> {
+        self.log_error.take()
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.wtr
+    }
+
+    /// Returns a reference to the log writer.
+    pub fn log_ref(&self) -> &L {
+        &self.log
+    }
+
+    /// Unwraps this `TeeWriter`, returning the underlying writer and the log
+    /// writer.
+    pub fn into_inner(self) -> (W, L) {
+        (self.wtr, self.log)
+    }
+
+    /// Writes an unsigned 8 bit integer and logs it.
+    pub fn write_u8(&mut self, n: u8) -> Result<()> {
+        self.emit("u8", "--", &n, &[n])
+    }
+
+    /// Writes a signed 8 bit integer and logs it.
+    pub fn write_i8(&mut self, n: i8) -> Result<()> {
+        self.emit("i8", "--", &n, &[n as u8])
+    }
+
+    /// Writes an unsigned 16 bit integer and logs it.
+    pub fn write_u16<E: ByteOrder>(&mut self, n: u16) -> Result<()> {
+        let mut buf = [0; 2];
+        E::write_u16(&mut buf, n);
+        self.emit("u16", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes a signed 16 bit integer and logs it.
+    pub fn write_i16<E: ByteOrder>(&mut self, n: i16) -> Result<()> {
+        let mut buf = [0; 2];
+        E::write_i16(&mut buf, n);
+        self.emit("i16", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes an unsigned 24 bit integer and logs it.
+    pub fn write_u24<E: ByteOrder>(&mut self, n: u32) -> Result<()> {
+        let mut buf = [0; 3];
+        E::write_u24(&mut buf, n);
+        self.emit("u24", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes a signed 24 bit integer and logs it.
+    pub fn write_i24<E: ByteOrder>(&mut self, n: i32) -> Result<()> {
+        let mut buf = [0; 3];
+        E::write_i24(&mut buf, n);
+        self.emit("i24", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes an unsigned 32 bit integer and logs it.
+    pub fn write_u32<E: ByteOrder>(&mut self, n: u32) -> Result<()> {
+        let mut buf = [0; 4];
+        E::write_u32(&mut buf, n);
+        self.emit("u32", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes a signed 32 bit integer and logs it.
+    pub fn write_i32<E: ByteOrder>(&mut self, n: i32) -> Result<()> {
+        let mut buf = [0; 4];
+        E::write_i32(&mut buf, n);
+        self.emit("i32", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes an unsigned 48 bit integer and logs it.
+    pub fn write_u48<E: ByteOrder>(&mut self, n: u64) -> Result<()> {
+        let mut buf = [0; 6];
+        E::write_u48(&mut buf, n);
+        self.emit("u48", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes a signed 48 bit integer and logs it.
+    pub fn write_i48<E: ByteOrder>(&mut self, n: i64) -> Result<()> {
+        let mut buf = [0; 6];
+        E::write_i48(&mut buf, n);
+        self.emit("i48", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes an unsigned 64 bit integer and logs it.
+    pub fn write_u64<E: ByteOrder>(&mut self, n: u64) -> Result<()> {
+        let mut buf = [0; 8];
+        E::write_u64(&mut buf, n);
+        self.emit("u64", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes a signed 64 bit integer and logs it.
+    pub fn write_i64<E: ByteOrder>(&mut self, n: i64) -> Result<()> {
+        let mut buf = [0; 8];
+        E::write_i64(&mut buf, n);
+        self.emit("i64", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes an unsigned 128 bit integer and logs it.
+    pub fn write_u128<E: ByteOrder>(&mut self, n: u128) -> Result<()> {
+        let mut buf = [0; 16];
+        E::write_u128(&mut buf, n);
+        self.emit("u128", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes a signed 128 bit integer and logs it.
+    pub fn write_i128<E: ByteOrder>(&mut self, n: i128) -> Result<()> {
+        let mut buf = [0; 16];
+        E::write_i128(&mut buf, n);
+        self.emit("i128", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes an unsigned n-bytes integer and logs it.
+    ///
+    /// # Panics
+    ///
+    /// If the given integer is not representable in the given number of
+    /// bytes, this method panics. If `nbytes > 8`, this method panics.
+    pub fn write_uint<E: ByteOrder>(
+        &mut self,
+        n: u64,
+        nbytes: usize,
+    ) -> Result<()> {
+        let mut buf = [0; 8];
+        E::write_uint(&mut buf, n, nbytes);
+        self.emit("uint", order_name::<E>(), &n, &buf[..nbytes])
+    }
+
+    /// Writes a signed n-bytes integer and logs it.
+    ///
+    /// # Panics
+    ///
+    /// If the given integer is not representable in the given number of
+    /// bytes, this method panics. If `nbytes > 8`, this method panics.
+    pub fn write_int<E: ByteOrder>(
+        &mut self,
+        n: i64,
+        nbytes: usize,
+    ) -> Result<()> {
+        let mut buf = [0; 8];
+        E::write_int(&mut buf, n, nbytes);
+        self.emit("int", order_name::<E>(), &n, &buf[..nbytes])
+    }
+
+    /// Writes a IEEE754 single-precision floating point number and logs it.
+    pub fn write_f32<E: ByteOrder>(&mut self, n: f32) -> Result<()> {
+        let mut buf = [0; 4];
+        E::write_f32(&mut buf, n);
+        self.emit("f32", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes a IEEE754 double-precision floating point number and logs it.
+    pub fn write_f64<E: ByteOrder>(&mut self, n: f64) -> Result<()> {
+        let mut buf = [0; 8];
+        E::write_f64(&mut buf, n);
+        self.emit("f64", order_name::<E>(), &n, &buf)
+    }
+
+    /// Writes `bytes` to the underlying writer and logs a line for them.
+    ///
+    /// If the underlying writer fails part way through, the line only lists
+    /// the bytes that were written, and the offset only advances past them.
+    fn emit(
+        &mut self,
+        ty: &str,
+        order: &str,
+        value: &dyn fmt::Display,
+        bytes: &[u8],
+    ) -> Result<()> {
+        let mut written = 0;
+        let result = loop {
+            if written == bytes.len() {
+                break Ok(());
+            }
+            match self.wtr.write(&bytes[written..]) {
+                Ok(0) => {
+                    break Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    ));
+                }
+                Ok(n) => written += n,
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => break Err(err),
+            }
+        };
+        if written > 0 {
+            self.log_line(ty, order, value, &bytes[..written]);
+        }
+        result
+    }
+
+    /// Logs a line for `bytes`, which were just written at the current
+    /// offset, and advances the offset past them.
+    ///
+    /// If writing the line fails, the error is kept for a later `flush`.
+    fn log_line(
+        &mut self,
+        ty: &str,
+        order: &str,
+        value: &dyn fmt::Display,
+        bytes: &[u8],
+    ) {
+        let offset = self.offset;
+        self.offset += bytes.len() as u64;
+        let result =
+            write!(self.log, "{:08x}  {} {} {} ", offset, ty, order, value)
+                .and_then(|()| write_hex(&mut self.log, bytes))
+                .and_then(|()| writeln!(self.log));
+        if let Err(err) = result {
+            self.log_error.get_or_insert(err);
+        }
+    }
+}
+
+impl<W: Write, L: Write> Write for TeeWriter<W, L> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let n = self.wtr.write(buf)?;
+        if n > 0 {
+            self.log_line("bytes", "--", &n, &buf[..n]);
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.wtr.flush()?;
+        if let Some(err) = self.log_error.take() {
+            return Err(err);
+        }
+        self.log.flush()
+    }
+}
+
+/// Writes `bytes` as space separated lowercase hex, in square brackets.
+fn write_hex<L: Write>(log: &mut L, bytes: &[u8]) -> io::Result<()> {
+    log.write_all(b"[")?;
+    for (i, b) in bytes.iter().enumerate() {
+        if i > 0 {
+            log.write_all(b" ")?;
+        }
+        write!(log, "{:02x}", b)?;
+    }
+    log.write_all(b"]")
+}
+
+/// Returns a short name for the byte order `E`.
+fn order_name<E: ByteOrder>() -> &'static str {
+    if E::read_u16(&[0, 1]) == 1 {
+        "BE"
+    } else {
+        "LE"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+
+    use super::TeeWriter;
+    use crate::{BigEndian, LittleEndian, WriteBytesExt};
+
+    #[test]
+    fn mixed_writes() {
+        let mut tee = TeeWriter::new(vec![], vec![]);
+        tee.write_u8(7).unwrap();
+        tee.write_all(b"ab").unwrap();
+        tee.write_uint::<LittleEndian>(0x010203, 3).unwrap();
+        tee.write_f32::<BigEndian>(1.5).unwrap();
+        assert_eq!(10, tee.offset());
+
+        let (data, log) = tee.into_inner();
+        assert_eq!(data, b"\x07ab\x03\x02\x01\x3f\xc0\x00\x00");
+        assert_eq!(
+            String::from_utf8(log).unwrap(),
+            "00000000  u8 -- 7 [07]\n\
+             00000001  bytes -- 2 [61 62]\n\
+             00000003  uint LE 66051 [03 02 01]\n\
+             00000006  f32 BE 1.5 [3f c0 00 00]\n",
+        );
+    }
+
+    #[test]
+    fn extension_trait_still_usable() {
+        // Going through the extension trait bypasses the typed annotations
+        // and logs the raw bytes instead.
+        let mut tee = TeeWriter::new(vec![], vec![]);
+        WriteBytesExt::write_u16::<BigEndian>(&mut tee, 1).unwrap();
+        let (data, log) = tee.into_inner();
+        assert_eq!(data, [0, 1]);
+        assert_eq!(
+            String::from_utf8(log).unwrap(),
+            "00000000  bytes -- 2 [00 01]\n"
+        );
+    }
+
+    #[test]
+    fn log_errors_are_deferred() {
+        struct FailingLog;
+
+        impl Write for FailingLog {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::Other, "log is full"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut tee = TeeWriter::new(vec![], FailingLog);
+        assert_eq!(2, tee.write(b"ab").unwrap());
+        tee.write_u16::<BigEndian>(3).unwrap();
+        assert_eq!(4, tee.offset());
+        assert_eq!(tee.get_ref(), b"ab\x00\x03");
+
+        let err = tee.flush().unwrap_err();
+        assert_eq!("log is full", err.to_string());
+        assert!(tee.take_log_error().is_none());
+        tee.flush().unwrap();
+    }
+
+    #[test]
+    fn partial_writes_are_logged() {
+        // Accepts at most two bytes per call and then fails once full.
+        struct ShortWriter(Vec<u8>);
+
+        impl Write for ShortWriter {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                if self.0.len() >= 3 {
+                    return Err(io::Error::new(io::ErrorKind::Other, "full"));
+                }
+                let n = buf.len().min(2);
+                self.0.extend_from_slice(&buf[..n]);
+                Ok(n)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut tee = TeeWriter::new(ShortWriter(vec![]), vec![]);
+        tee.write_u8(9).unwrap();
+        let err = tee.write_u32::<BigEndian>(0x01020304).unwrap_err();
+        assert_eq!("full", err.to_string());
+        assert_eq!(3, tee.offset());
+
+        let (data, log) = tee.into_inner();
+        assert_eq!(data.0, [9, 1, 2]);
+        assert_eq!(
+            String::from_utf8(log).unwrap(),
+            "00000000  u8 -- 9 [09]\n\
+             00000001  u32 BE 16909060 [01 02]\n",
+        );
+    }
+}
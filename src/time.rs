@@ -0,0 +1,577 @@
+/*!
+Reading and writing `timeval` and `timespec` style timestamps.
+
+Kernel interfaces, packet captures and many log formats store a point in time
+as a pair of integers: whole seconds since the Unix epoch, followed by a
+subsecond part in either microseconds (`struct timeval`) or nanoseconds
+(`struct timespec`). The width of each field depends on the ABI that wrote
+it. For example, `time_t` is 32 bits on older and embedded targets but 64
+bits almost everywhere else, and the subsecond field is a C `long`.
+
+A [`TimeLayout`] describes one such variant. It can decode a timestamp into a
+[`Duration`] or a [`SystemTime`], and encode one back, in any byte order.
+
+# Examples
+
+Decode a 64-bit little-endian `struct timespec`:
+
+```rust
+use std::time::{Duration, UNIX_EPOCH};
+
+use byteorder::{time::TimeLayout, ByteOrder, LittleEndian};
+
+let mut buf = [0; 16];
+LittleEndian::write_i64(&mut buf[..8], 1_500_000_000);
+LittleEndian::write_i64(&mut buf[8..], 250_000_000);
+
+let layout = TimeLayout::TIMESPEC64;
+let time = layout.read_system_time::<LittleEndian>(&buf).unwrap();
+assert_eq!(UNIX_EPOCH + Duration::new(1_500_000_000, 250_000_000), time);
+```
+*/
+
+use std::{
+    io,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{ByteOrder, Error, ErrorKind};
+
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+/// The width of a single integer field in a timestamp.
+///
+/// All fields are treated as signed, since both `time_t` and C's `long` are
+/// signed.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FieldWidth {
+    /// A 32 bit field.
+    W32,
+    /// A 64 bit field.
+    W64,
+}
+
+impl FieldWidth {
+    /// Returns the size of this field in bytes.
+    #[inline]
+    pub const fn size(self) -> usize {
+        match self {
+            FieldWidth::W32 => 4,
+            FieldWidth::W64 => 8,
+        }
+    }
+
+    fn read<E: ByteOrder>(self, buf: &[u8]) -> i64 {
+        match self {
+            FieldWidth::W32 => i64::from(E::read_i32(buf)),
+            FieldWidth::W64 => E::read_i64(buf),
+        }
+    }
+
+    fn write<E: ByteOrder>(self, buf: &mut [u8], n: i64) -> Result<(), Error> {
+        match self {
+            FieldWidth::W32 => {
+                let n = i32::try_from(n)
+                    .map_err(|_| Error::new(ErrorKind::OutOfRange))?;
+                E::write_i32(buf, n);
+            }
+            FieldWidth::W64 => E::write_i64(buf, n),
+        }
+        Ok(())
+    }
+}
+
+/// The unit of the subsecond field in a timestamp.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SubsecUnit {
+    /// Microseconds, as in `struct timeval`.
+    Micros,
+    /// Nanoseconds, as in `struct timespec`.
+    Nanos,
+}
+
+impl SubsecUnit {
+    /// Returns the number of nanoseconds in one of this unit.
+    #[inline]
+    const fn nanos(self) -> u32 {
+        match self {
+            SubsecUnit::Micros => 1_000,
+            SubsecUnit::Nanos => 1,
+        }
+    }
+
+    /// Returns the number of this unit in one second.
+    #[inline]
+    const fn per_sec(self) -> u32 {
+        NANOS_PER_SEC / self.nanos()
+    }
+}
+
+/// The layout of a timestamp made of a seconds field followed by a
+/// subsecond field.
+///
+/// Constants are provided for the common `timeval` and `timespec` variants.
+/// Other combinations can be built with [`TimeLayout::new`].
+///
+/// When reading, the seconds field may be negative for times before the
+/// Unix epoch, but the subsecond field must be in the range `0` to one
+/// second (exclusive), as the C library requires. An error is returned
+/// otherwise.
+///
+/// When writing, subsecond precision finer than the layout's unit is
+/// truncated, and an error is returned if the seconds do not fit in the
+/// seconds field.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TimeLayout {
+    seconds: FieldWidth,
+    subsec: FieldWidth,
+    unit: SubsecUnit,
+}
+
+impl TimeLayout {
+    /// A `struct timeval` with 32 bit fields, as used by 32 bit targets and
+    /// the classic pcap record header.
+    pub const TIMEVAL32: TimeLayout =
+        TimeLayout::new(FieldWidth::W32, FieldWidth::W32, SubsecUnit::Micros);
+
+    /// A `struct timeval` with 64 bit fields, as used by 64 bit Unix
+    /// targets.
+    pub const TIMEVAL64: TimeLayout =
+        TimeLayout::new(FieldWidth::W64, FieldWidth::W64, SubsecUnit::Micros);
+
+    /// A `struct timespec` with 32 bit fields.
+    pub const TIMESPEC32: TimeLayout =
+        TimeLayout::new(FieldWidth::W32, FieldWidth::W32, SubsecUnit::Nanos);
+
+    /// A `struct timespec` with 64 bit fields, as used by 64 bit Unix
+    /// targets.
+    pub const TIMESPEC64: TimeLayout =
+        TimeLayout::new(FieldWidth::W64, FieldWidth::W64, SubsecUnit::Nanos);
+
+    /// Creates a new layout with the given field widths and subsecond unit.
+    ///
+    /// # Examples
+    ///
+    /// A 64 bit `time_t` paired with a 32 bit `long`, as used by 32 bit
+    /// targets that adopted 64 bit time:
+    ///
+    /// ```rust
+    /// use byteorder::time::{FieldWidth, SubsecUnit, TimeLayout};
+    ///
+    /// let layout =
+    ///     TimeLayout::new(FieldWidth::W64, FieldWidth::W32, SubsecUnit::Nanos);
+    /// assert_eq!(12, layout.size());
+    /// ```
+    #[inline]
+    pub const fn new(
+        seconds: FieldWidth,
+        subsec: FieldWidth,
+        unit: SubsecUnit,
+    ) -> TimeLayout {
+        TimeLayout { seconds, subsec, unit }
+    }
+
+    /// Returns the total size of this layout in bytes.
+    #[inline]
+    pub const fn size(&self) -> usize {
+        self.seconds.size() + self.subsec.size()
+    }
+
+    /// Returns the width of the seconds field.
+    #[inline]
+    pub const fn seconds(&self) -> FieldWidth {
+        self.seconds
+    }
+
+    /// Returns the width of the subsecond field.
+    #[inline]
+    pub const fn subsec(&self) -> FieldWidth {
+        self.subsec
+    }
+
+    /// Returns the unit of the subsecond field.
+    #[inline]
+    pub const fn unit(&self) -> SubsecUnit {
+        self.unit
+    }
+
+    /// Reads a timestamp as a duration.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error when `buf` is shorter than
+    /// [`TimeLayout::size`], when the seconds are negative or when the
+    /// subsecond field is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use byteorder::{time::TimeLayout, BigEndian};
+    ///
+    /// let buf = [0, 0, 0, 5, 0, 0, 0x01, 0xF4];
+    /// let d = TimeLayout::TIMEVAL32.read_duration::<BigEndian>(&buf).unwrap();
+    /// assert_eq!(Duration::new(5, 500_000), d);
+    /// ```
+    pub fn read_duration<E: ByteOrder>(
+        &self,
+        buf: &[u8],
+    ) -> Result<Duration, Error> {
+        let (secs, nanos) = self.read_parts::<E>(buf)?;
+        let secs = u64::try_from(secs)
+            .map_err(|_| Error::new(ErrorKind::OutOfRange))?;
+        Ok(Duration::new(secs, nanos))
+    }
+
+    /// Writes a duration as a timestamp.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error when `buf` is shorter than
+    /// [`TimeLayout::size`], or when the seconds do not fit in the seconds
+    /// field.
+    pub fn write_duration<E: ByteOrder>(
+        &self,
+        buf: &mut [u8],
+        d: Duration,
+    ) -> Result<(), Error> {
+        let secs = i64::try_from(d.as_secs())
+            .map_err(|_| Error::new(ErrorKind::OutOfRange))?;
+        self.write_parts::<E>(buf, secs, d.subsec_nanos())
+    }
+
+    /// Reads a timestamp as a point in time, relative to the Unix epoch.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error when `buf` is shorter than
+    /// [`TimeLayout::size`], when the subsecond field is out of range or when
+    /// the time cannot be represented by `SystemTime` on this platform.
+    pub fn read_system_time<E: ByteOrder>(
+        &self,
+        buf: &[u8],
+    ) -> Result<SystemTime, Error> {
+        let (secs, nanos) = self.read_parts::<E>(buf)?;
+        let time = if secs >= 0 {
+            UNIX_EPOCH.checked_add(Duration::new(secs as u64, nanos))
+        } else {
+            // The subsecond part always counts forward, so a time before the
+            // epoch is `secs` whole seconds back and then `nanos` forward.
+            UNIX_EPOCH
+                .checked_sub(Duration::from_secs(secs.unsigned_abs()))
+                .and_then(|t| t.checked_add(Duration::new(0, nanos)))
+        };
+        time.ok_or_else(|| Error::new(ErrorKind::OutOfRange))
+    }
+
+    /// Writes a point in time as a timestamp, relative to the Unix epoch.
+    ///
+    /// Times before the epoch are written with negative seconds and a
+    /// non-negative subsecond part, as the C library does.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error when `buf` is shorter than
+    /// [`TimeLayout::size`], or when the seconds do not fit in the seconds
+    /// field.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// use byteorder::{time::TimeLayout, LittleEndian};
+    ///
+    /// let layout = TimeLayout::TIMEVAL32;
+    /// let mut buf = [0; 8];
+    ///
+    /// let time = UNIX_EPOCH - Duration::from_millis(1500);
+    /// layout.write_system_time::<LittleEndian>(&mut buf, time).unwrap();
+    /// assert_eq!(buf, [0xFE, 0xFF, 0xFF, 0xFF, 0x20, 0xA1, 0x07, 0x00]);
+    /// assert_eq!(time, layout.read_system_time::<LittleEndian>(&buf).unwrap());
+    ///
+    /// // Doesn't fit in a 32 bit time_t.
+    /// let time = UNIX_EPOCH + Duration::from_secs(1 << 31);
+    /// assert!(layout.write_system_time::<LittleEndian>(&mut buf, time).is_err());
+    /// ```
+    pub fn write_system_time<E: ByteOrder>(
+        &self,
+        buf: &mut [u8],
+        time: SystemTime,
+    ) -> Result<(), Error> {
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(d) => self.write_duration::<E>(buf, d),
+            Err(err) => {
+                let d = err.duration();
+                let mut secs = i64::try_from(d.as_secs())
+                    .map_err(|_| Error::new(ErrorKind::OutOfRange))?;
+                secs = -secs;
+                let mut nanos = d.subsec_nanos();
+                if nanos > 0 {
+                    secs -= 1;
+                    nanos = NANOS_PER_SEC - nanos;
+                }
+                self.write_parts::<E>(buf, secs, nanos)
+            }
+        }
+    }
+
+    /// Reads a timestamp as a duration from `rdr`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// timestamp is invalid, as described in [`TimeLayout::read_duration`],
+    /// an error of kind `InvalidData` is returned.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    pub fn read_duration_from<E: ByteOrder, R: io::Read + ?Sized>(
+        &self,
+        rdr: &mut R,
+    ) -> io::Result<Duration> {
+        let mut buf = [0; 16];
+        let buf = &mut buf[..self.size()];
+        rdr.read_exact(buf)?;
+        Ok(self.read_duration::<E>(buf)?)
+    }
+
+    /// Writes a duration as a timestamp to `wtr`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If the
+    /// duration does not fit in this layout, an error of kind `InvalidData`
+    /// is returned and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    pub fn write_duration_to<E: ByteOrder, W: io::Write + ?Sized>(
+        &self,
+        wtr: &mut W,
+        d: Duration,
+    ) -> io::Result<()> {
+        let mut buf = [0; 16];
+        let buf = &mut buf[..self.size()];
+        self.write_duration::<E>(buf, d)?;
+        wtr.write_all(buf)
+    }
+
+    /// Reads a timestamp as a point in time from `rdr`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Read::read_exact`]. If the
+    /// timestamp is invalid, as described in
+    /// [`TimeLayout::read_system_time`], an error of kind `InvalidData` is
+    /// returned.
+    ///
+    /// [`Read::read_exact`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.read_exact
+    pub fn read_system_time_from<E: ByteOrder, R: io::Read + ?Sized>(
+        &self,
+        rdr: &mut R,
+    ) -> io::Result<SystemTime> {
+        let mut buf = [0; 16];
+        let buf = &mut buf[..self.size()];
+        rdr.read_exact(buf)?;
+        Ok(self.read_system_time::<E>(buf)?)
+    }
+
+    /// Writes a point in time as a timestamp to `wtr`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`Write::write_all`]. If the
+    /// time does not fit in this layout, an error of kind `InvalidData` is
+    /// returned and nothing is written.
+    ///
+    /// [`Write::write_all`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all
+    pub fn write_system_time_to<E: ByteOrder, W: io::Write + ?Sized>(
+        &self,
+        wtr: &mut W,
+        time: SystemTime,
+    ) -> io::Result<()> {
+        let mut buf = [0; 16];
+        let buf = &mut buf[..self.size()];
+        self.write_system_time::<E>(buf, time)?;
+        wtr.write_all(buf)
+    }
+
+    /// Reads the raw seconds and the subsecond part, converted to
+    /// nanoseconds.
+    fn read_parts<E: ByteOrder>(
+        &self,
+        buf: &[u8],
+    ) -> Result<(i64, u32), Error> {
+        Error::check_len(self.size(), buf.len())?;
+        let (secs, subsec) = buf.split_at(self.seconds.size());
+        let secs = self.seconds.read::<E>(secs);
+        let subsec = self.subsec.read::<E>(subsec);
+        if subsec < 0 || subsec >= i64::from(self.unit.per_sec()) {
+            return Err(Error::new(ErrorKind::OutOfRange));
+        }
+        Ok((secs, subsec as u32 * self.unit.nanos()))
+    }
+
+    /// Writes the raw seconds and the subsecond part, given in nanoseconds.
+    fn write_parts<E: ByteOrder>(
+        &self,
+        buf: &mut [u8],
+        secs: i64,
+        nanos: u32,
+    ) -> Result<(), Error> {
+        Error::check_len(self.size(), buf.len())?;
+        let (secs_buf, subsec_buf) = buf.split_at_mut(self.seconds.size());
+        // Check the seconds first, so that nothing is written on failure.
+        // The subsecond part always fits in 32 bits.
+        self.seconds.write::<E>(secs_buf, secs)?;
+        let subsec = i64::from(nanos / self.unit.nanos());
+        self.subsec.write::<E>(subsec_buf, subsec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{FieldWidth, SubsecUnit, TimeLayout};
+    use crate::{BigEndian, ByteOrder, ErrorKind, LittleEndian};
+
+    #[test]
+    fn sizes() {
+        assert_eq!(8, TimeLayout::TIMEVAL32.size());
+        assert_eq!(16, TimeLayout::TIMEVAL64.size());
+        assert_eq!(8, TimeLayout::TIMESPEC32.size());
+        assert_eq!(16, TimeLayout::TIMESPEC64.size());
+        let mixed = TimeLayout::new(
+            FieldWidth::W64,
+            FieldWidth::W32,
+            SubsecUnit::Micros,
+        );
+        assert_eq!(12, mixed.size());
+    }
+
+    #[test]
+    fn duration_roundtrip() {
+        let layouts = [
+            TimeLayout::TIMEVAL32,
+            TimeLayout::TIMEVAL64,
+            TimeLayout::TIMESPEC32,
+            TimeLayout::TIMESPEC64,
+        ];
+        for layout in layouts.iter() {
+            let mut buf = [0; 16];
+            let d = Duration::new(1_234_567, 890_123_000);
+            layout.write_duration::<BigEndian>(&mut buf, d).unwrap();
+            assert_eq!(d, layout.read_duration::<BigEndian>(&buf).unwrap());
+            layout.write_duration::<LittleEndian>(&mut buf, d).unwrap();
+            assert_eq!(d, layout.read_duration::<LittleEndian>(&buf).unwrap());
+        }
+    }
+
+    #[test]
+    fn truncates_to_micros() {
+        let mut buf = [0; 8];
+        let d = Duration::new(1, 999_999_999);
+        TimeLayout::TIMEVAL32
+            .write_duration::<BigEndian>(&mut buf, d)
+            .unwrap();
+        assert_eq!(999_999, BigEndian::read_i32(&buf[4..]));
+        let got = TimeLayout::TIMEVAL32.read_duration::<BigEndian>(&buf);
+        assert_eq!(Duration::new(1, 999_999_000), got.unwrap());
+    }
+
+    #[test]
+    fn before_epoch() {
+        let layout = TimeLayout::TIMESPEC64;
+        let mut buf = [0; 16];
+        let time = UNIX_EPOCH - Duration::new(10, 250_000_000);
+        layout.write_system_time::<BigEndian>(&mut buf, time).unwrap();
+        assert_eq!(-11, BigEndian::read_i64(&buf));
+        assert_eq!(750_000_000, BigEndian::read_i64(&buf[8..]));
+        assert_eq!(time, layout.read_system_time::<BigEndian>(&buf).unwrap());
+
+        let err = layout.read_duration::<BigEndian>(&buf).unwrap_err();
+        assert_eq!(&ErrorKind::OutOfRange, err.kind());
+    }
+
+    #[test]
+    fn invalid_subsec() {
+        let mut buf = [0; 8];
+        BigEndian::write_i32(&mut buf[4..], 1_000_000);
+        let err = TimeLayout::TIMEVAL32
+            .read_duration::<BigEndian>(&buf)
+            .unwrap_err();
+        assert_eq!(&ErrorKind::OutOfRange, err.kind());
+        // The same value is fine when it is in nanoseconds.
+        assert!(TimeLayout::TIMESPEC32
+            .read_duration::<BigEndian>(&buf)
+            .is_ok());
+
+        BigEndian::write_i32(&mut buf[4..], -1);
+        let err = TimeLayout::TIMESPEC32
+            .read_duration::<BigEndian>(&buf)
+            .unwrap_err();
+        assert_eq!(&ErrorKind::OutOfRange, err.kind());
+    }
+
+    #[test]
+    fn seconds_overflow() {
+        let mut buf = [0xAA; 8];
+        let d = Duration::from_secs(1 << 31);
+        let err = TimeLayout::TIMEVAL32
+            .write_duration::<BigEndian>(&mut buf, d)
+            .unwrap_err();
+        assert_eq!(&ErrorKind::OutOfRange, err.kind());
+        assert_eq!([0xAA; 8], buf);
+        let d = Duration::from_secs(u64::MAX);
+        let mut buf = [0; 16];
+        assert!(TimeLayout::TIMEVAL64
+            .write_duration::<BigEndian>(&mut buf, d)
+            .is_err());
+    }
+
+    #[test]
+    fn short_buffer() {
+        let err = TimeLayout::TIMESPEC64
+            .read_duration::<BigEndian>(&[0; 15])
+            .unwrap_err();
+        assert_eq!(
+            &ErrorKind::BufferTooSmall { needed: 16, available: 15 },
+            err.kind()
+        );
+    }
+
+    #[test]
+    fn io() {
+        let layout = TimeLayout::TIMESPEC32;
+        let time = UNIX_EPOCH + Duration::new(42, 7);
+        let mut wtr = vec![];
+        layout
+            .write_system_time_to::<LittleEndian, _>(&mut wtr, time)
+            .unwrap();
+        layout
+            .write_duration_to::<LittleEndian, _>(
+                &mut wtr,
+                Duration::new(1, 0),
+            )
+            .unwrap();
+        assert_eq!(16, wtr.len());
+
+        let mut rdr = &wtr[..];
+        let got = layout.read_system_time_from::<LittleEndian, _>(&mut rdr);
+        assert_eq!(time, got.unwrap());
+        let got = layout.read_duration_from::<LittleEndian, _>(&mut rdr);
+        assert_eq!(Duration::new(1, 0), got.unwrap());
+
+        let err = layout
+            .read_duration_from::<LittleEndian, _>(&mut rdr)
+            .unwrap_err();
+        assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+
+        let bad = [0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF];
+        let err = layout
+            .read_duration_from::<LittleEndian, _>(&mut &bad[..])
+            .unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+    }
+}
@@ -0,0 +1,349 @@
+/*!
+Reading and writing numbers with tokio's asynchronous readers and writers.
+
+[`AsyncReadBytesExt`] and [`AsyncWriteBytesExt`] are the asynchronous
+counterparts of [`ReadBytesExt`](crate::ReadBytesExt) and
+[`WriteBytesExt`](crate::WriteBytesExt), for every type that implements
+tokio's `AsyncRead` or `AsyncWrite`. Their methods have the same names and
+take the byte order as a type parameter in the same way, but return futures:
+
+```rust,no_run
+use byteorder::{tokio::AsyncReadBytesExt, BigEndian};
+use tokio::io::AsyncRead;
+
+async fn read_header<R>(stream: &mut R) -> std::io::Result<(u16, u32)>
+where
+    R: AsyncRead + Unpin,
+{
+    let kind = stream.read_u16::<BigEndian>().await?;
+    let len = stream.read_u32::<BigEndian>().await?;
+    Ok((kind, len))
+}
+```
+
+This module requires the `tokio` feature.
+
+# Name clashes
+
+Tokio's own `AsyncReadExt` and `AsyncWriteExt` have methods like `read_u16`
+and `write_u16` too, which always use big-endian. When tokio's trait and the
+one from this module are both in scope, calls like `rdr.read_u16()` are
+ambiguous and fail to compile. Either import only one of them in a module, or
+call the methods of this one as
+`AsyncReadBytesExt::read_u16::<BigEndian>(&mut rdr)`.
+
+# Cancellation
+
+Like `read_exact` and `write_all`, the futures returned by these traits are
+not cancellation safe: if one is dropped before it completes, for example in
+a losing branch of `select!`, the bytes it had already read are lost, or
+some of its bytes may have been written.
+
+When a read must survive being cancelled, use a [`ResumableRead`] instead,
+which keeps the bytes read so far outside of the future.
+*/
+
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io;
+
+use ::tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::ByteOrder;
+
+crate::async_methods::future_types!(AsyncRead, AsyncWrite);
+
+/// Reads into `buf` with a single call to `poll_read`, and returns the number
+/// of bytes read.
+fn poll_read_some<R: AsyncRead + ?Sized>(
+    rdr: Pin<&mut R>,
+    cx: &mut Context<'_>,
+    buf: &mut [u8],
+) -> Poll<io::Result<usize>> {
+    let mut buf = ReadBuf::new(buf);
+    match rdr.poll_read(cx, &mut buf) {
+        Poll::Pending => Poll::Pending,
+        Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+        Poll::Ready(Ok(())) => Poll::Ready(Ok(buf.filled().len())),
+    }
+}
+
+/// Writes from `buf` with a single call to `poll_write`, and returns the
+/// number of bytes written.
+fn poll_write_some<W: AsyncWrite + ?Sized>(
+    wtr: Pin<&mut W>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+) -> Poll<io::Result<usize>> {
+    wtr.poll_write(cx, buf)
+}
+
+/// Extends tokio's `AsyncRead` with methods for reading numbers.
+///
+/// This is implemented for every type that implements `AsyncRead` and
+/// `Unpin`. See the [module documentation](self) for details.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use byteorder::{tokio::AsyncReadBytesExt, LittleEndian};
+/// use tokio::io::AsyncRead;
+///
+/// async fn read_point<R>(rdr: &mut R) -> std::io::Result<(f32, f32)>
+/// where
+///     R: AsyncRead + Unpin,
+/// {
+///     let x = rdr.read_f32::<LittleEndian>().await?;
+///     let y = rdr.read_f32::<LittleEndian>().await?;
+///     Ok((x, y))
+/// }
+/// ```
+pub trait AsyncReadBytesExt: AsyncRead + Unpin {
+    crate::async_methods::read_methods!();
+}
+
+impl<R: AsyncRead + Unpin + ?Sized> AsyncReadBytesExt for R {}
+
+/// Extends tokio's `AsyncWrite` with methods for writing numbers.
+///
+/// This is implemented for every type that implements `AsyncWrite` and
+/// `Unpin`. See the [module documentation](self) for details.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use byteorder::{tokio::AsyncWriteBytesExt, BigEndian};
+/// use tokio::io::AsyncWrite;
+///
+/// async fn write_header<W>(wtr: &mut W, len: u32) -> std::io::Result<()>
+/// where
+///     W: AsyncWrite + Unpin,
+/// {
+///     wtr.write_u16::<BigEndian>(0xCAFE).await?;
+///     wtr.write_u32::<BigEndian>(len).await
+/// }
+/// ```
+pub trait AsyncWriteBytesExt: AsyncWrite + Unpin {
+    crate::async_methods::write_methods!();
+}
+
+impl<W: AsyncWrite + Unpin + ?Sized> AsyncWriteBytesExt for W {}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use super::{AsyncReadBytesExt, AsyncWriteBytesExt, ResumableRead};
+    use crate::{
+        async_methods::{block_on, poll_once},
+        BigEndian, LittleEndian,
+    };
+
+    /// A reader that alternates between returning `Pending` and a single
+    /// byte, to exercise partial reads.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        pending: bool,
+    }
+
+    impl<'a> AsyncRead for Trickle<'a> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            if let Some((&b, rest)) = self.data.split_first() {
+                buf.put_slice(&[b]);
+                self.data = rest;
+            }
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    /// A writer that alternates between returning `Pending` and accepting
+    /// a single byte, to exercise partial writes.
+    #[derive(Default)]
+    struct Drip {
+        data: Vec<u8>,
+        pending: bool,
+    }
+
+    impl AsyncWrite for Drip {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            self.data.push(buf[0]);
+            Poll::Ready(Ok(1))
+        }
+
+        fn poll_flush(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn reads_numbers() {
+        let data =
+            [0xFF, 1, 2, 3, 4, 5, 6, 0, 0, 0x80, 0x3F, 7, 8, 9, 10, 11, 12];
+        let mut rdr = &data[..];
+        assert_eq!(-1, block_on(rdr.read_i8()).unwrap());
+        assert_eq!(0x010203, block_on(rdr.read_u24::<BigEndian>()).unwrap());
+        assert_eq!(
+            0x060504,
+            block_on(rdr.read_uint::<LittleEndian>(3)).unwrap()
+        );
+        assert_eq!(1.0, block_on(rdr.read_f32::<LittleEndian>()).unwrap());
+        assert_eq!(0x0708, block_on(rdr.read_i16::<BigEndian>()).unwrap());
+        assert_eq!(
+            0x0C0B0A09,
+            block_on(rdr.read_uint128::<LittleEndian>(4)).unwrap()
+        );
+        let err = block_on(rdr.read_u8()).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn partial_reads() {
+        let data = u128::MAX.to_be_bytes();
+        let mut rdr = Trickle { data: &data[..], pending: false };
+        let n = block_on(rdr.read_u128::<BigEndian>()).unwrap();
+        assert_eq!(u128::MAX, n);
+
+        let mut rdr = Trickle { data: &[1, 2, 3], pending: false };
+        let err = block_on(rdr.read_u32::<BigEndian>()).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn writes_numbers() {
+        let mut wtr = vec![];
+        block_on(wtr.write_i8(-1)).unwrap();
+        block_on(wtr.write_u24::<BigEndian>(0x010203)).unwrap();
+        block_on(wtr.write_uint::<LittleEndian>(0x060504, 3)).unwrap();
+        block_on(wtr.write_f32::<LittleEndian>(1.0)).unwrap();
+        block_on(wtr.write_int128::<BigEndian>(-2, 9)).unwrap();
+        assert_eq!(
+            wtr,
+            [
+                0xFF, 1, 2, 3, 4, 5, 6, 0, 0, 0x80, 0x3F, 0xFF, 0xFF, 0xFF,
+                0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE
+            ]
+        );
+
+        let mut wtr = Drip::default();
+        block_on(wtr.write_u128::<LittleEndian>(1)).unwrap();
+        block_on(wtr.write_f64::<BigEndian>(-0.0)).unwrap();
+        assert_eq!(24, wtr.data.len());
+        assert_eq!(1, wtr.data[0]);
+        assert_eq!(0x80, wtr.data[16]);
+
+        let mut full = [0; 3];
+        let mut wtr = io::Cursor::new(&mut full[..]);
+        let err = block_on(wtr.write_u32::<BigEndian>(1)).unwrap_err();
+        assert_eq!(io::ErrorKind::WriteZero, err.kind());
+    }
+
+    #[test]
+    fn reads_varints() {
+        let mut data = vec![0xE5, 0x8E, 0x26, 0x03];
+        data.extend_from_slice(&[0xFF; 18]);
+        data.push(0x03);
+        // Too long: 10 bytes that don't end the varint, then one more.
+        data.extend_from_slice(&[0x80; 10]);
+        data.push(0x00);
+        let mut rdr = Trickle { data: &data[..], pending: false };
+        assert_eq!(624485, block_on(rdr.read_uleb128()).unwrap());
+        assert_eq!(-2, block_on(rdr.read_varint_zigzag()).unwrap());
+        assert_eq!(u128::MAX, block_on(rdr.read_uleb128_u128()).unwrap());
+        let err = block_on(rdr.read_varint_u64()).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        // Only the maximum number of bytes were read.
+        assert_eq!(0, block_on(rdr.read_varint_zigzag_i128()).unwrap());
+        let err = block_on(rdr.read_uleb128()).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn bulk() {
+        // Bigger than the staging buffer, so it is written in chunks.
+        let src: Vec<u32> =
+            (0..3000).map(|i: u32| i.wrapping_mul(0x0101_0101)).collect();
+        let mut wtr = vec![];
+        block_on(wtr.write_u32_from::<BigEndian>(&src)).unwrap();
+        block_on(wtr.write_i8_from(&[-1, 2])).unwrap();
+        block_on(wtr.write_f64_from::<LittleEndian>(&[1.5, -0.0])).unwrap();
+        assert_eq!(12018, wtr.len());
+        assert_eq!(&[0, 0, 0, 0, 1, 1, 1, 1], &wtr[..8]);
+
+        let mut rdr = &wtr[..];
+        let mut dst = vec![0; 3000];
+        block_on(rdr.read_u32_into::<BigEndian>(&mut dst)).unwrap();
+        assert_eq!(src, dst);
+        let mut dst = [0; 2];
+        block_on(rdr.read_i8_into(&mut dst)).unwrap();
+        assert_eq!([-1, 2], dst);
+        let rest = rdr.to_vec();
+        let mut rdr = Trickle { data: &rest[..], pending: false };
+        let mut dst = [0.0; 2];
+        block_on(rdr.read_f64_into::<LittleEndian>(&mut dst)).unwrap();
+        assert_eq!([1.5, -0.0], dst);
+        let err =
+            block_on(rdr.read_i16_into::<BigEndian>(&mut [0; 1])).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn resumable_reads() {
+        let data = [1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 0xFF];
+        let mut rdr = Trickle { data: &data[..], pending: false };
+        let mut next = ResumableRead::<u32>::new::<BigEndian>();
+        // The first poll reads nothing, and every later one reads a byte
+        // before the reader returns `Pending` again. Each future is dropped
+        // after one poll.
+        for _ in 0..4 {
+            assert!(poll_once(next.read(&mut rdr)).is_pending());
+        }
+        assert_eq!(3, next.filled());
+        assert_eq!(0x01020304, block_on(next.read(&mut rdr)).unwrap());
+        assert_eq!(0, next.filled());
+        assert_eq!(0x05060708, block_on(next.read(&mut rdr)).unwrap());
+
+        let mut pair = ResumableRead::<[u8; 2]>::new::<BigEndian>();
+        assert_eq!([0, 1], block_on(pair.read(&mut rdr)).unwrap());
+        let err = block_on(pair.read(&mut rdr)).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+        assert_eq!(1, pair.filled());
+        pair.reset();
+        assert_eq!(0, pair.filled());
+    }
+}
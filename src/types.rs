@@ -0,0 +1,983 @@
+/*!
+Integer and float types stored in a fixed byte order.
+
+The types in this module, like [`U16<E>`](U16) and [`I64<E>`](I64), hold a
+number as its bytes in the byte order `E`, rather than in the byte order of
+the platform. Their fields can be read with [`get`](U32::get) and written
+with [`set`](U32::set), which convert the bytes on the way.
+They are printed by `Display` as the number they hold, and by `Debug` as
+that number together with their bytes, like
+`U32<BE>(0x0000012C = [00,00,01,2C])`.
+
+Each type is `repr(transparent)` over a byte array, so it has an alignment of
+1 and no padding. A `#[repr(C)]` struct made only of these types and byte
+arrays therefore has exactly the layout of the data it describes, which lets
+an on-disk or on-the-wire header be described field by field:
+
+```rust
+use byteorder::{
+    types::{U16, U32},
+    BigEndian, LittleEndian,
+};
+
+#[derive(Clone, Copy, Default)]
+#[repr(C)]
+struct Header {
+    magic: [u8; 4],
+    version: U16<BigEndian>,
+    flags: U16<BigEndian>,
+    len: U32<LittleEndian>,
+}
+
+let mut header = Header::default();
+header.magic = *b"FRM1";
+header.version.set(2);
+header.len.set(1024);
+assert_eq!(2, header.version.get());
+assert_eq!(12, core::mem::size_of::<Header>());
+assert_eq!(1, core::mem::align_of::<Header>());
+```
+
+The types also work like the numbers they hold where that is convenient.
+They implement the arithmetic operators, and the integers implement the
+bitwise and shift operators, with either a value of the same type or a plain
+number on the right hand side. They are compared by the numbers they hold,
+both with each other and with plain numbers:
+
+```rust
+use byteorder::{types::U16, BigEndian};
+
+let mut flags = U16::<BigEndian>::new(0x0001);
+flags |= 0x8000;
+assert_eq!(flags, 0x8001);
+assert!(flags > U16::new(0x00FF));
+assert_eq!([0x80, 0x02], (flags + 1).to_bytes());
+```
+
+Like the operators of plain integers, the arithmetic operators panic on
+overflow when debug assertions are enabled, and wrap around otherwise.
+
+The integer types implement `Eq`, `Ord` and `Hash` by the number they hold,
+never by their bytes. So a `U32<LittleEndian>` is ordered like the `u32` it
+holds, even though its bytes would be ordered differently, and it hashes to
+the same value as that `u32`. This makes the integer types work as keys of a
+`BTreeMap` or `HashMap`, whatever their byte order:
+
+```rust
+use std::collections::BTreeMap;
+
+use byteorder::{types::U32, LittleEndian};
+
+let mut names = BTreeMap::new();
+names.insert(U32::<LittleEndian>::new(256), "second");
+names.insert(U32::<LittleEndian>::new(1), "first");
+let names: Vec<&str> = names.into_values().collect();
+assert_eq!(vec!["first", "second"], names);
+```
+
+The floating point types implement only `PartialEq` and `PartialOrd`, by the
+number they hold, with the same semantics as `f32` and `f64`. In particular,
+`NaN` is not equal to itself.
+
+Formats often require a field to be nonzero, e.g., a count or an identifier
+where zero is reserved. The integer types have a
+[`get_nonzero`](U32::get_nonzero) method, which checks this while converting
+to one of the `NonZero` integer types of the standard library. Since those
+have a niche, an optional field decoded this way takes no extra space:
+
+```rust
+use core::num::NonZeroU32;
+
+use byteorder::{types::U32, BigEndian, ErrorKind};
+
+let id = U32::<BigEndian>::from_bytes([0, 0, 0, 7]);
+assert_eq!(NonZeroU32::new(7), id.get_nonzero().ok());
+
+let err = U32::<BigEndian>::ZERO.get_nonzero().unwrap_err();
+assert_eq!(&ErrorKind::OutOfRange, err.kind());
+
+let parent: Option<NonZeroU32> = U32::<BigEndian>::ZERO.get_nonzero().ok();
+assert_eq!(None, parent);
+assert_eq!(4, core::mem::size_of::<Option<NonZeroU32>>());
+```
+
+Converting a whole struct like `Header` from a byte slice requires `unsafe`
+code, or a crate like `zerocopy` or `bytemuck`, since this crate can't check
+that the struct is made only of such fields. A single value can be viewed in
+place from a byte array with safe code, using
+[`from_bytes_ref`](U32::from_bytes_ref) and
+[`from_bytes_mut`](U32::from_bytes_mut).
+
+A byte slice can also be viewed as a slice of one of these types with
+[`layout_slice`] and [`layout_slice_mut`]. This lets a large table, e.g., one
+in a memory mapped file, be indexed like an array, converting only the
+entries that are actually read:
+
+```rust
+use byteorder::{
+    types::{layout_slice, U32},
+    BigEndian,
+};
+
+let table = b"\x00\x00\x00\x10\x00\x00\x01\x00\x00\x00\x10\x00";
+let offsets: &[U32<BigEndian>] = layout_slice(table).unwrap();
+assert_eq!(3, offsets.len());
+assert_eq!(0x100, offsets[1].get());
+```
+
+# Casting with `bytemuck`
+
+With the `bytemuck` feature enabled, every type in this module implements
+`bytemuck`'s `Pod` and `Zeroable` traits. A struct made of them can then
+derive those traits too, and be cast from bytes without copying them or
+writing any `unsafe` code:
+
+```rust
+# #[cfg(feature = "bytemuck")]
+# fn main() {
+use bytemuck::{Pod, Zeroable};
+use byteorder::{
+    types::{U16, U32},
+    BigEndian,
+};
+
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+struct Header {
+    magic: [u8; 4],
+    version: U16<BigEndian>,
+    flags: U16<BigEndian>,
+    len: U32<BigEndian>,
+}
+
+let data = b"FRM1\x00\x02\x00\x00\x00\x00\x04\x00payload";
+let header: &Header = bytemuck::from_bytes(&data[..12]);
+assert_eq!(b"FRM1", &header.magic);
+assert_eq!(2, header.version.get());
+assert_eq!(1024, header.len.get());
+# }
+# #[cfg(not(feature = "bytemuck"))]
+# fn main() {}
+```
+
+# Casting with `zerocopy`
+
+Likewise, with the `zerocopy` feature enabled, every type in this module
+derives `zerocopy`'s `FromBytes`, `IntoBytes`, `KnownLayout`, `Immutable` and
+`Unaligned` traits, so that it can be a field of a struct that derives them:
+
+```rust
+# #[cfg(feature = "zerocopy")]
+# fn main() {
+use byteorder::{
+    types::{U16, U32},
+    LittleEndian,
+};
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned};
+
+#[derive(FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned)]
+#[repr(C)]
+struct Packet {
+    kind: U16<LittleEndian>,
+    seq: U16<LittleEndian>,
+    len: U32<LittleEndian>,
+}
+
+let data = b"\x01\x00\x07\x00\x03\x00\x00\x00abc";
+let (packet, payload) = Packet::ref_from_prefix(data).unwrap();
+assert_eq!(7, packet.seq.get());
+assert_eq!(b"abc", payload);
+assert_eq!(&data[..8], packet.as_bytes());
+# }
+# #[cfg(not(feature = "zerocopy"))]
+# fn main() {}
+```
+
+The `zerocopy` crate has byte order aware integers of its own, in its
+`byteorder` module. Those are unrelated to the types in this module, and
+take zerocopy's byte order types rather than this crate's.
+
+# Serializing with `serde`
+
+With the `serde` feature enabled, every type in this module implements
+`serde`'s `Serialize` and `Deserialize` traits. A value is serialized as the
+number it holds, so the byte order `E` doesn't show up in the serialized form,
+and the same data can be deserialized into a value with a different byte
+order.
+*/
+
+use core::{
+    cmp::Ordering,
+    fmt, hash,
+    marker::PhantomData,
+    mem,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64,
+    },
+    ops, slice,
+};
+
+use crate::{ByteOrder, Endianness, Error, ErrorKind};
+
+/// A type of this module, which can be viewed in place from any bytes.
+///
+/// Every implementation is `repr(transparent)` over a byte array, so it has
+/// an alignment of 1, no padding, and every bit pattern is a valid value.
+/// This trait is sealed, and is used by [`layout_slice`] and
+/// [`layout_slice_mut`].
+///
+/// # Safety
+///
+/// Implementations must uphold the properties above. This trait can't be
+/// implemented outside of this crate.
+pub unsafe trait Unaligned: Copy + crate::private::Sealed {}
+
+/// Views `bytes` as a slice of `T`, without copying them.
+///
+/// This returns `None` when the length of `bytes` is not a multiple of the
+/// size of `T`. Since every `T` has an alignment of 1, `bytes` may start at
+/// any address.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{
+///     types::{layout_slice, I16},
+///     LittleEndian,
+/// };
+///
+/// let samples: &[I16<LittleEndian>] =
+///     layout_slice(&[0x01, 0x00, 0xFF, 0xFF]).unwrap();
+/// assert_eq!(vec![1, -1], samples.iter().map(|s| s.get()).collect::<Vec<_>>());
+///
+/// assert!(layout_slice::<I16<LittleEndian>>(&[0x01, 0x00, 0xFF]).is_none());
+/// ```
+#[inline]
+pub fn layout_slice<T: Unaligned>(bytes: &[u8]) -> Option<&[T]> {
+    let size = mem::size_of::<T>();
+    if bytes.len() % size != 0 {
+        return None;
+    }
+    // SAFETY: `T` has an alignment of 1 and every bit pattern is a valid
+    // `T`, and the slice covers exactly the bytes of `bytes`.
+    Some(unsafe {
+        slice::from_raw_parts(bytes.as_ptr() as *const T, bytes.len() / size)
+    })
+}
+
+/// Views `bytes` as a mutable slice of `T`, without copying them.
+///
+/// This returns `None` when the length of `bytes` is not a multiple of the
+/// size of `T`. Since every `T` has an alignment of 1, `bytes` may start at
+/// any address.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{
+///     types::{layout_slice_mut, U16},
+///     BigEndian,
+/// };
+///
+/// let mut buf = [0; 6];
+/// let entries: &mut [U16<BigEndian>] = layout_slice_mut(&mut buf).unwrap();
+/// entries[2].set(0x1234);
+/// assert_eq!([0, 0, 0, 0, 0x12, 0x34], buf);
+/// ```
+#[inline]
+pub fn layout_slice_mut<T: Unaligned>(bytes: &mut [u8]) -> Option<&mut [T]> {
+    let size = mem::size_of::<T>();
+    if bytes.len() % size != 0 {
+        return None;
+    }
+    // SAFETY: see `layout_slice`. `T` also has no padding, so any `T`
+    // written through the slice leaves every byte of `bytes` initialized.
+    Some(unsafe {
+        slice::from_raw_parts_mut(
+            bytes.as_mut_ptr() as *mut T,
+            bytes.len() / size,
+        )
+    })
+}
+
+/// Returns the short name of the byte order `E`, for `Debug` output.
+fn order_name<E: ByteOrder>() -> &'static str {
+    match Endianness::of::<E>() {
+        Endianness::Big => "BE",
+        Endianness::Little => "LE",
+    }
+}
+
+/// Formats the number held by a type of this module in its `Debug` output.
+///
+/// Integers are written in hexadecimal, zero padded to their full width, so
+/// that they line up with their bytes. Floats are written like `f32` and
+/// `f64` write themselves.
+trait DebugValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+macro_rules! debug_value {
+    (int: $($ty:ty),*; float: $($float:ty),*) => {
+        $(
+            impl DebugValue for $ty {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "{:#0w$X}", self, w = 2 + 2 * mem::size_of::<$ty>())
+                }
+            }
+        )*
+        $(
+            impl DebugValue for $float {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Debug::fmt(self, f)
+                }
+            }
+        )*
+    };
+}
+
+debug_value! {
+    int: u16, u32, u64, u128, i16, i32, i64, i128;
+    float: f32, f64
+}
+
+macro_rules! endian_type {
+    ($(
+        $(#[$attr:meta])*
+        $name:ident($ty:ty, $size:expr, $read:ident, $write:ident);
+    )*) => {$(
+        $(#[$attr])*
+        #[cfg_attr(
+            feature = "zerocopy",
+            derive(
+                zerocopy::FromBytes,
+                zerocopy::Immutable,
+                zerocopy::IntoBytes,
+                zerocopy::KnownLayout,
+                zerocopy::Unaligned,
+            )
+        )]
+        #[repr(transparent)]
+        pub struct $name<E>([u8; $size], PhantomData<E>);
+
+        impl<E> $name<E> {
+            /// A value whose bytes are all zero.
+            pub const ZERO: $name<E> = $name([0; $size], PhantomData);
+
+            /// Creates a value from its bytes, in the byte order `E`.
+            #[inline]
+            pub const fn from_bytes(bytes: [u8; $size]) -> $name<E> {
+                $name(bytes, PhantomData)
+            }
+
+            /// Returns the bytes of this value, in the byte order `E`.
+            #[inline]
+            pub const fn to_bytes(self) -> [u8; $size] {
+                self.0
+            }
+
+            /// Returns the bytes of this value, in the byte order `E`.
+            #[inline]
+            pub fn as_bytes(&self) -> &[u8; $size] {
+                &self.0
+            }
+
+            /// Views `bytes` as a value, without copying them.
+            #[inline]
+            pub fn from_bytes_ref(bytes: &[u8; $size]) -> &$name<E> {
+                // SAFETY: this type is `repr(transparent)` over
+                // `[u8; $size]`, since `PhantomData` is zero sized and has
+                // an alignment of 1.
+                unsafe { &*(bytes as *const [u8; $size] as *const $name<E>) }
+            }
+
+            /// Views `bytes` as a mutable value, without copying them.
+            #[inline]
+            pub fn from_bytes_mut(bytes: &mut [u8; $size]) -> &mut $name<E> {
+                // SAFETY: see `from_bytes_ref`.
+                unsafe { &mut *(bytes as *mut [u8; $size] as *mut $name<E>) }
+            }
+        }
+
+        impl<E: ByteOrder> $name<E> {
+            /// Creates a value holding `n`.
+            #[inline]
+            pub fn new(n: $ty) -> $name<E> {
+                let mut bytes = [0; $size];
+                E::$write(&mut bytes, n);
+                $name(bytes, PhantomData)
+            }
+
+            /// Returns the number held by this value.
+            #[inline]
+            pub fn get(self) -> $ty {
+                E::$read(&self.0)
+            }
+
+            /// Replaces the number held by this value with `n`.
+            #[inline]
+            pub fn set(&mut self, n: $ty) {
+                E::$write(&mut self.0, n)
+            }
+        }
+
+        impl<E> crate::private::Sealed for $name<E> {}
+
+        // SAFETY: this type is `repr(transparent)` over a byte array.
+        unsafe impl<E> Unaligned for $name<E> {}
+
+        // SAFETY: this type is `repr(transparent)` over a byte array, for
+        // which every bit pattern is valid, including all zeros.
+        #[cfg(feature = "bytemuck")]
+        unsafe impl<E> bytemuck::Zeroable for $name<E> {}
+
+        // SAFETY: see above. The type also has no padding and is `Copy`.
+        #[cfg(feature = "bytemuck")]
+        unsafe impl<E: 'static> bytemuck::Pod for $name<E> {}
+
+        #[cfg(feature = "serde")]
+        impl<E: ByteOrder> serde::Serialize for $name<E> {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                self.get().serialize(serializer)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de, E: ByteOrder> serde::Deserialize<'de> for $name<E> {
+            fn deserialize<D: serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<$name<E>, D::Error> {
+                <$ty>::deserialize(deserializer).map($name::new)
+            }
+        }
+
+        impl<E> Clone for $name<E> {
+            #[inline]
+            fn clone(&self) -> $name<E> {
+                *self
+            }
+        }
+
+        impl<E> Copy for $name<E> {}
+
+        impl<E> Default for $name<E> {
+            #[inline]
+            fn default() -> $name<E> {
+                $name::ZERO
+            }
+        }
+
+        impl<E: ByteOrder> fmt::Debug for $name<E> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}<{}>(", stringify!($name), order_name::<E>())?;
+                DebugValue::fmt(&self.get(), f)?;
+                f.write_str(" = [")?;
+                for (i, b) in self.0.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{:02X}", b)?;
+                }
+                f.write_str("])")
+            }
+        }
+
+        impl<E: ByteOrder> fmt::Display for $name<E> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.get(), f)
+            }
+        }
+
+        impl<E: ByteOrder> From<$ty> for $name<E> {
+            #[inline]
+            fn from(n: $ty) -> $name<E> {
+                $name::new(n)
+            }
+        }
+
+        impl<E: ByteOrder> From<$name<E>> for $ty {
+            #[inline]
+            fn from(n: $name<E>) -> $ty {
+                n.get()
+            }
+        }
+
+        impl<E> From<[u8; $size]> for $name<E> {
+            #[inline]
+            fn from(bytes: [u8; $size]) -> $name<E> {
+                $name::from_bytes(bytes)
+            }
+        }
+
+        impl<E> From<$name<E>> for [u8; $size] {
+            #[inline]
+            fn from(n: $name<E>) -> [u8; $size] {
+                n.to_bytes()
+            }
+        }
+
+        impl<E: ByteOrder> PartialEq<$ty> for $name<E> {
+            #[inline]
+            fn eq(&self, other: &$ty) -> bool {
+                self.get() == *other
+            }
+        }
+
+        impl<E: ByteOrder> PartialOrd<$ty> for $name<E> {
+            #[inline]
+            fn partial_cmp(&self, other: &$ty) -> Option<Ordering> {
+                self.get().partial_cmp(other)
+            }
+        }
+
+        impl<E: ByteOrder> PartialEq<$name<E>> for $ty {
+            #[inline]
+            fn eq(&self, other: &$name<E>) -> bool {
+                *self == other.get()
+            }
+        }
+
+        impl<E: ByteOrder> PartialOrd<$name<E>> for $ty {
+            #[inline]
+            fn partial_cmp(&self, other: &$name<E>) -> Option<Ordering> {
+                self.partial_cmp(&other.get())
+            }
+        }
+
+        binop!($name, $ty, Add, add, AddAssign, add_assign);
+        binop!($name, $ty, Sub, sub, SubAssign, sub_assign);
+        binop!($name, $ty, Mul, mul, MulAssign, mul_assign);
+        binop!($name, $ty, Div, div, DivAssign, div_assign);
+        binop!($name, $ty, Rem, rem, RemAssign, rem_assign);
+    )*};
+}
+
+/// Implements the operator `$op` for a type of this module, with either
+/// another value of the same type or a number on the right hand side, by
+/// applying it to the numbers they hold.
+macro_rules! binop {
+    (
+        $name:ident, $ty:ty,
+        $op:ident, $method:ident, $op_assign:ident, $method_assign:ident
+    ) => {
+        impl<E: ByteOrder> ops::$op for $name<E> {
+            type Output = $name<E>;
+
+            #[inline]
+            fn $method(self, rhs: $name<E>) -> $name<E> {
+                $name::new(ops::$op::$method(self.get(), rhs.get()))
+            }
+        }
+
+        impl<E: ByteOrder> ops::$op<$ty> for $name<E> {
+            type Output = $name<E>;
+
+            #[inline]
+            fn $method(self, rhs: $ty) -> $name<E> {
+                $name::new(ops::$op::$method(self.get(), rhs))
+            }
+        }
+
+        impl<E: ByteOrder> ops::$op_assign for $name<E> {
+            #[inline]
+            fn $method_assign(&mut self, rhs: $name<E>) {
+                *self = ops::$op::$method(*self, rhs);
+            }
+        }
+
+        impl<E: ByteOrder> ops::$op_assign<$ty> for $name<E> {
+            #[inline]
+            fn $method_assign(&mut self, rhs: $ty) {
+                *self = ops::$op::$method(*self, rhs);
+            }
+        }
+    };
+}
+
+/// Implements the comparisons and bitwise operators of integers.
+///
+/// Equality, ordering and hashing all use the number held, so that they agree
+/// with each other and with those of the plain integer type.
+macro_rules! endian_int {
+    ($($name:ident($ty:ty, $nonzero:ident),)*) => {$(
+        impl<E: ByteOrder> $name<E> {
+            /// Returns the number held by this value, for a field that must
+            /// not be zero.
+            ///
+            /// # Errors
+            ///
+            /// This returns an error of kind
+            /// [`ErrorKind::OutOfRange`] when the number is zero.
+            #[inline]
+            pub fn get_nonzero(self) -> Result<$nonzero, Error> {
+                $nonzero::new(self.get())
+                    .ok_or_else(|| Error::new(ErrorKind::OutOfRange))
+            }
+        }
+
+        impl<E: ByteOrder> From<$nonzero> for $name<E> {
+            #[inline]
+            fn from(n: $nonzero) -> $name<E> {
+                $name::new(n.get())
+            }
+        }
+
+        impl<E: ByteOrder> PartialEq for $name<E> {
+            #[inline]
+            fn eq(&self, other: &$name<E>) -> bool {
+                self.get() == other.get()
+            }
+        }
+
+        impl<E: ByteOrder> Eq for $name<E> {}
+
+        impl<E: ByteOrder> PartialOrd for $name<E> {
+            #[inline]
+            fn partial_cmp(&self, other: &$name<E>) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<E: ByteOrder> Ord for $name<E> {
+            #[inline]
+            fn cmp(&self, other: &$name<E>) -> Ordering {
+                self.get().cmp(&other.get())
+            }
+        }
+
+        impl<E: ByteOrder> hash::Hash for $name<E> {
+            #[inline]
+            fn hash<H: hash::Hasher>(&self, state: &mut H) {
+                self.get().hash(state)
+            }
+        }
+
+        impl<E: ByteOrder> ops::Not for $name<E> {
+            type Output = $name<E>;
+
+            #[inline]
+            fn not(self) -> $name<E> {
+                $name::from_bytes(self.0.map(|b| !b))
+            }
+        }
+
+        binop!($name, $ty, BitAnd, bitand, BitAndAssign, bitand_assign);
+        binop!($name, $ty, BitOr, bitor, BitOrAssign, bitor_assign);
+        binop!($name, $ty, BitXor, bitxor, BitXorAssign, bitxor_assign);
+        binop!($name, $ty, Shl, shl, ShlAssign, shl_assign);
+        binop!($name, $ty, Shr, shr, ShrAssign, shr_assign);
+    )*};
+}
+
+/// Implements the comparisons of floating point numbers, which compare the
+/// numbers held rather than their bytes, like `f32` and `f64` do.
+macro_rules! endian_float {
+    ($($name:ident,)*) => {$(
+        impl<E: ByteOrder> PartialEq for $name<E> {
+            #[inline]
+            fn eq(&self, other: &$name<E>) -> bool {
+                self.get() == other.get()
+            }
+        }
+
+        impl<E: ByteOrder> PartialOrd for $name<E> {
+            #[inline]
+            fn partial_cmp(&self, other: &$name<E>) -> Option<Ordering> {
+                self.get().partial_cmp(&other.get())
+            }
+        }
+    )*};
+}
+
+endian_type! {
+    /// An unsigned 16 bit integer stored in the byte order `E`.
+    U16(u16, 2, read_u16, write_u16);
+    /// An unsigned 32 bit integer stored in the byte order `E`.
+    U32(u32, 4, read_u32, write_u32);
+    /// An unsigned 64 bit integer stored in the byte order `E`.
+    U64(u64, 8, read_u64, write_u64);
+    /// An unsigned 128 bit integer stored in the byte order `E`.
+    U128(u128, 16, read_u128, write_u128);
+    /// A signed 16 bit integer stored in the byte order `E`.
+    I16(i16, 2, read_i16, write_i16);
+    /// A signed 32 bit integer stored in the byte order `E`.
+    I32(i32, 4, read_i32, write_i32);
+    /// A signed 64 bit integer stored in the byte order `E`.
+    I64(i64, 8, read_i64, write_i64);
+    /// A signed 128 bit integer stored in the byte order `E`.
+    I128(i128, 16, read_i128, write_i128);
+    /// An IEEE754 single-precision floating point number stored in the byte
+    /// order `E`.
+    F32(f32, 4, read_f32, write_f32);
+    /// An IEEE754 double-precision floating point number stored in the byte
+    /// order `E`.
+    F64(f64, 8, read_f64, write_f64);
+}
+
+endian_int! {
+    U16(u16, NonZeroU16), U32(u32, NonZeroU32),
+    U64(u64, NonZeroU64), U128(u128, NonZeroU128),
+    I16(i16, NonZeroI16), I32(i32, NonZeroI32),
+    I64(i64, NonZeroI64), I128(i128, NonZeroI128),
+}
+
+endian_float! {
+    F32, F64,
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::{align_of, size_of};
+
+    use super::{
+        layout_slice, layout_slice_mut, F64, I16, I32, U128, U16, U32,
+    };
+    use crate::{BigEndian, LittleEndian};
+
+    #[repr(C)]
+    struct Header {
+        magic: [u8; 4],
+        version: U16<BigEndian>,
+        kind: U16<LittleEndian>,
+        len: U32<BigEndian>,
+    }
+
+    #[test]
+    fn layout() {
+        assert_eq!(2, size_of::<U16<BigEndian>>());
+        assert_eq!(16, size_of::<U128<LittleEndian>>());
+        assert_eq!(1, align_of::<U128<LittleEndian>>());
+        assert_eq!(12, size_of::<Header>());
+        assert_eq!(1, align_of::<Header>());
+    }
+
+    #[test]
+    fn get_set() {
+        let mut n = U32::<BigEndian>::new(0x0102_0304);
+        assert_eq!([1, 2, 3, 4], n.to_bytes());
+        n.set(5);
+        assert_eq!(5, n.get());
+        assert_eq!(&[0, 0, 0, 5], n.as_bytes());
+        assert_eq!(n, U32::from_bytes([0, 0, 0, 5]));
+        assert_eq!(-2, i32::from(I32::<LittleEndian>::from(-2)));
+        assert_eq!([0xFE, 0xFF, 0xFF, 0xFF], I32::<LittleEndian>::new(-2).0);
+        assert_eq!(1.5, F64::<BigEndian>::new(1.5).get());
+        assert_eq!(0, U16::<BigEndian>::default().get());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn formatting() {
+        assert_eq!(
+            "U32<BE>(0x0000012C = [00,00,01,2C])",
+            format!("{:?}", U32::<BigEndian>::new(300)),
+        );
+        assert_eq!(
+            "U16<LE>(0x0102 = [02,01])",
+            format!("{:?}", U16::<LittleEndian>::new(258)),
+        );
+        assert_eq!(
+            "I16<BE>(0xFFFE = [FF,FE])",
+            format!("{:?}", I16::<BigEndian>::new(-2)),
+        );
+        assert_eq!(
+            "F64<BE>(-0.5 = [BF,E0,00,00,00,00,00,00])",
+            format!("{:?}", F64::<BigEndian>::new(-0.5)),
+        );
+
+        assert_eq!("300", U32::<BigEndian>::new(300).to_string());
+        assert_eq!("-2", I16::<LittleEndian>::new(-2).to_string());
+        assert_eq!("  1.50", format!("{:6.2}", F64::<BigEndian>::new(1.5)));
+    }
+
+    #[test]
+    fn ops() {
+        let a = U32::<LittleEndian>::new(6);
+        let b = U32::<LittleEndian>::new(4);
+        assert_eq!(10, (a + b).get());
+        assert_eq!(2, (a - b).get());
+        assert_eq!(24, (a * 4).get());
+        assert_eq!(1, (a / b).get());
+        assert_eq!(2, (a % 4).get());
+        assert_eq!(6, (a | b).get());
+        assert_eq!(4, (a & b).get());
+        assert_eq!(2, (a ^ 4).get());
+        assert_eq!(!6, (!a).get());
+        assert_eq!(12, (a << 1).get());
+        assert_eq!(3, (a >> 1).get());
+
+        let mut n = I32::<BigEndian>::new(-1);
+        n += 3;
+        n *= I32::new(5);
+        n -= 1;
+        n <<= 2;
+        assert_eq!(36, n);
+
+        let mut x = F64::<BigEndian>::new(1.5);
+        x += 1.0;
+        x /= F64::new(5.0);
+        assert_eq!(x, 0.5);
+    }
+
+    #[test]
+    fn comparisons() {
+        // Compared by their bytes, 0x0100 would come before 0x0002 in
+        // little-endian order.
+        let a = U16::<LittleEndian>::new(0x0100);
+        let b = U16::<LittleEndian>::new(0x0002);
+        assert!(a > b);
+        assert!(a > 0xFF);
+        assert_eq!(Some(b), [a, b].iter().copied().min());
+        assert!(I32::<LittleEndian>::new(-1) < I32::new(0));
+
+        let nan = F64::<BigEndian>::new(f64::NAN);
+        assert_ne!(nan, nan);
+        assert_eq!(F64::<BigEndian>::new(0.0), F64::new(-0.0));
+        assert!(F64::<BigEndian>::new(-0.5) < 0.0);
+
+        assert_eq!([0, 2], <[u8; 2]>::from(U16::<BigEndian>::from([0, 2])));
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn bytemuck_casts() {
+        use bytemuck::{Pod, Zeroable};
+
+        #[derive(Clone, Copy, Pod, Zeroable)]
+        #[repr(C)]
+        struct Record {
+            id: U32<LittleEndian>,
+            value: I32<BigEndian>,
+        }
+
+        let mut data = [1, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFE, 2, 0, 0, 0];
+        let records: &[Record] = bytemuck::cast_slice(&data[..8]);
+        assert_eq!(1, records[0].id.get());
+        assert_eq!(-2, records[0].value.get());
+
+        let n: &mut U32<LittleEndian> =
+            bytemuck::from_bytes_mut(&mut data[8..]);
+        n.set(3);
+        assert_eq!(3, data[8]);
+        assert_eq!(0, U128::<BigEndian>::zeroed().get());
+        assert!(bytemuck::try_from_bytes::<Record>(&data[..7]).is_err());
+    }
+
+    #[cfg(feature = "zerocopy")]
+    #[test]
+    fn zerocopy_casts() {
+        use zerocopy::{FromBytes, FromZeros, IntoBytes};
+
+        #[derive(
+            zerocopy::FromBytes,
+            zerocopy::Immutable,
+            zerocopy::IntoBytes,
+            zerocopy::KnownLayout,
+            zerocopy::Unaligned,
+        )]
+        #[repr(C)]
+        struct Record {
+            id: U32<LittleEndian>,
+            value: I32<BigEndian>,
+        }
+
+        let mut data = [1, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFE];
+        let record = Record::ref_from_bytes(&data[..]).unwrap();
+        assert_eq!(1, record.id.get());
+        assert_eq!(-2, record.value.get());
+        assert!(Record::ref_from_bytes(&data[..7]).is_err());
+
+        let record = Record::mut_from_bytes(&mut data[..]).unwrap();
+        record.value.set(3);
+        assert_eq!(&[1, 0, 0, 0, 0, 0, 0, 3], record.as_bytes());
+        assert_eq!(0, U128::<BigEndian>::new_zeroed().get());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_values() {
+        use serde_test::{assert_de_tokens, assert_tokens, Token};
+
+        assert_tokens(&U16::<BigEndian>::new(0x0102), &[Token::U16(0x0102)]);
+        assert_tokens(&I32::<LittleEndian>::new(-7), &[Token::I32(-7)]);
+        assert_tokens(&F64::<BigEndian>::new(1.5), &[Token::F64(1.5)]);
+        assert_de_tokens(&U32::<LittleEndian>::new(1), &[Token::U32(1)]);
+    }
+
+    #[test]
+    fn layout_slices() {
+        let mut bytes = [0u8; 33];
+        let bytes = &mut bytes[1..];
+
+        let words: &mut [U32<LittleEndian>] = layout_slice_mut(bytes).unwrap();
+        assert_eq!(8, words.len());
+        words[7].set(0xDEAD_BEEF);
+        assert_eq!([0xEF, 0xBE, 0xAD, 0xDE], bytes[28..]);
+
+        let wide: &[U128<BigEndian>] = layout_slice(bytes).unwrap();
+        assert_eq!(2, wide.len());
+        assert_eq!(0xEFBE_ADDE, wide[1].get());
+
+        assert_eq!(Some(&[][..]), layout_slice::<U16<BigEndian>>(&[]));
+        assert!(layout_slice::<U16<BigEndian>>(&bytes[1..]).is_none());
+        assert!(layout_slice_mut::<F64<BigEndian>>(&mut bytes[4..]).is_none());
+    }
+
+    #[test]
+    fn nonzero() {
+        use core::num::{NonZeroI16, NonZeroU128};
+
+        use crate::ErrorKind;
+
+        let n = I16::<LittleEndian>::from_bytes([0xFE, 0xFF]);
+        assert_eq!(NonZeroI16::new(-2), n.get_nonzero().ok());
+        let err = I16::<LittleEndian>::ZERO.get_nonzero().unwrap_err();
+        assert_eq!(&ErrorKind::OutOfRange, err.kind());
+
+        let n = NonZeroU128::new(1 << 100).unwrap();
+        let wide = U128::<BigEndian>::from(n);
+        assert_eq!(Ok(n), wide.get_nonzero());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_and_order_by_value() {
+        use std::{
+            collections::{hash_map::DefaultHasher, HashSet},
+            hash::{Hash, Hasher},
+        };
+
+        fn hash<T: Hash>(value: T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let n = 0x0102_0304u32;
+        assert_eq!(hash(n), hash(U32::<BigEndian>::new(n)));
+        assert_eq!(hash(n), hash(U32::<LittleEndian>::new(n)));
+        assert_eq!(hash(-5i16), hash(I16::<LittleEndian>::new(-5)));
+
+        let set: HashSet<U32<LittleEndian>> =
+            [1, 256, 1].iter().map(|&n| U32::new(n)).collect();
+        assert_eq!(2, set.len());
+        assert!(set.contains(&U32::new(256)));
+
+        let mut values: Vec<I16<LittleEndian>> =
+            [256, -1, 1].iter().map(|&n| I16::new(n)).collect();
+        values.sort();
+        assert_eq!(vec![-1, 1, 256], values);
+    }
+
+    #[test]
+    fn views() {
+        let mut bytes = [0x12, 0x34];
+        assert_eq!(0x1234, U16::<BigEndian>::from_bytes_ref(&bytes).get());
+        U16::<LittleEndian>::from_bytes_mut(&mut bytes).set(0x1234);
+        assert_eq!([0x34, 0x12], bytes);
+    }
+}
@@ -0,0 +1,928 @@
+/*!
+Variable length integers.
+
+Variable length integers, or varints, use fewer bytes for smaller numbers.
+They are used by DWARF, WebAssembly and protocol buffers, among many others.
+
+# LEB128
+
+Unsigned LEB128, as read and written by [`decode_uleb128`] and
+[`encode_uleb128`], stores seven bits per byte, least significant group
+first, and sets the high bit of every byte except the last. A `u64` takes at
+most [`MAX_ULEB128_LEN`] bytes. Decoding stops with an error after that many
+bytes, so malicious input can't make a decoder loop forever, and rejects
+encodings of values that don't fit in a `u64`. Encodings that are longer
+than necessary, with redundant groups of zero bits at the end, are accepted
+as long as they respect that limit, since DWARF and WebAssembly allow them.
+
+The [`ReadBytesExt::read_uleb128`] and [`WriteBytesExt::write_uleb128`]
+methods read and write the same encoding with `std::io`. With the `std`
+feature, `encode_varints` and `decode_varints` convert whole slices of `u64`s
+at once, which suits columnar storage.
+
+Hashes, UUIDs and other large identifiers often don't fit in a `u64`.
+[`decode_uleb128_u128`] and [`encode_uleb128_u128`] use the same encoding
+for `u128`s, limited to [`MAX_ULEB128_U128_LEN`] bytes, with the same
+checks for overlong encodings and overflow. Values that fit in a `u64` have
+the same encoding either way, so a format can widen a field from `u64` to
+`u128` without breaking existing data. Signed `i128`s can be encoded with
+[`zigzag_encode_i128`] first.
+
+# Protocol buffers
+
+The varints of the protocol buffers wire format are unsigned LEB128 limited
+to a `u64`, so [`decode_varint_u64`] and [`encode_varint_u64`], along with
+the `read_varint_u64` and `write_varint_u64` methods of the `std::io`
+extension traits, are the same as their LEB128 counterparts. They exist
+under these names so that protocol buffers code reads naturally, and so that
+it is clear that they follow its rules exactly: an encoding is at most 10
+bytes long, and the 10th byte, if any, may only be 0 or 1. Any other 10th
+byte, or an 11th byte, is an error rather than being silently truncated.
+
+# Git offsets
+
+Git packfiles store the distance back to the base of an `OFS_DELTA` object in
+an encoding that looks like LEB128 but isn't: the groups of seven bits are
+stored most significant first, and every continuation byte adds one to the
+value, so that each value has exactly one encoding. [`decode_git_offset`] and
+[`encode_git_offset`], along with the `read_git_offset` and
+`write_git_offset` methods of the `std::io` extension traits, read and write
+it.
+
+# Prefix varints
+
+Prefix varints, as read and written by [`decode_prefix_varint`] and
+[`encode_prefix_varint`], store the number of bytes that follow the first
+byte in the first byte itself, as a run of one bits at its top followed by a
+zero bit, much like UTF-8. The rest of the first byte and the bytes after it
+hold the value, most significant bits first:
+
+| Length | First byte  | Value bits |
+|-------:|-------------|-----------:|
+| 1      | `0xxxxxxx`  | 7          |
+| 2      | `10xxxxxx`  | 14         |
+| 3      | `110xxxxx`  | 21         |
+| ...    | ...         | ...        |
+| 8      | `11111110`  | 56         |
+| 9      | `11111111`  | 64         |
+
+A decoder learns the length from the first byte alone, with a single
+`leading_ones` instruction instead of a branch per byte. Since the length
+comes first and the value is big-endian, the shortest encodings of two
+numbers compare in the same order as the numbers themselves, which makes
+them suitable for keys in sorted stores. Encodings that are longer than
+necessary are accepted when decoding, but never produced. The
+`read_prefix_varint` and `write_prefix_varint` methods of the `std::io`
+extension traits read and write the same encoding.
+
+# ZigZag
+
+Varints make small unsigned numbers short, but a small negative number, cast
+to an unsigned integer, is huge. ZigZag encoding, as done by
+[`zigzag_encode_i64`] and friends, maps signed integers to unsigned ones so
+that numbers close to zero stay small: 0, -1, 1, -2, 2 and so on become 0, 1,
+2, 3, 4. This is how protocol buffers encode their `sint32` and `sint64`
+types. [`ReadBytesExt::read_varint_zigzag`] and
+[`WriteBytesExt::write_varint_zigzag`] combine it with LEB128.
+
+# Group varints
+
+All of the above decode one number at a time, with a branch per byte or per
+number. For long lists of `u32`s, the [`group`] submodule encodes four
+numbers at a time behind a single control byte, and decodes them with one
+SIMD shuffle on CPUs that support it. This is the same idea as the
+"stream VByte" family of codecs.
+
+[`ReadBytesExt::read_uleb128`]: https://docs.rs/byteorder/1/byteorder/trait.ReadBytesExt.html#method.read_uleb128
+[`WriteBytesExt::write_uleb128`]: https://docs.rs/byteorder/1/byteorder/trait.WriteBytesExt.html#method.write_uleb128
+[`ReadBytesExt::read_varint_zigzag`]: https://docs.rs/byteorder/1/byteorder/trait.ReadBytesExt.html#method.read_varint_zigzag
+[`WriteBytesExt::write_varint_zigzag`]: https://docs.rs/byteorder/1/byteorder/trait.WriteBytesExt.html#method.write_varint_zigzag
+
+# Examples
+
+```rust
+use byteorder::varint::{decode_uleb128, encode_uleb128, MAX_ULEB128_LEN};
+
+let mut buf = [0; MAX_ULEB128_LEN];
+let len = encode_uleb128(624485, &mut buf).unwrap();
+assert_eq!(&[0xE5, 0x8E, 0x26], &buf[..len]);
+assert_eq!(Ok((624485, 3)), decode_uleb128(&buf));
+```
+*/
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{Error, ErrorKind};
+
+pub mod group;
+
+/// The maximum number of bytes in the unsigned LEB128 encoding of a `u64`.
+pub const MAX_ULEB128_LEN: usize = 10;
+
+/// Returns the number of bytes in the shortest unsigned LEB128 encoding of
+/// `n`.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::varint::uleb128_len;
+///
+/// assert_eq!(1, uleb128_len(0));
+/// assert_eq!(1, uleb128_len(127));
+/// assert_eq!(2, uleb128_len(128));
+/// assert_eq!(10, uleb128_len(u64::MAX));
+/// ```
+#[inline]
+pub const fn uleb128_len(n: u64) -> usize {
+    let bits = 64 - (n | 1).leading_zeros() as usize;
+    (bits + 6) / 7
+}
+
+/// Writes the unsigned LEB128 encoding of `n` to the start of `buf`, and
+/// returns the number of bytes written.
+///
+/// The shortest encoding is always written.
+///
+/// # Errors
+///
+/// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+/// is shorter than [`uleb128_len(n)`](uleb128_len), in which case nothing
+/// is written. A buffer of [`MAX_ULEB128_LEN`] bytes is always big enough.
+#[inline]
+pub fn encode_uleb128(mut n: u64, buf: &mut [u8]) -> Result<usize, Error> {
+    let len = uleb128_len(n);
+    Error::check_len(len, buf.len())?;
+    for b in &mut buf[..len - 1] {
+        *b = (n as u8) | 0x80;
+        n >>= 7;
+    }
+    buf[len - 1] = n as u8;
+    Ok(len)
+}
+
+/// Reads an unsigned LEB128 integer from the start of `buf`, and returns it
+/// along with the number of bytes it occupied.
+///
+/// # Errors
+///
+/// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+/// ends before the last byte of the encoding, in which case `needed` is a
+/// lower bound. It returns an error of kind [`ErrorKind::OutOfRange`] when
+/// the encoding is longer than [`MAX_ULEB128_LEN`] bytes, or its value
+/// doesn't fit in a `u64`.
+#[inline]
+pub fn decode_uleb128(buf: &[u8]) -> Result<(u64, usize), Error> {
+    let mut n = 0u64;
+    for (i, &b) in buf.iter().enumerate().take(MAX_ULEB128_LEN) {
+        // The last byte only has room for the single remaining bit.
+        if i == MAX_ULEB128_LEN - 1 && b > 1 {
+            return Err(Error::new(ErrorKind::OutOfRange));
+        }
+        n |= u64::from(b & 0x7F) << (7 * i);
+        if b & 0x80 == 0 {
+            return Ok((n, i + 1));
+        }
+    }
+    Err(Error::new(ErrorKind::BufferTooSmall {
+        needed: buf.len() + 1,
+        available: buf.len(),
+    }))
+}
+
+/// Writes `n` as a protocol buffers varint to the start of `buf`, and
+/// returns the number of bytes written.
+///
+/// This is the same as [`encode_uleb128`].
+///
+/// # Errors
+///
+/// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+/// is too short, in which case nothing is written.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::varint::encode_varint_u64;
+///
+/// let mut buf = [0; 10];
+/// assert_eq!(Ok(2), encode_varint_u64(150, &mut buf));
+/// assert_eq!(&[0x96, 0x01], &buf[..2]);
+/// ```
+#[inline]
+pub fn encode_varint_u64(n: u64, buf: &mut [u8]) -> Result<usize, Error> {
+    encode_uleb128(n, buf)
+}
+
+/// Reads a protocol buffers varint from the start of `buf`, and returns it
+/// along with the number of bytes it occupied.
+///
+/// This is the same as [`decode_uleb128`].
+///
+/// # Errors
+///
+/// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+/// ends before the last byte of the varint, and an error of kind
+/// [`ErrorKind::OutOfRange`] when the varint is longer than 10 bytes or
+/// doesn't fit in a `u64`.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{varint::decode_varint_u64, ErrorKind};
+///
+/// assert_eq!(Ok((150, 2)), decode_varint_u64(&[0x96, 0x01, 0xFF]));
+///
+/// let overlong = [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0];
+/// let err = decode_varint_u64(&overlong).unwrap_err();
+/// assert_eq!(&ErrorKind::OutOfRange, err.kind());
+/// ```
+#[inline]
+pub fn decode_varint_u64(buf: &[u8]) -> Result<(u64, usize), Error> {
+    decode_uleb128(buf)
+}
+
+/// Appends the unsigned LEB128 encodings of all of `values` to `out`.
+///
+/// This is the same as calling [`encode_uleb128`] for every value, but
+/// grows `out` once, by exactly the size of the batch, instead of once per
+/// value.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::varint::{decode_varints, encode_varints};
+///
+/// let mut column = vec![];
+/// encode_varints(&[1, 300, u64::MAX], &mut column);
+/// assert_eq!(13, column.len());
+/// assert_eq!(Ok((vec![1, 300, u64::MAX], 13)), decode_varints(&column));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_varints(values: &[u64], out: &mut Vec<u8>) {
+    let start = out.len();
+    let len: usize = values.iter().map(|&n| uleb128_len(n)).sum();
+    out.resize(start + len, 0);
+    let mut pos = start;
+    for &n in values {
+        let mut n = n;
+        while n >= 0x80 {
+            out[pos] = (n as u8) | 0x80;
+            n >>= 7;
+            pos += 1;
+        }
+        out[pos] = n as u8;
+        pos += 1;
+    }
+    debug_assert_eq!(out.len(), pos);
+}
+
+/// Decodes every complete unsigned LEB128 integer in `buf`, and returns
+/// them along with the number of bytes they occupied.
+///
+/// The number of bytes is less than the length of `buf` only when `buf`
+/// ends in the middle of an integer, so that a caller decoding a stream in
+/// chunks can carry the rest over to the next chunk. The returned vector is
+/// allocated once, with room for exactly the number of integers in `buf`.
+///
+/// # Errors
+///
+/// This returns an error of kind [`ErrorKind::OutOfRange`] when any of the
+/// integers is invalid, as described for [`decode_uleb128`].
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::varint::decode_varints;
+///
+/// // The last integer is cut short.
+/// let (values, len) = decode_varints(&[0x01, 0xAC, 0x02, 0x80]).unwrap();
+/// assert_eq!((vec![1, 300], 3), (values, len));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_varints(buf: &[u8]) -> Result<(Vec<u64>, usize), Error> {
+    // Every integer ends with exactly one byte without the high bit set.
+    let count = buf.iter().filter(|&&b| b & 0x80 == 0).count();
+    let mut values = Vec::with_capacity(count);
+    let mut pos = 0;
+    while values.len() < count {
+        let (n, len) = decode_uleb128(&buf[pos..])?;
+        values.push(n);
+        pos += len;
+    }
+    Ok((values, pos))
+}
+
+/// The maximum number of bytes in the unsigned LEB128 encoding of a `u128`.
+pub const MAX_ULEB128_U128_LEN: usize = 19;
+
+/// Returns the number of bytes in the shortest unsigned LEB128 encoding of
+/// the `u128` `n`.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::varint::uleb128_u128_len;
+///
+/// assert_eq!(1, uleb128_u128_len(0));
+/// assert_eq!(10, uleb128_u128_len(u128::from(u64::MAX)));
+/// assert_eq!(19, uleb128_u128_len(u128::MAX));
+/// ```
+#[inline]
+pub const fn uleb128_u128_len(n: u128) -> usize {
+    let bits = 128 - (n | 1).leading_zeros() as usize;
+    (bits + 6) / 7
+}
+
+/// Writes the unsigned LEB128 encoding of the `u128` `n` to the start of
+/// `buf`, and returns the number of bytes written.
+///
+/// This is the same encoding as [`encode_uleb128`], so values that fit in a
+/// `u64` are encoded the same way by both.
+///
+/// # Errors
+///
+/// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+/// is shorter than [`uleb128_u128_len(n)`](uleb128_u128_len), in which case
+/// nothing is written. A buffer of [`MAX_ULEB128_U128_LEN`] bytes is always
+/// big enough.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::varint::{encode_uleb128_u128, MAX_ULEB128_U128_LEN};
+///
+/// let mut buf = [0; MAX_ULEB128_U128_LEN];
+/// assert_eq!(Ok(19), encode_uleb128_u128(u128::MAX, &mut buf));
+/// assert_eq!(0x03, buf[18]);
+/// ```
+#[inline]
+pub fn encode_uleb128_u128(
+    mut n: u128,
+    buf: &mut [u8],
+) -> Result<usize, Error> {
+    let len = uleb128_u128_len(n);
+    Error::check_len(len, buf.len())?;
+    for b in &mut buf[..len - 1] {
+        *b = (n as u8) | 0x80;
+        n >>= 7;
+    }
+    buf[len - 1] = n as u8;
+    Ok(len)
+}
+
+/// Reads an unsigned LEB128 integer that fits in a `u128` from the start of
+/// `buf`, and returns it along with the number of bytes it occupied.
+///
+/// # Errors
+///
+/// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+/// ends before the last byte of the encoding, in which case `needed` is a
+/// lower bound. It returns an error of kind [`ErrorKind::OutOfRange`] when
+/// the encoding is longer than [`MAX_ULEB128_U128_LEN`] bytes, or its value
+/// doesn't fit in a `u128`.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{varint::decode_uleb128_u128, ErrorKind};
+///
+/// let mut max = [0xFF; 19];
+/// max[18] = 0x03;
+/// assert_eq!(Ok((u128::MAX, 19)), decode_uleb128_u128(&max));
+///
+/// max[18] = 0x04;
+/// let err = decode_uleb128_u128(&max).unwrap_err();
+/// assert_eq!(&ErrorKind::OutOfRange, err.kind());
+/// ```
+#[inline]
+pub fn decode_uleb128_u128(buf: &[u8]) -> Result<(u128, usize), Error> {
+    let mut n = 0u128;
+    for (i, &b) in buf.iter().enumerate().take(MAX_ULEB128_U128_LEN) {
+        // The last byte only has room for the two remaining bits.
+        if i == MAX_ULEB128_U128_LEN - 1 && b > 3 {
+            return Err(Error::new(ErrorKind::OutOfRange));
+        }
+        n |= u128::from(b & 0x7F) << (7 * i);
+        if b & 0x80 == 0 {
+            return Ok((n, i + 1));
+        }
+    }
+    Err(Error::new(ErrorKind::BufferTooSmall {
+        needed: buf.len() + 1,
+        available: buf.len(),
+    }))
+}
+
+/// The maximum number of bytes in the git offset encoding of a `u64`.
+pub const MAX_GIT_OFFSET_LEN: usize = 10;
+
+/// Returns the number of bytes in the git offset encoding of `n`.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::varint::git_offset_len;
+///
+/// assert_eq!(1, git_offset_len(127));
+/// assert_eq!(2, git_offset_len(128));
+/// assert_eq!(2, git_offset_len(16511));
+/// assert_eq!(3, git_offset_len(16512));
+/// ```
+#[inline]
+pub const fn git_offset_len(mut n: u64) -> usize {
+    let mut len = 1;
+    n >>= 7;
+    while n != 0 {
+        n -= 1;
+        n >>= 7;
+        len += 1;
+    }
+    len
+}
+
+/// Writes the git offset encoding of `n` to the start of `buf`, and returns
+/// the number of bytes written.
+///
+/// # Errors
+///
+/// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+/// is shorter than [`git_offset_len(n)`](git_offset_len), in which case
+/// nothing is written. A buffer of [`MAX_GIT_OFFSET_LEN`] bytes is always
+/// big enough.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::varint::encode_git_offset;
+///
+/// let mut buf = [0; 10];
+/// assert_eq!(Ok(2), encode_git_offset(300, &mut buf));
+/// assert_eq!(&[0x81, 0x2C], &buf[..2]);
+/// ```
+#[inline]
+pub fn encode_git_offset(mut n: u64, buf: &mut [u8]) -> Result<usize, Error> {
+    let len = git_offset_len(n);
+    Error::check_len(len, buf.len())?;
+    // The least significant group comes last, so fill in backwards.
+    buf[len - 1] = (n & 0x7F) as u8;
+    for b in buf[..len - 1].iter_mut().rev() {
+        n = (n >> 7) - 1;
+        *b = 0x80 | (n & 0x7F) as u8;
+    }
+    Ok(len)
+}
+
+/// Reads a git offset from the start of `buf`, and returns it along with
+/// the number of bytes it occupied.
+///
+/// # Errors
+///
+/// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+/// ends before the last byte of the encoding, in which case `needed` is a
+/// lower bound. It returns an error of kind [`ErrorKind::OutOfRange`] when
+/// the value doesn't fit in a `u64`, which also bounds the encoding to
+/// [`MAX_GIT_OFFSET_LEN`] bytes.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::varint::decode_git_offset;
+///
+/// assert_eq!(Ok((300, 2)), decode_git_offset(&[0x81, 0x2C]));
+/// ```
+#[inline]
+pub fn decode_git_offset(buf: &[u8]) -> Result<(u64, usize), Error> {
+    let mut n = 0u64;
+    for (i, &b) in buf.iter().enumerate() {
+        if i > 0 {
+            // Seven more bits must fit, after adding the bias.
+            n = match n.checked_add(1) {
+                Some(n) if n >> 57 == 0 => n,
+                _ => return Err(Error::new(ErrorKind::OutOfRange)),
+            };
+        }
+        n = (n << 7) | u64::from(b & 0x7F);
+        if b & 0x80 == 0 {
+            return Ok((n, i + 1));
+        }
+    }
+    Err(Error::new(ErrorKind::BufferTooSmall {
+        needed: buf.len() + 1,
+        available: buf.len(),
+    }))
+}
+
+/// The maximum number of bytes in the prefix varint encoding of a `u64`.
+pub const MAX_PREFIX_VARINT_LEN: usize = 9;
+
+/// Returns the number of bytes in the prefix varint encoding of `n`.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::varint::prefix_varint_len;
+///
+/// assert_eq!(1, prefix_varint_len(127));
+/// assert_eq!(2, prefix_varint_len(128));
+/// assert_eq!(8, prefix_varint_len((1 << 56) - 1));
+/// assert_eq!(9, prefix_varint_len(1 << 56));
+/// ```
+#[inline]
+pub const fn prefix_varint_len(n: u64) -> usize {
+    let bits = 64 - (n | 1).leading_zeros() as usize;
+    if bits > 56 {
+        9
+    } else {
+        (bits + 6) / 7
+    }
+}
+
+/// Writes the prefix varint encoding of `n` to the start of `buf`, and
+/// returns the number of bytes written.
+///
+/// # Errors
+///
+/// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+/// is shorter than [`prefix_varint_len(n)`](prefix_varint_len), in which
+/// case nothing is written. A buffer of [`MAX_PREFIX_VARINT_LEN`] bytes is
+/// always big enough.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::varint::encode_prefix_varint;
+///
+/// let mut buf = [0; 9];
+/// assert_eq!(Ok(2), encode_prefix_varint(300, &mut buf));
+/// assert_eq!(&[0x81, 0x2C], &buf[..2]);
+/// ```
+#[inline]
+pub fn encode_prefix_varint(n: u64, buf: &mut [u8]) -> Result<usize, Error> {
+    let len = prefix_varint_len(n);
+    Error::check_len(len, buf.len())?;
+    if len == MAX_PREFIX_VARINT_LEN {
+        buf[0] = 0xFF;
+        buf[1..9].copy_from_slice(&n.to_be_bytes());
+    } else {
+        buf[..len].copy_from_slice(&n.to_be_bytes()[8 - len..]);
+        // The value fits below the marker, whose zero bit is already clear.
+        buf[0] |= !(0xFF >> (len - 1));
+    }
+    Ok(len)
+}
+
+/// Reads a prefix varint from the start of `buf`, and returns it along with
+/// the number of bytes it occupied.
+///
+/// # Errors
+///
+/// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+/// is shorter than the length given by its first byte. Since every
+/// encoding of at most [`MAX_PREFIX_VARINT_LEN`] bytes is a valid `u64`,
+/// there are no other errors.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::varint::decode_prefix_varint;
+///
+/// assert_eq!(Ok((300, 2)), decode_prefix_varint(&[0x81, 0x2C, 0xFF]));
+/// assert!(decode_prefix_varint(&[0xC0, 0]).is_err());
+/// ```
+#[inline]
+pub fn decode_prefix_varint(buf: &[u8]) -> Result<(u64, usize), Error> {
+    Error::check_len(1, buf.len())?;
+    let len = prefix_varint_len_of(buf[0]);
+    Error::check_len(len, buf.len())?;
+    Ok((prefix_varint_value(&buf[..len]), len))
+}
+
+/// Returns the length of a prefix varint from its first byte.
+#[inline]
+fn prefix_varint_len_of(first: u8) -> usize {
+    first.leading_ones() as usize + 1
+}
+
+/// Returns the value of the complete prefix varint `enc`.
+#[inline]
+fn prefix_varint_value(enc: &[u8]) -> u64 {
+    // Only the bits below the marker's zero bit, which for the longest
+    // encoding is none at all.
+    let mut n = u64::from(enc[0]) & (0xFF >> enc.len());
+    for &b in &enc[1..] {
+        n = (n << 8) | u64::from(b);
+    }
+    n
+}
+
+/// Maps a signed 32 bit integer to an unsigned one, so that numbers close to
+/// zero are small.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::varint::{zigzag_decode_u32, zigzag_encode_i32};
+///
+/// assert_eq!(0, zigzag_encode_i32(0));
+/// assert_eq!(1, zigzag_encode_i32(-1));
+/// assert_eq!(2, zigzag_encode_i32(1));
+/// assert_eq!(u32::MAX, zigzag_encode_i32(i32::MIN));
+/// assert_eq!(-1, zigzag_decode_u32(1));
+/// ```
+#[inline]
+pub const fn zigzag_encode_i32(n: i32) -> u32 {
+    ((n << 1) ^ (n >> 31)) as u32
+}
+
+/// Maps a signed 64 bit integer to an unsigned one, so that numbers close to
+/// zero are small.
+#[inline]
+pub const fn zigzag_encode_i64(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+/// The inverse of [`zigzag_encode_i32`].
+#[inline]
+pub const fn zigzag_decode_u32(n: u32) -> i32 {
+    ((n >> 1) as i32) ^ -((n & 1) as i32)
+}
+
+/// The inverse of [`zigzag_encode_i64`].
+#[inline]
+pub const fn zigzag_decode_u64(n: u64) -> i64 {
+    ((n >> 1) as i64) ^ -((n & 1) as i64)
+}
+
+/// Maps a signed 128 bit integer to an unsigned one, so that numbers close
+/// to zero are small.
+#[inline]
+pub const fn zigzag_encode_i128(n: i128) -> u128 {
+    ((n << 1) ^ (n >> 127)) as u128
+}
+
+/// The inverse of [`zigzag_encode_i128`].
+#[inline]
+pub const fn zigzag_decode_u128(n: u128) -> i128 {
+    ((n >> 1) as i128) ^ -((n & 1) as i128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_git_offset, decode_prefix_varint, decode_uleb128,
+        decode_uleb128_u128, encode_git_offset, encode_prefix_varint,
+        encode_uleb128, encode_uleb128_u128, git_offset_len,
+        prefix_varint_len, uleb128_len, uleb128_u128_len, zigzag_decode_u128,
+        zigzag_decode_u32, zigzag_decode_u64, zigzag_encode_i128,
+        zigzag_encode_i32, zigzag_encode_i64, MAX_GIT_OFFSET_LEN,
+        MAX_PREFIX_VARINT_LEN, MAX_ULEB128_LEN, MAX_ULEB128_U128_LEN,
+    };
+    use crate::{Error, ErrorKind};
+
+    #[test]
+    fn uleb128_roundtrip() {
+        for shift in 0..64 {
+            for &n in &[1u64 << shift, (1 << shift) - 1, !0 >> shift] {
+                let mut buf = [0; MAX_ULEB128_LEN];
+                let len = encode_uleb128(n, &mut buf).unwrap();
+                assert_eq!(uleb128_len(n), len);
+                assert_eq!(Ok((n, len)), decode_uleb128(&buf[..len]));
+                assert_eq!(
+                    Err(Error::new(ErrorKind::BufferTooSmall {
+                        needed: len,
+                        available: len - 1
+                    })),
+                    encode_uleb128(n, &mut buf[..len - 1])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn uleb128_limits() {
+        let max = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+        assert_eq!(Ok((u64::MAX, 10)), decode_uleb128(&max));
+        // Redundant zero groups are fine, within the limit.
+        assert_eq!(Ok((1, 3)), decode_uleb128(&[0x81, 0x80, 0x00]));
+
+        let mut overflow = max;
+        overflow[9] = 0x02;
+        assert_eq!(
+            Err(Error::new(ErrorKind::OutOfRange)),
+            decode_uleb128(&overflow)
+        );
+        let mut overlong = [0x80; 11];
+        overlong[10] = 0;
+        assert_eq!(
+            Err(Error::new(ErrorKind::OutOfRange)),
+            decode_uleb128(&overlong)
+        );
+        assert_eq!(
+            Err(Error::new(ErrorKind::BufferTooSmall {
+                needed: 3,
+                available: 2
+            })),
+            decode_uleb128(&[0x80, 0x80])
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn varint_batches() {
+        use alloc::{vec, vec::Vec};
+
+        use super::{decode_varints, encode_varints};
+
+        let values: Vec<u64> = (0..64)
+            .flat_map(|shift| vec![1 << shift, (1 << shift) - 1, !0 >> shift])
+            .collect();
+        let mut buf = vec![0xAA];
+        encode_varints(&values, &mut buf);
+        let mut expected = vec![0xAA];
+        for &n in &values {
+            let mut one = [0; MAX_ULEB128_LEN];
+            let len = encode_uleb128(n, &mut one).unwrap();
+            expected.extend_from_slice(&one[..len]);
+        }
+        assert_eq!(expected, buf);
+
+        assert_eq!(
+            Ok((values.clone(), buf.len() - 1)),
+            decode_varints(&buf[1..])
+        );
+        // A partial integer at the end is left over.
+        let full = buf.len() - 1;
+        buf.extend_from_slice(&[0xFF, 0xFF]);
+        assert_eq!(Ok((values, full)), decode_varints(&buf[1..]));
+        assert_eq!(Ok((vec![], 0)), decode_varints(&[]));
+
+        let mut overlong = [0x80; 11];
+        overlong[10] = 0;
+        assert_eq!(
+            Err(Error::new(ErrorKind::OutOfRange)),
+            decode_varints(&overlong)
+        );
+    }
+
+    #[test]
+    fn uleb128_u128() {
+        for shift in 0..128 {
+            for &n in &[1u128 << shift, (1 << shift) - 1, !0 >> shift] {
+                let mut buf = [0; MAX_ULEB128_U128_LEN];
+                let len = encode_uleb128_u128(n, &mut buf).unwrap();
+                assert_eq!(uleb128_u128_len(n), len);
+                assert_eq!(Ok((n, len)), decode_uleb128_u128(&buf[..len]));
+                assert_eq!(
+                    Err(Error::new(ErrorKind::BufferTooSmall {
+                        needed: len,
+                        available: len - 1
+                    })),
+                    encode_uleb128_u128(n, &mut buf[..len - 1])
+                );
+                // Values that fit in a u64 are encoded the same way.
+                if let Ok(n) = u64::try_from(n) {
+                    let mut buf64 = [0; MAX_ULEB128_LEN];
+                    assert_eq!(Ok(len), encode_uleb128(n, &mut buf64));
+                    assert_eq!(&buf[..len], &buf64[..len]);
+                }
+            }
+        }
+
+        let mut overflow = [0xFF; MAX_ULEB128_U128_LEN];
+        overflow[18] = 0x04;
+        assert_eq!(
+            Err(Error::new(ErrorKind::OutOfRange)),
+            decode_uleb128_u128(&overflow)
+        );
+        let mut overlong = [0x80; 20];
+        overlong[19] = 0;
+        assert_eq!(
+            Err(Error::new(ErrorKind::OutOfRange)),
+            decode_uleb128_u128(&overlong)
+        );
+        assert_eq!(
+            Err(Error::new(ErrorKind::BufferTooSmall {
+                needed: 12,
+                available: 11
+            })),
+            decode_uleb128_u128(&[0x80; 11])
+        );
+    }
+
+    #[test]
+    fn zigzag() {
+        let cases =
+            [(0, 0), (-1, 1), (1, 2), (-2, 3), (i32::MAX, u32::MAX - 1)];
+        for &(n, z) in cases.iter() {
+            assert_eq!(z, zigzag_encode_i32(n));
+            assert_eq!(n, zigzag_decode_u32(z));
+            assert_eq!(u64::from(z), zigzag_encode_i64(i64::from(n)));
+            assert_eq!(i64::from(n), zigzag_decode_u64(u64::from(z)));
+        }
+        assert_eq!(u32::MAX, zigzag_encode_i32(i32::MIN));
+        assert_eq!(i32::MIN, zigzag_decode_u32(u32::MAX));
+        assert_eq!(u64::MAX, zigzag_encode_i64(i64::MIN));
+        assert_eq!(i64::MIN, zigzag_decode_u64(u64::MAX));
+        assert_eq!(u128::MAX, zigzag_encode_i128(i128::MIN));
+        assert_eq!(i128::MIN, zigzag_decode_u128(u128::MAX));
+        assert_eq!(u128::MAX - 1, zigzag_encode_i128(i128::MAX));
+        assert_eq!(-3, zigzag_decode_u128(5));
+    }
+
+    #[test]
+    fn git_offsets() {
+        let cases: &[(u64, &[u8])] = &[
+            (0, &[0x00]),
+            (127, &[0x7F]),
+            (128, &[0x80, 0x00]),
+            (16511, &[0xFF, 0x7F]),
+            (16512, &[0x80, 0x80, 0x00]),
+        ];
+        for &(n, enc) in cases {
+            let mut buf = [0; MAX_GIT_OFFSET_LEN];
+            assert_eq!(Ok(enc.len()), encode_git_offset(n, &mut buf));
+            assert_eq!(enc, &buf[..enc.len()]);
+            assert_eq!(Ok((n, enc.len())), decode_git_offset(enc));
+        }
+        for shift in 0..64 {
+            for &n in &[1u64 << shift, (1 << shift) - 1, !0 >> shift] {
+                let mut buf = [0; MAX_GIT_OFFSET_LEN];
+                let len = encode_git_offset(n, &mut buf).unwrap();
+                assert_eq!(git_offset_len(n), len);
+                assert_eq!(Ok((n, len)), decode_git_offset(&buf[..len]));
+                assert!(encode_git_offset(n, &mut buf[..len - 1]).is_err());
+            }
+        }
+
+        // The largest value that fits in ten bytes doesn't fit in a u64.
+        let mut over = [0xFF; MAX_GIT_OFFSET_LEN];
+        over[9] = 0x7F;
+        assert_eq!(
+            Err(Error::new(ErrorKind::OutOfRange)),
+            decode_git_offset(&over)
+        );
+        assert_eq!(
+            Err(Error::new(ErrorKind::OutOfRange)),
+            decode_git_offset(&[0x80; 20])
+        );
+        assert_eq!(
+            Err(Error::new(ErrorKind::BufferTooSmall {
+                needed: 2,
+                available: 1
+            })),
+            decode_git_offset(&[0x80])
+        );
+    }
+
+    #[test]
+    fn prefix_varints() {
+        let cases: &[(u64, &[u8])] = &[
+            (0, &[0x00]),
+            (127, &[0x7F]),
+            (128, &[0x80, 0x80]),
+            (0x3FFF, &[0xBF, 0xFF]),
+            (0x4000, &[0xC0, 0x40, 0x00]),
+            ((1 << 56) - 1, &[0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]),
+            (u64::MAX, &[0xFF; 9]),
+        ];
+        for &(n, enc) in cases {
+            let mut buf = [0; MAX_PREFIX_VARINT_LEN];
+            assert_eq!(Ok(enc.len()), encode_prefix_varint(n, &mut buf));
+            assert_eq!(enc, &buf[..enc.len()]);
+            assert_eq!(Ok((n, enc.len())), decode_prefix_varint(enc));
+        }
+
+        let (mut prev, mut prev_len) = ([0; MAX_PREFIX_VARINT_LEN], 0);
+        for shift in 0..64 {
+            for &n in &[(1u64 << shift) - 1, 1 << shift] {
+                let mut buf = [0; MAX_PREFIX_VARINT_LEN];
+                let len = encode_prefix_varint(n, &mut buf).unwrap();
+                assert_eq!(prefix_varint_len(n), len);
+                assert_eq!(Ok((n, len)), decode_prefix_varint(&buf[..len]));
+                assert!(encode_prefix_varint(n, &mut buf[..len - 1]).is_err());
+                // Encodings sort like the numbers.
+                assert!(prev[..prev_len] <= buf[..len]);
+                prev = buf;
+                prev_len = len;
+            }
+        }
+
+        // Overlong encodings are accepted.
+        assert_eq!(Ok((1, 3)), decode_prefix_varint(&[0xC0, 0, 1]));
+        assert_eq!(
+            Err(Error::new(ErrorKind::BufferTooSmall {
+                needed: 9,
+                available: 8
+            })),
+            decode_prefix_varint(&[0xFF; 8])
+        );
+        assert_eq!(
+            Err(Error::new(ErrorKind::BufferTooSmall {
+                needed: 1,
+                available: 0
+            })),
+            decode_prefix_varint(&[])
+        );
+    }
+}
@@ -0,0 +1,313 @@
+/*!
+Group varint, a bulk codec for `u32`s.
+
+Group varint encodes 32 bit integers four at a time. Each group starts with a
+control byte holding four 2 bit fields, one per value starting with the least
+significant bits, each giving the length of its value minus one. The values
+follow the control byte, each in 1 to 4 bytes, in little-endian. Since the
+lengths of all four values are known from a single byte, a decoder doesn't
+need a branch per byte like it does for LEB128, which makes this several
+times faster for decoding long lists of integers, like the columns of an
+analytics store.
+
+The number of values isn't part of the encoding, and must be stored
+separately. When it is not a multiple of four, the last group has fewer
+values, and the fields for the missing values in its control byte are zero.
+
+On `x86_64`, decoding uses SSSE3 to decode a whole group with one shuffle
+when the CPU supports it. With the `std` feature, this is detected at
+runtime. Without it, it is only used when the crate is compiled with the
+`ssse3` target feature enabled. Both paths produce identical results.
+
+# Examples
+
+```rust
+use byteorder::varint::group;
+
+let values = [1, 300, 70_000, 1 << 30, 5];
+let mut buf = [0; 32];
+let len = group::encode(&values, &mut buf).unwrap();
+assert_eq!(group::encoded_len(&values), len);
+// Two control bytes, and 1 + 2 + 3 + 4 + 1 bytes of values.
+assert_eq!(13, len);
+
+let mut out = [0; 5];
+assert_eq!(Ok(len), group::decode(&buf[..len], &mut out));
+assert_eq!(values, out);
+```
+*/
+
+use crate::{Error, ErrorKind};
+
+/// Returns the number of bytes needed to store `n`, from 1 to 4.
+#[inline]
+fn value_len(n: u32) -> usize {
+    (4 - ((n | 1).leading_zeros() >> 3)) as usize
+}
+
+/// Returns the maximum number of bytes in the encoding of `count` values.
+///
+/// This is useful for sizing a buffer before encoding.
+#[inline]
+pub const fn max_encoded_len(count: usize) -> usize {
+    count * 4 + (count + 3) / 4
+}
+
+/// Returns the number of bytes in the encoding of `values`.
+pub fn encoded_len(values: &[u32]) -> usize {
+    let data: usize = values.iter().map(|&n| value_len(n)).sum();
+    data + (values.len() + 3) / 4
+}
+
+/// Encodes `values` to the start of `buf`, and returns the number of bytes
+/// written.
+///
+/// # Errors
+///
+/// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `buf`
+/// is shorter than [`encoded_len(values)`](encoded_len), in which case an
+/// unspecified prefix of `buf` may have been written. A buffer of
+/// [`max_encoded_len(values.len())`](max_encoded_len) bytes is always big
+/// enough.
+pub fn encode(values: &[u32], buf: &mut [u8]) -> Result<usize, Error> {
+    if buf.len() < max_encoded_len(values.len()) {
+        Error::check_len(encoded_len(values), buf.len())?;
+    }
+    let mut pos = 0;
+    for group in values.chunks(4) {
+        let control = pos;
+        let mut lens = 0;
+        pos += 1;
+        for (i, &n) in group.iter().enumerate() {
+            let len = value_len(n);
+            lens |= ((len - 1) as u8) << (2 * i);
+            buf[pos..pos + len].copy_from_slice(&n.to_le_bytes()[..len]);
+            pos += len;
+        }
+        buf[control] = lens;
+    }
+    Ok(pos)
+}
+
+/// Decodes `out.len()` values from the start of `data` into `out`, and
+/// returns the number of bytes of `data` they occupied.
+///
+/// # Errors
+///
+/// This returns an error of kind [`ErrorKind::BufferTooSmall`] when `data`
+/// ends before `out.len()` values were decoded. In that case, `needed` is
+/// the number of bytes up to the end of the group that could not be
+/// decoded, and an unspecified prefix of `out` may have been written.
+pub fn decode(data: &[u8], out: &mut [u32]) -> Result<usize, Error> {
+    let (mut pos, mut i) = (0, 0);
+    #[cfg(target_arch = "x86_64")]
+    {
+        if has_ssse3() {
+            // SAFETY: the CPU supports SSSE3.
+            let (p, n) = unsafe { ssse3::decode_groups(data, out) };
+            pos = p;
+            i = n;
+        }
+    }
+    while i < out.len() {
+        let count = (out.len() - i).min(4);
+        Error::check_len(pos + 1, data.len())?;
+        let control = data[pos];
+        let len = group_len(control, count);
+        if data.len() < pos + 1 + len {
+            return Err(Error::new(ErrorKind::BufferTooSmall {
+                needed: pos + 1 + len,
+                available: data.len(),
+            }));
+        }
+        pos += 1;
+        for (lane, n) in out[i..i + count].iter_mut().enumerate() {
+            let len = usize::from((control >> (2 * lane)) & 3) + 1;
+            let mut bytes = [0; 4];
+            bytes[..len].copy_from_slice(&data[pos..pos + len]);
+            *n = u32::from_le_bytes(bytes);
+            pos += len;
+        }
+        i += count;
+    }
+    Ok(pos)
+}
+
+/// Returns the number of data bytes of the first `count` values of a group
+/// with the given control byte.
+#[inline]
+fn group_len(control: u8, count: usize) -> usize {
+    (0..count).map(|lane| usize::from((control >> (2 * lane)) & 3) + 1).sum()
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline]
+fn has_ssse3() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("ssse3")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "ssse3")
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod ssse3 {
+    use core::arch::x86_64::{
+        __m128i, _mm_loadu_si128, _mm_shuffle_epi8, _mm_storeu_si128,
+    };
+
+    /// For every control byte, the shuffle that moves the data bytes of a
+    /// group into four little-endian `u32`s, with `0x80` for the bytes that
+    /// must be zero.
+    static SHUFFLES: [[u8; 16]; 256] = shuffles();
+
+    /// For every control byte, the number of data bytes in its group.
+    static LENGTHS: [u8; 256] = lengths();
+
+    const fn shuffles() -> [[u8; 16]; 256] {
+        let mut table = [[0x80; 16]; 256];
+        let mut control = 0;
+        while control < 256 {
+            let mut src = 0;
+            let mut lane = 0;
+            while lane < 4 {
+                let len = ((control >> (2 * lane)) & 3) + 1;
+                let mut j = 0;
+                while j < len {
+                    table[control][4 * lane + j] = src as u8;
+                    src += 1;
+                    j += 1;
+                }
+                lane += 1;
+            }
+            control += 1;
+        }
+        table
+    }
+
+    const fn lengths() -> [u8; 256] {
+        let mut table = [0; 256];
+        let mut control = 0;
+        while control < 256 {
+            let mut lane = 0;
+            while lane < 4 {
+                table[control] += (((control >> (2 * lane)) & 3) + 1) as u8;
+                lane += 1;
+            }
+            control += 1;
+        }
+        table
+    }
+
+    /// Decodes as many whole groups into `out` as can be done with 16 byte
+    /// loads that stay within `data`, and returns the number of bytes read
+    /// and values written.
+    ///
+    /// # Safety
+    ///
+    /// The CPU must support SSSE3.
+    #[target_feature(enable = "ssse3")]
+    pub(super) unsafe fn decode_groups(
+        data: &[u8],
+        out: &mut [u32],
+    ) -> (usize, usize) {
+        let (mut pos, mut i) = (0, 0);
+        // Every group needs a control byte and a 16 byte load after it.
+        while i + 4 <= out.len() && pos + 17 <= data.len() {
+            let control = usize::from(data[pos]);
+            let src = data.as_ptr().add(pos + 1) as *const __m128i;
+            let mask = SHUFFLES[control].as_ptr() as *const __m128i;
+            let values =
+                _mm_shuffle_epi8(_mm_loadu_si128(src), _mm_loadu_si128(mask));
+            _mm_storeu_si128(out.as_mut_ptr().add(i) as *mut __m128i, values);
+            pos += 1 + usize::from(LENGTHS[control]);
+            i += 4;
+        }
+        (pos, i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, encoded_len, max_encoded_len};
+    use crate::{Error, ErrorKind};
+
+    /// A deterministic mix of values of every length.
+    fn values(count: usize) -> impl Iterator<Item = u32> {
+        let mut x = 0x9E37_79B9u32;
+        (0..count).map(move |i| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x >> (8 * (i % 4))
+        })
+    }
+
+    #[test]
+    fn roundtrip() {
+        for count in 0..70 {
+            let mut vals = [0; 70];
+            for (v, n) in vals.iter_mut().zip(values(count)) {
+                *v = n;
+            }
+            let vals = &vals[..count];
+            let mut buf = [0xAA; max_encoded_len(70) + 16];
+            let len = encode(vals, &mut buf).unwrap();
+            assert_eq!(encoded_len(vals), len);
+            assert!(len <= max_encoded_len(count));
+
+            let mut out = [0; 70];
+            // With trailing bytes, so that the fast path is taken if it
+            // exists, and without.
+            assert_eq!(Ok(len), decode(&buf, &mut out[..count]));
+            assert_eq!(vals, &out[..count]);
+            let mut out = [0; 70];
+            assert_eq!(Ok(len), decode(&buf[..len], &mut out[..count]));
+            assert_eq!(vals, &out[..count]);
+        }
+    }
+
+    #[test]
+    fn format() {
+        let mut buf = [0; 16];
+        let len = encode(&[1, 0x0100, 0x01_0000, 0x0100_0000, 7], &mut buf);
+        assert_eq!(Ok(13), len);
+        assert_eq!(
+            &[0b11_10_01_00, 1, 0, 1, 0, 0, 1, 0, 0, 0, 1, 0b00, 7],
+            &buf[..13]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let vals = [u32::MAX; 5];
+        let mut buf = [0; 22];
+        assert_eq!(
+            Err(Error::new(ErrorKind::BufferTooSmall {
+                needed: 22,
+                available: 21
+            })),
+            encode(&vals, &mut buf[..21])
+        );
+        assert_eq!(Ok(22), encode(&vals, &mut buf));
+
+        let mut out = [0; 5];
+        assert_eq!(
+            Err(Error::new(ErrorKind::BufferTooSmall {
+                needed: 22,
+                available: 21
+            })),
+            decode(&buf[..21], &mut out)
+        );
+        assert_eq!(
+            Err(Error::new(ErrorKind::BufferTooSmall {
+                needed: 17,
+                available: 16
+            })),
+            decode(&buf[..16], &mut out)
+        );
+    }
+}
@@ -0,0 +1,226 @@
+/*!
+Reading records whose fields depend on a format version.
+
+Formats evolve by adding fields, and a parser for such a format ends up full
+of `if version >= 3 { read } else { default }`. A [`VersionedReader`] carries
+the version of the data being read, so each optional field becomes a single
+call that names the version it was introduced in.
+
+It also handles a subtle case correctly: optional fields at the end of a
+record. Some writers never emit trailing fields they don't know about, so a
+reader must treat "the input ended right before this field" as "the field is
+absent", while still treating "the input ended in the middle of this field"
+as corruption.
+
+# Examples
+
+```rust
+use std::io::Cursor;
+
+use byteorder::{versioned::VersionedReader, LittleEndian, ReadBytesExt};
+
+struct Entry {
+    id: u32,
+    // Added in version 2.
+    flags: u16,
+    // Added in version 3, and optional at the end of the record.
+    checksum: u32,
+}
+
+fn read_entry<R: std::io::Read>(
+    rdr: &mut VersionedReader<R>,
+) -> std::io::Result<Entry> {
+    Ok(Entry {
+        id: rdr.read_u32::<LittleEndian>()?,
+        flags: rdr.since(2, 0, |r| r.read_u16::<LittleEndian>())?,
+        checksum: rdr.trailing(3, 0, |r| r.read_u32::<LittleEndian>())?,
+    })
+}
+
+// A version 3 writer that didn't bother writing the checksum.
+let mut rdr = VersionedReader::new(Cursor::new(vec![1, 0, 0, 0, 5, 0]), 3);
+let entry = read_entry(&mut rdr).unwrap();
+assert_eq!((1, 5, 0), (entry.id, entry.flags, entry.checksum));
+
+// A version 1 record doesn't have flags at all.
+let mut rdr = VersionedReader::new(Cursor::new(vec![1, 0, 0, 0]), 1);
+let entry = read_entry(&mut rdr).unwrap();
+assert_eq!((1, 0), (entry.id, entry.flags));
+```
+*/
+
+use std::io::{self, Read, Result};
+
+/// A reader that knows the version of the data it reads.
+///
+/// Fields that were added in some version of a format are read with
+/// [`VersionedReader::since`], or [`VersionedReader::trailing`] when they
+/// may be missing from the end of a record. All other reads go through the
+/// `io::Read` implementation, so the methods of
+/// [`ReadBytesExt`](crate::ReadBytesExt) work as usual.
+#[derive(Debug)]
+pub struct VersionedReader<R> {
+    rdr: R,
+    version: u32,
+    nread: u64,
+}
+
+impl<R: Read> VersionedReader<R> {
+    /// Creates a reader for data of the given version.
+    pub fn new(rdr: R, version: u32) -> VersionedReader<R> {
+        VersionedReader { rdr, version, nread: 0 }
+    }
+
+    /// Returns the version of the data being read.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Changes the version of the data being read, e.g., after reading it
+    /// from a header through this reader.
+    pub fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+
+    /// Returns the number of bytes read through this reader so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.nread
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.rdr
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Bytes read directly from the underlying reader are not counted by
+    /// [`VersionedReader::bytes_read`].
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.rdr
+    }
+
+    /// Unwraps this reader, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.rdr
+    }
+
+    /// Reads a field that was introduced in version `since`.
+    ///
+    /// If the data is at least version `since`, then the field is read with
+    /// `read`. Otherwise, `default` is returned and nothing is read.
+    ///
+    /// # Errors
+    ///
+    /// This method returns any error returned by `read`.
+    pub fn since<V, F>(&mut self, since: u32, default: V, read: F) -> Result<V>
+    where
+        F: FnOnce(&mut Self) -> Result<V>,
+    {
+        if self.version >= since {
+            read(self)
+        } else {
+            Ok(default)
+        }
+    }
+
+    /// Reads an optional field that was introduced in version `since` and
+    /// that may be omitted at the end of a record.
+    ///
+    /// This behaves like [`VersionedReader::since`], except that if `read`
+    /// fails with `UnexpectedEof` before it consumed any bytes, then the
+    /// field is considered absent and `default` is returned.
+    ///
+    /// # Errors
+    ///
+    /// This method returns any error returned by `read`, except for the end
+    /// of input described above. In particular, if the input ends part way
+    /// through the field, an error of kind `UnexpectedEof` is returned.
+    pub fn trailing<V, F>(
+        &mut self,
+        since: u32,
+        default: V,
+        read: F,
+    ) -> Result<V>
+    where
+        F: FnOnce(&mut Self) -> Result<V>,
+    {
+        if self.version < since {
+            return Ok(default);
+        }
+        let start = self.nread;
+        match read(self) {
+            Err(ref err)
+                if err.kind() == io::ErrorKind::UnexpectedEof
+                    && self.nread == start =>
+            {
+                Ok(default)
+            }
+            result => result,
+        }
+    }
+}
+
+impl<R: Read> Read for VersionedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = self.rdr.read(buf)?;
+        self.nread += n as u64;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+
+    use super::VersionedReader;
+    use crate::{BigEndian, ReadBytesExt};
+
+    #[test]
+    fn since() {
+        let mut rdr = VersionedReader::new(Cursor::new(vec![0, 9]), 4);
+        let got = rdr.since(5, 1, |r| r.read_u16::<BigEndian>()).unwrap();
+        assert_eq!(1, got);
+        assert_eq!(0, rdr.bytes_read());
+        let got = rdr.since(4, 1, |r| r.read_u16::<BigEndian>()).unwrap();
+        assert_eq!(9, got);
+        assert_eq!(2, rdr.bytes_read());
+
+        // A required field is still required.
+        let err = rdr.since(4, 1, |r| r.read_u16::<BigEndian>()).unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+    }
+
+    #[test]
+    fn trailing() {
+        let mut rdr = VersionedReader::new(Cursor::new(vec![0, 0, 1]), 2);
+        let got = rdr.trailing(1, 0, |r| r.read_u16::<BigEndian>()).unwrap();
+        assert_eq!(0, got);
+        // Only one byte is left, which is a truncated field, not a missing
+        // one.
+        let err =
+            rdr.trailing(1, 0, |r| r.read_u16::<BigEndian>()).unwrap_err();
+        assert_eq!(ErrorKind::UnexpectedEof, err.kind());
+        // And now there really is nothing left.
+        let got = rdr.trailing(1, 7, |r| r.read_u16::<BigEndian>()).unwrap();
+        assert_eq!(7, got);
+    }
+
+    #[test]
+    fn version_from_header() {
+        let mut rdr = VersionedReader::new(Cursor::new(vec![3, 0xAA]), 0);
+        let version = rdr.read_u8().unwrap();
+        rdr.set_version(u32::from(version));
+        assert_eq!(3, rdr.version());
+        assert_eq!(0xAA, rdr.since(3, 0, |r| r.read_u8()).unwrap());
+        assert_eq!(2, rdr.into_inner().position());
+    }
+
+    #[test]
+    fn read_if() {
+        let mut rdr = Cursor::new(vec![1, 2]);
+        assert_eq!(0, rdr.read_if(false, 0, |r| r.read_u8()).unwrap());
+        assert_eq!(1, rdr.read_if(true, 0, |r| r.read_u8()).unwrap());
+        assert_eq!(1, rdr.position());
+    }
+}
@@ -0,0 +1,628 @@
+/*!
+Compile time sizes and offsets of values in their wire encoding.
+
+The [`WireSize`] trait gives the number of bytes a type occupies when it is
+encoded with this crate, which is independent of the type's in-memory
+representation, padding and alignment. The [`wire_layout!`](crate::wire_layout)
+macro uses it to compute the total size of a struct and the offset of each of
+its fields, all as constants. This means buffers can be declared as
+`[u8; Header::WIRE_SIZE]` and field offsets checked in `const` assertions,
+rather than repeating magic numbers that silently drift from the struct
+definition.
+
+The [`FromWire`] and [`ToWire`] traits decode and encode values of such
+types in a given byte order. They are implemented for numbers, arrays,
+tuples of up to 12 elements, [`FourCc`](crate::fourcc::FourCc) and the types
+in [`fixed`](crate::fixed), and the [`wire_newtype!`](crate::wire_newtype) macro implements them for
+newtypes around any of those, like `struct Crc(u32)`. The
+[`ReadBytesExt::read_wire`](crate::ReadBytesExt::read_wire) and
+[`WriteBytesExt::write_wire`](crate::WriteBytesExt::write_wire) methods
+accept any type implementing them.
+
+# Examples
+
+```rust
+use byteorder::{wire_layout, BigEndian, ByteOrder};
+
+wire_layout! {
+    /// The header of some made up file format.
+    #[derive(Debug)]
+    pub struct Header {
+        pub magic: [u8; 4] => OFFSET_OF_MAGIC,
+        pub version: u16 => OFFSET_OF_VERSION,
+        pub count: u32 => OFFSET_OF_COUNT,
+    }
+}
+
+const _: () = assert!(Header::OFFSET_OF_COUNT == 6);
+
+let mut buf = [0; Header::WIRE_SIZE];
+assert_eq!(10, buf.len());
+BigEndian::write_u32(&mut buf[Header::OFFSET_OF_COUNT..], 42);
+assert_eq!(&buf[6..], &[0, 0, 0, 42]);
+```
+*/
+
+use crate::{ByteOrder, Error};
+
+/// Types with a fixed size in their wire encoding.
+///
+/// For numbers, this is simply the size of the type. For arrays, it is the
+/// wire size of the element type multiplied by the length, and for tuples,
+/// it is the sum of the wire sizes of the elements. Structs declared
+/// with [`wire_layout!`](crate::wire_layout) implement this trait as the sum
+/// of the wire sizes of their fields.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::wire::WireSize;
+///
+/// assert_eq!(4, u32::WIRE_SIZE);
+/// assert_eq!(24, <[u64; 3]>::WIRE_SIZE);
+/// ```
+pub trait WireSize {
+    /// The number of bytes that a value of this type occupies when encoded.
+    const WIRE_SIZE: usize;
+}
+
+macro_rules! impl_wire_size {
+    ($($ty:ty),*) => {
+        $(
+            impl WireSize for $ty {
+                const WIRE_SIZE: usize = core::mem::size_of::<$ty>();
+            }
+        )*
+    };
+}
+
+impl_wire_size!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, f32, f64);
+
+impl<T: WireSize, const N: usize> WireSize for [T; N] {
+    const WIRE_SIZE: usize = T::WIRE_SIZE * N;
+}
+
+impl WireSize for crate::fourcc::FourCc {
+    const WIRE_SIZE: usize = 4;
+}
+
+/// Types that can be decoded from their wire encoding.
+///
+/// Decoding is fallible, so that types with restricted values can reject
+/// invalid encodings instead of panicking.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{wire::FromWire, BigEndian, LittleEndian};
+///
+/// assert_eq!(Ok(0x0102), u16::from_wire::<BigEndian>(&[1, 2, 3]));
+/// assert_eq!(Ok([1, 2]), <[u16; 2]>::from_wire::<LittleEndian>(&[1, 0, 2, 0]));
+/// assert!(u32::from_wire::<BigEndian>(&[1, 2, 3]).is_err());
+/// ```
+///
+/// Implementing it for a type that only allows some values:
+///
+/// ```rust
+/// use byteorder::{
+///     wire::{FromWire, WireSize},
+///     BigEndian, ByteOrder, Error, ErrorKind,
+/// };
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Percent(u8);
+///
+/// impl WireSize for Percent {
+///     const WIRE_SIZE: usize = 1;
+/// }
+///
+/// impl FromWire for Percent {
+///     fn from_wire<E: ByteOrder>(buf: &[u8]) -> Result<Percent, Error> {
+///         match u8::from_wire::<E>(buf)? {
+///             n if n <= 100 => Ok(Percent(n)),
+///             _ => Err(ErrorKind::OutOfRange.into()),
+///         }
+///     }
+/// }
+///
+/// assert_eq!(Ok(Percent(42)), Percent::from_wire::<BigEndian>(&[42]));
+/// assert!(Percent::from_wire::<BigEndian>(&[101]).is_err());
+/// ```
+pub trait FromWire: WireSize + Sized {
+    /// Decodes a value from the start of `buf` in byte order `E`.
+    ///
+    /// Only the first `WIRE_SIZE` bytes of `buf` are read.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error of kind
+    /// [`ErrorKind::BufferTooSmall`](crate::ErrorKind::BufferTooSmall) when
+    /// `buf` is shorter than `WIRE_SIZE` bytes, or any error reported by the
+    /// type itself when the encoding is not a valid value.
+    fn from_wire<E: ByteOrder>(buf: &[u8]) -> Result<Self, Error>;
+}
+
+/// Types that can be encoded into their wire encoding.
+///
+/// # Examples
+///
+/// ```rust
+/// use byteorder::{wire::ToWire, BigEndian};
+///
+/// let mut buf = [0; 4];
+/// [1u8, 2].to_wire::<BigEndian>(&mut buf).unwrap();
+/// 0x0304u16.to_wire::<BigEndian>(&mut buf[2..]).unwrap();
+/// assert_eq!([1, 2, 3, 4], buf);
+/// ```
+pub trait ToWire: WireSize {
+    /// Encodes this value at the start of `buf` in byte order `E`.
+    ///
+    /// Only the first `WIRE_SIZE` bytes of `buf` are written.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error of kind
+    /// [`ErrorKind::BufferTooSmall`](crate::ErrorKind::BufferTooSmall) when
+    /// `buf` is shorter than `WIRE_SIZE` bytes, or any error reported by the
+    /// type itself when the value can't be encoded.
+    fn to_wire<E: ByteOrder>(&self, buf: &mut [u8]) -> Result<(), Error>;
+}
+
+macro_rules! impl_wire_number {
+    ($($ty:ty: $read:ident, $write:ident;)*) => {
+        $(
+            impl FromWire for $ty {
+                #[inline]
+                fn from_wire<E: ByteOrder>(buf: &[u8]) -> Result<$ty, Error> {
+                    E::$read(buf)
+                }
+            }
+
+            impl ToWire for $ty {
+                #[inline]
+                fn to_wire<E: ByteOrder>(
+                    &self,
+                    buf: &mut [u8],
+                ) -> Result<(), Error> {
+                    E::$write(buf, *self)
+                }
+            }
+        )*
+    };
+}
+
+impl_wire_number! {
+    u16: try_read_u16, try_write_u16;
+    i16: try_read_i16, try_write_i16;
+    u32: try_read_u32, try_write_u32;
+    i32: try_read_i32, try_write_i32;
+    u64: try_read_u64, try_write_u64;
+    i64: try_read_i64, try_write_i64;
+    u128: try_read_u128, try_write_u128;
+    i128: try_read_i128, try_write_i128;
+    f32: try_read_f32, try_write_f32;
+    f64: try_read_f64, try_write_f64;
+}
+
+impl FromWire for u8 {
+    #[inline]
+    fn from_wire<E: ByteOrder>(buf: &[u8]) -> Result<u8, Error> {
+        Error::check_len(1, buf.len())?;
+        Ok(buf[0])
+    }
+}
+
+impl ToWire for u8 {
+    #[inline]
+    fn to_wire<E: ByteOrder>(&self, buf: &mut [u8]) -> Result<(), Error> {
+        Error::check_len(1, buf.len())?;
+        buf[0] = *self;
+        Ok(())
+    }
+}
+
+impl FromWire for i8 {
+    #[inline]
+    fn from_wire<E: ByteOrder>(buf: &[u8]) -> Result<i8, Error> {
+        u8::from_wire::<E>(buf).map(|n| n as i8)
+    }
+}
+
+impl ToWire for i8 {
+    #[inline]
+    fn to_wire<E: ByteOrder>(&self, buf: &mut [u8]) -> Result<(), Error> {
+        (*self as u8).to_wire::<E>(buf)
+    }
+}
+
+impl<T: FromWire + Copy + Default, const N: usize> FromWire for [T; N] {
+    fn from_wire<E: ByteOrder>(buf: &[u8]) -> Result<[T; N], Error> {
+        Error::check_len(Self::WIRE_SIZE, buf.len())?;
+        let mut out = [T::default(); N];
+        for (i, x) in out.iter_mut().enumerate() {
+            *x = T::from_wire::<E>(&buf[i * T::WIRE_SIZE..])?;
+        }
+        Ok(out)
+    }
+}
+
+impl<T: ToWire, const N: usize> ToWire for [T; N] {
+    fn to_wire<E: ByteOrder>(&self, buf: &mut [u8]) -> Result<(), Error> {
+        Error::check_len(Self::WIRE_SIZE, buf.len())?;
+        for (i, x) in self.iter().enumerate() {
+            x.to_wire::<E>(&mut buf[i * T::WIRE_SIZE..])?;
+        }
+        Ok(())
+    }
+}
+
+impl FromWire for crate::fourcc::FourCc {
+    #[inline]
+    fn from_wire<E: ByteOrder>(
+        buf: &[u8],
+    ) -> Result<crate::fourcc::FourCc, Error> {
+        <[u8; 4]>::from_wire::<E>(buf).map(crate::fourcc::FourCc::new)
+    }
+}
+
+impl ToWire for crate::fourcc::FourCc {
+    #[inline]
+    fn to_wire<E: ByteOrder>(&self, buf: &mut [u8]) -> Result<(), Error> {
+        self.to_bytes().to_wire::<E>(buf)
+    }
+}
+
+/// Implements the wire traits for tuples, whose elements are encoded back to
+/// back in order, like the fields of a struct.
+macro_rules! impl_wire_tuple {
+    ($(($($T:ident)+))+) => {$(
+        impl<$($T: WireSize),+> WireSize for ($($T,)+) {
+            const WIRE_SIZE: usize = 0 $(+ $T::WIRE_SIZE)+;
+        }
+
+        impl<$($T: FromWire),+> FromWire for ($($T,)+) {
+            #[allow(unused_assignments)]
+            fn from_wire<E: ByteOrder>(buf: &[u8]) -> Result<Self, Error> {
+                Error::check_len(Self::WIRE_SIZE, buf.len())?;
+                let mut pos = 0;
+                Ok(($({
+                    let value = $T::from_wire::<E>(&buf[pos..])?;
+                    pos += $T::WIRE_SIZE;
+                    value
+                },)+))
+            }
+        }
+
+        impl<$($T: ToWire),+> ToWire for ($($T,)+) {
+            #[allow(non_snake_case, unused_assignments)]
+            fn to_wire<E: ByteOrder>(
+                &self,
+                buf: &mut [u8],
+            ) -> Result<(), Error> {
+                Error::check_len(Self::WIRE_SIZE, buf.len())?;
+                let ($($T,)+) = self;
+                let mut pos = 0;
+                $(
+                    $T.to_wire::<E>(&mut buf[pos..])?;
+                    pos += $T::WIRE_SIZE;
+                )+
+                Ok(())
+            }
+        }
+    )+};
+}
+
+impl_wire_tuple! {
+    (T0)
+    (T0 T1)
+    (T0 T1 T2)
+    (T0 T1 T2 T3)
+    (T0 T1 T2 T3 T4)
+    (T0 T1 T2 T3 T4 T5)
+    (T0 T1 T2 T3 T4 T5 T6)
+    (T0 T1 T2 T3 T4 T5 T6 T7)
+    (T0 T1 T2 T3 T4 T5 T6 T7 T8)
+    (T0 T1 T2 T3 T4 T5 T6 T7 T8 T9)
+    (T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10)
+    (T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11)
+}
+
+/// Declares a struct along with constants describing its wire layout.
+///
+/// Each field is followed by `=>` and the name of an associated constant
+/// that will hold the byte offset of that field, when the fields are
+/// encoded back to back in declaration order without padding. The struct
+/// also gets an associated `WIRE_SIZE` constant with its total encoded size,
+/// and an implementation of [`WireSize`](crate::wire::WireSize), so it can
+/// in turn be used as a field of another `wire_layout!` struct.
+///
+/// Every field type must implement `WireSize`. Attributes, including doc
+/// comments and derives, may be given on the struct and on each field.
+/// The offset constants have the same visibility as the struct.
+///
+/// See the [`wire`](crate::wire) module for an example.
+///
+/// # Examples
+///
+/// Nesting one layout inside another:
+///
+/// ```rust
+/// use byteorder::wire_layout;
+///
+/// wire_layout! {
+///     struct Point {
+///         x: i32 => OFFSET_OF_X,
+///         y: i32 => OFFSET_OF_Y,
+///     }
+/// }
+///
+/// wire_layout! {
+///     struct Segment {
+///         id: u16 => OFFSET_OF_ID,
+///         from: Point => OFFSET_OF_FROM,
+///         to: Point => OFFSET_OF_TO,
+///     }
+/// }
+///
+/// assert_eq!(18, Segment::WIRE_SIZE);
+/// assert_eq!(10, Segment::OFFSET_OF_TO);
+/// assert_eq!(14, Segment::OFFSET_OF_TO + Point::OFFSET_OF_Y);
+/// ```
+#[macro_export]
+macro_rules! wire_layout {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$field_attr:meta])*
+                $field_vis:vis $field:ident : $ty:ty => $offset:ident
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name {
+            $(
+                $(#[$field_attr])*
+                $field_vis $field: $ty,
+            )*
+        }
+
+        impl $name {
+            /// The number of bytes that this struct occupies when encoded.
+            #[allow(dead_code)]
+            $vis const WIRE_SIZE: usize =
+                0 $(+ <$ty as $crate::wire::WireSize>::WIRE_SIZE)*;
+
+            $crate::wire_layout!(@offsets ($vis) 0; $($offset: $ty,)*);
+        }
+
+        impl $crate::wire::WireSize for $name {
+            const WIRE_SIZE: usize = $name::WIRE_SIZE;
+        }
+    };
+    (@offsets ($($vis:tt)*) $at:expr; $offset:ident: $ty:ty, $($rest:tt)*) => {
+        #[allow(dead_code)]
+        $($vis)* const $offset: usize = $at;
+
+        $crate::wire_layout!(
+            @offsets
+            ($($vis)*)
+            $at + <$ty as $crate::wire::WireSize>::WIRE_SIZE;
+            $($rest)*
+        );
+    };
+    (@offsets ($($vis:tt)*) $at:expr;) => {};
+}
+
+/// Implements the wire traits for newtypes around types that have them.
+///
+/// Each type must be a tuple struct with a single field whose type
+/// implements [`WireSize`](crate::wire::WireSize),
+/// [`FromWire`](crate::wire::FromWire) and [`ToWire`](crate::wire::ToWire),
+/// and whose field is visible where the macro is used. The newtype then
+/// implements all three traits with exactly the encoding of its field.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::io::Cursor;
+///
+/// use byteorder::{wire_newtype, BigEndian, ReadBytesExt};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Crc(u32);
+///
+/// #[derive(Debug, PartialEq)]
+/// struct BlockId(u64);
+///
+/// wire_newtype!(Crc(u32), BlockId(u64));
+///
+/// let mut rdr = Cursor::new(vec![0, 0, 0, 7, 0, 0, 0, 0, 0, 0, 0, 9]);
+/// assert_eq!(Crc(7), rdr.read_wire::<Crc, BigEndian>().unwrap());
+/// assert_eq!(BlockId(9), rdr.read_wire::<BlockId, BigEndian>().unwrap());
+/// ```
+#[macro_export]
+macro_rules! wire_newtype {
+    ($($name:ident($ty:ty)),* $(,)?) => {
+        $(
+            impl $crate::wire::WireSize for $name {
+                const WIRE_SIZE: usize =
+                    <$ty as $crate::wire::WireSize>::WIRE_SIZE;
+            }
+
+            impl $crate::wire::FromWire for $name {
+                #[inline]
+                fn from_wire<E: $crate::ByteOrder>(
+                    buf: &[u8],
+                ) -> ::core::result::Result<$name, $crate::Error> {
+                    <$ty as $crate::wire::FromWire>::from_wire::<E>(buf)
+                        .map($name)
+                }
+            }
+
+            impl $crate::wire::ToWire for $name {
+                #[inline]
+                fn to_wire<E: $crate::ByteOrder>(
+                    &self,
+                    buf: &mut [u8],
+                ) -> ::core::result::Result<(), $crate::Error> {
+                    $crate::wire::ToWire::to_wire::<E>(&self.0, buf)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FromWire, ToWire, WireSize};
+    use crate::{fourcc::FourCc, BigEndian, Error, ErrorKind, LittleEndian};
+
+    crate::wire_layout! {
+        #[derive(Clone, Copy, Debug, Default, PartialEq)]
+        struct Inner {
+            a: u8 => OFFSET_OF_A,
+            b: [i16; 3] => OFFSET_OF_B,
+        }
+    }
+
+    crate::wire_layout! {
+        struct Outer {
+            kind: FourCc => OFFSET_OF_KIND,
+            /// A documented field.
+            inner: Inner => OFFSET_OF_INNER,
+            value: f64 => OFFSET_OF_VALUE,
+            wide: u128 => OFFSET_OF_WIDE
+        }
+    }
+
+    crate::wire_layout! {
+        struct Empty {}
+    }
+
+    #[test]
+    fn primitives() {
+        assert_eq!(1, u8::WIRE_SIZE);
+        assert_eq!(2, i16::WIRE_SIZE);
+        assert_eq!(8, f64::WIRE_SIZE);
+        assert_eq!(16, i128::WIRE_SIZE);
+        assert_eq!(0, <[u32; 0]>::WIRE_SIZE);
+        assert_eq!(12, <[[u16; 2]; 3]>::WIRE_SIZE);
+    }
+
+    #[test]
+    fn tuples() {
+        type Header = (FourCc, u16, (i8, [u8; 2]), f32);
+        assert_eq!(13, Header::WIRE_SIZE);
+        assert_eq!(
+            45,
+            <(u8, u16, u32, u64, u8, u16, u32, u64, u8, u16, u32, u64)>::WIRE_SIZE
+        );
+
+        let header: Header = (FourCc::new(*b"fmt "), 3, (-1, [4, 5]), 0.5);
+        let mut buf = [0; 14];
+        header.to_wire::<LittleEndian>(&mut buf).unwrap();
+        assert_eq!(b"fmt \x03\x00\xFF\x04\x05\x00\x00\x00\x3F\x00", &buf);
+        assert_eq!(Ok(header), Header::from_wire::<LittleEndian>(&buf));
+        assert_eq!(
+            Ok((0x666D, 0x7420)),
+            <(u16, u16)>::from_wire::<BigEndian>(&buf)
+        );
+
+        let err = Header::from_wire::<BigEndian>(&buf[..12]).unwrap_err();
+        assert_eq!(
+            &ErrorKind::BufferTooSmall { needed: 13, available: 12 },
+            err.kind()
+        );
+        assert!(header.to_wire::<BigEndian>(&mut buf[..12]).is_err());
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(0, Inner::OFFSET_OF_A);
+        assert_eq!(1, Inner::OFFSET_OF_B);
+        assert_eq!(7, Inner::WIRE_SIZE);
+        assert_eq!(7, <Inner as WireSize>::WIRE_SIZE);
+
+        assert_eq!(0, Outer::OFFSET_OF_KIND);
+        assert_eq!(4, Outer::OFFSET_OF_INNER);
+        assert_eq!(11, Outer::OFFSET_OF_VALUE);
+        assert_eq!(19, Outer::OFFSET_OF_WIDE);
+        assert_eq!(35, Outer::WIRE_SIZE);
+
+        assert_eq!(0, Empty::WIRE_SIZE);
+    }
+
+    #[test]
+    fn usable_in_const_context() {
+        const BUF: [u8; Outer::WIRE_SIZE] = [0; Outer::WIRE_SIZE];
+        assert_eq!(35, BUF.len());
+
+        let inner = Inner { a: 1, b: [2, 3, 4] };
+        assert_eq!(inner, inner.clone());
+        let outer =
+            Outer { kind: FourCc::new(*b"test"), inner, value: 1.0, wide: 0 };
+        assert_eq!(1, outer.inner.a);
+        assert_eq!(*b"test", outer.kind.to_bytes());
+        assert_eq!((1.0, 0), (outer.value, outer.wide));
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Crc(u32);
+
+    #[derive(Debug, PartialEq)]
+    struct Tag(FourCc);
+
+    crate::wire_newtype!(Crc(u32), Tag(FourCc));
+
+    #[test]
+    fn from_and_to_wire() {
+        let mut buf = [0; 16];
+        (-2i8).to_wire::<BigEndian>(&mut buf).unwrap();
+        assert_eq!(Ok(-2), i8::from_wire::<BigEndian>(&buf));
+        1.5f64.to_wire::<LittleEndian>(&mut buf).unwrap();
+        assert_eq!(Ok(1.5), f64::from_wire::<LittleEndian>(&buf));
+        [[1u16, 2], [3, 4]].to_wire::<BigEndian>(&mut buf).unwrap();
+        assert_eq!([0, 1, 0, 2, 0, 3, 0, 4], buf[..8]);
+        assert_eq!(
+            Ok([[1u16, 2], [3, 4]]),
+            <[[u16; 2]; 2]>::from_wire::<BigEndian>(&buf)
+        );
+    }
+
+    #[test]
+    fn short_buffers() {
+        fn too_small(needed: usize, available: usize) -> Error {
+            Error::new(ErrorKind::BufferTooSmall { needed, available })
+        }
+
+        let mut buf = [0; 3];
+        let err = u8::from_wire::<BigEndian>(&[]).unwrap_err();
+        assert_eq!(too_small(1, 0), err);
+        let err = 0u32.to_wire::<BigEndian>(&mut buf).unwrap_err();
+        assert_eq!(too_small(4, 3), err);
+        // An array is checked as a whole, so nothing is written.
+        buf = [9; 3];
+        let err = [1u16, 2].to_wire::<BigEndian>(&mut buf).unwrap_err();
+        assert_eq!(too_small(4, 3), err);
+        assert_eq!([9; 3], buf);
+        let err = <[u8; 4]>::from_wire::<BigEndian>(&buf).unwrap_err();
+        assert_eq!(too_small(4, 3), err);
+    }
+
+    #[test]
+    fn newtypes() {
+        assert_eq!(4, Crc::WIRE_SIZE);
+        let mut buf = [0; 8];
+        Crc(0xDEADBEEF).to_wire::<LittleEndian>(&mut buf).unwrap();
+        Tag(FourCc::new(*b"RIFF"))
+            .to_wire::<LittleEndian>(&mut buf[4..])
+            .unwrap();
+        assert_eq!(*b"\xEF\xBE\xAD\xDERIFF", buf);
+        assert_eq!(Ok(Crc(0xDEADBEEF)), Crc::from_wire::<LittleEndian>(&buf));
+        assert_eq!(
+            Ok(Tag(FourCc::new(*b"RIFF"))),
+            Tag::from_wire::<BigEndian>(&buf[4..])
+        );
+    }
+}